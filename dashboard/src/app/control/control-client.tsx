@@ -171,6 +171,19 @@ import {
   DataTable,
   parseSerializableDataTable,
 } from "@/components/tool-ui/data-table";
+import { Chart, parseSerializableChart } from "@/components/tool-ui/chart";
+import { FileTree, parseSerializableFileTree } from "@/components/tool-ui/file-tree";
+import {
+  DiffViewer,
+  parseSerializableDiffViewer,
+  type DiffViewerDecision,
+} from "@/components/tool-ui/diff-viewer";
+import {
+  Form,
+  FormErrorBoundary,
+  parseSerializableForm,
+  type FormValues,
+} from "@/components/tool-ui/form";
 import { useScrollToBottom } from "@/hooks/use-scroll-to-bottom";
 import { useLocalStorage } from "@/hooks/use-local-storage";
 import { useCopyToClipboard } from "@/hooks/use-copy-to-clipboard";
@@ -457,6 +470,220 @@ function QuestionToolItem({
   );
 }
 
+type AskUserField = {
+  id: string;
+  label: string;
+  type: "text" | "textarea" | "file";
+  placeholder?: string;
+  required: boolean;
+  multiple: boolean;
+};
+
+type AskUserAnswers = Record<string, string | string[]>;
+
+// ask_user is also served by the workspace MCP, so it may arrive qualified
+// as mcp__<server>__ask_user.
+function isAskUserTool(name: string): boolean {
+  return name === "ask_user" || name.endsWith("__ask_user");
+}
+
+function isUiToolName(name: string): boolean {
+  return name.startsWith("ui_") || name === "question" || isAskUserTool(name);
+}
+
+function parseAskUserFields(args: unknown): AskUserField[] {
+  const raw = isRecord(args) ? args["fields"] : undefined;
+  const fields = Array.isArray(raw)
+    ? raw
+        .map((entry) => (isRecord(entry) ? entry : null))
+        .filter((entry): entry is Record<string, unknown> => Boolean(entry))
+        .map((entry) => ({
+          id: String(entry["id"] ?? ""),
+          label: String(entry["label"] ?? entry["id"] ?? ""),
+          type:
+            entry["type"] === "textarea" || entry["type"] === "file"
+              ? (entry["type"] as "textarea" | "file")
+              : ("text" as const),
+          placeholder:
+            typeof entry["placeholder"] === "string" ? entry["placeholder"] : undefined,
+          required: entry["required"] !== false,
+          multiple: Boolean(entry["multiple"]),
+        }))
+        .filter((field) => field.id.length > 0)
+    : [];
+  if (fields.length > 0) return fields;
+  return [
+    { id: "answer", label: "Answer", type: "textarea", required: true, multiple: false },
+  ];
+}
+
+function AskUserToolItem({
+  item,
+  workspaceId,
+  missionId,
+  onSubmit,
+}: {
+  item: ToolItem;
+  workspaceId?: string;
+  missionId?: string;
+  onSubmit: (toolCallId: string, answers: AskUserAnswers) => Promise<void>;
+}) {
+  const args = isRecord(item.args) ? item.args : {};
+  const question = typeof args["question"] === "string" ? args["question"] : "";
+  const context = typeof args["context"] === "string" ? args["context"] : undefined;
+  const fields = useMemo(() => parseAskUserFields(item.args), [item.args]);
+  const [values, setValues] = useState<AskUserAnswers>({});
+  const [uploading, setUploading] = useState<string | null>(null);
+  const [submitting, setSubmitting] = useState(false);
+
+  useEffect(() => {
+    setValues({});
+  }, [item.toolCallId]);
+
+  const hasResult = item.result !== undefined;
+  const disabled = hasResult || submitting || uploading !== null;
+
+  const canSubmit = fields.every((field) => {
+    if (!field.required) return true;
+    const value = values[field.id];
+    return Array.isArray(value) ? value.length > 0 : Boolean(value?.trim());
+  });
+
+  const handleFiles = async (field: AskUserField, files: FileList | null) => {
+    if (!files || files.length === 0) return;
+    setUploading(field.id);
+    try {
+      const paths: string[] = [];
+      for (const file of Array.from(files)) {
+        const result = await uploadFile(file, "./context/", undefined, workspaceId, missionId);
+        paths.push(result.path);
+      }
+      setValues((prev) => {
+        const existing = field.multiple && Array.isArray(prev[field.id]) ? (prev[field.id] as string[]) : [];
+        return { ...prev, [field.id]: [...existing, ...paths] };
+      });
+    } catch (error) {
+      console.error("Upload failed:", error);
+      toast.error("Failed to upload file");
+    } finally {
+      setUploading(null);
+    }
+  };
+
+  const handleSubmit = async () => {
+    if (!canSubmit || disabled) return;
+    setSubmitting(true);
+    try {
+      await onSubmit(item.toolCallId, values);
+    } finally {
+      setSubmitting(false);
+    }
+  };
+
+  const inputClass =
+    "w-full rounded-lg border border-white/10 bg-white/[0.03] px-3 py-2 text-sm text-white/80 focus:border-indigo-500/40 focus:outline-none";
+
+  return (
+    <div className="flex justify-start gap-3">
+      <div className="flex h-8 w-8 shrink-0 items-center justify-center rounded-full bg-indigo-500/20">
+        <Bot className="h-4 w-4 text-indigo-400" />
+      </div>
+      <div className="max-w-[90%] min-w-[320px] rounded-2xl rounded-tl-md bg-white/[0.03] border border-white/[0.06] px-4 py-3">
+        <div className="mb-2 text-xs text-white/40">
+          Tool: <span className="font-mono text-indigo-400">ask_user</span>
+        </div>
+        <div className="space-y-3">
+          <div className="text-sm font-medium text-white/90 whitespace-pre-wrap">{question}</div>
+          {context && <div className="text-xs text-white/50 whitespace-pre-wrap">{context}</div>}
+          {fields.map((field) => {
+            const value = values[field.id];
+            return (
+              <div key={`${item.toolCallId}-${field.id}`} className="space-y-1">
+                {(fields.length > 1 || field.id !== "answer") && (
+                  <label className="text-xs text-white/60">
+                    {field.label}
+                    {!field.required && <span className="text-white/30"> (optional)</span>}
+                  </label>
+                )}
+                {field.type === "file" ? (
+                  <div className="space-y-1">
+                    <label
+                      className={cn(
+                        "inline-flex items-center gap-2 rounded-lg border border-white/10 px-3 py-2 text-sm text-white/70",
+                        disabled ? "opacity-50 cursor-not-allowed" : "cursor-pointer hover:border-white/20"
+                      )}
+                    >
+                      {uploading === field.id ? (
+                        <Loader className="h-4 w-4 animate-spin" />
+                      ) : (
+                        <Paperclip className="h-4 w-4" />
+                      )}
+                      {uploading === field.id ? "Uploading…" : field.placeholder ?? "Choose file"}
+                      <input
+                        type="file"
+                        multiple={field.multiple}
+                        disabled={disabled}
+                        className="hidden"
+                        onChange={(e) => {
+                          void handleFiles(field, e.target.files);
+                          e.target.value = "";
+                        }}
+                      />
+                    </label>
+                    {Array.isArray(value) &&
+                      value.map((path) => (
+                        <div key={path} className="font-mono text-xs text-white/50 truncate">
+                          {path}
+                        </div>
+                      ))}
+                  </div>
+                ) : field.type === "textarea" ? (
+                  <textarea
+                    value={typeof value === "string" ? value : ""}
+                    onChange={(e) => setValues((prev) => ({ ...prev, [field.id]: e.target.value }))}
+                    placeholder={field.placeholder}
+                    disabled={disabled}
+                    rows={3}
+                    className={cn(inputClass, "resize-y")}
+                  />
+                ) : (
+                  <input
+                    type="text"
+                    value={typeof value === "string" ? value : ""}
+                    onChange={(e) => setValues((prev) => ({ ...prev, [field.id]: e.target.value }))}
+                    onKeyDown={(e) => {
+                      if (e.key === "Enter") void handleSubmit();
+                    }}
+                    placeholder={field.placeholder}
+                    disabled={disabled}
+                    className={inputClass}
+                  />
+                )}
+              </div>
+            );
+          })}
+          {hasResult ? (
+            <div className="text-xs text-green-400">Answer sent.</div>
+          ) : (
+            <button
+              onClick={handleSubmit}
+              disabled={!canSubmit || disabled}
+              className={cn(
+                "inline-flex items-center gap-2 rounded-lg px-4 py-2 text-sm font-medium transition-colors",
+                !canSubmit || disabled
+                  ? "bg-white/5 text-white/30 cursor-not-allowed"
+                  : "bg-indigo-500/20 text-indigo-200 hover:bg-indigo-500/30"
+              )}
+            >
+              {submitting ? "Sending…" : "Submit Answer"}
+            </button>
+          )}
+        </div>
+      </div>
+    </div>
+  );
+}
+
 function isRecord(value: unknown): value is Record<string, unknown> {
   return typeof value === "object" && value !== null;
 }
@@ -2023,6 +2250,11 @@ export default function ControlClient() {
     >
   >({});
 
+  // Agent-reported batch progress (ui_progress tool), tracked per mission
+  const [missionProgressByMission, setMissionProgressByMission] = useState<
+    Record<string, { label: string; current: number; total: number }>
+  >({});
+
   // Mission state
   const [currentMission, setCurrentMission] = useState<Mission | null>(null);
   const [viewingMission, setViewingMission] = useState<Mission | null>(null);
@@ -2390,6 +2622,11 @@ export default function ControlClient() {
     return progressByMission[viewingMissionId] ?? null;
   }, [progressByMission, viewingMissionId]);
 
+  const viewingMissionProgress = useMemo(() => {
+    if (!viewingMissionId) return null;
+    return missionProgressByMission[viewingMissionId] ?? null;
+  }, [missionProgressByMission, viewingMissionId]);
+
   useEffect(() => {
     if (items.length === 0) return;
     let lastAssistantIdx = -1;
@@ -2418,7 +2655,7 @@ export default function ControlClient() {
       items.some(
         (item) =>
           item.kind === "tool" &&
-          item.name === "question" &&
+          (item.name === "question" || isAskUserTool(item.name)) &&
           item.result === undefined
       ),
     [items]
@@ -3032,7 +3269,7 @@ export default function ControlClient() {
           finalizePendingThinking(timestamp);
           const toolCallId = event.tool_call_id || `unknown-${event.id}`;
           const name = event.tool_name || "unknown";
-          const isUiTool = name.startsWith("ui_") || name === "question";
+          const isUiTool = isUiToolName(name);
           // Parse args from content (stored as JSON string)
           let args: unknown = undefined;
           try {
@@ -4452,7 +4689,7 @@ export default function ControlClient() {
 
       if (event.type === "tool_call" && isRecord(data)) {
         const name = String(data["name"] ?? "");
-        const isUiTool = name.startsWith("ui_") || name === "question";
+        const isUiTool = isUiToolName(name);
         const toolCallId = String(data["tool_call_id"] ?? "");
 
         setItems((prev) => {
@@ -4633,6 +4870,28 @@ export default function ControlClient() {
         }
       }
 
+      // Stall recovery actions taken by the server
+      if (event.type === "stall_recovery" && isRecord(data)) {
+        const msg = String(data["message"] ?? "Stalled mission recovered");
+        const missionId =
+          typeof data["mission_id"] === "string" ? data["mission_id"] : undefined;
+        setItems((prev) => [
+          ...prev,
+          { kind: "system", id: `stall-${Date.now()}`, content: msg, timestamp: Date.now(), missionId },
+        ]);
+        toast.info(msg);
+      }
+
+      if (event.type === "mission_paused" && isRecord(data)) {
+        const msg = data["paused"] ? "Mission paused" : "Mission resumed";
+        const missionId =
+          typeof data["mission_id"] === "string" ? data["mission_id"] : undefined;
+        setItems((prev) => [
+          ...prev,
+          { kind: "system", id: `pause-${Date.now()}`, content: msg, timestamp: Date.now(), missionId },
+        ]);
+      }
+
       // Handle mission status changes
       if (event.type === "mission_status_changed" && isRecord(data)) {
         const newStatus = String(data["status"] ?? "");
@@ -4708,6 +4967,25 @@ export default function ControlClient() {
           }));
         }
       }
+
+      // Handle agent-reported batch progress (ui_progress)
+      if (event.type === "mission_progress" && isRecord(data)) {
+        const progressMissionId =
+          typeof data["mission_id"] === "string"
+            ? data["mission_id"]
+            : currentMissionRef.current?.id ?? null;
+        const total = Number(data["total"] ?? 0);
+        if (progressMissionId && total > 0) {
+          setMissionProgressByMission((prev) => ({
+            ...prev,
+            [progressMissionId]: {
+              label: String(data["label"] ?? "Progress"),
+              current: Math.min(Number(data["current"] ?? 0), total),
+              total,
+            },
+          }));
+        }
+      }
     };
 
     const scheduleReconnect = () => {
@@ -5583,6 +5861,34 @@ export default function ControlClient() {
                 </div>
               </>
             )}
+
+            {/* Agent-reported batch progress */}
+            {viewingMissionProgress && (
+              <>
+                <div className="h-4 w-px bg-white/[0.08]" />
+                <div
+                  className="flex items-center gap-2"
+                  title={`${viewingMissionProgress.label}: ${viewingMissionProgress.current}/${viewingMissionProgress.total}`}
+                >
+                  <span className="max-w-[10rem] truncate text-[10px] uppercase tracking-wider text-white/40">
+                    {viewingMissionProgress.label}
+                  </span>
+                  <div className="h-1.5 w-24 overflow-hidden rounded-full bg-white/[0.08]">
+                    <div
+                      className="h-full rounded-full bg-emerald-400 transition-all duration-300"
+                      style={{
+                        width: `${Math.round(
+                          (viewingMissionProgress.current / viewingMissionProgress.total) * 100
+                        )}%`,
+                      }}
+                    />
+                  </div>
+                  <span className="text-sm font-medium text-emerald-400 tabular-nums">
+                    {viewingMissionProgress.current}/{viewingMissionProgress.total}
+                  </span>
+                </div>
+              </>
+            )}
           </div>
         </div>
       </div>
@@ -5906,6 +6212,30 @@ export default function ControlClient() {
                         />
                       );
                     }
+                    if (isAskUserTool(item.name)) {
+                      return (
+                        <AskUserToolItem
+                          key={item.id}
+                          item={item}
+                          workspaceId={missionForDownloads?.workspace_id}
+                          missionId={missionForDownloads?.id}
+                          onSubmit={async (toolCallId, answers) => {
+                            setItems((prev) =>
+                              prev.map((it) =>
+                                it.kind === "tool" && it.toolCallId === toolCallId
+                                  ? { ...it, result: { answers } }
+                                  : it
+                              )
+                            );
+                            await postControlToolResult({
+                              tool_call_id: toolCallId,
+                              name: item.name,
+                              result: { answers },
+                            });
+                          }}
+                        />
+                      );
+                    }
                     if (item.name === "ui_optionList") {
                       const toolCallId = item.toolCallId;
                       const rawArgs: Record<string, unknown> = isRecord(item.args)
@@ -5996,6 +6326,146 @@ export default function ControlClient() {
                       );
                     }
 
+                    if (item.name === "ui_form") {
+                      const toolCallId = item.toolCallId;
+                      const rawArgs: Record<string, unknown> = isRecord(item.args)
+                        ? item.args
+                        : {};
+
+                      let form: ReturnType<typeof parseSerializableForm> | null = null;
+                      let parseErr: string | null = null;
+                      try {
+                        form = parseSerializableForm({
+                          ...rawArgs,
+                          id:
+                            typeof rawArgs["id"] === "string" && rawArgs["id"]
+                              ? (rawArgs["id"] as string)
+                              : `form-${toolCallId}`,
+                        });
+                      } catch (e) {
+                        parseErr =
+                          e instanceof Error ? e.message : "Invalid form payload";
+                      }
+
+                      const confirmed =
+                        item.result === undefined
+                          ? undefined
+                          : isRecord(item.result) && isRecord(item.result["values"])
+                            ? (item.result["values"] as FormValues)
+                            : null;
+
+                      const submitResult = async (result: { values: FormValues } | null) => {
+                        setItems((prev) =>
+                          prev.map((it) =>
+                            it.kind === "tool" && it.toolCallId === toolCallId
+                              ? { ...it, result }
+                              : it
+                          )
+                        );
+                        await postControlToolResult({
+                          tool_call_id: toolCallId,
+                          name: item.name,
+                          result,
+                        });
+                      };
+
+                      return (
+                        <div key={item.id} className="flex justify-start gap-3">
+                          <div className="flex h-8 w-8 shrink-0 items-center justify-center rounded-full bg-indigo-500/20">
+                            <Bot className="h-4 w-4 text-indigo-400" />
+                          </div>
+                          <div className="max-w-[80%] rounded-2xl rounded-tl-md bg-white/[0.03] border border-white/[0.06] px-4 py-3">
+                            <div className="mb-2 text-xs text-white/40">
+                              Tool:{" "}
+                              <span className="font-mono text-indigo-400">
+                                {item.name}
+                              </span>
+                            </div>
+
+                            {parseErr || !form ? (
+                              <div className="rounded-lg bg-red-500/10 border border-red-500/20 p-3 text-sm text-red-400">
+                                {parseErr ?? "Failed to render Form"}
+                              </div>
+                            ) : (
+                              <FormErrorBoundary>
+                                <Form
+                                  {...form}
+                                  confirmed={confirmed}
+                                  onSubmit={(values) => submitResult({ values })}
+                                  onCancel={() => submitResult(null)}
+                                />
+                              </FormErrorBoundary>
+                            )}
+                          </div>
+                        </div>
+                      );
+                    }
+
+                    if (item.name === "ui_diffViewer") {
+                      const toolCallId = item.toolCallId;
+                      const rawArgs: Record<string, unknown> = isRecord(item.args)
+                        ? item.args
+                        : {};
+                      const diffViewer = parseSerializableDiffViewer({
+                        ...rawArgs,
+                        id:
+                          typeof rawArgs["id"] === "string" && rawArgs["id"]
+                            ? (rawArgs["id"] as string)
+                            : `diff-viewer-${toolCallId}`,
+                      });
+
+                      const confirmed =
+                        item.result === undefined
+                          ? undefined
+                          : isRecord(item.result) &&
+                              typeof item.result["approved"] === "boolean"
+                            ? (item.result as unknown as DiffViewerDecision)
+                            : null;
+
+                      const submitResult = async (result: DiffViewerDecision | null) => {
+                        setItems((prev) =>
+                          prev.map((it) =>
+                            it.kind === "tool" && it.toolCallId === toolCallId
+                              ? { ...it, result }
+                              : it
+                          )
+                        );
+                        await postControlToolResult({
+                          tool_call_id: toolCallId,
+                          name: item.name,
+                          result,
+                        });
+                      };
+
+                      return (
+                        <div key={item.id} className="flex justify-start gap-3">
+                          <div className="flex h-8 w-8 shrink-0 items-center justify-center rounded-full bg-indigo-500/20">
+                            <Bot className="h-4 w-4 text-indigo-400" />
+                          </div>
+                          <div className="max-w-[90%] rounded-2xl rounded-tl-md bg-white/[0.03] border border-white/[0.06] px-4 py-3">
+                            <div className="mb-2 text-xs text-white/40">
+                              Tool:{" "}
+                              <span className="font-mono text-indigo-400">
+                                {item.name}
+                              </span>
+                            </div>
+                            {diffViewer ? (
+                              <DiffViewer
+                                {...diffViewer}
+                                confirmed={confirmed}
+                                onDecide={(decision) => submitResult(decision)}
+                                onDismiss={() => submitResult(null)}
+                              />
+                            ) : (
+                              <div className="rounded-lg bg-red-500/10 border border-red-500/20 p-3 text-sm text-red-400">
+                                Failed to render DiffViewer
+                              </div>
+                            )}
+                          </div>
+                        </div>
+                      );
+                    }
+
                     if (item.name === "ui_dataTable") {
                       const rawArgs: Record<string, unknown> = isRecord(item.args)
                         ? item.args
@@ -6031,6 +6501,71 @@ export default function ControlClient() {
                       );
                     }
 
+                    if (item.name === "ui_chart") {
+                      const rawArgs: Record<string, unknown> = isRecord(item.args)
+                        ? item.args
+                        : {};
+                      const chart = parseSerializableChart(rawArgs);
+
+                      return (
+                        <div key={item.id} className="flex justify-start gap-3">
+                          <div className="flex h-8 w-8 shrink-0 items-center justify-center rounded-full bg-indigo-500/20">
+                            <Bot className="h-4 w-4 text-indigo-400" />
+                          </div>
+                          <div className="max-w-[90%] rounded-2xl rounded-tl-md bg-white/[0.03] border border-white/[0.06] px-4 py-3">
+                            <div className="mb-2 text-xs text-white/40">
+                              Tool:{" "}
+                              <span className="font-mono text-indigo-400">
+                                {item.name}
+                              </span>
+                            </div>
+                            {chart ? (
+                              <Chart {...chart} />
+                            ) : (
+                              <div className="rounded-lg bg-red-500/10 border border-red-500/20 p-3 text-sm text-red-400">
+                                Failed to render Chart
+                              </div>
+                            )}
+                          </div>
+                        </div>
+                      );
+                    }
+
+                    if (item.name === "ui_fileTree") {
+                      const rawArgs: Record<string, unknown> = isRecord(item.args)
+                        ? item.args
+                        : {};
+                      const fileTree = parseSerializableFileTree(rawArgs);
+
+                      return (
+                        <div key={item.id} className="flex justify-start gap-3">
+                          <div className="flex h-8 w-8 shrink-0 items-center justify-center rounded-full bg-indigo-500/20">
+                            <Bot className="h-4 w-4 text-indigo-400" />
+                          </div>
+                          <div className="max-w-[90%] rounded-2xl rounded-tl-md bg-white/[0.03] border border-white/[0.06] px-4 py-3">
+                            <div className="mb-2 text-xs text-white/40">
+                              Tool:{" "}
+                              <span className="font-mono text-indigo-400">
+                                {item.name}
+                              </span>
+                            </div>
+                            {fileTree ? (
+                              <FileTree
+                                {...fileTree}
+                                basePath={missionWorkingDirectory}
+                                workspaceId={missionForDownloads?.workspace_id}
+                                missionId={missionForDownloads?.id}
+                              />
+                            ) : (
+                              <div className="rounded-lg bg-red-500/10 border border-red-500/20 p-3 text-sm text-red-400">
+                                Failed to render FileTree
+                              </div>
+                            )}
+                          </div>
+                        </div>
+                      );
+                    }
+
                     // Unknown UI tool - still show with ToolCallItem
                     return (
                       <ToolCallItem
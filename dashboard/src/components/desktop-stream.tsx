@@ -1,7 +1,7 @@
 "use client";
 
 import { useState, useEffect, useRef, useCallback } from "react";
-import type { MouseEvent, WheelEvent, KeyboardEvent } from "react";
+import type { MouseEvent, WheelEvent, KeyboardEvent, ClipboardEvent } from "react";
 import { cn } from "@/lib/utils";
 import { getValidJwt } from "@/lib/auth";
 import { getRuntimeApiBase } from "@/lib/settings";
@@ -510,6 +510,10 @@ export function DesktopStream({
       if (event.key === "Shift" || event.key === "Control" || event.key === "Alt" || event.key === "Meta") {
         return;
       }
+      // Let the browser fire a paste event; handlePaste forwards the text.
+      if ((event.ctrlKey || event.metaKey) && !event.altKey && event.key.toLowerCase() === "v") {
+        return;
+      }
       const isPrintable = event.key.length === 1 && !event.ctrlKey && !event.metaKey && !event.altKey;
       if (isPrintable) {
         sendCommand({ t: "type", text: event.key });
@@ -523,6 +527,20 @@ export function DesktopStream({
     [connectionState, formatKeyForXdotool, sendCommand]
   );
 
+  const handlePaste = useCallback(
+    (event: ClipboardEvent<HTMLDivElement>) => {
+      if (connectionState !== "connected") return;
+      const text = event.clipboardData.getData("text/plain");
+      if (text) {
+        // Put the text on the remote clipboard, then paste it there.
+        sendCommand({ t: "clipboard_set", text });
+        sendCommand({ t: "key", key: "ctrl+v" });
+      }
+      event.preventDefault();
+    },
+    [connectionState, sendCommand]
+  );
+
   // Control handlers
   const handlePause = useCallback(() => {
     setIsPaused(true);
@@ -661,6 +679,7 @@ export function DesktopStream({
       ref={containerRef}
       tabIndex={0}
       onKeyDown={handleKeyDown}
+      onPaste={handlePaste}
       className={cn(
         "relative flex flex-col bg-[#0a0a0a] rounded-xl overflow-hidden border border-white/[0.06]",
         className
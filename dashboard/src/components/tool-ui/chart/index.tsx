@@ -0,0 +1,328 @@
+"use client";
+
+import {
+  Area,
+  AreaChart,
+  Bar,
+  BarChart,
+  CartesianGrid,
+  Cell,
+  Legend,
+  Line,
+  LineChart,
+  Pie,
+  PieChart,
+  ResponsiveContainer,
+  Scatter,
+  ScatterChart,
+  Tooltip,
+  XAxis,
+  YAxis,
+} from "recharts";
+import { cn } from "@/lib/utils";
+
+export type ChartType = "line" | "bar" | "area" | "pie" | "scatter";
+
+export interface ChartSeries {
+  key: string;
+  label: string;
+  color: string;
+}
+
+export interface ChartProps {
+  id: string;
+  title?: string;
+  description?: string;
+  type: ChartType;
+  xKey: string;
+  xLabel?: string;
+  yLabel?: string;
+  series: ChartSeries[];
+  data: Array<Record<string, unknown>>;
+  stacked?: boolean;
+  className?: string;
+}
+
+const PALETTE = [
+  "#818cf8",
+  "#34d399",
+  "#fbbf24",
+  "#f87171",
+  "#60a5fa",
+  "#c084fc",
+  "#2dd4bf",
+  "#fb923c",
+];
+
+const AXIS_STYLE = { fontSize: 11, fill: "rgba(255,255,255,0.4)" };
+const TOOLTIP_STYLE = {
+  backgroundColor: "#111",
+  border: "1px solid rgba(255,255,255,0.1)",
+  borderRadius: 8,
+  fontSize: 12,
+};
+
+export function Chart({
+  id,
+  title,
+  description,
+  type,
+  xKey,
+  xLabel,
+  yLabel,
+  series,
+  data,
+  stacked,
+  className,
+}: ChartProps) {
+  const axes = (
+    <>
+      <CartesianGrid strokeDasharray="3 3" stroke="rgba(255,255,255,0.06)" />
+      <XAxis
+        dataKey={xKey}
+        tick={AXIS_STYLE}
+        stroke="rgba(255,255,255,0.1)"
+        label={xLabel ? { value: xLabel, position: "insideBottom", offset: -4, ...AXIS_STYLE } : undefined}
+      />
+      <YAxis
+        tick={AXIS_STYLE}
+        stroke="rgba(255,255,255,0.1)"
+        label={yLabel ? { value: yLabel, angle: -90, position: "insideLeft", ...AXIS_STYLE } : undefined}
+      />
+      <Tooltip contentStyle={TOOLTIP_STYLE} />
+      {series.length > 1 && <Legend wrapperStyle={{ fontSize: 12 }} />}
+    </>
+  );
+  const stackId = stacked ? "stack" : undefined;
+
+  let chart;
+  switch (type) {
+    case "bar":
+      chart = (
+        <BarChart data={data}>
+          {axes}
+          {series.map((s) => (
+            <Bar key={s.key} dataKey={s.key} name={s.label} fill={s.color} stackId={stackId} />
+          ))}
+        </BarChart>
+      );
+      break;
+    case "area":
+      chart = (
+        <AreaChart data={data}>
+          {axes}
+          {series.map((s) => (
+            <Area
+              key={s.key}
+              dataKey={s.key}
+              name={s.label}
+              stroke={s.color}
+              fill={s.color}
+              fillOpacity={0.2}
+              stackId={stackId}
+            />
+          ))}
+        </AreaChart>
+      );
+      break;
+    case "scatter":
+      chart = (
+        <ScatterChart>
+          {axes}
+          {series.map((s) => (
+            <Scatter
+              key={s.key}
+              name={s.label}
+              data={data.filter((row) => row[s.key] !== undefined && row[s.key] !== null)}
+              dataKey={s.key}
+              fill={s.color}
+            />
+          ))}
+        </ScatterChart>
+      );
+      break;
+    case "pie": {
+      const valueKey = series[0]?.key ?? "value";
+      chart = (
+        <PieChart>
+          <Tooltip contentStyle={TOOLTIP_STYLE} />
+          <Legend wrapperStyle={{ fontSize: 12 }} />
+          <Pie data={data} dataKey={valueKey} nameKey={xKey} outerRadius="80%">
+            {data.map((_, index) => (
+              <Cell key={index} fill={PALETTE[index % PALETTE.length]} />
+            ))}
+          </Pie>
+        </PieChart>
+      );
+      break;
+    }
+    default:
+      chart = (
+        <LineChart data={data}>
+          {axes}
+          {series.map((s) => (
+            <Line
+              key={s.key}
+              dataKey={s.key}
+              name={s.label}
+              stroke={s.color}
+              strokeWidth={2}
+              dot={data.length <= 40}
+            />
+          ))}
+        </LineChart>
+      );
+  }
+
+  return (
+    <div
+      className={cn(
+        "w-full max-w-2xl min-w-80 overflow-hidden rounded-xl border border-white/[0.06] bg-white/[0.02]",
+        className
+      )}
+      data-slot="chart"
+      data-tool-ui-id={id}
+    >
+      {(title || description) && (
+        <div className="border-b border-white/[0.06] px-4 py-3">
+          {title && <h3 className="text-sm font-medium text-white">{title}</h3>}
+          {description && <p className="mt-0.5 text-xs text-white/50">{description}</p>}
+        </div>
+      )}
+      <div className="h-72 px-2 py-3">
+        {data.length === 0 ? (
+          <div className="flex h-full items-center justify-center text-sm text-white/40">
+            No data
+          </div>
+        ) : (
+          <ResponsiveContainer width="100%" height="100%">
+            {chart}
+          </ResponsiveContainer>
+        )}
+      </div>
+    </div>
+  );
+}
+
+export type SerializableChart = Omit<ChartProps, "className">;
+
+function toNumber(value: unknown): number | null {
+  if (typeof value === "number" && Number.isFinite(value)) return value;
+  if (typeof value === "string" && value.trim() !== "") {
+    const n = Number(value);
+    return Number.isFinite(n) ? n : null;
+  }
+  return null;
+}
+
+/**
+ * Parse ui_chart args. Accepts either row data (`data` + `series` keys) or
+ * per-series arrays (`labels` + `series: [{ name, data: number[] }]`).
+ */
+export function parseSerializableChart(input: unknown): SerializableChart | null {
+  if (!input || typeof input !== "object") return null;
+  const obj = input as Record<string, unknown>;
+
+  const rawType = typeof obj.type === "string" ? obj.type : "line";
+  const type: ChartType = ["line", "bar", "area", "pie", "scatter"].includes(rawType)
+    ? (rawType as ChartType)
+    : "line";
+  const xKey = typeof obj.xKey === "string" && obj.xKey ? obj.xKey : "x";
+  const rawSeries = Array.isArray(obj.series) ? obj.series : [];
+
+  let data: Array<Record<string, unknown>> = [];
+  let series: ChartSeries[] = [];
+
+  const perSeriesArrays = rawSeries.some(
+    (s) => typeof s === "object" && s !== null && Array.isArray((s as Record<string, unknown>).data)
+  );
+
+  if (perSeriesArrays) {
+    const labels = Array.isArray(obj.labels) ? obj.labels : [];
+    const rows = new Map<string, Record<string, unknown>>();
+    const rowFor = (x: unknown) => {
+      const key = String(x);
+      let row = rows.get(key);
+      if (!row) {
+        row = { [xKey]: x };
+        rows.set(key, row);
+      }
+      return row;
+    };
+    rawSeries.forEach((s, index) => {
+      if (typeof s !== "object" || s === null) return;
+      const sObj = s as Record<string, unknown>;
+      const label = String(sObj.name ?? sObj.label ?? `Series ${index + 1}`);
+      const key = `s${index}`;
+      series.push({
+        key,
+        label,
+        color: typeof sObj.color === "string" ? sObj.color : PALETTE[index % PALETTE.length],
+      });
+      (sObj.data as unknown[]).forEach((point, i) => {
+        // Points are numbers (paired with labels), [x, y] pairs or {x, y}.
+        if (Array.isArray(point) && point.length >= 2) {
+          rowFor(point[0])[key] = toNumber(point[1]);
+        } else if (typeof point === "object" && point !== null) {
+          const p = point as Record<string, unknown>;
+          rowFor(p.x ?? p.label ?? i)[key] = toNumber(p.y ?? p.value);
+        } else {
+          rowFor(labels[i] ?? i)[key] = toNumber(point);
+        }
+      });
+    });
+    data = Array.from(rows.values());
+  } else if (Array.isArray(obj.data)) {
+    data = obj.data
+      .filter((row): row is Record<string, unknown> => typeof row === "object" && row !== null)
+      .map((row) => {
+        const converted: Record<string, unknown> = { ...row };
+        for (const [k, v] of Object.entries(row)) {
+          if (k !== xKey) {
+            const n = toNumber(v);
+            if (n !== null) converted[k] = n;
+          }
+        }
+        return converted;
+      });
+    const keys: Array<{ key: string; label?: string; color?: string }> = rawSeries.length
+      ? rawSeries.map((s) =>
+          typeof s === "string"
+            ? { key: s }
+            : {
+                key: String((s as Record<string, unknown>).key ?? ""),
+                label: (s as Record<string, unknown>).label as string | undefined,
+                color: (s as Record<string, unknown>).color as string | undefined,
+              }
+        )
+      : Array.from(
+          new Set(
+            data.flatMap((row) =>
+              Object.keys(row).filter((k) => k !== xKey && typeof row[k] === "number")
+            )
+          )
+        ).map((key) => ({ key }));
+    series = keys
+      .filter((s) => s.key)
+      .map((s, index) => ({
+        key: s.key,
+        label: typeof s.label === "string" ? s.label : s.key,
+        color: typeof s.color === "string" ? s.color : PALETTE[index % PALETTE.length],
+      }));
+  }
+
+  if (series.length === 0) return null;
+
+  return {
+    id: typeof obj.id === "string" ? obj.id : `chart-${Date.now()}`,
+    title: typeof obj.title === "string" ? obj.title : undefined,
+    description: typeof obj.description === "string" ? obj.description : undefined,
+    type,
+    xKey,
+    xLabel: typeof obj.xLabel === "string" ? obj.xLabel : undefined,
+    yLabel: typeof obj.yLabel === "string" ? obj.yLabel : undefined,
+    series,
+    data,
+    stacked: obj.stacked === true,
+  };
+}
@@ -0,0 +1,415 @@
+"use client";
+
+import { useMemo, useState } from "react";
+import { Check, X } from "lucide-react";
+import { cn } from "@/lib/utils";
+
+export type DiffRowKind = "context" | "added" | "removed" | "changed" | "hunk";
+
+export interface DiffRow {
+  kind: DiffRowKind;
+  oldLine?: number;
+  newLine?: number;
+  oldText?: string;
+  newText?: string;
+}
+
+export interface DiffViewerDecision {
+  approved: boolean;
+  comment?: string;
+}
+
+export interface SerializableDiffViewer {
+  id: string;
+  title?: string;
+  description?: string;
+  path?: string;
+  approveLabel?: string;
+  rejectLabel?: string;
+  rows: DiffRow[];
+}
+
+export interface DiffViewerProps extends SerializableDiffViewer {
+  /** User decision; renders the read-only receipt when set (null = dismissed). */
+  confirmed?: DiffViewerDecision | null;
+  onDecide?: (decision: DiffViewerDecision) => void | Promise<void>;
+  onDismiss?: () => void | Promise<void>;
+  className?: string;
+}
+
+interface DiffOp {
+  kind: "context" | "added" | "removed";
+  text: string;
+  a?: number;
+  b?: number;
+}
+
+// LCS on lines is quadratic; above this we fall back to a plain replace block.
+const MAX_LCS_CELLS = 4_000_000;
+// Unchanged runs longer than this are collapsed to their edges.
+const CONTEXT_LINES = 3;
+
+function splitLines(text: string): string[] {
+  if (text === "") return [];
+  const lines = text.replace(/\r\n/g, "\n").split("\n");
+  if (lines[lines.length - 1] === "") lines.pop();
+  return lines;
+}
+
+/** Line diff of two texts as side-by-side rows. */
+export function diffTexts(oldText: string, newText: string): DiffRow[] {
+  const a = splitLines(oldText);
+  const b = splitLines(newText);
+
+  const ops: DiffOp[] = [];
+
+  if (a.length * b.length > MAX_LCS_CELLS) {
+    a.forEach((text, i) => ops.push({ kind: "removed", text, a: i + 1 }));
+    b.forEach((text, j) => ops.push({ kind: "added", text, b: j + 1 }));
+  } else {
+    const n = a.length;
+    const m = b.length;
+    const lcs = Array.from({ length: n + 1 }, () => new Uint32Array(m + 1));
+    for (let i = n - 1; i >= 0; i--) {
+      for (let j = m - 1; j >= 0; j--) {
+        lcs[i][j] =
+          a[i] === b[j] ? lcs[i + 1][j + 1] + 1 : Math.max(lcs[i + 1][j], lcs[i][j + 1]);
+      }
+    }
+    let i = 0;
+    let j = 0;
+    while (i < n || j < m) {
+      if (i < n && j < m && a[i] === b[j]) {
+        ops.push({ kind: "context", text: a[i], a: i + 1, b: j + 1 });
+        i++;
+        j++;
+      } else if (j < m && (i >= n || lcs[i][j + 1] >= lcs[i + 1][j])) {
+        ops.push({ kind: "added", text: b[j], b: j + 1 });
+        j++;
+      } else {
+        ops.push({ kind: "removed", text: a[i], a: i + 1 });
+        i++;
+      }
+    }
+  }
+
+  return collapseContext(pairOps(ops));
+}
+
+/** Pair adjacent removed/added runs so replacements sit on the same row. */
+function pairOps(ops: DiffOp[]): DiffRow[] {
+  const rows: DiffRow[] = [];
+  let removed: DiffOp[] = [];
+  let added: DiffOp[] = [];
+  const flush = () => {
+    const len = Math.max(removed.length, added.length);
+    for (let k = 0; k < len; k++) {
+      const r = removed[k];
+      const ad = added[k];
+      rows.push({
+        kind: r && ad ? "changed" : r ? "removed" : "added",
+        oldLine: r?.a,
+        oldText: r?.text,
+        newLine: ad?.b,
+        newText: ad?.text,
+      });
+    }
+    removed = [];
+    added = [];
+  };
+  for (const op of ops) {
+    if (op.kind === "removed") removed.push(op);
+    else if (op.kind === "added") added.push(op);
+    else {
+      flush();
+      rows.push({
+        kind: "context",
+        oldLine: op.a,
+        newLine: op.b,
+        oldText: op.text,
+        newText: op.text,
+      });
+    }
+  }
+  flush();
+  return rows;
+}
+
+function collapseContext(rows: DiffRow[]): DiffRow[] {
+  const out: DiffRow[] = [];
+  let i = 0;
+  while (i < rows.length) {
+    if (rows[i].kind !== "context") {
+      out.push(rows[i++]);
+      continue;
+    }
+    let end = i;
+    while (end < rows.length && rows[end].kind === "context") end++;
+    const keepHead = i === 0 ? 0 : CONTEXT_LINES;
+    const keepTail = end === rows.length ? 0 : CONTEXT_LINES;
+    if (end - i > keepHead + keepTail + 1) {
+      out.push(...rows.slice(i, i + keepHead));
+      out.push({ kind: "hunk", oldText: `${end - i - keepHead - keepTail} unchanged lines` });
+      out.push(...rows.slice(end - keepTail, end));
+    } else {
+      out.push(...rows.slice(i, end));
+    }
+    i = end;
+  }
+  return out;
+}
+
+/** Parse a unified diff (single file) into side-by-side rows. */
+export function parseUnifiedDiff(diff: string): DiffRow[] {
+  const rows: DiffRow[] = [];
+  let ops: DiffOp[] = [];
+  let oldLine = 0;
+  let newLine = 0;
+  let inHunk = false;
+
+  for (const line of splitLines(diff)) {
+    const header = /^@@ -(\d+)(?:,\d+)? \+(\d+)(?:,\d+)? @@(.*)$/.exec(line);
+    if (header) {
+      rows.push(...pairOps(ops));
+      ops = [];
+      oldLine = Number(header[1]);
+      newLine = Number(header[2]);
+      inHunk = true;
+      rows.push({ kind: "hunk", oldText: line });
+      continue;
+    }
+    if (!inHunk || line.startsWith("\\")) continue;
+    if (line.startsWith("+")) {
+      ops.push({ kind: "added", text: line.slice(1), b: newLine++ });
+    } else if (line.startsWith("-")) {
+      ops.push({ kind: "removed", text: line.slice(1), a: oldLine++ });
+    } else if (line.startsWith(" ") || line === "") {
+      ops.push({ kind: "context", text: line.slice(1), a: oldLine++, b: newLine++ });
+    }
+  }
+  rows.push(...pairOps(ops));
+  return rows;
+}
+
+/**
+ * Parse ui_diffViewer args. Accepts either `oldContent` + `newContent` or a
+ * unified `diff`; the former wins when both are present.
+ */
+export function parseSerializableDiffViewer(input: unknown): SerializableDiffViewer | null {
+  if (!input || typeof input !== "object") return null;
+  const obj = input as Record<string, unknown>;
+  const str = (key: string) => (typeof obj[key] === "string" ? (obj[key] as string) : undefined);
+
+  const oldContent = str("oldContent");
+  const newContent = str("newContent");
+  const diff = str("diff");
+
+  let rows: DiffRow[];
+  if (oldContent !== undefined || newContent !== undefined) {
+    rows = diffTexts(oldContent ?? "", newContent ?? "");
+  } else if (diff !== undefined) {
+    rows = parseUnifiedDiff(diff);
+  } else {
+    return null;
+  }
+
+  return {
+    id: str("id") || `diff-viewer-${Date.now()}`,
+    title: str("title"),
+    description: str("description"),
+    path: str("path"),
+    approveLabel: str("approveLabel"),
+    rejectLabel: str("rejectLabel"),
+    rows,
+  };
+}
+
+const ROW_STYLES: Record<"old" | "new", Partial<Record<DiffRowKind, string>>> = {
+  old: { removed: "bg-red-500/10 text-red-200", changed: "bg-red-500/10 text-red-200" },
+  new: { added: "bg-emerald-500/10 text-emerald-200", changed: "bg-emerald-500/10 text-emerald-200" },
+};
+
+function DiffCell({
+  side,
+  row,
+}: {
+  side: "old" | "new";
+  row: DiffRow;
+}) {
+  const line = side === "old" ? row.oldLine : row.newLine;
+  const text = side === "old" ? row.oldText : row.newText;
+  const empty = line === undefined;
+  return (
+    <>
+      <td className="w-10 select-none border-r border-white/[0.04] px-2 text-right text-white/25">
+        {line ?? ""}
+      </td>
+      <td
+        className={cn(
+          "whitespace-pre-wrap break-all px-2",
+          empty ? "bg-white/[0.015]" : ROW_STYLES[side][row.kind] ?? "text-white/60",
+          side === "old" && "border-r border-white/[0.06]"
+        )}
+      >
+        {empty ? "" : text || " "}
+      </td>
+    </>
+  );
+}
+
+export function DiffViewer({
+  id,
+  title,
+  description,
+  path,
+  approveLabel,
+  rejectLabel,
+  rows,
+  confirmed,
+  onDecide,
+  onDismiss,
+  className,
+}: DiffViewerProps) {
+  const [comment, setComment] = useState("");
+  const [submitting, setSubmitting] = useState(false);
+
+  const stats = useMemo(() => {
+    let added = 0;
+    let removed = 0;
+    for (const row of rows) {
+      if (row.kind === "hunk") continue;
+      if (row.newLine !== undefined && row.kind !== "context") added++;
+      if (row.oldLine !== undefined && row.kind !== "context") removed++;
+    }
+    return { added, removed };
+  }, [rows]);
+
+  const decide = async (approved: boolean) => {
+    if (submitting || !onDecide) return;
+    setSubmitting(true);
+    try {
+      const trimmed = comment.trim();
+      await onDecide(trimmed ? { approved, comment: trimmed } : { approved });
+    } finally {
+      setSubmitting(false);
+    }
+  };
+
+  return (
+    <div
+      className={cn(
+        "w-full max-w-4xl overflow-hidden rounded-xl border border-white/[0.06] bg-white/[0.02]",
+        className
+      )}
+      data-slot="diff-viewer"
+      data-tool-ui-id={id}
+      data-receipt={confirmed !== undefined ? "true" : undefined}
+    >
+      <div className="flex items-center gap-3 border-b border-white/[0.06] px-4 py-3">
+        <div className="min-w-0 flex-1">
+          {title && <h3 className="text-sm font-medium text-white">{title}</h3>}
+          {path && <div className="truncate font-mono text-xs text-white/50">{path}</div>}
+        </div>
+        <div className="shrink-0 font-mono text-xs tabular-nums">
+          <span className="text-emerald-400">+{stats.added}</span>{" "}
+          <span className="text-red-400">-{stats.removed}</span>
+        </div>
+      </div>
+      {description && (
+        <div className="border-b border-white/[0.06] px-4 py-2 text-xs text-white/50 whitespace-pre-wrap">
+          {description}
+        </div>
+      )}
+      <div className="max-h-[28rem] overflow-auto">
+        {rows.length === 0 ? (
+          <div className="px-4 py-8 text-center text-sm text-white/40">No changes</div>
+        ) : (
+          <table className="w-full table-fixed font-mono text-xs leading-5">
+            <colgroup>
+              <col className="w-10" />
+              <col />
+              <col className="w-10" />
+              <col />
+            </colgroup>
+            <tbody>
+              {rows.map((row, index) =>
+                row.kind === "hunk" ? (
+                  <tr key={index} className="bg-indigo-500/[0.06]">
+                    <td colSpan={4} className="px-3 py-1 text-indigo-300/70">
+                      {row.oldText}
+                    </td>
+                  </tr>
+                ) : (
+                  <tr key={index}>
+                    <DiffCell side="old" row={row} />
+                    <DiffCell side="new" row={row} />
+                  </tr>
+                )
+              )}
+            </tbody>
+          </table>
+        )}
+      </div>
+      <div className="border-t border-white/[0.06] px-4 py-3">
+        {confirmed !== undefined ? (
+          <div className="text-sm">
+            {confirmed === null ? (
+              <span className="text-white/50">Dismissed</span>
+            ) : confirmed.approved ? (
+              <span className="inline-flex items-center gap-1.5 text-emerald-400">
+                <Check className="h-4 w-4" /> Approved
+              </span>
+            ) : (
+              <span className="inline-flex items-center gap-1.5 text-red-400">
+                <X className="h-4 w-4" /> Rejected
+              </span>
+            )}
+            {confirmed?.comment && (
+              <div className="mt-1 text-xs text-white/50 whitespace-pre-wrap">
+                {confirmed.comment}
+              </div>
+            )}
+          </div>
+        ) : (
+          <div className="space-y-3">
+            <textarea
+              value={comment}
+              onChange={(e) => setComment(e.target.value)}
+              disabled={submitting}
+              rows={2}
+              placeholder="Optional comment for the agent"
+              className="w-full rounded-lg border border-white/10 bg-white/[0.03] px-3 py-2 text-sm text-white/80 focus:border-indigo-500/40 focus:outline-none disabled:opacity-50"
+            />
+            <div className="flex justify-end gap-2">
+              {onDismiss && (
+                <button
+                  onClick={() => void onDismiss()}
+                  disabled={submitting}
+                  className="rounded-lg px-3 py-2 text-sm text-white/50 hover:text-white/80 transition-colors"
+                >
+                  Dismiss
+                </button>
+              )}
+              <button
+                onClick={() => void decide(false)}
+                disabled={submitting}
+                className="inline-flex items-center gap-2 rounded-lg bg-red-500/15 px-4 py-2 text-sm font-medium text-red-300 hover:bg-red-500/25 transition-colors disabled:opacity-50"
+              >
+                <X className="h-4 w-4" />
+                {rejectLabel ?? "Reject"}
+              </button>
+              <button
+                onClick={() => void decide(true)}
+                disabled={submitting}
+                className="inline-flex items-center gap-2 rounded-lg bg-emerald-500/20 px-4 py-2 text-sm font-medium text-emerald-200 hover:bg-emerald-500/30 transition-colors disabled:opacity-50"
+              >
+                <Check className="h-4 w-4" />
+                {submitting ? "Sending…" : approveLabel ?? "Approve"}
+              </button>
+            </div>
+          </div>
+        )}
+      </div>
+    </div>
+  );
+}
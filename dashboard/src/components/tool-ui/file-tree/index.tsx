@@ -0,0 +1,284 @@
+"use client";
+
+import { useMemo, useState } from "react";
+import { ChevronDown, ChevronRight, Download, File, Folder, Loader } from "lucide-react";
+import { cn } from "@/lib/utils";
+import { formatBytes } from "@/lib/format";
+import { getRuntimeApiBase } from "@/lib/settings";
+import { authHeader } from "@/lib/auth";
+
+export interface FileTreeEntry {
+  path: string;
+  size?: number;
+  description?: string;
+}
+
+export interface SerializableFileTree {
+  id: string;
+  title?: string;
+  description?: string;
+  root?: string;
+  files: FileTreeEntry[];
+}
+
+export interface FileTreeProps extends SerializableFileTree {
+  /** Mission working directory used to resolve relative paths. */
+  basePath?: string;
+  workspaceId?: string;
+  missionId?: string;
+  className?: string;
+}
+
+interface TreeNode {
+  name: string;
+  /** Set for files; directories only have children. */
+  file?: FileTreeEntry;
+  children: Map<string, TreeNode>;
+}
+
+function buildTree(files: FileTreeEntry[]): TreeNode {
+  const root: TreeNode = { name: "", children: new Map() };
+  for (const file of files) {
+    const parts = file.path.split("/").filter((part) => part && part !== ".");
+    if (parts.length === 0) continue;
+    let node = root;
+    parts.forEach((part, index) => {
+      let child = node.children.get(part);
+      if (!child) {
+        child = { name: part, children: new Map() };
+        node.children.set(part, child);
+      }
+      if (index === parts.length - 1) child.file = file;
+      node = child;
+    });
+  }
+  return root;
+}
+
+function sortedChildren(node: TreeNode): TreeNode[] {
+  // Directories first, then alphabetical.
+  return Array.from(node.children.values()).sort((a, b) => {
+    const aDir = a.children.size > 0 && !a.file;
+    const bDir = b.children.size > 0 && !b.file;
+    if (aDir !== bDir) return aDir ? -1 : 1;
+    return a.name.localeCompare(b.name);
+  });
+}
+
+function joinPath(base: string | undefined, path: string): string {
+  if (!base || path.startsWith("/")) return path;
+  return `${base.replace(/\/+$/, "")}/${path.replace(/^\.\//, "")}`;
+}
+
+/** Parse ui_fileTree args. `files` may hold plain path strings or objects. */
+export function parseSerializableFileTree(input: unknown): SerializableFileTree | null {
+  if (!input || typeof input !== "object") return null;
+  const obj = input as Record<string, unknown>;
+  if (!Array.isArray(obj.files)) return null;
+
+  const files: FileTreeEntry[] = [];
+  for (const raw of obj.files) {
+    if (typeof raw === "string" && raw.trim()) {
+      files.push({ path: raw.trim() });
+    } else if (raw && typeof raw === "object") {
+      const entry = raw as Record<string, unknown>;
+      if (typeof entry.path !== "string" || !entry.path.trim()) continue;
+      const size = Number(entry.size);
+      files.push({
+        path: entry.path.trim(),
+        size: entry.size !== undefined && Number.isFinite(size) && size >= 0 ? size : undefined,
+        description: typeof entry.description === "string" ? entry.description : undefined,
+      });
+    }
+  }
+  if (files.length === 0) return null;
+
+  return {
+    id: typeof obj.id === "string" ? obj.id : `file-tree-${Date.now()}`,
+    title: typeof obj.title === "string" ? obj.title : undefined,
+    description: typeof obj.description === "string" ? obj.description : undefined,
+    root: typeof obj.root === "string" && obj.root ? obj.root : undefined,
+    files,
+  };
+}
+
+function FileRow({
+  node,
+  depth,
+  resolve,
+  workspaceId,
+  missionId,
+}: {
+  node: TreeNode;
+  depth: number;
+  resolve: (path: string) => string;
+  workspaceId?: string;
+  missionId?: string;
+}) {
+  const isDir = node.children.size > 0 && !node.file;
+  const [open, setOpen] = useState(true);
+  const [downloading, setDownloading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const handleDownload = async () => {
+    if (!node.file || downloading) return;
+    setDownloading(true);
+    setError(null);
+    try {
+      const API_BASE = getRuntimeApiBase();
+      const params = new URLSearchParams({ path: resolve(node.file.path) });
+      if (workspaceId) params.set("workspace_id", workspaceId);
+      if (missionId) params.set("mission_id", missionId);
+      const res = await fetch(`${API_BASE}/api/fs/download?${params.toString()}`, {
+        headers: { ...authHeader() },
+      });
+      if (!res.ok) {
+        setError(`Download failed (${res.status})`);
+        return;
+      }
+      const blob = await res.blob();
+      const url = URL.createObjectURL(blob);
+      const a = document.createElement("a");
+      a.href = url;
+      a.download = node.name;
+      document.body.appendChild(a);
+      a.click();
+      document.body.removeChild(a);
+      URL.revokeObjectURL(url);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : "Download failed");
+    } finally {
+      setDownloading(false);
+    }
+  };
+
+  return (
+    <>
+      <div
+        className="group flex items-center gap-2 rounded-md px-2 py-1 hover:bg-white/[0.03]"
+        style={{ paddingLeft: `${depth * 16 + 8}px` }}
+      >
+        {isDir ? (
+          <button
+            onClick={() => setOpen((v) => !v)}
+            className="flex min-w-0 flex-1 items-center gap-1.5 text-left text-white/70"
+          >
+            {open ? (
+              <ChevronDown className="h-3.5 w-3.5 shrink-0 text-white/30" />
+            ) : (
+              <ChevronRight className="h-3.5 w-3.5 shrink-0 text-white/30" />
+            )}
+            <Folder className="h-4 w-4 shrink-0 text-indigo-400/70" />
+            <span className="truncate">{node.name}</span>
+          </button>
+        ) : (
+          <button
+            onClick={() => void handleDownload()}
+            title={node.file?.path}
+            className="flex min-w-0 flex-1 items-center gap-1.5 pl-5 text-left text-white/80"
+          >
+            <File className="h-4 w-4 shrink-0 text-white/40" />
+            <span className="truncate group-hover:text-indigo-300">{node.name}</span>
+            {node.file?.description && (
+              <span className="truncate text-xs text-white/40">— {node.file.description}</span>
+            )}
+          </button>
+        )}
+        {node.file?.size !== undefined && (
+          <span className="shrink-0 text-xs tabular-nums text-white/40">
+            {formatBytes(node.file.size)}
+          </span>
+        )}
+        {node.file && (
+          <button
+            onClick={() => void handleDownload()}
+            disabled={downloading}
+            title="Download"
+            className="shrink-0 text-white/30 hover:text-white/70 transition-colors"
+          >
+            {downloading ? (
+              <Loader className="h-3.5 w-3.5 animate-spin" />
+            ) : (
+              <Download className="h-3.5 w-3.5" />
+            )}
+          </button>
+        )}
+      </div>
+      {error && (
+        <div
+          className="px-2 text-xs text-red-400"
+          style={{ paddingLeft: `${depth * 16 + 28}px` }}
+        >
+          {error}
+        </div>
+      )}
+      {isDir &&
+        open &&
+        sortedChildren(node).map((child) => (
+          <FileRow
+            key={child.name}
+            node={child}
+            depth={depth + 1}
+            resolve={resolve}
+            workspaceId={workspaceId}
+            missionId={missionId}
+          />
+        ))}
+    </>
+  );
+}
+
+export function FileTree({
+  id,
+  title,
+  description,
+  root,
+  files,
+  basePath,
+  workspaceId,
+  missionId,
+  className,
+}: FileTreeProps) {
+  const tree = useMemo(() => buildTree(files), [files]);
+  const totalSize = useMemo(
+    () => files.reduce((sum, file) => sum + (file.size ?? 0), 0),
+    [files]
+  );
+  const resolve = (path: string) => joinPath(basePath, joinPath(root, path));
+
+  return (
+    <div
+      className={cn(
+        "w-full max-w-2xl overflow-hidden rounded-xl border border-white/[0.06] bg-white/[0.02]",
+        className
+      )}
+      data-slot="file-tree"
+      data-tool-ui-id={id}
+    >
+      <div className="flex items-center gap-3 border-b border-white/[0.06] px-4 py-3">
+        <div className="min-w-0 flex-1">
+          <h3 className="text-sm font-medium text-white">{title ?? "Files"}</h3>
+          {description && (
+            <div className="mt-0.5 text-xs text-white/50 whitespace-pre-wrap">{description}</div>
+          )}
+        </div>
+        <div className="shrink-0 text-xs text-white/40 tabular-nums">
+          {files.length} {files.length === 1 ? "file" : "files"}
+          {totalSize > 0 && ` · ${formatBytes(totalSize)}`}
+        </div>
+      </div>
+      <div className="max-h-96 overflow-auto py-1 text-sm">
+        {sortedChildren(tree).map((child) => (
+          <FileRow
+            key={child.name}
+            node={child}
+            depth={0}
+            resolve={resolve}
+            workspaceId={workspaceId}
+            missionId={missionId}
+          />
+        ))}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,18 @@
+"use client";
+
+import * as React from "react";
+import {
+  ToolUIErrorBoundary,
+  type ToolUIErrorBoundaryProps,
+} from "../shared";
+
+export function FormErrorBoundary(
+  props: Omit<ToolUIErrorBoundaryProps, "componentName">,
+) {
+  const { children, ...rest } = props;
+  return (
+    <ToolUIErrorBoundary componentName="Form" {...rest}>
+      {children}
+    </ToolUIErrorBoundary>
+  );
+}
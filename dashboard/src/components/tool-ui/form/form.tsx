@@ -0,0 +1,250 @@
+"use client";
+
+import { useMemo, useState } from "react";
+import { Check } from "lucide-react";
+import { cn } from "@/lib/utils";
+import {
+  defaultFormValues,
+  fieldOptions,
+  validateField,
+  type FormField,
+  type FormValue,
+  type FormValues,
+  type SerializableForm,
+} from "./schema";
+
+export interface FormProps extends SerializableForm {
+  /** Submitted values; renders the read-only receipt when set. */
+  confirmed?: FormValues | null;
+  onSubmit?: (values: FormValues) => void | Promise<void>;
+  onCancel?: () => void | Promise<void>;
+  className?: string;
+}
+
+const inputClass =
+  "w-full rounded-lg border border-white/10 bg-white/[0.03] px-3 py-2 text-sm text-white/80 focus:border-indigo-500/40 focus:outline-none disabled:opacity-50";
+
+function formatValue(field: FormField, value: FormValue | undefined): string {
+  if (value === null || value === undefined || value === "") return "-";
+  if (typeof value === "boolean") return value ? "Yes" : "No";
+  if (field.type === "select") {
+    return fieldOptions(field).find((opt) => opt.value === value)?.label ?? String(value);
+  }
+  return String(value);
+}
+
+function FieldInput({
+  field,
+  value,
+  disabled,
+  onChange,
+}: {
+  field: FormField;
+  value: FormValue;
+  disabled: boolean;
+  onChange: (value: FormValue) => void;
+}) {
+  switch (field.type) {
+    case "boolean":
+      return (
+        <label className="flex items-center gap-2 text-sm text-white/80 cursor-pointer">
+          <input
+            type="checkbox"
+            checked={value === true}
+            disabled={disabled}
+            onChange={(e) => onChange(e.target.checked)}
+          />
+          {field.label ?? field.id}
+        </label>
+      );
+    case "select":
+      return (
+        <select
+          value={typeof value === "string" ? value : ""}
+          disabled={disabled}
+          onChange={(e) => onChange(e.target.value || null)}
+          className={inputClass}
+        >
+          <option value="">{field.placeholder ?? "Select…"}</option>
+          {fieldOptions(field).map((opt) => (
+            <option key={opt.value} value={opt.value}>
+              {opt.label}
+            </option>
+          ))}
+        </select>
+      );
+    case "textarea":
+      return (
+        <textarea
+          value={typeof value === "string" ? value : ""}
+          placeholder={field.placeholder}
+          disabled={disabled}
+          rows={3}
+          onChange={(e) => onChange(e.target.value)}
+          className={cn(inputClass, "resize-y")}
+        />
+      );
+    case "number":
+      return (
+        <input
+          type="number"
+          value={typeof value === "number" ? value : ""}
+          min={field.min}
+          max={field.max}
+          placeholder={field.placeholder}
+          disabled={disabled}
+          onChange={(e) =>
+            onChange(e.target.value === "" ? null : e.target.valueAsNumber)
+          }
+          className={inputClass}
+        />
+      );
+    default:
+      return (
+        <input
+          type={field.type === "email" || field.type === "url" ? field.type : "text"}
+          value={typeof value === "string" ? value : ""}
+          placeholder={field.placeholder}
+          disabled={disabled}
+          onChange={(e) => onChange(e.target.value)}
+          className={inputClass}
+        />
+      );
+  }
+}
+
+export function Form({
+  id,
+  title,
+  description,
+  submitLabel,
+  fields,
+  confirmed,
+  onSubmit,
+  onCancel,
+  className,
+}: FormProps) {
+  const [values, setValues] = useState<FormValues>(() => defaultFormValues(fields));
+  const [touched, setTouched] = useState(false);
+  const [submitting, setSubmitting] = useState(false);
+
+  const errors = useMemo(() => {
+    const result: Record<string, string> = {};
+    for (const field of fields) {
+      const error = validateField(field, values[field.id] ?? null);
+      if (error) result[field.id] = error;
+    }
+    return result;
+  }, [fields, values]);
+
+  if (confirmed !== undefined) {
+    return (
+      <div
+        className={cn(
+          "w-full max-w-md min-w-80 rounded-xl border border-white/[0.06] bg-white/[0.02] px-4 py-3",
+          className
+        )}
+        data-slot="form"
+        data-tool-ui-id={id}
+        data-receipt="true"
+      >
+        {title && <div className="mb-2 text-sm font-medium text-white">{title}</div>}
+        {confirmed === null ? (
+          <div className="text-sm text-white/50">Cancelled</div>
+        ) : (
+          <dl className="space-y-1 text-sm">
+            {fields.map((field) => (
+              <div key={field.id} className="flex gap-2">
+                <dt className="text-white/40">{field.label ?? field.id}:</dt>
+                <dd className="text-white/80 break-all">
+                  {formatValue(field, confirmed[field.id])}
+                </dd>
+              </div>
+            ))}
+          </dl>
+        )}
+      </div>
+    );
+  }
+
+  const hasErrors = Object.keys(errors).length > 0;
+
+  const handleSubmit = async () => {
+    setTouched(true);
+    if (hasErrors || submitting || !onSubmit) return;
+    setSubmitting(true);
+    try {
+      await onSubmit(values);
+    } finally {
+      setSubmitting(false);
+    }
+  };
+
+  return (
+    <div
+      className={cn(
+        "w-full max-w-md min-w-80 rounded-xl border border-white/[0.06] bg-white/[0.02] px-4 py-3",
+        className
+      )}
+      data-slot="form"
+      data-tool-ui-id={id}
+    >
+      {title && <div className="text-sm font-medium text-white">{title}</div>}
+      {description && (
+        <div className="mt-1 text-xs text-white/50 whitespace-pre-wrap">{description}</div>
+      )}
+      <div className="mt-3 space-y-3">
+        {fields.map((field) => (
+          <div key={field.id} className="space-y-1">
+            {field.type !== "boolean" && (
+              <label className="text-xs text-white/60">
+                {field.label ?? field.id}
+                {field.required === false && (
+                  <span className="text-white/30"> (optional)</span>
+                )}
+              </label>
+            )}
+            <FieldInput
+              field={field}
+              value={values[field.id] ?? null}
+              disabled={submitting}
+              onChange={(value) =>
+                setValues((prev) => ({ ...prev, [field.id]: value }))
+              }
+            />
+            {field.description && (
+              <div className="text-xs text-white/40">{field.description}</div>
+            )}
+            {touched && errors[field.id] && (
+              <div className="text-xs text-red-400">{errors[field.id]}</div>
+            )}
+          </div>
+        ))}
+      </div>
+      <div className="mt-4 flex justify-end gap-2">
+        {onCancel && (
+          <button
+            onClick={() => void onCancel()}
+            disabled={submitting}
+            className="rounded-lg px-3 py-2 text-sm text-white/50 hover:text-white/80 transition-colors"
+          >
+            Cancel
+          </button>
+        )}
+        <button
+          onClick={() => void handleSubmit()}
+          disabled={submitting || (touched && hasErrors)}
+          className={cn(
+            "inline-flex items-center gap-2 rounded-lg px-4 py-2 text-sm font-medium transition-colors",
+            submitting || (touched && hasErrors)
+              ? "bg-white/5 text-white/30 cursor-not-allowed"
+              : "bg-indigo-500/20 text-indigo-200 hover:bg-indigo-500/30"
+          )}
+        >
+          <Check className="h-4 w-4" />
+          {submitting ? "Sending…" : submitLabel ?? "Submit"}
+        </button>
+      </div>
+    </div>
+  );
+}
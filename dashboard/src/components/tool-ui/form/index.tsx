@@ -0,0 +1,14 @@
+export { Form } from "./form";
+export type { FormProps } from "./form";
+export { FormErrorBoundary } from "./error-boundary";
+export type {
+  FormField,
+  FormValue,
+  FormValues,
+  SerializableForm,
+} from "./schema";
+export {
+  FormFieldSchema,
+  SerializableFormSchema,
+  parseSerializableForm,
+} from "./schema";
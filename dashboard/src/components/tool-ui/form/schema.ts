@@ -0,0 +1,119 @@
+import { z } from "zod";
+import { ToolUIIdSchema, parseWithSchema } from "../shared";
+
+export const FormFieldOptionSchema = z.union([
+  z.string().min(1),
+  z.object({
+    value: z.string().min(1),
+    label: z.string().optional(),
+  }),
+]);
+
+export const FormFieldSchema = z.object({
+  id: z.string().min(1),
+  label: z.string().optional(),
+  type: z
+    .enum(["text", "textarea", "number", "boolean", "select", "email", "url"])
+    .optional(),
+  description: z.string().optional(),
+  placeholder: z.string().optional(),
+  required: z.boolean().optional(),
+  default: z.union([z.string(), z.number(), z.boolean()]).optional(),
+  /** Choices for `select` fields. */
+  options: z.array(FormFieldOptionSchema).optional(),
+  /** Bounds for `number` fields, or length bounds for text fields. */
+  min: z.number().optional(),
+  max: z.number().optional(),
+  /** Regular expression text values must match. */
+  pattern: z.string().optional(),
+  /** Message shown when `pattern` doesn't match. */
+  patternHint: z.string().optional(),
+});
+
+export const SerializableFormSchema = z.object({
+  id: ToolUIIdSchema,
+  title: z.string().optional(),
+  description: z.string().optional(),
+  submitLabel: z.string().optional(),
+  fields: z.array(FormFieldSchema).min(1),
+});
+
+export type FormField = z.infer<typeof FormFieldSchema>;
+export type FormValue = string | number | boolean | null;
+export type FormValues = Record<string, FormValue>;
+export type SerializableForm = z.infer<typeof SerializableFormSchema>;
+
+export function parseSerializableForm(input: unknown): SerializableForm {
+  return parseWithSchema(SerializableFormSchema, input, "Form");
+}
+
+export function fieldOptions(field: FormField): Array<{ value: string; label: string }> {
+  return (field.options ?? []).map((opt) =>
+    typeof opt === "string"
+      ? { value: opt, label: opt }
+      : { value: opt.value, label: opt.label ?? opt.value },
+  );
+}
+
+/** Initial values from field defaults. */
+export function defaultFormValues(fields: FormField[]): FormValues {
+  const values: FormValues = {};
+  for (const field of fields) {
+    if (field.default !== undefined) {
+      values[field.id] = field.default;
+    } else if (field.type === "boolean") {
+      values[field.id] = false;
+    } else {
+      values[field.id] = null;
+    }
+  }
+  return values;
+}
+
+/** Validate one field, returning an error message or null. */
+export function validateField(field: FormField, value: FormValue): string | null {
+  const empty = value === null || value === "";
+  if (empty) {
+    return field.required === false || field.type === "boolean"
+      ? null
+      : "Required";
+  }
+  if (field.type === "number") {
+    if (typeof value !== "number" || Number.isNaN(value)) return "Must be a number";
+    if (field.min !== undefined && value < field.min) return `Must be at least ${field.min}`;
+    if (field.max !== undefined && value > field.max) return `Must be at most ${field.max}`;
+    return null;
+  }
+  if (typeof value === "string") {
+    if (field.min !== undefined && value.length < field.min) {
+      return `Must be at least ${field.min} characters`;
+    }
+    if (field.max !== undefined && value.length > field.max) {
+      return `Must be at most ${field.max} characters`;
+    }
+    if (field.type === "email" && !/^[^\s@]+@[^\s@]+\.[^\s@]+$/.test(value)) {
+      return "Must be an email address";
+    }
+    if (field.type === "url") {
+      try {
+        new URL(value);
+      } catch {
+        return "Must be a URL";
+      }
+    }
+    if (field.type === "select") {
+      const allowed = fieldOptions(field).map((opt) => opt.value);
+      if (!allowed.includes(value)) return "Pick one of the options";
+    }
+    if (field.pattern) {
+      try {
+        if (!new RegExp(`^(?:${field.pattern})$`).test(value)) {
+          return field.patternHint ?? "Invalid format";
+        }
+      } catch {
+        // Ignore invalid patterns from the model.
+      }
+    }
+  }
+  return null;
+}
@@ -32,6 +32,7 @@ import {
   libDel,
   ensureLibraryResponse,
 } from "./api/core";
+import type { MissionHistoryEntry, MissionPriority } from "./api/missions";
 
 // Types that remain in this file (not yet migrated to modules)
 export interface TaskState {
@@ -121,6 +122,39 @@ export async function getStats(): Promise<StatsResponse> {
   return apiGet("/api/stats", "Failed to fetch stats");
 }
 
+// Cost report
+export type CostGroupBy = "mission" | "backend" | "model" | "day";
+
+export interface CostGroup {
+  key: string;
+  label?: string;
+  cost_cents: number;
+  turns: number;
+}
+
+export interface CostReport {
+  group_by: CostGroupBy;
+  since: string | null;
+  until: string | null;
+  total_cents: number;
+  turns: number;
+  groups: CostGroup[];
+}
+
+export async function getCosts(params: {
+  group_by?: CostGroupBy;
+  since?: string;
+  until?: string;
+  days?: number;
+} = {}): Promise<CostReport> {
+  const query = new URLSearchParams();
+  for (const [key, value] of Object.entries(params)) {
+    if (value !== undefined) query.set(key, String(value));
+  }
+  const suffix = query.toString() ? `?${query}` : "";
+  return apiGet(`/api/costs${suffix}`, "Failed to fetch costs");
+}
+
 // List all tasks
 export async function listTasks(): Promise<TaskState[]> {
   return apiGet("/api/tasks", "Failed to fetch tasks");
@@ -201,6 +235,8 @@ export function streamTask(
           for (const line of raw.split("\n")) {
             if (line.startsWith("event:")) {
               eventType = line.slice("event:".length).trim();
+            } else if (line.startsWith("id:")) {
+              lastControlEventId = line.slice("id:".length).trim();
             } else if (line.startsWith("data:")) {
               data += line.slice("data:".length).trim();
             }
@@ -307,6 +343,13 @@ export type ControlAgentEvent =
       shared_files?: SharedFile[];
     }
   | { type: "thinking"; content: string; done: boolean; mission_id?: string }
+  | {
+      type: "diagnostic";
+      level: "info" | "warn" | "error";
+      source: string;
+      message: string;
+      mission_id?: string;
+    }
   | {
       type: "tool_call";
       tool_call_id: string;
@@ -339,7 +382,10 @@ export async function postControlMessage(
     headers: { "Content-Type": "application/json" },
     body: JSON.stringify(body),
   });
-  if (!res.ok) throw new Error("Failed to post control message");
+  if (!res.ok) {
+    const text = await res.text().catch(() => "");
+    throw new Error(text || "Failed to post control message");
+  }
   return res.json();
 }
 
@@ -361,6 +407,7 @@ export interface QueuedMessage {
   content: string;
   agent: string | null;
   mission_id: string | null;
+  priority: MissionPriority;
 }
 
 export async function getQueue(): Promise<QueuedMessage[]> {
@@ -371,10 +418,41 @@ export async function removeFromQueue(messageId: string): Promise<void> {
   return apiDel(`/api/control/queue/${messageId}`, "Failed to remove from queue");
 }
 
+export async function setQueuedPriority(messageId: string, priority: MissionPriority): Promise<void> {
+  return apiPatch(`/api/control/queue/${messageId}`, { priority }, "Failed to update queued message");
+}
+
+export async function editQueuedMessage(messageId: string, content: string): Promise<void> {
+  return apiPatch(`/api/control/queue/${messageId}`, { content }, "Failed to edit queued message");
+}
+
+export async function getMissionQueue(missionId: string): Promise<QueuedMessage[]> {
+  return apiGet(`/api/control/missions/${missionId}/queue`, "Failed to fetch mission queue");
+}
+
+export async function reorderQueue(messageIds: string[]): Promise<{ moved: number }> {
+  return apiPut("/api/control/queue", { message_ids: messageIds }, "Failed to reorder queue");
+}
+
 export async function clearQueue(): Promise<{ cleared: number }> {
   return apiDel("/api/control/queue", "Failed to clear queue");
 }
 
+// Control session history (kept across restarts)
+export interface ControlHistory {
+  current_mission: string | null;
+  messages: MissionHistoryEntry[];
+}
+
+export async function getControlHistory(): Promise<ControlHistory> {
+  return apiGet("/api/control/history", "Failed to fetch control history");
+}
+
+export async function truncateControlHistory(keep?: number): Promise<{ removed: number }> {
+  const query = keep === undefined ? "" : `?keep=${keep}`;
+  return apiDel(`/api/control/history${query}`, "Failed to truncate control history");
+}
+
 // Agent tree snapshot (for refresh resilience)
 export interface AgentTreeNode {
   id: string;
@@ -424,6 +502,10 @@ export type StreamDiagnosticUpdate = {
   timestamp: number;
 };
 
+// Id of the last control event received, sent as Last-Event-ID when the
+// stream reconnects so the server replays what was missed.
+let lastControlEventId: string | null = null;
+
 export function streamControl(
   onEvent: (event: { type: string; data: unknown }) => void,
   onDiagnostics?: (update: StreamDiagnosticUpdate) => void
@@ -444,7 +526,10 @@ export function streamControl(
     try {
       const res = await apiFetch(streamUrl, {
         method: "GET",
-        headers: { Accept: "text/event-stream" },
+        headers: {
+          Accept: "text/event-stream",
+          ...(lastControlEventId ? { "Last-Event-ID": lastControlEventId } : {}),
+        },
         signal: controller.signal,
       });
 
@@ -977,6 +1062,21 @@ export interface LibraryStatus {
   ahead: number;
   behind: number;
   modified_files: string[];
+  stashed_changes?: number;
+  warning?: string | null;
+  auto_commit?: LibraryAutoCommitStatus;
+  read_only?: boolean;
+}
+
+export interface LibraryAutoCommitStatus {
+  window_secs: number;
+  pending_files: number;
+  pending_since?: string;
+  last_commit?: string;
+  last_commit_at?: string;
+  last_push_at?: string;
+  unpushed: boolean;
+  last_error?: string;
 }
 
 // MCP Server definition (OpenCode-aligned format)
@@ -990,6 +1090,16 @@ export interface McpServerDef {
   headers?: Record<string, string>;
   // Common
   enabled?: boolean;
+  // Per-environment overlays, e.g. { prod: { url: "..." } }
+  envs?: Record<string, McpServerOverlay>;
+}
+
+export interface McpServerOverlay {
+  command?: string[];
+  env?: Record<string, string>;
+  url?: string;
+  headers?: Record<string, string>;
+  enabled?: boolean;
 }
 
 // Skill file within a skill folder
@@ -1016,6 +1126,8 @@ export interface SkillSummary {
   description: string | null;
   path: string;
   source?: SkillSource;
+  enabled?: boolean;
+  workspaces?: string[];
 }
 
 export interface Skill {
@@ -1079,6 +1191,8 @@ export interface CommandParam {
   name: string;
   required: boolean;
   description: string | null;
+  type?: 'string' | 'integer' | 'number' | 'boolean';
+  default?: string;
 }
 
 export interface CommandSummary {
@@ -2480,6 +2594,111 @@ export async function updateLibraryRemote(
   return res.json();
 }
 
+// Tool permission policy (allow / deny / ask per tool)
+export type ToolPolicyAction = 'allow' | 'deny' | 'ask';
+
+export interface ToolPolicyRule {
+  /** Tool name glob, e.g. "run_command" or "desktop_*". */
+  tool: string;
+  action: ToolPolicyAction;
+  mission_id?: string;
+  workspace_id?: string;
+}
+
+export interface ToolPolicy {
+  default_action: ToolPolicyAction;
+  rules: ToolPolicyRule[];
+}
+
+export async function getToolPolicy(): Promise<ToolPolicy> {
+  const res = await apiFetch('/api/settings/tool-policy');
+  if (!res.ok) throw new Error('Failed to fetch tool policy');
+  return res.json();
+}
+
+export async function updateToolPolicy(policy: ToolPolicy): Promise<ToolPolicy> {
+  const res = await apiFetch('/api/settings/tool-policy', {
+    method: 'PUT',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify(policy),
+  });
+  if (!res.ok) {
+    const text = await res.text();
+    throw new Error(text || 'Failed to update tool policy');
+  }
+  return res.json();
+}
+
+export type ToolResultStrategy = 'truncate' | 'summarize';
+
+export interface ToolResultLimit {
+  /** Tool name glob, e.g. "grep_search" or "desktop_*". */
+  tool: string;
+  /** Cap in bytes (0 = unlimited). */
+  max_bytes: number;
+  strategy?: ToolResultStrategy;
+}
+
+export interface ToolResultLimits {
+  max_bytes: number;
+  strategy: ToolResultStrategy;
+  summary_model?: string;
+  tools: ToolResultLimit[];
+}
+
+export async function getToolResultLimits(): Promise<ToolResultLimits> {
+  const res = await apiFetch('/api/settings/tool-result-limits');
+  if (!res.ok) throw new Error('Failed to fetch tool result limits');
+  return res.json();
+}
+
+export async function updateToolResultLimits(limits: ToolResultLimits): Promise<ToolResultLimits> {
+  const res = await apiFetch('/api/settings/tool-result-limits', {
+    method: 'PUT',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify(limits),
+  });
+  if (!res.ok) {
+    const text = await res.text();
+    throw new Error(text || 'Failed to update tool result limits');
+  }
+  return res.json();
+}
+
+export interface ToolAuditEntry {
+  timestamp: string;
+  tool: string;
+  /** SHA-256 of the JSON-encoded arguments. */
+  args_digest: string;
+  working_dir: string;
+  duration_ms: number;
+  result_bytes: number;
+  success: boolean;
+  error?: string;
+  mission_id?: string;
+  workspace_id?: string;
+}
+
+export interface ToolAuditQuery {
+  mission_id?: string;
+  workspace_id?: string;
+  tool?: string;
+  success?: boolean;
+  since?: string;
+  limit?: number;
+}
+
+export async function listToolAudit(query: ToolAuditQuery = {}): Promise<ToolAuditEntry[]> {
+  const params = new URLSearchParams();
+  for (const [key, value] of Object.entries(query)) {
+    if (value !== undefined) params.set(key, String(value));
+  }
+  const qs = params.toString();
+  const res = await apiFetch(`/api/audit/tools${qs ? `?${qs}` : ''}`);
+  if (!res.ok) throw new Error('Failed to fetch tool audit log');
+  return res.json();
+}
+
 // ============================================
 // Backends API
 // ============================================
@@ -2536,6 +2755,28 @@ export async function updateBackendConfig(
   );
 }
 
+export interface BackendModelCatalog {
+  backend_id: string;
+  models: { id: string; name: string }[];
+  source: 'cli' | 'builtin' | 'stale';
+  fetched_at: string;
+  ttl_secs: number;
+}
+
+// Get the cached model catalog for a backend (refreshed server-side when stale)
+export async function getBackendModels(backendId: string): Promise<BackendModelCatalog> {
+  return apiGet(`/api/backends/${encodeURIComponent(backendId)}/models`, 'Failed to get backend models');
+}
+
+// Force a model catalog refresh from the backend CLI
+export async function refreshBackendModels(backendId: string): Promise<BackendModelCatalog> {
+  return apiPost(
+    `/api/backends/${encodeURIComponent(backendId)}/models/refresh`,
+    undefined,
+    'Failed to refresh backend models',
+  );
+}
+
 // ============================================
 // Backup & Restore API
 // ============================================
@@ -2,7 +2,7 @@
  * Missions API - CRUD and control operations for missions.
  */
 
-import { apiGet, apiPost, apiFetch } from "./core";
+import { apiGet, apiPost, apiPut, apiDel, apiFetch } from "./core";
 
 // ---------------------------------------------------------------------------
 // Types
@@ -10,6 +10,8 @@ import { apiGet, apiPost, apiFetch } from "./core";
 
 export type MissionStatus = "active" | "completed" | "failed" | "interrupted" | "blocked" | "not_feasible";
 
+export type MissionPriority = "low" | "normal" | "high" | "urgent";
+
 export interface MissionHistoryEntry {
   role: string;
   content: string;
@@ -25,6 +27,60 @@ export interface DesktopSessionInfo {
   url?: string;
 }
 
+export type RetryOn = "llm_error" | "stalled" | "network";
+
+export interface RetryPolicy {
+  max_attempts?: number;
+  backoff_secs?: number;
+  retry_on?: RetryOn[];
+}
+
+export type DeliverableCheck =
+  | { kind: "exists"; path: string }
+  | { kind: "non_empty"; path: string }
+  | { kind: "command"; command: string; timeout_secs?: number }
+  | { kind: "http_status"; url: string; status?: number }
+  | { kind: "json_schema"; path: string; schema: Record<string, unknown> };
+
+export interface CheckResult {
+  check: DeliverableCheck;
+  passed: boolean;
+  detail?: string;
+  duration_ms: number;
+}
+
+export interface VerificationReport {
+  verified_at: string;
+  passed: boolean;
+  results: CheckResult[];
+}
+
+export interface MissionVerifier {
+  // AI provider type, e.g. "anthropic"; the default provider if unset
+  provider?: string;
+  model?: string;
+}
+
+export interface StallThresholds {
+  warn_secs: number;
+  severe_secs: number;
+}
+
+export interface CompletionDeliverable {
+  path: string;
+  description?: string;
+  size_bytes: number;
+  sha256: string;
+}
+
+export interface CompletionReport {
+  status: string;
+  summary?: string;
+  deliverables: CompletionDeliverable[];
+  follow_ups: string[];
+  reported_at: string;
+}
+
 export interface Mission {
   id: string;
   status: MissionStatus;
@@ -33,6 +89,18 @@ export interface Mission {
   workspace_name?: string;
   agent?: string;
   backend?: string;
+  priority?: MissionPriority;
+  budget_cents?: number;
+  spent_cents?: number;
+  timeout_minutes?: number;
+  retry_policy?: RetryPolicy;
+  max_concurrent_messages?: number;
+  tags?: Record<string, string>;
+  deliverable_checks?: DeliverableCheck[];
+  verification?: VerificationReport;
+  verifier?: MissionVerifier;
+  stall_thresholds?: StallThresholds;
+  completion_report?: CompletionReport;
   history: MissionHistoryEntry[];
   desktop_sessions?: DesktopSessionInfo[];
   created_at: string;
@@ -61,16 +129,37 @@ export interface CreateMissionOptions {
   modelOverride?: string;
   configProfile?: string;
   backend?: string;
+  priority?: MissionPriority;
+  budgetCents?: number;
+  timeoutMinutes?: number;
+  retryPolicy?: RetryPolicy;
+  maxConcurrentMessages?: number;
+  tags?: Record<string, string>;
+  deliverableChecks?: DeliverableCheck[];
+  verifier?: MissionVerifier;
+  stallThresholds?: StallThresholds;
+}
+
+export interface ListMissionsFilter {
+  // "key:value" or bare keys; a mission must match all of them
+  tags?: string[];
+  // Mission status, or "running"/"queued"
+  state?: string;
+  // Searched in the title and initial prompt
+  q?: string;
+  limit?: number;
 }
 
 export interface RunningMissionInfo {
   mission_id: string;
-  state: "queued" | "running" | "waiting_for_tool" | "finished";
+  state: "queued" | "waiting_for_workspace" | "running" | "waiting_for_tool" | "finished";
   queue_len: number;
   history_len: number;
   seconds_since_activity: number;
   health: MissionHealth;
   expected_deliverables: number;
+  paused?: boolean;
+  tags?: Record<string, string>;
 }
 
 export type MissionStallSeverity = "warning" | "severe";
@@ -84,14 +173,28 @@ export type MissionHealth =
       severity: MissionStallSeverity;
     }
   | { status: "missing_deliverables"; missing: string[] }
+  | { status: "failed_checks"; failed: string[] }
   | { status: "unexpected_end"; reason: string };
 
 // ---------------------------------------------------------------------------
 // API Functions
 // ---------------------------------------------------------------------------
 
-export async function listMissions(): Promise<Mission[]> {
-  return apiGet("/api/control/missions", "Failed to fetch missions");
+export async function listMissions(filter?: ListMissionsFilter): Promise<Mission[]> {
+  const params = new URLSearchParams();
+  if (filter?.tags?.length) params.set("tag", filter.tags.join(","));
+  if (filter?.state) params.set("state", filter.state);
+  if (filter?.q) params.set("q", filter.q);
+  if (filter?.limit !== undefined) params.set("limit", String(filter.limit));
+  const query = params.toString();
+  return apiGet(`/api/control/missions${query ? `?${query}` : ""}`, "Failed to fetch missions");
+}
+
+export async function setMissionTags(
+  id: string,
+  tags: Record<string, string>
+): Promise<Mission> {
+  return apiPut(`/api/missions/${id}/tags`, { tags }, "Failed to set mission tags");
 }
 
 export async function getMission(id: string): Promise<Mission> {
@@ -124,6 +227,15 @@ export async function createMission(
     model_override?: string;
     config_profile?: string;
     backend?: string;
+    priority?: MissionPriority;
+    budget_cents?: number;
+    timeout_minutes?: number;
+    retry_policy?: RetryPolicy;
+    max_concurrent_messages?: number;
+    tags?: Record<string, string>;
+    deliverable_checks?: DeliverableCheck[];
+    verifier?: MissionVerifier;
+    stall_thresholds?: StallThresholds;
   } = {};
 
   if (options?.title) body.title = options.title;
@@ -132,6 +244,16 @@ export async function createMission(
   if (options?.modelOverride) body.model_override = options.modelOverride;
   if (options?.configProfile) body.config_profile = options.configProfile;
   if (options?.backend) body.backend = options.backend;
+  if (options?.priority) body.priority = options.priority;
+  if (options?.budgetCents) body.budget_cents = options.budgetCents;
+  if (options?.timeoutMinutes !== undefined) body.timeout_minutes = options.timeoutMinutes;
+  if (options?.retryPolicy) body.retry_policy = options.retryPolicy;
+  if (options?.maxConcurrentMessages !== undefined)
+    body.max_concurrent_messages = options.maxConcurrentMessages;
+  if (options?.tags) body.tags = options.tags;
+  if (options?.deliverableChecks?.length) body.deliverable_checks = options.deliverableChecks;
+  if (options?.verifier) body.verifier = options.verifier;
+  if (options?.stallThresholds) body.stall_thresholds = options.stallThresholds;
 
   const res = await apiFetch("/api/control/missions", {
     method: "POST",
@@ -173,6 +295,21 @@ export async function cancelMission(missionId: string): Promise<void> {
   return apiPost(`/api/control/missions/${missionId}/cancel`, undefined, "Failed to cancel mission");
 }
 
+// Pause a mission; resume it with resumeMission
+export async function pauseMission(missionId: string): Promise<void> {
+  return apiPost(`/api/missions/${missionId}/pause`, undefined, "Failed to pause mission");
+}
+
+// Send a message to the running turn instead of queueing it. "injected":
+// the backend took it mid-turn; "interrupted": the turn was stopped and the
+// message runs next in the same session.
+export async function steerMission(
+  missionId: string,
+  content: string
+): Promise<{ ok: boolean; mode: "injected" | "interrupted" }> {
+  return apiPost(`/api/missions/${missionId}/steer`, { content }, "Failed to steer mission");
+}
+
 export async function setMissionStatus(
   id: string,
   status: MissionStatus
@@ -180,6 +317,27 @@ export async function setMissionStatus(
   return apiPost(`/api/control/missions/${id}/status`, { status }, "Failed to set mission status");
 }
 
+export async function setMissionPriority(
+  id: string,
+  priority: MissionPriority
+): Promise<void> {
+  return apiPost(`/api/control/missions/${id}/priority`, { priority }, "Failed to set mission priority");
+}
+
+export async function setMissionBudget(
+  id: string,
+  budgetCents: number | null
+): Promise<Mission> {
+  return apiPost(`/api/control/missions/${id}/budget`, { budget_cents: budgetCents }, "Failed to set mission budget");
+}
+
+export async function setMissionTimeout(
+  id: string,
+  timeoutMinutes: number | null
+): Promise<Mission> {
+  return apiPost(`/api/control/missions/${id}/timeout`, { timeout_minutes: timeoutMinutes }, "Failed to set mission timeout");
+}
+
 export async function deleteMission(id: string): Promise<{ ok: boolean; deleted: string }> {
   const res = await apiFetch(`/api/control/missions/${id}`, {
     method: "DELETE",
@@ -202,11 +360,113 @@ export async function cleanupEmptyMissions(): Promise<{ ok: boolean; deleted_cou
   return res.json();
 }
 
+export interface MissionCheckpointSummary {
+  id: string;
+  created_at: string;
+  history_len: number;
+  missing_deliverables: string[];
+  session_id: string | null;
+  workspace_ref: string | null;
+}
+
+export async function listMissionCheckpoints(id: string): Promise<MissionCheckpointSummary[]> {
+  return apiGet(`/api/missions/${id}/checkpoints`, "Failed to fetch mission checkpoints");
+}
+
+export interface MissionArtifact {
+  id: string;
+  mission_id: string;
+  path: string;
+  name: string;
+  description: string | null;
+  size: number;
+  sha256: string;
+  source: "tool" | "deliverable";
+  created_at: string;
+  updated_at: string;
+}
+
+export async function listMissionArtifacts(id: string): Promise<MissionArtifact[]> {
+  return apiGet(`/api/missions/${id}/artifacts`, "Failed to fetch mission artifacts");
+}
+
+export async function downloadMissionArtifact(
+  missionId: string,
+  artifactId: string
+): Promise<Blob> {
+  const res = await apiFetch(`/api/missions/${missionId}/artifacts/${artifactId}/download`);
+  if (!res.ok) {
+    const text = await res.text();
+    throw new Error(`Failed to download artifact: ${text}`);
+  }
+  return res.blob();
+}
+
+export async function getMissionTranscript(id: string, format: "md" | "json" = "md"): Promise<string> {
+  const res = await apiFetch(`/api/missions/${id}/transcript?format=${format}`);
+  if (!res.ok) {
+    const text = await res.text();
+    throw new Error(`Failed to export transcript: ${text}`);
+  }
+  return res.text();
+}
+
+export interface MissionHealthReport {
+  mission_id: string;
+  status: MissionStatus;
+  running: boolean;
+  health: MissionHealth;
+  verification?: VerificationReport;
+}
+
+export async function getMissionHealth(id: string): Promise<MissionHealthReport> {
+  return apiGet(`/api/missions/${id}/health`, "Failed to fetch mission health");
+}
+
+export async function verifyMission(id: string): Promise<VerificationReport> {
+  return apiPost(`/api/missions/${id}/verify`, undefined, "Failed to verify mission");
+}
+
+export async function setDeliverableChecks(
+  id: string,
+  checks: DeliverableCheck[]
+): Promise<Mission> {
+  return apiPut(`/api/missions/${id}/checks`, { checks }, "Failed to set deliverable checks");
+}
+
+export async function setMissionVerifier(
+  id: string,
+  verifier: MissionVerifier | null
+): Promise<Mission> {
+  return apiPut(`/api/missions/${id}/verifier`, { verifier }, "Failed to set mission verifier");
+}
+
+export async function cloneMission(
+  id: string,
+  options?: { title?: string; prompt?: string }
+): Promise<Mission> {
+  return apiPost(`/api/missions/${id}/clone`, options ?? {}, "Failed to re-run mission");
+}
+
+export async function setStallThresholds(
+  id: string,
+  stallThresholds: StallThresholds | null
+): Promise<Mission> {
+  return apiPut(
+    `/api/missions/${id}/stall-thresholds`,
+    { stall_thresholds: stallThresholds },
+    "Failed to set stall thresholds"
+  );
+}
+
 export async function resumeMission(
   id: string,
-  options?: { skipMessage?: boolean }
+  options?: { skipMessage?: boolean; checkpoint?: string }
 ): Promise<Mission> {
-  const res = await apiFetch(`/api/control/missions/${id}/resume`, {
+  const query = options?.checkpoint
+    ? `?checkpoint=${encodeURIComponent(options.checkpoint)}`
+    : "";
+  const res = await apiFetch(`/api/missions/${id}/resume${query}`, {
     method: "POST",
     headers: options ? { "Content-Type": "application/json" } : undefined,
     body: options ? JSON.stringify({ skip_message: options.skipMessage }) : undefined,
@@ -217,3 +477,35 @@ export async function resumeMission(
   }
   return res.json();
 }
+
+// Named control sessions: send `X-Control-Session: <name>` (or
+// `?control_session=<name>` on streams) to talk to one instead of the
+// default session.
+export interface ControlSession {
+  name: string;
+  working_dir: string;
+  created_at: string;
+  status: { state: string; queue_len: number; mission_id: string | null } | null;
+}
+
+export async function listControlSessions(): Promise<ControlSession[]> {
+  return apiGet("/api/control/sessions", "Failed to list control sessions");
+}
+
+export async function createControlSession(
+  name: string,
+  workingDir?: string
+): Promise<ControlSession> {
+  return apiPost(
+    "/api/control/sessions",
+    { name, working_dir: workingDir },
+    "Failed to create control session"
+  );
+}
+
+export async function deleteControlSession(name: string): Promise<void> {
+  return apiDel(
+    `/api/control/sessions/${encodeURIComponent(name)}`,
+    "Failed to delete control session"
+  );
+}
@@ -28,7 +28,11 @@ export interface Workspace {
   init_script?: string | null;
   shared_network?: boolean | null;
   tailscale_mode?: TailscaleMode | null;
+  /** Tool names available in this workspace (`*` globs allowed); empty = all tools. */
+  tools?: string[];
   config_profile?: string | null;
+  /** Missions that may run in this workspace at once; null = no limit. */
+  max_concurrent_missions?: number | null;
 }
 
 export type ContainerDistro =
@@ -90,7 +94,9 @@ export async function createWorkspace(data: {
   init_script?: string;
   shared_network?: boolean | null;
   tailscale_mode?: TailscaleMode | null;
+  tools?: string[];
   config_profile?: string | null;
+  max_concurrent_missions?: number;
 }): Promise<Workspace> {
   return apiPost("/api/workspaces", data, "Failed to create workspace");
 }
@@ -107,7 +113,9 @@ export async function updateWorkspace(
     init_script?: string | null;
     shared_network?: boolean | null;
     tailscale_mode?: TailscaleMode | null;
+    tools?: string[];
     config_profile?: string | null;
+    max_concurrent_missions?: number;
   }
 ): Promise<Workspace> {
   return apiPut(`/api/workspaces/${id}`, data, "Failed to update workspace");
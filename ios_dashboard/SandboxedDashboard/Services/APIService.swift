@@ -146,7 +146,7 @@ final class APIService {
     }
     
     func resumeMission(id: String) async throws -> Mission {
-        try await post("/api/control/missions/\(id)/resume", body: EmptyBody())
+        try await post("/api/missions/\(id)/resume", body: EmptyBody())
     }
     
     func cancelMission(id: String) async throws {
@@ -8,8 +8,12 @@ use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
 use crate::config::Config;
-use crate::mcp::McpRegistry;
+use crate::mcp::{McpRegistry, McpTool};
+use crate::secrets::SecretsStore;
 use crate::tools::mission::MissionControl;
+use crate::tools::{
+    tool_allowed, PolicyApprover, PolicyScope, ToolPolicy, ToolRegistry, ToolResultLimits,
+};
 
 /// Shared context passed to all agents during execution.
 ///
@@ -52,6 +56,21 @@ pub struct AgentContext {
 
     /// MCP registry for dynamic tool discovery and execution.
     pub mcp: Option<Arc<McpRegistry>>,
+
+    /// Workspace the mission runs in (for workspace-scoped tool policy rules).
+    pub workspace_id: Option<Uuid>,
+
+    /// Tool permission policy enforced by `tool_registry()`.
+    pub tool_policy: Option<ToolPolicy>,
+
+    /// Workspace tool allowlist (empty = all tools).
+    pub tool_allowlist: Vec<String>,
+
+    /// Size limits applied to tool results by `tool_registry()`.
+    pub tool_result_limits: Option<ToolResultLimits>,
+
+    /// Secrets vault that tool calls resolve `registry/key` references against.
+    pub secrets: Option<Arc<SecretsStore>>,
 }
 
 impl AgentContext {
@@ -70,6 +89,11 @@ impl AgentContext {
             progress_snapshot: None,
             mission_id: None,
             mcp: None,
+            workspace_id: None,
+            tool_policy: None,
+            tool_allowlist: Vec::new(),
+            tool_result_limits: None,
+            secrets: None,
         }
     }
 
@@ -88,7 +112,52 @@ impl AgentContext {
             progress_snapshot: self.progress_snapshot.clone(),
             mission_id: self.mission_id,
             mcp: self.mcp.clone(),
+            workspace_id: self.workspace_id,
+            tool_policy: self.tool_policy.clone(),
+            tool_allowlist: self.tool_allowlist.clone(),
+            tool_result_limits: self.tool_result_limits.clone(),
+            secrets: self.secrets.clone(),
+        }
+    }
+
+    /// Build the tool registry for this context, restricted to the workspace
+    /// tool allowlist and enforcing the tool policy. `ask` rules are routed to
+    /// the dashboard when a frontend tool hub is attached.
+    pub fn tool_registry(&self) -> ToolRegistry {
+        let mut registry = ToolRegistry::with_mission_control(self.mission_control.clone())
+            .with_secrets(self.secrets.clone())
+            .with_allowlist(&self.tool_allowlist)
+            .with_scope(PolicyScope {
+                mission_id: self.mission_id,
+                workspace_id: self.workspace_id,
+            });
+        if let Some(policy) = self.tool_policy.clone() {
+            registry = registry.with_policy(policy);
+        }
+        if let Some(limits) = self.tool_result_limits.clone() {
+            registry = registry.with_result_limits(limits);
+        }
+        if let (Some(hub), Some(events)) = (&self.frontend_tool_hub, &self.control_events) {
+            registry = registry.with_approver(PolicyApprover {
+                hub: Arc::clone(hub),
+                events: events.clone(),
+                mission_id: self.mission_id,
+            });
         }
+        registry
+    }
+
+    /// MCP tools available to this context, filtered by the workspace tool
+    /// allowlist.
+    pub async fn mcp_tools(&self) -> Vec<McpTool> {
+        let Some(mcp) = &self.mcp else {
+            return Vec::new();
+        };
+        mcp.list_tools()
+            .await
+            .into_iter()
+            .filter(|tool| tool_allowed(&self.tool_allowlist, &tool.name))
+            .collect()
     }
 
     /// Get the working directory path as a string.
@@ -213,6 +213,7 @@ impl OpenCodeAgent {
         &self,
         tool_call_id: &str,
         name: &str,
+        args: &serde_json::Value,
         session_id: &str,
         directory: &str,
         ctx: &AgentContext,
@@ -225,6 +226,7 @@ impl OpenCodeAgent {
         };
         let tool_hub = Arc::clone(tool_hub);
 
+        let args = args.clone();
         let client = self.client.clone();
         let tool_call_id = tool_call_id.to_string();
         let session_id = session_id.to_string();
@@ -261,10 +263,11 @@ impl OpenCodeAgent {
                     });
                 }
             }
-            let rx = tool_hub.register(tool_call_id.clone()).await;
-            let Ok(result) = rx.await else {
-                return;
-            };
+            let rx = tool_hub
+                .register(tool_call_id.clone(), "question", &args)
+                .await;
+            // No result means nobody answered in time; the question is rejected
+            let result = rx.await.ok();
             if let (Some(status), Some(events), Some(mid)) =
                 (&control_status, &events_tx, mission_id)
             {
@@ -292,11 +295,6 @@ impl OpenCodeAgent {
                 }
             }
 
-            let answers = result
-                .get("answers")
-                .cloned()
-                .unwrap_or_else(|| result.clone());
-
             let request_id = match client.list_questions(&directory).await {
                 Ok(list) => list
                     .iter()
@@ -334,13 +332,26 @@ impl OpenCodeAgent {
                 return;
             };
 
-            if let Err(e) = client
-                .reply_question(&directory, &request_id, answers)
-                .await
-            {
+            let outcome = match result {
+                Some(result) => {
+                    let answers = result
+                        .get("answers")
+                        .cloned()
+                        .unwrap_or_else(|| result.clone());
+                    client
+                        .reply_question(&directory, &request_id, answers)
+                        .await
+                        .map_err(|e| format!("Failed to reply to question: {}", e))
+                }
+                None => client
+                    .reject_question(&directory, &request_id)
+                    .await
+                    .map_err(|e| format!("Failed to reject unanswered question: {}", e)),
+            };
+            if let Err(message) = outcome {
                 if let Some(tx) = &events_tx {
                     let _ = tx.send(AgentEvent::Error {
-                        message: format!("Failed to reply to question: {}", e),
+                        message,
                         mission_id,
                         resumable,
                     });
@@ -476,10 +487,11 @@ impl Agent for OpenCodeAgent {
                                     }
                                 }
 
-                                if let OpenCodeEvent::ToolCall { id, name, .. } = &oc_event {
+                                if let OpenCodeEvent::ToolCall { id, name, args } = &oc_event {
                                     self.handle_frontend_tool_call(
                                         id,
                                         name,
+                                        args,
                                         &session.id,
                                         &directory,
                                         ctx,
@@ -538,10 +550,11 @@ impl Agent for OpenCodeAgent {
                                         sse_text_buffer = content.clone();
                                     }
                                 }
-                                if let OpenCodeEvent::ToolCall { id, name, .. } = &oc_event {
+                                if let OpenCodeEvent::ToolCall { id, name, args } = &oc_event {
                                     self.handle_frontend_tool_call(
                                         id,
                                         name,
+                                        args,
                                         &session.id,
                                         &directory,
                                         ctx,
@@ -132,6 +132,10 @@ pub enum TerminalReason {
     InfiniteLoop,
     /// Hit maximum iterations limit
     MaxIterations,
+    /// Mission cost budget was used up
+    BudgetExhausted,
+    /// Mission ran longer than its wall-clock limit
+    TimedOut,
 }
 
 /// Errors that can occur in agent operations.
@@ -5,10 +5,23 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
+static GLOBAL_PROVIDERS: OnceLock<Arc<AIProviderStore>> = OnceLock::new();
+
+/// Make the server's provider store available to built-in tools that call
+/// models directly (e.g. image analysis).
+pub fn set_global(store: Arc<AIProviderStore>) {
+    let _ = GLOBAL_PROVIDERS.set(store);
+}
+
+/// The server's provider store, if one was registered.
+pub fn global() -> Option<Arc<AIProviderStore>> {
+    GLOBAL_PROVIDERS.get().cloned()
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Authentication Methods
 // ─────────────────────────────────────────────────────────────────────────────
@@ -0,0 +1,163 @@
+//! `@agent` mentions in control messages.
+//!
+//! A message starting with `@name` runs with that agent. The mention is
+//! stripped from the prompt and the name is checked against the agents of the
+//! backend the message will run on, so a typo is a 400 listing the valid
+//! agents rather than a turn that silently falls back to the default agent.
+
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use uuid::Uuid;
+
+use super::control::ControlState;
+use super::routes::AppState;
+use crate::backend::AgentInfo;
+
+/// Split a leading `@agent` mention off `content`. Returns the agent name and
+/// the rest of the prompt.
+pub fn parse_mention(content: &str) -> Option<(&str, &str)> {
+    let rest = content.strip_prefix('@')?;
+    let end = rest
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+        .unwrap_or(rest.len());
+    let (name, prompt) = rest.split_at(end);
+    if name.is_empty() || !(prompt.is_empty() || prompt.starts_with(char::is_whitespace)) {
+        return None;
+    }
+    Some((name, prompt.trim_start()))
+}
+
+/// The agent `name` refers to, matched case-insensitively on id or name.
+pub fn find_agent<'a>(agents: &'a [AgentInfo], name: &str) -> Option<&'a AgentInfo> {
+    agents
+        .iter()
+        .find(|agent| agent.id.eq_ignore_ascii_case(name) || agent.name.eq_ignore_ascii_case(name))
+}
+
+/// Check `name` against `agents`, returning the agent's canonical id.
+fn validate(agents: &[AgentInfo], name: &str, backend: &str) -> Result<String, String> {
+    if agents.is_empty() {
+        // Nothing to check against (e.g. OpenCode is unreachable)
+        return Ok(name.to_string());
+    }
+    match find_agent(agents, name) {
+        Some(agent) => Ok(agent.id.clone()),
+        None => {
+            let valid: Vec<&str> = agents.iter().map(|agent| agent.id.as_str()).collect();
+            Err(format!(
+                "Unknown agent '{}' for backend {}. Valid agents: {}",
+                name,
+                backend,
+                valid.join(", ")
+            ))
+        }
+    }
+}
+
+/// Resolve a message's agent: the explicit `agent` if given, otherwise a
+/// leading mention in `content`. Returns the validated agent and the prompt,
+/// with the mention removed.
+pub async fn resolve(
+    state: &Arc<AppState>,
+    control: &ControlState,
+    agent: Option<String>,
+    content: String,
+    mission_id: Option<Uuid>,
+) -> Result<(Option<String>, String), (StatusCode, String)> {
+    let (name, content) = match agent.filter(|a| !a.trim().is_empty()) {
+        Some(agent) => (agent.trim().to_string(), content),
+        None => match parse_mention(&content) {
+            Some((name, prompt)) => (name.to_string(), prompt.to_string()),
+            None => return Ok((None, content)),
+        },
+    };
+    if content.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("content is required after @{}", name),
+        ));
+    }
+
+    let mission_id = match mission_id {
+        Some(id) => Some(id),
+        None => *control.current_mission.read().await,
+    };
+    let mission_backend = match mission_id {
+        Some(id) => control
+            .mission_store
+            .get_mission(id)
+            .await
+            .ok()
+            .flatten()
+            .map(|mission| mission.backend),
+        None => None,
+    };
+    let backend = {
+        let registry = state.backend_registry.read().await;
+        mission_backend
+            .and_then(|id| registry.get(&id))
+            .or_else(|| registry.default_backend())
+    };
+    let Some(backend) = backend else {
+        return Ok((Some(name), content));
+    };
+    let agents = backend
+        .list_agents()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let agent = validate(&agents, &name, backend.id()).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    Ok((Some(agent), content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agents() -> Vec<AgentInfo> {
+        vec![
+            AgentInfo {
+                id: "general-purpose".to_string(),
+                name: "General Purpose".to_string(),
+            },
+            AgentInfo {
+                id: "Explore".to_string(),
+                name: "Codebase Explorer".to_string(),
+            },
+        ]
+    }
+
+    #[test]
+    fn parses_leading_mention() {
+        assert_eq!(
+            parse_mention("@Explore find the config loader"),
+            Some(("Explore", "find the config loader"))
+        );
+        assert_eq!(
+            parse_mention("@general-purpose\nhi"),
+            Some(("general-purpose", "hi"))
+        );
+        assert_eq!(parse_mention("@Explore"), Some(("Explore", "")));
+        assert_eq!(parse_mention("email me@example.com"), None);
+        assert_eq!(parse_mention("@alice@example.com hi"), None);
+        assert_eq!(parse_mention("@ hi"), None);
+    }
+
+    #[test]
+    fn validates_against_backend_agents() {
+        assert_eq!(
+            validate(&agents(), "explore", "claudecode").unwrap(),
+            "Explore"
+        );
+        assert_eq!(
+            validate(&agents(), "General Purpose", "claudecode").unwrap(),
+            "general-purpose"
+        );
+        let err = validate(&agents(), "oracle", "claudecode").unwrap_err();
+        assert_eq!(
+            err,
+            "Unknown agent 'oracle' for backend claudecode. Valid agents: general-purpose, Explore"
+        );
+        assert_eq!(validate(&[], "oracle", "opencode").unwrap(), "oracle");
+    }
+}
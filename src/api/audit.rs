@@ -0,0 +1,50 @@
+//! API endpoints for the tool execution audit log.
+
+use std::sync::Arc;
+
+use axum::{extract::Query, http::StatusCode, response::Json, routing::get, Router};
+
+use crate::tools::audit::{self, ToolAuditEntry, ToolAuditQuery};
+
+use super::routes::AppState;
+
+/// Create the audit API routes.
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new().route(
+        "/tools",
+        get(list_tool_executions).post(record_tool_execution),
+    )
+}
+
+/// GET /api/audit/tools - List recorded tool executions, filtered by
+/// `mission_id`, `workspace_id`, `tool`, `success`, `since` and `limit`.
+async fn list_tool_executions(
+    Query(query): Query<ToolAuditQuery>,
+) -> Result<Json<Vec<ToolAuditEntry>>, (StatusCode, String)> {
+    let Some(log) = audit::global() else {
+        return Ok(Json(Vec::new()));
+    };
+    log.query(&query).await.map(Json).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to read audit log: {}", e),
+        )
+    })
+}
+
+/// POST /api/audit/tools - Record a tool execution reported by the
+/// workspace MCP server.
+async fn record_tool_execution(
+    Json(entry): Json<ToolAuditEntry>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let Some(log) = audit::global() else {
+        return Ok(StatusCode::NO_CONTENT);
+    };
+    log.append(&entry).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to write audit log: {}", e),
+        )
+    })?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -40,6 +40,9 @@ struct Claims {
 pub struct AuthUser {
     pub id: String,
     pub username: String,
+    /// Named control session the request targets (see `control_sessions`);
+    /// the user's default session if unset
+    pub control_session: Option<String>,
 }
 
 fn constant_time_eq(a: &str, b: &str) -> bool {
@@ -104,6 +107,35 @@ pub fn verify_token_for_config(token: &str, config: &Config) -> bool {
     }
 }
 
+/// The user a websocket connection acts as, given the JWT it offered.
+/// Matches what [`require_auth`] would insert for the same token.
+pub fn user_for_token(token: Option<&str>, config: &Config) -> Option<AuthUser> {
+    if config.dev_mode {
+        return Some(AuthUser {
+            id: "dev".to_string(),
+            username: "dev".to_string(),
+            control_session: None,
+        });
+    }
+    let mode = config.auth.auth_mode(config.dev_mode);
+    if mode == AuthMode::Disabled {
+        return Some(AuthUser {
+            id: "default".to_string(),
+            username: "default".to_string(),
+            control_session: None,
+        });
+    }
+    let claims = verify_jwt(token?, config.auth.jwt_secret.as_deref()?).ok()?;
+    match mode {
+        AuthMode::MultiUser => user_for_claims(&claims, &config.auth.users),
+        _ => Some(AuthUser {
+            id: claims.sub,
+            username: claims.usr,
+            control_session: None,
+        }),
+    }
+}
+
 pub async fn login(
     State(state): State<std::sync::Arc<AppState>>,
     Json(req): Json<LoginRequest>,
@@ -149,6 +181,7 @@ pub async fn login(
             AuthUser {
                 id: effective_id,
                 username: account.username.clone(),
+                control_session: None,
             }
         }
         AuthMode::SingleTenant | AuthMode::Disabled => {
@@ -167,6 +200,7 @@ pub async fn login(
             AuthUser {
                 id: "default".to_string(),
                 username: "default".to_string(),
+                control_session: None,
             }
         }
     };
@@ -194,6 +228,7 @@ pub async fn require_auth(
         req.extensions_mut().insert(AuthUser {
             id: "dev".to_string(),
             username: "dev".to_string(),
+            control_session: None,
         });
         return next.run(req).await;
     }
@@ -237,10 +272,12 @@ pub async fn require_auth(
                 AuthMode::SingleTenant => AuthUser {
                     id: claims.sub,
                     username: claims.usr,
+                    control_session: None,
                 },
                 AuthMode::Disabled => AuthUser {
                     id: "default".to_string(),
                     username: "default".to_string(),
+                    control_session: None,
                 },
             };
             req.extensions_mut().insert(user);
@@ -266,5 +303,6 @@ fn user_for_claims(claims: &Claims, users: &[UserAccount]) -> Option<AuthUser> {
         .map(|u| AuthUser {
             id: effective_user_id(u),
             username: u.username.clone(),
+            control_session: None,
         })
 }
@@ -3,13 +3,14 @@
 use std::sync::Arc;
 
 use axum::{
-    extract::{Extension, Path, State},
+    extract::{Extension, Path, Query, State},
     http::StatusCode,
     Json,
 };
 use serde::{Deserialize, Serialize};
 
 use crate::backend::registry::BackendInfo;
+use crate::model_catalog::ModelCatalog;
 
 use super::auth::AuthUser;
 use super::routes::AppState;
@@ -182,6 +183,14 @@ pub async fn get_backend_config(
             // Clear invalid/redacted values so frontend shows empty field
             obj.remove("api_key");
         }
+        let login_configured = match state.secrets.as_ref() {
+            Some(store) => crate::backend::amp::auth::has_login(store).await,
+            None => false,
+        };
+        obj.insert(
+            "login_configured".to_string(),
+            serde_json::Value::Bool(login_configured),
+        );
         settings = serde_json::Value::Object(obj);
     }
 
@@ -228,6 +237,68 @@ pub async fn get_backend_config(
     }))
 }
 
+/// Configured CLI path override for a backend, if any.
+async fn configured_cli_path(state: &AppState, id: &str) -> Option<String> {
+    state
+        .backend_configs
+        .get(id)
+        .await
+        .and_then(|entry| {
+            entry
+                .settings
+                .get("cli_path")
+                .and_then(|v| v.as_str())
+                .map(|s| s.trim().to_string())
+        })
+        .filter(|s| !s.is_empty())
+}
+
+/// Get the model catalog for a backend (refreshed automatically when stale)
+pub async fn get_backend_models(
+    State(state): State<Arc<AppState>>,
+    Extension(_user): Extension<AuthUser>,
+    Path(id): Path<String>,
+) -> Result<Json<ModelCatalog>, (StatusCode, String)> {
+    if state.backend_registry.read().await.get(&id).is_none() {
+        return Err((StatusCode::NOT_FOUND, format!("Backend {} not found", id)));
+    }
+    let cli_path = configured_cli_path(&state, &id).await;
+    state
+        .model_catalogs
+        .get_or_refresh(&id, cli_path.as_deref())
+        .await
+        .map(Json)
+        .map_err(|e| {
+            (
+                StatusCode::BAD_GATEWAY,
+                format!("Failed to list models: {}", e),
+            )
+        })
+}
+
+/// Re-list models from the backend CLI and update the cached catalog
+pub async fn refresh_backend_models(
+    State(state): State<Arc<AppState>>,
+    Extension(_user): Extension<AuthUser>,
+    Path(id): Path<String>,
+) -> Result<Json<ModelCatalog>, (StatusCode, String)> {
+    if state.backend_registry.read().await.get(&id).is_none() {
+        return Err((StatusCode::NOT_FOUND, format!("Backend {} not found", id)));
+    }
+    let cli_path = configured_cli_path(&state, &id).await;
+    state
+        .model_catalogs
+        .refresh(&id, cli_path.as_deref())
+        .await
+        .map(Json)
+        .map_err(|e| {
+            (
+                StatusCode::BAD_GATEWAY,
+                format!("Failed to refresh models: {}", e),
+            )
+        })
+}
+
 /// Request to update backend configuration
 #[derive(Debug, Clone, Deserialize)]
 pub struct UpdateBackendConfigRequest {
@@ -267,6 +338,11 @@ pub async fn update_backend_config(
                 .and_then(|v| v.as_str())
                 .map(|s| s.trim().to_string())
                 .filter(|s| !s.is_empty());
+            let default_model = settings
+                .get("default_model")
+                .and_then(|v| v.as_str())
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty());
             let permissive = settings
                 .get("permissive")
                 .and_then(|v| v.as_bool())
@@ -274,6 +350,7 @@ pub async fn update_backend_config(
             serde_json::json!({
                 "base_url": base_url,
                 "default_agent": default_agent,
+                "default_model": default_model,
                 "permissive": permissive,
             })
         }
@@ -347,6 +424,16 @@ pub async fn update_backend_config(
                 .map(|s| s.trim().to_string())
                 .filter(|s| !s.is_empty())
                 .unwrap_or_else(|| "smart".to_string());
+            let default_agent = settings
+                .get("default_agent")
+                .and_then(|v| v.as_str())
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty());
+            let default_model = settings
+                .get("default_model")
+                .and_then(|v| v.as_str())
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty());
             let permissive = settings
                 .get("permissive")
                 .and_then(|v| v.as_bool())
@@ -356,6 +443,8 @@ pub async fn update_backend_config(
                 "api_key": api_key,
                 "cli_path": cli_path,
                 "default_mode": default_mode,
+                "default_agent": default_agent,
+                "default_model": default_model,
                 "permissive": permissive,
             })
         }
@@ -382,3 +471,79 @@ pub async fn update_backend_config(
         "message": "Backend configuration updated."
     })))
 }
+
+#[derive(Debug, Deserialize)]
+pub struct WarmupQuery {
+    /// Workspace to warm up (defaults to the host workspace)
+    pub workspace_id: Option<uuid::Uuid>,
+}
+
+/// Pre-cache the oh-my-opencode package inside a workspace so the first
+/// mission there doesn't pay the bunx/npx cold start.
+pub async fn warmup_opencode(
+    State(state): State<Arc<AppState>>,
+    Extension(_user): Extension<AuthUser>,
+    Query(query): Query<WarmupQuery>,
+) -> Result<Json<crate::api::mission_runner::OpencodeWarmup>, (StatusCode, String)> {
+    if let Some(id) = query.workspace_id {
+        if state.workspaces.get(id).await.is_none() {
+            return Err((StatusCode::NOT_FOUND, format!("Workspace {} not found", id)));
+        }
+    }
+    let workspace =
+        crate::workspace::resolve_workspace(&state.workspaces, &state.config, query.workspace_id)
+            .await;
+
+    crate::api::mission_runner::warm_up_opencode(&workspace, &state.config.working_dir)
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::BAD_GATEWAY, e))
+}
+
+/// Start an Amp device login.
+///
+/// Returns the verification URL and user code to show to the user, then polls
+/// for approval in the background and persists the token in the secrets store.
+pub async fn amp_login(
+    State(state): State<Arc<AppState>>,
+    Extension(_user): Extension<AuthUser>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    use crate::backend::amp::auth;
+
+    let store = state.secrets.clone().ok_or_else(|| {
+        (
+            StatusCode::BAD_REQUEST,
+            "Secrets store not available".to_string(),
+        )
+    })?;
+    if !store.can_decrypt().await {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Secrets are locked. Unlock them before logging in to Amp.".to_string(),
+        ));
+    }
+
+    let amp_url = auth::amp_service_url();
+    let device = auth::start_device_flow(&amp_url)
+        .await
+        .map_err(|e| (StatusCode::BAD_GATEWAY, e.to_string()))?;
+
+    let pending = device.clone();
+    tokio::spawn(async move {
+        match auth::poll_device_token(&amp_url, &pending).await {
+            Ok(token) => match auth::store_token(&store, &token).await {
+                Ok(()) => tracing::info!("Amp login completed"),
+                Err(e) => tracing::warn!("Failed to persist Amp login token: {}", e),
+            },
+            Err(e) => tracing::warn!("Amp login did not complete: {}", e),
+        }
+    });
+
+    Ok(Json(serde_json::json!({
+        "ok": true,
+        "verification_uri": device.verification_uri,
+        "verification_uri_complete": device.verification_uri_complete,
+        "user_code": device.user_code,
+        "expires_in": device.expires_in,
+    })))
+}
@@ -7,7 +7,7 @@
 //! - supports frontend/interactive tools by accepting tool results
 //! - supports persistent missions (goal-oriented sessions)
 
-use std::collections::{HashMap, VecDeque};
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::convert::Infallible;
 use std::sync::Arc;
 
@@ -25,19 +25,44 @@ use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
 use crate::agents::{AgentContext, AgentRef, TerminalReason};
-use crate::config::Config;
+use crate::config::{Config, FrontendToolTimeouts, StallAction};
 use crate::mcp::McpRegistry;
 use crate::secrets::SecretsStore;
 use crate::workspace;
 
+use super::agent_mention;
 use super::auth::AuthUser;
+use super::control_history::{self, ControlHistoryStore};
+use super::control_sessions::ControlSessionRegistry;
 use super::desktop;
+use super::event_journal::{self, EventJournal};
 use super::library::SharedLibrary;
+use super::mission_artifacts::{
+    resolve_artifact_path, ArtifactSource, MissionArtifact, MissionArtifactStore,
+};
+use super::mission_budget::{self, check_budget, format_cents, is_exhausted, BudgetCheck};
+use super::mission_checkpoints::{CheckpointSummary, MissionCheckpoint, MissionCheckpointStore};
+use super::mission_completion;
+use super::mission_filter::{validate_tags, ListMissionsQuery, MissionFilter};
+use super::mission_history::{self, ConversationEntry, SharedHistorySummary};
+use super::mission_retention::{self, RetentionQuery, RetentionReport};
+use super::mission_retry::RetryPolicy;
+use super::mission_runner::{insert_by_priority, reorder_queue as reorder_queued, MissionRunner};
+use super::mission_snapshots::MissionSnapshotStore;
+use super::mission_stall::{StallMonitor, StallThresholds, NUDGE_MESSAGE, RESTART_MESSAGE};
 use super::mission_store::{
     self, create_mission_store, now_string, Mission, MissionHistoryEntry, MissionStore,
     MissionStoreType, StoredEvent,
 };
+use super::mission_timeout::{effective_timeout, format_limit, MissionRun};
+use super::mission_transcript::{requested_deliverables, Transcript, TranscriptFormat};
+use super::mission_verification::{
+    self, DeliverableCheck, MissionHealthReport, VerificationReport,
+};
+use super::mission_verifier::{self, MissionVerifier, VerifierVerdict};
 use super::routes::AppState;
+use super::shutdown;
+use super::workspace_slots;
 
 /// Returns a safe index to truncate a string at, ensuring we don't cut UTF-8 characters.
 pub(super) fn safe_truncate_index(s: &str, max: usize) -> usize {
@@ -97,6 +122,15 @@ fn activity_label_from_tool_call(tool_name: &str, args: &serde_json::Value) -> S
             let path = extract_str(args, &["file_path", "path"]).unwrap_or("…");
             format!("Writing: {}", basename(path))
         }
+        "move_file" | "copy_file" => {
+            let source = extract_str(args, &["source"]).unwrap_or("…");
+            let verb = if tool_name == "move_file" {
+                "Moving"
+            } else {
+                "Copying"
+            };
+            format!("{}: {}", verb, basename(source))
+        }
         "Grep" | "grep" | "search" => {
             let pattern = extract_str(args, &["pattern"]).unwrap_or("…");
             format!("Searching: {}", truncate(pattern, 40))
@@ -166,23 +200,6 @@ fn extract_title_from_assistant(content: &str) -> Option<String> {
     }
 }
 
-/// Build a simple history context from conversation history.
-fn build_history_context(history: &[(String, String)], max_chars: usize) -> String {
-    let mut result = String::new();
-    let mut total_chars = 0;
-
-    for (role, content) in history.iter().rev() {
-        let entry = format!("{}: {}\n\n", role.to_uppercase(), content);
-        if total_chars + entry.len() > max_chars && !result.is_empty() {
-            break;
-        }
-        result = format!("{}{}", entry, result);
-        total_chars += entry.len();
-    }
-
-    result
-}
-
 async fn mission_has_active_automation(
     mission_store: &Arc<dyn MissionStore>,
     mission_id: Uuid,
@@ -200,6 +217,426 @@ async fn mission_has_active_automation(
     }
 }
 
+/// Add a finished turn's cost to the mission's spend and check its budget,
+/// emitting a warning event when a threshold is crossed. Returns true if the
+/// budget is used up.
+async fn charge_mission_cost(
+    mission_store: &Arc<dyn MissionStore>,
+    events_tx: &broadcast::Sender<AgentEvent>,
+    mission_id: Uuid,
+    cost_cents: u64,
+) -> bool {
+    let spent_cents = match mission_store.add_mission_cost(mission_id, cost_cents).await {
+        Ok(spent) => spent,
+        Err(err) => {
+            tracing::warn!("Failed to record cost for mission {}: {}", mission_id, err);
+            return false;
+        }
+    };
+    let Ok(Some(mission)) = mission_store.get_mission(mission_id).await else {
+        return false;
+    };
+    mission_budget::track(mission_id, mission.budget_cents, spent_cents);
+    let Some(budget_cents) = mission.budget_cents else {
+        return false;
+    };
+    match check_budget(
+        budget_cents,
+        spent_cents.saturating_sub(cost_cents),
+        spent_cents,
+    ) {
+        BudgetCheck::WithinBudget => false,
+        BudgetCheck::Warning(percent) => {
+            tracing::info!(
+                "Mission {} used {}% of its budget ({} of {})",
+                mission_id,
+                percent,
+                format_cents(spent_cents),
+                format_cents(budget_cents)
+            );
+            let _ = events_tx.send(AgentEvent::BudgetWarning {
+                percent,
+                spent_cents,
+                budget_cents,
+                mission_id: Some(mission_id),
+            });
+            false
+        }
+        BudgetCheck::Exhausted => {
+            tracing::warn!(
+                "Mission {} exhausted its budget ({} of {})",
+                mission_id,
+                format_cents(spent_cents),
+                format_cents(budget_cents)
+            );
+            true
+        }
+    }
+}
+
+/// Checkpoint a mission in the background after one of its turns.
+fn spawn_mission_checkpoint(
+    checkpoints: &Arc<MissionCheckpointStore>,
+    mission_store: &Arc<dyn MissionStore>,
+    workspaces: &workspace::SharedWorkspaceStore,
+    config: &Config,
+    mission_id: Uuid,
+) {
+    let checkpoints = Arc::clone(checkpoints);
+    let mission_store = Arc::clone(mission_store);
+    let workspaces = Arc::clone(workspaces);
+    let config = config.clone();
+    tokio::spawn(async move {
+        checkpoint_mission(
+            &checkpoints,
+            &mission_store,
+            &workspaces,
+            &config,
+            mission_id,
+        )
+        .await;
+    });
+}
+
+/// Checkpoint a mission as it is now.
+async fn checkpoint_mission(
+    checkpoints: &MissionCheckpointStore,
+    mission_store: &Arc<dyn MissionStore>,
+    workspaces: &workspace::SharedWorkspaceStore,
+    config: &Config,
+    mission_id: Uuid,
+) {
+    let Ok(Some(mission)) = mission_store.get_mission(mission_id).await else {
+        return;
+    };
+    let workspace_root =
+        workspace::resolve_workspace_root(workspaces, config, Some(mission.workspace_id)).await;
+    let checkpoint = MissionCheckpoint::capture(&mission, &workspace_root).await;
+    if let Err(e) = checkpoints.save(&checkpoint) {
+        tracing::warn!("Failed to checkpoint mission {}: {}", mission_id, e);
+    }
+}
+
+/// Register mission artifacts in the background: the file named in a
+/// `register_artifact` call or, with `requested` unset, the deliverables from
+/// the mission's first message that now exist.
+fn spawn_artifact_registration(
+    artifacts: &Arc<MissionArtifactStore>,
+    mission_store: &Arc<dyn MissionStore>,
+    workspaces: &workspace::SharedWorkspaceStore,
+    config: &Config,
+    mission_id: Uuid,
+    requested: Option<(String, Option<String>)>,
+) {
+    let artifacts = Arc::clone(artifacts);
+    let mission_store = Arc::clone(mission_store);
+    let workspaces = Arc::clone(workspaces);
+    let config = config.clone();
+    tokio::spawn(async move {
+        let Ok(Some(mission)) = mission_store.get_mission(mission_id).await else {
+            return;
+        };
+        let ws =
+            workspace::resolve_workspace(&workspaces, &config, Some(mission.workspace_id)).await;
+        let candidates: Vec<(String, Option<String>, ArtifactSource)> = match requested {
+            Some((path, description)) => vec![(path, description, ArtifactSource::Tool)],
+            None => mission
+                .history
+                .iter()
+                .find(|entry| entry.role == "user")
+                .map(|entry| crate::task::extract_deliverables(&entry.content))
+                .unwrap_or_default()
+                .deliverables
+                .iter()
+                .filter(|d| !matches!(d, crate::task::Deliverable::Directory { .. }))
+                .filter_map(|d| d.path())
+                .map(|p| (p.display().to_string(), None, ArtifactSource::Deliverable))
+                .collect(),
+        };
+        for (path, description, source) in candidates {
+            let host_path = resolve_artifact_path(&ws, mission_id, &path);
+            if source == ArtifactSource::Deliverable && !host_path.is_file() {
+                continue;
+            }
+            let artifacts = Arc::clone(&artifacts);
+            let result = tokio::task::spawn_blocking(move || {
+                artifacts.register(mission_id, &host_path, description, source)
+            })
+            .await;
+            if let Ok(Err(e)) = result {
+                tracing::warn!(
+                    "Failed to register artifact {} for mission {}: {}",
+                    path,
+                    mission_id,
+                    e
+                );
+            }
+        }
+    });
+}
+
+/// Run a mission's deliverable checks and store the report.
+async fn verify_deliverables(
+    mission_store: &Arc<dyn MissionStore>,
+    workspaces: &workspace::SharedWorkspaceStore,
+    config: &Config,
+    events_tx: &broadcast::Sender<AgentEvent>,
+    mission_id: Uuid,
+) -> Result<Option<VerificationReport>, String> {
+    let Some(mission) = mission_store.get_mission(mission_id).await? else {
+        return Err(format!("Mission {} not found", mission_id));
+    };
+    let ws = workspace::resolve_workspace(workspaces, config, Some(mission.workspace_id)).await;
+    let Some(report) = mission_verification::verify(&mission, &ws).await else {
+        return Ok(None);
+    };
+    mission_store
+        .update_mission_verification(mission_id, &report)
+        .await?;
+    if !report.passed {
+        tracing::warn!(
+            "Mission {} completed but {} of {} deliverable checks failed",
+            mission_id,
+            report.failed().count(),
+            report.results.len()
+        );
+    }
+    let _ = events_tx.send(AgentEvent::DeliverablesVerified {
+        mission_id,
+        report: report.clone(),
+    });
+    Ok(Some(report))
+}
+
+/// Verify a completed mission's deliverables in the background.
+fn spawn_deliverable_verification(
+    mission_store: &Arc<dyn MissionStore>,
+    workspaces: &workspace::SharedWorkspaceStore,
+    config: &Config,
+    events_tx: &broadcast::Sender<AgentEvent>,
+    mission_id: Uuid,
+) {
+    let mission_store = Arc::clone(mission_store);
+    let workspaces = Arc::clone(workspaces);
+    let config = config.clone();
+    let events_tx = events_tx.clone();
+    tokio::spawn(async move {
+        if let Err(e) =
+            verify_deliverables(&mission_store, &workspaces, &config, &events_tx, mission_id).await
+        {
+            tracing::warn!(
+                "Failed to verify deliverables of mission {}: {}",
+                mission_id,
+                e
+            );
+        }
+    });
+}
+
+/// The workspace's cap on missions running at once.
+async fn workspace_limit(
+    workspaces: &workspace::SharedWorkspaceStore,
+    workspace_id: Uuid,
+) -> Option<u32> {
+    workspaces
+        .get(workspace_id)
+        .await
+        .and_then(|workspace| workspace.max_concurrent_missions)
+}
+
+/// Workspace slot of the main control session's turn. Like parallel runners,
+/// the main session takes a slot in its mission's workspace before a turn
+/// and leaves the message queued while the workspace is full.
+#[derive(Debug, Default)]
+struct MainWorkspaceSlot {
+    /// Workspace and mission holding the slot of the running turn
+    held: Option<(Uuid, Uuid)>,
+    /// Workspace and mission of the next queued message, while it waits
+    waiting: Option<(Uuid, Uuid)>,
+}
+
+impl MainWorkspaceSlot {
+    /// Take a slot for a turn of `mission_id`. Returns false, and marks the
+    /// mission as waiting, when its workspace is full.
+    async fn acquire(
+        &mut self,
+        mission_store: &Arc<dyn MissionStore>,
+        workspaces: &workspace::SharedWorkspaceStore,
+        mission_id: Option<Uuid>,
+    ) -> bool {
+        self.release();
+        let Some(mission_id) = mission_id else {
+            self.waiting = None;
+            return true;
+        };
+        let Ok(Some(mission)) = mission_store.get_mission(mission_id).await else {
+            self.waiting = None;
+            return true;
+        };
+        let workspace_id = mission.workspace_id;
+        let limit = workspace_limit(workspaces, workspace_id).await;
+        if !workspace_slots::try_acquire(workspace_id, mission_id, limit) {
+            if self.waiting.is_none() {
+                tracing::info!(
+                    "Mission {} waits for workspace {} ({} missions running)",
+                    mission_id,
+                    workspace_id,
+                    workspace_slots::running_in(workspace_id)
+                );
+            }
+            self.waiting = Some((workspace_id, mission_id));
+            return false;
+        }
+        self.waiting = None;
+        self.held = Some((workspace_id, mission_id));
+        true
+    }
+
+    /// Give back the slot of the finished turn.
+    fn release(&mut self) {
+        if let Some((workspace_id, mission_id)) = self.held.take() {
+            workspace_slots::release(workspace_id, mission_id);
+        }
+    }
+
+    /// Mission whose queued message waits for a slot.
+    fn waiting_mission(&self) -> Option<Uuid> {
+        self.waiting.map(|(_, mission_id)| mission_id)
+    }
+
+    /// Whether the waiting mission's workspace has a free slot now.
+    async fn ready(&self, workspaces: &workspace::SharedWorkspaceStore) -> bool {
+        let Some((workspace_id, _)) = self.waiting else {
+            return false;
+        };
+        !workspace_limit(workspaces, workspace_id)
+            .await
+            .is_some_and(|limit| workspace_slots::running_in(workspace_id) >= limit as usize)
+    }
+}
+
+/// Have a mission's verifier review a completion claim in the background:
+/// run the deliverable checks, then complete the mission or send the agent
+/// a corrective follow-up (once per mission).
+fn spawn_verifier_review(
+    mission_store: &Arc<dyn MissionStore>,
+    workspaces: &workspace::SharedWorkspaceStore,
+    config: &Config,
+    events_tx: &broadcast::Sender<AgentEvent>,
+    cmd_tx: &mpsc::WeakSender<ControlCommand>,
+    mission_id: Uuid,
+    summary: Option<String>,
+) {
+    let mission_store = Arc::clone(mission_store);
+    let workspaces = Arc::clone(workspaces);
+    let config = config.clone();
+    let events_tx = events_tx.clone();
+    let cmd_tx = cmd_tx.clone();
+    tokio::spawn(async move {
+        let report =
+            match verify_deliverables(&mission_store, &workspaces, &config, &events_tx, mission_id)
+                .await
+            {
+                Ok(report) => report,
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to verify deliverables of mission {}: {}",
+                        mission_id,
+                        e
+                    );
+                    None
+                }
+            };
+        let mission = match mission_store.get_mission(mission_id).await {
+            Ok(Some(mission)) => mission,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::warn!("Failed to load mission {} for review: {}", mission_id, e);
+                return;
+            }
+        };
+        // Changed by the user in the meantime
+        if !matches!(
+            mission.status,
+            MissionStatus::Pending | MissionStatus::Active | MissionStatus::Interrupted
+        ) {
+            return;
+        }
+
+        let verifier = mission.verifier.clone().unwrap_or_default();
+        let verdict = match mission_verifier::review(&mission, &verifier, report.as_ref()).await {
+            Ok(verdict) => Some(verdict),
+            Err(e) => {
+                // An unavailable verifier doesn't hold the mission back
+                tracing::warn!(
+                    "Verifier of mission {} failed, completing it anyway: {}",
+                    mission_id,
+                    e
+                );
+                None
+            }
+        };
+        if let Some(verdict) = verdict.clone() {
+            let follow_up = !verdict.passed && mission_verifier::start_follow_up(mission_id);
+            let content = mission_verifier::corrective_message(&verdict.feedback);
+            let _ = events_tx.send(AgentEvent::VerifierReviewed {
+                mission_id,
+                verdict,
+                follow_up,
+            });
+            if follow_up {
+                let (respond, _) = oneshot::channel();
+                let sent = match cmd_tx.upgrade() {
+                    Some(tx) => tx
+                        .send(ControlCommand::UserMessage {
+                            id: Uuid::new_v4(),
+                            content,
+                            agent: None,
+                            target_mission_id: Some(mission_id),
+                            priority: None,
+                            respond,
+                        })
+                        .await
+                        .is_ok(),
+                    None => false,
+                };
+                if sent {
+                    tracing::info!(
+                        "Verifier rejected the completion of mission {}; follow-up sent",
+                        mission_id
+                    );
+                    return;
+                }
+            }
+        }
+
+        mission_verifier::finish(mission_id);
+        let summary = match verdict {
+            Some(verdict) if !verdict.passed => Some(format!(
+                "Completed with unresolved verifier feedback: {}",
+                verdict.feedback
+            )),
+            _ => summary,
+        };
+        if let Err(e) = mission_store
+            .update_mission_status_with_reason(
+                mission_id,
+                MissionStatus::Completed,
+                Some("completed"),
+            )
+            .await
+        {
+            tracing::warn!("Failed to complete mission {}: {}", mission_id, e);
+            return;
+        }
+        let _ = events_tx.send(AgentEvent::MissionStatusChanged {
+            mission_id,
+            status: MissionStatus::Completed,
+            summary,
+        });
+    });
+}
+
 pub(crate) async fn resolve_claudecode_default_model(
     library: &SharedLibrary,
     config_profile: Option<&str>,
@@ -281,13 +718,17 @@ async fn close_mission_desktop_sessions(
 #[derive(Debug, Clone, Deserialize)]
 pub struct ControlMessageRequest {
     pub content: String,
-    /// Optional agent override for this specific message (e.g., from @agent mention)
+    /// Optional agent override for this specific message. Without it, a
+    /// leading `@agent` mention in `content` is used (and stripped).
     #[serde(default)]
     pub agent: Option<String>,
     /// Target mission ID. If provided and differs from the currently running mission,
     /// the backend will automatically start this mission in parallel (if capacity allows).
     #[serde(default)]
     pub mission_id: Option<Uuid>,
+    /// Queue priority (defaults to the target mission's priority)
+    #[serde(default)]
+    pub priority: Option<MissionPriority>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -304,6 +745,7 @@ pub struct QueuedMessage {
     pub agent: Option<String>,
     /// Which mission this queued message belongs to
     pub mission_id: Option<Uuid>,
+    pub priority: MissionPriority,
 }
 
 /// Tool result posted by the frontend for an interactive tool call.
@@ -314,6 +756,17 @@ pub struct ControlToolResultRequest {
     pub result: serde_json::Value,
 }
 
+/// Approval request posted by the workspace MCP server for a tool whose
+/// policy action is `ask`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ControlToolApprovalRequest {
+    pub tool: String,
+    #[serde(default)]
+    pub args: serde_json::Value,
+    #[serde(default)]
+    pub mission_id: Option<Uuid>,
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum ControlRunState {
@@ -328,6 +781,29 @@ impl Default for ControlRunState {
     }
 }
 
+/// Severity of a diagnostic line emitted by a backend CLI.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticLevel {
+    Info,
+    Warn,
+    Error,
+}
+
+impl DiagnosticLevel {
+    /// Best-effort severity for a raw CLI stderr line.
+    pub fn classify(line: &str) -> Self {
+        let lower = line.to_lowercase();
+        if lower.contains("error") || lower.contains("fatal") || lower.contains("panic") {
+            DiagnosticLevel::Error
+        } else if lower.contains("warn") {
+            DiagnosticLevel::Warn
+        } else {
+            DiagnosticLevel::Info
+        }
+    }
+}
+
 /// A file shared by the agent (images render inline, other files show as download links).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SharedFile {
@@ -452,6 +928,17 @@ pub enum AgentEvent {
         #[serde(skip_serializing_if = "Option::is_none")]
         mission_id: Option<Uuid>,
     },
+    /// Backend CLI diagnostics (stderr and other non-model output). Kept
+    /// separate from `Thinking`, which is reserved for model reasoning.
+    Diagnostic {
+        level: DiagnosticLevel,
+        /// Producer of the line, e.g. "opencode", "amp", "codex"
+        source: String,
+        message: String,
+        /// Mission this diagnostic belongs to (for parallel execution)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        mission_id: Option<Uuid>,
+    },
     /// Text content delta (streaming assistant response)
     TextDelta {
         /// Accumulated text content so far
@@ -542,6 +1029,63 @@ pub enum AgentEvent {
         #[serde(skip_serializing_if = "Option::is_none")]
         mission_id: Option<Uuid>,
     },
+    /// Agent-reported batch progress (from the ui_progress tool)
+    MissionProgress {
+        /// What is being processed (e.g., "Resizing images")
+        label: String,
+        /// Number of items processed so far
+        current: u64,
+        /// Total number of items
+        total: u64,
+        /// Mission this progress belongs to
+        #[serde(skip_serializing_if = "Option::is_none")]
+        mission_id: Option<Uuid>,
+    },
+    /// A mission's spend crossed a warning threshold of its cost budget
+    BudgetWarning {
+        /// Threshold crossed (50 or 80)
+        percent: u8,
+        spent_cents: u64,
+        budget_cents: u64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        mission_id: Option<Uuid>,
+    },
+    /// A recovery action was taken for a stalled mission
+    StallRecovery {
+        action: StallAction,
+        /// Escalation step (1 = first action of this stall)
+        step: u32,
+        seconds_since_activity: u64,
+        /// What was attempted
+        message: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        mission_id: Option<Uuid>,
+    },
+    /// A mission was paused or resumed
+    MissionPaused { mission_id: Uuid, paused: bool },
+    /// A completed mission's deliverable checks were run
+    DeliverablesVerified {
+        mission_id: Uuid,
+        report: VerificationReport,
+    },
+    /// A mission's verifier reviewed a completion claim
+    VerifierReviewed {
+        mission_id: Uuid,
+        verdict: VerifierVerdict,
+        /// Whether a corrective follow-up was sent to the agent
+        follow_up: bool,
+    },
+    /// The mission's backend processes are still working (CPU time or reads
+    /// advanced since the last sample) even if the agent is silent
+    Heartbeat {
+        mission_id: Uuid,
+        /// Live processes in the backend's process tree
+        processes: usize,
+        /// CPU time used by the process tree so far
+        cpu_ms: u64,
+        /// Bytes read by the process tree so far
+        bytes_read: u64,
+    },
 }
 
 /// A node in the agent tree (for visualization)
@@ -612,6 +1156,7 @@ impl AgentEvent {
             AgentEvent::UserMessage { .. } => "user_message",
             AgentEvent::AssistantMessage { .. } => "assistant_message",
             AgentEvent::Thinking { .. } => "thinking",
+            AgentEvent::Diagnostic { .. } => "diagnostic",
             AgentEvent::TextDelta { .. } => "text_delta",
             AgentEvent::ToolCall { .. } => "tool_call",
             AgentEvent::ToolResult { .. } => "tool_result",
@@ -622,6 +1167,13 @@ impl AgentEvent {
             AgentEvent::Progress { .. } => "progress",
             AgentEvent::SessionIdUpdate { .. } => "session_id_update",
             AgentEvent::MissionActivity { .. } => "mission_activity",
+            AgentEvent::MissionProgress { .. } => "mission_progress",
+            AgentEvent::BudgetWarning { .. } => "budget_warning",
+            AgentEvent::StallRecovery { .. } => "stall_recovery",
+            AgentEvent::MissionPaused { .. } => "mission_paused",
+            AgentEvent::DeliverablesVerified { .. } => "deliverables_verified",
+            AgentEvent::VerifierReviewed { .. } => "verifier_reviewed",
+            AgentEvent::Heartbeat { .. } => "heartbeat",
         }
     }
 
@@ -631,6 +1183,7 @@ impl AgentEvent {
             AgentEvent::UserMessage { mission_id, .. } => *mission_id,
             AgentEvent::AssistantMessage { mission_id, .. } => *mission_id,
             AgentEvent::Thinking { mission_id, .. } => *mission_id,
+            AgentEvent::Diagnostic { mission_id, .. } => *mission_id,
             AgentEvent::TextDelta { mission_id, .. } => *mission_id,
             AgentEvent::ToolCall { mission_id, .. } => *mission_id,
             AgentEvent::ToolResult { mission_id, .. } => *mission_id,
@@ -641,6 +1194,13 @@ impl AgentEvent {
             AgentEvent::Progress { mission_id, .. } => *mission_id,
             AgentEvent::SessionIdUpdate { mission_id, .. } => Some(*mission_id),
             AgentEvent::MissionActivity { mission_id, .. } => *mission_id,
+            AgentEvent::MissionProgress { mission_id, .. } => *mission_id,
+            AgentEvent::BudgetWarning { mission_id, .. } => *mission_id,
+            AgentEvent::StallRecovery { mission_id, .. } => *mission_id,
+            AgentEvent::MissionPaused { mission_id, .. } => Some(*mission_id),
+            AgentEvent::DeliverablesVerified { mission_id, .. } => Some(*mission_id),
+            AgentEvent::VerifierReviewed { mission_id, .. } => Some(*mission_id),
+            AgentEvent::Heartbeat { mission_id, .. } => Some(*mission_id),
         }
     }
 }
@@ -655,6 +1215,8 @@ pub enum ControlCommand {
         agent: Option<String>,
         /// Target mission ID - if provided and differs from running mission, start in parallel
         target_mission_id: Option<Uuid>,
+        /// Queue priority (None = the target mission's priority)
+        priority: Option<MissionPriority>,
         /// Respond with whether the message was queued (true = waiting to be processed)
         respond: oneshot::Sender<bool>,
     },
@@ -700,6 +1262,14 @@ pub enum ControlCommand {
         mission_id: Uuid,
         respond: oneshot::Sender<Result<(), String>>,
     },
+    /// Send a message to a mission's running turn
+    SteerMission {
+        mission_id: Uuid,
+        content: String,
+        /// Responds with whether the message reached the turn in flight
+        /// (`false`: the turn was interrupted and the message runs next)
+        respond: oneshot::Sender<Result<bool, String>>,
+    },
     /// List currently running missions
     ListRunning {
         respond: oneshot::Sender<Vec<super::mission_runner::RunningMissionInfo>>,
@@ -711,12 +1281,25 @@ pub enum ControlCommand {
         clean_workspace: bool,
         /// If true, only update status without sending the "MISSION RESUMED" message
         skip_message: bool,
+        /// Checkpoint to roll the mission back to before resuming
+        checkpoint: Option<Box<MissionCheckpoint>>,
         respond: oneshot::Sender<Result<Mission, String>>,
     },
     /// Graceful shutdown - mark running missions as interrupted
     GracefulShutdown {
         respond: oneshot::Sender<Vec<Uuid>>,
     },
+    /// Stop the actor of a deleted session (only sent while it is idle)
+    Stop,
+    /// Get the session's conversation history and current mission
+    GetHistory {
+        respond: oneshot::Sender<ControlHistoryResponse>,
+    },
+    /// Drop all but the last `keep` history entries
+    TruncateHistory {
+        keep: usize,
+        respond: oneshot::Sender<usize>, // number of entries removed
+    },
     /// Get the current message queue
     GetQueue {
         respond: oneshot::Sender<Vec<QueuedMessage>>,
@@ -730,6 +1313,29 @@ pub enum ControlCommand {
     ClearQueue {
         respond: oneshot::Sender<usize>, // number of messages cleared
     },
+    /// Replace a queued message's content
+    EditQueuedMessage {
+        message_id: Uuid,
+        content: String,
+        respond: oneshot::Sender<bool>, // true if found
+    },
+    /// Change a queued message's priority, moving it ahead of lower ones
+    SetQueuedPriority {
+        message_id: Uuid,
+        priority: MissionPriority,
+        respond: oneshot::Sender<bool>, // true if the message was found
+    },
+    /// Move queued messages to the front, in the given order
+    ReorderQueue {
+        message_ids: Vec<Uuid>,
+        respond: oneshot::Sender<usize>, // number of messages moved
+    },
+    /// Change a mission's priority and re-queue its waiting messages
+    SetMissionPriority {
+        mission_id: Uuid,
+        priority: MissionPriority,
+        respond: oneshot::Sender<Result<(), String>>,
+    },
 }
 
 // ==================== Mission Types ====================
@@ -765,6 +1371,30 @@ impl std::fmt::Display for MissionStatus {
     }
 }
 
+/// How urgently a mission's messages should run when they have to queue.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum MissionPriority {
+    Low,
+    #[default]
+    Normal,
+    High,
+    Urgent,
+}
+
+impl std::fmt::Display for MissionPriority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Low => write!(f, "low"),
+            Self::Normal => write!(f, "normal"),
+            Self::High => write!(f, "high"),
+            Self::Urgent => write!(f, "urgent"),
+        }
+    }
+}
+
 // Mission and MissionHistoryEntry are now defined in mission_store module
 
 /// Metadata for a desktop session started during a mission.
@@ -797,6 +1427,67 @@ pub struct SetMissionStatusRequest {
     pub status: MissionStatus,
 }
 
+/// Request to change a mission's or a queued message's priority.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SetPriorityRequest {
+    pub priority: MissionPriority,
+}
+
+/// Changes to a queued message; at least one field is required.
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateQueuedMessageRequest {
+    #[serde(default)]
+    pub content: Option<String>,
+    #[serde(default)]
+    pub priority: Option<MissionPriority>,
+}
+
+/// Request to set or clear a mission's cost budget.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SetBudgetRequest {
+    /// New limit in cents (null removes the budget)
+    pub budget_cents: Option<u64>,
+}
+
+/// Request to set or clear a mission's run time limit.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SetTimeoutRequest {
+    /// New limit in minutes (0 = unlimited, null = server default)
+    pub timeout_minutes: Option<u64>,
+}
+
+/// Request to replace a mission's tags.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SetTagsRequest {
+    pub tags: BTreeMap<String, String>,
+}
+
+/// Request to replace a mission's deliverable checks.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SetDeliverableChecksRequest {
+    pub checks: Vec<DeliverableCheck>,
+}
+
+/// Request to set or clear a mission's verifier.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SetVerifierRequest {
+    /// `null` turns the verifier off
+    pub verifier: Option<MissionVerifier>,
+}
+
+/// Request to set or clear a mission's stall thresholds.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SetStallThresholdsRequest {
+    /// `null` goes back to the server defaults
+    pub stall_thresholds: Option<StallThresholds>,
+}
+
+/// Request to move queued messages to the front of the queue.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReorderQueueRequest {
+    pub message_ids: Vec<Uuid>,
+}
+
 // MissionStore trait and implementations are in mission_store module
 
 /// Shared tool hub used to await frontend tool results.
@@ -804,24 +1495,38 @@ pub struct SetMissionStatusRequest {
 /// Supports both orderings:
 /// - register-then-resolve (normal flow)
 /// - resolve-then-register (frontend submits answer before backend registers)
+///
+/// Calls nobody answers within their configured limit get a default answer
+/// (or are dropped, failing the turn), see `FrontendToolTimeouts`.
 #[derive(Debug)]
 pub struct FrontendToolHub {
-    pending: Mutex<HashMap<String, oneshot::Sender<serde_json::Value>>>,
+    pending: Arc<Mutex<HashMap<String, oneshot::Sender<serde_json::Value>>>>,
     early_results: Mutex<HashMap<String, serde_json::Value>>,
+    timeouts: FrontendToolTimeouts,
 }
 
 impl FrontendToolHub {
     pub fn new() -> Self {
+        Self::with_timeouts(FrontendToolTimeouts::default())
+    }
+
+    pub fn with_timeouts(timeouts: FrontendToolTimeouts) -> Self {
         Self {
-            pending: Mutex::new(HashMap::new()),
+            pending: Arc::new(Mutex::new(HashMap::new())),
             early_results: Mutex::new(HashMap::new()),
+            timeouts,
         }
     }
 
-    /// Register a tool call that expects a frontend-provided result.
-    /// If the result was already submitted (resolve-before-register), it is
-    /// delivered immediately.
-    pub async fn register(&self, tool_call_id: String) -> oneshot::Receiver<serde_json::Value> {
+    /// Register a call to the frontend tool `tool` that expects a
+    /// frontend-provided result. If the result was already submitted
+    /// (resolve-before-register), it is delivered immediately.
+    pub async fn register(
+        &self,
+        tool_call_id: String,
+        tool: &str,
+        args: &serde_json::Value,
+    ) -> oneshot::Receiver<serde_json::Value> {
         let (tx, rx) = oneshot::channel();
 
         {
@@ -832,6 +1537,30 @@ impl FrontendToolHub {
             }
         }
 
+        if let Some(limit) = self.timeouts.timeout_for(tool) {
+            let answer = crate::tools::unattended_answer(limit.action, tool, args);
+            let pending = Arc::clone(&self.pending);
+            let id = tool_call_id.clone();
+            let tool = tool.to_string();
+            tokio::spawn(async move {
+                tokio::time::sleep(limit.after).await;
+                let Some(tx) = pending.lock().await.remove(&id) else {
+                    return;
+                };
+                tracing::warn!(
+                    tool_call_id = %id,
+                    tool = %tool,
+                    "Frontend tool not answered within {}s, action: {:?}",
+                    limit.after.as_secs(),
+                    limit.action
+                );
+                // Dropping the sender without an answer fails the call
+                if let Some(answer) = answer {
+                    let _ = tx.send(answer);
+                }
+            });
+        }
+
         let mut pending = self.pending.lock().await;
         pending.insert(tool_call_id, tx);
         rx
@@ -882,12 +1611,16 @@ pub struct ControlState {
     pub max_parallel: usize,
     /// Mission persistence (SQLite-backed)
     pub mission_store: Arc<dyn MissionStore>,
+    /// Replayable log of the events sent on `events_tx`
+    pub journal: Arc<EventJournal>,
 }
 
 /// Control session manager for per-user sessions.
 #[derive(Clone)]
 pub struct ControlHub {
     sessions: Arc<RwLock<HashMap<String, ControlState>>>,
+    /// Named sessions users created besides their default one
+    named: Arc<ControlSessionRegistry>,
     config: Config,
     root_agent: AgentRef,
     mcp: Arc<McpRegistry>,
@@ -907,6 +1640,7 @@ impl ControlHub {
     ) -> Self {
         Self {
             sessions: Arc::new(RwLock::new(HashMap::new())),
+            named: Arc::new(ControlSessionRegistry::load(&config.working_dir)),
             config,
             root_agent,
             mcp,
@@ -916,14 +1650,26 @@ impl ControlHub {
         }
     }
 
+    /// The session the user's request targets: a named session if it set
+    /// one, else the user's default session.
     pub async fn get_or_spawn(&self, user: &AuthUser) -> ControlState {
-        if let Some(existing) = self.sessions.read().await.get(&user.id).cloned() {
+        let key = session_key(&user.id, user.control_session.as_deref());
+        if let Some(existing) = self.sessions.read().await.get(&key).cloned() {
             return existing;
         }
         let mut sessions = self.sessions.write().await;
-        if let Some(existing) = sessions.get(&user.id).cloned() {
+        if let Some(existing) = sessions.get(&key).cloned() {
             return existing;
         }
+        // Named sessions keep their missions in a store of their own
+        let named = match user.control_session.as_deref() {
+            Some(name) => self.named.get(&user.id, name).await,
+            None => None,
+        };
+        let store_user = match user.control_session.as_deref() {
+            Some(name) => format!("{}--{}", user.id, name),
+            None => user.id.clone(),
+        };
 
         // Get mission store type from environment (default: SQLite)
         let store_type = std::env::var("MISSION_STORE_TYPE")
@@ -936,7 +1682,7 @@ impl ControlHub {
             .join(".sandboxed-sh")
             .join("missions");
         let mission_store: Arc<dyn MissionStore> =
-            match create_mission_store(store_type, base_dir, &user.id).await {
+            match create_mission_store(store_type, base_dir, &store_user).await {
                 Ok(store) => Arc::from(store),
                 Err(err) => {
                     tracing::warn!(
@@ -948,6 +1694,11 @@ impl ControlHub {
                 }
             };
 
+        // The conversation is kept next to the missions, if they persist
+        let history_store = mission_store
+            .is_persistent()
+            .then(|| ControlHistoryStore::new(&self.config.working_dir, &store_user));
+
         let state = spawn_control_session(
             self.config.clone(),
             Arc::clone(&self.root_agent),
@@ -956,15 +1707,65 @@ impl ControlHub {
             Arc::clone(&self.library),
             mission_store,
             self.secrets.clone(),
+            named.map(|session| session.working_dir),
+            history_store,
         );
-        sessions.insert(user.id.clone(), state.clone());
+        sessions.insert(key, state.clone());
         state
     }
 
+    pub fn named_sessions(&self) -> &ControlSessionRegistry {
+        &self.named
+    }
+
+    /// Status of a named session, if it has been started since the server
+    /// started.
+    pub async fn session_status(&self, user_id: &str, name: &str) -> Option<ControlStatus> {
+        let session = self
+            .sessions
+            .read()
+            .await
+            .get(&session_key(user_id, Some(name)))
+            .cloned()?;
+        let status = session.status.read().await.clone();
+        Some(status)
+    }
+
+    /// Delete a named session. Fails while it is running something; its
+    /// missions stay on disk.
+    pub async fn remove_session(&self, user_id: &str, name: &str) -> Result<(), String> {
+        let key = session_key(user_id, Some(name));
+        let mut sessions = self.sessions.write().await;
+        if let Some(session) = sessions.get(&key) {
+            let busy = session.status.read().await.state != ControlRunState::Idle
+                || !session.running_missions.read().await.is_empty();
+            if busy {
+                return Err(format!("Control session '{}' is busy", name));
+            }
+        }
+        self.named.remove(user_id, name).await?;
+        if let Some(session) = sessions.remove(&key) {
+            let _ = session.cmd_tx.send(ControlCommand::Stop).await;
+        }
+        Ok(())
+    }
+
     pub async fn all_sessions(&self) -> Vec<ControlState> {
         self.sessions.read().await.values().cloned().collect()
     }
 
+    /// Running missions of every session. Sessions that don't answer are
+    /// left out.
+    pub async fn running_missions(&self) -> Vec<super::mission_runner::RunningMissionInfo> {
+        let mut running = Vec::new();
+        for session in self.all_sessions().await {
+            if let Ok(missions) = running_missions_of(&session).await {
+                running.extend(missions);
+            }
+        }
+        running
+    }
+
     /// Get a mission store for desktop management.
     /// Uses the default user's store if available, or creates a temporary one.
     pub async fn get_mission_store(&self) -> Arc<dyn MissionStore> {
@@ -997,6 +1798,13 @@ impl ControlHub {
     }
 }
 
+fn session_key(user_id: &str, session: Option<&str>) -> String {
+    match session {
+        Some(name) => format!("{}/{}", user_id, name),
+        None => user_id.to_string(),
+    }
+}
+
 /// Execution progress for showing overall mission progress
 #[derive(Debug, Clone, Serialize, Default)]
 pub struct ExecutionProgress {
@@ -1017,6 +1825,13 @@ pub struct ControlStatus {
     pub mission_id: Option<Uuid>,
 }
 
+/// Conversation of a control session.
+#[derive(Debug, Clone, Serialize)]
+pub struct ControlHistoryResponse {
+    pub current_mission: Option<Uuid>,
+    pub messages: Vec<MissionHistoryEntry>,
+}
+
 async fn set_and_emit_status(
     status: &Arc<RwLock<ControlStatus>>,
     events: &broadcast::Sender<AgentEvent>,
@@ -1053,11 +1868,31 @@ pub async fn post_message(
     if content.is_empty() {
         return Err((StatusCode::BAD_REQUEST, "content is required".to_string()));
     }
+    if shutdown::is_draining() {
+        return Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Server is shutting down".to_string(),
+        ));
+    }
 
     let id = Uuid::new_v4();
-    let agent = req.agent;
     let target_mission_id = req.mission_id;
     let control = control_for_user(&state, &user).await;
+    let (agent, content) =
+        agent_mention::resolve(&state, &control, req.agent, content, target_mission_id).await?;
+    if let Some(mid) = target_mission_id {
+        if let Ok(Some(mission)) = control.mission_store.get_mission(mid).await {
+            if is_exhausted(mission.budget_cents, mission.spent_cents) {
+                return Err((
+                    StatusCode::PAYMENT_REQUIRED,
+                    format!(
+                        "Mission budget of {} is used up",
+                        format_cents(mission.budget_cents.unwrap_or_default())
+                    ),
+                ));
+            }
+        }
+    }
     let (queued_tx, queued_rx) = oneshot::channel();
     tracing::info!(
         user_id = %user.id,
@@ -1075,6 +1910,7 @@ pub async fn post_message(
             content,
             agent,
             target_mission_id,
+            priority: req.priority,
             respond: queued_tx,
         })
         .await
@@ -1129,6 +1965,31 @@ pub async fn post_tool_result(
     Ok(Json(serde_json::json!({ "ok": true })))
 }
 
+/// Ask the user whether a tool call may run. Blocks until the dashboard
+/// answers (or the approval times out) and returns `{ "allowed": bool }`.
+pub async fn post_tool_approval(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Json(req): Json<ControlToolApprovalRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    if req.tool.trim().is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "tool is required".to_string()));
+    }
+
+    let control = control_for_user(&state, &user).await;
+    let approver = crate::tools::PolicyApprover {
+        hub: Arc::clone(&control.tool_hub),
+        events: control.events_tx.clone(),
+        mission_id: req.mission_id,
+    };
+    let allowed = approver
+        .request(&req.tool, &req.args)
+        .await
+        .map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e.to_string()))?;
+
+    Ok(Json(serde_json::json!({ "allowed": allowed })))
+}
+
 /// Cancel the currently running control session task.
 pub async fn post_cancel(
     State(state): State<Arc<AppState>>,
@@ -1210,6 +2071,123 @@ pub async fn remove_from_queue(
     }
 }
 
+/// Edit a queued message before it starts: its content, its priority or
+/// both.
+pub async fn update_queued_message(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Path(message_id): Path<Uuid>,
+    Json(req): Json<UpdateQueuedMessageRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let content = req.content.map(|content| content.trim().to_string());
+    if content.as_deref().is_some_and(str::is_empty) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "content can't be empty".to_string(),
+        ));
+    }
+    if content.is_none() && req.priority.is_none() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "content or priority is required".to_string(),
+        ));
+    }
+    let control = control_for_user(&state, &user).await;
+    let mut commands = Vec::new();
+    let mut replies = Vec::new();
+    if let Some(content) = content {
+        let (tx, rx) = oneshot::channel();
+        commands.push(ControlCommand::EditQueuedMessage {
+            message_id,
+            content,
+            respond: tx,
+        });
+        replies.push(rx);
+    }
+    if let Some(priority) = req.priority {
+        let (tx, rx) = oneshot::channel();
+        commands.push(ControlCommand::SetQueuedPriority {
+            message_id,
+            priority,
+            respond: tx,
+        });
+        replies.push(rx);
+    }
+    for command in commands {
+        control.cmd_tx.send(command).await.map_err(|_| {
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "control session unavailable".to_string(),
+            )
+        })?;
+    }
+    let mut found = true;
+    for rx in replies {
+        found &= rx.await.map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "failed to update queue".to_string(),
+            )
+        })?;
+    }
+    if found {
+        Ok(Json(serde_json::json!({ "ok": true })))
+    } else {
+        Err((StatusCode::NOT_FOUND, "message not in queue".to_string()))
+    }
+}
+
+/// List a mission's queued messages, in the order they will run.
+pub async fn get_mission_queue(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Path(mission_id): Path<Uuid>,
+) -> Result<Json<Vec<QueuedMessage>>, (StatusCode, String)> {
+    let Json(queue) = get_queue(State(state), Extension(user)).await?;
+    Ok(Json(
+        queue
+            .into_iter()
+            .filter(|queued| queued.mission_id == Some(mission_id))
+            .collect(),
+    ))
+}
+
+/// Move queued messages to the front of their queue, in the given order.
+pub async fn reorder_queue(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Json(req): Json<ReorderQueueRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    if req.message_ids.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "message_ids is required".to_string(),
+        ));
+    }
+    let control = control_for_user(&state, &user).await;
+    let (tx, rx) = oneshot::channel();
+    control
+        .cmd_tx
+        .send(ControlCommand::ReorderQueue {
+            message_ids: req.message_ids,
+            respond: tx,
+        })
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "control session unavailable".to_string(),
+            )
+        })?;
+    let moved = rx.await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "failed to reorder queue".to_string(),
+        )
+    })?;
+    Ok(Json(serde_json::json!({ "ok": true, "moved": moved })))
+}
+
 /// Clear all messages from the queue.
 pub async fn clear_queue(
     State(state): State<Arc<AppState>>,
@@ -1236,19 +2214,116 @@ pub async fn clear_queue(
     Ok(Json(serde_json::json!({ "ok": true, "cleared": cleared })))
 }
 
+/// Get the control session's conversation history.
+pub async fn get_history(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+) -> Result<Json<ControlHistoryResponse>, (StatusCode, String)> {
+    let control = control_for_user(&state, &user).await;
+    let (tx, rx) = oneshot::channel();
+    control
+        .cmd_tx
+        .send(ControlCommand::GetHistory { respond: tx })
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "control session unavailable".to_string(),
+            )
+        })?;
+    let history = rx.await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "failed to get history".to_string(),
+        )
+    })?;
+    Ok(Json(history))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TruncateHistoryQuery {
+    /// Number of newest entries to keep; everything is cleared if unset
+    pub keep: Option<usize>,
+}
+
+/// Clear the control session's conversation history, or keep only its
+/// newest entries.
+pub async fn truncate_history(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    axum::extract::Query(query): axum::extract::Query<TruncateHistoryQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let control = control_for_user(&state, &user).await;
+    let (tx, rx) = oneshot::channel();
+    control
+        .cmd_tx
+        .send(ControlCommand::TruncateHistory {
+            keep: query.keep.unwrap_or(0),
+            respond: tx,
+        })
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "control session unavailable".to_string(),
+            )
+        })?;
+    let removed = rx.await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "failed to truncate history".to_string(),
+        )
+    })?;
+    Ok(Json(serde_json::json!({ "ok": true, "removed": removed })))
+}
+
 // ==================== Mission Endpoints ====================
 
 /// List all missions.
 pub async fn list_missions(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<AuthUser>,
+    axum::extract::Query(query): axum::extract::Query<ListMissionsQuery>,
 ) -> Result<Json<Vec<Mission>>, (StatusCode, String)> {
+    const PAGE_SIZE: usize = 200;
+
+    let filter = MissionFilter::parse(&query).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    let limit = query.limit.unwrap_or(50).clamp(1, 1000);
     let control = control_for_user(&state, &user).await;
-    let mut missions = control
-        .mission_store
-        .list_missions(50, 0)
-        .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    let mut missions = if filter.is_empty() {
+        control
+            .mission_store
+            .list_missions(limit, 0)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?
+    } else {
+        let running = if filter.needs_running() {
+            running_missions_of(&control).await?
+        } else {
+            Vec::new()
+        };
+        // Page through the store until enough missions match
+        let mut matched = Vec::new();
+        let mut offset = 0;
+        loop {
+            let page = control
+                .mission_store
+                .list_missions(PAGE_SIZE, offset)
+                .await
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+            let exhausted = page.len() < PAGE_SIZE;
+            offset += page.len();
+            matched.extend(page.into_iter().filter(|mission| {
+                let info = running.iter().find(|r| r.mission_id == mission.id);
+                filter.matches(mission, info)
+            }));
+            if exhausted || matched.len() >= limit {
+                break;
+            }
+        }
+        matched.truncate(limit);
+        matched
+    };
 
     // Populate workspace_name for each mission
     for mission in &mut missions {
@@ -1299,8 +2374,40 @@ pub struct CreateMissionRequest {
     pub config_profile: Option<String>,
     /// Backend to use for this mission ("opencode" or "claudecode")
     pub backend: Option<String>,
+    /// Queue priority for the mission's messages (defaults to normal)
+    pub priority: Option<MissionPriority>,
+    /// Cost limit in cents; the mission stops once it is used up
+    pub budget_cents: Option<u64>,
+    /// Wall-clock limit for a run in minutes (0 = unlimited; defaults to
+    /// the server's MISSION_TIMEOUT_MINUTES)
+    pub timeout_minutes: Option<u64>,
+    /// Retry failed turns on transient errors (parallel runners only)
+    pub retry_policy: Option<RetryPolicy>,
+    /// Run up to this many queued messages at once, each extra one in its
+    /// own backend session (parallel runners only; default 1)
+    pub max_concurrent_messages: Option<u32>,
+    /// Key/value labels for filtering the mission list
+    pub tags: Option<BTreeMap<String, String>>,
+    /// Checks run against the mission's output when it completes
+    pub deliverable_checks: Option<Vec<DeliverableCheck>>,
+    /// Model reviewing completion claims before the mission completes
+    pub verifier: Option<MissionVerifier>,
+    /// Seconds without activity before the mission counts as stalled
+    pub stall_thresholds: Option<StallThresholds>,
+}
+
+/// Request body for re-running a mission.
+#[derive(Debug, Default, Deserialize)]
+pub struct CloneMissionRequest {
+    /// Title of the new mission (defaults to the original's)
+    pub title: Option<String>,
+    /// Prompt to send instead of the original's first message
+    pub prompt: Option<String>,
 }
 
+/// Upper bound for `max_concurrent_messages`.
+const MAX_CONCURRENT_MESSAGES: u32 = 8;
+
 pub async fn create_mission(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<AuthUser>,
@@ -1308,6 +2415,53 @@ pub async fn create_mission(
 ) -> Result<Json<Mission>, (StatusCode, String)> {
     let (tx, rx) = oneshot::channel();
 
+    let priority = body.as_ref().and_then(|b| b.priority);
+    let budget_cents = body.as_ref().and_then(|b| b.budget_cents);
+    let timeout_minutes = body.as_ref().and_then(|b| b.timeout_minutes);
+    let retry_policy = body.as_ref().and_then(|b| b.retry_policy.clone());
+    if retry_policy.as_ref().is_some_and(|p| p.max_attempts == 0) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "retry_policy.max_attempts must be at least 1".to_string(),
+        ));
+    }
+    if budget_cents == Some(0) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "budget_cents must be positive".to_string(),
+        ));
+    }
+    let max_concurrent_messages = body.as_ref().and_then(|b| b.max_concurrent_messages);
+    if max_concurrent_messages.is_some_and(|n| n == 0 || n > MAX_CONCURRENT_MESSAGES) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!(
+                "max_concurrent_messages must be between 1 and {}",
+                MAX_CONCURRENT_MESSAGES
+            ),
+        ));
+    }
+    let tags = body
+        .as_ref()
+        .and_then(|b| b.tags.clone())
+        .unwrap_or_default();
+    validate_tags(&tags).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    let deliverable_checks = body
+        .as_ref()
+        .and_then(|b| b.deliverable_checks.clone())
+        .unwrap_or_default();
+    mission_verification::validate_checks(&deliverable_checks)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    let verifier = body.as_ref().and_then(|b| b.verifier.clone());
+    if let Some(verifier) = &verifier {
+        mission_verifier::validate(verifier).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    }
+    let stall_thresholds = body.as_ref().and_then(|b| b.stall_thresholds);
+    if let Some(thresholds) = &stall_thresholds {
+        thresholds
+            .validate()
+            .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    }
     let (title, workspace_id, agent, model_override, config_profile, mut backend) = body
         .map(|b| {
             (
@@ -1385,6 +2539,14 @@ pub async fn create_mission(
         }
     }
 
+    // Amp continues a single thread per mission, so its turns can't run side by side
+    if backend.as_deref() == Some("amp") && max_concurrent_messages.is_some_and(|n| n > 1) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "max_concurrent_messages is not supported with the amp backend".to_string(),
+        ));
+    }
+
     // If no model_override specified, resolve from config profile for Claude Code
     if backend.as_deref() == Some("claudecode") && model_override.is_none() {
         if let Some(default_model) =
@@ -1415,18 +2577,170 @@ pub async fn create_mission(
             )
         })?;
 
-    rx.await
+    let mut mission = rx
+        .await
         .map_err(|_| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 "Failed to receive response".to_string(),
             )
         })?
-        .map(Json)
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    if let Some(priority) = priority.filter(|p| *p != MissionPriority::default()) {
+        control
+            .mission_store
+            .update_mission_priority(mission.id, priority)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+        mission.priority = priority;
+    }
+    if let Some(budget_cents) = budget_cents {
+        control
+            .mission_store
+            .update_mission_budget(mission.id, Some(budget_cents))
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+        mission.budget_cents = Some(budget_cents);
+    }
+    if let Some(timeout_minutes) = timeout_minutes {
+        control
+            .mission_store
+            .update_mission_timeout(mission.id, Some(timeout_minutes))
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+        mission.timeout_minutes = Some(timeout_minutes);
+    }
+    if let Some(retry_policy) = retry_policy {
+        control
+            .mission_store
+            .update_mission_retry_policy(mission.id, Some(retry_policy.clone()))
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+        mission.retry_policy = Some(retry_policy);
+    }
+    if let Some(max_concurrent_messages) = max_concurrent_messages.filter(|n| *n > 1) {
+        control
+            .mission_store
+            .update_mission_concurrency(mission.id, Some(max_concurrent_messages))
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+        mission.max_concurrent_messages = Some(max_concurrent_messages);
+    }
+    if !tags.is_empty() {
+        control
+            .mission_store
+            .update_mission_tags(mission.id, &tags)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+        mission.tags = tags;
+    }
+    if !deliverable_checks.is_empty() {
+        control
+            .mission_store
+            .update_mission_deliverable_checks(mission.id, &deliverable_checks)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+        mission.deliverable_checks = deliverable_checks;
+    }
+    if let Some(verifier) = verifier {
+        control
+            .mission_store
+            .update_mission_verifier(mission.id, Some(verifier.clone()))
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+        mission.verifier = Some(verifier);
+    }
+    if let Some(thresholds) = stall_thresholds {
+        control
+            .mission_store
+            .update_mission_stall_thresholds(mission.id, Some(thresholds))
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+        mission.stall_thresholds = Some(thresholds);
+    }
+
+    Ok(Json(mission))
 }
 
-/// Load/switch to a mission.
+/// Re-run a mission: create a new one with the same settings, in the same
+/// workspace (with a fresh working directory), and send it the original
+/// first message or an edited prompt.
+pub async fn clone_mission(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+    body: Option<Json<CloneMissionRequest>>,
+) -> Result<Json<Mission>, (StatusCode, String)> {
+    let req = body.map(|Json(req)| req).unwrap_or_default();
+    let control = control_for_user(&state, &user).await;
+    let source = control
+        .mission_store
+        .get_mission(id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Mission {} not found", id)))?;
+
+    let prompt = req
+        .prompt
+        .filter(|p| !p.trim().is_empty())
+        .or_else(|| {
+            source
+                .history
+                .iter()
+                .find(|entry| entry.role == "user")
+                .map(|entry| entry.content.clone())
+        })
+        .ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                format!(
+                    "Mission {} has no first message to re-run; pass a prompt",
+                    id
+                ),
+            )
+        })?;
+
+    let Json(mission) = create_mission(
+        State(Arc::clone(&state)),
+        Extension(user.clone()),
+        Some(Json(CreateMissionRequest {
+            title: req.title.or_else(|| source.title.clone()),
+            workspace_id: Some(source.workspace_id),
+            agent: source.agent.clone(),
+            model_override: source.model_override.clone(),
+            config_profile: source.config_profile.clone(),
+            backend: Some(source.backend.clone()),
+            priority: Some(source.priority),
+            budget_cents: source.budget_cents,
+            timeout_minutes: source.timeout_minutes,
+            retry_policy: source.retry_policy.clone(),
+            max_concurrent_messages: source.max_concurrent_messages,
+            tags: Some(source.tags.clone()).filter(|tags| !tags.is_empty()),
+            deliverable_checks: Some(source.deliverable_checks.clone()),
+            verifier: source.verifier.clone(),
+            stall_thresholds: source.stall_thresholds,
+        })),
+    )
+    .await?;
+
+    let _ = post_message(
+        State(Arc::clone(&state)),
+        Extension(user),
+        Json(ControlMessageRequest {
+            content: prompt,
+            agent: None,
+            mission_id: Some(mission.id),
+            priority: None,
+        }),
+    )
+    .await?;
+
+    tracing::info!("Mission {} cloned from {}", mission.id, id);
+    Ok(Json(mission))
+}
+
+/// Load/switch to a mission.
 pub async fn load_mission(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<AuthUser>,
@@ -1500,6 +2814,142 @@ pub async fn set_mission_status(
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))
 }
 
+/// Set mission priority. Its messages waiting in the queue move accordingly.
+pub async fn set_mission_priority(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<SetPriorityRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let (tx, rx) = oneshot::channel();
+
+    let control = control_for_user(&state, &user).await;
+    control
+        .cmd_tx
+        .send(ControlCommand::SetMissionPriority {
+            mission_id: id,
+            priority: req.priority,
+            respond: tx,
+        })
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "control session unavailable".to_string(),
+            )
+        })?;
+
+    rx.await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to receive response".to_string(),
+            )
+        })?
+        .map(|_| Json(serde_json::json!({ "ok": true, "priority": req.priority })))
+        .map_err(|e| {
+            if e.contains("not found") {
+                (StatusCode::NOT_FOUND, e)
+            } else {
+                (StatusCode::INTERNAL_SERVER_ERROR, e)
+            }
+        })
+}
+
+/// Set or clear a mission's cost budget. Raising it lets a mission that ran
+/// out continue.
+pub async fn set_mission_budget(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<SetBudgetRequest>,
+) -> Result<Json<Mission>, (StatusCode, String)> {
+    if req.budget_cents == Some(0) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "budget_cents must be positive".to_string(),
+        ));
+    }
+    let control = control_for_user(&state, &user).await;
+    control
+        .mission_store
+        .update_mission_budget(id, req.budget_cents)
+        .await
+        .map_err(|e| {
+            if e.contains("not found") {
+                (StatusCode::NOT_FOUND, e)
+            } else {
+                (StatusCode::INTERNAL_SERVER_ERROR, e)
+            }
+        })?;
+    let mission = control
+        .mission_store
+        .get_mission(id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Mission {} not found", id)))?;
+    mission_budget::track(id, mission.budget_cents, mission.spent_cents);
+    Ok(Json(mission))
+}
+
+/// Set or clear a mission's run time limit. A running mission picks up the
+/// new limit within a minute.
+pub async fn set_mission_timeout(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<SetTimeoutRequest>,
+) -> Result<Json<Mission>, (StatusCode, String)> {
+    let control = control_for_user(&state, &user).await;
+    control
+        .mission_store
+        .update_mission_timeout(id, req.timeout_minutes)
+        .await
+        .map_err(|e| {
+            if e.contains("not found") {
+                (StatusCode::NOT_FOUND, e)
+            } else {
+                (StatusCode::INTERNAL_SERVER_ERROR, e)
+            }
+        })?;
+    control
+        .mission_store
+        .get_mission(id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?
+        .map(Json)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Mission {} not found", id)))
+}
+
+/// Replace a mission's tags.
+pub async fn set_mission_tags(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<SetTagsRequest>,
+) -> Result<Json<Mission>, (StatusCode, String)> {
+    validate_tags(&req.tags).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    let control = control_for_user(&state, &user).await;
+    control
+        .mission_store
+        .update_mission_tags(id, &req.tags)
+        .await
+        .map_err(|e| {
+            if e.contains("not found") {
+                (StatusCode::NOT_FOUND, e)
+            } else {
+                (StatusCode::INTERNAL_SERVER_ERROR, e)
+            }
+        })?;
+    control
+        .mission_store
+        .get_mission(id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?
+        .map(Json)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Mission {} not found", id)))
+}
+
 /// Get the current mission (if any).
 pub async fn get_current_mission(
     State(state): State<Arc<AppState>>,
@@ -1669,12 +3119,64 @@ pub async fn list_running_missions(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<AuthUser>,
 ) -> Result<Json<Vec<super::mission_runner::RunningMissionInfo>>, (StatusCode, String)> {
+    let control = control_for_user(&state, &user).await;
+    let mut running = running_missions_of(&control).await?;
+    for info in &mut running {
+        if let Ok(Some(mission)) = control.mission_store.get_mission(info.mission_id).await {
+            info.tags = mission.tags;
+        }
+    }
+
+    Ok(Json(running))
+}
+
+/// Ask the control actor for its running missions.
+pub(super) async fn running_missions_of(
+    control: &ControlState,
+) -> Result<Vec<super::mission_runner::RunningMissionInfo>, (StatusCode, String)> {
+    let (tx, rx) = oneshot::channel();
+    control
+        .cmd_tx
+        .send(ControlCommand::ListRunning { respond: tx })
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "control session unavailable".to_string(),
+            )
+        })?;
+
+    rx.await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to receive response".to_string(),
+        )
+    })
+}
+
+/// Request body for starting a mission in parallel.
+#[derive(Debug, Deserialize)]
+pub struct StartParallelRequest {
+    pub content: String,
+}
+
+/// Start a mission in parallel (if capacity allows).
+pub async fn start_mission_parallel(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Path(mission_id): Path<Uuid>,
+    Json(req): Json<StartParallelRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
     let (tx, rx) = oneshot::channel();
 
     let control = control_for_user(&state, &user).await;
     control
         .cmd_tx
-        .send(ControlCommand::ListRunning { respond: tx })
+        .send(ControlCommand::StartParallel {
+            mission_id,
+            content: req.content,
+            respond: tx,
+        })
         .await
         .map_err(|_| {
             (
@@ -1683,102 +3185,714 @@ pub async fn list_running_missions(
             )
         })?;
 
-    let running = rx.await.map_err(|_| {
+    rx.await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to receive response".to_string(),
+            )
+        })?
+        .map(|_| Json(serde_json::json!({ "ok": true, "mission_id": mission_id })))
+        .map_err(|e| (StatusCode::CONFLICT, e))
+}
+
+/// Cancel a specific mission.
+pub async fn cancel_mission(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Path(mission_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let (tx, rx) = oneshot::channel();
+
+    let control = control_for_user(&state, &user).await;
+    control
+        .cmd_tx
+        .send(ControlCommand::CancelMission {
+            mission_id,
+            respond: tx,
+        })
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "control session unavailable".to_string(),
+            )
+        })?;
+
+    rx.await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to receive response".to_string(),
+            )
+        })?
+        .map(|_| {
+            // A cancelled mission doesn't stay paused
+            super::mission_pause::resume(mission_id);
+            Json(serde_json::json!({ "ok": true, "cancelled": mission_id }))
+        })
+        .map_err(|e| (StatusCode::NOT_FOUND, e))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SteerMissionRequest {
+    pub content: String,
+}
+
+/// Send a message to a mission's running turn instead of queueing it.
+pub async fn steer_mission(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Path(mission_id): Path<Uuid>,
+    Json(req): Json<SteerMissionRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    if req.content.trim().is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "content is required".to_string()));
+    }
+    let (tx, rx) = oneshot::channel();
+
+    let control = control_for_user(&state, &user).await;
+    control
+        .cmd_tx
+        .send(ControlCommand::SteerMission {
+            mission_id,
+            content: req.content,
+            respond: tx,
+        })
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "control session unavailable".to_string(),
+            )
+        })?;
+
+    let injected = rx
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to receive response".to_string(),
+            )
+        })?
+        .map_err(|e| (StatusCode::CONFLICT, e))?;
+    Ok(Json(serde_json::json!({
+        "ok": true,
+        "mode": if injected { "injected" } else { "interrupted" },
+    })))
+}
+
+/// Pause a mission: stop reading its backend's output and don't start new
+/// turns until it is resumed. The backend process is left running.
+pub async fn pause_mission(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Path(mission_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let control = control_for_user(&state, &user).await;
+    let mission = control
+        .mission_store
+        .get_mission(mission_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                format!("Mission {} not found", mission_id),
+            )
+        })?;
+    if !matches!(
+        mission.status,
+        MissionStatus::Active | MissionStatus::Pending
+    ) {
+        return Err((
+            StatusCode::CONFLICT,
+            format!("Cannot pause a mission with status {}", mission.status),
+        ));
+    }
+
+    if super::mission_pause::pause(mission_id) {
+        let _ = control.events_tx.send(AgentEvent::MissionPaused {
+            mission_id,
+            paused: true,
+        });
+    }
+    Ok(Json(
+        serde_json::json!({ "ok": true, "paused": mission_id }),
+    ))
+}
+
+/// Kill every backend CLI process recorded for a mission (and its children).
+/// Unlike cancel, this does not touch mission state; it is a last-resort
+/// kill-switch for runaway or orphaned harness processes.
+pub async fn kill_mission_processes(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Path(mission_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let control = control_for_user(&state, &user).await;
+    if control
+        .mission_store
+        .get_mission(mission_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?
+        .is_none()
+    {
+        return Err((
+            StatusCode::NOT_FOUND,
+            format!("Mission {} not found", mission_id),
+        ));
+    }
+
+    let registry = crate::process_registry::global().ok_or_else(|| {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "process registry unavailable".to_string(),
+        )
+    })?;
+    let killed = tokio::task::spawn_blocking(move || registry.kill_mission(mission_id))
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(
+        serde_json::json!({ "ok": true, "killed_pids": killed }),
+    ))
+}
+
+/// Periodically kill recorded backend processes whose missions are no longer
+/// running in any control session.
+pub async fn process_reaper_loop(hub: ControlHub) {
+    const REAP_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+    loop {
+        tokio::time::sleep(REAP_INTERVAL).await;
+
+        let Some(registry) = crate::process_registry::global() else {
+            continue;
+        };
+        if registry.list().is_empty() {
+            continue;
+        }
+
+        let mut active = std::collections::HashSet::new();
+        let mut complete = true;
+        for session in hub.all_sessions().await {
+            let (tx, rx) = oneshot::channel();
+            if session
+                .cmd_tx
+                .send(ControlCommand::ListRunning { respond: tx })
+                .await
+                .is_err()
+            {
+                complete = false;
+                continue;
+            }
+            match rx.await {
+                Ok(running) => active.extend(running.into_iter().map(|m| m.mission_id)),
+                Err(_) => complete = false,
+            }
+        }
+        // Never reap on a partial view of running missions.
+        if !complete {
+            continue;
+        }
+
+        let reaped = tokio::task::spawn_blocking(move || registry.reap(&active))
+            .await
+            .unwrap_or_default();
+        for record in reaped {
+            tracing::warn!(
+                pid = record.pid,
+                mission_id = %record.mission_id,
+                program = %record.program,
+                "Reaped orphaned backend process"
+            );
+        }
+    }
+}
+
+/// Apply the mission retention policy to every session's missions once an
+/// hour. Only spawned when a retention policy is configured.
+pub async fn retention_loop(hub: ControlHub) {
+    const SWEEP_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+    loop {
+        tokio::time::sleep(SWEEP_INTERVAL).await;
+
+        for session in hub.all_sessions().await {
+            // Never clean up without knowing which missions are running.
+            let Ok(running) = running_missions_of(&session).await else {
+                continue;
+            };
+            let running: Vec<Uuid> = running.iter().map(|m| m.mission_id).collect();
+            let report = mission_retention::sweep(
+                &session.mission_store,
+                &hub.workspaces,
+                &hub.config,
+                &running,
+                false,
+            )
+            .await;
+            if !report.missions.is_empty() {
+                tracing::info!(
+                    missions = report.missions.len(),
+                    reclaimed_bytes = report.reclaimable_bytes,
+                    "Mission retention sweep finished"
+                );
+            }
+            for error in report.errors {
+                tracing::warn!("Mission retention: {}", error);
+            }
+        }
+    }
+}
+
+/// Request body for resuming a mission
+#[derive(Debug, Deserialize, Default)]
+pub struct ResumeMissionRequest {
+    /// If true, clean the mission's work directory before resuming
+    #[serde(default)]
+    pub clean_workspace: bool,
+    /// If true, only update the mission status without sending the "MISSION RESUMED" message.
+    /// Useful when the user is about to send their own custom message.
+    #[serde(default)]
+    pub skip_message: bool,
+}
+
+/// Query parameters for resuming a mission.
+#[derive(Debug, Default, Deserialize)]
+pub struct ResumeMissionQuery {
+    /// Checkpoint id (or "latest") to roll the mission back to first
+    pub checkpoint: Option<String>,
+}
+
+/// List a mission's checkpoints, newest first.
+pub async fn list_checkpoints(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Path(mission_id): Path<Uuid>,
+) -> Result<Json<Vec<CheckpointSummary>>, (StatusCode, String)> {
+    let control = control_for_user(&state, &user).await;
+    control
+        .mission_store
+        .get_mission(mission_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                format!("Mission {} not found", mission_id),
+            )
+        })?;
+    let checkpoints = MissionCheckpointStore::new(&state.config.working_dir);
+    Ok(Json(
+        checkpoints
+            .list(mission_id)
+            .iter()
+            .map(MissionCheckpoint::summary)
+            .collect(),
+    ))
+}
+
+/// List a mission's artifacts, in registration order.
+pub async fn list_artifacts(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Path(mission_id): Path<Uuid>,
+) -> Result<Json<Vec<MissionArtifact>>, (StatusCode, String)> {
+    let control = control_for_user(&state, &user).await;
+    control
+        .mission_store
+        .get_mission(mission_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                format!("Mission {} not found", mission_id),
+            )
+        })?;
+    let artifacts = MissionArtifactStore::new(&state.config.working_dir);
+    Ok(Json(artifacts.list(mission_id)))
+}
+
+/// Query parameters for the transcript export.
+#[derive(Debug, Default, Deserialize)]
+pub struct TranscriptQuery {
+    #[serde(default)]
+    pub format: TranscriptFormat,
+}
+
+/// Export a mission's conversation as Markdown (default) or JSON.
+pub async fn get_transcript(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Path(mission_id): Path<Uuid>,
+    axum::extract::Query(query): axum::extract::Query<TranscriptQuery>,
+) -> Result<axum::response::Response, (StatusCode, String)> {
+    use axum::response::IntoResponse;
+
+    let control = control_for_user(&state, &user).await;
+    let mission = control
+        .mission_store
+        .get_mission(mission_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                format!("Mission {} not found", mission_id),
+            )
+        })?;
+    let events = control
+        .mission_store
+        .get_events(mission_id, None, None, None)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    let ws =
+        workspace::resolve_workspace(&state.workspaces, &state.config, Some(mission.workspace_id))
+            .await;
+    let deliverables = requested_deliverables(&mission, &ws);
+    let artifacts = MissionArtifactStore::new(&state.config.working_dir).list(mission_id);
+    let transcript = Transcript::build(&mission, &events, deliverables, artifacts);
+
+    Ok(match query.format {
+        TranscriptFormat::Json => Json(transcript).into_response(),
+        TranscriptFormat::Md => (
+            [(
+                axum::http::header::CONTENT_TYPE,
+                "text/markdown; charset=utf-8",
+            )],
+            transcript.to_markdown(),
+        )
+            .into_response(),
+    })
+}
+
+/// Replace the checks run when a mission completes.
+pub async fn set_deliverable_checks(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<SetDeliverableChecksRequest>,
+) -> Result<Json<Mission>, (StatusCode, String)> {
+    mission_verification::validate_checks(&req.checks).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    let control = control_for_user(&state, &user).await;
+    control
+        .mission_store
+        .update_mission_deliverable_checks(id, &req.checks)
+        .await
+        .map_err(|e| {
+            if e.contains("not found") {
+                (StatusCode::NOT_FOUND, e)
+            } else {
+                (StatusCode::INTERNAL_SERVER_ERROR, e)
+            }
+        })?;
+    control
+        .mission_store
+        .get_mission(id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?
+        .map(Json)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Mission {} not found", id)))
+}
+
+/// Set or clear the model reviewing a mission's completion claims.
+pub async fn set_verifier(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<SetVerifierRequest>,
+) -> Result<Json<Mission>, (StatusCode, String)> {
+    if let Some(verifier) = &req.verifier {
+        mission_verifier::validate(verifier).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    }
+    let control = control_for_user(&state, &user).await;
+    control
+        .mission_store
+        .update_mission_verifier(id, req.verifier)
+        .await
+        .map_err(|e| {
+            if e.contains("not found") {
+                (StatusCode::NOT_FOUND, e)
+            } else {
+                (StatusCode::INTERNAL_SERVER_ERROR, e)
+            }
+        })?;
+    control
+        .mission_store
+        .get_mission(id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?
+        .map(Json)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Mission {} not found", id)))
+}
+
+/// Set or clear how long a mission may go without activity before it counts
+/// as stalled. Running missions pick the change up within a minute.
+pub async fn set_stall_thresholds(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<SetStallThresholdsRequest>,
+) -> Result<Json<Mission>, (StatusCode, String)> {
+    if let Some(thresholds) = &req.stall_thresholds {
+        thresholds
+            .validate()
+            .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    }
+    let control = control_for_user(&state, &user).await;
+    control
+        .mission_store
+        .update_mission_stall_thresholds(id, req.stall_thresholds)
+        .await
+        .map_err(|e| {
+            if e.contains("not found") {
+                (StatusCode::NOT_FOUND, e)
+            } else {
+                (StatusCode::INTERNAL_SERVER_ERROR, e)
+            }
+        })?;
+    control
+        .mission_store
+        .get_mission(id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?
+        .map(Json)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Mission {} not found", id)))
+}
+
+/// Run a mission's deliverable checks now and return the report.
+pub async fn verify_mission(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<VerificationReport>, (StatusCode, String)> {
+    let control = control_for_user(&state, &user).await;
+    verify_deliverables(
+        &control.mission_store,
+        &state.workspaces,
+        &state.config,
+        &control.events_tx,
+        id,
+    )
+    .await
+    .map_err(|e| {
+        if e.contains("not found") {
+            (StatusCode::NOT_FOUND, e)
+        } else {
+            (StatusCode::INTERNAL_SERVER_ERROR, e)
+        }
+    })?
+    .map(Json)
+    .ok_or_else(|| {
         (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "Failed to receive response".to_string(),
+            StatusCode::BAD_REQUEST,
+            format!("Mission {} has no deliverables to verify", id),
         )
-    })?;
-
-    Ok(Json(running))
+    })
 }
 
-/// Request body for starting a mission in parallel.
-#[derive(Debug, Deserialize)]
-pub struct StartParallelRequest {
-    pub content: String,
+/// Health of a mission: stall state while it runs, deliverable checks once
+/// it has completed.
+pub async fn get_mission_health(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<MissionHealthReport>, (StatusCode, String)> {
+    let control = control_for_user(&state, &user).await;
+    let mission = control
+        .mission_store
+        .get_mission(id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Mission {} not found", id)))?;
+    let running = running_missions_of(&control)
+        .await?
+        .into_iter()
+        .find(|info| info.mission_id == id);
+    let health = match (&running, &mission.verification) {
+        (Some(info), _) => info.health.clone(),
+        (None, Some(report)) => report.health(),
+        (None, None) => super::mission_runner::MissionHealth::Healthy,
+    };
+    Ok(Json(MissionHealthReport {
+        mission_id: id,
+        status: mission.status,
+        running: running.is_some(),
+        health,
+        verification: mission.verification,
+    }))
 }
 
-/// Start a mission in parallel (if capacity allows).
-pub async fn start_mission_parallel(
+/// Report what the retention policy would clean up now.
+pub async fn retention_report(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<AuthUser>,
-    Path(mission_id): Path<Uuid>,
-    Json(req): Json<StartParallelRequest>,
-) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    let (tx, rx) = oneshot::channel();
+) -> Result<Json<RetentionReport>, (StatusCode, String)> {
+    run_retention_sweep(&state, &user, true).await.map(Json)
+}
 
-    let control = control_for_user(&state, &user).await;
-    control
-        .cmd_tx
-        .send(ControlCommand::StartParallel {
-            mission_id,
-            content: req.content,
-            respond: tx,
-        })
+/// Run the retention policy now (`?dry_run=true` to only report).
+pub async fn run_retention(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    axum::extract::Query(query): axum::extract::Query<RetentionQuery>,
+) -> Result<Json<RetentionReport>, (StatusCode, String)> {
+    run_retention_sweep(&state, &user, query.dry_run)
         .await
-        .map_err(|_| {
-            (
-                StatusCode::SERVICE_UNAVAILABLE,
-                "control session unavailable".to_string(),
-            )
-        })?;
+        .map(Json)
+}
 
-    rx.await
-        .map_err(|_| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Failed to receive response".to_string(),
-            )
-        })?
-        .map(|_| Json(serde_json::json!({ "ok": true, "mission_id": mission_id })))
-        .map_err(|e| (StatusCode::CONFLICT, e))
+async fn run_retention_sweep(
+    state: &Arc<AppState>,
+    user: &AuthUser,
+    dry_run: bool,
+) -> Result<RetentionReport, (StatusCode, String)> {
+    let control = control_for_user(state, user).await;
+    let running: Vec<Uuid> = running_missions_of(&control)
+        .await?
+        .iter()
+        .map(|m| m.mission_id)
+        .collect();
+    Ok(mission_retention::sweep(
+        &control.mission_store,
+        &state.workspaces,
+        &state.config,
+        &running,
+        dry_run,
+    )
+    .await)
 }
 
-/// Cancel a specific mission.
-pub async fn cancel_mission(
+/// Download one of a mission's artifacts.
+pub async fn download_artifact(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<AuthUser>,
-    Path(mission_id): Path<Uuid>,
-) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    let (tx, rx) = oneshot::channel();
+    Path((mission_id, artifact_id)): Path<(Uuid, Uuid)>,
+) -> Result<axum::response::Response, (StatusCode, String)> {
+    use axum::response::IntoResponse;
 
     let control = control_for_user(&state, &user).await;
     control
-        .cmd_tx
-        .send(ControlCommand::CancelMission {
-            mission_id,
-            respond: tx,
-        })
+        .mission_store
+        .get_mission(mission_id)
         .await
-        .map_err(|_| {
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?
+        .ok_or_else(|| {
             (
-                StatusCode::SERVICE_UNAVAILABLE,
-                "control session unavailable".to_string(),
+                StatusCode::NOT_FOUND,
+                format!("Mission {} not found", mission_id),
+            )
+        })?;
+    let artifact = MissionArtifactStore::new(&state.config.working_dir)
+        .get(mission_id, artifact_id)
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                format!("Artifact {} not found", artifact_id),
             )
         })?;
 
-    rx.await
+    let file = tokio::fs::File::open(&artifact.path).await.map_err(|e| {
+        (
+            StatusCode::NOT_FOUND,
+            format!("Artifact file {} not found: {}", artifact.path.display(), e),
+        )
+    })?;
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        axum::http::header::CONTENT_DISPOSITION,
+        format!(
+            "attachment; filename=\"{}\"",
+            artifact.name.replace('"', "")
+        )
+        .parse()
         .map_err(|_| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                "Failed to receive response".to_string(),
+                "Invalid artifact name".to_string(),
             )
-        })?
-        .map(|_| Json(serde_json::json!({ "ok": true, "cancelled": mission_id })))
-        .map_err(|e| (StatusCode::NOT_FOUND, e))
+        })?,
+    );
+    headers.insert(
+        axum::http::header::CONTENT_TYPE,
+        super::fs::content_type_for_path(&artifact.path)
+            .parse()
+            .unwrap(),
+    );
+    let body = axum::body::Body::from_stream(tokio_util::io::ReaderStream::new(file));
+    Ok((headers, body).into_response())
 }
 
-/// Request body for resuming a mission
-#[derive(Debug, Deserialize, Default)]
-pub struct ResumeMissionRequest {
-    /// If true, clean the mission's work directory before resuming
-    #[serde(default)]
-    pub clean_workspace: bool,
-    /// If true, only update the mission status without sending the "MISSION RESUMED" message.
-    /// Useful when the user is about to send their own custom message.
-    #[serde(default)]
-    pub skip_message: bool,
+/// Rebuild parallel runners from the snapshots of a previous server run.
+///
+/// Only missions in this session's store are restored; snapshots of missions
+/// that have since ended are dropped. A runner whose turn was cut off marks
+/// its mission interrupted so it can be resumed.
+async fn restore_parallel_runners(
+    snapshots: &Arc<MissionSnapshotStore>,
+    mission_store: &Arc<dyn MissionStore>,
+    workspaces: &workspace::SharedWorkspaceStore,
+    events_tx: &broadcast::Sender<AgentEvent>,
+    runners: &mut HashMap<Uuid, MissionRunner>,
+) {
+    for snapshot in snapshots.load_all() {
+        let mission_id = snapshot.mission_id;
+        let mission = match mission_store.get_mission(mission_id).await {
+            Ok(Some(mission)) => mission,
+            _ => continue,
+        };
+        if !matches!(
+            mission.status,
+            MissionStatus::Pending | MissionStatus::Active | MissionStatus::Interrupted
+        ) {
+            snapshots.remove(mission_id);
+            continue;
+        }
+
+        let was_running = snapshot.was_running();
+        let runner = MissionRunner::restore(snapshot, Arc::clone(snapshots))
+            .with_retry_policy(mission.retry_policy.clone())
+            .with_concurrency(mission.max_concurrent_messages)
+            .with_workspace_limit(workspace_limit(workspaces, mission.workspace_id).await);
+        if was_running {
+            let entries = runner.stored_history();
+            if let Err(e) = mission_store
+                .update_mission_history(mission_id, &entries)
+                .await
+            {
+                tracing::warn!("Failed to persist restored mission history: {}", e);
+            }
+            if mission.status != MissionStatus::Interrupted
+                && mission_store
+                    .update_mission_status(mission_id, MissionStatus::Interrupted)
+                    .await
+                    .is_ok()
+            {
+                let _ = events_tx.send(AgentEvent::MissionStatusChanged {
+                    mission_id,
+                    status: MissionStatus::Interrupted,
+                    summary: Some(
+                        "Interrupted: server restarted while mission was active".to_string(),
+                    ),
+                });
+            }
+        }
+        tracing::info!(
+            "Restored runner for mission {} ({} queued messages{})",
+            mission_id,
+            runner.queue.len(),
+            if was_running {
+                ", turn interrupted"
+            } else {
+                ""
+            }
+        );
+        runners.insert(mission_id, runner);
+    }
 }
 
 /// Resume an interrupted mission.
@@ -1787,20 +3901,74 @@ pub async fn resume_mission(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<AuthUser>,
     Path(mission_id): Path<Uuid>,
+    axum::extract::Query(query): axum::extract::Query<ResumeMissionQuery>,
     body: Option<Json<ResumeMissionRequest>>,
 ) -> Result<Json<Mission>, (StatusCode, String)> {
+    let control = control_for_user(&state, &user).await;
+    // A paused mission just carries on where it was
+    if super::mission_pause::is_paused(mission_id) {
+        if query.checkpoint.is_some() {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "Mission is paused: resume it without a checkpoint, or cancel it first to restore one"
+                    .to_string(),
+            ));
+        }
+        let mission = control
+            .mission_store
+            .get_mission(mission_id)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?
+            .ok_or_else(|| {
+                (
+                    StatusCode::NOT_FOUND,
+                    format!("Mission {} not found", mission_id),
+                )
+            })?;
+        if super::mission_pause::resume(mission_id) {
+            let _ = control.events_tx.send(AgentEvent::MissionPaused {
+                mission_id,
+                paused: false,
+            });
+        }
+        return Ok(Json(mission));
+    }
+
     let (clean_workspace, skip_message) = body
         .map(|b| (b.clean_workspace, b.skip_message))
         .unwrap_or((false, false));
+    let checkpoint = match query.checkpoint.as_deref() {
+        None => None,
+        Some(value) => {
+            let checkpoints = MissionCheckpointStore::new(&state.config.working_dir);
+            let checkpoint = if value == "latest" {
+                checkpoints.latest(mission_id)
+            } else {
+                let checkpoint_id = Uuid::parse_str(value).map_err(|_| {
+                    (
+                        StatusCode::BAD_REQUEST,
+                        "checkpoint must be a checkpoint id or 'latest'".to_string(),
+                    )
+                })?;
+                checkpoints.get(mission_id, checkpoint_id)
+            };
+            Some(Box::new(checkpoint.ok_or_else(|| {
+                (
+                    StatusCode::NOT_FOUND,
+                    format!("Checkpoint {} not found", value),
+                )
+            })?))
+        }
+    };
     let (tx, rx) = oneshot::channel();
 
-    let control = control_for_user(&state, &user).await;
     control
         .cmd_tx
         .send(ControlCommand::ResumeMission {
             mission_id,
             clean_workspace,
             skip_message,
+            checkpoint,
             respond: tx,
         })
         .await
@@ -1898,6 +4066,10 @@ pub async fn delete_mission(
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
 
     if deleted {
+        super::mission_pause::resume(mission_id);
+        control.journal.remove_mission(mission_id);
+        MissionCheckpointStore::new(&state.config.working_dir).remove_all(mission_id);
+        MissionArtifactStore::new(&state.config.working_dir).remove_all(mission_id);
         Ok(Json(serde_json::json!({
             "ok": true,
             "deleted": mission_id
@@ -1950,18 +4122,41 @@ pub async fn cleanup_empty_missions(
     })))
 }
 
+/// Query parameters of the control event stream.
+#[derive(Debug, Default, Deserialize)]
+pub struct StreamQuery {
+    /// Only stream this mission's events
+    pub mission_id: Option<Uuid>,
+    /// Resume after this event id, like the `Last-Event-ID` header
+    pub last_event_id: Option<u64>,
+}
+
 /// Stream control session events via SSE.
+///
+/// Events carry their journal id. A client reconnecting with
+/// `Last-Event-ID` (or `?last_event_id=`) first receives the events it
+/// missed.
 pub async fn stream(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<AuthUser>,
+    headers: HeaderMap,
+    axum::extract::Query(query): axum::extract::Query<StreamQuery>,
 ) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, (StatusCode, String)> {
     let control = control_for_user(&state, &user).await;
-    let mut rx = control.events_tx.subscribe();
+    let journal = Arc::clone(&control.journal);
+    let mut latest = journal.subscribe();
+    let resume_after = headers
+        .get("last-event-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .or(query.last_event_id);
+    let mission_filter = query.mission_id;
     let stream_id = Uuid::new_v4();
     tracing::info!(
         stream_id = %stream_id,
         user_id = %user.id,
         username = %user.username,
+        resume_after = ?resume_after,
         "Control SSE stream opened"
     );
 
@@ -1972,6 +4167,7 @@ pub async fn stream(
         stream_id: Uuid,
         user_id: String,
         username: String,
+        _subscriber: super::metrics::SubscriberGuard,
     }
 
     impl Drop for StreamDropGuard {
@@ -1989,6 +4185,7 @@ pub async fn stream(
         stream_id,
         user_id: user.id.clone(),
         username: user.username.clone(),
+        _subscriber: super::metrics::global().subscriber(false),
     };
 
     let stream = async_stream::stream! {
@@ -2003,45 +4200,63 @@ pub async fn stream(
         let mut keepalive_interval = tokio::time::interval(std::time::Duration::from_secs(15));
         keepalive_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
 
+        let mut last_id = resume_after.unwrap_or_else(|| *latest.borrow());
         loop {
+            let upto = *latest.borrow_and_update();
+            let replay = match journal.after(last_id, mission_filter) {
+                Some(entries) => entries,
+                None => match mission_filter {
+                    // Older than what's held in memory: a mission's events
+                    // are read back from its log.
+                    Some(mid) if journal.is_persistent() => {
+                        let entries = journal.read_mission_log(mid, last_id).await;
+                        last_id = last_id.max(upto);
+                        entries
+                    }
+                    _ => {
+                        tracing::warn!(
+                            stream_id = %stream_id,
+                            after = last_id,
+                            "Control SSE stream can't replay missed events"
+                        );
+                        let sse = Event::default()
+                            .event("error")
+                            .json_data(AgentEvent::Error { message: "event stream lagged; some events were dropped".to_string(), mission_id: mission_filter, resumable: false })
+                            .unwrap();
+                        yield Ok(sse);
+                        last_id = upto;
+                        Vec::new()
+                    }
+                },
+            };
+            for entry in replay {
+                last_id = last_id.max(entry.id);
+                if matches!(entry.event.as_str(), "thinking" | "diagnostic") {
+                    tracing::trace!(
+                        stream_id = %stream_id,
+                        event = %entry.event,
+                        mission_id = ?entry.mission_id,
+                        "Control SSE event"
+                    );
+                } else {
+                    tracing::debug!(
+                        stream_id = %stream_id,
+                        event = %entry.event,
+                        mission_id = ?entry.mission_id,
+                        "Control SSE event"
+                    );
+                }
+                let sse = Event::default()
+                    .id(entry.id.to_string())
+                    .event(entry.event)
+                    .data(entry.data);
+                yield Ok(sse);
+            }
+
             tokio::select! {
-                result = rx.recv() => {
-                    match result {
-                        Ok(ev) => {
-                            let mission_id = ev.mission_id();
-                            match &ev {
-                                AgentEvent::Thinking { .. } => {
-                                    tracing::trace!(
-                                        stream_id = %stream_id,
-                                        event = %ev.event_name(),
-                                        mission_id = ?mission_id,
-                                        "Control SSE event"
-                                    );
-                                }
-                                _ => {
-                                    tracing::debug!(
-                                        stream_id = %stream_id,
-                                        event = %ev.event_name(),
-                                        mission_id = ?mission_id,
-                                        "Control SSE event"
-                                    );
-                                }
-                            }
-                            let sse = Event::default().event(ev.event_name()).json_data(&ev).unwrap();
-                            yield Ok(sse);
-                        }
-                        Err(broadcast::error::RecvError::Lagged(_)) => {
-                            tracing::warn!(
-                                stream_id = %stream_id,
-                                "Control SSE stream lagged; events dropped"
-                            );
-                            let sse = Event::default()
-                                .event("error")
-                                .json_data(AgentEvent::Error { message: "event stream lagged; some events were dropped".to_string(), mission_id: None, resumable: false })
-                                .unwrap();
-                            yield Ok(sse);
-                        }
-                        Err(broadcast::error::RecvError::Closed) => break,
+                changed = latest.changed() => {
+                    if changed.is_err() {
+                        break;
                     }
                 }
                 _ = keepalive_interval.tick() => {
@@ -2060,7 +4275,8 @@ pub async fn stream(
     ))
 }
 
-/// Spawn the global control session actor.
+/// Spawn a control session actor. `session_dir` is the working directory of
+/// a named session (the server's working directory if unset).
 fn spawn_control_session(
     config: Config,
     root_agent: AgentRef,
@@ -2069,10 +4285,20 @@ fn spawn_control_session(
     library: SharedLibrary,
     mission_store: Arc<dyn MissionStore>,
     secrets: Option<Arc<SecretsStore>>,
+    session_dir: Option<std::path::PathBuf>,
+    history_store: Option<ControlHistoryStore>,
 ) -> ControlState {
     let (cmd_tx, cmd_rx) = mpsc::channel::<ControlCommand>(256);
     let (events_tx, events_rx) = broadcast::channel::<AgentEvent>(1024);
-    let tool_hub = Arc::new(FrontendToolHub::new());
+    let journal = Arc::new(EventJournal::new(
+        mission_store
+            .is_persistent()
+            .then(|| event_journal::journal_dir(&config.working_dir)),
+    ));
+    event_journal::spawn_writer(Arc::clone(&journal), events_tx.subscribe());
+    let tool_hub = Arc::new(FrontendToolHub::with_timeouts(
+        config.frontend_tool_timeouts.clone(),
+    ));
     let status = Arc::new(RwLock::new(ControlStatus {
         state: ControlRunState::Idle,
         queue_len: 0,
@@ -2088,6 +4314,8 @@ fn spawn_control_session(
     let progress = Arc::new(RwLock::new(ExecutionProgress::default()));
     let running_missions = Arc::new(RwLock::new(Vec::new()));
     let max_parallel = config.max_parallel_missions;
+    // Lets background tasks message the actor without keeping it alive
+    let actor_cmd_tx = cmd_tx.downgrade();
 
     let state = ControlState {
         cmd_tx,
@@ -2100,6 +4328,7 @@ fn spawn_control_session(
         running_missions: Arc::clone(&running_missions),
         max_parallel,
         mission_store: Arc::clone(&mission_store),
+        journal,
     };
 
     // Spawn the main control actor
@@ -2110,6 +4339,7 @@ fn spawn_control_session(
         workspaces.clone(),
         library.clone(),
         cmd_rx,
+        actor_cmd_tx,
         mission_cmd_rx,
         mission_cmd_tx,
         events_tx.clone(),
@@ -2121,6 +4351,8 @@ fn spawn_control_session(
         progress,
         mission_store,
         secrets,
+        session_dir,
+        history_store,
     ));
 
     // Recover orphaned missions from previous run.
@@ -2204,6 +4436,7 @@ fn spawn_control_session(
                     }
                     Err(broadcast::error::RecvError::Lagged(n)) => {
                         tracing::warn!("Event logger lagged by {} events", n);
+                        super::metrics::global().lag_dropped("event_logger", n);
                     }
                     Err(broadcast::error::RecvError::Closed) => break,
                 }
@@ -2598,6 +4831,7 @@ async fn automation_scheduler_loop(
                         content: substituted_content.clone(),
                         agent: None,
                         target_mission_id: Some(mission.id),
+                        priority: None,
                         respond: respond_tx,
                     })
                     .await;
@@ -2689,6 +4923,7 @@ async fn control_actor_loop(
     workspaces: workspace::SharedWorkspaceStore,
     library: SharedLibrary,
     mut cmd_rx: mpsc::Receiver<ControlCommand>,
+    cmd_tx: mpsc::WeakSender<ControlCommand>,
     mut mission_cmd_rx: mpsc::Receiver<crate::tools::mission::MissionControlCommand>,
     mission_cmd_tx: mpsc::Sender<crate::tools::mission::MissionControlCommand>,
     events_tx: broadcast::Sender<AgentEvent>,
@@ -2700,10 +4935,13 @@ async fn control_actor_loop(
     progress: Arc<RwLock<ExecutionProgress>>,
     mission_store: Arc<dyn MissionStore>,
     secrets: Option<Arc<SecretsStore>>,
+    session_dir: Option<std::path::PathBuf>,
+    history_store: Option<ControlHistoryStore>,
 ) {
     // Queue stores (id, content, agent, target_mission_id) for the current/primary mission
     // The target_mission_id tracks which mission each queued message is intended for
-    let mut queue: VecDeque<(Uuid, String, Option<String>, Option<Uuid>)> = VecDeque::new();
+    let mut queue: VecDeque<(Uuid, String, Option<String>, Option<Uuid>, MissionPriority)> =
+        VecDeque::new();
     let mut history: Vec<(String, String)> = Vec::new(); // (role, content) pairs (user/assistant)
     let mut running: Option<tokio::task::JoinHandle<(Uuid, String, crate::agents::AgentResult)>> =
         None;
@@ -2717,6 +4955,8 @@ async fn control_actor_loop(
     let mut main_runner_activity: Option<String> = None;
     // Track subtasks for the main runner
     let mut main_runner_subtasks: Vec<super::mission_runner::SubtaskInfo> = Vec::new();
+    // Workspace slot of the main runner's turn
+    let mut main_slot = MainWorkspaceSlot::default();
 
     // Parallel mission runners - each runs independently
     let mut parallel_runners: std::collections::HashMap<
@@ -2724,6 +4964,43 @@ async fn control_actor_loop(
         super::mission_runner::MissionRunner,
     > = std::collections::HashMap::new();
 
+    // Runner state is persisted so runners survive a server restart
+    let runner_snapshots = Arc::new(MissionSnapshotStore::new(&config.working_dir));
+    let checkpoints = Arc::new(MissionCheckpointStore::new(&config.working_dir));
+    let artifacts = Arc::new(MissionArtifactStore::new(&config.working_dir));
+    // Wall-clock limits of the mission runs in progress
+    let mut mission_runs: std::collections::HashMap<Uuid, MissionRun> =
+        std::collections::HashMap::new();
+    let mut mission_limits_refreshed = std::time::Instant::now();
+    // Summaries of the main session's mission histories that outgrew the context
+    let mut history_summaries: std::collections::HashMap<Uuid, SharedHistorySummary> =
+        std::collections::HashMap::new();
+    // Escalation state of stalled missions
+    let mut stall_monitor = StallMonitor::new(config.mission_stall_actions.clone());
+    // Missions whose turn was interrupted by a steering message
+    let mut steered: std::collections::HashSet<Uuid> = std::collections::HashSet::new();
+    if mission_store.is_persistent() {
+        restore_parallel_runners(
+            &runner_snapshots,
+            &mission_store,
+            &workspaces,
+            &events_tx,
+            &mut parallel_runners,
+        )
+        .await;
+    }
+    // Pick up the conversation where the previous server run left it
+    if let Some(saved) = history_store.as_ref().and_then(ControlHistoryStore::load) {
+        history = saved.messages();
+        *current_mission.write().await = saved.current_mission;
+        tracing::info!(
+            "Restored {} control history entries (mission {:?})",
+            history.len(),
+            saved.current_mission
+        );
+    }
+    let mut saved_history = control_history::fingerprint(&history, *current_mission.read().await);
+
     // Helper to extract file paths from text (for mission summaries)
     fn extract_file_paths(text: &str) -> Vec<String> {
         let mut paths = Vec::new();
@@ -2829,6 +5106,24 @@ async fn control_actor_loop(
             .ok_or_else(|| format!("Mission {} not found", id))
     }
 
+    // Helper to pick a message's queue priority: the explicit one, else its mission's
+    async fn message_priority(
+        mission_store: &Arc<dyn MissionStore>,
+        explicit: Option<MissionPriority>,
+        mission_id: Option<Uuid>,
+    ) -> MissionPriority {
+        if let Some(priority) = explicit {
+            return priority;
+        }
+        match mission_id {
+            Some(id) => match mission_store.get_mission(id).await {
+                Ok(Some(mission)) => mission.priority,
+                _ => MissionPriority::default(),
+            },
+            None => MissionPriority::default(),
+        }
+    }
+
     // Helper to create a new mission
     async fn create_new_mission(mission_store: &Arc<dyn MissionStore>) -> Result<Mission, String> {
         create_new_mission_with_title(mission_store, None, None, None, None, None, None).await
@@ -2863,8 +5158,9 @@ async fn control_actor_loop(
         workspaces: &workspace::SharedWorkspaceStore,
         mission_id: Uuid,
         clean_workspace: bool,
+        checkpoint: Option<&MissionCheckpoint>,
     ) -> Result<(Mission, String), String> {
-        let mission = load_mission_record(mission_store, mission_id).await?;
+        let mut mission = load_mission_record(mission_store, mission_id).await?;
 
         // Check if mission can be resumed (interrupted, blocked, or failed)
         // Failed missions can be resumed to retry after transient errors (e.g., 529 overloaded)
@@ -2878,6 +5174,27 @@ async fn control_actor_loop(
             ));
         }
 
+        // Roll the conversation and backend session back to the checkpoint
+        if let Some(checkpoint) = checkpoint {
+            mission_store
+                .update_mission_history(mission_id, &checkpoint.history)
+                .await?;
+            if let Some(session_id) = &checkpoint.session_id {
+                mission_store
+                    .update_mission_session_id(mission_id, session_id)
+                    .await?;
+            }
+            tracing::info!(
+                mission_id = %mission_id,
+                checkpoint_id = %checkpoint.id,
+                history_len = checkpoint.history.len(),
+                "Rolled mission back to checkpoint"
+            );
+            mission.history = checkpoint.history.clone();
+            mission.session_id = checkpoint.session_id.clone().or(mission.session_id);
+        }
+        mission_budget::track(mission_id, mission.budget_cents, mission.spent_cents);
+
         let workspace_root =
             workspace::resolve_workspace_root(workspaces, config, Some(mission.workspace_id)).await;
 
@@ -2931,6 +5248,10 @@ async fn control_actor_loop(
             ));
         }
 
+        if let Some(checkpoint) = checkpoint {
+            resume_parts.push(checkpoint.resume_note());
+        }
+
         // Add history summary
         if !mission.history.is_empty() {
             resume_parts.push("\n## Previous Conversation Summary".to_string());
@@ -3024,11 +5345,13 @@ async fn control_actor_loop(
     }
 
     loop {
+        // Set when the main session should start its next queued message
+        let mut start_queued = false;
         tokio::select! {
             cmd = cmd_rx.recv() => {
                 let Some(cmd) = cmd else { break };
                 match cmd {
-                    ControlCommand::UserMessage { id, content, agent: msg_agent, target_mission_id, respond } => {
+                    ControlCommand::UserMessage { id, content, agent: msg_agent, target_mission_id, priority, respond } => {
                         // Smart routing: decide where to send this message based on target_mission_id
                         // and what's currently running.
 
@@ -3059,6 +5382,26 @@ async fn control_actor_loop(
                             None
                         });
 
+                        // Refuse new work for a mission whose budget is used up
+                        if let Some(mid) = effective_target.or(main_mission_id) {
+                            if let Ok(Some(mission)) = mission_store.get_mission(mid).await {
+                                mission_budget::track(mid, mission.budget_cents, mission.spent_cents);
+                                if is_exhausted(mission.budget_cents, mission.spent_cents) {
+                                    let _ = events_tx.send(AgentEvent::Error {
+                                        message: format!(
+                                            "Mission budget of {} is used up ({} spent); raise it to continue",
+                                            format_cents(mission.budget_cents.unwrap_or_default()),
+                                            format_cents(mission.spent_cents)
+                                        ),
+                                        mission_id: Some(mid),
+                                        resumable: false,
+                                    });
+                                    let _ = respond.send(false);
+                                    continue;
+                                }
+                            }
+                        }
+
                         // Determine if target is already running somewhere
                         let target_in_parallel = effective_target
                             .map(|tid| parallel_runners.contains_key(&tid))
@@ -3072,15 +5415,16 @@ async fn control_actor_loop(
                             if target_in_parallel {
                                 if let Some(runner) = parallel_runners.get_mut(&tid) {
                                     let was_running = runner.is_running();
-                                    runner.queue_message(id, content.clone(), msg_agent);
+                                    let priority = message_priority(&mission_store, priority, Some(tid)).await;
+                                    runner.queue_message(id, content.clone(), msg_agent, priority);
                                     let _ = events_tx.send(AgentEvent::UserMessage {
                                         id,
                                         content: content.clone(),
                                         queued: was_running,
                                         mission_id: Some(tid),
                                     });
-                                    // Try to start if not already running
-                                    if !runner.is_running() {
+                                    // Try to start if not already running (or a concurrent slot is free)
+                                    if runner.has_free_slot() {
                                         runner.start_next(
                                             config.clone(),
                                             Arc::clone(&root_agent),
@@ -3149,13 +5493,22 @@ async fn control_actor_loop(
                                                 Some(mission.backend.clone()),
                                                 mission.session_id.clone(),
                                                 mission.config_profile.clone(),
-                                            );
+                                            )
+                                            .with_retry_policy(mission.retry_policy.clone())
+                                            .with_concurrency(mission.max_concurrent_messages)
+                                            .with_workspace_limit(workspace_limit(&workspaces, mission.workspace_id).await)
+                                            .with_snapshots(Arc::clone(&runner_snapshots));
                                             // Load existing history
                                             for entry in &mission.history {
-                                                runner.history.push((entry.role.clone(), entry.content.clone()));
+                                                runner.history.push(ConversationEntry::message(&entry.role, &entry.content));
                                             }
                                             // Queue the message
-                                            runner.queue_message(id, content.clone(), msg_agent);
+                                            runner.queue_message(
+                                                id,
+                                                content.clone(),
+                                                msg_agent,
+                                                priority.unwrap_or(mission.priority),
+                                            );
                                             // Emit user message event
                                             let _ = events_tx.send(AgentEvent::UserMessage {
                                                 id,
@@ -3327,7 +5680,12 @@ async fn control_actor_loop(
                         // Capture the target mission ID once, before queuing
                         // This ensures we use the same mission_id for events and execution
                         let target_mission_id = current_mission.read().await.clone();
-                        queue.push_back((id, content, msg_agent, target_mission_id));
+                        let priority = message_priority(&mission_store, priority, target_mission_id).await;
+                        insert_by_priority(
+                            &mut queue,
+                            (id, content, msg_agent, target_mission_id, priority),
+                            |queued| queued.4,
+                        );
                         let status_mission_id = if running.is_some() {
                             running_mission_id
                         } else {
@@ -3340,7 +5698,11 @@ async fn control_actor_loop(
                             queue.len(),
                             status_mission_id,
                         ).await;
-                        if was_running {
+                        // An idle session still waits for a slot in the mission's workspace
+                        let waiting_for_workspace = running.is_none()
+                            && !shutdown::is_draining()
+                            && !main_slot.acquire(&mission_store, &workspaces, queue.front().and_then(|queued| queued.3)).await;
+                        if was_running || waiting_for_workspace {
                             let _ = events_tx.send(AgentEvent::UserMessage {
                                 id,
                                 content: content_clone,
@@ -3348,8 +5710,8 @@ async fn control_actor_loop(
                                 mission_id: target_mission_id,
                             });
                         }
-                        if running.is_none() {
-                            if let Some((mid, msg, per_msg_agent, msg_target_mid)) = queue.pop_front() {
+                        if running.is_none() && !waiting_for_workspace && !shutdown::is_draining() {
+                            if let Some((mid, msg, per_msg_agent, msg_target_mid, _)) = queue.pop_front() {
                                 set_and_emit_status(
                                     &status,
                                     &events_tx,
@@ -3383,6 +5745,7 @@ async fn control_actor_loop(
                                 // Use the mission ID that was captured when message was queued
                                 // This prevents race conditions where current_mission changes between queueing and execution
                                 let mission_id = msg_target_mid;
+                                let history_summary = mission_id.map(|mid| Arc::clone(history_summaries.entry(mid).or_default()));
                                 let (workspace_id, model_override, mission_agent, backend_id, session_id, mission_config_profile) = if let Some(mid) = mission_id {
                                     match mission_store.get_mission(mid).await {
                                         Ok(Some(mission)) => {
@@ -3446,6 +5809,8 @@ async fn control_actor_loop(
                                 main_runner_last_activity = std::time::Instant::now();
                                 main_runner_activity = None;
                                 main_runner_subtasks.clear();
+                                let turn_dir = session_dir.clone();
+                                let turn_secrets = secrets.clone();
                                 running = Some(tokio::spawn(async move {
                                     let result = run_single_control_turn(
                                         cfg,
@@ -3458,6 +5823,7 @@ async fn control_actor_loop(
                                         status_ref,
                                         cancel,
                                         hist_snapshot,
+                                        history_summary,
                                         msg.clone(),
                                         Some(mission_ctrl),
                                         tree_ref,
@@ -3470,6 +5836,8 @@ async fn control_actor_loop(
                                         session_id,
                                         false, // force_session_resume: regular message, not a resume
                                         mission_config_profile,
+                                        turn_dir,
+                                        turn_secrets,
                                     )
                                     .await;
                                     (mid, msg, result)
@@ -3478,7 +5846,7 @@ async fn control_actor_loop(
                                 set_and_emit_status(&status, &events_tx, ControlRunState::Idle, 0, None).await;
                             }
                         }
-                        let _ = respond.send(was_running);
+                        let _ = respond.send(was_running || waiting_for_workspace);
                     }
                     ControlCommand::ToolResult { tool_call_id, name, result } => {
                         // Deliver to the tool hub. resolve() caches the result if
@@ -3609,6 +5977,9 @@ async fn control_actor_loop(
                                 status: new_status,
                                 summary: None,
                             });
+                            if new_status == MissionStatus::Completed {
+                                spawn_deliverable_verification(&mission_store, &workspaces, &config, &events_tx, id);
+                            }
                         }
                         let _ = respond.send(result);
                     }
@@ -3653,15 +6024,19 @@ async fn control_actor_loop(
                                 Some(mission.backend.clone()),
                                 mission.session_id.clone(),
                                 mission.config_profile.clone(),
-                            );
+                            )
+                            .with_retry_policy(mission.retry_policy.clone())
+                            .with_concurrency(mission.max_concurrent_messages)
+                            .with_workspace_limit(workspace_limit(&workspaces, mission.workspace_id).await)
+                            .with_snapshots(Arc::clone(&runner_snapshots));
 
                             // Load existing history into runner to preserve conversation context
                             for entry in &mission.history {
-                                runner.history.push((entry.role.clone(), entry.content.clone()));
+                                runner.history.push(ConversationEntry::message(&entry.role, &entry.content));
                             }
 
                             // Queue the initial message (no per-message agent override for parallel start)
-                            runner.queue_message(Uuid::new_v4(), content, None);
+                            runner.queue_message(Uuid::new_v4(), content, None, mission.priority);
 
                             // Start execution
                             let started = runner.start_next(
@@ -3682,7 +6057,12 @@ async fn control_actor_loop(
                                 tracing::info!("Mission {} started in parallel", mission_id);
                                 parallel_runners.insert(mission_id, runner);
                                 let _ = respond.send(Ok(()));
+                            } else if runner.is_waiting_for_workspace() {
+                                // Starts once another mission leaves the workspace
+                                parallel_runners.insert(mission_id, runner);
+                                let _ = respond.send(Ok(()));
                             } else {
+                                runner.discard_snapshot();
                                 let _ = respond.send(Err("Failed to start mission execution".to_string()));
                             }
                         }
@@ -3696,6 +6076,8 @@ async fn control_actor_loop(
                                 mission_id: Some(mission_id),
                                 resumable: true, // Cancelled missions can be resumed
                             });
+                            runner.discard_snapshot();
+                            runner.release_workspace();
                             parallel_runners.remove(&mission_id);
                             close_mission_desktop_sessions(
                                 &mission_store,
@@ -3730,6 +6112,76 @@ async fn control_actor_loop(
                             }
                         }
                     }
+                    ControlCommand::SteerMission { mission_id, content, respond } => {
+                        let is_main = running.is_some() && running_mission_id == Some(mission_id);
+                        let parallel = parallel_runners.get(&mission_id);
+                        if !is_main && !parallel.is_some_and(|runner| runner.is_running()) {
+                            let _ = respond.send(Err(format!("Mission {} is not running", mission_id)));
+                        } else if parallel.is_some_and(|runner| runner.max_concurrent > 1) {
+                            let _ = respond.send(Err(
+                                "Cannot steer a mission that processes messages concurrently".to_string(),
+                            ));
+                        } else {
+                            let id = Uuid::new_v4();
+                            let injected = super::mission_steer::steer(mission_id, content.clone());
+                            if injected {
+                                if let Some(runner) = parallel_runners.get_mut(&mission_id) {
+                                    runner.history.push(ConversationEntry::user(content.clone()));
+                                } else {
+                                    if *current_mission.read().await == Some(mission_id) {
+                                        history.push(("user".to_string(), content.clone()));
+                                    }
+                                    match mission_store.get_mission(mission_id).await {
+                                        Ok(Some(mission)) => {
+                                            let mut entries = mission.history;
+                                            entries.push(MissionHistoryEntry {
+                                                role: "user".to_string(),
+                                                content: content.clone(),
+                                            });
+                                            if let Err(e) = mission_store
+                                                .update_mission_history(mission_id, &entries)
+                                                .await
+                                            {
+                                                tracing::warn!("Failed to persist steering message: {}", e);
+                                            }
+                                        }
+                                        Ok(None) => {}
+                                        Err(e) => tracing::warn!(
+                                            "Failed to load mission {} for steering message: {}",
+                                            mission_id,
+                                            e
+                                        ),
+                                    }
+                                }
+                            } else {
+                                // The backend can't take input mid-turn: stop the turn and
+                                // continue its session with the message
+                                if is_main {
+                                    queue.push_front((
+                                        id,
+                                        content.clone(),
+                                        None,
+                                        Some(mission_id),
+                                        MissionPriority::Urgent,
+                                    ));
+                                    if let Some(token) = &running_cancel {
+                                        token.cancel();
+                                    }
+                                } else if let Some(runner) = parallel_runners.get_mut(&mission_id) {
+                                    runner.queue_message_front(id, content.clone(), None);
+                                    runner.cancel();
+                                }
+                                steered.insert(mission_id);
+                            }
+                            let _ = events_tx.send(AgentEvent::UserMessage {
+                                id,
+                                content,
+                                queued: !injected,
+                                mission_id: Some(mission_id),
+                            });
+                            let _ = respond.send(Ok(injected));
+                        }
+                    }
                     ControlCommand::ListRunning { respond } => {
                         // Return info about currently running missions
                         let mut running_list = Vec::new();
@@ -3755,22 +6207,46 @@ async fn control_actor_loop(
                                 } else {
                                     super::mission_runner::MissionRunState::Running
                                 };
+                                let paused = super::mission_pause::is_paused(mission_id);
                                 running_list.push(super::mission_runner::RunningMissionInfo {
                                     mission_id,
                                     state: state_label.to_string(),
                                     queue_len: queue.len(),
                                     history_len: history.len(),
                                     seconds_since_activity,
-                                    health: super::mission_runner::running_health(
-                                        mission_state,
-                                        seconds_since_activity,
-                                    ),
+                                    health: if paused {
+                                        super::mission_runner::MissionHealth::Healthy
+                                    } else {
+                                        super::mission_runner::running_health(
+                                            mission_state,
+                                            seconds_since_activity,
+                                            stall_monitor.thresholds(mission_id),
+                                        )
+                                    },
                                     expected_deliverables: 0,
                                     current_activity: main_runner_activity.clone(),
                                     subtask_total: main_runner_subtasks.len(),
                                     subtask_completed: main_runner_subtasks.iter().filter(|s| s.completed).count(),
+                                    paused,
+                                    tags: Default::default(),
                                 });
                             }
+                        } else if let Some(mission_id) = main_slot.waiting_mission() {
+                            // Queued behind a full workspace
+                            running_list.push(super::mission_runner::RunningMissionInfo {
+                                mission_id,
+                                state: "waiting_for_workspace".to_string(),
+                                queue_len: queue.len(),
+                                history_len: history.len(),
+                                seconds_since_activity: 0,
+                                health: super::mission_runner::MissionHealth::Healthy,
+                                expected_deliverables: 0,
+                                current_activity: None,
+                                subtask_total: 0,
+                                subtask_completed: 0,
+                                paused: super::mission_pause::is_paused(mission_id),
+                                tags: Default::default(),
+                            });
                         }
 
                         // Add all parallel runners
@@ -3780,7 +6256,7 @@ async fn control_actor_loop(
 
                         let _ = respond.send(running_list);
                     }
-                    ControlCommand::ResumeMission { mission_id, clean_workspace, skip_message, respond } => {
+                    ControlCommand::ResumeMission { mission_id, clean_workspace, skip_message, checkpoint, respond } => {
                         // Resume an interrupted mission by building resume context
                         match resume_mission_impl(
                             &mission_store,
@@ -3788,8 +6264,53 @@ async fn control_actor_loop(
                             &workspaces,
                             mission_id,
                             clean_workspace,
+                            checkpoint.as_deref(),
                         )
                         .await {
+                            Ok((mission, resume_prompt)) if parallel_runners.contains_key(&mission_id) => {
+                                // Runner restored after a restart: resume it in place
+                                if let Err(e) = mission_store
+                                    .update_mission_status(mission_id, MissionStatus::Active)
+                                    .await
+                                {
+                                    tracing::warn!("Failed to resume mission {}: {}", mission_id, e);
+                                } else {
+                                    let _ = events_tx.send(AgentEvent::MissionStatusChanged {
+                                        mission_id,
+                                        status: MissionStatus::Active,
+                                        summary: None,
+                                    });
+                                }
+                                if let Some(runner) = parallel_runners.get_mut(&mission_id) {
+                                    if checkpoint.is_some() {
+                                        runner.history = mission.history.iter()
+                                            .map(|e| ConversationEntry::message(&e.role, &e.content))
+                                            .collect();
+                                        runner.session_id = mission.session_id.clone();
+                                    }
+                                    if !skip_message {
+                                        runner.queue_message_front(Uuid::new_v4(), resume_prompt, None);
+                                    }
+                                    runner.start_next(
+                                        config.clone(),
+                                        Arc::clone(&root_agent),
+                                        Arc::clone(&mcp),
+                                        Arc::clone(&workspaces),
+                                        library.clone(),
+                                        events_tx.clone(),
+                                        Arc::clone(&tool_hub),
+                                        Arc::clone(&status),
+                                        mission_cmd_tx.clone(),
+                                        Arc::new(RwLock::new(Some(mission_id))),
+                                        secrets.clone(),
+                                    );
+                                }
+                                let mut updated_mission = mission;
+                                updated_mission.status = MissionStatus::Active;
+                                updated_mission.resumable = false;
+                                updated_mission.interrupted_at = None;
+                                let _ = respond.send(Ok(updated_mission));
+                            }
                             Ok((mission, resume_prompt)) => {
                                 // First persist current mission history (if any)
                                 persist_mission_history(
@@ -3824,12 +6345,19 @@ async fn control_actor_loop(
                                 // Skip if the caller just wants to update the status (e.g., before sending a custom message)
                                 if !skip_message {
                                     let msg_id = Uuid::new_v4();
-                                    queue.push_back((msg_id, resume_prompt, None, Some(mission_id)));
+                                    insert_by_priority(
+                                        &mut queue,
+                                        (msg_id, resume_prompt, None, Some(mission_id), mission.priority),
+                                        |queued| queued.4,
+                                    );
                                 }
 
                                 // Start execution if not already running
-                                if running.is_none() {
-                                    if let Some((mid, msg, _per_msg_agent, msg_target_mid)) = queue.pop_front() {
+                                if running.is_none()
+                                    && !shutdown::is_draining()
+                                    && main_slot.acquire(&mission_store, &workspaces, queue.front().and_then(|queued| queued.3)).await
+                                {
+                                    if let Some((mid, msg, _per_msg_agent, msg_target_mid, _)) = queue.pop_front() {
                                         let target_mid = msg_target_mid.unwrap_or(mission_id);
                                         set_and_emit_status(
                                             &status,
@@ -3862,11 +6390,14 @@ async fn control_actor_loop(
                                         let agent_override = mission.agent.clone();
                                         let session_id = mission.session_id.clone();
                                         let mission_config_profile = mission.config_profile.clone();
+                                        let history_summary = Some(Arc::clone(history_summaries.entry(mission_id).or_default()));
                                         running_cancel = Some(cancel.clone());
                                         // Capture which mission this task is working on (the resumed mission)
                                         running_mission_id = Some(mission_id);
                                         main_runner_activity = None;
                                         main_runner_subtasks.clear();
+                                        let turn_dir = session_dir.clone();
+                                        let turn_secrets = secrets.clone();
                                         running = Some(tokio::spawn(async move {
                                             let result = run_single_control_turn(
                                                 cfg,
@@ -3879,6 +6410,7 @@ async fn control_actor_loop(
                                                 status_ref,
                                                 cancel,
                                                 hist_snapshot,
+                                                history_summary,
                                                 msg.clone(),
                                                 Some(mission_ctrl),
                                                 tree_ref,
@@ -3891,6 +6423,8 @@ async fn control_actor_loop(
                                                 session_id,
                                                 true, // force_session_resume: this is a resume operation
                                                 mission_config_profile,
+                                                turn_dir,
+                                                turn_secrets,
                                             )
                                             .await;
                                             (mid, msg, result)
@@ -3910,6 +6444,7 @@ async fn control_actor_loop(
                             }
                         }
                     }
+                    ControlCommand::Stop => break,
                     ControlCommand::GracefulShutdown { respond } => {
                         // Mark all running missions as interrupted
                         let mut interrupted_ids = Vec::new();
@@ -3937,6 +6472,15 @@ async fn control_actor_loop(
                                 // Note: If missions differ, don't persist - the local history
                                 // belongs to current_mission, not running_mission_id
 
+                                // Resume point for after the restart
+                                checkpoint_mission(
+                                    &checkpoints,
+                                    &mission_store,
+                                    &workspaces,
+                                    &config,
+                                    mission_id,
+                                )
+                                .await;
                                 if mission_store
                                     .update_mission_status(mission_id, MissionStatus::Interrupted)
                                     .await
@@ -3956,14 +6500,7 @@ async fn control_actor_loop(
                         // Handle parallel missions
                         for (mission_id, runner) in parallel_runners.iter_mut() {
                             // Persist history for parallel mission
-                            let entries: Vec<MissionHistoryEntry> = runner
-                                .history
-                                .iter()
-                                .map(|(role, content)| MissionHistoryEntry {
-                                    role: role.clone(),
-                                    content: content.clone(),
-                                })
-                                .collect();
+                            let entries = runner.stored_history();
                             if let Err(e) = mission_store
                                 .update_mission_history(*mission_id, &entries)
                                 .await
@@ -3974,6 +6511,17 @@ async fn control_actor_loop(
                                     e
                                 );
                             }
+                            // Idle runners were checkpointed after their last turn
+                            if runner.is_running() {
+                                checkpoint_mission(
+                                    &checkpoints,
+                                    &mission_store,
+                                    &workspaces,
+                                    &config,
+                                    *mission_id,
+                                )
+                                .await;
+                            }
                             if mission_store
                                 .update_mission_status(*mission_id, MissionStatus::Interrupted)
                                 .await
@@ -3988,15 +6536,43 @@ async fn control_actor_loop(
 
                         let _ = respond.send(interrupted_ids);
                     }
+                    ControlCommand::GetHistory { respond } => {
+                        let _ = respond.send(ControlHistoryResponse {
+                            current_mission: *current_mission.read().await,
+                            messages: history
+                                .iter()
+                                .map(|(role, content)| MissionHistoryEntry {
+                                    role: role.clone(),
+                                    content: content.clone(),
+                                })
+                                .collect(),
+                        });
+                    }
+                    ControlCommand::TruncateHistory { keep, respond } => {
+                        let removed = control_history::truncate(&mut history, keep);
+                        if removed > 0 {
+                            // The summary covered entries that are gone now
+                            if let Some(mid) = *current_mission.read().await {
+                                history_summaries.remove(&mid);
+                            }
+                            if let Some(store) = &history_store {
+                                let mission = *current_mission.read().await;
+                                store.save(mission, &history);
+                                saved_history = control_history::fingerprint(&history, mission);
+                            }
+                        }
+                        let _ = respond.send(removed);
+                    }
                     ControlCommand::GetQueue { respond } => {
                         // Collect queued messages from main runner with their target mission IDs
                         let mut queued: Vec<QueuedMessage> = queue
                             .iter()
-                            .map(|(id, content, agent, target_mid)| QueuedMessage {
+                            .map(|(id, content, agent, target_mid, priority)| QueuedMessage {
                                 id: *id,
                                 content: content.clone(),
                                 agent: agent.clone(),
                                 mission_id: *target_mid,
+                                priority: *priority,
                             })
                             .collect();
                         // Also collect queued messages from parallel runners
@@ -4007,6 +6583,7 @@ async fn control_actor_loop(
                                     content: qm.content.clone(),
                                     agent: qm.agent.clone(),
                                     mission_id: Some(*mid),
+                                    priority: qm.priority,
                                 });
                             }
                         }
@@ -4017,7 +6594,7 @@ async fn control_actor_loop(
 
                         // Try to remove from main queue
                         let before_len = queue.len();
-                        queue.retain(|(id, _, _, _)| *id != message_id);
+                        queue.retain(|(id, _, _, _, _)| *id != message_id);
                         if queue.len() < before_len {
                             removed = true;
                             // Emit event for main queue change
@@ -4073,13 +6650,67 @@ async fn control_actor_loop(
                         tracing::info!("Cleared {} total queued messages (main + parallel)", cleared);
                         let _ = respond.send(cleared);
                     }
+                    ControlCommand::EditQueuedMessage { message_id, content, respond } => {
+                        let mut found = false;
+                        if let Some(queued) = queue.iter_mut().find(|(id, _, _, _, _)| *id == message_id) {
+                            queued.1 = content.clone();
+                            found = true;
+                        }
+                        for runner in parallel_runners.values_mut() {
+                            if runner.edit_queued_message(message_id, &content) {
+                                found = true;
+                            }
+                        }
+                        let _ = respond.send(found);
+                    }
+                    ControlCommand::SetQueuedPriority { message_id, priority, respond } => {
+                        let mut found = false;
+                        if let Some(index) = queue.iter().position(|(id, _, _, _, _)| *id == message_id) {
+                            if let Some(mut queued) = queue.remove(index) {
+                                queued.4 = priority;
+                                insert_by_priority(&mut queue, queued, |queued| queued.4);
+                            }
+                            found = true;
+                        }
+                        for runner in parallel_runners.values_mut() {
+                            if runner.set_queued_priority(message_id, priority) {
+                                found = true;
+                            }
+                        }
+                        let _ = respond.send(found);
+                    }
+                    ControlCommand::ReorderQueue { message_ids, respond } => {
+                        let mut moved = reorder_queued(&mut queue, &message_ids, |queued| queued.0);
+                        for runner in parallel_runners.values_mut() {
+                            moved += runner.reorder_queue(&message_ids);
+                        }
+                        let _ = respond.send(moved);
+                    }
+                    ControlCommand::SetMissionPriority { mission_id, priority, respond } => {
+                        if let Err(e) = mission_store.update_mission_priority(mission_id, priority).await {
+                            let _ = respond.send(Err(e));
+                            continue;
+                        }
+                        // Re-place the mission's messages waiting in the main queue;
+                        // a parallel runner's queue only holds its own mission.
+                        let (waiting, rest): (VecDeque<_>, VecDeque<_>) = queue
+                            .drain(..)
+                            .partition(|(_, _, _, target_mid, _)| *target_mid == Some(mission_id));
+                        queue = rest;
+                        for mut queued in waiting {
+                            queued.4 = priority;
+                            insert_by_priority(&mut queue, queued, |queued| queued.4);
+                        }
+                        tracing::info!("Mission {} priority set to {}", mission_id, priority);
+                        let _ = respond.send(Ok(()));
+                    }
                 }
             }
             // Handle agent-initiated mission status changes (from complete_mission tool)
             mission_cmd = mission_cmd_rx.recv() => {
                 if let Some(cmd) = mission_cmd {
                     match cmd {
-                        crate::tools::mission::MissionControlCommand::SetStatus { status, summary } => {
+                        crate::tools::mission::MissionControlCommand::SetStatus { status, summary, report } => {
                             let mission_id = current_mission.read().await.clone();
                             if let Some(id) = mission_id {
                                 let new_status = match status {
@@ -4107,37 +6738,58 @@ async fn control_actor_loop(
                                     }
                                 }
 
-                                if mission_store
-                                    .update_mission_status(id, new_status)
-                                    .await
-                                    .is_ok()
-                                {
-                                    // Generate and store mission summary
-                                    if let Some(ref summary_text) = summary {
-                                        // Extract key files from conversation (look for paths in assistant messages)
-                                        let key_files: Vec<String> = history
+                                if let Err(e) = mission_store.update_mission_completion_report(id, &report).await {
+                                    tracing::warn!("Failed to store completion report: {}", e);
+                                }
+
+                                // Generate and store mission summary
+                                if let Some(ref summary_text) = summary {
+                                    // Key files are the reported deliverables, or else paths
+                                    // mentioned in assistant messages
+                                    let key_files: Vec<String> = if report.deliverables.is_empty() {
+                                        history
                                             .iter()
                                             .filter(|(role, _)| role == "assistant")
                                             .flat_map(|(_, content)| extract_file_paths(content))
                                             .take(10)
-                                            .collect();
+                                            .collect()
+                                    } else {
+                                        report.paths()
+                                    };
 
-                                        if let Err(e) = mission_store
-                                            .insert_mission_summary(id, summary_text, &key_files, success)
-                                            .await
-                                        {
-                                            tracing::warn!("Failed to store mission summary: {}", e);
-                                        } else {
-                                            tracing::info!("Stored mission summary for {}", id);
-                                        }
+                                    if let Err(e) = mission_store
+                                        .insert_mission_summary(id, summary_text, &key_files, success)
+                                        .await
+                                    {
+                                        tracing::warn!("Failed to store mission summary: {}", e);
+                                    } else {
+                                        tracing::info!("Stored mission summary for {}", id);
                                     }
+                                }
+
+                                // With a verifier, the claim is reviewed once the turn is over
+                                if new_status == MissionStatus::Completed
+                                    && matches!(mission_store.get_mission(id).await, Ok(Some(m)) if m.verifier.is_some())
+                                {
+                                    tracing::info!("Mission {} claims completion; its verifier will review it", id);
+                                    mission_verifier::claim(id, summary);
+                                    continue;
+                                }
 
+                                if mission_store
+                                    .update_mission_status(id, new_status)
+                                    .await
+                                    .is_ok()
+                                {
                                     let _ = events_tx.send(AgentEvent::MissionStatusChanged {
                                         mission_id: id,
                                         status: new_status,
                                         summary,
                                     });
                                     tracing::info!("Mission {} marked as {} by agent", id, new_status);
+                                    if new_status == MissionStatus::Completed {
+                                        spawn_deliverable_verification(&mission_store, &workspaces, &config, &events_tx, id);
+                                    }
                                 }
                             }
                         }
@@ -4157,9 +6809,53 @@ async fn control_actor_loop(
                     running = None;
                     running_cancel = None;
                     running_mission_id = None;
+                    main_slot.release();
                     main_runner_activity = None;
                     match res {
-                        Ok((_mid, user_msg, agent_result)) => {
+                        Ok((_mid, user_msg, mut agent_result)) => {
+                            // A turn stopped by stall recovery: a restart carries on with the
+                            // queued continuation, a cancel fails the mission.
+                            if let Some(mid) = completed_mission_id {
+                                // Interrupted by a steer, which is queued to run next
+                                if steered.remove(&mid) {
+                                    agent_result.terminal_reason = None;
+                                }
+                                stall_monitor.take_injected(mid);
+                                match stall_monitor.take_stopping(mid) {
+                                    Some(StallAction::Restart) => agent_result.terminal_reason = None,
+                                    Some(StallAction::Cancel) => {
+                                        agent_result.success = false;
+                                        agent_result.terminal_reason = Some(TerminalReason::Stalled);
+                                        queue.retain(|(_, _, _, target_mid, _)| *target_mid != Some(mid));
+                                    }
+                                    _ => {}
+                                }
+                            }
+
+                            // A run cancelled by its time limit fails as timed out; queued
+                            // messages for it are dropped.
+                            if let Some(mid) = completed_mission_id {
+                                if mission_runs.get(&mid).is_some_and(|run| run.timed_out) {
+                                    mission_runs.remove(&mid);
+                                    agent_result.success = false;
+                                    agent_result.terminal_reason = Some(TerminalReason::TimedOut);
+                                    queue.retain(|(_, _, _, target_mid, _)| *target_mid != Some(mid));
+                                }
+                            }
+
+                            // Stop the mission (and drop its queued messages) once its budget is used up
+                            if let Some(mid) = completed_mission_id {
+                                if let Some(streamed_cents) = mission_budget::take_stopped(mid) {
+                                    agent_result.cost_cents = agent_result.cost_cents.max(streamed_cents);
+                                    agent_result.output = "Stopped mid-turn: the mission reached its cost budget".to_string();
+                                }
+                                if charge_mission_cost(&mission_store, &events_tx, mid, agent_result.cost_cents).await {
+                                    agent_result.success = false;
+                                    agent_result.terminal_reason = Some(TerminalReason::BudgetExhausted);
+                                    queue.retain(|(_, _, _, target_mid, _)| *target_mid != Some(mid));
+                                }
+                            }
+
                             // Only append assistant to local history if this mission is still the current mission.
                             // Note: User message was already added before execution started.
                             // If the user created a new mission mid-execution, history was cleared for that new mission,
@@ -4231,6 +6927,11 @@ async fn control_actor_loop(
                                 }
                             }
 
+                            if let Some(mid) = completed_mission_id {
+                                spawn_mission_checkpoint(&checkpoints, &mission_store, &workspaces, &config, mid);
+                                spawn_artifact_registration(&artifacts, &mission_store, &workspaces, &config, mid, None);
+                            }
+
                             // P1 FIX: Auto-complete mission if agent execution ended in a terminal state
                             // without an explicit complete_mission call.
                             // This prevents missions from staying "active" forever after max iterations, stalls, etc.
@@ -4240,7 +6941,13 @@ async fn control_actor_loop(
                             // We also check the current mission status from DB to handle:
                             // - Explicit complete_mission calls (which update DB status)
                             // - Parallel missions (each has its own DB status)
-                            if agent_result.terminal_reason.is_some() {
+                            // - Completion claimed by the agent, deferred to the mission's verifier
+                            let claim = completed_mission_id.and_then(mission_verifier::take_claim);
+                            // The report itself is stored when the command is handled
+                            if let Some(mid) = completed_mission_id {
+                                mission_completion::take_reported(mid);
+                            }
+                            if agent_result.terminal_reason.is_some() || claim.is_some() {
                                 // Use completed_mission_id (the actual mission that just finished)
                                 // instead of current_mission (which can change when user creates a new mission)
                                 if let Some(mission_id) = completed_mission_id {
@@ -4252,6 +6959,8 @@ async fn control_actor_loop(
                                                 let new_status = match agent_result.terminal_reason {
                                                     Some(TerminalReason::Completed) => MissionStatus::Completed,
                                                     Some(TerminalReason::MaxIterations) => MissionStatus::Blocked,
+                                                    Some(TerminalReason::BudgetExhausted) => MissionStatus::Blocked,
+                                                    Some(TerminalReason::TimedOut) => MissionStatus::Failed,
                                                     _ if agent_result.success => MissionStatus::Completed,
                                                     _ => MissionStatus::Failed,
                                                 };
@@ -4263,6 +6972,8 @@ async fn control_actor_loop(
                                                     TerminalReason::Stalled => "stalled",
                                                     TerminalReason::InfiniteLoop => "infinite_loop",
                                                     TerminalReason::MaxIterations => "max_iterations",
+                                                    TerminalReason::BudgetExhausted => "budget_exhausted",
+                                                    TerminalReason::TimedOut => "timed_out",
                                                 });
                                                 if new_status == MissionStatus::Completed
                                                     && mission_has_active_automation(&mission_store, mission_id).await
@@ -4271,6 +6982,8 @@ async fn control_actor_loop(
                                                         "Skipping auto-complete for mission {} because active automations are enabled",
                                                         mission_id
                                                     );
+                                                } else if new_status == MissionStatus::Completed && mission.verifier.is_some() {
+                                                    spawn_verifier_review(&mission_store, &workspaces, &config, &events_tx, &cmd_tx, mission_id, claim.flatten());
                                                 } else {
                                                     tracing::info!(
                                                         "Auto-completing mission {} with status '{:?}' (terminal_reason: {:?})",
@@ -4292,6 +7005,8 @@ async fn control_actor_loop(
                                                             Some(TerminalReason::Stalled) => Some("No progress detected".to_string()),
                                                             Some(TerminalReason::InfiniteLoop) => Some("Detected repetitive behavior".to_string()),
                                                             Some(TerminalReason::LlmError) => Some("Model error".to_string()),
+                                                            Some(TerminalReason::BudgetExhausted) => Some("Cost budget exhausted".to_string()),
+                                                            Some(TerminalReason::TimedOut) => Some("Exceeded the mission time limit".to_string()),
                                                             None if agent_result.success => None,
                                                             None => Some("Unexpected termination".to_string()),
                                                         };
@@ -4300,6 +7015,9 @@ async fn control_actor_loop(
                                                             status: new_status,
                                                             summary,
                                                         });
+                                                        if new_status == MissionStatus::Completed {
+                                                            spawn_deliverable_verification(&mission_store, &workspaces, &config, &events_tx, mission_id);
+                                                        }
                                                     }
                                                 }
                                             } else {
@@ -4362,122 +7080,267 @@ async fn control_actor_loop(
                     }
                 }
 
-                // Start next queued message, if any.
-                if let Some((mid, msg, per_msg_agent, msg_target_mid)) = queue.pop_front() {
-                    set_and_emit_status(
-                        &status,
-                        &events_tx,
-                        ControlRunState::Running,
-                        queue.len(),
-                        msg_target_mid,
-                    ).await;
-                    let _ = events_tx.send(AgentEvent::UserMessage { id: mid, content: msg.clone(), queued: false, mission_id: msg_target_mid });
-
-                    // Immediately persist user message so it's visible when loading mission
-                    history.push(("user".to_string(), msg.clone()));
-                    persist_mission_history_to(&mission_store, msg_target_mid, &history)
-                        .await;
+                start_queued = true;
+            }
+            // Poll parallel runners for completion
+            _ = tokio::time::sleep(std::time::Duration::from_millis(100)) => {
+                // A main-session message waiting for its workspace starts once a slot frees up
+                if running.is_none() && main_slot.ready(&workspaces).await {
+                    start_queued = true;
+                }
 
-                    let cfg = config.clone();
-                    let agent = Arc::clone(&root_agent);
-                    let mcp_ref = Arc::clone(&mcp);
-                    let workspaces_ref = Arc::clone(&workspaces);
-                    let library_ref = Arc::clone(&library);
-                    let events = events_tx.clone();
-                    let tools_hub = Arc::clone(&tool_hub);
-                    let status_ref = Arc::clone(&status);
-                    let cancel = CancellationToken::new();
-                    let hist_snapshot = history.clone();
-                    let mission_ctrl = crate::tools::mission::MissionControl {
-                        current_mission_id: Arc::clone(&current_mission),
-                        cmd_tx: mission_cmd_tx.clone(),
+                // Enforce wall-clock limits. A run lasts while the mission has
+                // a turn in flight or queued messages.
+                let mut active_missions: Vec<Uuid> = parallel_runners
+                    .iter()
+                    .filter(|(_, runner)| runner.is_running() || !runner.queue.is_empty())
+                    .map(|(id, _)| *id)
+                    .collect();
+                if running.is_some() {
+                    active_missions.extend(running_mission_id);
+                }
+                mission_runs.retain(|id, _| active_missions.contains(id));
+                let refresh_limits = mission_limits_refreshed.elapsed() >= std::time::Duration::from_secs(60);
+                if refresh_limits {
+                    mission_limits_refreshed = std::time::Instant::now();
+                }
+                for mid in &active_missions {
+                    if mission_runs.contains_key(mid) && !refresh_limits {
+                        continue;
+                    }
+                    let (override_minutes, thresholds) = match mission_store.get_mission(*mid).await {
+                        Ok(Some(mission)) => (mission.timeout_minutes, mission.stall_thresholds.unwrap_or_default()),
+                        _ => (None, StallThresholds::default()),
                     };
-                    let tree_ref = Arc::clone(&current_tree);
-                    let progress_ref = Arc::clone(&progress);
-                    running_cancel = Some(cancel.clone());
-                    // Use the mission ID that was captured when message was queued
-                    // This prevents race conditions where current_mission changes between queueing and execution
-                    let mission_id = msg_target_mid;
-                    let (workspace_id, model_override, mission_agent, backend_id, session_id, mission_config_profile) = if let Some(mid) = mission_id {
-                        match mission_store.get_mission(mid).await {
-                            Ok(Some(mission)) => (
-                                Some(mission.workspace_id),
-                                mission.model_override.clone(),
-                                mission.agent.clone(),
-                                Some(mission.backend.clone()),
-                                mission.session_id.clone(),
-                                mission.config_profile.clone(),
-                            ),
-                            Ok(None) => {
-                                tracing::warn!(
-                                    "Mission {} not found while resolving workspace",
-                                    mid
-                                );
-                                (None, None, None, None, None, None)
-                            }
-                            Err(e) => {
-                                tracing::warn!(
-                                    "Failed to load mission {} for workspace: {}",
-                                    mid,
-                                    e
-                                );
-                                (None, None, None, None, None, None)
+                    stall_monitor.set_thresholds(*mid, thresholds);
+                    if let Some(runner) = parallel_runners.get_mut(mid) {
+                        runner.stall_thresholds = thresholds;
+                    }
+                    let limit = effective_timeout(override_minutes, config.mission_timeout_minutes);
+                    mission_runs
+                        .entry(*mid)
+                        .and_modify(|run| run.limit = limit)
+                        .or_insert_with(|| MissionRun::start(limit));
+                }
+                for (mid, run) in mission_runs.iter_mut() {
+                    if !run.should_stop() {
+                        continue;
+                    }
+                    run.timed_out = true;
+                    let limit = format_limit(run.limit.unwrap_or_default());
+                    tracing::warn!("Mission {} exceeded its time limit of {}; cancelling", mid, limit);
+                    if running_mission_id == Some(*mid) {
+                        if let Some(token) = &running_cancel {
+                            token.cancel();
+                        }
+                    } else if let Some(runner) = parallel_runners.get_mut(mid) {
+                        runner.cancel();
+                    }
+                    let _ = events_tx.send(AgentEvent::Error {
+                        message: format!("Mission exceeded its time limit of {} and was stopped", limit),
+                        mission_id: Some(*mid),
+                        resumable: true,
+                    });
+                }
+
+                // Escalate stalls of running turns
+                stall_monitor.retain(&active_missions);
+                let mut turns: Vec<(Uuid, u64)> = parallel_runners
+                    .iter()
+                    .filter(|(_, runner)| runner.is_running())
+                    .map(|(id, runner)| (*id, runner.last_activity.elapsed().as_secs()))
+                    .collect();
+                if running.is_some() {
+                    if let Some(mid) = running_mission_id {
+                        turns.push((mid, main_runner_last_activity.elapsed().as_secs()));
+                    }
+                }
+                let now = std::time::Instant::now();
+                for (mid, seconds_since_activity) in turns {
+                    if mission_runs.get(&mid).is_some_and(|run| run.timed_out)
+                        || super::mission_pause::is_paused(mid)
+                    {
+                        continue;
+                    }
+                    let Some((action, step)) = stall_monitor.escalate(mid, seconds_since_activity, now) else {
+                        continue;
+                    };
+                    let is_main = running_mission_id == Some(mid);
+                    // A nudge still waiting is superseded by whatever comes next
+                    if let Some(previous) = stall_monitor.take_injected(mid) {
+                        if is_main {
+                            queue.retain(|(id, _, _, _, _)| *id != previous);
+                        } else if let Some(runner) = parallel_runners.get_mut(&mid) {
+                            runner.remove_from_queue(previous);
+                        }
+                    }
+                    let inject = match action {
+                        StallAction::Nudge => Some(NUDGE_MESSAGE),
+                        StallAction::Restart => Some(RESTART_MESSAGE),
+                        StallAction::Cancel => None,
+                    };
+                    if let Some(content) = inject {
+                        let message_id = Uuid::new_v4();
+                        if is_main {
+                            queue.push_front((
+                                message_id,
+                                content.to_string(),
+                                None,
+                                Some(mid),
+                                MissionPriority::Urgent,
+                            ));
+                        } else if let Some(runner) = parallel_runners.get_mut(&mid) {
+                            runner.queue_message_front(message_id, content.to_string(), None);
+                        }
+                        stall_monitor.set_injected(mid, message_id);
+                    }
+                    if action != StallAction::Nudge {
+                        if is_main {
+                            if let Some(token) = &running_cancel {
+                                token.cancel();
                             }
+                        } else if let Some(runner) = parallel_runners.get_mut(&mid) {
+                            runner.cancel();
                         }
-                    } else {
-                        (None, None, None, None, None, None)
+                    }
+
+                    let minutes = seconds_since_activity / 60;
+                    let message = match action {
+                        StallAction::Nudge => format!("No progress for {} minutes; asked the agent to continue", minutes),
+                        StallAction::Restart => format!("No progress for {} minutes; restarting the turn", minutes),
+                        StallAction::Cancel => format!("No progress for {} minutes; cancelling the mission", minutes),
                     };
-                    // Per-message agent overrides mission agent
-                    let agent_override = per_msg_agent.or(mission_agent);
-                    running_mission_id = mission_id;
-                    // Reset activity tracking when new task starts
-                    main_runner_last_activity = std::time::Instant::now();
-                    main_runner_activity = None;
-                    main_runner_subtasks.clear();
-                    running = Some(tokio::spawn(async move {
-                        let result = run_single_control_turn(
-                            cfg,
-                            agent,
-                            mcp_ref,
-                            workspaces_ref,
-                            library_ref,
-                            events,
-                            tools_hub,
-                            status_ref,
-                            cancel,
-                            hist_snapshot,
-                            msg.clone(),
-                            Some(mission_ctrl),
-                            tree_ref,
-                            progress_ref,
-                            mission_id,
-                            workspace_id,
-                            backend_id,
-                            model_override,
-                            agent_override,
-                            session_id,
-                            false, // force_session_resume: continuation turn, not a resume
-                            mission_config_profile,
-                        )
-                        .await;
-                        (mid, msg, result)
-                    }));
-                } else {
-                    set_and_emit_status(&status, &events_tx, ControlRunState::Idle, 0, None).await;
+                    tracing::warn!("Mission {} stalled: {}", mid, message);
+                    if action == StallAction::Cancel {
+                        let notify = config.notify.clone();
+                        let alert = message.clone();
+                        tokio::spawn(async move {
+                            crate::tools::send_alert(&notify, "Mission stalled", &alert, mid).await;
+                        });
+                    }
+                    let _ = events_tx.send(AgentEvent::StallRecovery {
+                        action,
+                        step,
+                        seconds_since_activity,
+                        message,
+                        mission_id: Some(mid),
+                    });
                 }
-            }
-            // Poll parallel runners for completion
-            _ = tokio::time::sleep(std::time::Duration::from_millis(100)) => {
+
                 let mut completed_missions = Vec::new();
 
                 for (mission_id, runner) in parallel_runners.iter_mut() {
+                    if runner.retry_due() || runner.workspace_ready() {
+                        runner.start_next(
+                            config.clone(),
+                            Arc::clone(&root_agent),
+                            Arc::clone(&mcp),
+                            Arc::clone(&workspaces),
+                            library.clone(),
+                            events_tx.clone(),
+                            Arc::clone(&tool_hub),
+                            Arc::clone(&status),
+                            mission_cmd_tx.clone(),
+                            Arc::new(RwLock::new(Some(*mission_id))),
+                            secrets.clone(),
+                        );
+                        continue;
+                    }
                     if runner.check_finished() {
-                        if let Some((msg_id, _user_msg, result)) = runner.poll_completion().await {
+                        if let Some((_msg_id, _user_msg, mut result)) = runner.poll_completion().await {
                             tracing::info!(
                                 "Parallel mission {} completed (success: {}, cost: {} cents)",
                                 mission_id, result.success, result.cost_cents
                             );
 
+                            let continue_after_interrupt = stall_monitor.take_injected(*mission_id).is_some()
+                                | steered.remove(mission_id);
+                            if stall_monitor.take_stopping(*mission_id) == Some(StallAction::Cancel) {
+                                result.success = false;
+                                result.terminal_reason = Some(TerminalReason::Stalled);
+                                runner.clear_queue();
+                                match mission_store
+                                    .update_mission_status_with_reason(
+                                        *mission_id,
+                                        MissionStatus::Failed,
+                                        Some("stalled"),
+                                    )
+                                    .await
+                                {
+                                    Ok(()) => {
+                                        let _ = events_tx.send(AgentEvent::MissionStatusChanged {
+                                            mission_id: *mission_id,
+                                            status: MissionStatus::Failed,
+                                            summary: Some("No progress detected".to_string()),
+                                        });
+                                    }
+                                    Err(e) => tracing::warn!(
+                                        "Failed to fail stalled mission {}: {}",
+                                        mission_id,
+                                        e
+                                    ),
+                                }
+                            }
+
+                            if mission_runs.get(mission_id).is_some_and(|run| run.timed_out) {
+                                mission_runs.remove(mission_id);
+                                result.success = false;
+                                result.terminal_reason = Some(TerminalReason::TimedOut);
+                                runner.clear_queue();
+                                match mission_store
+                                    .update_mission_status_with_reason(
+                                        *mission_id,
+                                        MissionStatus::Failed,
+                                        Some("timed_out"),
+                                    )
+                                    .await
+                                {
+                                    Ok(()) => {
+                                        let _ = events_tx.send(AgentEvent::MissionStatusChanged {
+                                            mission_id: *mission_id,
+                                            status: MissionStatus::Failed,
+                                            summary: Some("Exceeded the mission time limit".to_string()),
+                                        });
+                                    }
+                                    Err(e) => tracing::warn!(
+                                        "Failed to fail timed-out mission {}: {}",
+                                        mission_id,
+                                        e
+                                    ),
+                                }
+                            }
+
+                            if let Some(streamed_cents) = mission_budget::take_stopped(*mission_id) {
+                                result.cost_cents = result.cost_cents.max(streamed_cents);
+                                result.output = "Stopped mid-turn: the mission reached its cost budget".to_string();
+                            }
+                            if charge_mission_cost(&mission_store, &events_tx, *mission_id, result.cost_cents).await {
+                                result.success = false;
+                                result.terminal_reason = Some(TerminalReason::BudgetExhausted);
+                                runner.clear_queue();
+                                match mission_store
+                                    .update_mission_status_with_reason(
+                                        *mission_id,
+                                        MissionStatus::Blocked,
+                                        Some("budget_exhausted"),
+                                    )
+                                    .await
+                                {
+                                    Ok(()) => {
+                                        let _ = events_tx.send(AgentEvent::MissionStatusChanged {
+                                            mission_id: *mission_id,
+                                            status: MissionStatus::Blocked,
+                                            summary: Some("Cost budget exhausted".to_string()),
+                                        });
+                                    }
+                                    Err(e) => tracing::warn!(
+                                        "Failed to block mission {} over budget: {}",
+                                        mission_id,
+                                        e
+                                    ),
+                                }
+                            }
+
                             // Emit completion event with mission_id
                             // Mark failures as resumable
                             let resumable = !result.success;
@@ -4493,16 +7356,21 @@ async fn control_actor_loop(
                                 shared_files: None,
                                 resumable,
                             });
+                            if let Some(retry) = runner.pending_retry {
+                                let _ = events_tx.send(AgentEvent::Error {
+                                    message: format!(
+                                        "Turn failed ({}); retrying in {}s (retry {})",
+                                        retry.reason,
+                                        retry.delay.as_secs(),
+                                        retry.retry
+                                    ),
+                                    mission_id: Some(*mission_id),
+                                    resumable: false,
+                                });
+                            }
 
                             // Persist history for this mission
-                            let entries: Vec<MissionHistoryEntry> = runner
-                                .history
-                                .iter()
-                                .map(|(role, content)| MissionHistoryEntry {
-                                    role: role.clone(),
-                                    content: content.clone(),
-                                })
-                                .collect();
+                            let entries = runner.stored_history();
                             if let Err(e) = mission_store
                                 .update_mission_history(*mission_id, &entries)
                                 .await
@@ -4512,6 +7380,8 @@ async fn control_actor_loop(
                                     e
                                 );
                             }
+                            spawn_mission_checkpoint(&checkpoints, &mission_store, &workspaces, &config, *mission_id);
+                            spawn_artifact_registration(&artifacts, &mission_store, &workspaces, &config, *mission_id, None);
 
                             // If runner has no more queued messages, update status and mark for cleanup
                             if runner.queue.is_empty() && !runner.is_running() {
@@ -4534,6 +7404,9 @@ async fn control_actor_loop(
                                                 "Skipping parallel completion for mission {} because active automations are enabled",
                                                 mission_id
                                             );
+                                        } else if new_status == MissionStatus::Completed && mission.verifier.is_some() {
+                                            let summary = mission_verifier::take_claim(*mission_id).flatten();
+                                            spawn_verifier_review(&mission_store, &workspaces, &config, &events_tx, &cmd_tx, *mission_id, summary);
                                         } else if let Err(e) = mission_store
                                             .update_mission_status(*mission_id, new_status)
                                             .await
@@ -4548,10 +7421,32 @@ async fn control_actor_loop(
                                                 status: new_status,
                                                 summary: None,
                                             });
+                                            if new_status == MissionStatus::Completed {
+                                                spawn_deliverable_verification(&mission_store, &workspaces, &config, &events_tx, *mission_id);
+                                            }
                                         }
                                     }
                                 }
                                 completed_missions.push(*mission_id);
+                            } else if (continue_after_interrupt || runner.max_concurrent > 1)
+                                && runner.has_free_slot()
+                            {
+                                // Run the nudge or restart message queued by stall recovery or
+                                // a steer, or the next ones of a mission running messages
+                                // concurrently
+                                runner.start_next(
+                                    config.clone(),
+                                    Arc::clone(&root_agent),
+                                    Arc::clone(&mcp),
+                                    Arc::clone(&workspaces),
+                                    library.clone(),
+                                    events_tx.clone(),
+                                    Arc::clone(&tool_hub),
+                                    Arc::clone(&status),
+                                    mission_cmd_tx.clone(),
+                                    Arc::new(RwLock::new(Some(*mission_id))),
+                                    secrets.clone(),
+                                );
                             }
                         }
                     }
@@ -4559,7 +7454,10 @@ async fn control_actor_loop(
 
                 // Remove completed runners
                 for mid in completed_missions {
-                    parallel_runners.remove(&mid);
+                    if let Some(runner) = parallel_runners.remove(&mid) {
+                        runner.discard_snapshot();
+                        runner.release_workspace();
+                    }
                     tracing::info!("Parallel mission {} removed from runners", mid);
                 }
             }
@@ -4571,6 +7469,7 @@ async fn control_actor_loop(
                         AgentEvent::ToolCall { mission_id, .. } => *mission_id,
                         AgentEvent::ToolResult { mission_id, .. } => *mission_id,
                         AgentEvent::Thinking { mission_id, .. } => *mission_id,
+                        AgentEvent::Diagnostic { mission_id, .. } => *mission_id,
                         AgentEvent::TextDelta { mission_id, .. } => *mission_id,
                         AgentEvent::UserMessage { mission_id, .. } => *mission_id,
                         AgentEvent::AssistantMessage { mission_id, .. } => *mission_id,
@@ -4580,7 +7479,10 @@ async fn control_actor_loop(
                         AgentEvent::AgentTree { mission_id, .. } => *mission_id,
                         AgentEvent::Progress { mission_id, .. } => *mission_id,
                         AgentEvent::MissionActivity { mission_id, .. } => *mission_id,
+                        AgentEvent::MissionProgress { mission_id, .. } => *mission_id,
                         AgentEvent::SessionIdUpdate { mission_id, .. } => Some(*mission_id),
+                        AgentEvent::MissionPaused { mission_id, .. } => Some(*mission_id),
+                        AgentEvent::Heartbeat { mission_id, .. } => Some(*mission_id),
                         _ => None,
                     };
                     // Update last_activity for matching runner (main or parallel)
@@ -4592,6 +7494,19 @@ async fn control_actor_loop(
                             // Update parallel runner activity
                             runner.touch();
                         }
+                        // Output from the agent ends a stall, and so do a
+                        // pause or resume and a busy backend process
+                        if matches!(
+                            event,
+                            AgentEvent::ToolCall { .. }
+                                | AgentEvent::ToolResult { .. }
+                                | AgentEvent::Thinking { .. }
+                                | AgentEvent::TextDelta { .. }
+                                | AgentEvent::MissionPaused { .. }
+                                | AgentEvent::Heartbeat { .. }
+                        ) {
+                            stall_monitor.progress(mid);
+                        }
                     }
 
                     // --- Activity tracking & subtask detection ---
@@ -4650,6 +7565,32 @@ async fn control_actor_loop(
                                     }
                                 }
 
+                                // Agent-reported batch progress
+                                if let Some((label, current, total)) =
+                                    crate::tools::parse_progress_args(name, args)
+                                {
+                                    let _ = events_tx.send(AgentEvent::MissionProgress {
+                                        label,
+                                        current,
+                                        total,
+                                        mission_id: Some(*mid),
+                                    });
+                                }
+
+                                // Agent-registered output files
+                                if let Some(requested) =
+                                    crate::tools::parse_artifact_args(name, args)
+                                {
+                                    spawn_artifact_registration(
+                                        &artifacts,
+                                        &mission_store,
+                                        &workspaces,
+                                        &config,
+                                        *mid,
+                                        Some(requested),
+                                    );
+                                }
+
                                 // Desktop session detection from ToolCall.
                                 // Claude Code and Amp don't emit ToolResult for MCP tools,
                                 // so we detect the session start from the ToolCall and
@@ -4908,6 +7849,154 @@ async fn control_actor_loop(
                 }
             }
         }
+
+        // Start the main session's next queued message, if any (none while
+        // shutting down), once its mission has a slot in its workspace.
+        if start_queued && running.is_none() {
+            let next = if shutdown::is_draining()
+                || !main_slot
+                    .acquire(
+                        &mission_store,
+                        &workspaces,
+                        queue.front().and_then(|queued| queued.3),
+                    )
+                    .await
+            {
+                None
+            } else {
+                queue.pop_front()
+            };
+            if let Some((mid, msg, per_msg_agent, msg_target_mid, _)) = next {
+                set_and_emit_status(
+                    &status,
+                    &events_tx,
+                    ControlRunState::Running,
+                    queue.len(),
+                    msg_target_mid,
+                )
+                .await;
+                let _ = events_tx.send(AgentEvent::UserMessage {
+                    id: mid,
+                    content: msg.clone(),
+                    queued: false,
+                    mission_id: msg_target_mid,
+                });
+
+                // Immediately persist user message so it's visible when loading mission
+                history.push(("user".to_string(), msg.clone()));
+                persist_mission_history_to(&mission_store, msg_target_mid, &history).await;
+
+                let cfg = config.clone();
+                let agent = Arc::clone(&root_agent);
+                let mcp_ref = Arc::clone(&mcp);
+                let workspaces_ref = Arc::clone(&workspaces);
+                let library_ref = Arc::clone(&library);
+                let events = events_tx.clone();
+                let tools_hub = Arc::clone(&tool_hub);
+                let status_ref = Arc::clone(&status);
+                let cancel = CancellationToken::new();
+                let hist_snapshot = history.clone();
+                let mission_ctrl = crate::tools::mission::MissionControl {
+                    current_mission_id: Arc::clone(&current_mission),
+                    cmd_tx: mission_cmd_tx.clone(),
+                };
+                let tree_ref = Arc::clone(&current_tree);
+                let progress_ref = Arc::clone(&progress);
+                running_cancel = Some(cancel.clone());
+                // Use the mission ID that was captured when message was queued
+                // This prevents race conditions where current_mission changes between queueing and execution
+                let mission_id = msg_target_mid;
+                let history_summary =
+                    mission_id.map(|mid| Arc::clone(history_summaries.entry(mid).or_default()));
+                let (
+                    workspace_id,
+                    model_override,
+                    mission_agent,
+                    backend_id,
+                    session_id,
+                    mission_config_profile,
+                ) = if let Some(mid) = mission_id {
+                    match mission_store.get_mission(mid).await {
+                        Ok(Some(mission)) => (
+                            Some(mission.workspace_id),
+                            mission.model_override.clone(),
+                            mission.agent.clone(),
+                            Some(mission.backend.clone()),
+                            mission.session_id.clone(),
+                            mission.config_profile.clone(),
+                        ),
+                        Ok(None) => {
+                            tracing::warn!("Mission {} not found while resolving workspace", mid);
+                            (None, None, None, None, None, None)
+                        }
+                        Err(e) => {
+                            tracing::warn!("Failed to load mission {} for workspace: {}", mid, e);
+                            (None, None, None, None, None, None)
+                        }
+                    }
+                } else {
+                    (None, None, None, None, None, None)
+                };
+                // Per-message agent overrides mission agent
+                let agent_override = per_msg_agent.or(mission_agent);
+                running_mission_id = mission_id;
+                // Reset activity tracking when new task starts
+                main_runner_last_activity = std::time::Instant::now();
+                main_runner_activity = None;
+                main_runner_subtasks.clear();
+                let turn_dir = session_dir.clone();
+                let turn_secrets = secrets.clone();
+                running = Some(tokio::spawn(async move {
+                    let result = run_single_control_turn(
+                        cfg,
+                        agent,
+                        mcp_ref,
+                        workspaces_ref,
+                        library_ref,
+                        events,
+                        tools_hub,
+                        status_ref,
+                        cancel,
+                        hist_snapshot,
+                        history_summary,
+                        msg.clone(),
+                        Some(mission_ctrl),
+                        tree_ref,
+                        progress_ref,
+                        mission_id,
+                        workspace_id,
+                        backend_id,
+                        model_override,
+                        agent_override,
+                        session_id,
+                        false, // force_session_resume: continuation turn, not a resume
+                        mission_config_profile,
+                        turn_dir,
+                        turn_secrets,
+                    )
+                    .await;
+                    (mid, msg, result)
+                }));
+            } else {
+                set_and_emit_status(
+                    &status,
+                    &events_tx,
+                    ControlRunState::Idle,
+                    queue.len(),
+                    None,
+                )
+                .await;
+            }
+        }
+
+        if let Some(store) = &history_store {
+            let mission = *current_mission.read().await;
+            let fingerprint = control_history::fingerprint(&history, mission);
+            if fingerprint != saved_history {
+                store.save(mission, &history);
+                saved_history = fingerprint;
+            }
+        }
     }
 }
 
@@ -4922,6 +8011,7 @@ async fn run_single_control_turn(
     status: Arc<RwLock<ControlStatus>>,
     cancel: CancellationToken,
     history: Vec<(String, String)>,
+    history_summary: Option<SharedHistorySummary>,
     user_message: String,
     mission_control: Option<crate::tools::mission::MissionControl>,
     tree_snapshot: Arc<RwLock<Option<AgentTreeNode>>>,
@@ -4934,7 +8024,19 @@ async fn run_single_control_turn(
     session_id: Option<String>,
     force_session_resume: bool,
     mission_config_profile: Option<String>,
+    session_dir: Option<std::path::PathBuf>,
+    secrets: Option<Arc<SecretsStore>>,
 ) -> crate::agents::AgentResult {
+    // A paused mission doesn't start new turns.
+    if let Some(mid) = mission_id {
+        super::mission_pause::wait_while_paused(mid, &cancel).await;
+        if cancel.is_cancelled() {
+            return crate::agents::AgentResult::failure("Cancelled".to_string(), 0)
+                .with_terminal_reason(TerminalReason::Cancelled);
+        }
+    }
+    let _turn_timer =
+        super::metrics::global().turn_timer(backend_id.as_deref().unwrap_or("opencode"));
     let is_claudecode = backend_id.as_deref() == Some("claudecode");
     // Get config profile: mission's config_profile takes priority over workspace's
     let workspace_config_profile = if let Some(ws_id) = workspace_id {
@@ -4943,7 +8045,12 @@ async fn run_single_control_turn(
         None
     };
     let effective_config_profile = mission_config_profile.or(workspace_config_profile);
-    if let Some(model) = model_override {
+    let backend_defaults = backend_id
+        .as_deref()
+        .map(super::mission_runner::get_backend_defaults_from_config)
+        .unwrap_or_default();
+    let agent_override = agent_override.or(backend_defaults.agent);
+    if let Some(model) = model_override.or(backend_defaults.model) {
         config.default_model = Some(model);
     } else if is_claudecode && config.default_model.is_none() {
         if let Some(default_model) =
@@ -4987,14 +8094,23 @@ async fn run_single_control_turn(
                 ws.path.clone()
             }
         };
+        // Surface missing skill dependencies now rather than mid-mission.
+        if let Some(lib) = lib_ref {
+            for message in workspace::check_skill_requirements(&ws, &mcp, lib).await {
+                tracing::warn!(mission_id = %mid, "{}", message);
+                let _ = events_tx.send(AgentEvent::Diagnostic {
+                    level: DiagnosticLevel::Warn,
+                    source: "library".to_string(),
+                    message,
+                    mission_id: Some(mid),
+                });
+            }
+        }
         (dir, Some(ws))
     } else {
-        (
-            config.working_dir.clone(),
-            Some(workspace::Workspace::default_host(
-                config.working_dir.clone(),
-            )),
-        )
+        // Outside a mission, turns run in the session's own directory
+        let dir = session_dir.unwrap_or_else(|| config.working_dir.clone());
+        (dir.clone(), Some(workspace::Workspace::default_host(dir)))
     };
 
     if let Some(ws) = runtime_workspace.as_ref() {
@@ -5018,8 +8134,16 @@ async fn run_single_control_turn(
         }
         _ => history.as_slice(),
     };
-    let history_context =
-        build_history_context(history_for_prompt, config.context.max_history_total_chars);
+    let history_for_prompt: Vec<ConversationEntry> = history_for_prompt
+        .iter()
+        .map(|(role, content)| ConversationEntry::message(role, content))
+        .collect();
+    let history_context = mission_history::history_context(
+        &history_for_prompt,
+        history_summary.as_ref(),
+        &config.context,
+    )
+    .await;
     let mut convo = String::new();
     convo.push_str(&history_context);
     convo.push_str("User:\n");
@@ -5044,9 +8168,16 @@ async fn run_single_control_turn(
     ctx.progress_snapshot = Some(progress_snapshot);
     ctx.mission_id = mission_id;
     ctx.mcp = Some(mcp);
+    ctx.workspace_id = workspace_id;
+    ctx.secrets = secrets;
+    if let Some(settings) = crate::settings::global() {
+        ctx.tool_policy = Some(settings.get_tool_policy().await);
+        ctx.tool_result_limits = Some(settings.get_tool_result_limits().await);
+    }
 
     let fallback_workspace = workspace::Workspace::default_host(config.working_dir.clone());
     let exec_workspace = runtime_workspace.as_ref().unwrap_or(&fallback_workspace);
+    ctx.tool_allowlist = exec_workspace.tools.clone();
 
     // Execute based on backend
     let result = match backend_id.as_deref() {
@@ -5767,6 +8898,7 @@ pub async fn webhook_receiver(
             content: substituted_content,
             agent: None,
             target_mission_id: Some(mission.id),
+            priority: None,
             respond: respond_tx,
         })
         .await;
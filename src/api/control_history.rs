@@ -0,0 +1,200 @@
+//! On-disk copy of a control session's conversation.
+//!
+//! The main control session keeps its `(role, content)` history in memory. It
+//! is written to `.sandboxed-sh/runtime/control/<session>.json` whenever it
+//! changes, together with the session's current mission, so a restarted
+//! server picks up the conversation where it was left.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::mission_history::{self, ConversationEntry};
+
+/// Serialized state of a control session's conversation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ControlHistory {
+    #[serde(default)]
+    pub current_mission: Option<Uuid>,
+    #[serde(default, deserialize_with = "mission_history::deserialize_history")]
+    pub history: Vec<ConversationEntry>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ControlHistory {
+    /// The history as `(role, content)` pairs. Tool calls are skipped.
+    pub fn messages(&self) -> Vec<(String, String)> {
+        self.history
+            .iter()
+            .filter_map(|entry| {
+                let content = entry.message_text()?;
+                Some((entry.role().to_string(), content.to_string()))
+            })
+            .collect()
+    }
+}
+
+/// File holding one control session's history.
+#[derive(Debug)]
+pub struct ControlHistoryStore {
+    path: PathBuf,
+}
+
+impl ControlHistoryStore {
+    /// Store of the session whose missions live under `store_user`.
+    pub fn new(working_dir: &Path, store_user: &str) -> Self {
+        let file: String = store_user
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        Self::at(
+            working_dir
+                .join(".sandboxed-sh")
+                .join("runtime")
+                .join("control")
+                .join(format!("{}.json", file)),
+        )
+    }
+
+    fn at(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// The stored history, if any. An unreadable file is ignored.
+    pub fn load(&self) -> Option<ControlHistory> {
+        let contents = std::fs::read_to_string(&self.path).ok()?;
+        match serde_json::from_str(&contents) {
+            Ok(saved) => Some(saved),
+            Err(e) => {
+                tracing::warn!("Ignoring control history {}: {}", self.path.display(), e);
+                None
+            }
+        }
+    }
+
+    /// Write the history, replacing the previous copy atomically.
+    pub fn save(&self, current_mission: Option<Uuid>, history: &[(String, String)]) {
+        let saved = ControlHistory {
+            current_mission,
+            history: history
+                .iter()
+                .map(|(role, content)| ConversationEntry::message(role, content))
+                .collect(),
+            updated_at: Utc::now(),
+        };
+        if let Some(dir) = self.path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        let tmp = self.path.with_extension("json.tmp");
+        let result = serde_json::to_string(&saved)
+            .map_err(|e| e.to_string())
+            .and_then(|contents| std::fs::write(&tmp, contents).map_err(|e| e.to_string()))
+            .and_then(|_| std::fs::rename(&tmp, &self.path).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            tracing::warn!(
+                "Failed to persist control history {}: {}",
+                self.path.display(),
+                e
+            );
+        }
+    }
+}
+
+/// Cheap stand-in for the history's contents, to tell when it needs saving.
+pub fn fingerprint(
+    history: &[(String, String)],
+    current_mission: Option<Uuid>,
+) -> (usize, usize, Option<Uuid>) {
+    let last_len = history
+        .last()
+        .map(|(_, content)| content.len())
+        .unwrap_or(0);
+    (history.len(), last_len, current_mission)
+}
+
+/// Drop all but the last `keep` entries. Returns how many were removed.
+pub fn truncate(history: &mut Vec<(String, String)>, keep: usize) -> usize {
+    let removed = history.len().saturating_sub(keep);
+    history.drain(..removed);
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(items: &[(&str, &str)]) -> Vec<(String, String)> {
+        items
+            .iter()
+            .map(|(role, content)| (role.to_string(), content.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn round_trips_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ControlHistoryStore::at(dir.path().join("control").join("user.json"));
+        assert!(store.load().is_none());
+
+        let mission = Uuid::new_v4();
+        let history = pairs(&[("user", "Hello"), ("assistant", "Hi there")]);
+        store.save(Some(mission), &history);
+
+        let saved = store.load().unwrap();
+        assert_eq!(saved.current_mission, Some(mission));
+        assert_eq!(saved.messages(), history);
+    }
+
+    #[test]
+    fn reads_legacy_pairs() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("user.json");
+        std::fs::write(
+            &path,
+            r#"{"history": [["user", "Hello"]], "updated_at": "2026-01-01T00:00:00Z"}"#,
+        )
+        .unwrap();
+        let saved = ControlHistoryStore::at(path).load().unwrap();
+        assert_eq!(saved.current_mission, None);
+        assert_eq!(saved.messages(), pairs(&[("user", "Hello")]));
+    }
+
+    #[test]
+    fn sanitizes_session_file_name() {
+        let store = ControlHistoryStore::new(Path::new("/work"), "../alice--docs");
+        assert_eq!(
+            store.path,
+            Path::new("/work/.sandboxed-sh/runtime/control/___alice--docs.json")
+        );
+    }
+
+    #[test]
+    fn truncates_to_newest_entries() {
+        let mut history = pairs(&[("user", "a"), ("assistant", "b"), ("user", "c")]);
+        assert_eq!(truncate(&mut history, 2), 1);
+        assert_eq!(history, pairs(&[("assistant", "b"), ("user", "c")]));
+        assert_eq!(truncate(&mut history, 5), 0);
+        assert_eq!(truncate(&mut history, 0), 2);
+        assert!(history.is_empty());
+    }
+
+    #[test]
+    fn fingerprint_tracks_changes() {
+        let mut history = pairs(&[("user", "a")]);
+        let before = fingerprint(&history, None);
+        history.push(("assistant".to_string(), "b".to_string()));
+        assert_ne!(fingerprint(&history, None), before);
+        assert_ne!(
+            fingerprint(&history, Some(Uuid::new_v4())),
+            fingerprint(&history, None)
+        );
+    }
+}
@@ -0,0 +1,414 @@
+//! Named control sessions.
+//!
+//! Every user has a default control session. Users can create more, each
+//! with a name and a working directory of its own: its own actor, message
+//! queue, conversation history, mission store and event stream. Requests
+//! pick a session with the `X-Control-Session` header (or the
+//! `control_session` query parameter, for event streams and websockets);
+//! without one they go to the default session.
+
+use std::path::{Path as FsPath, PathBuf};
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    extract::{Path, State},
+    http::{Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    routing::{delete, get},
+    Extension, Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+use super::auth::AuthUser;
+use super::control::ControlStatus;
+use super::routes::AppState;
+
+/// Header naming the session a request targets.
+pub const SESSION_HEADER: &str = "x-control-session";
+/// Query parameter naming the session, for clients that can't set headers.
+pub const SESSION_QUERY: &str = "control_session";
+/// Name standing for the user's default session.
+const DEFAULT_SESSION: &str = "default";
+const MAX_NAME_LEN: usize = 64;
+
+/// A named session, as persisted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamedSession {
+    pub user_id: String,
+    pub name: String,
+    /// Where the session's turns run when they aren't part of a mission
+    pub working_dir: PathBuf,
+    pub created_at: String,
+}
+
+/// A named session, as listed.
+#[derive(Debug, Clone, Serialize)]
+pub struct ControlSessionInfo {
+    pub name: String,
+    pub working_dir: PathBuf,
+    pub created_at: String,
+    /// Current state; unset until the session is first used after a restart
+    pub status: Option<ControlStatus>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateControlSessionRequest {
+    pub name: String,
+    /// Absolute path of an existing directory. Defaults to
+    /// `sessions/<name>` in the server's working directory.
+    #[serde(default)]
+    pub working_dir: Option<String>,
+}
+
+/// Check a session name: letters, digits, `-` and `_`, and not `default`.
+pub fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > MAX_NAME_LEN {
+        return Err(format!(
+            "Session name must be 1 to {} characters",
+            MAX_NAME_LEN
+        ));
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!(
+            "Invalid session name '{}': use letters, digits, '-' and '_'",
+            name
+        ));
+    }
+    if name.eq_ignore_ascii_case(DEFAULT_SESSION) {
+        return Err(format!("'{}' is the default session", name));
+    }
+    Ok(())
+}
+
+/// Named sessions of every user, persisted to disk.
+pub struct ControlSessionRegistry {
+    sessions: RwLock<Vec<NamedSession>>,
+    storage_path: PathBuf,
+}
+
+impl ControlSessionRegistry {
+    /// Create a registry, loading existing sessions from disk.
+    pub fn load(working_dir: &FsPath) -> Self {
+        let storage_path = working_dir.join(".sandboxed-sh/control_sessions.json");
+        let sessions = match std::fs::read_to_string(&storage_path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                tracing::warn!(
+                    "Failed to parse control sessions from {}: {}",
+                    storage_path.display(),
+                    e
+                );
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+        Self {
+            sessions: RwLock::new(sessions),
+            storage_path,
+        }
+    }
+
+    async fn save_to_disk(&self, sessions: &[NamedSession]) -> Result<(), String> {
+        if let Some(parent) = self.storage_path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let contents = serde_json::to_string_pretty(sessions).map_err(|e| e.to_string())?;
+        tokio::fs::write(&self.storage_path, contents)
+            .await
+            .map_err(|e| format!("Failed to write {}: {}", self.storage_path.display(), e))
+    }
+
+    /// Sessions of `user_id`, by name.
+    pub async fn list(&self, user_id: &str) -> Vec<NamedSession> {
+        let mut sessions: Vec<NamedSession> = self
+            .sessions
+            .read()
+            .await
+            .iter()
+            .filter(|s| s.user_id == user_id)
+            .cloned()
+            .collect();
+        sessions.sort_by(|a, b| a.name.cmp(&b.name));
+        sessions
+    }
+
+    pub async fn get(&self, user_id: &str, name: &str) -> Option<NamedSession> {
+        self.sessions
+            .read()
+            .await
+            .iter()
+            .find(|s| s.user_id == user_id && s.name == name)
+            .cloned()
+    }
+
+    /// Add a session. Fails if the user already has one with that name.
+    pub async fn create(&self, session: NamedSession) -> Result<(), String> {
+        let mut sessions = self.sessions.write().await;
+        if sessions
+            .iter()
+            .any(|s| s.user_id == session.user_id && s.name == session.name)
+        {
+            return Err(format!("Control session '{}' already exists", session.name));
+        }
+        sessions.push(session);
+        self.save_to_disk(&sessions).await
+    }
+
+    /// Forget a session. Returns `Ok(false)` if it doesn't exist.
+    pub async fn remove(&self, user_id: &str, name: &str) -> Result<bool, String> {
+        let mut sessions = self.sessions.write().await;
+        let before = sessions.len();
+        sessions.retain(|s| !(s.user_id == user_id && s.name == name));
+        if sessions.len() == before {
+            return Ok(false);
+        }
+        self.save_to_disk(&sessions).await?;
+        Ok(true)
+    }
+}
+
+/// Session named by the request, if any. `default` means no named session.
+fn requested_session(req: &Request<Body>) -> Option<String> {
+    let from_header = req
+        .headers()
+        .get(SESSION_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    let from_query = || {
+        req.uri().query()?.split('&').find_map(|pair| {
+            let (key, value) = pair.split_once('=')?;
+            (key == SESSION_QUERY).then(|| value.to_string())
+        })
+    };
+    from_header
+        .or_else(from_query)
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty() && !name.eq_ignore_ascii_case(DEFAULT_SESSION))
+}
+
+/// Middleware (after `require_auth`) pointing the request's user at the
+/// named session it asked for.
+pub async fn select_session(
+    State(state): State<Arc<AppState>>,
+    mut req: Request<Body>,
+    next: Next,
+) -> Response {
+    let Some(name) = requested_session(&req) else {
+        return next.run(req).await;
+    };
+    let Some(user) = req.extensions_mut().get_mut::<AuthUser>() else {
+        return next.run(req).await;
+    };
+    if let Err(response) = resolve(&state, user, &name).await {
+        return response.into_response();
+    }
+    next.run(req).await
+}
+
+/// Point `user` at their session `name`, which must exist.
+pub async fn resolve(
+    state: &AppState,
+    user: &mut AuthUser,
+    name: &str,
+) -> Result<(), (StatusCode, String)> {
+    let name = name.trim();
+    if name.is_empty() || name.eq_ignore_ascii_case(DEFAULT_SESSION) {
+        return Ok(());
+    }
+    if state
+        .control
+        .named_sessions()
+        .get(&user.id, name)
+        .await
+        .is_none()
+    {
+        return Err((
+            StatusCode::NOT_FOUND,
+            format!("Control session '{}' not found", name),
+        ));
+    }
+    user.control_session = Some(name.to_string());
+    Ok(())
+}
+
+/// Create the session routes (nested under `/api/control/sessions`).
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(list_sessions).post(create_session))
+        .route("/:name", delete(delete_session))
+}
+
+/// GET /api/control/sessions
+async fn list_sessions(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+) -> Json<Vec<ControlSessionInfo>> {
+    let mut sessions = Vec::new();
+    for session in state.control.named_sessions().list(&user.id).await {
+        let status = state.control.session_status(&user.id, &session.name).await;
+        sessions.push(ControlSessionInfo {
+            name: session.name,
+            working_dir: session.working_dir,
+            created_at: session.created_at,
+            status,
+        });
+    }
+    Json(sessions)
+}
+
+/// POST /api/control/sessions
+async fn create_session(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Json(req): Json<CreateControlSessionRequest>,
+) -> Result<(StatusCode, Json<ControlSessionInfo>), (StatusCode, String)> {
+    let name = req.name.trim().to_string();
+    validate_name(&name).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    let working_dir = match req.working_dir.as_deref().map(str::trim) {
+        Some(dir) if !dir.is_empty() => {
+            let dir = PathBuf::from(dir);
+            if !dir.is_absolute() || !dir.is_dir() {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    format!("{} is not an absolute path to a directory", dir.display()),
+                ));
+            }
+            dir
+        }
+        _ => {
+            let dir = state.config.working_dir.join("sessions").join(&name);
+            tokio::fs::create_dir_all(&dir).await.map_err(|e| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Failed to create {}: {}", dir.display(), e),
+                )
+            })?;
+            dir
+        }
+    };
+    let session = NamedSession {
+        user_id: user.id.clone(),
+        name,
+        working_dir,
+        created_at: super::mission_store::now_string(),
+    };
+    state
+        .control
+        .named_sessions()
+        .create(session.clone())
+        .await
+        .map_err(|e| (StatusCode::CONFLICT, e))?;
+    tracing::info!(
+        user_id = %user.id,
+        session = %session.name,
+        working_dir = %session.working_dir.display(),
+        "Control session created"
+    );
+    Ok((
+        StatusCode::CREATED,
+        Json(ControlSessionInfo {
+            name: session.name,
+            working_dir: session.working_dir,
+            created_at: session.created_at,
+            status: None,
+        }),
+    ))
+}
+
+/// DELETE /api/control/sessions/:name
+async fn delete_session(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Path(name): Path<String>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    if state
+        .control
+        .named_sessions()
+        .get(&user.id, &name)
+        .await
+        .is_none()
+    {
+        return Err((
+            StatusCode::NOT_FOUND,
+            format!("Control session '{}' not found", name),
+        ));
+    }
+    state
+        .control
+        .remove_session(&user.id, &name)
+        .await
+        .map_err(|e| (StatusCode::CONFLICT, e))?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(user_id: &str, name: &str) -> NamedSession {
+        NamedSession {
+            user_id: user_id.to_string(),
+            name: name.to_string(),
+            working_dir: PathBuf::from("/tmp"),
+            created_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn validates_names() {
+        assert!(validate_name("ops-2_night").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("two words").is_err());
+        assert!(validate_name("../etc").is_err());
+        assert!(validate_name("Default").is_err());
+        assert!(validate_name(&"x".repeat(MAX_NAME_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn reads_session_from_header_or_query() {
+        let req = Request::builder()
+            .uri("/api/control/stream?mission_id=1&control_session=ops")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(requested_session(&req).as_deref(), Some("ops"));
+
+        let req = Request::builder()
+            .uri("/api/control/message?control_session=ops")
+            .header(SESSION_HEADER, "review")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(requested_session(&req).as_deref(), Some("review"));
+
+        let req = Request::builder()
+            .uri("/api/control/message")
+            .header(SESSION_HEADER, "default")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(requested_session(&req), None);
+    }
+
+    #[tokio::test]
+    async fn registry_persists_sessions_per_user() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = ControlSessionRegistry::load(dir.path());
+        registry.create(session("alice", "ops")).await.unwrap();
+        registry.create(session("bob", "ops")).await.unwrap();
+        assert!(registry.create(session("alice", "ops")).await.is_err());
+
+        let reloaded = ControlSessionRegistry::load(dir.path());
+        assert_eq!(reloaded.list("alice").await.len(), 1);
+        assert!(reloaded.get("bob", "ops").await.is_some());
+
+        assert!(reloaded.remove("alice", "ops").await.unwrap());
+        assert!(!reloaded.remove("alice", "ops").await.unwrap());
+        assert!(reloaded.get("alice", "ops").await.is_none());
+        assert!(reloaded.get("bob", "ops").await.is_some());
+    }
+}
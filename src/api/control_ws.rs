@@ -0,0 +1,437 @@
+//! WebSocket transport for control session events.
+//!
+//! An alternative to the SSE stream for setups where a reverse proxy buffers
+//! SSE. Events come from the same journal and use the same envelope: every
+//! message is `{"id": ..., "event": ..., "data": ...}` where `data` is the
+//! `AgentEvent`. The client can narrow the stream to some missions and event
+//! types, resume after an event id, and opt into acks: with acks on, at most
+//! [`MAX_UNACKED`] events are in flight and the rest wait in the journal.
+//!
+//! Client messages:
+//! - `{"type": "subscribe", "mission_ids": [...], "event_types": [...], "last_event_id": N, "acks": true}`
+//!   (every field optional; replaces the current subscription)
+//! - `{"type": "ack", "id": N}` acknowledges every event up to `N`
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+};
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::auth;
+use super::control::{AgentEvent, ControlState};
+use super::control_sessions;
+use super::event_journal::{EventJournal, JournalEntry};
+use super::routes::AppState;
+
+/// Events in flight at once when the client acks.
+pub const MAX_UNACKED: usize = 512;
+
+/// Query parameters of the websocket endpoint; same meaning as in the
+/// `subscribe` message.
+#[derive(Debug, Default, Deserialize)]
+pub struct ControlWsQuery {
+    /// Comma-separated mission ids
+    pub mission_ids: Option<String>,
+    /// Comma-separated event names
+    pub event_types: Option<String>,
+    pub last_event_id: Option<u64>,
+    #[serde(default)]
+    pub acks: bool,
+    /// Named control session to stream (the default session if unset)
+    pub control_session: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClientMessage {
+    Subscribe {
+        #[serde(default)]
+        mission_ids: Vec<Uuid>,
+        #[serde(default)]
+        event_types: Vec<String>,
+        last_event_id: Option<u64>,
+        #[serde(default)]
+        acks: bool,
+    },
+    Ack {
+        id: u64,
+    },
+}
+
+/// Which events a client receives. Empty lists match everything.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+struct Subscription {
+    mission_ids: Vec<Uuid>,
+    event_types: Vec<String>,
+}
+
+impl Subscription {
+    fn from_query(query: &ControlWsQuery) -> Result<Self, String> {
+        let list = |value: &Option<String>| -> Vec<String> {
+            value
+                .as_deref()
+                .unwrap_or("")
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(String::from)
+                .collect()
+        };
+        let mission_ids = list(&query.mission_ids)
+            .iter()
+            .map(|id| Uuid::parse_str(id).map_err(|_| format!("Invalid mission id '{}'", id)))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            mission_ids,
+            event_types: list(&query.event_types),
+        })
+    }
+
+    fn matches(&self, entry: &JournalEntry) -> bool {
+        let mission_match = self.mission_ids.is_empty()
+            || entry
+                .mission_id
+                .is_some_and(|id| self.mission_ids.contains(&id));
+        mission_match && (self.event_types.is_empty() || self.event_types.contains(&entry.event))
+    }
+}
+
+/// Events sent but not yet acknowledged by the client.
+#[derive(Debug, Default)]
+struct AckWindow {
+    enabled: bool,
+    unacked: VecDeque<u64>,
+}
+
+impl AckWindow {
+    fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            unacked: VecDeque::new(),
+        }
+    }
+
+    fn has_room(&self) -> bool {
+        !self.enabled || self.unacked.len() < MAX_UNACKED
+    }
+
+    fn sent(&mut self, id: u64) {
+        if self.enabled {
+            self.unacked.push_back(id);
+        }
+    }
+
+    fn ack(&mut self, id: u64) {
+        while self.unacked.front().is_some_and(|&sent| sent <= id) {
+            self.unacked.pop_front();
+        }
+    }
+}
+
+/// A message in the SSE envelope; `data` is already JSON.
+fn envelope(id: Option<u64>, event: &str, data: &str) -> String {
+    let event = serde_json::to_string(event).unwrap_or_else(|_| "\"\"".to_string());
+    match id {
+        Some(id) => format!(r#"{{"id":{},"event":{},"data":{}}}"#, id, event, data),
+        None => format!(r#"{{"event":{},"data":{}}}"#, event, data),
+    }
+}
+
+fn event_envelope(event: &AgentEvent) -> String {
+    let data = serde_json::to_string(event).unwrap_or_else(|_| "null".to_string());
+    envelope(None, event.event_name(), &data)
+}
+
+fn error_envelope(message: String) -> String {
+    event_envelope(&AgentEvent::Error {
+        message,
+        mission_id: None,
+        resumable: false,
+    })
+}
+
+fn extract_jwt_from_protocols(headers: &HeaderMap) -> Option<String> {
+    let raw = headers
+        .get("sec-websocket-protocol")
+        .and_then(|v| v.to_str().ok())?;
+    // Client sends: ["sandboxed", "jwt.<token>"]
+    for part in raw.split(',').map(|s| s.trim()) {
+        if let Some(rest) = part.strip_prefix("jwt.") {
+            if !rest.is_empty() {
+                return Some(rest.to_string());
+            }
+        }
+    }
+    None
+}
+
+/// WebSocket endpoint streaming control session events.
+pub async fn control_ws(
+    ws: WebSocketUpgrade,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ControlWsQuery>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    // Enforce auth in non-dev mode by taking JWT from Sec-WebSocket-Protocol.
+    let token = extract_jwt_from_protocols(&headers);
+    if token.is_none() && state.config.auth.auth_required(state.config.dev_mode) {
+        return (StatusCode::UNAUTHORIZED, "Missing websocket JWT").into_response();
+    }
+    let Some(mut user) = auth::user_for_token(token.as_deref(), &state.config) else {
+        return (StatusCode::UNAUTHORIZED, "Invalid or expired token").into_response();
+    };
+    if let Some(name) = query.control_session.as_deref() {
+        if let Err(e) = control_sessions::resolve(&state, &mut user, name).await {
+            return e.into_response();
+        }
+    }
+    let subscription = match Subscription::from_query(&query) {
+        Ok(subscription) => subscription,
+        Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
+    };
+
+    let control = state.control.get_or_spawn(&user).await;
+    tracing::info!(username = %user.username, "Control websocket upgrade requested");
+    ws.protocols(["sandboxed"])
+        .on_upgrade(move |socket| {
+            handle_socket(
+                socket,
+                control,
+                subscription,
+                query.last_event_id,
+                query.acks,
+            )
+        })
+        .into_response()
+}
+
+/// Journal events after `after` that the subscription wants, and the id
+/// the cursor can move to once they are all sent. `None` if some of them
+/// can no longer be replayed.
+async fn pending(
+    journal: &EventJournal,
+    subscription: &Subscription,
+    after: u64,
+    upto: u64,
+) -> Option<(Vec<JournalEntry>, u64)> {
+    if let Some(entries) = journal.after(after, None) {
+        let cursor = entries.last().map_or(after, |entry| entry.id);
+        let entries = entries
+            .into_iter()
+            .filter(|entry| subscription.matches(entry))
+            .collect();
+        return Some((entries, cursor));
+    }
+    // Older than what's held in memory: missions' events are read back
+    // from their logs.
+    if !journal.is_persistent() || subscription.mission_ids.is_empty() {
+        return None;
+    }
+    let mut entries = Vec::new();
+    for mission_id in &subscription.mission_ids {
+        entries.extend(journal.read_mission_log(*mission_id, after).await);
+    }
+    entries.sort_by_key(|entry| entry.id);
+    entries.dedup_by_key(|entry| entry.id);
+    entries.retain(|entry| subscription.matches(entry));
+    let cursor = entries.last().map_or(upto, |entry| entry.id.max(upto));
+    Some((entries, cursor))
+}
+
+async fn handle_socket(
+    socket: WebSocket,
+    control: ControlState,
+    mut subscription: Subscription,
+    resume_after: Option<u64>,
+    acks: bool,
+) {
+    let _subscriber = super::metrics::global().subscriber(true);
+    let (mut ws_sender, mut ws_receiver) = socket.split();
+    let journal = Arc::clone(&control.journal);
+    let mut latest = journal.subscribe();
+    let mut cursor = resume_after.unwrap_or_else(|| *latest.borrow());
+    let mut window = AckWindow::new(acks);
+
+    // Initial status snapshot, like the SSE stream.
+    let initial = control.status.read().await.clone();
+    let status = event_envelope(&AgentEvent::Status {
+        state: initial.state,
+        queue_len: initial.queue_len,
+        mission_id: initial.mission_id,
+    });
+    if ws_sender.send(Message::Text(status)).await.is_err() {
+        return;
+    }
+
+    let mut ping = tokio::time::interval(Duration::from_secs(15));
+    ping.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+    'stream: loop {
+        if window.has_room() {
+            let upto = *latest.borrow_and_update();
+            match pending(&journal, &subscription, cursor, upto).await {
+                Some((entries, next_cursor)) => {
+                    let mut complete = true;
+                    for entry in entries {
+                        let message = envelope(Some(entry.id), &entry.event, &entry.data);
+                        if ws_sender.send(Message::Text(message)).await.is_err() {
+                            break 'stream;
+                        }
+                        cursor = entry.id;
+                        window.sent(entry.id);
+                        if !window.has_room() {
+                            complete = false;
+                            break;
+                        }
+                    }
+                    if complete {
+                        cursor = cursor.max(next_cursor);
+                    }
+                }
+                None => {
+                    tracing::warn!(
+                        after = cursor,
+                        "Control websocket can't replay missed events"
+                    );
+                    let message =
+                        error_envelope("event stream lagged; some events were dropped".to_string());
+                    if ws_sender.send(Message::Text(message)).await.is_err() {
+                        break;
+                    }
+                    cursor = upto;
+                }
+            }
+        }
+
+        tokio::select! {
+            changed = latest.changed(), if window.has_room() => {
+                if changed.is_err() {
+                    break;
+                }
+            }
+            msg = ws_receiver.next() => match msg {
+                Some(Ok(Message::Text(text))) => match serde_json::from_str::<ClientMessage>(&text) {
+                    Ok(ClientMessage::Subscribe { mission_ids, event_types, last_event_id, acks }) => {
+                        subscription = Subscription { mission_ids, event_types };
+                        window = AckWindow::new(acks);
+                        if let Some(id) = last_event_id {
+                            cursor = id;
+                        }
+                        let data = serde_json::to_string(&subscription).unwrap_or_else(|_| "null".to_string());
+                        if ws_sender.send(Message::Text(envelope(None, "subscribed", &data))).await.is_err() {
+                            break;
+                        }
+                    }
+                    Ok(ClientMessage::Ack { id }) => window.ack(id),
+                    Err(e) => {
+                        let message = error_envelope(format!("Invalid message: {}", e));
+                        if ws_sender.send(Message::Text(message)).await.is_err() {
+                            break;
+                        }
+                    }
+                },
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+            _ = ping.tick() => {
+                if ws_sender.send(Message::Ping(Vec::new())).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+    tracing::info!("Control websocket closed");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: u64, mission_id: Option<Uuid>, event: &str) -> JournalEntry {
+        JournalEntry {
+            id,
+            mission_id,
+            event: event.to_string(),
+            data: "{}".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_subscription_filters() {
+        let mid = Uuid::new_v4();
+        let all = Subscription::default();
+        assert!(all.matches(&entry(1, None, "status")));
+
+        let query = ControlWsQuery {
+            mission_ids: Some(format!("{}, ", mid)),
+            event_types: Some("tool_call,tool_result".to_string()),
+            ..Default::default()
+        };
+        let sub = Subscription::from_query(&query).unwrap();
+        assert!(sub.matches(&entry(1, Some(mid), "tool_call")));
+        assert!(!sub.matches(&entry(2, Some(mid), "thinking")));
+        assert!(!sub.matches(&entry(3, Some(Uuid::new_v4()), "tool_call")));
+        assert!(!sub.matches(&entry(4, None, "tool_call")));
+
+        assert!(Subscription::from_query(&ControlWsQuery {
+            mission_ids: Some("nope".to_string()),
+            ..Default::default()
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_ack_window() {
+        let mut window = AckWindow::new(true);
+        for id in 0..MAX_UNACKED as u64 {
+            window.sent(id);
+        }
+        assert!(!window.has_room());
+        window.ack(9);
+        assert!(window.has_room());
+        assert_eq!(window.unacked.len(), MAX_UNACKED - 10);
+
+        let mut unbounded = AckWindow::new(false);
+        for id in 0..=MAX_UNACKED as u64 {
+            unbounded.sent(id);
+        }
+        assert!(unbounded.has_room());
+    }
+
+    #[test]
+    fn test_envelope_and_client_messages() {
+        let message = envelope(Some(7), "tool_call", r#"{"type":"tool_call"}"#);
+        let value: serde_json::Value = serde_json::from_str(&message).unwrap();
+        assert_eq!(value["id"], 7);
+        assert_eq!(value["event"], "tool_call");
+        assert_eq!(value["data"]["type"], "tool_call");
+        assert!(
+            serde_json::from_str::<serde_json::Value>(&envelope(None, "status", "{}"))
+                .unwrap()
+                .get("id")
+                .is_none()
+        );
+
+        let subscribe: ClientMessage =
+            serde_json::from_str(r#"{"type":"subscribe","event_types":["error"]}"#).unwrap();
+        assert!(matches!(
+            subscribe,
+            ClientMessage::Subscribe { ref event_types, acks: false, .. } if event_types == &["error"]
+        ));
+        assert!(matches!(
+            serde_json::from_str::<ClientMessage>(r#"{"type":"ack","id":3}"#).unwrap(),
+            ClientMessage::Ack { id: 3 }
+        ));
+    }
+}
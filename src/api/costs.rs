@@ -0,0 +1,328 @@
+//! Cost report across missions.
+//!
+//! Every turn's cost is logged with its assistant message. `GET /api/costs`
+//! sums them by mission, backend, model or day over a time range, as JSON or
+//! CSV. Only the SQLite mission store keeps these events; other stores report
+//! nothing.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::sync::Arc;
+
+use axum::{
+    extract::{Extension, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::auth::AuthUser;
+use super::mission_store::TurnCost;
+use super::routes::AppState;
+
+/// What the report's rows are.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CostGroupBy {
+    #[default]
+    Mission,
+    Backend,
+    Model,
+    Day,
+}
+
+/// Output format of the report.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CostFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct CostQuery {
+    #[serde(default)]
+    pub group_by: CostGroupBy,
+    /// Start of the range (RFC 3339 or `YYYY-MM-DD`)
+    pub since: Option<String>,
+    /// End of the range, exclusive (RFC 3339, or `YYYY-MM-DD` to include
+    /// that day)
+    pub until: Option<String>,
+    /// Shorthand for `since`: the last N days
+    pub days: Option<u32>,
+    #[serde(default)]
+    pub format: CostFormat,
+}
+
+/// One row of the report.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CostGroup {
+    /// Mission id, backend, model or `YYYY-MM-DD`
+    pub key: String,
+    /// Mission title (mission grouping only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub cost_cents: u64,
+    pub turns: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CostReport {
+    pub group_by: CostGroupBy,
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub total_cents: u64,
+    pub turns: u64,
+    pub groups: Vec<CostGroup>,
+}
+
+impl CostReport {
+    pub fn build(
+        costs: &[TurnCost],
+        group_by: CostGroupBy,
+        since: Option<String>,
+        until: Option<String>,
+    ) -> Self {
+        let groups = aggregate(costs, group_by);
+        Self {
+            group_by,
+            since,
+            until,
+            total_cents: groups.iter().map(|g| g.cost_cents).sum(),
+            turns: groups.iter().map(|g| g.turns).sum(),
+            groups,
+        }
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("key,label,cost_cents,cost_usd,turns\n");
+        for group in &self.groups {
+            let _ = writeln!(
+                out,
+                "{},{},{},{:.2},{}",
+                csv_field(&group.key),
+                csv_field(group.label.as_deref().unwrap_or_default()),
+                group.cost_cents,
+                group.cost_cents as f64 / 100.0,
+                group.turns
+            );
+        }
+        out
+    }
+}
+
+/// Sum turn costs by `group_by`. Days come in order; other groupings are
+/// sorted by cost, highest first.
+pub fn aggregate(costs: &[TurnCost], group_by: CostGroupBy) -> Vec<CostGroup> {
+    let mut groups: HashMap<String, CostGroup> = HashMap::new();
+    for cost in costs {
+        let key = match group_by {
+            CostGroupBy::Mission => cost.mission_id.to_string(),
+            CostGroupBy::Backend => cost.backend.clone(),
+            CostGroupBy::Model => cost
+                .model
+                .clone()
+                .filter(|m| !m.is_empty())
+                .unwrap_or_else(|| "unknown".to_string()),
+            CostGroupBy::Day => cost.timestamp.get(..10).unwrap_or_default().to_string(),
+        };
+        let group = groups.entry(key.clone()).or_insert_with(|| CostGroup {
+            key,
+            label: None,
+            cost_cents: 0,
+            turns: 0,
+        });
+        if group_by == CostGroupBy::Mission && group.label.is_none() {
+            group.label = cost.mission_title.clone();
+        }
+        group.cost_cents += cost.cost_cents;
+        group.turns += 1;
+    }
+    let mut groups: Vec<CostGroup> = groups.into_values().collect();
+    if group_by == CostGroupBy::Day {
+        groups.sort_by(|a, b| a.key.cmp(&b.key));
+    } else {
+        groups.sort_by(|a, b| b.cost_cents.cmp(&a.cost_cents).then(a.key.cmp(&b.key)));
+    }
+    groups
+}
+
+/// Parse a range bound. A bare date starts at midnight UTC; as an `until`
+/// bound it includes the whole day.
+fn parse_bound(value: &str, until: bool) -> Result<DateTime<Utc>, String> {
+    let value = value.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        let date = if until {
+            date.succ_opt().unwrap_or(date)
+        } else {
+            date
+        };
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc());
+    }
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|_| format!("Invalid date '{}': use RFC 3339 or YYYY-MM-DD", value))
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Costs of the user's missions, grouped.
+pub async fn get_costs(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Query(query): Query<CostQuery>,
+) -> Result<Response, (StatusCode, String)> {
+    let since = match (&query.since, query.days) {
+        (Some(since), _) => Some(parse_bound(since, false)),
+        (None, Some(days)) => Some(Ok(Utc::now() - Duration::days(days as i64))),
+        (None, None) => None,
+    }
+    .transpose()
+    .map_err(|e| (StatusCode::BAD_REQUEST, e))?
+    .map(|dt| dt.to_rfc3339());
+    let until = query
+        .until
+        .as_deref()
+        .map(|until| parse_bound(until, true))
+        .transpose()
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?
+        .map(|dt| dt.to_rfc3339());
+
+    let control = state.control.get_or_spawn(&user).await;
+    let costs = control
+        .mission_store
+        .get_turn_costs(since.as_deref(), until.as_deref())
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    let report = CostReport::build(&costs, query.group_by, since, until);
+
+    Ok(match query.format {
+        CostFormat::Json => Json(report).into_response(),
+        CostFormat::Csv => (
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+                (
+                    header::CONTENT_DISPOSITION,
+                    "attachment; filename=\"costs.csv\"",
+                ),
+            ],
+            report.to_csv(),
+        )
+            .into_response(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn turn(
+        mission_id: Uuid,
+        backend: &str,
+        model: Option<&str>,
+        cents: u64,
+        ts: &str,
+    ) -> TurnCost {
+        TurnCost {
+            mission_id,
+            mission_title: Some("Nightly, report".to_string()),
+            backend: backend.to_string(),
+            model: model.map(str::to_string),
+            cost_cents: cents,
+            timestamp: ts.to_string(),
+        }
+    }
+
+    fn sample() -> (Uuid, Uuid, Vec<TurnCost>) {
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+        let costs = vec![
+            turn(
+                a,
+                "opencode",
+                Some("gpt-5"),
+                120,
+                "2026-10-08T10:00:00+00:00",
+            ),
+            turn(b, "claudecode", None, 300, "2026-10-09T09:00:00+00:00"),
+            turn(
+                a,
+                "opencode",
+                Some("gpt-5"),
+                30,
+                "2026-10-09T23:59:00+00:00",
+            ),
+        ];
+        (a, b, costs)
+    }
+
+    #[test]
+    fn groups_by_mission_and_model() {
+        let (a, b, costs) = sample();
+        let groups = aggregate(&costs, CostGroupBy::Mission);
+        assert_eq!(groups[0].key, b.to_string());
+        assert_eq!(groups[1].key, a.to_string());
+        assert_eq!((groups[1].cost_cents, groups[1].turns), (150, 2));
+        assert_eq!(groups[1].label.as_deref(), Some("Nightly, report"));
+
+        let groups = aggregate(&costs, CostGroupBy::Model);
+        let keys: Vec<_> = groups.iter().map(|g| g.key.as_str()).collect();
+        assert_eq!(keys, ["unknown", "gpt-5"]);
+        assert!(groups.iter().all(|g| g.label.is_none()));
+    }
+
+    #[test]
+    fn groups_by_day_in_order() {
+        let (_, _, costs) = sample();
+        let groups = aggregate(&costs, CostGroupBy::Day);
+        let days: Vec<_> = groups
+            .iter()
+            .map(|g| (g.key.as_str(), g.cost_cents))
+            .collect();
+        assert_eq!(days, [("2026-10-08", 120), ("2026-10-09", 330)]);
+    }
+
+    #[test]
+    fn renders_csv() {
+        let (_, _, costs) = sample();
+        let report = CostReport::build(&costs, CostGroupBy::Mission, None, None);
+        assert_eq!(report.total_cents, 450);
+        assert_eq!(report.turns, 3);
+        let csv = report.to_csv();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("key,label,cost_cents,cost_usd,turns"));
+        assert!(lines
+            .next()
+            .unwrap()
+            .ends_with(",\"Nightly, report\",300,3.00,1"));
+    }
+
+    #[test]
+    fn parses_bounds() {
+        assert_eq!(
+            parse_bound("2026-10-09", false).unwrap().to_rfc3339(),
+            "2026-10-09T00:00:00+00:00"
+        );
+        assert_eq!(
+            parse_bound("2026-10-09", true).unwrap().to_rfc3339(),
+            "2026-10-10T00:00:00+00:00"
+        );
+        assert_eq!(
+            parse_bound("2026-10-09T12:00:00+02:00", false)
+                .unwrap()
+                .to_rfc3339(),
+            "2026-10-09T10:00:00+00:00"
+        );
+        assert!(parse_bound("last week", false).is_err());
+    }
+}
@@ -21,6 +21,7 @@ use tokio::sync::mpsc;
 
 use super::auth;
 use super::routes::AppState;
+use crate::tools::desktop;
 
 /// Query parameters for the desktop stream endpoint
 #[derive(Debug, Deserialize)]
@@ -135,6 +136,12 @@ enum ClientCommand {
     /// Press a key (xdotool syntax, e.g. "Return" or "ctrl+shift+T")
     #[serde(rename = "key")]
     Key { key: String, delay_ms: Option<u64> },
+    /// Replace the clipboard contents (e.g. before forwarding a paste)
+    #[serde(rename = "clipboard_set")]
+    SetClipboard { text: String },
+    /// Read the clipboard; answered with `{"t": "clipboard", "text": ...}`
+    #[serde(rename = "clipboard_get")]
+    GetClipboard,
 }
 
 #[derive(Debug, Deserialize)]
@@ -287,6 +294,28 @@ async fn handle_desktop_stream(socket: WebSocket, params: StreamParams) {
                             }
                         }
                     }
+                    ClientCommand::SetClipboard { text } => {
+                        if let Err(err) = desktop::set_clipboard(&x11_display, &text, false).await {
+                            if send_stream_error(&mut ws_sender, err).await.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                    ClientCommand::GetClipboard => {
+                        let sent = match desktop::get_clipboard(&x11_display, false).await {
+                            Ok(text) => {
+                                let msg = serde_json::json!({ "t": "clipboard", "text": text });
+                                ws_sender
+                                    .send(Message::Text(msg.to_string()))
+                                    .await
+                                    .map_err(|_| ())
+                            }
+                            Err(err) => send_stream_error(&mut ws_sender, err).await,
+                        };
+                        if sent.is_err() {
+                            return;
+                        }
+                    }
                 }
             }
 
@@ -569,4 +598,13 @@ mod tests {
         assert_eq!(50_u32.clamp(1, 30), 30);
         assert_eq!(15_u32.clamp(1, 30), 15);
     }
+
+    #[test]
+    fn test_clipboard_commands_parse() {
+        let cmd: ClientCommand =
+            serde_json::from_str(r#"{"t":"clipboard_set","text":"hello"}"#).unwrap();
+        assert!(matches!(cmd, ClientCommand::SetClipboard { text } if text == "hello"));
+        let cmd: ClientCommand = serde_json::from_str(r#"{"t":"clipboard_get"}"#).unwrap();
+        assert!(matches!(cmd, ClientCommand::GetClipboard));
+    }
 }
@@ -0,0 +1,276 @@
+//! Durable log of control session events.
+//!
+//! Every event broadcast by a control session gets a monotonically
+//! increasing id and is appended to the session's journal. The most recent
+//! events are kept in memory; events that belong to a mission are also
+//! appended to `<dir>/<mission_id>.jsonl`. SSE streams read from the journal
+//! instead of the broadcast channel, so a slow client doesn't lose events and
+//! a client reconnecting with `Last-Event-ID` gets what it missed.
+//!
+//! Ids start from the current time in microseconds, so they keep increasing
+//! across restarts without reading the logs back.
+
+use std::collections::VecDeque;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, watch};
+use uuid::Uuid;
+
+use super::control::AgentEvent;
+
+/// Events kept in memory for replay.
+const RECENT_CAPACITY: usize = 5000;
+
+/// A journaled event, ready to be sent as an SSE message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub id: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mission_id: Option<Uuid>,
+    /// SSE event name
+    pub event: String,
+    /// The event, serialized as JSON
+    pub data: String,
+}
+
+struct Recent {
+    next_id: u64,
+    entries: VecDeque<JournalEntry>,
+}
+
+pub struct EventJournal {
+    /// Where mission logs are written (`None` = memory only)
+    dir: Option<PathBuf>,
+    recent: Mutex<Recent>,
+    latest: watch::Sender<u64>,
+}
+
+/// Directory of the mission event logs.
+pub fn journal_dir(working_dir: &Path) -> PathBuf {
+    working_dir
+        .join(".sandboxed-sh")
+        .join("runtime")
+        .join("events")
+}
+
+/// Path of a mission's event log.
+pub fn mission_log_path(dir: &Path, mission_id: Uuid) -> PathBuf {
+    dir.join(format!("{}.jsonl", mission_id))
+}
+
+impl EventJournal {
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        let first_id = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_micros() as u64)
+            .unwrap_or(0)
+            .max(1);
+        Self {
+            dir,
+            recent: Mutex::new(Recent {
+                next_id: first_id,
+                entries: VecDeque::new(),
+            }),
+            latest: watch::channel(first_id - 1).0,
+        }
+    }
+
+    /// Append an event and return its id.
+    pub fn append(&self, event: &AgentEvent) -> u64 {
+        let data = match serde_json::to_string(event) {
+            Ok(data) => data,
+            Err(e) => {
+                tracing::warn!("Failed to serialize event for the journal: {}", e);
+                return *self.latest.borrow();
+            }
+        };
+        let mut recent = self.recent.lock().unwrap_or_else(|e| e.into_inner());
+        let id = recent.next_id;
+        recent.next_id += 1;
+        let entry = JournalEntry {
+            id,
+            mission_id: event.mission_id(),
+            event: event.event_name().to_string(),
+            data,
+        };
+        // Written under the lock so the log stays in id order.
+        if let (Some(dir), Some(mission_id)) = (&self.dir, entry.mission_id) {
+            if let Err(e) = append_line(dir, &mission_log_path(dir, mission_id), &entry) {
+                tracing::warn!("Failed to append to event log of {}: {}", mission_id, e);
+            }
+        }
+        if recent.entries.len() == RECENT_CAPACITY {
+            recent.entries.pop_front();
+        }
+        recent.entries.push_back(entry);
+        drop(recent);
+        self.latest.send_replace(id);
+        id
+    }
+
+    /// Whether mission events are also written to disk.
+    pub fn is_persistent(&self) -> bool {
+        self.dir.is_some()
+    }
+
+    /// Id of the last appended event, updated on every append.
+    pub fn subscribe(&self) -> watch::Receiver<u64> {
+        self.latest.subscribe()
+    }
+
+    /// Events after `after`, only those of `mission_id` if given. `None` if
+    /// some of them are no longer held in memory.
+    pub fn after(&self, after: u64, mission_id: Option<Uuid>) -> Option<Vec<JournalEntry>> {
+        let recent = self.recent.lock().unwrap_or_else(|e| e.into_inner());
+        let oldest = recent
+            .entries
+            .front()
+            .map_or(recent.next_id, |entry| entry.id);
+        if after.saturating_add(1) < oldest {
+            return None;
+        }
+        Some(
+            recent
+                .entries
+                .iter()
+                .filter(|entry| entry.id > after)
+                .filter(|entry| mission_id.is_none() || entry.mission_id == mission_id)
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// A mission's events after `after`, read back from its log.
+    pub async fn read_mission_log(&self, mission_id: Uuid, after: u64) -> Vec<JournalEntry> {
+        let Some(dir) = &self.dir else {
+            return Vec::new();
+        };
+        let path = mission_log_path(dir, mission_id);
+        tokio::task::spawn_blocking(move || read_log(&path, after))
+            .await
+            .unwrap_or_default()
+    }
+
+    /// Delete a mission's log.
+    pub fn remove_mission(&self, mission_id: Uuid) {
+        if let Some(dir) = &self.dir {
+            let _ = std::fs::remove_file(mission_log_path(dir, mission_id));
+        }
+    }
+}
+
+/// Journal every event broadcast on `events` until the channel closes.
+pub fn spawn_writer(journal: Arc<EventJournal>, mut events: broadcast::Receiver<AgentEvent>) {
+    tokio::task::spawn_blocking(move || loop {
+        match events.blocking_recv() {
+            Ok(event) => {
+                journal.append(&event);
+                super::metrics::global().observe_event(&event);
+            }
+            Err(broadcast::error::RecvError::Lagged(n)) => {
+                tracing::warn!("Event journal lagged by {} events", n);
+                super::metrics::global().lag_dropped("journal", n);
+            }
+            Err(broadcast::error::RecvError::Closed) => break,
+        }
+    });
+}
+
+fn append_line(dir: &Path, path: &Path, entry: &JournalEntry) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let mut line = serde_json::to_vec(entry)?;
+    line.push(b'\n');
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(&line)
+}
+
+fn read_log(path: &Path, after: u64) -> Vec<JournalEntry> {
+    let Ok(file) = std::fs::File::open(path) else {
+        return Vec::new();
+    };
+    std::io::BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str::<JournalEntry>(&line).ok())
+        .filter(|entry| entry.id > after)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paused_event(mission_id: Uuid) -> AgentEvent {
+        AgentEvent::MissionPaused {
+            mission_id,
+            paused: true,
+        }
+    }
+
+    #[test]
+    fn test_ids_increase_and_replay_after_id() {
+        let journal = EventJournal::new(None);
+        let mid = Uuid::new_v4();
+        let first = journal.append(&paused_event(mid));
+        let second = journal.append(&paused_event(Uuid::new_v4()));
+        let third = journal.append(&paused_event(mid));
+        assert!(first < second && second < third);
+        assert_eq!(*journal.subscribe().borrow(), third);
+
+        let all = journal.after(first, None).unwrap();
+        assert_eq!(
+            all.iter().map(|e| e.id).collect::<Vec<_>>(),
+            vec![second, third]
+        );
+        let mine = journal.after(first - 1, Some(mid)).unwrap();
+        assert_eq!(
+            mine.iter().map(|e| e.id).collect::<Vec<_>>(),
+            vec![first, third]
+        );
+        assert_eq!(mine[0].event, "mission_paused");
+        assert!(journal.after(third, None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_gap_is_reported() {
+        let journal = EventJournal::new(None);
+        let first = journal.append(&paused_event(Uuid::new_v4()));
+        // An id from before this journal existed can't be replayed.
+        assert!(journal.after(first - 10, None).is_none());
+
+        for _ in 0..RECENT_CAPACITY {
+            journal.append(&paused_event(Uuid::new_v4()));
+        }
+        assert!(journal.after(first - 1, None).is_none());
+        assert_eq!(journal.after(first, None).unwrap().len(), RECENT_CAPACITY);
+    }
+
+    #[tokio::test]
+    async fn test_mission_log_on_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let mid = Uuid::new_v4();
+        let first = {
+            let journal = EventJournal::new(Some(dir.path().to_path_buf()));
+            let first = journal.append(&paused_event(mid));
+            journal.append(&paused_event(mid));
+            first
+        };
+
+        // A later process reads what the earlier one wrote.
+        let journal = EventJournal::new(Some(dir.path().to_path_buf()));
+        let replayed = journal.read_mission_log(mid, first).await;
+        assert_eq!(replayed.len(), 1);
+        assert!(replayed[0].id > first);
+        assert_eq!(journal.read_mission_log(mid, 0).await.len(), 2);
+
+        journal.remove_mission(mid);
+        assert!(journal.read_mission_log(mid, 0).await.is_empty());
+    }
+}
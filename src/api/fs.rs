@@ -140,7 +140,7 @@ fn resolve_download_path(
     ))
 }
 
-fn content_type_for_path(path: &Path) -> &'static str {
+pub(super) fn content_type_for_path(path: &Path) -> &'static str {
     let ext = path
         .extension()
         .and_then(|e| e.to_str())
@@ -478,6 +478,17 @@ pub struct RmRequest {
     pub recursive: Option<bool>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ApplyPatchRequest {
+    /// Unified diff to apply.
+    pub patch: String,
+    /// Directory the patch paths are relative to. Resolved against the
+    /// workspace when `workspace_id` is set (defaults to the workspace root),
+    /// otherwise it must be absolute.
+    pub path: Option<String>,
+    pub workspace_id: Option<uuid::Uuid>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FsEntry {
     pub name: String,
@@ -562,6 +573,64 @@ pub async fn rm(
     Ok(Json(serde_json::json!({ "ok": true })))
 }
 
+/// Apply a unified diff under a directory; either every file changes or none.
+pub async fn apply_patch(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<ApplyPatchRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let root = match req.workspace_id {
+        Some(workspace_id) => {
+            resolve_path_for_workspace(
+                &state,
+                workspace_id,
+                req.path.as_deref().unwrap_or("."),
+                None,
+            )
+            .await?
+        }
+        None => {
+            let path = req.path.as_deref().unwrap_or_default();
+            if !Path::new(path).is_absolute() {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    "path must be absolute when workspace_id is not set".to_string(),
+                ));
+            }
+            PathBuf::from(path)
+        }
+    };
+    if !root.is_dir() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("Not a directory: {}", root.display()),
+        ));
+    }
+
+    // Patch paths must stay under the root.
+    let resolve = |path: &str| {
+        let rel = Path::new(path);
+        let escapes = rel.components().any(|c| {
+            !matches!(
+                c,
+                std::path::Component::Normal(_) | std::path::Component::CurDir
+            )
+        });
+        if escapes {
+            return Err(anyhow::anyhow!(
+                "Patch path must be relative and stay inside {}: {}",
+                root.display(),
+                path
+            ));
+        }
+        Ok(root.join(rel))
+    };
+
+    let files = crate::tools::patch::apply_patch(&req.patch, resolve)
+        .await
+        .map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, e.to_string()))?;
+    Ok(Json(serde_json::json!({ "ok": true, "files": files })))
+}
+
 pub async fn download(
     State(state): State<Arc<AppState>>,
     Query(q): Query<PathQuery>,
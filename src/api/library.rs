@@ -1,33 +1,43 @@
 //! Library management API endpoints.
 //!
 //! Provides endpoints for managing the configuration library:
-//! - Git operations (status, sync, commit, push)
+//! - Git operations (status, sync, commit, push, branches, merge conflicts, file history)
+//! - Push webhook and background sync
 //! - MCP server CRUD
 //! - Skills CRUD
-//! - Commands CRUD
+//! - Commands CRUD and argument rendering
 //! - Plugins CRUD
 //! - Library Agents CRUD
 //! - OpenCode settings (oh-my-opencode.json)
 //! - Sandboxed config (agent visibility, defaults)
 //! - Migration
+//! - Skill/command frontmatter validation
+//! - Full-text search across library content
 
 use axum::{
-    extract::{Multipart, Path, Query, State},
-    http::{HeaderMap, StatusCode},
+    body::Bytes,
+    extract::{FromRequest, Multipart, Path, Query, Request, State},
+    http::{header, HeaderMap, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
     routing::{delete, get, post, put},
     Json, Router,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+use crate::library::command_template;
+use crate::library::search::SearchHit;
+use crate::library::validate::{ItemKind, ValidationReport};
 use crate::library::{
     rename::{ItemType, RenameResult},
     AmpCodeConfig, ClaudeCodeConfig, Command, CommandSummary, ConfigProfile, ConfigProfileSummary,
-    GitAuthor, InitScript, InitScriptSummary, LibraryAgent, LibraryAgentSummary, LibraryStatus,
-    LibraryStore, McpServer, MigrationReport, Plugin, SandboxedConfig, Skill, SkillSummary,
-    WorkspaceTemplate, WorkspaceTemplateSummary,
+    ConflictResolution, GitAuthor, InitScript, InitScriptSummary, LibraryAgent,
+    LibraryAgentSummary, LibraryBranch, LibraryCommit, LibraryConflict, LibraryStatus,
+    LibraryStore, McpServer, MigrationReport, Plugin, SandboxedConfig, Skill, SkillSettings,
+    SkillSummary, WorkspaceTemplate, WorkspaceTemplateSummary,
 };
 use crate::nspawn::NspawnDistro;
 use crate::workspace::{self, WorkspaceType, DEFAULT_WORKSPACE_ID};
@@ -123,11 +133,12 @@ async fn ensure_library(
             "Library not configured. Set a Git repo in Settings.".to_string(),
         )
     })?;
+    let layers = state.settings.get_library_layers().await;
 
     {
         let library_guard = state.library.read().await;
         if let Some(library) = library_guard.as_ref() {
-            if library.remote() == remote {
+            if library.remote() == remote && library.layer_configs() == layers.as_slice() {
                 return Ok(Arc::clone(library));
             }
         }
@@ -135,14 +146,14 @@ async fn ensure_library(
 
     let mut library_guard = state.library.write().await;
     if let Some(library) = library_guard.as_ref() {
-        if library.remote() == remote {
+        if library.remote() == remote && library.layer_configs() == layers.as_slice() {
             return Ok(Arc::clone(library));
         }
     }
 
-    match LibraryStore::new(state.config.library_path.clone(), &remote).await {
+    match LibraryStore::with_layers(state.config.library_path.clone(), &remote, &layers).await {
         Ok(store) => {
-            let store = Arc::new(store);
+            let store = Arc::new(store.with_read_only(state.config.library_read_only));
             *library_guard = Some(Arc::clone(&store));
             drop(library_guard);
             sync_all_workspaces(state, store.as_ref()).await;
@@ -155,25 +166,81 @@ async fn ensure_library(
     }
 }
 
-/// Create library routes.
-pub fn routes() -> Router<Arc<super::routes::AppState>> {
+/// POST routes still served when the library is read-only. They only pull
+/// from the remote, reset local state or don't touch the library at all.
+const READ_ONLY_POST_ROUTES: &[&str] = &[
+    "/sync",
+    "/force-sync",
+    "/discard",
+    "/checkout",
+    "/conflicts/abort",
+    "/validate",
+];
+
+/// Answer 404 for write routes when the library is read-only.
+async fn hide_write_routes(request: Request, next: Next) -> Response {
+    let path = request.uri().path();
+    let allowed = match *request.method() {
+        Method::GET | Method::HEAD => true,
+        Method::POST => READ_ONLY_POST_ROUTES.contains(&path) || path.ends_with("/render"),
+        _ => false,
+    };
+    if allowed {
+        next.run(request).await
+    } else {
+        StatusCode::NOT_FOUND.into_response()
+    }
+}
+
+/// Create library routes. A read-only library only gets the routes that
+/// leave it unchanged.
+pub fn routes(read_only: bool) -> Router<Arc<super::routes::AppState>> {
+    if read_only {
+        library_routes().layer(axum::middleware::from_fn(hide_write_routes))
+    } else {
+        library_routes()
+    }
+}
+
+fn library_routes() -> Router<Arc<super::routes::AppState>> {
     Router::new()
         // Git operations
         .route("/status", get(get_status))
         .route("/sync", post(sync_library))
         .route("/force-sync", post(force_sync_library))
         .route("/force-push", post(force_push_library))
+        .route("/discard", post(discard_library_changes))
         .route("/commit", post(commit_library))
         .route("/push", post(push_library))
+        .route("/branches", get(list_branches))
+        .route("/branches", post(create_branch))
+        .route("/checkout", post(checkout_branch))
+        .route("/conflicts", get(list_conflicts))
+        .route("/conflicts/file", get(get_conflict))
+        .route("/conflicts/resolve", post(resolve_conflict))
+        .route("/conflicts/abort", post(abort_merge))
+        .route("/history", get(get_file_history))
+        .route("/diff", get(get_file_diff))
+        .route("/revert", post(revert_file))
+        // Validation
+        .route("/validate", post(validate_item))
+        // Search
+        .route("/search", get(search_library))
         // MCP servers
         .route("/mcps", get(get_mcps))
         .route("/mcps", put(save_mcps))
+        .route("/mcps/origins", get(get_mcp_origins))
+        .route("/mcps/resolved", get(get_resolved_mcps))
+        .route("/layers", get(list_layers))
         // Skills
         .route("/skill", get(list_skills))
         .route("/skill/import", post(import_skill))
         .route("/skill/:name", get(get_skill))
         .route("/skill/:name", put(save_skill))
         .route("/skill/:name", delete(delete_skill))
+        .route("/skill/:name/export", get(export_skill))
+        .route("/skill/:name/settings", put(save_skill_settings))
+        .route("/skill/:name/rename", post(rename_skill))
         .route("/skill/:name/files/*path", get(get_skill_reference))
         .route("/skill/:name/files/*path", put(save_skill_reference))
         .route("/skill/:name/files/*path", delete(delete_skill_reference))
@@ -183,6 +250,9 @@ pub fn routes() -> Router<Arc<super::routes::AppState>> {
         .route("/skills/:name", get(get_skill))
         .route("/skills/:name", put(save_skill))
         .route("/skills/:name", delete(delete_skill))
+        .route("/skills/:name/export", get(export_skill))
+        .route("/skills/:name/settings", put(save_skill_settings))
+        .route("/skills/:name/rename", post(rename_skill))
         .route("/skills/:name/references/*path", get(get_skill_reference))
         .route("/skills/:name/references/*path", put(save_skill_reference))
         .route(
@@ -194,11 +264,15 @@ pub fn routes() -> Router<Arc<super::routes::AppState>> {
         .route("/command/:name", get(get_command))
         .route("/command/:name", put(save_command))
         .route("/command/:name", delete(delete_command))
+        .route("/command/:name/render", post(render_command))
+        .route("/command/:name/rename", post(rename_command))
         // Legacy commands routes (dashboard still calls /commands)
         .route("/commands", get(list_commands))
         .route("/commands/:name", get(get_command))
         .route("/commands/:name", put(save_command))
         .route("/commands/:name", delete(delete_command))
+        .route("/commands/:name/render", post(render_command))
+        .route("/commands/:name/rename", post(rename_command))
         // Builtin commands (runtime-specific slash commands)
         .route("/builtin-commands", get(get_builtin_commands))
         // Plugins
@@ -317,15 +391,110 @@ pub struct CommitRequest {
     message: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct CreateBranchRequest {
+    name: String,
+    /// Commit or branch to start from (default: the current HEAD)
+    #[serde(default)]
+    start_point: Option<String>,
+    /// Switch the library to the new branch after creating it
+    #[serde(default)]
+    checkout: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CheckoutBranchRequest {
+    branch: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConflictQuery {
+    path: String,
+}
+
+/// `{"path": "...", "resolution": "ours" | "theirs" | "manual", "content": "..."}`
+#[derive(Debug, Deserialize)]
+pub struct ResolveConflictRequest {
+    path: String,
+    #[serde(flatten)]
+    resolution: ConflictResolution,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FileHistoryQuery {
+    path: String,
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResolvedMcpsQuery {
+    /// Environment to resolve for (default: `LIBRARY_ENVIRONMENT`)
+    environment: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LibrarySearchQuery {
+    q: String,
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FileDiffQuery {
+    path: String,
+    /// Older revision
+    from: String,
+    /// Newer revision (default: the working tree)
+    to: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RevertFileRequest {
+    path: String,
+    rev: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ResolveConflictResponse {
+    /// Files that still have conflicts
+    remaining: Vec<String>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct SaveContentRequest {
     content: String,
 }
 
+/// `?strict=true` rejects content that fails validation.
+#[derive(Debug, Default, Deserialize)]
+pub struct SaveOptions {
+    #[serde(default)]
+    strict: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ValidateRequest {
+    kind: ItemKind,
+    name: String,
+    /// Content to check (default: the saved content)
+    content: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ImportSkillRequest {
     /// Skill name (required for file upload)
-    name: String,
+    name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportSkillFromUrlRequest {
+    /// Git repository URL, or a `.tar.gz`/`.tgz`/`.tar` URL
+    url: String,
+    /// Subdirectory holding the skill (default: the root)
+    #[serde(default)]
+    path: Option<String>,
+    /// Skill name (default: the frontmatter `name`, then the URL)
+    #[serde(default)]
+    name: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -453,8 +622,12 @@ async fn get_status(
 
 /// POST /api/library/sync - Pull latest changes from remote.
 ///
+/// Returns 409 Conflict with `MERGE_CONFLICT:` if merging remote changes hit
+/// conflicts; resolve them via /conflicts, then /commit (or /conflicts/abort).
 /// Returns 409 Conflict if history has diverged (e.g., after force push).
 /// In that case, use /force-sync to reset to remote or /force-push to overwrite remote.
+/// Uncommitted local changes are kept; 409 with `STASH_CONFLICT:` means they
+/// conflict with the pulled files (resolve via /conflicts, or /discard).
 async fn sync_library(
     State(state): State<Arc<super::routes::AppState>>,
     headers: HeaderMap,
@@ -464,6 +637,9 @@ async fn sync_library(
     // Try to sync - check for diverged history error
     if let Err(e) = library.sync().await {
         let error_msg = e.to_string();
+        if error_msg.starts_with("MERGE_CONFLICT:") || error_msg.starts_with("STASH_CONFLICT:") {
+            return Err((StatusCode::CONFLICT, error_msg));
+        }
         if error_msg.starts_with("DIVERGED_HISTORY:") {
             // Return 409 Conflict with a structured error message
             let msg = error_msg
@@ -483,16 +659,21 @@ async fn sync_library(
 /// POST /api/library/force-sync - Force reset local branch to match remote.
 ///
 /// Use this when local and remote histories have diverged (e.g., after a force push on remote).
-/// This discards any local changes and resets to the remote state.
+/// This discards local commits and resets to the remote state. Uncommitted
+/// changes are re-applied on top (409 `STASH_CONFLICT:` if they conflict).
 async fn force_sync_library(
     State(state): State<Arc<super::routes::AppState>>,
     headers: HeaderMap,
 ) -> Result<(StatusCode, String), (StatusCode, String)> {
     let library = ensure_library(&state, &headers).await?;
-    library
-        .force_sync()
-        .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    library.force_sync().await.map_err(|e| {
+        let error_msg = e.to_string();
+        if error_msg.starts_with("STASH_CONFLICT:") {
+            (StatusCode::CONFLICT, error_msg)
+        } else {
+            (StatusCode::INTERNAL_SERVER_ERROR, error_msg)
+        }
+    })?;
 
     // Sync all library configurations
     sync_library_configs(&state, library.as_ref()).await?;
@@ -503,6 +684,175 @@ async fn force_sync_library(
     ))
 }
 
+/// POST /api/library/discard - Discard uncommitted local changes.
+///
+/// Drops modified and untracked files, changes a sync left stashed, and any
+/// merge in progress. Local commits are kept.
+async fn discard_library_changes(
+    State(state): State<Arc<super::routes::AppState>>,
+    headers: HeaderMap,
+) -> Result<(StatusCode, String), (StatusCode, String)> {
+    let library = ensure_library(&state, &headers).await?;
+    library
+        .discard_local_changes()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    sync_library_configs(&state, library.as_ref()).await?;
+
+    Ok((StatusCode::OK, "Local changes discarded".to_string()))
+}
+
+/// Pull the library without user interaction (webhook or background loop)
+/// and, if anything changed, re-sync configs and workspaces.
+async fn auto_sync_library(state: &Arc<super::routes::AppState>, trigger: &str) {
+    let library = match ensure_library(state, &HeaderMap::new()).await {
+        Ok(library) => library,
+        Err((_, e)) => {
+            tracing::warn!(trigger, error = %e, "Library auto-sync skipped");
+            return;
+        }
+    };
+    match library.auto_sync().await {
+        Ok(true) => {}
+        Ok(false) => return,
+        Err(e) => {
+            tracing::warn!(trigger, error = %e, "Library auto-sync failed");
+            return;
+        }
+    }
+    match sync_library_configs(state, library.as_ref()).await {
+        Ok(()) => tracing::info!(trigger, "Library auto-synced"),
+        Err((_, e)) => tracing::warn!(trigger, error = %e, "Failed to apply auto-synced library"),
+    }
+}
+
+/// Sync the library every `LIBRARY_SYNC_INTERVAL_SECS`, plus up to 10% random
+/// jitter so instances sharing a remote don't pull in lockstep.
+pub async fn library_sync_loop(state: Arc<super::routes::AppState>) {
+    use rand::Rng;
+
+    let interval = state.config.library_sync_interval_secs;
+    tracing::info!(interval_secs = interval, "Background library sync started");
+    loop {
+        let jitter = rand::thread_rng().gen_range(0..=interval / 10);
+        tokio::time::sleep(std::time::Duration::from_secs(interval + jitter)).await;
+        if state.settings.get_library_remote().await.is_none() {
+            continue;
+        }
+        auto_sync_library(&state, "interval").await;
+    }
+}
+
+/// Commit and push library edits once they have been left alone for
+/// `LIBRARY_AUTO_COMMIT_SECS`. The outcome shows up in `/status`.
+pub async fn library_auto_commit_loop(state: Arc<super::routes::AppState>) {
+    let window = state.config.library_auto_commit_secs;
+    tracing::info!(
+        window_secs = window,
+        "Background library auto-commit started"
+    );
+    loop {
+        tokio::time::sleep(std::time::Duration::from_secs(window)).await;
+        if state.settings.get_library_remote().await.is_none() {
+            continue;
+        }
+        let library = match ensure_library(&state, &HeaderMap::new()).await {
+            Ok(library) => library,
+            Err((_, e)) => {
+                tracing::warn!(error = %e, "Library auto-commit skipped");
+                continue;
+            }
+        };
+        if let Err(e) = library.auto_commit(window).await {
+            tracing::warn!(error = %e, "Library auto-commit failed");
+        }
+    }
+}
+
+/// Check a library webhook request against `secret`. GitHub signs the body
+/// (`X-Hub-Signature-256: sha256=<hex hmac>`); GitLab sends the secret itself
+/// (`X-Gitlab-Token`), which is compared in constant time.
+fn verify_library_webhook(secret: &str, headers: &HeaderMap, body: &[u8]) -> Result<(), String> {
+    use hmac::{Hmac, Mac};
+    use sha2::Sha256;
+
+    let mac = |data: &[u8]| {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key");
+        mac.update(data);
+        mac
+    };
+
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    if let Some(signature) = header("x-hub-signature-256") {
+        let signature = signature.trim();
+        let signature = signature.strip_prefix("sha256=").unwrap_or(signature);
+        let signature =
+            hex::decode(signature).map_err(|_| "Invalid webhook signature".to_string())?;
+        return mac(body)
+            .verify_slice(&signature)
+            .map_err(|_| "Invalid webhook signature".to_string());
+    }
+    if let Some(token) = header("x-gitlab-token") {
+        let expected = mac(secret.as_bytes()).finalize().into_bytes();
+        return mac(token.as_bytes())
+            .verify_slice(&expected)
+            .map_err(|_| "Invalid webhook token".to_string());
+    }
+    Err("Missing webhook signature".to_string())
+}
+
+/// POST /api/library/webhook - Sync the library when its remote is pushed to.
+///
+/// Public route, authenticated with `LIBRARY_WEBHOOK_SECRET` (404 when unset).
+/// Only pushes to the library's current branch start a sync, which runs in the
+/// background (202); pings, other events and other branches get 200.
+pub async fn library_webhook(
+    State(state): State<Arc<super::routes::AppState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<(StatusCode, String), (StatusCode, String)> {
+    let secret = state.config.library_webhook_secret.as_deref().ok_or((
+        StatusCode::NOT_FOUND,
+        "Library webhook is not configured".to_string(),
+    ))?;
+    verify_library_webhook(secret, &headers, &body).map_err(|e| (StatusCode::UNAUTHORIZED, e))?;
+
+    let event = headers
+        .get("x-github-event")
+        .or_else(|| headers.get("x-gitlab-event"))
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("push");
+    if event == "ping" {
+        return Ok((StatusCode::OK, "pong".to_string()));
+    }
+    if !matches!(event, "push" | "Push Hook") {
+        return Ok((StatusCode::OK, format!("Ignored {} event", event)));
+    }
+
+    let library = ensure_library(&state, &HeaderMap::new()).await?;
+    let pushed_ref = serde_json::from_slice::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|payload| payload.get("ref")?.as_str().map(str::to_string));
+    if let Some(pushed_ref) = pushed_ref {
+        let branch = library
+            .status()
+            .await
+            .map(|status| status.branch)
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        if pushed_ref != format!("refs/heads/{}", branch) {
+            return Ok((StatusCode::OK, format!("Ignored push to {}", pushed_ref)));
+        }
+    }
+
+    let state = Arc::clone(&state);
+    tokio::spawn(async move {
+        auto_sync_library(&state, "webhook").await;
+    });
+    Ok((StatusCode::ACCEPTED, "Library sync started".to_string()))
+}
+
 /// POST /api/library/force-push - Force push local changes to remote.
 ///
 /// Use this when you want to keep local changes and overwrite the remote history.
@@ -552,6 +902,292 @@ async fn push_library(
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
 }
 
+/// GET /api/library/branches - List local and remote library branches.
+async fn list_branches(
+    State(state): State<Arc<super::routes::AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<LibraryBranch>>, (StatusCode, String)> {
+    let library = ensure_library(&state, &headers).await?;
+    library
+        .list_branches()
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// POST /api/library/branches - Create a branch, optionally switching to it.
+async fn create_branch(
+    State(state): State<Arc<super::routes::AppState>>,
+    headers: HeaderMap,
+    Json(req): Json<CreateBranchRequest>,
+) -> Result<(StatusCode, String), (StatusCode, String)> {
+    let library = ensure_library(&state, &headers).await?;
+    library
+        .create_branch(req.name.trim(), req.start_point.as_deref())
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+
+    if req.checkout {
+        library
+            .checkout_branch(req.name.trim())
+            .await
+            .map_err(|e| (StatusCode::CONFLICT, e.to_string()))?;
+        sync_library_configs(&state, library.as_ref()).await?;
+    }
+
+    Ok((
+        StatusCode::CREATED,
+        format!("Created branch {}", req.name.trim()),
+    ))
+}
+
+/// POST /api/library/checkout - Switch the library to another branch.
+///
+/// Returns 409 Conflict if the library has uncommitted changes. Workspaces
+/// pinned to a branch are unaffected.
+async fn checkout_branch(
+    State(state): State<Arc<super::routes::AppState>>,
+    headers: HeaderMap,
+    Json(req): Json<CheckoutBranchRequest>,
+) -> Result<(StatusCode, String), (StatusCode, String)> {
+    let library = ensure_library(&state, &headers).await?;
+    library
+        .checkout_branch(req.branch.trim())
+        .await
+        .map_err(|e| (StatusCode::CONFLICT, e.to_string()))?;
+
+    // Skills and configs now come from the new branch
+    sync_library_configs(&state, library.as_ref()).await?;
+
+    Ok((
+        StatusCode::OK,
+        format!("Checked out branch {}", req.branch.trim()),
+    ))
+}
+
+/// GET /api/library/conflicts - List files with unresolved merge conflicts.
+async fn list_conflicts(
+    State(state): State<Arc<super::routes::AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<String>>, (StatusCode, String)> {
+    let library = ensure_library(&state, &headers).await?;
+    library
+        .conflicts()
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// GET /api/library/conflicts/file?path=... - Base, local and remote versions of a conflicted file.
+async fn get_conflict(
+    State(state): State<Arc<super::routes::AppState>>,
+    Query(query): Query<ConflictQuery>,
+    headers: HeaderMap,
+) -> Result<Json<LibraryConflict>, (StatusCode, String)> {
+    let library = ensure_library(&state, &headers).await?;
+    library
+        .get_conflict(&query.path)
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::NOT_FOUND, e.to_string()))
+}
+
+/// POST /api/library/conflicts/resolve - Resolve a conflicted file (ours, theirs or manual content).
+///
+/// Once no conflicts remain, POST /api/library/commit concludes the merge.
+async fn resolve_conflict(
+    State(state): State<Arc<super::routes::AppState>>,
+    headers: HeaderMap,
+    Json(req): Json<ResolveConflictRequest>,
+) -> Result<Json<ResolveConflictResponse>, (StatusCode, String)> {
+    let library = ensure_library(&state, &headers).await?;
+    library
+        .resolve_conflict(&req.path, &req.resolution)
+        .await
+        .map(|remaining| Json(ResolveConflictResponse { remaining }))
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))
+}
+
+/// POST /api/library/conflicts/abort - Abort the merge and restore the pre-sync state.
+async fn abort_merge(
+    State(state): State<Arc<super::routes::AppState>>,
+    headers: HeaderMap,
+) -> Result<(StatusCode, String), (StatusCode, String)> {
+    let library = ensure_library(&state, &headers).await?;
+    library
+        .abort_merge()
+        .await
+        .map(|_| (StatusCode::OK, "Merge aborted".to_string()))
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// GET /api/library/history?path=...&limit=... - Commits that touched a library file.
+async fn get_file_history(
+    State(state): State<Arc<super::routes::AppState>>,
+    Query(query): Query<FileHistoryQuery>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<LibraryCommit>>, (StatusCode, String)> {
+    let library = ensure_library(&state, &headers).await?;
+    let limit = query.limit.unwrap_or(50).clamp(1, 500);
+    library
+        .file_history(&query.path, limit)
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))
+}
+
+/// GET /api/library/diff?path=...&from=...&to=... - Diff a library file between revisions.
+async fn get_file_diff(
+    State(state): State<Arc<super::routes::AppState>>,
+    Query(query): Query<FileDiffQuery>,
+    headers: HeaderMap,
+) -> Result<(StatusCode, String), (StatusCode, String)> {
+    let library = ensure_library(&state, &headers).await?;
+    library
+        .diff(&query.path, &query.from, query.to.as_deref())
+        .await
+        .map(|diff| (StatusCode::OK, diff))
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))
+}
+
+/// POST /api/library/revert - Restore a library file to an earlier revision.
+///
+/// The file is restored in the working tree (commit it like any other edit)
+/// and synced to workspaces right away.
+async fn revert_file(
+    State(state): State<Arc<super::routes::AppState>>,
+    headers: HeaderMap,
+    Json(req): Json<RevertFileRequest>,
+) -> Result<(StatusCode, String), (StatusCode, String)> {
+    let library = ensure_library(&state, &headers).await?;
+    library
+        .revert(&req.path, &req.rev)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+
+    // Skill edits only need that skill re-synced; anything else may be config.
+    let skill = req
+        .path
+        .strip_prefix("skill/")
+        .and_then(|rest| rest.split('/').next());
+    match skill {
+        Some(name) if !name.is_empty() => {
+            sync_skill_to_workspaces(&state, library.as_ref(), name).await
+        }
+        _ => sync_library_configs(&state, library.as_ref()).await?,
+    }
+
+    Ok((
+        StatusCode::OK,
+        format!("Reverted {} to {}", req.path, req.rev),
+    ))
+}
+
+/// POST /api/library/validate - Check a skill or command against the frontmatter schema.
+async fn validate_item(
+    State(state): State<Arc<super::routes::AppState>>,
+    headers: HeaderMap,
+    Json(req): Json<ValidateRequest>,
+) -> Result<Json<ValidationReport>, (StatusCode, String)> {
+    let library = ensure_library(&state, &headers).await?;
+    let not_found = |e: anyhow::Error| (StatusCode::NOT_FOUND, e.to_string());
+    let report = match req.kind {
+        ItemKind::Skill => {
+            let content = match req.content {
+                Some(content) => content,
+                None => {
+                    library
+                        .get_skill(&req.name)
+                        .await
+                        .map_err(not_found)?
+                        .content
+                }
+            };
+            library.validate_skill(&req.name, &content)
+        }
+        ItemKind::Command => {
+            let content = match req.content {
+                Some(content) => content,
+                None => {
+                    library
+                        .get_command(&req.name)
+                        .await
+                        .map_err(not_found)?
+                        .content
+                }
+            };
+            library.validate_command(&content)
+        }
+    };
+    Ok(Json(report))
+}
+
+/// 422 with the validation issues if `report` is not valid.
+fn reject_invalid(report: ValidationReport) -> Result<(), (StatusCode, String)> {
+    if report.valid {
+        return Ok(());
+    }
+    Err((
+        StatusCode::UNPROCESSABLE_ENTITY,
+        format!("Validation failed: {}", report.summary()),
+    ))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Search
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// GET /api/library/search?q=...&limit=... - Search skills, skill references,
+/// commands and MCP definitions.
+async fn search_library(
+    State(state): State<Arc<super::routes::AppState>>,
+    Query(query): Query<LibrarySearchQuery>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<SearchHit>>, (StatusCode, String)> {
+    if query.q.trim().is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "q must not be empty".to_string()));
+    }
+    let library = ensure_library(&state, &headers).await?;
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+    library
+        .search(&query.q, limit)
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Library Layers
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// A configured library layer and whether it could be opened.
+#[derive(Debug, Serialize)]
+pub struct LibraryLayerInfo {
+    pub name: String,
+    pub remote: String,
+    pub active: bool,
+}
+
+/// GET /api/library/layers - List library layers in priority order.
+async fn list_layers(
+    State(state): State<Arc<super::routes::AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<LibraryLayerInfo>>, (StatusCode, String)> {
+    let library = ensure_library(&state, &headers).await?;
+    let active = library.layer_names();
+    Ok(Json(
+        library
+            .layer_configs()
+            .iter()
+            .map(|layer| LibraryLayerInfo {
+                name: layer.name.clone(),
+                remote: layer.remote.clone(),
+                active: active.contains(&layer.name),
+            })
+            .collect(),
+    ))
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // MCP Servers
 // ─────────────────────────────────────────────────────────────────────────────
@@ -569,6 +1205,39 @@ async fn get_mcps(
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
 }
 
+/// GET /api/library/mcps/origins - Layer name for each MCP server that comes
+/// from a library layer rather than the primary library.
+async fn get_mcp_origins(
+    State(state): State<Arc<super::routes::AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<HashMap<String, String>>, (StatusCode, String)> {
+    let library = ensure_library(&state, &headers).await?;
+    library
+        .mcp_server_origins()
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// GET /api/library/mcps/resolved - MCP server definitions with the overlays
+/// for `?environment=` (default: the configured `LIBRARY_ENVIRONMENT`) applied.
+async fn get_resolved_mcps(
+    State(state): State<Arc<super::routes::AppState>>,
+    Query(query): Query<ResolvedMcpsQuery>,
+    headers: HeaderMap,
+) -> Result<Json<HashMap<String, McpServer>>, (StatusCode, String)> {
+    let library = ensure_library(&state, &headers).await?;
+    let environment = query
+        .environment
+        .as_deref()
+        .or(state.config.library_environment.as_deref());
+    library
+        .get_mcp_servers_for_environment(environment)
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
 /// PUT /api/library/mcps - Save all MCP server definitions.
 async fn save_mcps(
     State(state): State<Arc<super::routes::AppState>>,
@@ -620,10 +1289,14 @@ async fn get_skill(
 async fn save_skill(
     State(state): State<Arc<super::routes::AppState>>,
     Path(name): Path<String>,
+    Query(options): Query<SaveOptions>,
     headers: HeaderMap,
     Json(req): Json<SaveContentRequest>,
 ) -> Result<(StatusCode, String), (StatusCode, String)> {
     let library = ensure_library(&state, &headers).await?;
+    if options.strict {
+        reject_invalid(library.validate_skill(&name, &req.content))?;
+    }
     library
         .save_skill(&name, &req.content)
         .await
@@ -647,6 +1320,29 @@ async fn delete_skill(
     Ok((StatusCode::OK, "Skill deleted successfully".to_string()))
 }
 
+/// PUT /api/library/skills/:name/settings - Enable or disable a skill, or limit
+/// it to some workspaces (`{"enabled": false}`, `{"workspaces": ["dev"]}`).
+async fn save_skill_settings(
+    State(state): State<Arc<super::routes::AppState>>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+    Json(settings): Json<SkillSettings>,
+) -> Result<Json<SkillSettings>, (StatusCode, String)> {
+    let library = ensure_library(&state, &headers).await?;
+    library
+        .set_skill_settings(&name, settings.clone())
+        .await
+        .map_err(|e| {
+            if e.to_string().contains("not found") {
+                (StatusCode::NOT_FOUND, e.to_string())
+            } else {
+                (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+            }
+        })?;
+    sync_skill_to_workspaces(&state, library.as_ref(), &name).await;
+    Ok(Json(settings))
+}
+
 /// GET /api/library/skills/:name/references/*path - Get a reference file.
 async fn get_skill_reference(
     State(state): State<Arc<super::routes::AppState>>,
@@ -706,24 +1402,104 @@ async fn delete_skill_reference(
     Ok((StatusCode::OK, "Reference deleted successfully".to_string()))
 }
 
-/// POST /api/library/skills/import - Import a skill from a file upload (.zip or .md).
+/// GET /api/library/skills/:name/export - Download a skill as a tar.gz with a
+/// manifest. Encrypted values are emptied in the exported files.
+async fn export_skill(
+    State(state): State<Arc<super::routes::AppState>>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+) -> Result<impl axum::response::IntoResponse, (StatusCode, String)> {
+    let library = ensure_library(&state, &headers).await?;
+    let archive = library.export_skill(&name).await.map_err(|e| {
+        if e.to_string().contains("not found") {
+            (StatusCode::NOT_FOUND, e.to_string())
+        } else {
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+        }
+    })?;
+
+    let headers = [
+        (header::CONTENT_TYPE, "application/gzip".to_string()),
+        (
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}.tar.gz\"", name),
+        ),
+    ];
+    Ok((headers, archive))
+}
+
+/// POST /api/library/skills/import - Import a skill from a file upload or a URL.
 ///
-/// Accepts multipart form data with:
+/// Multipart form data imports an uploaded file:
 /// - `name`: skill name (query parameter)
 /// - `file`: the uploaded file (.zip or .md)
 ///
 /// For .md files: creates a skill with the file as SKILL.md
 /// For .zip files: extracts and looks for SKILL.md in the archive
+///
+/// A JSON body (`{ "url", "path"?, "name"? }`) imports from a git repository
+/// or tarball URL instead; see `LibraryStore::import_skill_from_url`.
 async fn import_skill(
     State(state): State<Arc<super::routes::AppState>>,
     headers: HeaderMap,
     Query(req): Query<ImportSkillRequest>,
+    request: Request,
+) -> Result<Json<Skill>, (StatusCode, String)> {
+    let is_json = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
+
+    if is_json {
+        let Json(body) = Json::<ImportSkillFromUrlRequest>::from_request(request, &state)
+            .await
+            .map_err(|e| (StatusCode::BAD_REQUEST, e.body_text()))?;
+        return import_skill_from_url(&state, &headers, body).await;
+    }
+
+    let multipart = Multipart::from_request(request, &state)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.body_text()))?;
+    import_skill_from_upload(&state, &headers, req.name.unwrap_or_default(), multipart).await
+}
+
+async fn import_skill_from_url(
+    state: &Arc<super::routes::AppState>,
+    headers: &HeaderMap,
+    req: ImportSkillFromUrlRequest,
+) -> Result<Json<Skill>, (StatusCode, String)> {
+    let library = ensure_library(state, headers).await?;
+    let skill = library
+        .import_skill_from_url(&req.url, req.path.as_deref(), req.name.as_deref())
+        .await
+        .map_err(|e| {
+            let message = e.to_string();
+            if let Some(summary) = message.strip_prefix("INVALID_SKILL: ") {
+                (
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    format!("Validation failed: {}", summary),
+                )
+            } else if message.contains("already exists") {
+                (StatusCode::CONFLICT, message)
+            } else {
+                (StatusCode::BAD_REQUEST, message)
+            }
+        })?;
+
+    sync_skill_to_workspaces(state, library.as_ref(), &skill.name).await;
+    Ok(Json(skill))
+}
+
+async fn import_skill_from_upload(
+    state: &Arc<super::routes::AppState>,
+    headers: &HeaderMap,
+    name: String,
     mut multipart: Multipart,
 ) -> Result<Json<Skill>, (StatusCode, String)> {
-    let library = ensure_library(&state, &headers).await?;
+    let library = ensure_library(state, headers).await?;
 
     // Validate skill name
-    let skill_name = req.name.trim().to_lowercase();
+    let skill_name = name.trim().to_lowercase();
     if skill_name.is_empty() {
         return Err((
             StatusCode::BAD_REQUEST,
@@ -831,7 +1607,7 @@ async fn import_skill(
         )
     })?;
 
-    sync_skill_to_workspaces(&state, library.as_ref(), &skill_name).await;
+    sync_skill_to_workspaces(state, library.as_ref(), &skill_name).await;
     Ok(Json(skill))
 }
 
@@ -944,14 +1720,58 @@ async fn get_command(
     })
 }
 
+/// Argument values for rendering a command.
+#[derive(Debug, Deserialize)]
+pub struct RenderCommandRequest {
+    #[serde(default)]
+    pub args: HashMap<String, serde_json::Value>,
+}
+
+/// A command body with its arguments substituted.
+#[derive(Debug, Serialize)]
+pub struct RenderedCommand {
+    pub name: String,
+    pub content: String,
+}
+
+/// POST /api/library/commands/:name/render - Substitute typed arguments into a
+/// command. Returns 422 when arguments are unknown, missing or mistyped.
+async fn render_command(
+    State(state): State<Arc<super::routes::AppState>>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+    Json(req): Json<RenderCommandRequest>,
+) -> Result<Json<RenderedCommand>, (StatusCode, String)> {
+    let library = ensure_library(&state, &headers).await?;
+    let command = library.get_command(&name).await.map_err(|e| {
+        if e.to_string().contains("not found") {
+            (StatusCode::NOT_FOUND, e.to_string())
+        } else {
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+        }
+    })?;
+    let content =
+        command_template::render_command(&command.content, &req.args).map_err(|report| {
+            (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                format!("Invalid arguments: {}", report.summary()),
+            )
+        })?;
+    Ok(Json(RenderedCommand { name, content }))
+}
+
 /// PUT /api/library/commands/:name - Save a command.
 async fn save_command(
     State(state): State<Arc<super::routes::AppState>>,
     Path(name): Path<String>,
+    Query(options): Query<SaveOptions>,
     headers: HeaderMap,
     Json(req): Json<SaveContentRequest>,
 ) -> Result<(StatusCode, String), (StatusCode, String)> {
     let library = ensure_library(&state, &headers).await?;
+    if options.strict {
+        reject_invalid(library.validate_command(&req.content))?;
+    }
     library
         .save_command(&name, &req.content)
         .await
@@ -1765,11 +2585,45 @@ async fn rename_item(
         }
     };
 
-    let library = ensure_library(&state, &headers).await?;
+    apply_rename(&state, &headers, item_type, &name, req).await
+}
+
+/// POST /api/library/skills/:name/rename - Rename a skill, keeping its git
+/// history and updating references.
+async fn rename_skill(
+    State(state): State<Arc<super::routes::AppState>>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+    Json(req): Json<RenameRequest>,
+) -> Result<Json<RenameResult>, (StatusCode, String)> {
+    apply_rename(&state, &headers, ItemType::Skill, &name, req).await
+}
+
+/// POST /api/library/commands/:name/rename - Rename a command, keeping its
+/// git history.
+async fn rename_command(
+    State(state): State<Arc<super::routes::AppState>>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+    Json(req): Json<RenameRequest>,
+) -> Result<Json<RenameResult>, (StatusCode, String)> {
+    apply_rename(&state, &headers, ItemType::Command, &name, req).await
+}
+
+/// Rename (or preview renaming) an item, then update the workspaces that
+/// refer to it.
+async fn apply_rename(
+    state: &super::routes::AppState,
+    headers: &HeaderMap,
+    item_type: ItemType,
+    name: &str,
+    req: RenameRequest,
+) -> Result<Json<RenameResult>, (StatusCode, String)> {
+    let library = ensure_library(state, headers).await?;
 
     // Perform rename (or dry run)
     let result = library
-        .rename_item(item_type, &name, &req.new_name, req.dry_run)
+        .rename_item(item_type, name, &req.new_name, req.dry_run)
         .await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
@@ -1778,13 +2632,13 @@ async fn rename_item(
         match item_type {
             ItemType::Skill => {
                 // Update workspace skill lists
-                update_workspace_skill_references(&state, &name, &req.new_name).await;
+                update_workspace_skill_references(state, name, &req.new_name).await;
                 // Sync skills to workspaces
-                sync_skill_to_workspaces(&state, library.as_ref(), &req.new_name).await;
+                sync_skill_to_workspaces(state, library.as_ref(), &req.new_name).await;
             }
             ItemType::WorkspaceTemplate => {
                 // Update workspace template references
-                update_workspace_template_references(&state, &name, &req.new_name).await;
+                update_workspace_template_references(state, name, &req.new_name).await;
             }
             _ => {}
         }
@@ -2477,6 +3331,28 @@ mod tests {
         buf
     }
 
+    #[test]
+    fn test_verify_library_webhook() {
+        use hmac::{Hmac, Mac};
+
+        let body = br#"{"ref":"refs/heads/main"}"#;
+        let mut mac = Hmac::<sha2::Sha256>::new_from_slice(b"s3cret").unwrap();
+        mac.update(body);
+        let signature = format!("sha256={}", hex::encode(mac.finalize().into_bytes()));
+
+        let mut headers = HeaderMap::new();
+        assert!(verify_library_webhook("s3cret", &headers, body).is_err());
+        headers.insert("x-hub-signature-256", signature.parse().unwrap());
+        assert!(verify_library_webhook("s3cret", &headers, body).is_ok());
+        assert!(verify_library_webhook("other", &headers, body).is_err());
+        assert!(verify_library_webhook("s3cret", &headers, b"{}").is_err());
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-gitlab-token", "s3cret".parse().unwrap());
+        assert!(verify_library_webhook("s3cret", &headers, body).is_ok());
+        assert!(verify_library_webhook("s3cret2", &headers, body).is_err());
+    }
+
     #[test]
     fn test_find_zip_prefix_single_root() {
         let zip_data = create_zip(&[
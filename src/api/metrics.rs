@@ -0,0 +1,438 @@
+//! Prometheus metrics for the mission subsystem.
+//!
+//! Counters and histograms are updated where things happen: turns, backend
+//! spawns, events going through the journal, stream connections. Gauges
+//! describing current state (running missions, queue lengths) are read from
+//! the control sessions when `/metrics` is scraped. Rendered in the
+//! Prometheus text format by hand; nothing here needs a metrics crate.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+
+use super::control::AgentEvent;
+use super::routes::AppState;
+
+const TURN_BUCKETS: &[f64] = &[
+    1.0, 5.0, 15.0, 30.0, 60.0, 120.0, 300.0, 600.0, 1200.0, 1800.0, 3600.0,
+];
+const SPAWN_BUCKETS: &[f64] = &[0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+
+/// A counter split by one label.
+struct CounterVec {
+    name: &'static str,
+    help: &'static str,
+    label: &'static str,
+    values: Mutex<BTreeMap<String, u64>>,
+}
+
+impl CounterVec {
+    fn new(name: &'static str, help: &'static str, label: &'static str) -> Self {
+        Self {
+            name,
+            help,
+            label,
+            values: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    fn inc_by(&self, label_value: &str, n: u64) {
+        let mut values = self.values.lock().unwrap_or_else(|e| e.into_inner());
+        match values.get_mut(label_value) {
+            Some(value) => *value += n,
+            None => {
+                values.insert(label_value.to_string(), n);
+            }
+        }
+    }
+
+    fn render(&self, out: &mut String) {
+        help_and_type(out, self.name, self.help, "counter");
+        let values = self.values.lock().unwrap_or_else(|e| e.into_inner());
+        for (label_value, value) in values.iter() {
+            let _ = writeln!(
+                out,
+                "{}{{{}=\"{}\"}} {}",
+                self.name,
+                self.label,
+                escape(label_value),
+                value
+            );
+        }
+    }
+}
+
+#[derive(Default)]
+struct HistogramData {
+    /// Observations per bucket (not cumulative); the last one is `+Inf`
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+/// A histogram split by one label.
+struct HistogramVec {
+    name: &'static str,
+    help: &'static str,
+    label: &'static str,
+    buckets: &'static [f64],
+    values: Mutex<BTreeMap<String, HistogramData>>,
+}
+
+impl HistogramVec {
+    fn new(
+        name: &'static str,
+        help: &'static str,
+        label: &'static str,
+        buckets: &'static [f64],
+    ) -> Self {
+        Self {
+            name,
+            help,
+            label,
+            buckets,
+            values: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    fn observe(&self, label_value: &str, duration: Duration) {
+        let secs = duration.as_secs_f64();
+        let bucket = self
+            .buckets
+            .iter()
+            .position(|&bound| secs <= bound)
+            .unwrap_or(self.buckets.len());
+        let mut values = self.values.lock().unwrap_or_else(|e| e.into_inner());
+        let data = values.entry(label_value.to_string()).or_default();
+        if data.counts.is_empty() {
+            data.counts = vec![0; self.buckets.len() + 1];
+        }
+        data.counts[bucket] += 1;
+        data.sum += secs;
+        data.count += 1;
+    }
+
+    fn render(&self, out: &mut String) {
+        help_and_type(out, self.name, self.help, "histogram");
+        let values = self.values.lock().unwrap_or_else(|e| e.into_inner());
+        for (label_value, data) in values.iter() {
+            let label_value = escape(label_value);
+            let mut cumulative = 0;
+            for (i, count) in data.counts.iter().enumerate() {
+                cumulative += count;
+                let bound = self
+                    .buckets
+                    .get(i)
+                    .map_or_else(|| "+Inf".to_string(), |b| b.to_string());
+                let _ = writeln!(
+                    out,
+                    "{}_bucket{{{}=\"{}\",le=\"{}\"}} {}",
+                    self.name, self.label, label_value, bound, cumulative
+                );
+            }
+            let _ = writeln!(
+                out,
+                "{}_sum{{{}=\"{}\"}} {}",
+                self.name, self.label, label_value, data.sum
+            );
+            let _ = writeln!(
+                out,
+                "{}_count{{{}=\"{}\"}} {}",
+                self.name, self.label, label_value, data.count
+            );
+        }
+    }
+}
+
+pub struct Metrics {
+    turn_duration: HistogramVec,
+    spawn_latency: HistogramVec,
+    events: CounterVec,
+    tool_calls: CounterVec,
+    lag_dropped: CounterVec,
+    sse_subscribers: AtomicI64,
+    ws_subscribers: AtomicI64,
+}
+
+pub fn global() -> &'static Metrics {
+    static METRICS: OnceLock<Metrics> = OnceLock::new();
+    METRICS.get_or_init(Metrics::new)
+}
+
+impl Metrics {
+    fn new() -> Self {
+        Self {
+            turn_duration: HistogramVec::new(
+                "sandboxed_mission_turn_duration_seconds",
+                "Duration of mission turns.",
+                "backend",
+                TURN_BUCKETS,
+            ),
+            spawn_latency: HistogramVec::new(
+                "sandboxed_backend_spawn_seconds",
+                "Time taken to start a backend CLI or session for a turn.",
+                "backend",
+                SPAWN_BUCKETS,
+            ),
+            events: CounterVec::new("sandboxed_events_total", "Control events emitted.", "event"),
+            tool_calls: CounterVec::new(
+                "sandboxed_tool_calls_total",
+                "Tool calls made by agents.",
+                "tool",
+            ),
+            lag_dropped: CounterVec::new(
+                "sandboxed_event_lag_dropped_total",
+                "Events a broadcast receiver missed because it fell behind.",
+                "consumer",
+            ),
+            sse_subscribers: AtomicI64::new(0),
+            ws_subscribers: AtomicI64::new(0),
+        }
+    }
+
+    /// Count an event emitted by a control session.
+    pub fn observe_event(&self, event: &AgentEvent) {
+        self.events.inc_by(event.event_name(), 1);
+        if let AgentEvent::ToolCall { name, .. } = event {
+            self.tool_calls.inc_by(name, 1);
+        }
+    }
+
+    /// A broadcast receiver lagged and lost `n` events.
+    pub fn lag_dropped(&self, consumer: &str, n: u64) {
+        self.lag_dropped.inc_by(consumer, n);
+    }
+
+    pub fn observe_spawn(&self, backend: &str, duration: Duration) {
+        self.spawn_latency.observe(backend, duration);
+    }
+
+    /// Time a turn until the returned guard is dropped.
+    pub fn turn_timer(&'static self, backend: &str) -> TurnTimer {
+        TurnTimer {
+            metrics: self,
+            backend: backend.to_string(),
+            started: Instant::now(),
+        }
+    }
+
+    /// Count an open SSE (`websocket` = false) or websocket event stream
+    /// until the returned guard is dropped.
+    pub fn subscriber(&'static self, websocket: bool) -> SubscriberGuard {
+        let gauge = if websocket {
+            &self.ws_subscribers
+        } else {
+            &self.sse_subscribers
+        };
+        gauge.fetch_add(1, Ordering::Relaxed);
+        SubscriberGuard { gauge }
+    }
+
+    fn render(&self, out: &mut String) {
+        self.turn_duration.render(out);
+        self.spawn_latency.render(out);
+        self.events.render(out);
+        self.tool_calls.render(out);
+        self.lag_dropped.render(out);
+        help_and_type(
+            out,
+            "sandboxed_event_stream_subscribers",
+            "Open control event streams.",
+            "gauge",
+        );
+        for (transport, gauge) in [
+            ("sse", &self.sse_subscribers),
+            ("websocket", &self.ws_subscribers),
+        ] {
+            let _ = writeln!(
+                out,
+                "sandboxed_event_stream_subscribers{{transport=\"{}\"}} {}",
+                transport,
+                gauge.load(Ordering::Relaxed)
+            );
+        }
+    }
+}
+
+/// Records a turn's duration when dropped.
+pub struct TurnTimer {
+    metrics: &'static Metrics,
+    backend: String,
+    started: Instant,
+}
+
+impl Drop for TurnTimer {
+    fn drop(&mut self) {
+        self.metrics
+            .turn_duration
+            .observe(&self.backend, self.started.elapsed());
+    }
+}
+
+/// Keeps an event stream counted while alive.
+pub struct SubscriberGuard {
+    gauge: &'static AtomicI64,
+}
+
+impl Drop for SubscriberGuard {
+    fn drop(&mut self) {
+        self.gauge.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+fn help_and_type(out: &mut String, name: &str, help: &str, kind: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn escape(label_value: &str) -> String {
+    label_value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Render the mission gauges from the running missions' states and queues.
+fn render_missions(out: &mut String, running: &[super::mission_runner::RunningMissionInfo]) {
+    let mut by_state: BTreeMap<String, u64> = BTreeMap::new();
+    for mission in running {
+        let state = if mission.paused {
+            "paused"
+        } else {
+            mission.state.as_str()
+        };
+        *by_state.entry(state.to_string()).or_default() += 1;
+    }
+    help_and_type(
+        out,
+        "sandboxed_missions_running",
+        "Missions held by a runner, by state.",
+        "gauge",
+    );
+    for (state, count) in by_state {
+        let _ = writeln!(
+            out,
+            "sandboxed_missions_running{{state=\"{}\"}} {}",
+            escape(&state),
+            count
+        );
+    }
+    help_and_type(
+        out,
+        "sandboxed_mission_queue_messages",
+        "Messages waiting in running missions' queues.",
+        "gauge",
+    );
+    let queued: usize = running.iter().map(|m| m.queue_len).sum();
+    let _ = writeln!(out, "sandboxed_mission_queue_messages {}", queued);
+    help_and_type(
+        out,
+        "sandboxed_mission_queue_max_messages",
+        "Longest queue among running missions.",
+        "gauge",
+    );
+    let longest = running.iter().map(|m| m.queue_len).max().unwrap_or(0);
+    let _ = writeln!(out, "sandboxed_mission_queue_max_messages {}", longest);
+}
+
+/// `GET /metrics` in the Prometheus text format. Requires
+/// `Authorization: Bearer <METRICS_TOKEN>` when `METRICS_TOKEN` is set.
+pub async fn metrics_handler(State(state): State<Arc<AppState>>, headers: HeaderMap) -> Response {
+    if let Some(expected) = state.config.metrics_token.as_deref() {
+        let token = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .unwrap_or("");
+        if token != expected {
+            return (StatusCode::UNAUTHORIZED, "Invalid metrics token").into_response();
+        }
+    }
+
+    let running = state.control.running_missions().await;
+    let mut out = String::new();
+    render_missions(&mut out, &running);
+    global().render(&mut out);
+    (
+        [(
+            header::CONTENT_TYPE,
+            "text/plain; version=0.0.4; charset=utf-8",
+        )],
+        out,
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::mission_runner::{MissionHealth, RunningMissionInfo};
+
+    #[test]
+    fn test_histogram_buckets_are_cumulative() {
+        let histogram = HistogramVec::new("turn_seconds", "Turns.", "backend", &[1.0, 10.0]);
+        histogram.observe("claudecode", Duration::from_millis(500));
+        histogram.observe("claudecode", Duration::from_secs(5));
+        histogram.observe("claudecode", Duration::from_secs(50));
+        let mut out = String::new();
+        histogram.render(&mut out);
+        assert!(out.contains("# TYPE turn_seconds histogram"));
+        assert!(out.contains("turn_seconds_bucket{backend=\"claudecode\",le=\"1\"} 1"));
+        assert!(out.contains("turn_seconds_bucket{backend=\"claudecode\",le=\"10\"} 2"));
+        assert!(out.contains("turn_seconds_bucket{backend=\"claudecode\",le=\"+Inf\"} 3"));
+        assert!(out.contains("turn_seconds_sum{backend=\"claudecode\"} 55.5"));
+        assert!(out.contains("turn_seconds_count{backend=\"claudecode\"} 3"));
+    }
+
+    #[test]
+    fn test_counters_and_escaping() {
+        let counter = CounterVec::new("calls_total", "Calls.", "tool");
+        counter.inc_by("bash", 1);
+        counter.inc_by("bash", 2);
+        counter.inc_by("say \"hi\"", 1);
+        let mut out = String::new();
+        counter.render(&mut out);
+        assert!(out.contains("calls_total{tool=\"bash\"} 3"));
+        assert!(out.contains("calls_total{tool=\"say \\\"hi\\\"\"} 1"));
+    }
+
+    #[test]
+    fn test_mission_gauges() {
+        let mission = |state: &str, queue_len, paused| RunningMissionInfo {
+            mission_id: uuid::Uuid::new_v4(),
+            state: state.to_string(),
+            queue_len,
+            history_len: 0,
+            seconds_since_activity: 0,
+            health: MissionHealth::Healthy,
+            expected_deliverables: 0,
+            current_activity: None,
+            subtask_total: 0,
+            subtask_completed: 0,
+            paused,
+            tags: Default::default(),
+        };
+        let mut out = String::new();
+        render_missions(
+            &mut out,
+            &[
+                mission("running", 2, false),
+                mission("running", 0, true),
+                mission("queued", 1, false),
+            ],
+        );
+        assert!(out.contains("sandboxed_missions_running{state=\"running\"} 1"));
+        assert!(out.contains("sandboxed_missions_running{state=\"paused\"} 1"));
+        assert!(out.contains("sandboxed_missions_running{state=\"queued\"} 1"));
+        assert!(out.contains("sandboxed_mission_queue_messages 3"));
+        assert!(out.contains("sandboxed_mission_queue_max_messages 2"));
+    }
+}
@@ -0,0 +1,252 @@
+//! Mission artifacts.
+//!
+//! Output files a mission produced, registered either by the agent through
+//! the `register_artifact` tool or automatically when a deliverable named in
+//! the mission's first message exists after a turn. Each artifact records the
+//! host path, size and SHA-256 of the file at registration time; the list is
+//! kept in `.sandboxed-sh/runtime/artifacts/<mission_id>.json` and the files
+//! are served by the mission artifact download endpoint.
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::workspace::{self, Workspace, WorkspaceType};
+
+/// How an artifact was registered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArtifactSource {
+    /// The agent called `register_artifact`
+    Tool,
+    /// A deliverable from the mission's first message was found on disk
+    Deliverable,
+}
+
+/// A file produced by a mission.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MissionArtifact {
+    pub id: Uuid,
+    pub mission_id: Uuid,
+    /// Path on the host
+    pub path: PathBuf,
+    /// File name, used for downloads
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub size: u64,
+    pub sha256: String,
+    pub source: ArtifactSource,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Map a path reported by the agent to the host. Relative paths are taken
+/// from the mission's workspace directory; absolute paths inside a container
+/// workspace are rooted at the container's directory.
+pub fn resolve_artifact_path(workspace: &Workspace, mission_id: Uuid, path: &str) -> PathBuf {
+    let input = Path::new(path);
+    if !input.is_absolute() {
+        return workspace::mission_workspace_dir_for_root(&workspace.path, mission_id).join(input);
+    }
+    if workspace.workspace_type == WorkspaceType::Container
+        && workspace::use_nspawn_for_workspace(workspace)
+        && !input.starts_with(&workspace.path)
+    {
+        return workspace
+            .path
+            .join(input.strip_prefix("/").unwrap_or(input));
+    }
+    input.to_path_buf()
+}
+
+/// Size and hex SHA-256 of a file.
+fn hash_file(path: &Path) -> Result<(u64, String), String> {
+    let mut file = std::fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 64 * 1024];
+    let mut size = 0u64;
+    loop {
+        let n = file.read(&mut buf).map_err(|e| e.to_string())?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        size += n as u64;
+    }
+    Ok((size, hex::encode(hasher.finalize())))
+}
+
+/// Artifact lists, one file per mission.
+#[derive(Debug)]
+pub struct MissionArtifactStore {
+    dir: PathBuf,
+    /// Serializes read-modify-write of the per-mission lists
+    write_lock: Mutex<()>,
+}
+
+impl MissionArtifactStore {
+    pub fn new(working_dir: &Path) -> Self {
+        Self {
+            dir: working_dir
+                .join(".sandboxed-sh")
+                .join("runtime")
+                .join("artifacts"),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    fn path(&self, mission_id: Uuid) -> PathBuf {
+        self.dir.join(format!("{}.json", mission_id))
+    }
+
+    /// Record a file as an artifact of a mission. Registering a path again
+    /// refreshes its size and hash (and description, when one is given).
+    pub fn register(
+        &self,
+        mission_id: Uuid,
+        path: &Path,
+        description: Option<String>,
+        source: ArtifactSource,
+    ) -> Result<MissionArtifact, String> {
+        let metadata = std::fs::metadata(path)
+            .map_err(|e| format!("Artifact {} not found: {}", path.display(), e))?;
+        if !metadata.is_file() {
+            return Err(format!("Artifact {} is not a file", path.display()));
+        }
+        let (size, sha256) = hash_file(path)?;
+        let now = Utc::now();
+
+        let _guard = self.write_lock.lock().unwrap();
+        let mut artifacts = self.list(mission_id);
+        let artifact = match artifacts.iter_mut().find(|a| a.path == path) {
+            Some(existing) => {
+                existing.size = size;
+                existing.sha256 = sha256;
+                existing.updated_at = now;
+                if description.is_some() {
+                    existing.description = description;
+                }
+                if source == ArtifactSource::Tool {
+                    existing.source = source;
+                }
+                existing.clone()
+            }
+            None => {
+                let artifact = MissionArtifact {
+                    id: Uuid::new_v4(),
+                    mission_id,
+                    path: path.to_path_buf(),
+                    name: path
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_else(|| "artifact".to_string()),
+                    description,
+                    size,
+                    sha256,
+                    source,
+                    created_at: now,
+                    updated_at: now,
+                };
+                artifacts.push(artifact.clone());
+                artifact
+            }
+        };
+
+        std::fs::create_dir_all(&self.dir).map_err(|e| e.to_string())?;
+        let file = self.path(mission_id);
+        let tmp = file.with_extension("json.tmp");
+        let contents = serde_json::to_string(&artifacts).map_err(|e| e.to_string())?;
+        std::fs::write(&tmp, contents).map_err(|e| e.to_string())?;
+        std::fs::rename(&tmp, &file).map_err(|e| e.to_string())?;
+        Ok(artifact)
+    }
+
+    /// A mission's artifacts, in registration order.
+    pub fn list(&self, mission_id: Uuid) -> Vec<MissionArtifact> {
+        std::fs::read_to_string(self.path(mission_id))
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn get(&self, mission_id: Uuid, artifact_id: Uuid) -> Option<MissionArtifact> {
+        self.list(mission_id)
+            .into_iter()
+            .find(|artifact| artifact.id == artifact_id)
+    }
+
+    /// Forget all artifacts of a mission. The files themselves are left alone.
+    pub fn remove_all(&self, mission_id: Uuid) {
+        let path = self.path(mission_id);
+        if let Err(e) = std::fs::remove_file(&path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                tracing::warn!("Failed to remove artifacts {}: {}", path.display(), e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_and_refresh_artifact() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = MissionArtifactStore::new(dir.path());
+        let mission_id = Uuid::new_v4();
+        let file = dir.path().join("report.md");
+        std::fs::write(&file, "hello").unwrap();
+
+        let first = store
+            .register(mission_id, &file, None, ArtifactSource::Deliverable)
+            .unwrap();
+        assert_eq!(first.size, 5);
+        assert_eq!(
+            first.sha256,
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+        assert_eq!(first.name, "report.md");
+
+        std::fs::write(&file, "hello world").unwrap();
+        let second = store
+            .register(
+                mission_id,
+                &file,
+                Some("Final report".to_string()),
+                ArtifactSource::Tool,
+            )
+            .unwrap();
+        assert_eq!(second.id, first.id);
+        assert_eq!(second.size, 11);
+        assert_eq!(second.source, ArtifactSource::Tool);
+
+        assert_eq!(store.list(mission_id).len(), 1);
+        assert!(store.get(mission_id, first.id).is_some());
+        assert!(store
+            .register(mission_id, dir.path(), None, ArtifactSource::Tool)
+            .is_err());
+
+        store.remove_all(mission_id);
+        assert!(store.list(mission_id).is_empty());
+    }
+
+    #[test]
+    fn test_resolve_artifact_path() {
+        let workspace = Workspace::default_host(PathBuf::from("/srv/work"));
+        let mission_id = Uuid::new_v4();
+        let relative = resolve_artifact_path(&workspace, mission_id, "out/report.md");
+        assert!(relative.starts_with("/srv/work/workspaces"));
+        assert!(relative.ends_with("out/report.md"));
+        assert_eq!(
+            resolve_artifact_path(&workspace, mission_id, "/tmp/report.md"),
+            PathBuf::from("/tmp/report.md")
+        );
+    }
+}
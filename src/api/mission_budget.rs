@@ -0,0 +1,161 @@
+//! Per-mission cost budgets.
+//!
+//! A mission created with `budget_cents` has the cost of every turn added to
+//! its spend. Crossing 50% and 80% of the budget emits a warning; reaching it
+//! stops the mission with `TerminalReason::BudgetExhausted` and drops its
+//! queued messages.
+//!
+//! Backends that stream token usage (Claude Code, Amp) report the running
+//! cost of a turn as it goes; the turn is cancelled as soon as it reaches
+//! what the mission may still spend, instead of running to the end. Other
+//! backends only report cost when a turn ends, so the turn that crosses the
+//! limit is the last one to run.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+/// Budget percentages that trigger a warning event.
+pub const WARNING_PERCENTS: [u8; 2] = [50, 80];
+
+/// Outcome of charging a turn's cost against a budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetCheck {
+    WithinBudget,
+    /// The spend crossed this warning threshold (the highest one crossed)
+    Warning(u8),
+    Exhausted,
+}
+
+/// Compare the spend before and after a turn with the budget.
+pub fn check_budget(budget_cents: u64, spent_before: u64, spent_after: u64) -> BudgetCheck {
+    if spent_after >= budget_cents {
+        return BudgetCheck::Exhausted;
+    }
+    let threshold = |percent: u8| budget_cents.saturating_mul(percent as u64) / 100;
+    WARNING_PERCENTS
+        .iter()
+        .rev()
+        .find(|percent| spent_before < threshold(**percent) && spent_after >= threshold(**percent))
+        .map_or(BudgetCheck::WithinBudget, |percent| {
+            BudgetCheck::Warning(*percent)
+        })
+}
+
+/// Whether a mission has no budget left to start another turn.
+pub fn is_exhausted(budget_cents: Option<u64>, spent_cents: u64) -> bool {
+    budget_cents.is_some_and(|budget| spent_cents >= budget)
+}
+
+/// What a mission's turns may still spend, and the streamed cost at which
+/// one was stopped.
+#[derive(Debug)]
+struct TurnLimit {
+    remaining_cents: u64,
+    stopped_at: Option<u64>,
+}
+
+fn limits() -> &'static Mutex<HashMap<Uuid, TurnLimit>> {
+    static LIMITS: OnceLock<Mutex<HashMap<Uuid, TurnLimit>>> = OnceLock::new();
+    LIMITS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Record a mission's budget and spend, so its running turns can be checked
+/// against what is left.
+pub fn track(mission_id: Uuid, budget_cents: Option<u64>, spent_cents: u64) {
+    let mut limits = limits().lock().unwrap_or_else(|e| e.into_inner());
+    match budget_cents {
+        Some(budget_cents) => {
+            let remaining_cents = budget_cents.saturating_sub(spent_cents);
+            limits
+                .entry(mission_id)
+                .and_modify(|limit| limit.remaining_cents = remaining_cents)
+                .or_insert(TurnLimit {
+                    remaining_cents,
+                    stopped_at: None,
+                });
+        }
+        None => {
+            limits.remove(&mission_id);
+        }
+    }
+}
+
+/// Report the cost a running turn has streamed so far. Cancels the turn once
+/// it reaches what the mission may still spend; returns whether it did.
+pub fn report_turn_cost(mission_id: Uuid, cost_cents: u64, cancel: &CancellationToken) -> bool {
+    let mut limits = limits().lock().unwrap_or_else(|e| e.into_inner());
+    let Some(limit) = limits.get_mut(&mission_id) else {
+        return false;
+    };
+    if cost_cents < limit.remaining_cents {
+        return false;
+    }
+    limit.stopped_at = Some(limit.stopped_at.unwrap_or(0).max(cost_cents));
+    cancel.cancel();
+    true
+}
+
+/// Take the streamed cost at which a turn of the mission was stopped for
+/// reaching its budget, if one was.
+pub fn take_stopped(mission_id: Uuid) -> Option<u64> {
+    limits()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get_mut(&mission_id)
+        .and_then(|limit| limit.stopped_at.take())
+}
+
+/// Format cents as dollars, e.g. `$1.05`.
+pub fn format_cents(cents: u64) -> String {
+    format!("${}.{:02}", cents / 100, cents % 100)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_budget() {
+        assert_eq!(check_budget(1000, 0, 100), BudgetCheck::WithinBudget);
+        assert_eq!(check_budget(1000, 100, 500), BudgetCheck::Warning(50));
+        assert_eq!(check_budget(1000, 500, 700), BudgetCheck::WithinBudget);
+        assert_eq!(check_budget(1000, 700, 850), BudgetCheck::Warning(80));
+        // A single expensive turn reports only the highest threshold.
+        assert_eq!(check_budget(1000, 0, 900), BudgetCheck::Warning(80));
+        assert_eq!(check_budget(1000, 900, 1000), BudgetCheck::Exhausted);
+        assert_eq!(check_budget(1000, 1200, 1200), BudgetCheck::Exhausted);
+    }
+
+    #[test]
+    fn test_is_exhausted() {
+        assert!(!is_exhausted(None, 10_000));
+        assert!(!is_exhausted(Some(500), 499));
+        assert!(is_exhausted(Some(500), 500));
+        assert_eq!(format_cents(105), "$1.05");
+    }
+
+    #[test]
+    fn test_turn_is_cancelled_once_it_reaches_the_budget() {
+        let mission_id = Uuid::new_v4();
+        let cancel = CancellationToken::new();
+        track(mission_id, Some(150), 50);
+
+        assert!(!report_turn_cost(mission_id, 60, &cancel));
+        assert!(!cancel.is_cancelled());
+        assert_eq!(take_stopped(mission_id), None);
+
+        assert!(report_turn_cost(mission_id, 120, &cancel));
+        assert!(cancel.is_cancelled());
+        assert_eq!(take_stopped(mission_id), Some(120));
+        assert_eq!(take_stopped(mission_id), None);
+
+        // Without a budget, turns are never stopped.
+        let cancel = CancellationToken::new();
+        track(mission_id, None, 0);
+        assert!(!report_turn_cost(mission_id, 10_000, &cancel));
+        assert!(!cancel.is_cancelled());
+    }
+}
@@ -0,0 +1,279 @@
+//! Mission checkpoints.
+//!
+//! After every finished turn the mission's history, deliverable state,
+//! backend session id and a reference to the workspace state are written to
+//! `.sandboxed-sh/runtime/checkpoints/<mission_id>/<checkpoint_id>.json`.
+//! Resuming from a checkpoint rolls the mission back to that point, so a
+//! mission killed mid-way continues from its last good turn instead of
+//! starting over. Only the newest [`MAX_CHECKPOINTS`] are kept per mission.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::mission_store::{Mission, MissionHistoryEntry};
+use crate::task::extract_deliverables;
+
+/// Checkpoints kept per mission; older ones are pruned.
+pub const MAX_CHECKPOINTS: usize = 20;
+
+/// State of a mission after one of its turns.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MissionCheckpoint {
+    pub id: Uuid,
+    pub mission_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub history: Vec<MissionHistoryEntry>,
+    /// Deliverables requested in the mission's first message (paths for
+    /// files and directories)
+    #[serde(default)]
+    pub deliverables: Vec<String>,
+    /// Deliverables not produced yet
+    #[serde(default)]
+    pub missing_deliverables: Vec<String>,
+    /// Backend session to continue from (Claude Code, Amp, Codex)
+    #[serde(default)]
+    pub session_id: Option<String>,
+    pub workspace_id: Uuid,
+    /// Git commit capturing the workspace (tracked files) at checkpoint time
+    #[serde(default)]
+    pub workspace_ref: Option<String>,
+}
+
+/// Checkpoint metadata, without the history.
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckpointSummary {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub history_len: usize,
+    pub missing_deliverables: Vec<String>,
+    pub session_id: Option<String>,
+    pub workspace_ref: Option<String>,
+}
+
+impl MissionCheckpoint {
+    /// Capture a mission as it is now. `workspace_root` is inspected for the
+    /// deliverables and, if it is a git checkout, the workspace reference.
+    pub async fn capture(mission: &Mission, workspace_root: &Path) -> Self {
+        let deliverable_set = mission
+            .history
+            .iter()
+            .find(|entry| entry.role == "user")
+            .map(|entry| extract_deliverables(&entry.content))
+            .unwrap_or_default();
+        let deliverables = deliverable_set
+            .deliverables
+            .iter()
+            .map(|d| d.describe())
+            .collect();
+        let output = mission
+            .history
+            .iter()
+            .filter(|entry| entry.role == "assistant")
+            .map(|entry| entry.content.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        Self {
+            id: Uuid::new_v4(),
+            mission_id: mission.id,
+            created_at: Utc::now(),
+            history: mission.history.clone(),
+            deliverables,
+            missing_deliverables: deliverable_set.missing_descriptions(&output).await,
+            session_id: mission.session_id.clone(),
+            workspace_id: mission.workspace_id,
+            workspace_ref: git_workspace_ref(workspace_root).await,
+        }
+    }
+
+    pub fn summary(&self) -> CheckpointSummary {
+        CheckpointSummary {
+            id: self.id,
+            created_at: self.created_at,
+            history_len: self.history.len(),
+            missing_deliverables: self.missing_deliverables.clone(),
+            session_id: self.session_id.clone(),
+            workspace_ref: self.workspace_ref.clone(),
+        }
+    }
+
+    /// Resume-prompt paragraph describing the checkpoint.
+    pub fn resume_note(&self) -> String {
+        let mut note = format!(
+            "Restored from checkpoint {} taken at {} ({} messages).",
+            self.id,
+            self.created_at.to_rfc3339(),
+            self.history.len()
+        );
+        if let Some(workspace_ref) = &self.workspace_ref {
+            note.push_str(&format!(
+                " The workspace was at git commit {} then; later changes may be partial.",
+                workspace_ref
+            ));
+        }
+        if !self.missing_deliverables.is_empty() {
+            note.push_str(&format!(
+                "\nDeliverables still missing at the checkpoint: {}",
+                self.missing_deliverables.join(", ")
+            ));
+        }
+        note
+    }
+}
+
+/// A commit for the workspace's current state: `git stash create` when there
+/// are uncommitted changes (it doesn't touch the working tree), HEAD otherwise.
+/// None outside a git checkout.
+async fn git_workspace_ref(dir: &Path) -> Option<String> {
+    async fn git(dir: &Path, args: &[&str]) -> Option<String> {
+        let output = tokio::process::Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(args)
+            .output()
+            .await
+            .ok()?;
+        let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        (output.status.success() && !stdout.is_empty()).then_some(stdout)
+    }
+
+    if !dir.join(".git").exists() {
+        return None;
+    }
+    match git(dir, &["stash", "create"]).await {
+        Some(stash) => Some(stash),
+        None => git(dir, &["rev-parse", "HEAD"]).await,
+    }
+}
+
+/// Directory of checkpoints, one subdirectory per mission.
+#[derive(Debug)]
+pub struct MissionCheckpointStore {
+    dir: PathBuf,
+}
+
+impl MissionCheckpointStore {
+    pub fn new(working_dir: &Path) -> Self {
+        Self {
+            dir: working_dir
+                .join(".sandboxed-sh")
+                .join("runtime")
+                .join("checkpoints"),
+        }
+    }
+
+    fn mission_dir(&self, mission_id: Uuid) -> PathBuf {
+        self.dir.join(mission_id.to_string())
+    }
+
+    /// Write a checkpoint and prune the mission's oldest ones.
+    pub fn save(&self, checkpoint: &MissionCheckpoint) -> Result<(), String> {
+        let dir = self.mission_dir(checkpoint.mission_id);
+        std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+        let path = dir.join(format!("{}.json", checkpoint.id));
+        let tmp = path.with_extension("json.tmp");
+        let contents = serde_json::to_string(checkpoint).map_err(|e| e.to_string())?;
+        std::fs::write(&tmp, contents).map_err(|e| e.to_string())?;
+        std::fs::rename(&tmp, &path).map_err(|e| e.to_string())?;
+
+        let checkpoints = self.list(checkpoint.mission_id);
+        for old in checkpoints.iter().skip(MAX_CHECKPOINTS) {
+            let _ = std::fs::remove_file(dir.join(format!("{}.json", old.id)));
+        }
+        Ok(())
+    }
+
+    /// A mission's checkpoints, newest first.
+    pub fn list(&self, mission_id: Uuid) -> Vec<MissionCheckpoint> {
+        let Ok(entries) = std::fs::read_dir(self.mission_dir(mission_id)) else {
+            return Vec::new();
+        };
+        let mut checkpoints: Vec<MissionCheckpoint> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| {
+                let contents = std::fs::read_to_string(&path).ok()?;
+                serde_json::from_str(&contents).ok()
+            })
+            .collect();
+        checkpoints.sort_by_key(|c| std::cmp::Reverse(c.created_at));
+        checkpoints
+    }
+
+    pub fn get(&self, mission_id: Uuid, checkpoint_id: Uuid) -> Option<MissionCheckpoint> {
+        let path = self
+            .mission_dir(mission_id)
+            .join(format!("{}.json", checkpoint_id));
+        let contents = std::fs::read_to_string(path).ok()?;
+        serde_json::from_str(&contents).ok()
+    }
+
+    pub fn latest(&self, mission_id: Uuid) -> Option<MissionCheckpoint> {
+        self.list(mission_id).into_iter().next()
+    }
+
+    /// Delete all checkpoints of a mission.
+    pub fn remove_all(&self, mission_id: Uuid) {
+        let dir = self.mission_dir(mission_id);
+        if let Err(e) = std::fs::remove_dir_all(&dir) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                tracing::warn!("Failed to remove checkpoints {}: {}", dir.display(), e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checkpoint(mission_id: Uuid, messages: usize, age_secs: i64) -> MissionCheckpoint {
+        MissionCheckpoint {
+            id: Uuid::new_v4(),
+            mission_id,
+            created_at: Utc::now() - chrono::Duration::seconds(age_secs),
+            history: (0..messages)
+                .map(|i| MissionHistoryEntry {
+                    role: if i % 2 == 0 { "user" } else { "assistant" }.to_string(),
+                    content: format!("message {}", i),
+                })
+                .collect(),
+            deliverables: Vec::new(),
+            missing_deliverables: vec!["/tmp/report.md".to_string()],
+            session_id: Some("session".to_string()),
+            workspace_id: Uuid::new_v4(),
+            workspace_ref: None,
+        }
+    }
+
+    #[test]
+    fn test_checkpoints_newest_first_and_pruned() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = MissionCheckpointStore::new(dir.path());
+        let mission_id = Uuid::new_v4();
+        assert!(store.latest(mission_id).is_none());
+
+        let oldest = checkpoint(mission_id, 2, 1000);
+        store.save(&oldest).unwrap();
+        for i in 0..MAX_CHECKPOINTS {
+            store
+                .save(&checkpoint(mission_id, 4, (MAX_CHECKPOINTS - i) as i64))
+                .unwrap();
+        }
+        let newest = checkpoint(mission_id, 6, 0);
+        store.save(&newest).unwrap();
+
+        let list = store.list(mission_id);
+        assert_eq!(list.len(), MAX_CHECKPOINTS);
+        assert_eq!(list[0].id, newest.id);
+        assert!(store.get(mission_id, oldest.id).is_none());
+        assert_eq!(store.latest(mission_id).unwrap().history.len(), 6);
+        assert!(newest.resume_note().contains("/tmp/report.md"));
+
+        store.remove_all(mission_id);
+        assert!(store.list(mission_id).is_empty());
+    }
+}
@@ -0,0 +1,125 @@
+//! Structured completion reports.
+//!
+//! When the agent calls `complete_mission` it can hand in a report along
+//! with the status: a summary, a manifest of the files it delivered and
+//! follow-up suggestions. The tool fills in each file's size and SHA-256, the
+//! report is stored on the mission (`completion_report`) and the turn is
+//! flagged as explicitly completed, so nothing has to be guessed from the
+//! agent's final answer.
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+/// A file the agent delivered.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeliverableEntry {
+    /// Path as given by the agent, relative to the mission's working directory
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub size_bytes: u64,
+    pub sha256: String,
+}
+
+/// What the agent reported when it called `complete_mission`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompletionReport {
+    /// `completed`, `failed`, `blocked` or `not_feasible`
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(default)]
+    pub deliverables: Vec<DeliverableEntry>,
+    /// What the agent suggests doing next
+    #[serde(default)]
+    pub follow_ups: Vec<String>,
+    pub reported_at: String,
+}
+
+impl CompletionReport {
+    /// Paths of the delivered files.
+    pub fn paths(&self) -> Vec<String> {
+        self.deliverables.iter().map(|d| d.path.clone()).collect()
+    }
+}
+
+/// Size and SHA-256 of a delivered file, resolved against `working_dir`.
+pub fn describe_file(
+    working_dir: &Path,
+    path: &str,
+    description: Option<String>,
+) -> Result<DeliverableEntry, String> {
+    let full = working_dir.join(path);
+    let mut file = std::fs::File::open(&full).map_err(|e| format!("{}: {}", path, e))?;
+    let metadata = file.metadata().map_err(|e| format!("{}: {}", path, e))?;
+    if !metadata.is_file() {
+        return Err(format!("{}: not a file", path));
+    }
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher).map_err(|e| format!("{}: {}", path, e))?;
+    Ok(DeliverableEntry {
+        path: path.to_string(),
+        description,
+        size_bytes: metadata.len(),
+        sha256: hex::encode(hasher.finalize()),
+    })
+}
+
+fn reported() -> &'static Mutex<HashSet<Uuid>> {
+    static REPORTED: OnceLock<Mutex<HashSet<Uuid>>> = OnceLock::new();
+    REPORTED.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+/// Note that the mission's agent called `complete_mission`.
+pub fn mark_reported(mission_id: Uuid) {
+    reported()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(mission_id);
+}
+
+/// Whether `complete_mission` was called since the last check.
+pub fn take_reported(mission_id: Uuid) -> bool {
+    reported()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&mission_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describes_delivered_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("output")).unwrap();
+        std::fs::write(dir.path().join("output/report.md"), "hello").unwrap();
+
+        let entry =
+            describe_file(dir.path(), "output/report.md", Some("Report".to_string())).unwrap();
+        assert_eq!(entry.size_bytes, 5);
+        assert_eq!(
+            entry.sha256,
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+        assert_eq!(entry.description.as_deref(), Some("Report"));
+
+        assert!(describe_file(dir.path(), "output/missing.md", None).is_err());
+        assert!(describe_file(dir.path(), "output", None).is_err());
+    }
+
+    #[test]
+    fn reported_flag_is_taken_once() {
+        let mission = Uuid::new_v4();
+        assert!(!take_reported(mission));
+        mark_reported(mission);
+        assert!(take_reported(mission));
+        assert!(!take_reported(mission));
+    }
+}
@@ -0,0 +1,263 @@
+//! Mission tags and filtered listing.
+//!
+//! Tags are free-form `key: value` labels set on a mission. A listing can
+//! be narrowed by tags (`tag=team:infra,urgent`, all must match; a bare key
+//! matches any value), by state (a mission status, or `running`/`queued` for
+//! missions currently held by a runner) and by free text found in the title
+//! or initial prompt.
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use super::mission_runner::RunningMissionInfo;
+use super::mission_store::Mission;
+
+/// Most tags a mission can carry.
+pub const MAX_TAGS: usize = 32;
+const MAX_KEY_LEN: usize = 64;
+const MAX_VALUE_LEN: usize = 256;
+
+const STATES: &[&str] = &[
+    "pending",
+    "active",
+    "completed",
+    "failed",
+    "interrupted",
+    "blocked",
+    "not_feasible",
+    "running",
+    "queued",
+];
+
+/// Check tags before storing them.
+pub fn validate_tags(tags: &BTreeMap<String, String>) -> Result<(), String> {
+    if tags.len() > MAX_TAGS {
+        return Err(format!("A mission can have at most {} tags", MAX_TAGS));
+    }
+    for (key, value) in tags {
+        if key.trim().is_empty() || key.len() > MAX_KEY_LEN {
+            return Err(format!(
+                "Tag keys must be 1 to {} characters long",
+                MAX_KEY_LEN
+            ));
+        }
+        if key.contains(',') || key.contains(':') {
+            return Err(format!("Tag key '{}' cannot contain ',' or ':'", key));
+        }
+        if value.len() > MAX_VALUE_LEN {
+            return Err(format!(
+                "Value of tag '{}' is longer than {} characters",
+                key, MAX_VALUE_LEN
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Query parameters of a mission listing.
+#[derive(Debug, Default, Deserialize)]
+pub struct ListMissionsQuery {
+    /// Comma-separated `key:value` or `key` tags
+    pub tag: Option<String>,
+    pub state: Option<String>,
+    /// Free-text search over the title and initial prompt
+    pub q: Option<String>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Default)]
+pub struct MissionFilter {
+    tags: Vec<(String, Option<String>)>,
+    state: Option<String>,
+    text: Option<String>,
+}
+
+impl MissionFilter {
+    pub fn parse(query: &ListMissionsQuery) -> Result<Self, String> {
+        let tags = query
+            .tag
+            .as_deref()
+            .unwrap_or("")
+            .split(',')
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(|t| match t.split_once(':') {
+                Some((key, value)) => (key.trim().to_string(), Some(value.trim().to_string())),
+                None => (t.to_string(), None),
+            })
+            .collect();
+        let state = query
+            .state
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_lowercase);
+        if let Some(state) = &state {
+            if !STATES.contains(&state.as_str()) {
+                return Err(format!(
+                    "Unknown state '{}' (expected one of: {})",
+                    state,
+                    STATES.join(", ")
+                ));
+            }
+        }
+        let text = query
+            .q
+            .as_deref()
+            .map(str::trim)
+            .filter(|q| !q.is_empty())
+            .map(str::to_lowercase);
+        Ok(Self { tags, state, text })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.state.is_none() && self.text.is_none()
+    }
+
+    /// Whether the filter needs the list of running missions.
+    pub fn needs_running(&self) -> bool {
+        matches!(self.state.as_deref(), Some("running" | "queued"))
+    }
+
+    /// `running` is the mission's entry in the running list, if any.
+    pub fn matches(&self, mission: &Mission, running: Option<&RunningMissionInfo>) -> bool {
+        let tags_match =
+            self.tags
+                .iter()
+                .all(|(key, value)| match (mission.tags.get(key), value) {
+                    (Some(actual), Some(expected)) => actual == expected,
+                    (Some(_), None) => true,
+                    (None, _) => false,
+                });
+        if !tags_match {
+            return false;
+        }
+
+        let state_match = match self.state.as_deref() {
+            None => true,
+            Some("running") => {
+                running.is_some_and(|r| matches!(r.state.as_str(), "running" | "waiting_for_tool"))
+            }
+            Some("queued") => running.is_some_and(|r| r.state == "queued"),
+            Some(status) => mission.status.to_string() == status,
+        };
+        if !state_match {
+            return false;
+        }
+
+        match &self.text {
+            None => true,
+            Some(text) => {
+                let initial_prompt = mission
+                    .history
+                    .iter()
+                    .find(|entry| entry.role == "user")
+                    .map(|entry| entry.content.as_str());
+                [mission.title.as_deref(), initial_prompt]
+                    .into_iter()
+                    .flatten()
+                    .any(|s| s.to_lowercase().contains(text))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::mission_runner::MissionHealth;
+    use crate::api::mission_store::MissionHistoryEntry;
+
+    fn mission(tags: &[(&str, &str)], prompt: &str) -> Mission {
+        let mut mission: Mission = serde_json::from_value(serde_json::json!({
+            "id": uuid::Uuid::new_v4(),
+            "status": "active",
+            "backend": "claudecode",
+            "history": [],
+            "created_at": "2026-01-01T00:00:00Z",
+            "updated_at": "2026-01-01T00:00:00Z",
+        }))
+        .unwrap();
+        mission.tags = tags
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        mission.history.push(MissionHistoryEntry {
+            role: "user".to_string(),
+            content: prompt.to_string(),
+        });
+        mission
+    }
+
+    fn filter(tag: Option<&str>, state: Option<&str>, q: Option<&str>) -> MissionFilter {
+        MissionFilter::parse(&ListMissionsQuery {
+            tag: tag.map(String::from),
+            state: state.map(String::from),
+            q: q.map(String::from),
+            limit: None,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_tag_and_text_filters() {
+        let infra = mission(&[("team", "infra"), ("urgent", "")], "Fix the DNS outage");
+        let web = mission(&[("team", "web")], "Redesign the landing page");
+
+        let by_team = filter(Some("team:infra"), None, None);
+        assert!(by_team.matches(&infra, None));
+        assert!(!by_team.matches(&web, None));
+        assert!(filter(Some("team"), None, None).matches(&web, None));
+        assert!(!filter(Some("team:infra, urgent"), None, None).matches(&web, None));
+
+        assert!(filter(None, None, Some("dns")).matches(&infra, None));
+        assert!(!filter(None, None, Some("dns")).matches(&web, None));
+        assert!(filter(None, None, None).is_empty());
+    }
+
+    #[test]
+    fn test_state_filter() {
+        let m = mission(&[], "task");
+        assert!(filter(None, Some("active"), None).matches(&m, None));
+        assert!(!filter(None, Some("completed"), None).matches(&m, None));
+
+        let running = filter(None, Some("running"), None);
+        assert!(running.needs_running());
+        assert!(!running.matches(&m, None));
+        let info = RunningMissionInfo {
+            mission_id: m.id,
+            state: "waiting_for_tool".to_string(),
+            queue_len: 0,
+            history_len: 1,
+            seconds_since_activity: 0,
+            health: MissionHealth::Healthy,
+            expected_deliverables: 0,
+            current_activity: None,
+            subtask_total: 0,
+            subtask_completed: 0,
+            paused: false,
+            tags: BTreeMap::new(),
+        };
+        assert!(running.matches(&m, Some(&info)));
+
+        assert!(MissionFilter::parse(&ListMissionsQuery {
+            state: Some("sleeping".to_string()),
+            ..Default::default()
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_validate_tags() {
+        let tags = |pairs: &[(&str, &str)]| -> BTreeMap<String, String> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+        assert!(validate_tags(&tags(&[("team", "infra")])).is_ok());
+        assert!(validate_tags(&tags(&[("", "x")])).is_err());
+        assert!(validate_tags(&tags(&[("a:b", "x")])).is_err());
+    }
+}
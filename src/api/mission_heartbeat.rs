@@ -0,0 +1,131 @@
+//! Backend activity heartbeats.
+//!
+//! An agent can be silent for minutes while its backend is busy, e.g. running
+//! a long build through a tool. Every `MISSION_HEARTBEAT_SECS` the server
+//! samples the process tree of each mission with a turn in flight and, when
+//! it used CPU time or read data since the last sample, emits a `heartbeat`
+//! event. Heartbeats count as activity, so such missions are not reported as
+//! stalled or nudged.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use uuid::Uuid;
+
+use super::control::{running_missions_of, AgentEvent, ControlHub};
+use crate::process_registry::{self, ProcessActivity};
+
+/// Missions whose processes did work since the previous sample, among those
+/// in `busy`. `previous` is replaced by the current samples of busy missions.
+fn advanced(
+    previous: &mut HashMap<Uuid, ProcessActivity>,
+    current: &HashMap<Uuid, ProcessActivity>,
+    busy: &[Uuid],
+) -> Vec<(Uuid, ProcessActivity)> {
+    let mut advanced = Vec::new();
+    let mut next = HashMap::new();
+    for mission_id in busy {
+        let Some(sample) = current.get(mission_id) else {
+            continue;
+        };
+        if previous
+            .get(mission_id)
+            .is_some_and(|earlier| sample.advanced_since(earlier))
+        {
+            advanced.push((*mission_id, *sample));
+        }
+        next.insert(*mission_id, *sample);
+    }
+    *previous = next;
+    advanced
+}
+
+/// Emit heartbeats for busy missions every `interval`.
+pub async fn heartbeat_loop(hub: ControlHub, interval: Duration) {
+    let mut previous: HashMap<Uuid, ProcessActivity> = HashMap::new();
+
+    loop {
+        tokio::time::sleep(interval).await;
+
+        let Some(registry) = process_registry::global() else {
+            continue;
+        };
+        let sessions = hub.all_sessions().await;
+        let mut busy = Vec::new();
+        for session in &sessions {
+            let Ok(running) = running_missions_of(session).await else {
+                continue;
+            };
+            busy.extend(
+                running
+                    .into_iter()
+                    .filter(|m| matches!(m.state.as_str(), "running" | "waiting_for_tool"))
+                    .map(|m| (m.mission_id, session.events_tx.clone())),
+            );
+        }
+        if busy.is_empty() {
+            previous.clear();
+            continue;
+        }
+
+        let current = tokio::task::spawn_blocking(move || registry.activity())
+            .await
+            .unwrap_or_default();
+        let ids: Vec<Uuid> = busy.iter().map(|(id, _)| *id).collect();
+        for (mission_id, activity) in advanced(&mut previous, &current, &ids) {
+            let Some((_, events_tx)) = busy.iter().find(|(id, _)| *id == mission_id) else {
+                continue;
+            };
+            let _ = events_tx.send(AgentEvent::Heartbeat {
+                mission_id,
+                processes: activity.processes,
+                cpu_ms: activity.cpu_ms,
+                bytes_read: activity.bytes_read,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(cpu_ms: u64, bytes_read: u64) -> ProcessActivity {
+        ProcessActivity {
+            processes: 1,
+            cpu_ms,
+            bytes_read,
+        }
+    }
+
+    #[test]
+    fn reports_missions_that_did_work() {
+        let (a, b, idle) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let mut previous = HashMap::new();
+
+        // The first sample only sets the baseline
+        let current = HashMap::from([(a, sample(100, 0)), (b, sample(100, 0))]);
+        assert!(advanced(&mut previous, &current, &[a, b]).is_empty());
+
+        let current = HashMap::from([
+            (a, sample(250, 0)),
+            (b, sample(100, 4096)),
+            (idle, sample(500, 0)),
+        ]);
+        let mut beats: Vec<Uuid> = advanced(&mut previous, &current, &[a, b, idle])
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        beats.sort();
+        let mut expected = vec![a, b];
+        expected.sort();
+        assert_eq!(beats, expected);
+
+        // No change since the last sample: no heartbeat
+        assert!(advanced(&mut previous, &current, &[a, b, idle]).is_empty());
+
+        // Missions that stop being busy are forgotten
+        advanced(&mut previous, &current, &[a]);
+        assert_eq!(previous.keys().collect::<Vec<_>>(), vec![&a]);
+    }
+}
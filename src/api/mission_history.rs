@@ -0,0 +1,611 @@
+//! Conversation history and context for mission turns.
+//!
+//! A runner's history is a list of typed entries: user and assistant
+//! messages (with cost, model and thinking) and the tool calls and results in
+//! between, recorded from the mission's event stream while a turn runs. Each
+//! turn gets the mission's recent messages, up to
+//! `CONTEXT_MAX_HISTORY_CHARS`. Instead of dropping the older messages once
+//! they no longer fit, they are folded into a rolling summary written by an
+//! LLM (the same providers as the vision tool), which keeps decisions, file
+//! paths and open work. The summary covers a prefix of the history and is
+//! extended as the mission grows; it is kept by the runner and sent ahead of
+//! the messages it doesn't cover. Without a usable provider the history is
+//! truncated as before.
+
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+use super::control::AgentEvent;
+use super::mission_store::now_string;
+use crate::config::ContextConfig;
+use crate::tools::result_limits::truncate_head_tail;
+
+/// Bytes of a single message shown to the summarizer.
+const MAX_MESSAGE_BYTES: usize = 4_000;
+/// Bytes of tool arguments and results shown to the summarizer.
+const MAX_TOOL_BYTES: usize = 1_000;
+/// Bytes of messages summarized at once.
+const MAX_INPUT_BYTES: usize = 100_000;
+
+/// One step of a mission's conversation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ConversationEntry {
+    User {
+        content: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timestamp: Option<String>,
+    },
+    Assistant {
+        content: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timestamp: Option<String>,
+        /// Cost of the turn this answer ends
+        #[serde(default)]
+        cost_cents: u64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        model: Option<String>,
+        /// Reasoning streamed during the turn
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        thinking: Option<String>,
+    },
+    ToolCall {
+        tool_call_id: String,
+        name: String,
+        args: Value,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timestamp: Option<String>,
+    },
+    ToolResult {
+        tool_call_id: String,
+        name: String,
+        result: Value,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timestamp: Option<String>,
+    },
+}
+
+impl ConversationEntry {
+    /// A user message sent now.
+    pub fn user(content: impl Into<String>) -> Self {
+        Self::User {
+            content: content.into(),
+            timestamp: Some(now_string()),
+        }
+    }
+
+    /// A message from a plain `(role, content)` history, e.g. the mission
+    /// store's. Anything but `user` is an assistant message.
+    pub fn message(role: &str, content: &str) -> Self {
+        let content = content.to_string();
+        if role == "user" {
+            Self::User {
+                content,
+                timestamp: None,
+            }
+        } else {
+            Self::Assistant {
+                content,
+                timestamp: None,
+                cost_cents: 0,
+                model: None,
+                thinking: None,
+            }
+        }
+    }
+
+    pub fn role(&self) -> &'static str {
+        match self {
+            Self::User { .. } => "user",
+            Self::Assistant { .. } => "assistant",
+            Self::ToolCall { .. } => "tool_call",
+            Self::ToolResult { .. } => "tool_result",
+        }
+    }
+
+    /// Text of a user or assistant message.
+    pub fn message_text(&self) -> Option<&str> {
+        match self {
+            Self::User { content, .. } | Self::Assistant { content, .. } => Some(content),
+            Self::ToolCall { .. } | Self::ToolResult { .. } => None,
+        }
+    }
+
+    pub fn is_assistant(&self) -> bool {
+        matches!(self, Self::Assistant { .. })
+    }
+
+    /// How the entry reads in a turn's context. Tool activity is left out:
+    /// the agent sees its own results in its session.
+    fn context_text(&self) -> Option<String> {
+        self.message_text()
+            .map(|content| format!("{}: {}\n\n", self.role().to_uppercase(), content))
+    }
+
+    /// How the entry reads for the summarizer.
+    fn summary_text(&self) -> String {
+        match self {
+            Self::User { content, .. } | Self::Assistant { content, .. } => format!(
+                "{}: {}",
+                self.role().to_uppercase(),
+                truncate_head_tail(content, MAX_MESSAGE_BYTES)
+            ),
+            Self::ToolCall { name, args, .. } => format!(
+                "TOOL CALL {}: {}",
+                name,
+                truncate_head_tail(&args.to_string(), MAX_TOOL_BYTES)
+            ),
+            Self::ToolResult { name, result, .. } => {
+                let result = match result {
+                    Value::String(text) => text.clone(),
+                    other => other.to_string(),
+                };
+                format!(
+                    "TOOL RESULT {}: {}",
+                    name,
+                    truncate_head_tail(&result, MAX_TOOL_BYTES)
+                )
+            }
+        }
+    }
+}
+
+/// Read a stored history, including the `(role, content)` pairs written
+/// before entries were typed.
+pub fn deserialize_history<'de, D>(deserializer: D) -> Result<Vec<ConversationEntry>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Stored {
+        Entry(ConversationEntry),
+        Pair(String, String),
+    }
+    Ok(Vec::<Stored>::deserialize(deserializer)?
+        .into_iter()
+        .map(|stored| match stored {
+            Stored::Entry(entry) => entry,
+            Stored::Pair(role, content) => ConversationEntry::message(&role, &content),
+        })
+        .collect())
+}
+
+/// What a turn did besides its final answer.
+#[derive(Debug, Clone, Default)]
+pub struct TurnTrace {
+    pub started_at: String,
+    /// Tool calls and results, in order
+    pub tools: Vec<ConversationEntry>,
+    /// Reasoning blocks, joined
+    pub thinking: Option<String>,
+    /// Block being streamed
+    current_thinking: String,
+}
+
+impl TurnTrace {
+    fn new() -> Self {
+        Self {
+            started_at: now_string(),
+            ..Default::default()
+        }
+    }
+
+    fn observe(&mut self, event: &AgentEvent, mission_id: Uuid) {
+        if event.mission_id() != Some(mission_id) {
+            return;
+        }
+        match event {
+            AgentEvent::ToolCall {
+                tool_call_id,
+                name,
+                args,
+                ..
+            } => self.tools.push(ConversationEntry::ToolCall {
+                tool_call_id: tool_call_id.clone(),
+                name: name.clone(),
+                args: args.clone(),
+                timestamp: Some(now_string()),
+            }),
+            AgentEvent::ToolResult {
+                tool_call_id,
+                name,
+                result,
+                ..
+            } => self.tools.push(ConversationEntry::ToolResult {
+                tool_call_id: tool_call_id.clone(),
+                name: name.clone(),
+                result: result.clone(),
+                timestamp: Some(now_string()),
+            }),
+            AgentEvent::Thinking { content, done, .. } => {
+                // Backends send either the growing block or increments
+                if !content.is_empty() {
+                    if content.starts_with(self.current_thinking.as_str()) {
+                        self.current_thinking = content.clone();
+                    } else {
+                        self.current_thinking.push_str(content);
+                    }
+                }
+                if *done {
+                    self.end_thinking_block();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn end_thinking_block(&mut self) {
+        let block = std::mem::take(&mut self.current_thinking);
+        if block.trim().is_empty() {
+            return;
+        }
+        self.thinking = Some(match self.thinking.take() {
+            Some(thinking) => format!("{}\n\n{}", thinking, block.trim()),
+            None => block.trim().to_string(),
+        });
+    }
+}
+
+/// Record a mission's tool calls, results and thinking from the event
+/// stream until `done` is cancelled. Turns running at once for the same
+/// mission can't be told apart and record each other's activity.
+pub async fn trace_turn(
+    mut events: broadcast::Receiver<AgentEvent>,
+    mission_id: Uuid,
+    done: CancellationToken,
+) -> TurnTrace {
+    let mut trace = TurnTrace::new();
+    loop {
+        tokio::select! {
+            biased;
+            event = events.recv() => match event {
+                Ok(event) => trace.observe(&event, mission_id),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!("Mission {} history missed {} events", mission_id, skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            _ = done.cancelled() => {
+                while let Ok(event) = events.try_recv() {
+                    trace.observe(&event, mission_id);
+                }
+                break;
+            }
+        }
+    }
+    trace.end_thinking_block();
+    trace
+}
+
+/// Summary of the first `covered` entries of a mission's history.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistorySummary {
+    pub text: String,
+    pub covered: usize,
+    /// Model that wrote the summary
+    pub model: String,
+    pub updated_at: String,
+}
+
+/// Summary shared between a runner and its turns.
+pub type SharedHistorySummary = Arc<Mutex<Option<HistorySummary>>>;
+
+/// Build a history context string from conversation history: the newest
+/// messages that fit in `max_chars` (always at least one).
+pub fn build_history_context(history: &[ConversationEntry], max_chars: usize) -> String {
+    let mut result = String::new();
+    let mut total_chars = 0;
+    for entry in history.iter().rev() {
+        let Some(entry) = entry.context_text() else {
+            continue;
+        };
+        if total_chars + entry.len() > max_chars && !result.is_empty() {
+            break;
+        }
+        result = format!("{}{}", entry, result);
+        total_chars += entry.len();
+    }
+    result
+}
+
+/// Context for the next turn. When the messages the summary doesn't cover
+/// yet exceed the budget, the older half is folded into the summary first.
+pub async fn history_context(
+    history: &[ConversationEntry],
+    summary: Option<&SharedHistorySummary>,
+    context: &ContextConfig,
+) -> String {
+    let max_chars = context.max_history_total_chars;
+    let Some(summary) = summary.filter(|_| context.summarize_history) else {
+        return build_history_context(history, max_chars);
+    };
+    // A history rolled back past the summary makes it stale
+    let mut current = lock(summary).clone().filter(|s| s.covered <= history.len());
+    let covered = current.as_ref().map_or(0, |s| s.covered);
+
+    if history_len(&history[covered..]) > max_chars {
+        let keep_from = keep_from(history, covered, max_chars / 2);
+        if keep_from > covered {
+            match summarize(
+                current.as_ref(),
+                &history[covered..keep_from],
+                keep_from,
+                max_chars / 4,
+                context.history_summary_model.as_deref(),
+            )
+            .await
+            {
+                Ok(updated) => {
+                    tracing::info!(
+                        "Summarized {} history entries with {}",
+                        updated.covered,
+                        updated.model
+                    );
+                    *lock(summary) = Some(updated.clone());
+                    current = Some(updated);
+                }
+                Err(e) => tracing::warn!("Failed to summarize mission history: {}", e),
+            }
+        }
+    }
+    render(history, current.as_ref(), max_chars)
+}
+
+fn lock(summary: &SharedHistorySummary) -> std::sync::MutexGuard<'_, Option<HistorySummary>> {
+    summary.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn entry_len(entry: &ConversationEntry) -> usize {
+    entry.context_text().map_or(0, |text| text.len())
+}
+
+fn history_len(history: &[ConversationEntry]) -> usize {
+    history.iter().map(entry_len).sum()
+}
+
+/// Index of the oldest entry kept verbatim: the newest entries after
+/// `covered` that fit in `budget`, and at least the last one.
+fn keep_from(history: &[ConversationEntry], covered: usize, budget: usize) -> usize {
+    let mut total = 0;
+    let mut index = history.len();
+    while index > covered {
+        total += entry_len(&history[index - 1]);
+        if total > budget && index < history.len() {
+            break;
+        }
+        index -= 1;
+    }
+    index
+}
+
+fn render(
+    history: &[ConversationEntry],
+    summary: Option<&HistorySummary>,
+    max_chars: usize,
+) -> String {
+    match summary {
+        Some(summary) => {
+            let block = format!(
+                "SUMMARY OF EARLIER CONVERSATION:\n{}\n\n",
+                summary.text.trim()
+            );
+            let budget = max_chars.saturating_sub(block.len());
+            format!(
+                "{}{}",
+                block,
+                build_history_context(&history[summary.covered..], budget)
+            )
+        }
+        None => build_history_context(history, max_chars),
+    }
+}
+
+/// Fold `entries` (the history up to `covered`) into the previous summary.
+async fn summarize(
+    previous: Option<&HistorySummary>,
+    entries: &[ConversationEntry],
+    covered: usize,
+    max_chars: usize,
+    model: Option<&str>,
+) -> anyhow::Result<HistorySummary> {
+    let messages = entries
+        .iter()
+        .map(ConversationEntry::summary_text)
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let previous = previous.map_or("(none yet)", |s| s.text.as_str());
+    let prompt = format!(
+        "You keep the running summary of a long conversation between a user and an AI agent \
+         working on a task. Update the summary below with the new messages, so the agent can \
+         carry on without them. Keep the user's goals and requirements, decisions and their \
+         reasons, files and paths created or changed, commands, URLs and identifiers that \
+         matter, what is done and what is still open. Drop pleasantries and details that were \
+         superseded. Answer with the updated summary only, in at most {} characters.\n\n\
+         <summary>\n{}\n</summary>\n\n\
+         <new_messages>\n{}\n</new_messages>",
+        max_chars,
+        previous,
+        truncate_head_tail(&messages, MAX_INPUT_BYTES)
+    );
+    let max_tokens = (max_chars as u64 / 3).clamp(512, 4096);
+    let (text, model) = crate::tools::complete_text(&prompt, None, model, max_tokens).await?;
+    if text.trim().is_empty() {
+        anyhow::bail!("{} returned an empty summary", model);
+    }
+    Ok(HistorySummary {
+        text: truncate_head_tail(text.trim(), max_chars * 2),
+        covered,
+        model,
+        updated_at: now_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(role: &str, len: usize) -> ConversationEntry {
+        ConversationEntry::message(role, &"x".repeat(len))
+    }
+
+    fn tool_call(name: &str) -> ConversationEntry {
+        ConversationEntry::ToolCall {
+            tool_call_id: "call_1".to_string(),
+            name: name.to_string(),
+            args: serde_json::json!({"path": "README.md"}),
+            timestamp: None,
+        }
+    }
+
+    #[test]
+    fn keeps_newest_entries_within_budget() {
+        let history = vec![
+            entry("user", 92),
+            entry("assistant", 87),
+            entry("user", 92),
+            tool_call("read_file"),
+            entry("assistant", 87),
+        ];
+        // Each message takes 100 chars, tool calls none
+        assert_eq!(keep_from(&history, 0, 250), 2);
+        assert_eq!(keep_from(&history, 4, 250), 4);
+        // The last entry is kept even when it doesn't fit
+        assert_eq!(keep_from(&history, 0, 10), 4);
+    }
+
+    #[test]
+    fn renders_summary_before_uncovered_entries() {
+        let history = vec![
+            ConversationEntry::message("user", "Build the site"),
+            ConversationEntry::message("assistant", "Created site/index.html"),
+            ConversationEntry::message("user", "Now deploy it"),
+            tool_call("deploy"),
+        ];
+        let summary = HistorySummary {
+            text: "User asked for a site; site/index.html exists.".to_string(),
+            covered: 2,
+            model: "test".to_string(),
+            updated_at: String::new(),
+        };
+        assert_eq!(
+            render(&history, Some(&summary), 1000),
+            "SUMMARY OF EARLIER CONVERSATION:\nUser asked for a site; site/index.html exists.\n\nUSER: Now deploy it\n\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn short_history_is_not_summarized() {
+        let history = vec![
+            ConversationEntry::message("user", "Hi"),
+            ConversationEntry::message("assistant", "Hello"),
+        ];
+        let summary: SharedHistorySummary = Arc::default();
+        let context = ContextConfig::default();
+        assert_eq!(
+            history_context(&history, Some(&summary), &context).await,
+            "USER: Hi\n\nASSISTANT: Hello\n\n"
+        );
+        assert!(summary.lock().unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn stale_summary_is_ignored() {
+        let history = vec![ConversationEntry::message("user", "Hi")];
+        let summary: SharedHistorySummary = Arc::new(Mutex::new(Some(HistorySummary {
+            text: "Old".to_string(),
+            covered: 5,
+            model: "test".to_string(),
+            updated_at: String::new(),
+        })));
+        let context = ContextConfig::default();
+        assert_eq!(
+            history_context(&history, Some(&summary), &context).await,
+            "USER: Hi\n\n"
+        );
+    }
+
+    #[test]
+    fn reads_legacy_and_typed_history() {
+        #[derive(Deserialize)]
+        struct Stored {
+            #[serde(deserialize_with = "deserialize_history")]
+            history: Vec<ConversationEntry>,
+        }
+        let stored: Stored = serde_json::from_str(
+            r#"{"history": [
+                ["user", "Hi"],
+                {"type": "tool_call", "tool_call_id": "call_1", "name": "read_file", "args": {"path": "README.md"}},
+                {"type": "assistant", "content": "Hello", "cost_cents": 3}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            stored.history,
+            vec![
+                ConversationEntry::message("user", "Hi"),
+                tool_call("read_file"),
+                ConversationEntry::Assistant {
+                    content: "Hello".to_string(),
+                    timestamp: None,
+                    cost_cents: 3,
+                    model: None,
+                    thinking: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn trace_keeps_mission_tools_and_thinking() {
+        let mission_id = Uuid::new_v4();
+        let mut trace = TurnTrace::new();
+        let events = [
+            AgentEvent::Thinking {
+                content: "Let me".to_string(),
+                done: false,
+                mission_id: Some(mission_id),
+            },
+            AgentEvent::Thinking {
+                content: "Let me read it".to_string(),
+                done: true,
+                mission_id: Some(mission_id),
+            },
+            AgentEvent::ToolCall {
+                tool_call_id: "call_1".to_string(),
+                name: "read_file".to_string(),
+                args: serde_json::json!({"path": "README.md"}),
+                mission_id: Some(mission_id),
+            },
+            AgentEvent::ToolCall {
+                tool_call_id: "call_2".to_string(),
+                name: "bash".to_string(),
+                args: Value::Null,
+                mission_id: Some(Uuid::new_v4()),
+            },
+            AgentEvent::ToolResult {
+                tool_call_id: "call_1".to_string(),
+                name: "read_file".to_string(),
+                result: Value::String("# Readme".to_string()),
+                mission_id: Some(mission_id),
+            },
+            AgentEvent::Thinking {
+                content: "Done".to_string(),
+                done: false,
+                mission_id: Some(mission_id),
+            },
+        ];
+        for event in &events {
+            trace.observe(event, mission_id);
+        }
+        trace.end_thinking_block();
+        let roles: Vec<&str> = trace.tools.iter().map(ConversationEntry::role).collect();
+        assert_eq!(roles, ["tool_call", "tool_result"]);
+        assert_eq!(trace.thinking.as_deref(), Some("Let me read it\n\nDone"));
+    }
+}
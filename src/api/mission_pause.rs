@@ -0,0 +1,115 @@
+//! Pausing running missions.
+//!
+//! A paused mission stops reading its backend's output and doesn't start new
+//! turns. The CLI is left running: once its output pipe is full it blocks on
+//! the write, so it stops burning tokens without losing its state (for
+//! opencode in server mode, the server keeps running and its events are
+//! picked up where they were left). Resuming lets the mission carry on from
+//! the same point. Pauses are kept in memory only and don't survive a restart.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+fn gates() -> &'static Mutex<HashMap<Uuid, watch::Sender<bool>>> {
+    static GATES: OnceLock<Mutex<HashMap<Uuid, watch::Sender<bool>>>> = OnceLock::new();
+    GATES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Pause a mission. Returns `false` if it was already paused.
+pub fn pause(mission_id: Uuid) -> bool {
+    let mut gates = gates().lock().unwrap_or_else(|e| e.into_inner());
+    if gates.contains_key(&mission_id) {
+        return false;
+    }
+    gates.insert(mission_id, watch::channel(true).0);
+    true
+}
+
+/// Resume a paused mission. Returns `false` if it wasn't paused.
+pub fn resume(mission_id: Uuid) -> bool {
+    let sender = gates()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&mission_id);
+    match sender {
+        Some(sender) => {
+            sender.send_replace(false);
+            true
+        }
+        None => false,
+    }
+}
+
+pub fn is_paused(mission_id: Uuid) -> bool {
+    gates()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .contains_key(&mission_id)
+}
+
+/// Wait until the mission is resumed or `cancel` fires. Returns whether it
+/// had to wait at all.
+pub async fn wait_while_paused(mission_id: Uuid, cancel: &CancellationToken) -> bool {
+    let mut rx = match gates()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&mission_id)
+    {
+        Some(sender) => sender.subscribe(),
+        None => return false,
+    };
+    while *rx.borrow_and_update() {
+        tokio::select! {
+            _ = cancel.cancelled() => break,
+            changed = rx.changed() => {
+                if changed.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_pause_blocks_until_resumed() {
+        let mid = Uuid::new_v4();
+        let cancel = CancellationToken::new();
+        assert!(!wait_while_paused(mid, &cancel).await);
+
+        assert!(pause(mid));
+        assert!(!pause(mid));
+        assert!(is_paused(mid));
+        let waiter = tokio::spawn({
+            let cancel = cancel.clone();
+            async move { wait_while_paused(mid, &cancel).await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished());
+
+        assert!(resume(mid));
+        assert!(waiter.await.unwrap());
+        assert!(!is_paused(mid));
+        assert!(!resume(mid));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_ends_the_wait() {
+        let mid = Uuid::new_v4();
+        let cancel = CancellationToken::new();
+        pause(mid);
+        cancel.cancel();
+        assert!(wait_while_paused(mid, &cancel).await);
+        // Still paused: cancelling the turn doesn't resume the mission.
+        assert!(resume(mid));
+    }
+}
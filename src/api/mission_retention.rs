@@ -0,0 +1,453 @@
+//! Retention of finished missions.
+//!
+//! Missions that are done (any status but pending or active) and haven't
+//! been updated for the configured number of days are cleaned up in three
+//! independent steps: the transcript is archived as Markdown under
+//! `.sandboxed-sh/archive/missions/`, the mission's directory under its
+//! workspace is deleted, and its raw event log (tool calls and results,
+//! thinking, status changes; everything but the conversation) is pruned
+//! along with its SSE event journal.
+//! When archiving is enabled, a transcript is always written before the
+//! events it is rendered from are pruned. The mission itself and its
+//! conversation are kept. A dry run reports what a sweep would do and how
+//! much space it would reclaim.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::control::MissionStatus;
+use super::event_journal::{journal_dir, mission_log_path};
+use super::mission_artifacts::MissionArtifactStore;
+use super::mission_store::{Mission, MissionStore};
+use super::mission_transcript::{requested_deliverables, Transcript};
+use crate::config::{Config, RetentionConfig};
+use crate::workspace;
+
+/// Missions fetched from the store per page during a sweep.
+const PAGE_SIZE: usize = 200;
+
+const SECS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// A cleanup step applied to a mission.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetentionAction {
+    ArchiveTranscript,
+    DeleteWorkspace,
+    PruneEvents,
+}
+
+/// What a mission currently keeps on disk.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Footprint {
+    pub archived: bool,
+    /// Size of the mission directory, if it exists
+    pub workspace_bytes: Option<u64>,
+    pub raw_events: u64,
+    pub raw_event_bytes: u64,
+}
+
+/// Whether a mission is done with, as far as retention is concerned.
+fn is_finished(status: MissionStatus) -> bool {
+    !matches!(status, MissionStatus::Pending | MissionStatus::Active)
+}
+
+/// The steps due for a mission last updated `updated_at`, in the order they
+/// must run. Steps with nothing left to do are left out.
+pub fn plan(
+    policy: &RetentionConfig,
+    status: MissionStatus,
+    updated_at: &str,
+    footprint: &Footprint,
+    now: DateTime<Utc>,
+) -> Vec<RetentionAction> {
+    if !is_finished(status) {
+        return Vec::new();
+    }
+    let Ok(updated_at) = DateTime::parse_from_rfc3339(updated_at) else {
+        return Vec::new();
+    };
+    let age_days = now
+        .signed_duration_since(updated_at.with_timezone(&Utc))
+        .num_seconds()
+        / SECS_PER_DAY;
+    let due = |days: u64| days > 0 && age_days >= days as i64;
+
+    let prune = due(policy.events_after_days)
+        && (footprint.raw_events > 0 || footprint.raw_event_bytes > 0);
+    let archive = !footprint.archived
+        && (due(policy.archive_after_days) || (prune && policy.archive_after_days > 0));
+
+    let mut actions = Vec::new();
+    if archive {
+        actions.push(RetentionAction::ArchiveTranscript);
+    }
+    if due(policy.workspace_after_days) && footprint.workspace_bytes.is_some() {
+        actions.push(RetentionAction::DeleteWorkspace);
+    }
+    if prune {
+        actions.push(RetentionAction::PruneEvents);
+    }
+    actions
+}
+
+/// Query parameters of the retention endpoints.
+#[derive(Debug, Default, Deserialize)]
+pub struct RetentionQuery {
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// A mission a sweep cleaned up, or would clean up.
+#[derive(Debug, Clone, Serialize)]
+pub struct RetentionItem {
+    pub mission_id: Uuid,
+    pub title: Option<String>,
+    pub status: String,
+    pub updated_at: String,
+    pub actions: Vec<RetentionAction>,
+    /// Bytes freed by deleting the mission directory
+    pub workspace_bytes: u64,
+    pub raw_events: u64,
+    /// Bytes freed by pruning the raw events
+    pub raw_event_bytes: u64,
+}
+
+/// Outcome of a sweep.
+#[derive(Debug, Clone, Serialize)]
+pub struct RetentionReport {
+    pub dry_run: bool,
+    pub policy: RetentionConfig,
+    pub missions_scanned: usize,
+    pub missions: Vec<RetentionItem>,
+    pub reclaimable_bytes: u64,
+    pub errors: Vec<String>,
+}
+
+/// Where archived transcripts are written.
+pub fn archive_dir(working_dir: &Path) -> PathBuf {
+    working_dir
+        .join(".sandboxed-sh")
+        .join("archive")
+        .join("missions")
+}
+
+/// Apply the retention policy to a store's missions, skipping `running`.
+/// With `dry_run`, only report what would be done.
+pub async fn sweep(
+    store: &Arc<dyn MissionStore>,
+    workspaces: &workspace::SharedWorkspaceStore,
+    config: &Config,
+    running: &[Uuid],
+    dry_run: bool,
+) -> RetentionReport {
+    let policy = config.retention.clone();
+    let archive_dir = archive_dir(&config.working_dir);
+    let journal_dir = journal_dir(&config.working_dir);
+    let mut report = RetentionReport {
+        dry_run,
+        policy: policy.clone(),
+        missions_scanned: 0,
+        missions: Vec::new(),
+        reclaimable_bytes: 0,
+        errors: Vec::new(),
+    };
+    if !policy.is_enabled() {
+        return report;
+    }
+
+    let now = Utc::now();
+    let mut offset = 0;
+    loop {
+        let page = match store.list_missions(PAGE_SIZE, offset).await {
+            Ok(page) => page,
+            Err(e) => {
+                report
+                    .errors
+                    .push(format!("Failed to list missions: {}", e));
+                break;
+            }
+        };
+        offset += page.len();
+        let last_page = page.len() < PAGE_SIZE;
+        report.missions_scanned += page.len();
+
+        for mission in page {
+            if running.contains(&mission.id) || !is_finished(mission.status) {
+                continue;
+            }
+            let archive_path = archive_dir.join(format!("{}.md", mission.id));
+            let ws =
+                workspace::resolve_workspace(workspaces, config, Some(mission.workspace_id)).await;
+            let mission_dir = workspace::mission_workspace_dir_for_root(&ws.path, mission.id);
+            let journal_path = mission_log_path(&journal_dir, mission.id);
+            let (raw_events, raw_event_bytes) = match store.raw_event_size(mission.id).await {
+                Ok(size) => size,
+                Err(e) => {
+                    report.errors.push(format!("{}: {}", mission.id, e));
+                    continue;
+                }
+            };
+            let workspace_bytes = {
+                let dir = mission_dir.clone();
+                tokio::task::spawn_blocking(move || dir.is_dir().then(|| dir_size(&dir)))
+                    .await
+                    .unwrap_or(None)
+            };
+            let journal_bytes = std::fs::metadata(&journal_path)
+                .map(|m| m.len())
+                .unwrap_or(0);
+            let footprint = Footprint {
+                archived: archive_path.exists(),
+                workspace_bytes,
+                raw_events,
+                raw_event_bytes: raw_event_bytes + journal_bytes,
+            };
+
+            let actions = plan(
+                &policy,
+                mission.status,
+                &mission.updated_at,
+                &footprint,
+                now,
+            );
+            if actions.is_empty() {
+                continue;
+            }
+            let mut item = RetentionItem {
+                mission_id: mission.id,
+                title: mission.title.clone(),
+                status: mission.status.to_string(),
+                updated_at: mission.updated_at.clone(),
+                actions: actions.clone(),
+                workspace_bytes: 0,
+                raw_events: 0,
+                raw_event_bytes: 0,
+            };
+            if actions.contains(&RetentionAction::DeleteWorkspace) {
+                item.workspace_bytes = footprint.workspace_bytes.unwrap_or(0);
+            }
+            if actions.contains(&RetentionAction::PruneEvents) {
+                item.raw_events = raw_events;
+                item.raw_event_bytes = footprint.raw_event_bytes;
+            }
+
+            if !dry_run {
+                if let Err(e) = apply(
+                    store,
+                    config,
+                    &mission,
+                    &ws,
+                    &actions,
+                    &MissionPaths {
+                        archive: archive_path,
+                        workspace: mission_dir,
+                        journal: journal_path,
+                    },
+                )
+                .await
+                {
+                    report.errors.push(format!("{}: {}", mission.id, e));
+                    continue;
+                }
+            }
+            report.reclaimable_bytes += item.workspace_bytes + item.raw_event_bytes;
+            report.missions.push(item);
+        }
+
+        if last_page {
+            break;
+        }
+    }
+    report
+}
+
+/// Files a mission's cleanup writes or deletes.
+struct MissionPaths {
+    archive: PathBuf,
+    workspace: PathBuf,
+    journal: PathBuf,
+}
+
+async fn apply(
+    store: &Arc<dyn MissionStore>,
+    config: &Config,
+    mission: &Mission,
+    ws: &workspace::Workspace,
+    actions: &[RetentionAction],
+    paths: &MissionPaths,
+) -> Result<(), String> {
+    for action in actions {
+        match action {
+            RetentionAction::ArchiveTranscript => {
+                // Listings don't carry the history; the transcript falls
+                // back to it when the store has no event log.
+                let mission = store
+                    .get_mission(mission.id)
+                    .await?
+                    .ok_or_else(|| "mission disappeared".to_string())?;
+                let events = store.get_events(mission.id, None, None, None).await?;
+                let deliverables = requested_deliverables(&mission, ws);
+                let artifacts = MissionArtifactStore::new(&config.working_dir).list(mission.id);
+                let markdown =
+                    Transcript::build(&mission, &events, deliverables, artifacts).to_markdown();
+                if let Some(parent) = paths.archive.parent() {
+                    tokio::fs::create_dir_all(parent)
+                        .await
+                        .map_err(|e| format!("Failed to create archive directory: {}", e))?;
+                }
+                tokio::fs::write(&paths.archive, markdown)
+                    .await
+                    .map_err(|e| format!("Failed to archive transcript: {}", e))?;
+            }
+            RetentionAction::DeleteWorkspace => {
+                tokio::fs::remove_dir_all(&paths.workspace)
+                    .await
+                    .map_err(|e| {
+                        format!("Failed to delete {}: {}", paths.workspace.display(), e)
+                    })?;
+            }
+            RetentionAction::PruneEvents => {
+                store.prune_raw_events(mission.id).await?;
+                if let Err(e) = tokio::fs::remove_file(&paths.journal).await {
+                    if e.kind() != std::io::ErrorKind::NotFound {
+                        return Err(format!("Failed to delete the event journal: {}", e));
+                    }
+                }
+            }
+        }
+    }
+    tracing::info!(
+        mission_id = %mission.id,
+        actions = ?actions,
+        "Applied mission retention policy"
+    );
+    Ok(())
+}
+
+/// Total size of the files under `path`. Symlinks are not followed.
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(meta) if meta.is_dir() => dir_size(&entry.path()),
+            Ok(meta) => meta.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(archive: u64, workspace: u64, events: u64) -> RetentionConfig {
+        RetentionConfig {
+            archive_after_days: archive,
+            workspace_after_days: workspace,
+            events_after_days: events,
+        }
+    }
+
+    fn days_ago(days: i64) -> String {
+        (Utc::now() - chrono::Duration::days(days)).to_rfc3339()
+    }
+
+    const FULL: Footprint = Footprint {
+        archived: false,
+        workspace_bytes: Some(1024),
+        raw_events: 10,
+        raw_event_bytes: 2048,
+    };
+
+    #[test]
+    fn test_plan_by_age() {
+        let policy = policy(7, 14, 30);
+        let plan_at = |days| {
+            plan(
+                &policy,
+                MissionStatus::Completed,
+                &days_ago(days),
+                &FULL,
+                Utc::now(),
+            )
+        };
+        assert!(plan_at(3).is_empty());
+        assert_eq!(plan_at(8), vec![RetentionAction::ArchiveTranscript]);
+        assert_eq!(
+            plan_at(31),
+            vec![
+                RetentionAction::ArchiveTranscript,
+                RetentionAction::DeleteWorkspace,
+                RetentionAction::PruneEvents,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plan_skips_live_and_cleaned_missions() {
+        let policy = policy(1, 1, 1);
+        let old = days_ago(5);
+        assert!(plan(&policy, MissionStatus::Active, &old, &FULL, Utc::now()).is_empty());
+        assert!(plan(&policy, MissionStatus::Pending, &old, &FULL, Utc::now()).is_empty());
+        assert!(plan(&policy, MissionStatus::Failed, "garbage", &FULL, Utc::now()).is_empty());
+
+        let cleaned = Footprint {
+            archived: true,
+            ..Default::default()
+        };
+        assert!(plan(&policy, MissionStatus::Failed, &old, &cleaned, Utc::now()).is_empty());
+        assert!(plan(
+            &RetentionConfig::default(),
+            MissionStatus::Failed,
+            &old,
+            &FULL,
+            Utc::now()
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_pruning_archives_first() {
+        let actions = plan(
+            &policy(30, 0, 7),
+            MissionStatus::Interrupted,
+            &days_ago(10),
+            &FULL,
+            Utc::now(),
+        );
+        assert_eq!(
+            actions,
+            vec![
+                RetentionAction::ArchiveTranscript,
+                RetentionAction::PruneEvents
+            ]
+        );
+
+        // Without an archive policy, events are pruned on their own.
+        let actions = plan(
+            &policy(0, 0, 7),
+            MissionStatus::Interrupted,
+            &days_ago(10),
+            &FULL,
+            Utc::now(),
+        );
+        assert_eq!(actions, vec![RetentionAction::PruneEvents]);
+    }
+
+    #[test]
+    fn test_dir_size() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), b"hello").unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        std::fs::write(dir.path().join("sub").join("b.txt"), b"world!").unwrap();
+        assert_eq!(dir_size(dir.path()), 11);
+    }
+}
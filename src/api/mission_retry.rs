@@ -0,0 +1,201 @@
+//! Automatic retries of failed mission turns.
+//!
+//! A mission created with a `retry_policy` has a failed turn re-run when the
+//! failure looks transient: a provider error (overloaded, rate limited, 5xx),
+//! a stall, or a network error. Retries wait with exponential backoff and
+//! stop after `max_attempts` runs of the same message. Cancelled, timed-out
+//! and over-budget turns are never retried.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::agents::{AgentResult, TerminalReason};
+
+/// Longest wait between two attempts.
+const MAX_BACKOFF_SECS: u64 = 3600;
+
+/// Kind of failure a retry can be triggered by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetryOn {
+    /// Provider/model errors: overloaded (529), rate limited (429), 5xx
+    LlmError,
+    /// The agent stopped making progress
+    Stalled,
+    /// Connection failures between the backend and the provider
+    Network,
+}
+
+impl std::fmt::Display for RetryOn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RetryOn::LlmError => write!(f, "llm_error"),
+            RetryOn::Stalled => write!(f, "stalled"),
+            RetryOn::Network => write!(f, "network"),
+        }
+    }
+}
+
+fn default_max_attempts() -> u32 {
+    3
+}
+
+fn default_backoff_secs() -> u64 {
+    30
+}
+
+fn default_retry_on() -> Vec<RetryOn> {
+    vec![RetryOn::LlmError, RetryOn::Stalled, RetryOn::Network]
+}
+
+/// How a mission retries failed turns.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// Runs of a message in total, including the first one
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+    /// Wait before the first retry; doubled for every further one
+    #[serde(default = "default_backoff_secs")]
+    pub backoff_secs: u64,
+    #[serde(default = "default_retry_on")]
+    pub retry_on: Vec<RetryOn>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_max_attempts(),
+            backoff_secs: default_backoff_secs(),
+            retry_on: default_retry_on(),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Why a failed turn should be retried, given how many times its message
+    /// has run. `None` if it succeeded, isn't retryable or is out of attempts.
+    pub fn should_retry(&self, result: &AgentResult, attempts: u32) -> Option<RetryOn> {
+        if attempts >= self.max_attempts {
+            return None;
+        }
+        classify_failure(result).filter(|reason| self.retry_on.contains(reason))
+    }
+
+    /// Wait before the given retry (1 = first retry).
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 1u64 << retry.saturating_sub(1).min(16);
+        Duration::from_secs(
+            self.backoff_secs
+                .saturating_mul(factor)
+                .min(MAX_BACKOFF_SECS),
+        )
+    }
+}
+
+const NETWORK_PATTERNS: &[&str] = &[
+    "connection reset",
+    "connection refused",
+    "connection closed",
+    "econnreset",
+    "econnrefused",
+    "etimedout",
+    "network error",
+    "socket hang up",
+    "dns error",
+    "failed to lookup address",
+];
+
+const PROVIDER_PATTERNS: &[&str] = &[
+    "overloaded",
+    "api error: 529",
+    "api error: 429",
+    "api error: 500",
+    "api error: 503",
+    "rate limit",
+    "rate_limit",
+    "too many requests",
+    "502 bad gateway",
+    "503 service unavailable",
+    "504 gateway timeout",
+    "internal server error",
+];
+
+/// The transient failure kind of a turn, if any.
+pub fn classify_failure(result: &AgentResult) -> Option<RetryOn> {
+    if result.success {
+        return None;
+    }
+    match result.terminal_reason {
+        Some(TerminalReason::Cancelled)
+        | Some(TerminalReason::TimedOut)
+        | Some(TerminalReason::BudgetExhausted)
+        | Some(TerminalReason::Completed)
+        | Some(TerminalReason::MaxIterations)
+        | Some(TerminalReason::InfiniteLoop) => return None,
+        Some(TerminalReason::Stalled) => return Some(RetryOn::Stalled),
+        Some(TerminalReason::LlmError) | None => {}
+    }
+    let output = result.output.to_lowercase();
+    if NETWORK_PATTERNS.iter().any(|p| output.contains(p)) {
+        Some(RetryOn::Network)
+    } else if result.terminal_reason == Some(TerminalReason::LlmError)
+        || PROVIDER_PATTERNS.iter().any(|p| output.contains(p))
+    {
+        Some(RetryOn::LlmError)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failure(output: &str, reason: Option<TerminalReason>) -> AgentResult {
+        let mut result = AgentResult::failure(output, 0);
+        result.terminal_reason = reason;
+        result
+    }
+
+    #[test]
+    fn test_classify_failure() {
+        assert_eq!(
+            classify_failure(&failure("API Error: 529 Overloaded", None)),
+            Some(RetryOn::LlmError)
+        );
+        assert_eq!(
+            classify_failure(&failure("read ECONNRESET", None)),
+            Some(RetryOn::Network)
+        );
+        assert_eq!(
+            classify_failure(&failure("", Some(TerminalReason::Stalled))),
+            Some(RetryOn::Stalled)
+        );
+        assert_eq!(
+            classify_failure(&failure("overloaded", Some(TerminalReason::Cancelled))),
+            None
+        );
+        assert_eq!(classify_failure(&failure("Tests failed", None)), None);
+        assert_eq!(classify_failure(&AgentResult::success("529", 0)), None);
+    }
+
+    #[test]
+    fn test_policy_attempts_and_backoff() {
+        let policy = RetryPolicy {
+            retry_on: vec![RetryOn::LlmError],
+            ..Default::default()
+        };
+        let overloaded = failure("529 overloaded", None);
+        assert_eq!(policy.should_retry(&overloaded, 1), Some(RetryOn::LlmError));
+        assert_eq!(policy.should_retry(&overloaded, 3), None);
+        assert_eq!(
+            policy.should_retry(&failure("", Some(TerminalReason::Stalled)), 1),
+            None
+        );
+
+        assert_eq!(policy.backoff(1), Duration::from_secs(30));
+        assert_eq!(policy.backoff(3), Duration::from_secs(120));
+        assert_eq!(policy.backoff(30), Duration::from_secs(MAX_BACKOFF_SECS));
+    }
+}
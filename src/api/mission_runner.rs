@@ -12,7 +12,7 @@
 
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use tokio::sync::{broadcast, mpsc, RwLock};
 use tokio_util::sync::CancellationToken;
@@ -30,9 +30,17 @@ use crate::workspace_exec::WorkspaceExec;
 
 use super::control::{
     resolve_claudecode_default_model, safe_truncate_index, AgentEvent, AgentTreeNode,
-    ControlRunState, ControlStatus, ExecutionProgress, FrontendToolHub,
+    ControlRunState, ControlStatus, DiagnosticLevel, ExecutionProgress, FrontendToolHub,
+    MissionPriority,
 };
 use super::library::SharedLibrary;
+use super::mission_completion;
+use super::mission_history::{self, ConversationEntry, SharedHistorySummary, TurnTrace};
+use super::mission_retry::{RetryOn, RetryPolicy};
+use super::mission_snapshots::{MissionSnapshot, MissionSnapshotStore};
+use super::mission_stall::StallThresholds;
+use super::mission_store::{now_string, MissionHistoryEntry};
+use super::workspace_slots;
 
 #[derive(Debug, Default)]
 struct OpencodeSseState {
@@ -100,6 +108,21 @@ fn extract_thought_line(text: &str) -> Option<(String, String)> {
     })
 }
 
+/// Token totals in the form `crate::cost` prices.
+fn token_usage(
+    input_tokens: u64,
+    output_tokens: u64,
+    cache_creation_tokens: u64,
+    cache_read_tokens: u64,
+) -> crate::cost::TokenUsage {
+    crate::cost::TokenUsage {
+        input_tokens,
+        output_tokens,
+        cache_creation_input_tokens: (cache_creation_tokens > 0).then_some(cache_creation_tokens),
+        cache_read_input_tokens: (cache_read_tokens > 0).then_some(cache_read_tokens),
+    }
+}
+
 async fn set_control_state_for_mission(
     status: &Arc<RwLock<ControlStatus>>,
     events_tx: &broadcast::Sender<AgentEvent>,
@@ -570,7 +593,8 @@ fn parse_opencode_sse_event(
 }
 
 /// State of a running mission.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum MissionRunState {
     /// Waiting in queue
     Queued,
@@ -582,10 +606,7 @@ pub enum MissionRunState {
     Finished,
 }
 
-const STALL_WARN_SECS: u64 = 120;
-const STALL_SEVERE_SECS: u64 = 300;
-
-#[derive(Debug, Clone, Copy, serde::Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum MissionStallSeverity {
     Warning,
@@ -606,26 +627,22 @@ pub enum MissionHealth {
     },
     /// Mission completed without deliverables
     MissingDeliverables { missing: Vec<String> },
+    /// Deliverable checks failed when the mission completed
+    FailedChecks { failed: Vec<String> },
     /// Mission ended unexpectedly
     UnexpectedEnd { reason: String },
 }
 
-fn stall_severity(seconds_since_activity: u64) -> Option<MissionStallSeverity> {
-    if seconds_since_activity > STALL_SEVERE_SECS {
-        Some(MissionStallSeverity::Severe)
-    } else if seconds_since_activity > STALL_WARN_SECS {
-        Some(MissionStallSeverity::Warning)
-    } else {
-        None
-    }
-}
-
-pub fn running_health(state: MissionRunState, seconds_since_activity: u64) -> MissionHealth {
+pub fn running_health(
+    state: MissionRunState,
+    seconds_since_activity: u64,
+    thresholds: StallThresholds,
+) -> MissionHealth {
     if matches!(
         state,
         MissionRunState::Running | MissionRunState::WaitingForTool
     ) {
-        if let Some(severity) = stall_severity(seconds_since_activity) {
+        if let Some(severity) = thresholds.severity(seconds_since_activity) {
             return MissionHealth::Stalled {
                 seconds_since_activity,
                 last_state: format!("{:?}", state),
@@ -637,12 +654,50 @@ pub fn running_health(state: MissionRunState, seconds_since_activity: u64) -> Mi
 }
 
 /// A message queued for this mission.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct QueuedMessage {
     pub id: Uuid,
     pub content: String,
     /// Optional agent override for this specific message (e.g., from @agent mention)
     pub agent: Option<String>,
+    #[serde(default)]
+    pub priority: MissionPriority,
+}
+
+/// Insert `item` after everything of equal or higher priority, so messages
+/// of the same priority stay first-in first-out.
+pub fn insert_by_priority<T>(
+    queue: &mut VecDeque<T>,
+    item: T,
+    priority_of: impl Fn(&T) -> MissionPriority,
+) {
+    let priority = priority_of(&item);
+    let index = queue
+        .iter()
+        .position(|queued| priority_of(queued) < priority)
+        .unwrap_or(queue.len());
+    queue.insert(index, item);
+}
+
+/// Move the items whose ids are listed to the front of the queue, in the
+/// listed order. Other items keep their relative order; unknown ids are
+/// ignored. Returns the number of items moved.
+pub fn reorder_queue<T>(
+    queue: &mut VecDeque<T>,
+    ids: &[Uuid],
+    id_of: impl Fn(&T) -> Uuid,
+) -> usize {
+    let mut front = Vec::new();
+    for id in ids {
+        if let Some(index) = queue.iter().position(|item| id_of(item) == *id) {
+            front.extend(queue.remove(index));
+        }
+    }
+    let moved = front.len();
+    for item in front.into_iter().rev() {
+        queue.push_front(item);
+    }
+    moved
 }
 
 /// Isolated runner for a single mission.
@@ -654,6 +709,27 @@ pub struct SubtaskInfo {
     pub completed: bool,
 }
 
+/// A failed turn waiting to be run again.
+#[derive(Debug, Clone, Copy)]
+pub struct PendingRetry {
+    /// Retry number (1 = first retry)
+    pub retry: u32,
+    pub reason: RetryOn,
+    pub delay: Duration,
+    pub due: Instant,
+}
+
+/// What a turn task returns: the message ID and content, the result and
+/// the tool activity recorded along the way.
+type TurnOutput = (Uuid, String, AgentResult, TurnTrace);
+
+/// A turn running next to the main one, in its own backend session.
+struct ConcurrentTurn {
+    message: QueuedMessage,
+    cancel: CancellationToken,
+    handle: tokio::task::JoinHandle<TurnOutput>,
+}
+
 pub struct MissionRunner {
     /// Mission ID
     pub mission_id: Uuid,
@@ -679,14 +755,20 @@ pub struct MissionRunner {
     /// Message queue for this mission
     pub queue: VecDeque<QueuedMessage>,
 
-    /// Conversation history: (role, content)
-    pub history: Vec<(String, String)>,
+    /// Message of the turn currently executing
+    pub current_message: Option<QueuedMessage>,
+
+    /// Conversation history, including tool calls
+    pub history: Vec<ConversationEntry>,
+
+    /// Summary of the history entries that no longer fit in a turn's context
+    pub history_summary: SharedHistorySummary,
 
     /// Cancellation token for the current execution
     pub cancel_token: Option<CancellationToken>,
 
     /// Running task handle
-    running_handle: Option<tokio::task::JoinHandle<(Uuid, String, AgentResult)>>,
+    running_handle: Option<tokio::task::JoinHandle<TurnOutput>>,
 
     /// Tree snapshot for this mission
     pub tree_snapshot: Arc<RwLock<Option<AgentTreeNode>>>,
@@ -708,6 +790,33 @@ pub struct MissionRunner {
 
     /// Tracked subtasks (from delegate_task/Task tool calls)
     pub subtasks: Vec<SubtaskInfo>,
+
+    /// Where runner state is persisted on every transition
+    snapshots: Option<Arc<MissionSnapshotStore>>,
+
+    /// How failed turns are retried (no retries if unset)
+    pub retry_policy: Option<RetryPolicy>,
+
+    /// Message of the latest turn and how many times it has run
+    attempts: Option<(Uuid, u32)>,
+
+    /// Failed turn scheduled to run again
+    pub pending_retry: Option<PendingRetry>,
+
+    /// Queued messages that may run at once (1 = one after the other)
+    pub max_concurrent: usize,
+
+    /// Turns started while the main turn was running
+    concurrent_turns: Vec<ConcurrentTurn>,
+
+    /// The workspace's cap on missions running at once (no cap if unset)
+    pub workspace_limit: Option<u32>,
+
+    /// Whether the next turn is held back because the workspace is full
+    waiting_for_workspace: bool,
+
+    /// When the mission counts as stalled
+    pub stall_thresholds: StallThresholds,
 }
 
 impl MissionRunner {
@@ -729,7 +838,9 @@ impl MissionRunner {
             state: MissionRunState::Queued,
             agent_override,
             queue: VecDeque::new(),
+            current_message: None,
             history: Vec::new(),
+            history_summary: Arc::default(),
             cancel_token: None,
             running_handle: None,
             tree_snapshot: Arc::new(RwLock::new(None)),
@@ -739,17 +850,162 @@ impl MissionRunner {
             explicitly_completed: false,
             current_activity: None,
             subtasks: Vec::new(),
+            snapshots: None,
+            retry_policy: None,
+            attempts: None,
+            pending_retry: None,
+            max_concurrent: 1,
+            concurrent_turns: Vec::new(),
+            workspace_limit: None,
+            waiting_for_workspace: false,
+            stall_thresholds: StallThresholds::default(),
+        }
+    }
+
+    /// Retry failed turns according to `policy`.
+    pub fn with_retry_policy(mut self, policy: Option<RetryPolicy>) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Run up to `max` queued messages at once. Turns beyond the first use a
+    /// backend session of their own and are added to the history as they finish.
+    pub fn with_concurrency(mut self, max: Option<u32>) -> Self {
+        self.max_concurrent = max.unwrap_or(1).max(1) as usize;
+        self
+    }
+
+    /// Run only while fewer than `limit` other missions run in the workspace.
+    pub fn with_workspace_limit(mut self, limit: Option<u32>) -> Self {
+        self.workspace_limit = limit;
+        self
+    }
+
+    /// Persist this runner's state to `snapshots` from now on.
+    pub fn with_snapshots(mut self, snapshots: Arc<MissionSnapshotStore>) -> Self {
+        self.snapshots = Some(snapshots);
+        self.persist();
+        self
+    }
+
+    /// Rebuild a runner from a snapshot left by a previous server run.
+    ///
+    /// The runner comes back idle. A turn that was in flight is lost, so its
+    /// message is kept in the history for the resume context.
+    pub fn restore(snapshot: MissionSnapshot, snapshots: Arc<MissionSnapshotStore>) -> Self {
+        let was_running = snapshot.was_running();
+        let mut runner = Self::new(
+            snapshot.mission_id,
+            snapshot.workspace_id,
+            snapshot.agent_override,
+            Some(snapshot.backend_id),
+            snapshot.session_id,
+            snapshot.config_profile,
+        );
+        runner.queue = snapshot.queue.into();
+        runner.history = snapshot.history;
+        runner.history_summary = Arc::new(std::sync::Mutex::new(snapshot.history_summary));
+        runner.deliverables = snapshot.deliverables;
+        runner.explicitly_completed = snapshot.explicitly_completed;
+        if was_running {
+            if let Some(message) = snapshot.current_message {
+                runner
+                    .history
+                    .push(ConversationEntry::user(message.content));
+            }
+        }
+        for message in snapshot.concurrent_messages {
+            runner
+                .history
+                .push(ConversationEntry::user(message.content));
+        }
+        runner.with_snapshots(snapshots)
+    }
+
+    /// Serializable state of this runner.
+    pub fn snapshot(&self) -> MissionSnapshot {
+        MissionSnapshot {
+            mission_id: self.mission_id,
+            workspace_id: self.workspace_id,
+            backend_id: self.backend_id.clone(),
+            session_id: self.session_id.clone(),
+            config_profile: self.config_profile.clone(),
+            agent_override: self.agent_override.clone(),
+            state: self.state,
+            current_message: self.current_message.clone(),
+            concurrent_messages: self
+                .concurrent_turns
+                .iter()
+                .map(|turn| turn.message.clone())
+                .collect(),
+            queue: self.queue.iter().cloned().collect(),
+            history: self.history.clone(),
+            history_summary: self
+                .history_summary
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .clone(),
+            deliverables: self.deliverables.clone(),
+            explicitly_completed: self.explicitly_completed,
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    fn persist(&self) {
+        if let Some(snapshots) = &self.snapshots {
+            snapshots.save(&self.snapshot());
+        }
+    }
+
+    /// Delete the persisted state once the runner is dropped for good.
+    pub fn discard_snapshot(&self) {
+        if let Some(snapshots) = &self.snapshots {
+            snapshots.remove(self.mission_id);
+        }
+    }
+
+    /// Whether queued messages wait for a free slot in the workspace.
+    pub fn is_waiting_for_workspace(&self) -> bool {
+        self.waiting_for_workspace
+    }
+
+    /// Whether a mission waiting for the workspace could start now.
+    pub fn workspace_ready(&self) -> bool {
+        self.waiting_for_workspace
+            && !self.workspace_limit.is_some_and(|limit| {
+                workspace_slots::running_in(self.workspace_id) >= limit as usize
+            })
+    }
+
+    /// Give back the workspace slot, e.g. when the runner is dropped.
+    pub fn release_workspace(&self) {
+        workspace_slots::release(self.workspace_id, self.mission_id);
+    }
+
+    /// Give back the workspace slot once nothing runs any more.
+    fn release_workspace_if_idle(&self) {
+        if !self.is_running() {
+            self.release_workspace();
         }
     }
 
     /// Check if this runner is currently executing.
     pub fn is_running(&self) -> bool {
+        self.main_turn_active() || !self.concurrent_turns.is_empty()
+    }
+
+    fn main_turn_active(&self) -> bool {
         matches!(
             self.state,
             MissionRunState::Running | MissionRunState::WaitingForTool
         )
     }
 
+    /// Whether another queued message could start now.
+    pub fn has_free_slot(&self) -> bool {
+        !self.main_turn_active() || self.concurrent_turns.len() + 1 < self.max_concurrent
+    }
+
     /// Check if this runner has finished.
     pub fn is_finished(&self) -> bool {
         matches!(self.state, MissionRunState::Finished)
@@ -764,9 +1020,9 @@ impl MissionRunner {
     pub async fn check_health(&self) -> MissionHealth {
         let seconds_since = self.last_activity.elapsed().as_secs();
 
-        // If running and no activity for a while, consider stalled
-        if self.is_running() {
-            if let Some(severity) = stall_severity(seconds_since) {
+        // If running and no activity for a while, consider stalled (unless paused)
+        if self.is_running() && !super::mission_pause::is_paused(self.mission_id) {
+            if let Some(severity) = self.stall_thresholds.severity(seconds_since) {
                 return MissionHealth::Stalled {
                     seconds_since_activity: seconds_since,
                     last_state: format!("{:?}", self.state),
@@ -780,7 +1036,10 @@ impl MissionRunner {
             && !self.explicitly_completed
             && !self.deliverables.deliverables.is_empty()
         {
-            let missing = self.deliverables.missing_paths().await;
+            let missing = self
+                .deliverables
+                .missing_descriptions(&self.assistant_output())
+                .await;
             if !missing.is_empty() {
                 return MissionHealth::MissingDeliverables { missing };
             }
@@ -789,6 +1048,30 @@ impl MissionRunner {
         MissionHealth::Healthy
     }
 
+    /// The user and assistant messages, as the mission store keeps them.
+    pub fn stored_history(&self) -> Vec<MissionHistoryEntry> {
+        self.history
+            .iter()
+            .filter_map(|entry| {
+                entry.message_text().map(|content| MissionHistoryEntry {
+                    role: entry.role().to_string(),
+                    content: content.to_string(),
+                })
+            })
+            .collect()
+    }
+
+    /// The agent's messages so far, where commits, pull requests and other
+    /// non-file deliverables are looked for.
+    fn assistant_output(&self) -> String {
+        self.history
+            .iter()
+            .filter(|entry| entry.is_assistant())
+            .filter_map(ConversationEntry::message_text)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
     /// Extract deliverables from initial mission message.
     pub fn set_initial_message(&mut self, message: &str) {
         self.deliverables = extract_deliverables(message);
@@ -800,15 +1083,77 @@ impl MissionRunner {
                 self.deliverables
                     .deliverables
                     .iter()
-                    .filter_map(|d| d.path())
+                    .map(|d| d.describe())
                     .collect::<Vec<_>>()
             );
         }
     }
 
     /// Queue a message for this mission.
-    pub fn queue_message(&mut self, id: Uuid, content: String, agent: Option<String>) {
-        self.queue.push_back(QueuedMessage { id, content, agent });
+    pub fn queue_message(
+        &mut self,
+        id: Uuid,
+        content: String,
+        agent: Option<String>,
+        priority: MissionPriority,
+    ) {
+        insert_by_priority(
+            &mut self.queue,
+            QueuedMessage {
+                id,
+                content,
+                agent,
+                priority,
+            },
+            |qm| qm.priority,
+        );
+        self.persist();
+    }
+
+    /// Queue a message ahead of the others (e.g. a resume prompt).
+    pub fn queue_message_front(&mut self, id: Uuid, content: String, agent: Option<String>) {
+        self.queue.push_front(QueuedMessage {
+            id,
+            content,
+            agent,
+            priority: MissionPriority::Urgent,
+        });
+        self.persist();
+    }
+
+    /// Change a queued message's priority and move it accordingly.
+    /// Returns true if the message was found.
+    pub fn set_queued_priority(&mut self, message_id: Uuid, priority: MissionPriority) -> bool {
+        let Some(index) = self.queue.iter().position(|qm| qm.id == message_id) else {
+            return false;
+        };
+        if let Some(mut qm) = self.queue.remove(index) {
+            qm.priority = priority;
+            insert_by_priority(&mut self.queue, qm, |qm| qm.priority);
+        }
+        self.persist();
+        true
+    }
+
+    /// Replace a queued message's content. Returns false if it isn't queued
+    /// (anymore).
+    pub fn edit_queued_message(&mut self, message_id: Uuid, content: &str) -> bool {
+        let Some(qm) = self.queue.iter_mut().find(|qm| qm.id == message_id) else {
+            return false;
+        };
+        qm.content = content.to_string();
+        self.persist();
+        true
+    }
+
+    /// Move the given queued messages to the front, in order.
+    /// Returns the number of messages moved.
+    pub fn reorder_queue(&mut self, message_ids: &[Uuid]) -> usize {
+        let moved = reorder_queue(&mut self.queue, message_ids, |qm| qm.id);
+        if moved > 0 {
+            self.persist();
+        }
+        moved
     }
 
     /// Cancel the current execution.
@@ -816,6 +1161,9 @@ impl MissionRunner {
         if let Some(token) = &self.cancel_token {
             token.cancel();
         }
+        for turn in &self.concurrent_turns {
+            turn.cancel.cancel();
+        }
     }
 
     /// Remove a specific message from the queue by ID.
@@ -823,7 +1171,11 @@ impl MissionRunner {
     pub fn remove_from_queue(&mut self, message_id: Uuid) -> bool {
         let before_len = self.queue.len();
         self.queue.retain(|qm| qm.id != message_id);
-        self.queue.len() < before_len
+        let removed = self.queue.len() < before_len;
+        if removed {
+            self.persist();
+        }
+        removed
     }
 
     /// Clear all queued messages.
@@ -831,11 +1183,14 @@ impl MissionRunner {
     pub fn clear_queue(&mut self) -> usize {
         let cleared = self.queue.len();
         self.queue.clear();
+        self.pending_retry = None;
+        self.persist();
         cleared
     }
 
     /// Start executing the next queued message (if any and not already running).
-    /// Returns true if execution was started.
+    /// In concurrent mode, starts as many queued messages as there are free
+    /// slots. Returns true if execution was started.
     pub fn start_next(
         &mut self,
         config: Config,
@@ -850,128 +1205,221 @@ impl MissionRunner {
         current_mission: Arc<RwLock<Option<Uuid>>>,
         secrets: Option<Arc<SecretsStore>>,
     ) -> bool {
-        // Don't start if already running
-        if self.is_running() {
+        // Don't start while waiting to retry, or while the server shuts down
+        if self.pending_retry.is_some_and(|r| r.due > Instant::now())
+            || super::shutdown::is_draining()
+        {
             return false;
         }
 
-        // Get next message from queue
-        let msg = match self.queue.pop_front() {
-            Some(m) => m,
-            None => return false,
-        };
+        // An idle mission needs a slot in its workspace before it runs again
+        if !self.is_running()
+            && !self.queue.is_empty()
+            && !workspace_slots::try_acquire(
+                self.workspace_id,
+                self.mission_id,
+                self.workspace_limit,
+            )
+        {
+            if !self.waiting_for_workspace {
+                tracing::info!(
+                    "Mission {} waits for workspace {} ({} missions running)",
+                    self.mission_id,
+                    self.workspace_id,
+                    workspace_slots::running_in(self.workspace_id)
+                );
+                self.waiting_for_workspace = true;
+            }
+            return false;
+        }
+        self.waiting_for_workspace = false;
 
-        self.state = MissionRunState::Running;
-
-        let cancel = CancellationToken::new();
-        self.cancel_token = Some(cancel.clone());
-
-        let hist_snapshot = self.history.clone();
-        let tree_ref = Arc::clone(&self.tree_snapshot);
-        let progress_ref = Arc::clone(&self.progress_snapshot);
-        let mission_id = self.mission_id;
-        let workspace_id = self.workspace_id;
-        let agent_override = self.agent_override.clone();
-        let backend_id = self.backend_id.clone();
-        let session_id = self.session_id.clone();
-        let config_profile = self.config_profile.clone();
-        let user_message = msg.content.clone();
-        let msg_id = msg.id;
-        tracing::info!(
-            mission_id = %mission_id,
-            workspace_id = %workspace_id,
-            agent_override = ?agent_override,
-            message_id = %msg_id,
-            message_len = user_message.len(),
-            "Mission runner starting"
-        );
+        let mut started = false;
+        while self.has_free_slot() {
+            // Get next message from queue
+            let msg = match self.queue.pop_front() {
+                Some(m) => m,
+                None => break,
+            };
+            let cancel = CancellationToken::new();
 
-        // Create mission control for complete_mission tool
-        let mission_ctrl = crate::tools::mission::MissionControl {
-            current_mission_id: current_mission,
-            cmd_tx: mission_cmd_tx,
-        };
+            // The main turn continues the mission's session; a concurrent one
+            // gets a fresh session so the two don't interleave.
+            let concurrent = self.main_turn_active();
+            let session_id = if concurrent {
+                Some(Uuid::new_v4().to_string())
+            } else {
+                self.pending_retry = None;
+                self.attempts = match self.attempts {
+                    Some((id, runs)) if id == msg.id => Some((id, runs + 1)),
+                    _ => Some((msg.id, 1)),
+                };
+                self.state = MissionRunState::Running;
+                self.current_message = Some(msg.clone());
+                self.cancel_token = Some(cancel.clone());
+                self.session_id.clone()
+            };
 
-        // Emit user message event with mission context
-        let _ = events_tx.send(AgentEvent::UserMessage {
-            id: msg_id,
-            content: user_message.clone(),
-            queued: false,
-            mission_id: Some(mission_id),
-        });
+            let hist_snapshot = self.history.clone();
+            let history_summary = Arc::clone(&self.history_summary);
+            let tree_ref = Arc::clone(&self.tree_snapshot);
+            let progress_ref = Arc::clone(&self.progress_snapshot);
+            let mission_id = self.mission_id;
+            let workspace_id = self.workspace_id;
+            let agent_override = self.agent_override.clone();
+            let backend_id = self.backend_id.clone();
+            let config_profile = self.config_profile.clone();
+            let user_message = msg.content.clone();
+            let msg_id = msg.id;
+            tracing::info!(
+                mission_id = %mission_id,
+                workspace_id = %workspace_id,
+                agent_override = ?agent_override,
+                message_id = %msg_id,
+                message_len = user_message.len(),
+                concurrent,
+                "Mission runner starting"
+            );
 
-        let handle = tokio::spawn(async move {
-            let result = run_mission_turn(
-                config,
-                root_agent,
-                mcp,
-                workspaces,
-                library,
-                events_tx,
-                tool_hub,
-                status,
-                cancel,
-                hist_snapshot,
-                user_message.clone(),
-                Some(mission_ctrl),
-                tree_ref,
-                progress_ref,
-                mission_id,
-                Some(workspace_id),
-                backend_id,
-                agent_override,
-                secrets,
-                session_id,
-                config_profile,
-            )
-            .await;
-            (msg_id, user_message, result)
-        });
+            // Create mission control for complete_mission tool
+            let mission_ctrl = crate::tools::mission::MissionControl {
+                current_mission_id: Arc::clone(&current_mission),
+                cmd_tx: mission_cmd_tx.clone(),
+            };
 
-        self.running_handle = Some(handle);
-        true
+            // Emit user message event with mission context
+            let _ = events_tx.send(AgentEvent::UserMessage {
+                id: msg_id,
+                content: user_message.clone(),
+                queued: false,
+                mission_id: Some(mission_id),
+            });
+
+            let events_rx = events_tx.subscribe();
+            let config = config.clone();
+            let root_agent = Arc::clone(&root_agent);
+            let mcp = Arc::clone(&mcp);
+            let workspaces = Arc::clone(&workspaces);
+            let library = library.clone();
+            let events_tx = events_tx.clone();
+            let tool_hub = Arc::clone(&tool_hub);
+            let status = Arc::clone(&status);
+            let secrets = secrets.clone();
+            let turn_cancel = cancel.clone();
+            let handle = tokio::spawn(async move {
+                // Stops the recorder even if this task is aborted
+                let recorded = CancellationToken::new();
+                let recorder = tokio::spawn(mission_history::trace_turn(
+                    events_rx,
+                    mission_id,
+                    recorded.clone(),
+                ));
+                let recorded = recorded.drop_guard();
+                let result = run_mission_turn(
+                    config,
+                    root_agent,
+                    mcp,
+                    workspaces,
+                    library,
+                    events_tx,
+                    tool_hub,
+                    status,
+                    turn_cancel,
+                    hist_snapshot,
+                    history_summary,
+                    user_message.clone(),
+                    Some(mission_ctrl),
+                    tree_ref,
+                    progress_ref,
+                    mission_id,
+                    Some(workspace_id),
+                    backend_id,
+                    agent_override,
+                    secrets,
+                    session_id,
+                    config_profile,
+                )
+                .await;
+                drop(recorded);
+                let trace = recorder.await.unwrap_or_default();
+                (msg_id, user_message, result, trace)
+            });
+
+            if concurrent {
+                self.concurrent_turns.push(ConcurrentTurn {
+                    message: msg,
+                    cancel,
+                    handle,
+                });
+            } else {
+                self.running_handle = Some(handle);
+            }
+            self.persist();
+            started = true;
+        }
+        started
     }
 
     /// Poll for completion. Returns Some(result) if finished.
     pub async fn poll_completion(&mut self) -> Option<(Uuid, String, AgentResult)> {
+        // Concurrent turns are added to the history in completion order
+        if let Some(index) = self
+            .concurrent_turns
+            .iter()
+            .position(|turn| turn.handle.is_finished())
+        {
+            let turn = self.concurrent_turns.remove(index);
+            let result = match turn.handle.await {
+                Ok((id, message, result, trace)) => {
+                    self.touch();
+                    let result = (id, message, result);
+                    self.record_turn(&result, trace).await;
+                    Some(result)
+                }
+                Err(e) => {
+                    tracing::error!("Concurrent mission turn failed: {}", e);
+                    self.persist();
+                    None
+                }
+            };
+            self.release_workspace_if_idle();
+            return result;
+        }
+
         let handle = self.running_handle.take()?;
 
         // Check if handle is finished
         if handle.is_finished() {
             match handle.await {
-                Ok(result) => {
+                Ok((id, message, result, trace)) => {
+                    let result = (id, message, result);
                     self.touch(); // Update last activity
                     self.state = MissionRunState::Queued; // Ready for next message
+                    self.release_workspace_if_idle();
 
-                    // Check if complete_mission was called
-                    if result.2.output.contains("Mission marked as")
-                        || result.2.output.contains("complete_mission")
-                    {
-                        self.explicitly_completed = true;
-                    }
-
-                    // Add to history
-                    self.history.push(("user".to_string(), result.1.clone()));
-                    self.history
-                        .push(("assistant".to_string(), result.2.output.clone()));
-
-                    // Log warning if deliverables are missing and task ended
-                    if !self.explicitly_completed && !self.deliverables.deliverables.is_empty() {
-                        let missing = self.deliverables.missing_paths().await;
-                        if !missing.is_empty() {
-                            tracing::warn!(
-                                "Mission {} ended but deliverables are missing: {:?}",
-                                self.mission_id,
-                                missing
-                            );
-                        }
+                    // Transient failure: put the message back and retry it after
+                    // a backoff. The failed attempt stays out of the history.
+                    if let Some(retry) = self.schedule_retry(&result.2) {
+                        tracing::warn!(
+                            "Mission {} turn failed ({}); retry {} in {}s",
+                            self.mission_id,
+                            retry.reason,
+                            retry.retry,
+                            retry.delay.as_secs()
+                        );
+                        return Some(result);
                     }
 
+                    self.current_message = None;
+                    self.record_turn(&result, trace).await;
                     Some(result)
                 }
                 Err(e) => {
                     tracing::error!("Mission runner task failed: {}", e);
                     self.state = MissionRunState::Finished;
+                    self.release_workspace_if_idle();
+                    self.current_message = None;
+                    self.persist();
                     None
                 }
             }
@@ -982,28 +1430,79 @@ impl MissionRunner {
         }
     }
 
-    /// Check if the running task is finished (non-blocking).
-    pub fn check_finished(&self) -> bool {
-        self.running_handle
-            .as_ref()
-            .map(|h| h.is_finished())
-            .unwrap_or(true)
-    }
-}
+    /// Add a finished turn to the history.
+    async fn record_turn(&mut self, result: &(Uuid, String, AgentResult), trace: TurnTrace) {
+        // Check if complete_mission was called
+        if mission_completion::take_reported(self.mission_id) {
+            self.explicitly_completed = true;
+        }
 
-/// Build a history context string from conversation history.
-fn build_history_context(history: &[(String, String)], max_chars: usize) -> String {
-    let mut result = String::new();
-    let mut total_chars = 0;
-    for (role, content) in history.iter().rev() {
-        let entry = format!("{}: {}\n\n", role.to_uppercase(), content);
-        if total_chars + entry.len() > max_chars && !result.is_empty() {
-            break;
+        // Add to history
+        self.history.push(ConversationEntry::User {
+            content: result.1.clone(),
+            timestamp: Some(trace.started_at).filter(|t| !t.is_empty()),
+        });
+        self.history.extend(trace.tools);
+        self.history.push(ConversationEntry::Assistant {
+            content: result.2.output.clone(),
+            timestamp: Some(now_string()),
+            cost_cents: result.2.cost_cents,
+            model: result.2.model_used.clone(),
+            thinking: trace.thinking,
+        });
+        self.persist();
+
+        // Log warning if deliverables are missing and task ended
+        if !self.explicitly_completed && !self.deliverables.deliverables.is_empty() {
+            let missing = self
+                .deliverables
+                .missing_descriptions(&self.assistant_output())
+                .await;
+            if !missing.is_empty() {
+                tracing::warn!(
+                    "Mission {} ended but deliverables are missing: {:?}",
+                    self.mission_id,
+                    missing
+                );
+            }
         }
-        result = format!("{}{}", entry, result);
-        total_chars += entry.len();
     }
-    result
+
+    /// Requeue the current message if the retry policy covers this failure.
+    fn schedule_retry(&mut self, result: &AgentResult) -> Option<PendingRetry> {
+        let policy = self.retry_policy.as_ref()?;
+        let (_, runs) = self.attempts?;
+        let reason = policy.should_retry(result, runs)?;
+        let message = self.current_message.take()?;
+        let delay = policy.backoff(runs);
+        let retry = PendingRetry {
+            retry: runs,
+            reason,
+            delay,
+            due: Instant::now() + delay,
+        };
+        self.queue.push_front(message);
+        self.pending_retry = Some(retry);
+        self.persist();
+        Some(retry)
+    }
+
+    /// Whether a scheduled retry can start now.
+    pub fn retry_due(&self) -> bool {
+        !self.main_turn_active() && self.pending_retry.is_some_and(|r| r.due <= Instant::now())
+    }
+
+    /// Check if the running task is finished (non-blocking).
+    pub fn check_finished(&self) -> bool {
+        self.concurrent_turns
+            .iter()
+            .any(|turn| turn.handle.is_finished())
+            || self
+                .running_handle
+                .as_ref()
+                .map(|h| h.is_finished())
+                .unwrap_or(true)
+    }
 }
 
 /// Try to resolve a library command from a user message starting with `/`.
@@ -1066,7 +1565,8 @@ async fn run_mission_turn(
     tool_hub: Arc<FrontendToolHub>,
     status: Arc<RwLock<ControlStatus>>,
     cancel: CancellationToken,
-    history: Vec<(String, String)>,
+    history: Vec<ConversationEntry>,
+    history_summary: SharedHistorySummary,
     user_message: String,
     _mission_control: Option<crate::tools::mission::MissionControl>,
     _tree_snapshot: Arc<RwLock<Option<AgentTreeNode>>>,
@@ -1079,11 +1579,24 @@ async fn run_mission_turn(
     session_id: Option<String>,
     mission_config_profile: Option<String>,
 ) -> AgentResult {
+    // A paused mission doesn't start new turns.
+    super::mission_pause::wait_while_paused(mission_id, &cancel).await;
+    if cancel.is_cancelled() {
+        return AgentResult::failure("Cancelled".to_string(), 0)
+            .with_terminal_reason(TerminalReason::Cancelled);
+    }
+    let _turn_timer = super::metrics::global().turn_timer(&backend_id);
     let mut config = config;
-    let effective_agent = agent_override.clone();
+    let backend_defaults = get_backend_defaults_from_config(&backend_id);
+    let effective_agent = agent_override.clone().or(backend_defaults.agent);
     if let Some(ref agent) = effective_agent {
         config.opencode_agent = Some(agent.clone());
     }
+    // The backend's own default model beats the global one, which may not be
+    // valid for this backend.
+    if let Some(model) = backend_defaults.model {
+        config.default_model = Some(model);
+    }
     // Get config profile: mission's config_profile takes priority over workspace's
     let workspace_config_profile = if let Some(ws_id) = workspace_id {
         workspaces.get(ws_id).await.and_then(|ws| ws.config_profile)
@@ -1116,9 +1629,9 @@ async fn run_mission_turn(
     // Resolve library commands (e.g., /bugbot-review → expanded command content)
     let user_message = resolve_library_command(&library, &user_message).await;
 
-    // Build context with history
-    let max_history_chars = config.context.max_history_total_chars;
-    let history_context = build_history_context(&history, max_history_chars);
+    // Build context with history, summarizing what no longer fits
+    let history_context =
+        mission_history::history_context(&history, Some(&history_summary), &config.context).await;
 
     // Extract deliverables to include in instructions
     let deliverable_set = extract_deliverables(&user_message);
@@ -1184,7 +1697,7 @@ async fn run_mission_turn(
     let mission_work_dir = match {
         let lib_guard = library.read().await;
         let lib_ref = lib_guard.as_ref().map(|l| l.as_ref());
-        workspace::prepare_mission_workspace_with_skills_backend(
+        let prepared = workspace::prepare_mission_workspace_with_skills_backend(
             &workspace,
             &mcp,
             lib_ref,
@@ -1193,7 +1706,20 @@ async fn run_mission_turn(
             None, // custom_providers: TODO integrate with provider store
             effective_config_profile.as_deref(),
         )
-        .await
+        .await;
+        // Surface missing skill dependencies now rather than mid-mission.
+        if let Some(lib) = lib_ref {
+            for message in workspace::check_skill_requirements(&workspace, &mcp, lib).await {
+                tracing::warn!(mission_id = %mission_id, "{}", message);
+                let _ = events_tx.send(AgentEvent::Diagnostic {
+                    level: DiagnosticLevel::Warn,
+                    source: "library".to_string(),
+                    message,
+                    mission_id: Some(mission_id),
+                });
+            }
+        }
+        prepared
     } {
         Ok(dir) => {
             tracing::info!(
@@ -1213,7 +1739,7 @@ async fn run_mission_turn(
     // For Claude Code, check if this is a continuation turn (has prior assistant response).
     // Note: history may include the current user message before the turn runs,
     // so we check for assistant messages to determine if this is truly a continuation.
-    let is_continuation = history.iter().any(|(role, _)| role == "assistant");
+    let is_continuation = history.iter().any(ConversationEntry::is_assistant);
     let result = match backend_id.as_str() {
         "claudecode" => {
             run_claudecode_turn(
@@ -1251,7 +1777,7 @@ async fn run_mission_turn(
             .await
         }
         "amp" => {
-            let api_key = get_amp_api_key_from_config();
+            let api_key = resolve_amp_api_key(secrets.as_deref()).await;
             run_amp_turn(
                 &workspace,
                 &mission_work_dir,
@@ -1359,6 +1885,61 @@ fn read_backend_configs() -> Option<Vec<serde_json::Value>> {
     None
 }
 
+/// Forward a backend CLI stderr line to the UI as a diagnostic.
+fn emit_cli_diagnostic(
+    events_tx: &broadcast::Sender<AgentEvent>,
+    source: &str,
+    line: &str,
+    mission_id: Uuid,
+) {
+    let _ = events_tx.send(AgentEvent::Diagnostic {
+        level: DiagnosticLevel::classify(line),
+        source: source.to_string(),
+        message: line.to_string(),
+        mission_id: Some(mission_id),
+    });
+}
+
+/// Per-backend defaults from `backend_configs.json`, applied when a mission
+/// doesn't override the agent or model.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct BackendDefaults {
+    pub agent: Option<String>,
+    pub model: Option<String>,
+}
+
+fn backend_defaults_from_configs(
+    configs: &[serde_json::Value],
+    backend_id: &str,
+) -> BackendDefaults {
+    let Some(settings) = configs
+        .iter()
+        .find(|c| c.get("id").and_then(|v| v.as_str()) == Some(backend_id))
+        .and_then(|c| c.get("settings"))
+    else {
+        return BackendDefaults::default();
+    };
+    let read = |key: &str| {
+        settings
+            .get(key)
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string())
+    };
+    BackendDefaults {
+        agent: read("default_agent"),
+        model: read("default_model"),
+    }
+}
+
+/// Read the default agent and model configured for a backend.
+pub(crate) fn get_backend_defaults_from_config(backend_id: &str) -> BackendDefaults {
+    read_backend_configs()
+        .map(|configs| backend_defaults_from_configs(&configs, backend_id))
+        .unwrap_or_default()
+}
+
 /// Read CLI path from backend config file if available.
 fn get_claudecode_cli_path_from_config(_app_working_dir: &std::path::Path) -> Option<String> {
     let configs = read_backend_configs()?;
@@ -1422,6 +2003,15 @@ pub fn get_amp_api_key_from_config() -> Option<String> {
     None
 }
 
+/// Resolve the Amp credential for a turn: a configured API key wins, otherwise
+/// the login token stored via `POST /api/backends/amp/login` (refreshed if needed).
+pub async fn resolve_amp_api_key(secrets: Option<&SecretsStore>) -> Option<String> {
+    if let Some(key) = get_amp_api_key_from_config() {
+        return Some(key);
+    }
+    crate::backend::amp::auth::resolve_access_token(secrets?).await
+}
+
 /// Read amp.url from Amp CLI settings file (~/.config/amp/settings.json)
 fn get_amp_url_from_settings() -> Option<String> {
     let home = std::env::var("HOME").ok()?;
@@ -1440,6 +2030,19 @@ fn get_amp_url_from_settings() -> Option<String> {
         .map(|s| s.to_string())
 }
 
+/// Write a user message to a Claude CLI started with `--input-format stream-json`.
+fn write_claude_user_message(
+    writer: &mut dyn std::io::Write,
+    content: &str,
+) -> std::io::Result<()> {
+    let message = serde_json::json!({
+        "type": "user",
+        "message": { "role": "user", "content": [{ "type": "text", "text": content }] },
+    });
+    writeln!(writer, "{}", message)?;
+    writer.flush()
+}
+
 /// Execute a turn using Claude Code CLI backend.
 ///
 /// For Host workspaces: spawns the CLI directly on the host.
@@ -1845,6 +2448,8 @@ pub fn run_claudecode_turn<'a>(
             "--print".to_string(),
             "--output-format".to_string(),
             "stream-json".to_string(),
+            "--input-format".to_string(),
+            "stream-json".to_string(),
             "--verbose".to_string(),
             "--include-partial-messages".to_string(),
         ];
@@ -1891,13 +2496,14 @@ pub fn run_claudecode_turn<'a>(
         // Important: We use a marker file to track if the session was ever initiated.
         // This prevents "Session ID already in use" errors when a turn is cancelled
         // after the session is created but before any assistant response is recorded.
-        // The marker file contains the session ID to prevent cross-mission interference
-        // when workspaces are shared (e.g., fallback to workspace-wide directory).
+        // The marker file lists the session IDs started here (one per line) to prevent
+        // cross-mission interference when workspaces are shared (e.g., fallback to
+        // workspace-wide directory) and to keep concurrent turns' sessions apart.
         let session_marker = work_dir.join(".claude-session-initiated");
-        let session_was_initiated = session_marker.exists()
-            && std::fs::read_to_string(&session_marker)
-                .map(|content| content.trim() == session_id)
-                .unwrap_or(false);
+        let initiated_sessions = std::fs::read_to_string(&session_marker).unwrap_or_default();
+        let session_was_initiated = initiated_sessions
+            .lines()
+            .any(|line| line.trim() == session_id);
 
         // Determine if we should use --resume:
         // We can only resume if the session was actually initiated at THIS work_dir
@@ -1926,7 +2532,12 @@ pub fn run_claudecode_turn<'a>(
             );
         } else {
             // Create the marker file BEFORE starting the CLI to prevent races
-            if let Err(e) = std::fs::write(&session_marker, &session_id) {
+            let mut marker = initiated_sessions.trim_end().to_string();
+            if !marker.is_empty() {
+                marker.push('\n');
+            }
+            marker.push_str(&session_id);
+            if let Err(e) = std::fs::write(&session_marker, marker) {
                 tracing::warn!(
                     mission_id = %mission_id,
                     error = %e,
@@ -1955,14 +2566,6 @@ pub fn run_claudecode_turn<'a>(
             }
         }
 
-        // Provide the prompt as a positional argument (instead of stdin).
-        //
-        // In production we have observed cases where piping stdin from the backend results in
-        // Claude Code producing no stdout events (even though it creates the session files),
-        // leaving missions stuck "Agent is working..." indefinitely.
-        args.push("--".to_string());
-        args.push(effective_message.clone());
-
         // Build environment variables
         let mut env: HashMap<String, String> = HashMap::new();
         // Allow --dangerously-skip-permissions when running as root inside containers.
@@ -2116,11 +2719,15 @@ pub fn run_claudecode_turn<'a>(
         // Claude Code 2.1.x can hang indefinitely when stdout is a pipe (non-tty),
         // even in `--print --output-format stream-json` mode. Running it under a PTY
         // fixes this and restores streaming.
+        let spawn_started = Instant::now();
         let mut pty = match workspace_exec
             .spawn_streaming_pty(work_dir, &program, &full_args, env)
             .await
         {
-            Ok(child) => child,
+            Ok(child) => {
+                super::metrics::global().observe_spawn("claudecode", spawn_started.elapsed());
+                child
+            }
             Err(e) => {
                 let err_msg = format!("Failed to start Claude CLI: {}", e);
                 tracing::error!("{}", err_msg);
@@ -2128,12 +2735,33 @@ pub fn run_claudecode_turn<'a>(
                     .with_terminal_reason(TerminalReason::LlmError);
             }
         };
+        let _process_guard = crate::process_registry::track(pty.pid(), mission_id, &program);
 
-        // Keep stdin open - dropping the writer (closing stdin) can cause some Claude CLI
-        // agent modes to hang. We pass the prompt via argv so stdin is not needed, but the
-        // CLI may check if stdin is open during initialization.
-        let _stdin_writer = pty.take_writer();
-        tracing::debug!(mission_id = %mission_id, "PTY writer taken (kept alive)");
+        // With `--input-format stream-json` the CLI reads user messages from stdin:
+        // the prompt, then any steering messages sent while the turn runs. Stdin
+        // stays open until the result comes in. Raw input keeps the terminal from
+        // echoing the messages back or cutting long ones at its line limit.
+        if let Err(e) = pty.set_raw_input() {
+            tracing::warn!(mission_id = %mission_id, error = %e, "Failed to set raw PTY input");
+        }
+        let mut writer = match pty.take_writer() {
+            Ok(writer) => writer,
+            Err(e) => {
+                pty.kill();
+                let err_msg = format!("Failed to open Claude PTY input: {}", e);
+                tracing::error!("{}", err_msg);
+                return AgentResult::failure(err_msg, 0)
+                    .with_terminal_reason(TerminalReason::LlmError);
+            }
+        };
+        if let Err(e) = write_claude_user_message(&mut writer, &effective_message) {
+            pty.kill();
+            let err_msg = format!("Failed to send the prompt to Claude CLI: {}", e);
+            tracing::error!("{}", err_msg);
+            return AgentResult::failure(err_msg, 0).with_terminal_reason(TerminalReason::LlmError);
+        }
+        let mut stdin_writer = Some(writer);
+        let mut steer_rx = Some(super::mission_steer::register(mission_id));
 
         let reader = match pty.try_clone_reader() {
             Ok(r) => {
@@ -2149,7 +2777,9 @@ pub fn run_claudecode_turn<'a>(
             }
         };
 
-        let (line_tx, mut line_rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+        // Bounded so that a paused mission stops draining the PTY and the CLI
+        // blocks on its writes instead of running on.
+        let (line_tx, mut line_rx) = tokio::sync::mpsc::channel::<String>(256);
         let reader_mission_id = mission_id.to_string();
         let reader_handle = tokio::task::spawn_blocking(move || {
             use std::io::BufRead;
@@ -2179,7 +2809,7 @@ pub fn run_claudecode_turn<'a>(
                             );
                         }
                         let s = String::from_utf8_lossy(&buf).to_string();
-                        if line_tx.send(s).is_err() {
+                        if line_tx.blocking_send(s).is_err() {
                             tracing::debug!(
                                 mission_id = %reader_mission_id,
                                 "PTY reader: channel closed"
@@ -2205,6 +2835,8 @@ pub fn run_claudecode_turn<'a>(
         // Track tool calls for result mapping
         let mut pending_tools: HashMap<String, String> = HashMap::new();
         let mut total_cost_usd = 0.0f64;
+        // Cost of each assistant message so far, for stopping at the budget
+        let mut message_costs: HashMap<String, u64> = HashMap::new();
         let mut final_result = String::new();
         let mut had_error = false;
 
@@ -2230,11 +2862,16 @@ pub fn run_claudecode_turn<'a>(
                 .and_then(|v| v.parse::<u64>().ok())
                 .unwrap_or(600),
         );
-        let startup_deadline = Instant::now() + startup_timeout;
+        let mut startup_deadline = Instant::now() + startup_timeout;
         let mut idle_deadline = Instant::now() + idle_timeout;
 
         // Process events until completion or cancellation
         loop {
+            if super::mission_pause::wait_while_paused(mission_id, &cancel).await {
+                // Time spent paused doesn't count against the timeouts.
+                startup_deadline = Instant::now() + startup_timeout;
+                idle_deadline = Instant::now() + idle_timeout;
+            }
             tokio::select! {
                 _ = cancel.cancelled() => {
                     tracing::info!(mission_id = %mission_id, "Claude Code execution cancelled, killing process");
@@ -2267,12 +2904,28 @@ pub fn run_claudecode_turn<'a>(
                 _ = tokio::time::sleep_until(idle_deadline), if saw_non_init_event => {
                     pty.kill();
                     reader_handle.abort();
+                    if stdin_writer.is_none() {
+                        // The result is in; the CLI just didn't exit
+                        break;
+                    }
                     return AgentResult::failure(
                         "Claude Code produced no output for an extended period and was terminated (idle timeout).".to_string(),
                         0,
                     )
                     .with_terminal_reason(TerminalReason::LlmError);
                 }
+                Some(content) = next_steer(&mut steer_rx) => {
+                    if let Some(writer) = stdin_writer.as_mut() {
+                        if let Err(e) = write_claude_user_message(writer, &content) {
+                            tracing::warn!(mission_id = %mission_id, error = %e, "Failed to deliver steering message");
+                            let _ = events_tx.send(AgentEvent::Error {
+                                message: format!("Steering message not delivered: {}", e),
+                                mission_id: Some(mission_id),
+                                resumable: false,
+                            });
+                        }
+                    }
+                }
                 line_opt = line_rx.recv() => {
                     let Some(raw_line) = line_opt else {
                         // EOF - PTY closed
@@ -2292,6 +2945,8 @@ pub fn run_claudecode_turn<'a>(
                     }
 
                     if !line.starts_with('{') {
+                        // The PTY merges stderr into stdout.
+                        emit_cli_diagnostic(&events_tx, "claudecode", line, mission_id);
                         // Preserve a small excerpt for diagnostics on "no output" failures.
                         if non_json_output.len() < 20 {
                             non_json_output.push(if line.len() > 200 {
@@ -2413,6 +3068,30 @@ pub fn run_claudecode_turn<'a>(
                                     }
                                 }
                                 ClaudeEvent::Assistant(evt) => {
+                                    // Stop the turn as soon as its streamed cost reaches the budget
+                                    if let (Some(id), Some(usage), Some(model)) = (
+                                        &evt.message.id,
+                                        &evt.message.usage,
+                                        evt.message.model.as_deref().or(model),
+                                    ) {
+                                        let usage = token_usage(
+                                            usage.input_tokens.unwrap_or(0),
+                                            usage.output_tokens.unwrap_or(0),
+                                            usage.cache_creation_input_tokens.unwrap_or(0),
+                                            usage.cache_read_input_tokens.unwrap_or(0),
+                                        );
+                                        message_costs.insert(
+                                            id.clone(),
+                                            crate::cost::cost_cents_from_usage(model, &usage),
+                                        );
+                                        if super::mission_budget::report_turn_cost(
+                                            mission_id,
+                                            message_costs.values().sum(),
+                                            &cancel,
+                                        ) {
+                                            tracing::warn!(mission_id = %mission_id, "Claude Code turn reached the mission budget, stopping");
+                                        }
+                                    }
                                     for block in evt.message.content {
                                         match block {
                                             ContentBlock::Text { text } => {
@@ -2449,7 +3128,7 @@ pub fn run_claudecode_turn<'a>(
                                                     mission_id: Some(mission_id),
                                                 });
 
-                                                if name == "question" || name.starts_with("ui_") {
+                                                if crate::tools::is_frontend_tool(&name) {
                                                     if let Some(ref hub) = tool_hub {
                                                         tracing::info!(
                                                             mission_id = %mission_id,
@@ -2467,7 +3146,7 @@ pub fn run_claudecode_turn<'a>(
                                                             )
                                                             .await;
                                                         }
-                                                        let rx = hub.register(id.clone()).await;
+                                                        let rx = hub.register(id.clone(), &name, &input).await;
 
                                                         pty.kill();
                                                         reader_handle.abort();
@@ -2482,7 +3161,7 @@ pub fn run_claudecode_turn<'a>(
                                                                     Ok(v) => v,
                                                                     Err(_) => {
                                                                         return AgentResult::failure(
-                                                                            "Frontend tool result channel closed".to_string(), 0
+                                                                            "Frontend tool was not answered".to_string(), 0
                                                                         ).with_terminal_reason(TerminalReason::LlmError);
                                                                     }
                                                                 }
@@ -2608,7 +3287,22 @@ pub fn run_claudecode_turn<'a>(
                                         cost_usd = total_cost_usd,
                                         "Claude Code execution completed"
                                     );
-                                    break;
+                                    // Close stdin so the CLI exits. Steering messages it
+                                    // already got are answered first, and the output is
+                                    // read until the PTY closes.
+                                    if let Some(mut writer) = stdin_writer.take() {
+                                        if let Some(mut rx) = steer_rx.take() {
+                                            while let Some(content) = rx.try_recv() {
+                                                if let Err(e) = write_claude_user_message(&mut writer, &content) {
+                                                    tracing::warn!(mission_id = %mission_id, error = %e, "Failed to deliver steering message");
+                                                }
+                                            }
+                                        }
+                                        if let Err(e) = pty.end_input(&mut writer) {
+                                            tracing::warn!(mission_id = %mission_id, error = %e, "Failed to close Claude CLI input");
+                                            pty.kill();
+                                        }
+                                    }
                                 }
                             }
                 }
@@ -5528,6 +6222,95 @@ async fn cleanup_opencode_listeners(
         .await;
 }
 
+/// Pick the OpenCode CLI runner for a workspace: the configured path, else
+/// `bunx`, else `npx`. Returns the runner and whether it is the
+/// `oh-my-opencode` binary itself (rather than a package runner).
+async fn resolve_opencode_cli_runner(
+    workspace_exec: &WorkspaceExec,
+    work_dir: &std::path::Path,
+    app_working_dir: &std::path::Path,
+) -> Result<(String, bool), String> {
+    let configured_runner = get_opencode_cli_path_from_config(app_working_dir)
+        .or_else(|| std::env::var("OPENCODE_CLI_PATH").ok());
+
+    if let Some(path) = configured_runner {
+        if command_available(workspace_exec, work_dir, &path).await {
+            let is_direct = runner_is_oh_my_opencode(&path);
+            return Ok((path, is_direct));
+        }
+        return Err(format!(
+            "OpenCode CLI runner '{}' not found in workspace. Install it or update OPENCODE_CLI_PATH.",
+            path
+        ));
+    }
+
+    // Prefer bunx for oh-my-opencode (avoids version conflicts from npm global installs)
+    if command_available(workspace_exec, work_dir, "bunx").await {
+        Ok(("bunx".to_string(), false))
+    } else if command_available(workspace_exec, work_dir, "npx").await {
+        Ok(("npx".to_string(), false))
+    } else {
+        Err("No OpenCode CLI runner found in workspace (expected bunx or npx).".to_string())
+    }
+}
+
+/// Result of pre-caching the OpenCode CLI in a workspace.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct OpencodeWarmup {
+    /// Runner used (`bunx`, `npx` or a configured CLI path)
+    pub runner: String,
+    /// `oh-my-opencode --version` output, if it ran successfully
+    pub version: Option<String>,
+    /// Whether the package was already cached before the warm-up
+    pub already_cached: bool,
+    pub duration_ms: u64,
+}
+
+/// Run the `bunx oh-my-opencode --version` pre-cache step ahead of the first
+/// mission so it doesn't pay the package download on its first turn.
+pub async fn warm_up_opencode(
+    workspace: &Workspace,
+    app_working_dir: &std::path::Path,
+) -> Result<OpencodeWarmup, String> {
+    let started = std::time::Instant::now();
+    let workspace_exec = WorkspaceExec::new(workspace.clone());
+    let work_dir = workspace.path.as_path();
+    ensure_opencode_cli_available(&workspace_exec, work_dir).await?;
+
+    let (runner, runner_is_direct) =
+        resolve_opencode_cli_runner(&workspace_exec, work_dir, app_working_dir).await?;
+    let already_cached = runner_is_direct || find_oh_my_opencode_cli_js(workspace).is_some();
+
+    let args = if runner_is_direct {
+        vec!["--version".to_string()]
+    } else {
+        vec!["oh-my-opencode".to_string(), "--version".to_string()]
+    };
+    let output = workspace_exec
+        .output(work_dir, &runner, &args, HashMap::new())
+        .await
+        .map_err(|e| format!("Failed to run {} oh-my-opencode --version: {}", runner, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} oh-my-opencode --version failed: {}",
+            runner,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let version = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .rfind(|line| !line.is_empty())
+        .map(|line| line.to_string());
+
+    Ok(OpencodeWarmup {
+        runner,
+        version,
+        already_cached,
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
 async fn ensure_opencode_cli_available(
     workspace_exec: &WorkspaceExec,
     cwd: &std::path::Path,
@@ -5596,6 +6379,55 @@ async fn ensure_opencode_cli_available(
     Ok(())
 }
 
+/// Next steering message for the running turn; never resolves when the
+/// turn doesn't take them.
+async fn next_steer(rx: &mut Option<super::mission_steer::SteerReceiver>) -> Option<String> {
+    match rx {
+        Some(rx) => rx.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Post a steering message to a running OpenCode session. The server queues
+/// it into the session's current run.
+async fn post_opencode_steer(
+    workspace_exec: &WorkspaceExec,
+    work_dir: &std::path::Path,
+    base_url: &str,
+    session_id: &str,
+    directory: &str,
+    content: &str,
+) -> anyhow::Result<()> {
+    let url = format!(
+        "{}/session/{}/prompt_async?directory={}",
+        base_url,
+        session_id,
+        urlencoding::encode(directory)
+    );
+    let body = serde_json::json!({ "parts": [{ "type": "text", "text": content }] });
+    let args = vec![
+        "-sS".to_string(),
+        "-f".to_string(),
+        "-X".to_string(),
+        "POST".to_string(),
+        "-H".to_string(),
+        "Content-Type: application/json".to_string(),
+        "--data-binary".to_string(),
+        body.to_string(),
+        url,
+    ];
+    let output = workspace_exec
+        .output(work_dir, "curl", &args, HashMap::new())
+        .await?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "OpenCode rejected the message: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
 /// Execute a turn using OpenCode CLI backend.
 ///
 /// For Host workspaces: spawns the CLI directly on the host.
@@ -5724,35 +6556,15 @@ pub async fn run_opencode_turn(
         return AgentResult::failure(err_msg, 0).with_terminal_reason(TerminalReason::LlmError);
     }
 
-    let configured_runner = get_opencode_cli_path_from_config(app_working_dir)
-        .or_else(|| std::env::var("OPENCODE_CLI_PATH").ok());
-
-    let mut runner_is_direct = false;
-    let cli_runner = if let Some(path) = configured_runner {
-        if command_available(&workspace_exec, work_dir, &path).await {
-            runner_is_direct = runner_is_oh_my_opencode(&path);
-            path
-        } else {
-            let err_msg = format!(
-                "OpenCode CLI runner '{}' not found in workspace. Install it or update OPENCODE_CLI_PATH.",
-                path
-            );
-            tracing::error!("{}", err_msg);
-            return AgentResult::failure(err_msg, 0).with_terminal_reason(TerminalReason::LlmError);
-        }
-    } else {
-        // Prefer bunx for oh-my-opencode (avoids version conflicts from npm global installs)
-        if command_available(&workspace_exec, work_dir, "bunx").await {
-            "bunx".to_string()
-        } else if command_available(&workspace_exec, work_dir, "npx").await {
-            "npx".to_string()
-        } else {
-            let err_msg =
-                "No OpenCode CLI runner found in workspace (expected bunx or npx).".to_string();
-            tracing::error!("{}", err_msg);
-            return AgentResult::failure(err_msg, 0).with_terminal_reason(TerminalReason::LlmError);
-        }
-    };
+    let (cli_runner, runner_is_direct) =
+        match resolve_opencode_cli_runner(&workspace_exec, work_dir, app_working_dir).await {
+            Ok(runner) => runner,
+            Err(err_msg) => {
+                tracing::error!("{}", err_msg);
+                return AgentResult::failure(err_msg, 0)
+                    .with_terminal_reason(TerminalReason::LlmError);
+            }
+        };
 
     // Proactive network connectivity check - fail fast if API is unreachable
     // This catches DNS/network issues immediately instead of waiting for a timeout
@@ -5988,17 +6800,22 @@ pub async fn run_opencode_turn(
     cleanup_opencode_listeners(&workspace_exec, work_dir, Some(&opencode_port)).await;
 
     // Use WorkspaceExec to spawn the CLI in the correct workspace context
+    let spawn_started = Instant::now();
     let mut child = match workspace_exec
         .spawn_streaming(work_dir, &cli_runner, &args, env)
         .await
     {
-        Ok(child) => child,
+        Ok(child) => {
+            super::metrics::global().observe_spawn("opencode", spawn_started.elapsed());
+            child
+        }
         Err(e) => {
             let err_msg = format!("Failed to start OpenCode CLI: {}", e);
             tracing::error!("{}", err_msg);
             return AgentResult::failure(err_msg, 0).with_terminal_reason(TerminalReason::LlmError);
         }
     };
+    let _process_guard = crate::process_registry::track(child.id(), mission_id, &cli_runner);
 
     // Get stdout and stderr for reading output
     // oh-my-opencode run writes:
@@ -6025,6 +6842,11 @@ pub async fn run_opencode_turn(
     let sse_error_message: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
     let sse_cancel = CancellationToken::new();
 
+    let opencode_host = std::env::var("SANDBOXED_SH_OPENCODE_SERVER_HOSTNAME")
+        .ok()
+        .filter(|v| !v.trim().is_empty())
+        .unwrap_or_else(|| "127.0.0.1".to_string());
+
     // oh-my-opencode doesn't support --format json, so use SSE curl for events.
     let use_json_stdout = false;
     let sse_handle = if !use_json_stdout
@@ -6042,10 +6864,7 @@ pub async fn run_opencode_turn(
         let events_tx = events_tx.clone();
         let opencode_port = opencode_port.clone();
         let mission_id = mission_id;
-        let sse_host = std::env::var("SANDBOXED_SH_OPENCODE_SERVER_HOSTNAME")
-            .ok()
-            .filter(|v| !v.trim().is_empty())
-            .unwrap_or_else(|| "127.0.0.1".to_string());
+        let sse_host = opencode_host.clone();
 
         Some(tokio::spawn(async move {
             let event_url = format!(
@@ -6108,6 +6927,7 @@ pub async fn run_opencode_turn(
                         let _ = child.kill().await;
                         return;
                     }
+                    super::mission_pause::wait_while_paused(mission_id, &sse_cancel).await;
                     line.clear();
                     match reader.read_line(&mut line).await {
                         Ok(0) => break,
@@ -6208,6 +7028,7 @@ pub async fn run_opencode_turn(
     // Spawn a task to read stderr (just log in JSON mode, events come on stdout)
     let mission_id_clone = mission_id;
     let stderr_error_capture = sse_error_message.clone();
+    let stderr_events_tx = events_tx.clone();
     let stderr_handle = if let Some(stderr) = stderr {
         Some(tokio::spawn(async move {
             let stderr_reader = BufReader::new(stderr);
@@ -6216,6 +7037,7 @@ pub async fn run_opencode_turn(
                 let clean = line.trim().to_string();
                 if !clean.is_empty() {
                     tracing::debug!(mission_id = %mission_id_clone, line = %clean, "OpenCode CLI stderr");
+                    emit_cli_diagnostic(&stderr_events_tx, "opencode", &clean, mission_id_clone);
 
                     // Detect session errors from stderr
                     let lower = clean.to_lowercase();
@@ -6238,6 +7060,12 @@ pub async fn run_opencode_turn(
         None
     };
 
+    // Steering messages are posted to the running session through the
+    // server's API, which needs curl like the event stream.
+    let mut steer_rx = sse_handle
+        .is_some()
+        .then(|| super::mission_steer::register(mission_id));
+
     // Process stdout output from oh-my-opencode
     // Events come via SSE (when curl is available), stdout contains the assistant's text response.
     let stdout_reader = BufReader::new(stdout);
@@ -6245,10 +7073,11 @@ pub async fn run_opencode_turn(
     let mut state = OpencodeSseState::default();
 
     loop {
+        super::mission_pause::wait_while_paused(mission_id, &cancel).await;
         tokio::select! {
             _ = cancel.cancelled() => {
                 tracing::info!(mission_id = %mission_id, "OpenCode execution cancelled, killing process");
-                let _ = child.kill().await;
+                crate::workspace_exec::kill_process_tree(&mut child).await;
                 if let Some(handle) = stderr_handle {
                     handle.abort();
                 }
@@ -6259,6 +7088,41 @@ pub async fn run_opencode_turn(
                 return AgentResult::failure("Cancelled".to_string(), 0)
                     .with_terminal_reason(TerminalReason::Cancelled);
             }
+            Some(content) = next_steer(&mut steer_rx) => {
+                let session_id = session_id_capture.lock().unwrap().clone();
+                let Some(session_id) = session_id else {
+                    let _ = events_tx.send(AgentEvent::Error {
+                        message: "Steering message not delivered: the OpenCode session hasn't started yet".to_string(),
+                        mission_id: Some(mission_id),
+                        resumable: false,
+                    });
+                    continue;
+                };
+                let workspace_exec = workspace_exec.clone();
+                let work_dir = work_dir.to_path_buf();
+                let base_url = format!("http://{}:{}", opencode_host, opencode_port);
+                let directory = work_dir_arg.clone();
+                let events_tx = events_tx.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = post_opencode_steer(
+                        &workspace_exec,
+                        &work_dir,
+                        &base_url,
+                        &session_id,
+                        &directory,
+                        &content,
+                    )
+                    .await
+                    {
+                        tracing::warn!(mission_id = %mission_id, error = %e, "Failed to deliver steering message");
+                        let _ = events_tx.send(AgentEvent::Error {
+                            message: format!("Steering message not delivered: {}", e),
+                            mission_id: Some(mission_id),
+                            resumable: false,
+                        });
+                    }
+                });
+            }
             line_result = stdout_lines.next_line() => {
                 match line_result {
                     Ok(None) => {
@@ -6769,17 +7633,22 @@ pub async fn run_amp_turn(
     );
 
     // Use WorkspaceExec to spawn the CLI
+    let spawn_started = Instant::now();
     let mut child = match workspace_exec
         .spawn_streaming(work_dir, &amp_binary, &args, env)
         .await
     {
-        Ok(child) => child,
+        Ok(child) => {
+            super::metrics::global().observe_spawn("amp", spawn_started.elapsed());
+            child
+        }
         Err(e) => {
             let err_msg = format!("Failed to start Amp CLI: {}", e);
             tracing::error!("{}", err_msg);
             return AgentResult::failure(err_msg, 0).with_terminal_reason(TerminalReason::LlmError);
         }
     };
+    let _process_guard = crate::process_registry::track(child.id(), mission_id, &amp_binary);
 
     // Close stdin immediately - Amp uses --execute with args, not stdin
     // Leaving the pipe open can cause issues with Node.js process lifecycle
@@ -6801,6 +7670,7 @@ pub async fn run_amp_turn(
     let stderr_capture = std::sync::Arc::new(tokio::sync::Mutex::new(String::new()));
     let stderr_capture_clone = stderr_capture.clone();
     let mission_id_for_stderr = mission_id;
+    let stderr_events_tx = events_tx.clone();
     let stderr_handle = if let Some(stderr) = stderr {
         Some(tokio::spawn(async move {
             let stderr_reader = BufReader::new(stderr);
@@ -6809,6 +7679,7 @@ pub async fn run_amp_turn(
                 let trimmed = line.trim();
                 if !trimmed.is_empty() {
                     tracing::debug!(mission_id = %mission_id_for_stderr, stderr = %trimmed, "Amp CLI stderr");
+                    emit_cli_diagnostic(&stderr_events_tx, "amp", trimmed, mission_id_for_stderr);
                     let mut captured = stderr_capture_clone.lock().await;
                     if !captured.is_empty() {
                         captured.push('\n');
@@ -6846,10 +7717,11 @@ pub async fn run_amp_turn(
 
     // Process events until completion or cancellation
     loop {
+        super::mission_pause::wait_while_paused(mission_id, &cancel).await;
         tokio::select! {
             _ = cancel.cancelled() => {
                 tracing::info!(mission_id = %mission_id, "Amp execution cancelled, killing process");
-                let _ = child.kill().await;
+                crate::workspace_exec::kill_process_tree(&mut child).await;
                 if let Some(handle) = stderr_handle {
                     handle.abort();
                 }
@@ -6971,6 +7843,23 @@ pub async fn run_amp_turn(
                                     total_output_tokens += usage.output_tokens.unwrap_or(0);
                                     total_cache_creation_tokens += usage.cache_creation_input_tokens.unwrap_or(0);
                                     total_cache_read_tokens += usage.cache_read_input_tokens.unwrap_or(0);
+
+                                    // Stop the turn as soon as its streamed cost reaches the budget
+                                    if let Some(model) = model_used.as_deref() {
+                                        let usage = token_usage(
+                                            total_input_tokens,
+                                            total_output_tokens,
+                                            total_cache_creation_tokens,
+                                            total_cache_read_tokens,
+                                        );
+                                        if super::mission_budget::report_turn_cost(
+                                            mission_id,
+                                            crate::cost::cost_cents_from_usage(model, &usage),
+                                            &cancel,
+                                        ) {
+                                            tracing::warn!(mission_id = %mission_id, "Amp turn reached the mission budget, stopping");
+                                        }
+                                    }
                                 }
 
                                 for block in evt.message.content {
@@ -7119,20 +8008,12 @@ pub async fn run_amp_turn(
     }
 
     // Compute cost from accumulated token usage
-    let usage = crate::cost::TokenUsage {
-        input_tokens: total_input_tokens,
-        output_tokens: total_output_tokens,
-        cache_creation_input_tokens: if total_cache_creation_tokens > 0 {
-            Some(total_cache_creation_tokens)
-        } else {
-            None
-        },
-        cache_read_input_tokens: if total_cache_read_tokens > 0 {
-            Some(total_cache_read_tokens)
-        } else {
-            None
-        },
-    };
+    let usage = token_usage(
+        total_input_tokens,
+        total_output_tokens,
+        total_cache_creation_tokens,
+        total_cache_read_tokens,
+    );
     let cost_cents = model_used
         .as_deref()
         .map(|m| crate::cost::cost_cents_from_usage(m, &usage))
@@ -7259,14 +8140,29 @@ pub struct RunningMissionInfo {
     pub subtask_total: usize,
     /// Completed subtasks
     pub subtask_completed: usize,
+    /// Paused through the API; a paused mission is never reported as stalled
+    pub paused: bool,
+    /// The mission's tags
+    #[serde(skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub tags: std::collections::BTreeMap<String, String>,
 }
 
 impl From<&MissionRunner> for RunningMissionInfo {
     fn from(runner: &MissionRunner) -> Self {
         let seconds_since_activity = runner.last_activity.elapsed().as_secs();
+        // Concurrent turns keep the mission running between main turns
+        let state = if runner.is_running() && !runner.main_turn_active() {
+            MissionRunState::Running
+        } else {
+            runner.state
+        };
+        let paused = super::mission_pause::is_paused(runner.mission_id);
         Self {
             mission_id: runner.mission_id,
-            state: match runner.state {
+            state: match state {
+                MissionRunState::Queued if runner.waiting_for_workspace => {
+                    "waiting_for_workspace".to_string()
+                }
                 MissionRunState::Queued => "queued".to_string(),
                 MissionRunState::Running => "running".to_string(),
                 MissionRunState::WaitingForTool => "waiting_for_tool".to_string(),
@@ -7275,11 +8171,17 @@ impl From<&MissionRunner> for RunningMissionInfo {
             queue_len: runner.queue.len(),
             history_len: runner.history.len(),
             seconds_since_activity,
-            health: running_health(runner.state, seconds_since_activity),
+            health: if paused {
+                MissionHealth::Healthy
+            } else {
+                running_health(state, seconds_since_activity, runner.stall_thresholds)
+            },
             expected_deliverables: runner.deliverables.deliverables.len(),
             current_activity: runner.current_activity.clone(),
             subtask_total: runner.subtasks.len(),
             subtask_completed: runner.subtasks.iter().filter(|s| s.completed).count(),
+            paused,
+            tags: Default::default(),
         }
     }
 }
@@ -7354,8 +8256,19 @@ pub async fn run_codex_turn(
     let mut codex_config = crate::backend::codex::client::CodexConfig::default();
     codex_config.cli_path = cli_path;
 
+    // Forward CLI stderr to the UI as diagnostics
+    let (stderr_tx, mut stderr_rx) = mpsc::unbounded_channel::<String>();
+    let stderr_events_tx = events_tx.clone();
+    tokio::spawn(async move {
+        while let Some(line) = stderr_rx.recv().await {
+            emit_cli_diagnostic(&stderr_events_tx, "codex", &line, mission_id);
+        }
+    });
+
     // Create Codex backend
-    let backend = CodexBackend::with_config_and_workspace(codex_config, workspace_exec);
+    let backend = CodexBackend::with_config_and_workspace(codex_config, workspace_exec)
+        .with_mission_id(mission_id)
+        .with_stderr_sink(stderr_tx);
 
     // Create session
     let session = match backend
@@ -7376,9 +8289,13 @@ pub async fn run_codex_turn(
     };
 
     // Send message streaming
+    let spawn_started = Instant::now();
     let (mut event_rx, _handle) = match backend.send_message_streaming(&session, user_message).await
     {
-        Ok(result) => result,
+        Ok(result) => {
+            super::metrics::global().observe_spawn("codex", spawn_started.elapsed());
+            result
+        }
         Err(e) => {
             tracing::error!("Failed to send message to Codex: {}", e);
             return AgentResult::failure(format!("Codex execution failed: {}", e), 0)
@@ -7397,6 +8314,7 @@ pub async fn run_codex_turn(
     let mut last_summary: Option<String> = None;
 
     loop {
+        super::mission_pause::wait_while_paused(mission_id, &cancel).await;
         tokio::select! {
             _ = cancel.cancelled() => {
                 tracing::info!("Codex turn cancelled for mission {}", mission_id);
@@ -7517,9 +8435,41 @@ pub async fn run_codex_turn(
 
 #[cfg(test)]
 mod tests {
-    use super::sync_opencode_agent_config;
+    use super::{backend_defaults_from_configs, sync_opencode_agent_config, BackendDefaults};
     use std::fs;
 
+    #[test]
+    fn backend_defaults_are_read_per_backend() {
+        let configs: Vec<serde_json::Value> = serde_json::from_str(
+            r#"[
+  {"id": "opencode", "name": "OpenCode", "settings": {"default_agent": "Sisyphus", "default_model": "openai/gpt-5"}},
+  {"id": "claudecode", "name": "Claude Code", "settings": {"default_model": "  "}},
+  {"id": "amp", "name": "Amp"}
+]"#,
+        )
+        .expect("parse configs");
+
+        assert_eq!(
+            backend_defaults_from_configs(&configs, "opencode"),
+            BackendDefaults {
+                agent: Some("Sisyphus".to_string()),
+                model: Some("openai/gpt-5".to_string()),
+            }
+        );
+        assert_eq!(
+            backend_defaults_from_configs(&configs, "claudecode"),
+            BackendDefaults::default()
+        );
+        assert_eq!(
+            backend_defaults_from_configs(&configs, "amp"),
+            BackendDefaults::default()
+        );
+        assert_eq!(
+            backend_defaults_from_configs(&configs, "codex"),
+            BackendDefaults::default()
+        );
+    }
+
     #[test]
     fn sync_opencode_agent_config_removes_overrides_when_plugin_enabled() {
         let temp_dir = tempfile::tempdir().expect("temp dir");
@@ -7606,4 +8556,176 @@ mod tests {
         assert_eq!(prometheus_model, "openai/gpt-4o");
         assert_eq!(sisyphus_model, "openai/gpt-4o-mini");
     }
+
+    #[test]
+    fn restored_runner_keeps_queue_and_interrupted_turn() {
+        use super::{ConversationEntry, MissionRunState, MissionRunner, MissionSnapshotStore};
+        use std::sync::Arc;
+        use uuid::Uuid;
+
+        let dir = tempfile::tempdir().unwrap();
+        let snapshots = Arc::new(MissionSnapshotStore::new(dir.path()));
+        let mission_id = Uuid::new_v4();
+        let mut runner = MissionRunner::new(
+            mission_id,
+            Uuid::new_v4(),
+            None,
+            Some("claudecode".to_string()),
+            None,
+            None,
+        )
+        .with_snapshots(Arc::clone(&snapshots));
+        runner.history.push(ConversationEntry::user("Hi"));
+        runner.queue_message(
+            Uuid::new_v4(),
+            "First".to_string(),
+            None,
+            Default::default(),
+        );
+        runner.queue_message(
+            Uuid::new_v4(),
+            "Second".to_string(),
+            None,
+            Default::default(),
+        );
+        // Simulate a turn in flight when the server stopped.
+        runner.current_message = runner.queue.pop_front();
+        runner.state = MissionRunState::Running;
+        snapshots.save(&runner.snapshot());
+
+        let mut loaded = snapshots.load_all();
+        assert_eq!(loaded.len(), 1);
+        let restored = MissionRunner::restore(loaded.remove(0), Arc::clone(&snapshots));
+        assert_eq!(restored.state, MissionRunState::Queued);
+        assert_eq!(restored.backend_id, "claudecode");
+        assert_eq!(restored.queue.len(), 1);
+        assert_eq!(restored.queue[0].content, "Second");
+        assert_eq!(
+            restored.history.last().unwrap().message_text(),
+            Some("First")
+        );
+
+        restored.discard_snapshot();
+        assert!(snapshots.load_all().is_empty());
+    }
+
+    #[test]
+    fn queue_orders_by_priority_then_arrival() {
+        use super::{reorder_queue, MissionPriority, MissionRunner};
+        use uuid::Uuid;
+
+        let mut runner = MissionRunner::new(Uuid::new_v4(), Uuid::new_v4(), None, None, None, None);
+        let ids: Vec<Uuid> = (0..4).map(|_| Uuid::new_v4()).collect();
+        runner.queue_message(ids[0], "a".to_string(), None, MissionPriority::Normal);
+        runner.queue_message(ids[1], "b".to_string(), None, MissionPriority::Low);
+        runner.queue_message(ids[2], "c".to_string(), None, MissionPriority::High);
+        runner.queue_message(ids[3], "d".to_string(), None, MissionPriority::Normal);
+        let order = |runner: &MissionRunner| -> String {
+            runner.queue.iter().map(|qm| qm.content.as_str()).collect()
+        };
+        assert_eq!(order(&runner), "cadb");
+
+        assert!(runner.set_queued_priority(ids[1], MissionPriority::Urgent));
+        assert_eq!(order(&runner), "bcad");
+        assert!(!runner.set_queued_priority(Uuid::new_v4(), MissionPriority::Low));
+
+        assert_eq!(runner.reorder_queue(&[ids[3], Uuid::new_v4(), ids[0]]), 2);
+        assert_eq!(order(&runner), "dabc");
+        assert_eq!(reorder_queue(&mut runner.queue, &[], |qm| qm.id), 0);
+
+        assert!(runner.edit_queued_message(ids[0], "e"));
+        assert_eq!(order(&runner), "debc");
+        assert!(!runner.edit_queued_message(Uuid::new_v4(), "f"));
+    }
+
+    #[test]
+    fn transient_failure_requeues_until_attempts_run_out() {
+        use super::{MissionRunner, RetryPolicy};
+        use crate::agents::AgentResult;
+        use uuid::Uuid;
+
+        let mut runner = MissionRunner::new(Uuid::new_v4(), Uuid::new_v4(), None, None, None, None)
+            .with_retry_policy(Some(RetryPolicy {
+                max_attempts: 2,
+                backoff_secs: 0,
+                ..Default::default()
+            }));
+        let id = Uuid::new_v4();
+        runner.queue_message(id, "Build it".to_string(), None, Default::default());
+        let overloaded = AgentResult::failure("API Error: 529 overloaded_error", 0);
+
+        // First run fails and is requeued.
+        runner.current_message = runner.queue.pop_front();
+        runner.attempts = Some((id, 1));
+        let retry = runner.schedule_retry(&overloaded).unwrap();
+        assert_eq!(retry.retry, 1);
+        assert_eq!(runner.queue[0].id, id);
+        assert!(runner.retry_due());
+
+        // The second run is the last attempt.
+        runner.current_message = runner.queue.pop_front();
+        runner.attempts = Some((id, 2));
+        assert!(runner.schedule_retry(&overloaded).is_none());
+        assert!(runner.queue.is_empty());
+    }
+
+    #[tokio::test]
+    async fn concurrent_turns_fill_slots_and_finish_in_any_order() {
+        use super::{ConcurrentTurn, MissionRunState, MissionRunner, QueuedMessage};
+        use crate::agents::AgentResult;
+        use tokio_util::sync::CancellationToken;
+        use uuid::Uuid;
+
+        let mut runner = MissionRunner::new(Uuid::new_v4(), Uuid::new_v4(), None, None, None, None)
+            .with_concurrency(Some(2));
+        runner.state = MissionRunState::Running;
+        assert!(runner.has_free_slot());
+
+        let message = QueuedMessage {
+            id: Uuid::new_v4(),
+            content: "Second request".to_string(),
+            agent: None,
+            priority: Default::default(),
+        };
+        let id = message.id;
+        runner.concurrent_turns.push(ConcurrentTurn {
+            message,
+            cancel: CancellationToken::new(),
+            handle: tokio::spawn(async move {
+                (
+                    id,
+                    "Second request".to_string(),
+                    AgentResult::success("Done", 0),
+                    Default::default(),
+                )
+            }),
+        });
+        assert!(!runner.has_free_slot());
+
+        while !runner.concurrent_turns[0].handle.is_finished() {
+            tokio::task::yield_now().await;
+        }
+        let (finished_id, _, _) = runner.poll_completion().await.unwrap();
+        assert_eq!(finished_id, id);
+        // The main turn is still running; the concurrent one is already in the history.
+        assert!(runner.is_running());
+        assert_eq!(runner.history.last().unwrap().message_text(), Some("Done"));
+        assert!(runner.has_free_slot());
+    }
+
+    #[test]
+    fn runner_waiting_for_workspace_reports_it() {
+        use super::{MissionRunner, RunningMissionInfo};
+        use uuid::Uuid;
+
+        let mut runner = MissionRunner::new(Uuid::new_v4(), Uuid::new_v4(), None, None, None, None)
+            .with_workspace_limit(Some(1));
+        assert_eq!(RunningMissionInfo::from(&runner).state, "queued");
+        runner.waiting_for_workspace = true;
+        assert!(runner.is_waiting_for_workspace());
+        assert_eq!(
+            RunningMissionInfo::from(&runner).state,
+            "waiting_for_workspace"
+        );
+    }
 }
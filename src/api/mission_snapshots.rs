@@ -0,0 +1,192 @@
+//! On-disk snapshots of parallel mission runners.
+//!
+//! A runner's queue, history, deliverables, backend and workspace are written
+//! to `.sandboxed-sh/runtime/missions/<mission_id>.json` on every state
+//! transition, so a restarted server can rebuild its runners. A turn that was
+//! running when the server stopped can't be continued; the runner comes back
+//! idle and its mission is marked interrupted (and can be resumed).
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::mission_history::{self, ConversationEntry, HistorySummary};
+use super::mission_runner::{MissionRunState, QueuedMessage};
+use crate::task::DeliverableSet;
+
+/// Serialized state of a `MissionRunner`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MissionSnapshot {
+    pub mission_id: Uuid,
+    pub workspace_id: Uuid,
+    pub backend_id: String,
+    #[serde(default)]
+    pub session_id: Option<String>,
+    #[serde(default)]
+    pub config_profile: Option<String>,
+    #[serde(default)]
+    pub agent_override: Option<String>,
+    pub state: MissionRunState,
+    /// Message of the turn in flight, if any
+    #[serde(default)]
+    pub current_message: Option<QueuedMessage>,
+    /// Messages of turns running next to it (concurrent mode)
+    #[serde(default)]
+    pub concurrent_messages: Vec<QueuedMessage>,
+    #[serde(default)]
+    pub queue: Vec<QueuedMessage>,
+    #[serde(default, deserialize_with = "mission_history::deserialize_history")]
+    pub history: Vec<ConversationEntry>,
+    /// Summary of the oldest history entries
+    #[serde(default)]
+    pub history_summary: Option<HistorySummary>,
+    #[serde(default)]
+    pub deliverables: DeliverableSet,
+    #[serde(default)]
+    pub explicitly_completed: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl MissionSnapshot {
+    /// Whether a turn was running when the snapshot was taken.
+    pub fn was_running(&self) -> bool {
+        matches!(
+            self.state,
+            MissionRunState::Running | MissionRunState::WaitingForTool
+        )
+    }
+}
+
+/// Directory of runner snapshots, one file per mission.
+#[derive(Debug)]
+pub struct MissionSnapshotStore {
+    dir: PathBuf,
+}
+
+impl MissionSnapshotStore {
+    pub fn new(working_dir: &Path) -> Self {
+        Self::at(
+            working_dir
+                .join(".sandboxed-sh")
+                .join("runtime")
+                .join("missions"),
+        )
+    }
+
+    fn at(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn path(&self, mission_id: Uuid) -> PathBuf {
+        self.dir.join(format!("{}.json", mission_id))
+    }
+
+    /// Write a snapshot, replacing the previous one atomically.
+    pub fn save(&self, snapshot: &MissionSnapshot) {
+        let _ = std::fs::create_dir_all(&self.dir);
+        let path = self.path(snapshot.mission_id);
+        let tmp = path.with_extension("json.tmp");
+        let result = serde_json::to_string(snapshot)
+            .map_err(|e| e.to_string())
+            .and_then(|contents| std::fs::write(&tmp, contents).map_err(|e| e.to_string()))
+            .and_then(|_| std::fs::rename(&tmp, &path).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            tracing::warn!(
+                "Failed to persist runner state for mission {}: {}",
+                snapshot.mission_id,
+                e
+            );
+        }
+    }
+
+    /// Forget a mission's runner (it finished or was cancelled).
+    pub fn remove(&self, mission_id: Uuid) {
+        let path = self.path(mission_id);
+        if let Err(e) = std::fs::remove_file(&path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                tracing::warn!("Failed to remove runner state {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    /// All stored snapshots. Unreadable files are skipped.
+    pub fn load_all(&self) -> Vec<MissionSnapshot> {
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        let mut snapshots: Vec<MissionSnapshot> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| {
+                let contents = std::fs::read_to_string(&path).ok()?;
+                match serde_json::from_str(&contents) {
+                    Ok(snapshot) => Some(snapshot),
+                    Err(e) => {
+                        tracing::warn!("Ignoring runner state {}: {}", path.display(), e);
+                        None
+                    }
+                }
+            })
+            .collect();
+        snapshots.sort_by_key(|s| s.updated_at);
+        snapshots
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = MissionSnapshotStore::at(dir.path().join("missions"));
+        assert!(store.load_all().is_empty());
+
+        let mission_id = Uuid::new_v4();
+        let snapshot = MissionSnapshot {
+            mission_id,
+            workspace_id: Uuid::new_v4(),
+            backend_id: "claudecode".to_string(),
+            session_id: Some("session".to_string()),
+            config_profile: None,
+            agent_override: None,
+            state: MissionRunState::Running,
+            current_message: Some(QueuedMessage {
+                id: Uuid::new_v4(),
+                content: "Build the report".to_string(),
+                agent: None,
+                priority: Default::default(),
+            }),
+            concurrent_messages: Vec::new(),
+            queue: vec![QueuedMessage {
+                id: Uuid::new_v4(),
+                content: "Then email it".to_string(),
+                agent: Some("writer".to_string()),
+                priority: Default::default(),
+            }],
+            history: vec![ConversationEntry::user("Hello")],
+            history_summary: None,
+            deliverables: crate::task::extract_deliverables("Write /tmp/report.md"),
+            explicitly_completed: false,
+            updated_at: Utc::now(),
+        };
+        store.save(&snapshot);
+
+        let loaded = store.load_all();
+        assert_eq!(loaded.len(), 1);
+        assert!(loaded[0].was_running());
+        assert_eq!(loaded[0].queue[0].agent.as_deref(), Some("writer"));
+        assert_eq!(
+            loaded[0].deliverables.deliverables,
+            snapshot.deliverables.deliverables
+        );
+
+        store.remove(mission_id);
+        store.remove(mission_id);
+        assert!(store.load_all().is_empty());
+    }
+}
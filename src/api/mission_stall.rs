@@ -0,0 +1,257 @@
+//! Escalation for stalled missions.
+//!
+//! The health monitor reports a running mission as stalled once it goes
+//! without progress for a while. When `MISSION_STALL_ACTIONS` lists actions
+//! (`nudge`, `restart`, `cancel`), the control loop takes them one after the
+//! other: the first once the stall turns severe, each further one after
+//! another severe-stall interval without progress. Any progress from the
+//! agent (tool calls, results, thinking, text, or a heartbeat from its busy
+//! processes) starts the ladder over. A mission can set its own thresholds.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::config::StallAction;
+
+use super::mission_runner::MissionStallSeverity;
+
+const STALL_WARN_SECS: u64 = 120;
+const STALL_SEVERE_SECS: u64 = 300;
+
+/// Seconds without activity after which a running mission is reported as
+/// stalled, and after which the stall is severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StallThresholds {
+    pub warn_secs: u64,
+    pub severe_secs: u64,
+}
+
+impl Default for StallThresholds {
+    fn default() -> Self {
+        Self {
+            warn_secs: STALL_WARN_SECS,
+            severe_secs: STALL_SEVERE_SECS,
+        }
+    }
+}
+
+impl StallThresholds {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.warn_secs == 0 || self.severe_secs < self.warn_secs {
+            return Err(
+                "stall_thresholds.warn_secs must be positive and at most severe_secs".to_string(),
+            );
+        }
+        Ok(())
+    }
+
+    pub fn severity(&self, seconds_since_activity: u64) -> Option<MissionStallSeverity> {
+        if seconds_since_activity > self.severe_secs {
+            Some(MissionStallSeverity::Severe)
+        } else if seconds_since_activity > self.warn_secs {
+            Some(MissionStallSeverity::Warning)
+        } else {
+            None
+        }
+    }
+}
+
+/// Message queued by a `nudge`.
+pub const NUDGE_MESSAGE: &str = "You haven't made progress for several minutes. If you are waiting on a command or tool that won't finish, stop it. Then continue the task, or explain what is blocking you.";
+
+/// Message a `restart` continues the mission with.
+pub const RESTART_MESSAGE: &str = "Your previous turn was stopped because it made no progress for several minutes. Continue the task from where you left off, and avoid repeating the step that hung.";
+
+#[derive(Debug, Default)]
+struct Escalation {
+    thresholds: StallThresholds,
+    /// Actions taken during the current stall
+    step: usize,
+    last_action: Option<Instant>,
+    /// Message queued by a nudge or restart that hasn't started yet
+    injected: Option<Uuid>,
+    /// Restart or cancel requested; applied when the cancelled turn returns
+    stopping: Option<StallAction>,
+}
+
+/// Per-mission escalation state, owned by the control loop.
+#[derive(Debug)]
+pub struct StallMonitor {
+    actions: Vec<StallAction>,
+    missions: HashMap<Uuid, Escalation>,
+}
+
+impl StallMonitor {
+    pub fn new(actions: Vec<StallAction>) -> Self {
+        Self {
+            actions,
+            missions: HashMap::new(),
+        }
+    }
+
+    /// The next action to take for a running mission that has gone
+    /// `seconds_since_activity` without progress, with its 1-based step.
+    /// Restart and cancel are remembered until [`Self::take_stopping`].
+    pub fn escalate(
+        &mut self,
+        mission_id: Uuid,
+        seconds_since_activity: u64,
+        now: Instant,
+    ) -> Option<(StallAction, u32)> {
+        if self.actions.is_empty()
+            || seconds_since_activity <= self.thresholds(mission_id).severe_secs
+        {
+            return None;
+        }
+        let escalation = self.missions.entry(mission_id).or_default();
+        let interval = Duration::from_secs(escalation.thresholds.severe_secs);
+        if escalation.stopping.is_some()
+            || escalation
+                .last_action
+                .is_some_and(|at| now.saturating_duration_since(at) <= interval)
+        {
+            return None;
+        }
+        let action = *self.actions.get(escalation.step)?;
+        escalation.step += 1;
+        escalation.last_action = Some(now);
+        if action != StallAction::Nudge {
+            escalation.stopping = Some(action);
+        }
+        Some((action, escalation.step as u32))
+    }
+
+    /// Use the mission's own thresholds.
+    pub fn set_thresholds(&mut self, mission_id: Uuid, thresholds: StallThresholds) {
+        self.missions.entry(mission_id).or_default().thresholds = thresholds;
+    }
+
+    /// The thresholds a mission's stalls are measured against.
+    pub fn thresholds(&self, mission_id: Uuid) -> StallThresholds {
+        self.missions
+            .get(&mission_id)
+            .map(|escalation| escalation.thresholds)
+            .unwrap_or_default()
+    }
+
+    /// Remember the message a nudge or restart queued.
+    pub fn set_injected(&mut self, mission_id: Uuid, message_id: Uuid) {
+        self.missions.entry(mission_id).or_default().injected = Some(message_id);
+    }
+
+    /// Take the queued nudge or restart message, if any.
+    pub fn take_injected(&mut self, mission_id: Uuid) -> Option<Uuid> {
+        self.missions.get_mut(&mission_id)?.injected.take()
+    }
+
+    /// Take the restart or cancel to apply to a turn that just returned.
+    pub fn take_stopping(&mut self, mission_id: Uuid) -> Option<StallAction> {
+        self.missions.get_mut(&mission_id)?.stopping.take()
+    }
+
+    /// The agent made progress: the next stall starts from the first action.
+    pub fn progress(&mut self, mission_id: Uuid) {
+        if let Some(escalation) = self.missions.get_mut(&mission_id) {
+            escalation.step = 0;
+            escalation.last_action = None;
+        }
+    }
+
+    /// Drop the state of missions that are no longer active.
+    pub fn retain(&mut self, active: &[Uuid]) {
+        self.missions.retain(|id, _| active.contains(id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STALLED: u64 = STALL_SEVERE_SECS + 1;
+
+    #[test]
+    fn test_escalates_one_step_per_interval() {
+        let mut monitor = StallMonitor::new(vec![StallAction::Nudge, StallAction::Restart]);
+        let mid = Uuid::new_v4();
+        let start = Instant::now();
+        let later = |secs| start + Duration::from_secs(secs);
+
+        assert_eq!(monitor.escalate(mid, STALL_SEVERE_SECS, start), None);
+        assert_eq!(
+            monitor.escalate(mid, STALLED, start),
+            Some((StallAction::Nudge, 1))
+        );
+        assert_eq!(monitor.escalate(mid, STALLED, later(60)), None);
+        assert_eq!(
+            monitor.escalate(mid, STALLED, later(STALLED)),
+            Some((StallAction::Restart, 2))
+        );
+        // Nothing more until the restarted turn has returned.
+        assert_eq!(monitor.escalate(mid, STALLED, later(2 * STALLED)), None);
+        assert_eq!(monitor.take_stopping(mid), Some(StallAction::Restart));
+        // Out of actions.
+        assert_eq!(monitor.escalate(mid, STALLED, later(3 * STALLED)), None);
+    }
+
+    #[test]
+    fn test_progress_restarts_the_ladder() {
+        let mut monitor = StallMonitor::new(vec![StallAction::Nudge, StallAction::Cancel]);
+        let mid = Uuid::new_v4();
+        let now = Instant::now();
+        assert!(monitor.escalate(mid, STALLED, now).is_some());
+        monitor.set_injected(mid, Uuid::nil());
+        monitor.progress(mid);
+        assert_eq!(
+            monitor.escalate(mid, STALLED, now),
+            Some((StallAction::Nudge, 1))
+        );
+        assert_eq!(monitor.take_injected(mid), Some(Uuid::nil()));
+
+        monitor.retain(&[]);
+        assert_eq!(monitor.take_injected(mid), None);
+        assert!(StallMonitor::new(Vec::new())
+            .escalate(mid, STALLED, now)
+            .is_none());
+    }
+
+    #[test]
+    fn test_mission_thresholds() {
+        let mut monitor = StallMonitor::new(vec![StallAction::Nudge]);
+        let mid = Uuid::new_v4();
+        let now = Instant::now();
+        let thresholds = StallThresholds {
+            warn_secs: 600,
+            severe_secs: 1800,
+        };
+        monitor.set_thresholds(mid, thresholds);
+        assert_eq!(monitor.thresholds(mid), thresholds);
+        assert_eq!(monitor.escalate(mid, STALLED, now), None);
+        assert_eq!(
+            monitor.escalate(mid, 1801, now),
+            Some((StallAction::Nudge, 1))
+        );
+        assert_eq!(
+            monitor.thresholds(Uuid::new_v4()),
+            StallThresholds::default()
+        );
+
+        assert_eq!(thresholds.severity(600), None);
+        assert!(matches!(
+            thresholds.severity(601),
+            Some(MissionStallSeverity::Warning)
+        ));
+        assert!(matches!(
+            thresholds.severity(1801),
+            Some(MissionStallSeverity::Severe)
+        ));
+        assert!(StallThresholds {
+            warn_secs: 60,
+            severe_secs: 30
+        }
+        .validate()
+        .is_err());
+    }
+}
@@ -0,0 +1,106 @@
+//! Steering messages for running missions.
+//!
+//! A steer is a user message meant for the turn in flight rather than the
+//! next one. A backend turn that can take input mid-run registers here for
+//! the duration of the turn and forwards what it receives: OpenCode posts it
+//! through its server's session API, Claude Code writes it to the CLI's
+//! stream-json stdin. For other backends the control loop interrupts the turn
+//! instead and continues the same session with the message.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+type Channels = HashMap<Uuid, (u64, mpsc::UnboundedSender<String>)>;
+
+fn channels() -> &'static Mutex<Channels> {
+    static CHANNELS: OnceLock<Mutex<Channels>> = OnceLock::new();
+    CHANNELS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Steering messages for a running turn; unregisters when dropped.
+pub struct SteerReceiver {
+    mission_id: Uuid,
+    id: u64,
+    rx: mpsc::UnboundedReceiver<String>,
+}
+
+impl SteerReceiver {
+    pub async fn recv(&mut self) -> Option<String> {
+        self.rx.recv().await
+    }
+
+    /// A message already sent, if any, without waiting.
+    pub fn try_recv(&mut self) -> Option<String> {
+        self.rx.try_recv().ok()
+    }
+}
+
+impl Drop for SteerReceiver {
+    fn drop(&mut self) {
+        let mut channels = channels().lock().unwrap_or_else(|e| e.into_inner());
+        // A newer turn of the same mission may have registered since
+        if channels
+            .get(&self.mission_id)
+            .is_some_and(|(id, _)| *id == self.id)
+        {
+            channels.remove(&self.mission_id);
+        }
+    }
+}
+
+/// Accept steering messages for a mission's running turn.
+pub fn register(mission_id: Uuid) -> SteerReceiver {
+    static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    let (tx, rx) = mpsc::unbounded_channel();
+    channels()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(mission_id, (id, tx));
+    SteerReceiver { mission_id, id, rx }
+}
+
+/// Hand a message to the mission's running turn. Returns `false` if no turn
+/// of the mission takes messages mid-run.
+pub fn steer(mission_id: Uuid, content: String) -> bool {
+    channels()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&mission_id)
+        .is_some_and(|(_, tx)| tx.send(content).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_steer_reaches_registered_turn() {
+        let mid = Uuid::new_v4();
+        assert!(!steer(mid, "ignored".to_string()));
+
+        let mut rx = register(mid);
+        assert!(steer(mid, "use the v2 API".to_string()));
+        assert_eq!(rx.recv().await.as_deref(), Some("use the v2 API"));
+
+        assert!(steer(mid, "and keep the tests".to_string()));
+        assert_eq!(rx.try_recv().as_deref(), Some("and keep the tests"));
+        assert_eq!(rx.try_recv(), None);
+
+        drop(rx);
+        assert!(!steer(mid, "too late".to_string()));
+    }
+
+    #[test]
+    fn test_old_turn_does_not_unregister_newer_one() {
+        let mid = Uuid::new_v4();
+        let old = register(mid);
+        let _new = register(mid);
+        drop(old);
+        assert!(steer(mid, "still delivered".to_string()));
+    }
+}
@@ -3,11 +3,16 @@
 use super::{
     now_string, sanitize_filename, Mission, MissionHistoryEntry, MissionStatus, MissionStore,
 };
-use crate::api::control::{AgentTreeNode, DesktopSessionInfo};
+use crate::api::control::{AgentTreeNode, DesktopSessionInfo, MissionPriority};
+use crate::api::mission_completion::CompletionReport;
+use crate::api::mission_retry::RetryPolicy;
+use crate::api::mission_stall::StallThresholds;
+use crate::api::mission_verification::{DeliverableCheck, VerificationReport};
+use crate::api::mission_verifier::MissionVerifier;
 use async_trait::async_trait;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::fs;
@@ -124,6 +129,18 @@ impl MissionStore for FileMissionStore {
             desktop_sessions: Vec::new(),
             session_id: Some(Uuid::new_v4().to_string()),
             terminal_reason: None,
+            priority: MissionPriority::default(),
+            budget_cents: None,
+            spent_cents: 0,
+            timeout_minutes: None,
+            retry_policy: None,
+            max_concurrent_messages: None,
+            tags: Default::default(),
+            deliverable_checks: Vec::new(),
+            verification: None,
+            verifier: None,
+            stall_thresholds: None,
+            completion_report: None,
         };
         self.missions
             .write()
@@ -208,6 +225,182 @@ impl MissionStore for FileMissionStore {
         self.persist().await
     }
 
+    async fn update_mission_priority(
+        &self,
+        id: Uuid,
+        priority: MissionPriority,
+    ) -> Result<(), String> {
+        let mut missions = self.missions.write().await;
+        let mission = missions
+            .get_mut(&id)
+            .ok_or_else(|| format!("Mission {} not found", id))?;
+        mission.priority = priority;
+        mission.updated_at = now_string();
+        drop(missions);
+        self.persist().await
+    }
+
+    async fn update_mission_budget(
+        &self,
+        id: Uuid,
+        budget_cents: Option<u64>,
+    ) -> Result<(), String> {
+        let mut missions = self.missions.write().await;
+        let mission = missions
+            .get_mut(&id)
+            .ok_or_else(|| format!("Mission {} not found", id))?;
+        mission.budget_cents = budget_cents;
+        mission.updated_at = now_string();
+        drop(missions);
+        self.persist().await
+    }
+
+    async fn update_mission_timeout(
+        &self,
+        id: Uuid,
+        timeout_minutes: Option<u64>,
+    ) -> Result<(), String> {
+        let mut missions = self.missions.write().await;
+        let mission = missions
+            .get_mut(&id)
+            .ok_or_else(|| format!("Mission {} not found", id))?;
+        mission.timeout_minutes = timeout_minutes;
+        mission.updated_at = now_string();
+        drop(missions);
+        self.persist().await
+    }
+
+    async fn update_mission_retry_policy(
+        &self,
+        id: Uuid,
+        retry_policy: Option<RetryPolicy>,
+    ) -> Result<(), String> {
+        let mut missions = self.missions.write().await;
+        let mission = missions
+            .get_mut(&id)
+            .ok_or_else(|| format!("Mission {} not found", id))?;
+        mission.retry_policy = retry_policy;
+        mission.updated_at = now_string();
+        drop(missions);
+        self.persist().await
+    }
+
+    async fn update_mission_concurrency(
+        &self,
+        id: Uuid,
+        max_concurrent_messages: Option<u32>,
+    ) -> Result<(), String> {
+        let mut missions = self.missions.write().await;
+        let mission = missions
+            .get_mut(&id)
+            .ok_or_else(|| format!("Mission {} not found", id))?;
+        mission.max_concurrent_messages = max_concurrent_messages;
+        mission.updated_at = now_string();
+        drop(missions);
+        self.persist().await
+    }
+
+    async fn update_mission_tags(
+        &self,
+        id: Uuid,
+        tags: &BTreeMap<String, String>,
+    ) -> Result<(), String> {
+        let mut missions = self.missions.write().await;
+        let mission = missions
+            .get_mut(&id)
+            .ok_or_else(|| format!("Mission {} not found", id))?;
+        mission.tags = tags.clone();
+        mission.updated_at = now_string();
+        drop(missions);
+        self.persist().await
+    }
+
+    async fn update_mission_deliverable_checks(
+        &self,
+        id: Uuid,
+        checks: &[DeliverableCheck],
+    ) -> Result<(), String> {
+        let mut missions = self.missions.write().await;
+        let mission = missions
+            .get_mut(&id)
+            .ok_or_else(|| format!("Mission {} not found", id))?;
+        mission.deliverable_checks = checks.to_vec();
+        mission.updated_at = now_string();
+        drop(missions);
+        self.persist().await
+    }
+
+    async fn update_mission_verification(
+        &self,
+        id: Uuid,
+        report: &VerificationReport,
+    ) -> Result<(), String> {
+        let mut missions = self.missions.write().await;
+        let mission = missions
+            .get_mut(&id)
+            .ok_or_else(|| format!("Mission {} not found", id))?;
+        mission.verification = Some(report.clone());
+        drop(missions);
+        self.persist().await
+    }
+
+    async fn update_mission_verifier(
+        &self,
+        id: Uuid,
+        verifier: Option<MissionVerifier>,
+    ) -> Result<(), String> {
+        let mut missions = self.missions.write().await;
+        let mission = missions
+            .get_mut(&id)
+            .ok_or_else(|| format!("Mission {} not found", id))?;
+        mission.verifier = verifier;
+        mission.updated_at = now_string();
+        drop(missions);
+        self.persist().await
+    }
+
+    async fn update_mission_stall_thresholds(
+        &self,
+        id: Uuid,
+        thresholds: Option<StallThresholds>,
+    ) -> Result<(), String> {
+        let mut missions = self.missions.write().await;
+        let mission = missions
+            .get_mut(&id)
+            .ok_or_else(|| format!("Mission {} not found", id))?;
+        mission.stall_thresholds = thresholds;
+        mission.updated_at = now_string();
+        drop(missions);
+        self.persist().await
+    }
+
+    async fn update_mission_completion_report(
+        &self,
+        id: Uuid,
+        report: &CompletionReport,
+    ) -> Result<(), String> {
+        let mut missions = self.missions.write().await;
+        let mission = missions
+            .get_mut(&id)
+            .ok_or_else(|| format!("Mission {} not found", id))?;
+        mission.completion_report = Some(report.clone());
+        mission.updated_at = now_string();
+        drop(missions);
+        self.persist().await
+    }
+
+    async fn add_mission_cost(&self, id: Uuid, cost_cents: u64) -> Result<u64, String> {
+        let mut missions = self.missions.write().await;
+        let mission = missions
+            .get_mut(&id)
+            .ok_or_else(|| format!("Mission {} not found", id))?;
+        mission.spent_cents = mission.spent_cents.saturating_add(cost_cents);
+        let spent = mission.spent_cents;
+        drop(missions);
+        self.persist().await?;
+        Ok(spent)
+    }
+
     async fn update_mission_session_id(&self, id: Uuid, session_id: &str) -> Result<(), String> {
         let mut missions = self.missions.write().await;
         let mission = missions
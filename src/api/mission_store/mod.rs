@@ -13,11 +13,18 @@ pub use file::FileMissionStore;
 pub use memory::InMemoryMissionStore;
 pub use sqlite::SqliteMissionStore;
 
-use crate::api::control::{AgentEvent, AgentTreeNode, DesktopSessionInfo, MissionStatus};
+use crate::api::control::{
+    AgentEvent, AgentTreeNode, DesktopSessionInfo, MissionPriority, MissionStatus,
+};
+use crate::api::mission_completion::CompletionReport;
+use crate::api::mission_retry::RetryPolicy;
+use crate::api::mission_stall::StallThresholds;
+use crate::api::mission_verification::{DeliverableCheck, VerificationReport};
+use crate::api::mission_verifier::MissionVerifier;
 use async_trait::async_trait;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 use uuid::Uuid;
 
@@ -63,6 +70,43 @@ pub struct Mission {
     /// Why the mission terminated (for failed/completed missions)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub terminal_reason: Option<String>,
+    /// Queue priority of the mission's messages
+    #[serde(default)]
+    pub priority: MissionPriority,
+    /// Cost limit in cents; execution stops once it is used up
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget_cents: Option<u64>,
+    /// Cost reported by the backend so far, in cents
+    #[serde(default)]
+    pub spent_cents: u64,
+    /// Wall-clock limit for a run of this mission, in minutes, overriding
+    /// the server default (0 = unlimited)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_minutes: Option<u64>,
+    /// How failed turns are retried (no retries if unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_policy: Option<RetryPolicy>,
+    /// Queued messages the mission may run at once (one at a time if unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrent_messages: Option<u32>,
+    /// Free-form key/value labels, e.g. `team: infra`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, String>,
+    /// Checks run against the mission's output when it completes
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deliverable_checks: Vec<DeliverableCheck>,
+    /// Result of the latest deliverable verification
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verification: Option<VerificationReport>,
+    /// Model reviewing completion claims before the mission completes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verifier: Option<MissionVerifier>,
+    /// When the mission counts as stalled (server defaults if unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stall_thresholds: Option<StallThresholds>,
+    /// What the agent reported when it called `complete_mission`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completion_report: Option<CompletionReport>,
 }
 
 fn default_backend() -> String {
@@ -95,6 +139,17 @@ pub struct StoredEvent {
     pub metadata: serde_json::Value,
 }
 
+/// Cost of one turn, read from its assistant message event.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TurnCost {
+    pub mission_id: Uuid,
+    pub mission_title: Option<String>,
+    pub backend: String,
+    pub model: Option<String>,
+    pub cost_cents: u64,
+    pub timestamp: String,
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Automation Types
 // ─────────────────────────────────────────────────────────────────────────────
@@ -300,6 +355,86 @@ pub trait MissionStore: Send + Sync {
     /// Update mission title.
     async fn update_mission_title(&self, id: Uuid, title: &str) -> Result<(), String>;
 
+    /// Update mission priority.
+    async fn update_mission_priority(
+        &self,
+        id: Uuid,
+        priority: MissionPriority,
+    ) -> Result<(), String>;
+
+    /// Set or clear the mission's cost budget.
+    async fn update_mission_budget(
+        &self,
+        id: Uuid,
+        budget_cents: Option<u64>,
+    ) -> Result<(), String>;
+
+    /// Set or clear the mission's run time limit.
+    async fn update_mission_timeout(
+        &self,
+        id: Uuid,
+        timeout_minutes: Option<u64>,
+    ) -> Result<(), String>;
+
+    /// Set or clear the mission's retry policy.
+    async fn update_mission_retry_policy(
+        &self,
+        id: Uuid,
+        retry_policy: Option<RetryPolicy>,
+    ) -> Result<(), String>;
+
+    /// Set how many queued messages the mission may run at once.
+    async fn update_mission_concurrency(
+        &self,
+        id: Uuid,
+        max_concurrent_messages: Option<u32>,
+    ) -> Result<(), String>;
+
+    /// Replace the mission's tags.
+    async fn update_mission_tags(
+        &self,
+        id: Uuid,
+        tags: &BTreeMap<String, String>,
+    ) -> Result<(), String>;
+
+    /// Replace the checks run when the mission completes.
+    async fn update_mission_deliverable_checks(
+        &self,
+        id: Uuid,
+        checks: &[DeliverableCheck],
+    ) -> Result<(), String>;
+
+    /// Store the latest verification report. Doesn't touch `updated_at`.
+    async fn update_mission_verification(
+        &self,
+        id: Uuid,
+        report: &VerificationReport,
+    ) -> Result<(), String>;
+
+    /// Set or clear the model reviewing completion claims.
+    async fn update_mission_verifier(
+        &self,
+        id: Uuid,
+        verifier: Option<MissionVerifier>,
+    ) -> Result<(), String>;
+
+    /// Set or clear the mission's own stall thresholds.
+    async fn update_mission_stall_thresholds(
+        &self,
+        id: Uuid,
+        thresholds: Option<StallThresholds>,
+    ) -> Result<(), String>;
+
+    /// Store the report of the agent's latest `complete_mission` call.
+    async fn update_mission_completion_report(
+        &self,
+        id: Uuid,
+        report: &CompletionReport,
+    ) -> Result<(), String>;
+
+    /// Add a turn's cost to the mission's spend. Returns the new total.
+    async fn add_mission_cost(&self, id: Uuid, cost_cents: u64) -> Result<u64, String>;
+
     /// Update mission session ID (for backends like Amp that generate their own IDs).
     async fn update_mission_session_id(&self, id: Uuid, session_id: &str) -> Result<(), String>;
 
@@ -353,12 +488,38 @@ pub trait MissionStore: Send + Sync {
         Ok(vec![])
     }
 
+    /// Number of raw events of a mission and the bytes they take. Raw events
+    /// are everything but the user and assistant messages, which the mission's
+    /// history is read from.
+    async fn raw_event_size(&self, mission_id: Uuid) -> Result<(u64, u64), String> {
+        let _ = mission_id;
+        Ok((0, 0))
+    }
+
+    /// Delete a mission's raw events, keeping its conversation. Returns the
+    /// number of events removed.
+    async fn prune_raw_events(&self, mission_id: Uuid) -> Result<u64, String> {
+        let _ = mission_id;
+        Ok(0)
+    }
+
     /// Get total cost in cents across all missions.
     /// Aggregates cost_cents from all assistant_message events.
     async fn get_total_cost_cents(&self) -> Result<u64, String> {
         Ok(0)
     }
 
+    /// Cost of every turn logged in `[since, until)` (RFC 3339, both
+    /// optional), oldest first.
+    async fn get_turn_costs(
+        &self,
+        since: Option<&str>,
+        until: Option<&str>,
+    ) -> Result<Vec<TurnCost>, String> {
+        let _ = (since, until);
+        Ok(vec![])
+    }
+
     // === Automation methods (default no-op for backward compatibility) ===
 
     /// Create an automation for a mission.
@@ -3,13 +3,20 @@
 use super::{
     now_string, sanitize_filename, Automation, AutomationExecution, CommandSource, ExecutionStatus,
     Mission, MissionHistoryEntry, MissionStatus, MissionStore, RetryConfig, StoredEvent,
-    TriggerType, WebhookConfig,
+    TriggerType, TurnCost, WebhookConfig,
 };
-use crate::api::control::{AgentEvent, AgentTreeNode, DesktopSessionInfo};
+use crate::api::control::{
+    AgentEvent, AgentTreeNode, DesktopSessionInfo, DiagnosticLevel, MissionPriority,
+};
+use crate::api::mission_completion::CompletionReport;
+use crate::api::mission_retry::RetryPolicy;
+use crate::api::mission_stall::StallThresholds;
+use crate::api::mission_verification::{DeliverableCheck, VerificationReport};
+use crate::api::mission_verifier::MissionVerifier;
 use async_trait::async_trait;
 use chrono::Utc;
 use rusqlite::{params, Connection, OptionalExtension};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -34,7 +41,19 @@ CREATE TABLE IF NOT EXISTS missions (
     interrupted_at TEXT,
     resumable INTEGER NOT NULL DEFAULT 0,
     desktop_sessions TEXT,
-    terminal_reason TEXT
+    terminal_reason TEXT,
+    priority TEXT NOT NULL DEFAULT 'normal',
+    budget_cents INTEGER,
+    spent_cents INTEGER NOT NULL DEFAULT 0,
+    timeout_minutes INTEGER,
+    retry_policy TEXT,
+    max_concurrent_messages INTEGER,
+    tags TEXT,
+    deliverable_checks TEXT,
+    verification TEXT,
+    verifier TEXT,
+    stall_thresholds TEXT,
+    completion_report TEXT
 );
 
 CREATE INDEX IF NOT EXISTS idx_missions_updated_at ON missions(updated_at DESC);
@@ -413,6 +432,128 @@ impl SqliteMissionStore {
                 .map_err(|e| format!("Failed to add config_profile column: {}", e))?;
         }
 
+        // Check if 'priority' column exists in missions table
+        let has_priority_column: bool = conn
+            .prepare("SELECT 1 FROM pragma_table_info('missions') WHERE name = 'priority'")
+            .map_err(|e| format!("Failed to check for priority column: {}", e))?
+            .exists([])
+            .map_err(|e| format!("Failed to query table info: {}", e))?;
+
+        if !has_priority_column {
+            tracing::info!("Running migration: adding 'priority' column to missions table");
+            conn.execute(
+                "ALTER TABLE missions ADD COLUMN priority TEXT NOT NULL DEFAULT 'normal'",
+                [],
+            )
+            .map_err(|e| format!("Failed to add priority column: {}", e))?;
+        }
+
+        // Check if the budget columns exist in missions table
+        let has_budget_column: bool = conn
+            .prepare("SELECT 1 FROM pragma_table_info('missions') WHERE name = 'budget_cents'")
+            .map_err(|e| format!("Failed to check for budget_cents column: {}", e))?
+            .exists([])
+            .map_err(|e| format!("Failed to query table info: {}", e))?;
+
+        if !has_budget_column {
+            tracing::info!("Running migration: adding budget columns to missions table");
+            conn.execute_batch(
+                "ALTER TABLE missions ADD COLUMN budget_cents INTEGER;
+                 ALTER TABLE missions ADD COLUMN spent_cents INTEGER NOT NULL DEFAULT 0;",
+            )
+            .map_err(|e| format!("Failed to add budget columns: {}", e))?;
+        }
+
+        // Check if the timeout column exists in missions table
+        let has_timeout_column: bool = conn
+            .prepare("SELECT 1 FROM pragma_table_info('missions') WHERE name = 'timeout_minutes'")
+            .map_err(|e| format!("Failed to check for timeout_minutes column: {}", e))?
+            .exists([])
+            .map_err(|e| format!("Failed to query table info: {}", e))?;
+
+        if !has_timeout_column {
+            tracing::info!("Running migration: adding timeout_minutes column to missions table");
+            conn.execute(
+                "ALTER TABLE missions ADD COLUMN timeout_minutes INTEGER",
+                [],
+            )
+            .map_err(|e| format!("Failed to add timeout_minutes column: {}", e))?;
+        }
+
+        // Check if the retry_policy column exists in missions table
+        let has_retry_policy_column: bool = conn
+            .prepare("SELECT 1 FROM pragma_table_info('missions') WHERE name = 'retry_policy'")
+            .map_err(|e| format!("Failed to check for retry_policy column: {}", e))?
+            .exists([])
+            .map_err(|e| format!("Failed to query table info: {}", e))?;
+
+        if !has_retry_policy_column {
+            tracing::info!("Running migration: adding retry_policy column to missions table");
+            conn.execute("ALTER TABLE missions ADD COLUMN retry_policy TEXT", [])
+                .map_err(|e| format!("Failed to add retry_policy column: {}", e))?;
+        }
+
+        // Check if the max_concurrent_messages column exists in missions table
+        let has_concurrency_column: bool = conn
+            .prepare(
+                "SELECT 1 FROM pragma_table_info('missions') WHERE name = 'max_concurrent_messages'",
+            )
+            .map_err(|e| format!("Failed to check for max_concurrent_messages column: {}", e))?
+            .exists([])
+            .map_err(|e| format!("Failed to query table info: {}", e))?;
+
+        if !has_concurrency_column {
+            tracing::info!(
+                "Running migration: adding max_concurrent_messages column to missions table"
+            );
+            conn.execute(
+                "ALTER TABLE missions ADD COLUMN max_concurrent_messages INTEGER",
+                [],
+            )
+            .map_err(|e| format!("Failed to add max_concurrent_messages column: {}", e))?;
+        }
+
+        // Check if the tags column exists in missions table
+        let has_tags_column: bool = conn
+            .prepare("SELECT 1 FROM pragma_table_info('missions') WHERE name = 'tags'")
+            .map_err(|e| format!("Failed to check for tags column: {}", e))?
+            .exists([])
+            .map_err(|e| format!("Failed to query table info: {}", e))?;
+
+        if !has_tags_column {
+            tracing::info!("Running migration: adding tags column to missions table");
+            conn.execute("ALTER TABLE missions ADD COLUMN tags TEXT", [])
+                .map_err(|e| format!("Failed to add tags column: {}", e))?;
+        }
+
+        // Check if the deliverable verification, verifier, stall threshold and completion report
+        // columns exist in missions table
+        for column in [
+            "deliverable_checks",
+            "verification",
+            "verifier",
+            "stall_thresholds",
+            "completion_report",
+        ] {
+            let has_column: bool = conn
+                .prepare("SELECT 1 FROM pragma_table_info('missions') WHERE name = ?1")
+                .map_err(|e| format!("Failed to check for {} column: {}", column, e))?
+                .exists(params![column])
+                .map_err(|e| format!("Failed to query table info: {}", e))?;
+
+            if !has_column {
+                tracing::info!(
+                    "Running migration: adding {} column to missions table",
+                    column
+                );
+                conn.execute(
+                    &format!("ALTER TABLE missions ADD COLUMN {} TEXT", column),
+                    [],
+                )
+                .map_err(|e| format!("Failed to add {} column: {}", column, e))?;
+            }
+        }
+
         // Migrate automations table to new schema
         Self::migrate_automations_table(conn)?;
         Self::ensure_automation_indexes(conn)?;
@@ -609,6 +750,15 @@ fn parse_status(s: &str) -> MissionStatus {
     }
 }
 
+fn parse_priority(s: Option<&str>) -> MissionPriority {
+    match s {
+        Some("low") => MissionPriority::Low,
+        Some("high") => MissionPriority::High,
+        Some("urgent") => MissionPriority::Urgent,
+        _ => MissionPriority::Normal,
+    }
+}
+
 fn status_to_string(status: MissionStatus) -> &'static str {
     match status {
         MissionStatus::Pending => "pending",
@@ -636,7 +786,9 @@ impl MissionStore for SqliteMissionStore {
                     "SELECT id, status, title, workspace_id, workspace_name, agent, model_override,
                             created_at, updated_at, interrupted_at, resumable, desktop_sessions,
                             COALESCE(backend, 'opencode') as backend, session_id, terminal_reason,
-                            config_profile
+                            config_profile, priority, budget_cents, spent_cents, timeout_minutes,
+                            retry_policy, max_concurrent_messages, tags, deliverable_checks,
+                            verification, verifier, stall_thresholds, completion_report
                      FROM missions
                      ORDER BY updated_at DESC
                      LIMIT ?1 OFFSET ?2",
@@ -653,6 +805,18 @@ impl MissionStore for SqliteMissionStore {
                     let session_id: Option<String> = row.get(13)?;
                     let terminal_reason: Option<String> = row.get(14)?;
                     let config_profile: Option<String> = row.get(15)?;
+                    let priority: Option<String> = row.get(16)?;
+                    let budget_cents: Option<i64> = row.get(17)?;
+                    let spent_cents: Option<i64> = row.get(18)?;
+                    let timeout_minutes: Option<i64> = row.get(19)?;
+                    let retry_policy: Option<String> = row.get(20)?;
+                    let max_concurrent_messages: Option<i64> = row.get(21)?;
+                    let tags: Option<String> = row.get(22)?;
+                    let deliverable_checks: Option<String> = row.get(23)?;
+                    let verification: Option<String> = row.get(24)?;
+                    let verifier: Option<String> = row.get(25)?;
+                    let stall_thresholds: Option<String> = row.get(26)?;
+                    let completion_report: Option<String> = row.get(27)?;
 
                     Ok(Mission {
                         id: Uuid::parse_str(&id_str).unwrap_or_default(),
@@ -675,6 +839,25 @@ impl MissionStore for SqliteMissionStore {
                             .unwrap_or_default(),
                         session_id,
                         terminal_reason,
+                        priority: parse_priority(priority.as_deref()),
+                        budget_cents: budget_cents.map(|c| c.max(0) as u64),
+                        spent_cents: spent_cents.unwrap_or(0).max(0) as u64,
+                        timeout_minutes: timeout_minutes.map(|m| m.max(0) as u64),
+                        retry_policy: retry_policy.and_then(|p| serde_json::from_str(&p).ok()),
+                        max_concurrent_messages: max_concurrent_messages
+                            .map(|n| n.clamp(1, u32::MAX as i64) as u32),
+                        tags: tags
+                            .and_then(|t| serde_json::from_str(&t).ok())
+                            .unwrap_or_default(),
+                        deliverable_checks: deliverable_checks
+                            .and_then(|c| serde_json::from_str(&c).ok())
+                            .unwrap_or_default(),
+                        verification: verification.and_then(|v| serde_json::from_str(&v).ok()),
+                        verifier: verifier.and_then(|v| serde_json::from_str(&v).ok()),
+                        stall_thresholds: stall_thresholds
+                            .and_then(|t| serde_json::from_str(&t).ok()),
+                        completion_report: completion_report
+                            .and_then(|r| serde_json::from_str(&r).ok()),
                     })
                 })
                 .map_err(|e| e.to_string())?
@@ -700,7 +883,9 @@ impl MissionStore for SqliteMissionStore {
                     "SELECT id, status, title, workspace_id, workspace_name, agent, model_override,
                             created_at, updated_at, interrupted_at, resumable, desktop_sessions,
                             COALESCE(backend, 'opencode') as backend, session_id, terminal_reason,
-                            config_profile
+                            config_profile, priority, budget_cents, spent_cents, timeout_minutes,
+                            retry_policy, max_concurrent_messages, tags, deliverable_checks,
+                            verification, verifier, stall_thresholds, completion_report
                      FROM missions WHERE id = ?1",
                 )
                 .map_err(|e| e.to_string())?;
@@ -715,6 +900,18 @@ impl MissionStore for SqliteMissionStore {
                     let session_id: Option<String> = row.get(13)?;
                     let terminal_reason: Option<String> = row.get(14)?;
                     let config_profile: Option<String> = row.get(15)?;
+                    let priority: Option<String> = row.get(16)?;
+                    let budget_cents: Option<i64> = row.get(17)?;
+                    let spent_cents: Option<i64> = row.get(18)?;
+                    let timeout_minutes: Option<i64> = row.get(19)?;
+                    let retry_policy: Option<String> = row.get(20)?;
+                    let max_concurrent_messages: Option<i64> = row.get(21)?;
+                    let tags: Option<String> = row.get(22)?;
+                    let deliverable_checks: Option<String> = row.get(23)?;
+                    let verification: Option<String> = row.get(24)?;
+                    let verifier: Option<String> = row.get(25)?;
+                    let stall_thresholds: Option<String> = row.get(26)?;
+                    let completion_report: Option<String> = row.get(27)?;
 
                     Ok(Mission {
                         id: Uuid::parse_str(&id_str).unwrap_or_default(),
@@ -737,6 +934,25 @@ impl MissionStore for SqliteMissionStore {
                             .unwrap_or_default(),
                         session_id,
                         terminal_reason,
+                        priority: parse_priority(priority.as_deref()),
+                        budget_cents: budget_cents.map(|c| c.max(0) as u64),
+                        spent_cents: spent_cents.unwrap_or(0).max(0) as u64,
+                        timeout_minutes: timeout_minutes.map(|m| m.max(0) as u64),
+                        retry_policy: retry_policy.and_then(|p| serde_json::from_str(&p).ok()),
+                        max_concurrent_messages: max_concurrent_messages
+                            .map(|n| n.clamp(1, u32::MAX as i64) as u32),
+                        tags: tags
+                            .and_then(|t| serde_json::from_str(&t).ok())
+                            .unwrap_or_default(),
+                        deliverable_checks: deliverable_checks
+                            .and_then(|c| serde_json::from_str(&c).ok())
+                            .unwrap_or_default(),
+                        verification: verification.and_then(|v| serde_json::from_str(&v).ok()),
+                        verifier: verifier.and_then(|v| serde_json::from_str(&v).ok()),
+                        stall_thresholds: stall_thresholds
+                            .and_then(|t| serde_json::from_str(&t).ok()),
+                        completion_report: completion_report
+                            .and_then(|r| serde_json::from_str(&r).ok()),
                     })
                 })
                 .optional()
@@ -822,6 +1038,18 @@ impl MissionStore for SqliteMissionStore {
             desktop_sessions: Vec::new(),
             session_id: Some(session_id.clone()),
             terminal_reason: None,
+            priority: MissionPriority::default(),
+            budget_cents: None,
+            spent_cents: 0,
+            timeout_minutes: None,
+            retry_policy: None,
+            max_concurrent_messages: None,
+            tags: Default::default(),
+            deliverable_checks: Vec::new(),
+            verification: None,
+            verifier: None,
+            stall_thresholds: None,
+            completion_report: None,
         };
 
         let m = mission.clone();
@@ -967,6 +1195,325 @@ impl MissionStore for SqliteMissionStore {
         .map_err(|e| e.to_string())?
     }
 
+    async fn update_mission_priority(
+        &self,
+        id: Uuid,
+        priority: MissionPriority,
+    ) -> Result<(), String> {
+        let conn = self.conn.clone();
+        let now = now_string();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.blocking_lock();
+            let updated = conn
+                .execute(
+                    "UPDATE missions SET priority = ?1, updated_at = ?2 WHERE id = ?3",
+                    params![priority.to_string(), now, id.to_string()],
+                )
+                .map_err(|e| e.to_string())?;
+            if updated == 0 {
+                return Err(format!("Mission {} not found", id));
+            }
+            Ok(())
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    }
+
+    async fn update_mission_budget(
+        &self,
+        id: Uuid,
+        budget_cents: Option<u64>,
+    ) -> Result<(), String> {
+        let conn = self.conn.clone();
+        let now = now_string();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.blocking_lock();
+            let updated = conn
+                .execute(
+                    "UPDATE missions SET budget_cents = ?1, updated_at = ?2 WHERE id = ?3",
+                    params![budget_cents.map(|c| c as i64), now, id.to_string()],
+                )
+                .map_err(|e| e.to_string())?;
+            if updated == 0 {
+                return Err(format!("Mission {} not found", id));
+            }
+            Ok(())
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    }
+
+    async fn update_mission_timeout(
+        &self,
+        id: Uuid,
+        timeout_minutes: Option<u64>,
+    ) -> Result<(), String> {
+        let conn = self.conn.clone();
+        let now = now_string();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.blocking_lock();
+            let updated = conn
+                .execute(
+                    "UPDATE missions SET timeout_minutes = ?1, updated_at = ?2 WHERE id = ?3",
+                    params![timeout_minutes.map(|m| m as i64), now, id.to_string()],
+                )
+                .map_err(|e| e.to_string())?;
+            if updated == 0 {
+                return Err(format!("Mission {} not found", id));
+            }
+            Ok(())
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    }
+
+    async fn update_mission_retry_policy(
+        &self,
+        id: Uuid,
+        retry_policy: Option<RetryPolicy>,
+    ) -> Result<(), String> {
+        let conn = self.conn.clone();
+        let now = now_string();
+        let retry_policy_json = retry_policy
+            .map(|p| serde_json::to_string(&p))
+            .transpose()
+            .map_err(|e| e.to_string())?;
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.blocking_lock();
+            let updated = conn
+                .execute(
+                    "UPDATE missions SET retry_policy = ?1, updated_at = ?2 WHERE id = ?3",
+                    params![retry_policy_json, now, id.to_string()],
+                )
+                .map_err(|e| e.to_string())?;
+            if updated == 0 {
+                return Err(format!("Mission {} not found", id));
+            }
+            Ok(())
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    }
+
+    async fn update_mission_concurrency(
+        &self,
+        id: Uuid,
+        max_concurrent_messages: Option<u32>,
+    ) -> Result<(), String> {
+        let conn = self.conn.clone();
+        let now = now_string();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.blocking_lock();
+            let updated = conn
+                .execute(
+                    "UPDATE missions SET max_concurrent_messages = ?1, updated_at = ?2 WHERE id = ?3",
+                    params![max_concurrent_messages, now, id.to_string()],
+                )
+                .map_err(|e| e.to_string())?;
+            if updated == 0 {
+                return Err(format!("Mission {} not found", id));
+            }
+            Ok(())
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    }
+
+    async fn update_mission_tags(
+        &self,
+        id: Uuid,
+        tags: &BTreeMap<String, String>,
+    ) -> Result<(), String> {
+        let conn = self.conn.clone();
+        let now = now_string();
+        let tags_json = if tags.is_empty() {
+            None
+        } else {
+            Some(serde_json::to_string(tags).map_err(|e| e.to_string())?)
+        };
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.blocking_lock();
+            let updated = conn
+                .execute(
+                    "UPDATE missions SET tags = ?1, updated_at = ?2 WHERE id = ?3",
+                    params![tags_json, now, id.to_string()],
+                )
+                .map_err(|e| e.to_string())?;
+            if updated == 0 {
+                return Err(format!("Mission {} not found", id));
+            }
+            Ok(())
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    }
+
+    async fn update_mission_deliverable_checks(
+        &self,
+        id: Uuid,
+        checks: &[DeliverableCheck],
+    ) -> Result<(), String> {
+        let conn = self.conn.clone();
+        let now = now_string();
+        let checks_json = if checks.is_empty() {
+            None
+        } else {
+            Some(serde_json::to_string(checks).map_err(|e| e.to_string())?)
+        };
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.blocking_lock();
+            let updated = conn
+                .execute(
+                    "UPDATE missions SET deliverable_checks = ?1, updated_at = ?2 WHERE id = ?3",
+                    params![checks_json, now, id.to_string()],
+                )
+                .map_err(|e| e.to_string())?;
+            if updated == 0 {
+                return Err(format!("Mission {} not found", id));
+            }
+            Ok(())
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    }
+
+    async fn update_mission_verification(
+        &self,
+        id: Uuid,
+        report: &VerificationReport,
+    ) -> Result<(), String> {
+        let conn = self.conn.clone();
+        let report_json = serde_json::to_string(report).map_err(|e| e.to_string())?;
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.blocking_lock();
+            let updated = conn
+                .execute(
+                    "UPDATE missions SET verification = ?1 WHERE id = ?2",
+                    params![report_json, id.to_string()],
+                )
+                .map_err(|e| e.to_string())?;
+            if updated == 0 {
+                return Err(format!("Mission {} not found", id));
+            }
+            Ok(())
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    }
+
+    async fn update_mission_verifier(
+        &self,
+        id: Uuid,
+        verifier: Option<MissionVerifier>,
+    ) -> Result<(), String> {
+        let conn = self.conn.clone();
+        let now = now_string();
+        let verifier_json = verifier
+            .map(|v| serde_json::to_string(&v))
+            .transpose()
+            .map_err(|e| e.to_string())?;
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.blocking_lock();
+            let updated = conn
+                .execute(
+                    "UPDATE missions SET verifier = ?1, updated_at = ?2 WHERE id = ?3",
+                    params![verifier_json, now, id.to_string()],
+                )
+                .map_err(|e| e.to_string())?;
+            if updated == 0 {
+                return Err(format!("Mission {} not found", id));
+            }
+            Ok(())
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    }
+
+    async fn update_mission_stall_thresholds(
+        &self,
+        id: Uuid,
+        thresholds: Option<StallThresholds>,
+    ) -> Result<(), String> {
+        let conn = self.conn.clone();
+        let now = now_string();
+        let thresholds_json = thresholds
+            .map(|v| serde_json::to_string(&v))
+            .transpose()
+            .map_err(|e| e.to_string())?;
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.blocking_lock();
+            let updated = conn
+                .execute(
+                    "UPDATE missions SET stall_thresholds = ?1, updated_at = ?2 WHERE id = ?3",
+                    params![thresholds_json, now, id.to_string()],
+                )
+                .map_err(|e| e.to_string())?;
+            if updated == 0 {
+                return Err(format!("Mission {} not found", id));
+            }
+            Ok(())
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    }
+
+    async fn update_mission_completion_report(
+        &self,
+        id: Uuid,
+        report: &CompletionReport,
+    ) -> Result<(), String> {
+        let conn = self.conn.clone();
+        let now = now_string();
+        let report_json = serde_json::to_string(report).map_err(|e| e.to_string())?;
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.blocking_lock();
+            let updated = conn
+                .execute(
+                    "UPDATE missions SET completion_report = ?1, updated_at = ?2 WHERE id = ?3",
+                    params![report_json, now, id.to_string()],
+                )
+                .map_err(|e| e.to_string())?;
+            if updated == 0 {
+                return Err(format!("Mission {} not found", id));
+            }
+            Ok(())
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    }
+
+    async fn add_mission_cost(&self, id: Uuid, cost_cents: u64) -> Result<u64, String> {
+        let conn = self.conn.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.blocking_lock();
+            let spent: i64 = conn
+                .query_row(
+                    "UPDATE missions SET spent_cents = spent_cents + ?1 WHERE id = ?2
+                     RETURNING spent_cents",
+                    params![cost_cents as i64, id.to_string()],
+                    |row| row.get(0),
+                )
+                .optional()
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("Mission {} not found", id))?;
+            Ok(spent.max(0) as u64)
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    }
+
     async fn update_mission_session_id(&self, id: Uuid, session_id: &str) -> Result<(), String> {
         let conn = self.conn.clone();
         let now = now_string();
@@ -1137,6 +1684,18 @@ impl MissionStore for SqliteMissionStore {
                             .unwrap_or_default(),
                         session_id: None, // Not needed for stale mission checks
                         terminal_reason: None,
+                        priority: MissionPriority::default(),
+                        budget_cents: None,
+                        spent_cents: 0,
+                        timeout_minutes: None,
+                        retry_policy: None,
+                        max_concurrent_messages: None,
+                        tags: Default::default(),
+                        deliverable_checks: Vec::new(),
+                        verification: None,
+                        verifier: None,
+                        stall_thresholds: None,
+                        completion_report: None,
                     })
                 })
                 .map_err(|e| e.to_string())?
@@ -1193,6 +1752,18 @@ impl MissionStore for SqliteMissionStore {
                             .unwrap_or_default(),
                         session_id: None,
                         terminal_reason: None,
+                        priority: MissionPriority::default(),
+                        budget_cents: None,
+                        spent_cents: 0,
+                        timeout_minutes: None,
+                        retry_policy: None,
+                        max_concurrent_messages: None,
+                        tags: Default::default(),
+                        deliverable_checks: Vec::new(),
+                        verification: None,
+                        verifier: None,
+                        stall_thresholds: None,
+                        completion_report: None,
                     })
                 })
                 .map_err(|e| e.to_string())?
@@ -1291,6 +1862,19 @@ impl MissionStore for SqliteMissionStore {
                 content.clone(),
                 serde_json::json!({ "done": done }),
             ),
+            AgentEvent::Diagnostic {
+                level,
+                source,
+                message,
+                ..
+            } if *level != DiagnosticLevel::Info => (
+                "diagnostic",
+                None,
+                None,
+                None,
+                message.clone(),
+                serde_json::json!({ "level": level, "source": source }),
+            ),
             AgentEvent::ToolCall {
                 tool_call_id,
                 name,
@@ -1345,13 +1929,96 @@ impl MissionStore for SqliteMissionStore {
                 summary.clone().unwrap_or_default(),
                 serde_json::json!({ "status": status.to_string() }),
             ),
+            AgentEvent::BudgetWarning {
+                percent,
+                spent_cents,
+                budget_cents,
+                ..
+            } => (
+                "budget_warning",
+                None,
+                None,
+                None,
+                format!("{}% of the mission budget used", percent),
+                serde_json::json!({
+                    "percent": percent,
+                    "spent_cents": spent_cents,
+                    "budget_cents": budget_cents,
+                }),
+            ),
+            AgentEvent::StallRecovery {
+                action,
+                step,
+                seconds_since_activity,
+                message,
+                ..
+            } => (
+                "stall_recovery",
+                None,
+                None,
+                None,
+                message.clone(),
+                serde_json::json!({
+                    "action": action.as_str(),
+                    "step": step,
+                    "seconds_since_activity": seconds_since_activity,
+                }),
+            ),
+            AgentEvent::MissionPaused { paused, .. } => (
+                "mission_paused",
+                None,
+                None,
+                None,
+                if *paused {
+                    "Mission paused".to_string()
+                } else {
+                    "Mission resumed".to_string()
+                },
+                serde_json::json!({ "paused": paused }),
+            ),
+            AgentEvent::DeliverablesVerified { report, .. } => (
+                "deliverables_verified",
+                None,
+                None,
+                None,
+                format!(
+                    "{} of {} deliverable checks passed",
+                    report.results.len() - report.failed().count(),
+                    report.results.len()
+                ),
+                serde_json::json!({ "passed": report.passed }),
+            ),
+            AgentEvent::VerifierReviewed {
+                verdict, follow_up, ..
+            } => (
+                "verifier_reviewed",
+                None,
+                None,
+                None,
+                if verdict.passed {
+                    format!("Verifier ({}) passed the mission", verdict.model)
+                } else {
+                    format!(
+                        "Verifier ({}) rejected the mission: {}",
+                        verdict.model, verdict.feedback
+                    )
+                },
+                serde_json::json!({
+                    "passed": verdict.passed,
+                    "model": verdict.model,
+                    "follow_up": follow_up,
+                }),
+            ),
             // Skip events that are less important for debugging
             AgentEvent::Status { .. }
+            | AgentEvent::Diagnostic { .. }
             | AgentEvent::AgentPhase { .. }
             | AgentEvent::AgentTree { .. }
             | AgentEvent::Progress { .. }
             | AgentEvent::SessionIdUpdate { .. }
-            | AgentEvent::MissionActivity { .. } => return Ok(()),
+            | AgentEvent::MissionActivity { .. }
+            | AgentEvent::MissionProgress { .. }
+            | AgentEvent::Heartbeat { .. } => return Ok(()),
         };
 
         let event_type = event_type.to_string();
@@ -1515,6 +2182,81 @@ impl MissionStore for SqliteMissionStore {
         .map_err(|e| e.to_string())?
     }
 
+    async fn raw_event_size(&self, mission_id: Uuid) -> Result<(u64, u64), String> {
+        let conn = self.conn.clone();
+        let mid = mission_id.to_string();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.blocking_lock();
+            let mut stmt = conn
+                .prepare(
+                    "SELECT COALESCE(LENGTH(content), 0) + COALESCE(LENGTH(metadata), 0), content_file
+                     FROM mission_events
+                     WHERE mission_id = ?1 AND event_type NOT IN ('user_message', 'assistant_message')",
+                )
+                .map_err(|e| e.to_string())?;
+            let rows = stmt
+                .query_map(params![&mid], |row| {
+                    Ok((row.get::<_, i64>(0)?, row.get::<_, Option<String>>(1)?))
+                })
+                .map_err(|e| e.to_string())?;
+
+            let (mut count, mut bytes) = (0u64, 0u64);
+            for row in rows {
+                let (inline, content_file) = row.map_err(|e| e.to_string())?;
+                count += 1;
+                bytes += inline.max(0) as u64;
+                if let Some(path) = content_file {
+                    bytes += std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+                }
+            }
+            Ok((count, bytes))
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    }
+
+    async fn prune_raw_events(&self, mission_id: Uuid) -> Result<u64, String> {
+        let conn = self.conn.clone();
+        let mid = mission_id.to_string();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.blocking_lock();
+            let content_files: Vec<String> = {
+                let mut stmt = conn
+                    .prepare(
+                        "SELECT content_file FROM mission_events
+                         WHERE mission_id = ?1 AND content_file IS NOT NULL
+                           AND event_type NOT IN ('user_message', 'assistant_message')",
+                    )
+                    .map_err(|e| e.to_string())?;
+                let rows = stmt
+                    .query_map(params![&mid], |row| row.get::<_, String>(0))
+                    .map_err(|e| e.to_string())?;
+                rows.collect::<Result<_, _>>().map_err(|e| e.to_string())?
+            };
+
+            let removed = conn
+                .execute(
+                    "DELETE FROM mission_events
+                     WHERE mission_id = ?1 AND event_type NOT IN ('user_message', 'assistant_message')",
+                    params![&mid],
+                )
+                .map_err(|e| e.to_string())?;
+
+            for path in content_files {
+                if let Err(e) = std::fs::remove_file(&path) {
+                    if e.kind() != std::io::ErrorKind::NotFound {
+                        tracing::warn!("Failed to remove event content file {}: {}", path, e);
+                    }
+                }
+            }
+            Ok(removed as u64)
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    }
+
     async fn get_total_cost_cents(&self) -> Result<u64, String> {
         let conn = self.conn.lock().await;
 
@@ -1540,6 +2282,45 @@ impl MissionStore for SqliteMissionStore {
         Ok(total as u64)
     }
 
+    async fn get_turn_costs(
+        &self,
+        since: Option<&str>,
+        until: Option<&str>,
+    ) -> Result<Vec<TurnCost>, String> {
+        let conn = self.conn.lock().await;
+
+        let query = r#"
+            SELECT e.mission_id, m.title, COALESCE(m.backend, 'unknown'),
+                   json_extract(e.metadata, '$.model'),
+                   CAST(COALESCE(json_extract(e.metadata, '$.cost_cents'), 0) AS INTEGER),
+                   e.timestamp
+            FROM mission_events e
+            LEFT JOIN missions m ON m.id = e.mission_id
+            WHERE e.event_type = 'assistant_message'
+              AND (?1 IS NULL OR e.timestamp >= ?1)
+              AND (?2 IS NULL OR e.timestamp < ?2)
+            ORDER BY e.timestamp ASC
+        "#;
+
+        let mut stmt = conn.prepare(query).map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(params![since, until], |row| {
+                let mission_id: String = row.get(0)?;
+                let cost_cents: i64 = row.get(4)?;
+                Ok(TurnCost {
+                    mission_id: Uuid::parse_str(&mission_id).unwrap_or_default(),
+                    mission_title: row.get(1)?,
+                    backend: row.get(2)?,
+                    model: row.get(3)?,
+                    cost_cents: cost_cents.max(0) as u64,
+                    timestamp: row.get(5)?,
+                })
+            })
+            .map_err(|e| e.to_string())?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())
+    }
+
     async fn create_automation(&self, automation: Automation) -> Result<Automation, String> {
         let conn = self.conn.clone();
 
@@ -0,0 +1,533 @@
+//! Mission templates: saved prompts for repetitive missions.
+//!
+//! A template holds a prompt with `<name/>` placeholders, the mission
+//! defaults (backend, model, agent, workspace) and the files the mission is
+//! expected to produce. `POST /api/missions/from-template/:id` fills in the
+//! placeholders, creates the mission and sends it the prompt. Templates are
+//! stored in `{working_dir}/.sandboxed-sh/mission_templates.json`.
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path as FsPath, PathBuf};
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::get,
+    Extension, Json, Router,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use super::auth::AuthUser;
+use super::automation_variables::{substitute_variables, SubstitutionContext};
+use super::control::{self, ControlMessageRequest, CreateMissionRequest};
+use super::mission_store::Mission;
+use super::routes::AppState;
+
+/// Placeholders filled in without being passed as variables.
+const BUILTIN_VARIABLES: &[&str] = &[
+    "timestamp",
+    "date",
+    "unix_time",
+    "mission_id",
+    "mission_name",
+    "cwd",
+];
+
+/// A saved mission prompt with its defaults.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MissionTemplate {
+    pub id: Uuid,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Prompt sent to the mission; `<name/>` placeholders are filled in from
+    /// the variables given when the mission is created
+    pub prompt: String,
+    #[serde(default)]
+    pub workspace_id: Option<Uuid>,
+    #[serde(default)]
+    pub agent: Option<String>,
+    #[serde(default)]
+    pub backend: Option<String>,
+    #[serde(default)]
+    pub model_override: Option<String>,
+    /// Absolute paths of files the mission must produce
+    #[serde(default)]
+    pub deliverables: Vec<String>,
+    pub owner_id: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Fields accepted when creating a template.
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewMissionTemplate {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub prompt: String,
+    #[serde(default)]
+    pub workspace_id: Option<Uuid>,
+    #[serde(default)]
+    pub agent: Option<String>,
+    #[serde(default)]
+    pub backend: Option<String>,
+    #[serde(default, alias = "model")]
+    pub model_override: Option<String>,
+    #[serde(default)]
+    pub deliverables: Vec<String>,
+}
+
+/// Partial update of a template; absent fields are left unchanged.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MissionTemplateUpdate {
+    pub name: Option<String>,
+    /// `null` clears the field
+    #[serde(default, deserialize_with = "nullable")]
+    pub description: Option<Option<String>>,
+    pub prompt: Option<String>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub workspace_id: Option<Option<Uuid>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub agent: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub backend: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable", alias = "model")]
+    pub model_override: Option<Option<String>>,
+    pub deliverables: Option<Vec<String>>,
+}
+
+/// Distinguish an explicit `null` (`Some(None)`) from an absent field (`None`).
+fn nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// Body of `POST /api/missions/from-template/:id`. Set fields override the
+/// template's defaults.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CreateFromTemplateRequest {
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+    pub title: Option<String>,
+    pub workspace_id: Option<Uuid>,
+    pub agent: Option<String>,
+    pub backend: Option<String>,
+    #[serde(alias = "model")]
+    pub model_override: Option<String>,
+}
+
+/// Placeholders in `prompt` that are neither built in nor in `variables`.
+pub fn missing_variables(prompt: &str, variables: &HashMap<String, String>) -> Vec<String> {
+    let placeholder = regex::Regex::new(r"<([A-Za-z_][\w-]*)/>").expect("Invalid regex");
+    placeholder
+        .captures_iter(prompt)
+        .map(|caps| caps[1].to_string())
+        .filter(|name| !BUILTIN_VARIABLES.contains(&name.as_str()) && !variables.contains_key(name))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// The template's prompt with its deliverables listed after it, phrased so
+/// the mission runner picks them up as expected deliverables.
+fn prompt_with_deliverables(template: &MissionTemplate) -> String {
+    if template.deliverables.is_empty() {
+        return template.prompt.clone();
+    }
+    let list: Vec<String> = template
+        .deliverables
+        .iter()
+        .map(|path| format!("- Save the result to {}", path))
+        .collect();
+    format!(
+        "{}\n\nExpected deliverables:\n{}",
+        template.prompt.trim_end(),
+        list.join("\n")
+    )
+}
+
+fn validate(name: &str, prompt: &str, deliverables: &[String]) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("name is required".to_string());
+    }
+    if prompt.trim().is_empty() {
+        return Err("prompt is required".to_string());
+    }
+    if let Some(path) = deliverables.iter().find(|p| !p.starts_with('/')) {
+        return Err(format!("Deliverable '{}' must be an absolute path", path));
+    }
+    Ok(())
+}
+
+/// Templates persisted to disk.
+pub struct MissionTemplateStore {
+    templates: RwLock<Vec<MissionTemplate>>,
+    storage_path: PathBuf,
+}
+
+impl MissionTemplateStore {
+    /// Create a store, loading existing templates from disk.
+    pub async fn new(working_dir: &FsPath) -> Self {
+        let storage_path = working_dir.join(".sandboxed-sh/mission_templates.json");
+        let templates = match std::fs::read_to_string(&storage_path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                tracing::warn!(
+                    "Failed to parse mission templates from {}: {}",
+                    storage_path.display(),
+                    e
+                );
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+        Self {
+            templates: RwLock::new(templates),
+            storage_path,
+        }
+    }
+
+    async fn save_to_disk(&self, templates: &[MissionTemplate]) -> Result<(), String> {
+        if let Some(parent) = self.storage_path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let contents = serde_json::to_string_pretty(templates).map_err(|e| e.to_string())?;
+        tokio::fs::write(&self.storage_path, contents)
+            .await
+            .map_err(|e| format!("Failed to write {}: {}", self.storage_path.display(), e))
+    }
+
+    /// Templates owned by `owner_id`, by name.
+    pub async fn list(&self, owner_id: &str) -> Vec<MissionTemplate> {
+        let mut templates: Vec<MissionTemplate> = self
+            .templates
+            .read()
+            .await
+            .iter()
+            .filter(|t| t.owner_id == owner_id)
+            .cloned()
+            .collect();
+        templates.sort_by(|a, b| a.name.cmp(&b.name));
+        templates
+    }
+
+    pub async fn get(&self, id: Uuid) -> Option<MissionTemplate> {
+        self.templates
+            .read()
+            .await
+            .iter()
+            .find(|t| t.id == id)
+            .cloned()
+    }
+
+    pub async fn create(
+        &self,
+        owner_id: &str,
+        new: NewMissionTemplate,
+    ) -> Result<MissionTemplate, String> {
+        validate(&new.name, &new.prompt, &new.deliverables)?;
+        let now = Utc::now();
+        let template = MissionTemplate {
+            id: Uuid::new_v4(),
+            name: new.name.trim().to_string(),
+            description: new.description,
+            prompt: new.prompt,
+            workspace_id: new.workspace_id,
+            agent: new.agent,
+            backend: new.backend,
+            model_override: new.model_override,
+            deliverables: new.deliverables,
+            owner_id: owner_id.to_string(),
+            created_at: now,
+            updated_at: now,
+        };
+        let mut templates = self.templates.write().await;
+        templates.push(template.clone());
+        self.save_to_disk(&templates).await?;
+        Ok(template)
+    }
+
+    /// Apply a partial update. Returns `Ok(None)` if the template doesn't exist.
+    pub async fn update(
+        &self,
+        id: Uuid,
+        update: MissionTemplateUpdate,
+    ) -> Result<Option<MissionTemplate>, String> {
+        let mut templates = self.templates.write().await;
+        let Some(index) = templates.iter().position(|t| t.id == id) else {
+            return Ok(None);
+        };
+        let mut template = templates[index].clone();
+        if let Some(name) = update.name {
+            template.name = name.trim().to_string();
+        }
+        if let Some(description) = update.description {
+            template.description = description;
+        }
+        if let Some(prompt) = update.prompt {
+            template.prompt = prompt;
+        }
+        if let Some(workspace_id) = update.workspace_id {
+            template.workspace_id = workspace_id;
+        }
+        if let Some(agent) = update.agent {
+            template.agent = agent;
+        }
+        if let Some(backend) = update.backend {
+            template.backend = backend;
+        }
+        if let Some(model_override) = update.model_override {
+            template.model_override = model_override;
+        }
+        if let Some(deliverables) = update.deliverables {
+            template.deliverables = deliverables;
+        }
+        validate(&template.name, &template.prompt, &template.deliverables)?;
+        template.updated_at = Utc::now();
+
+        templates[index] = template.clone();
+        self.save_to_disk(&templates).await?;
+        Ok(Some(template))
+    }
+
+    /// Delete a template. Returns whether it existed.
+    pub async fn delete(&self, id: Uuid) -> Result<bool, String> {
+        let mut templates = self.templates.write().await;
+        let before = templates.len();
+        templates.retain(|t| t.id != id);
+        if templates.len() == before {
+            return Ok(false);
+        }
+        self.save_to_disk(&templates).await?;
+        Ok(true)
+    }
+}
+
+/// Create the template CRUD routes (nested under `/api/missions/templates`).
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(list_templates).post(create_template))
+        .route(
+            "/:id",
+            get(get_template)
+                .patch(update_template)
+                .delete(delete_template),
+        )
+}
+
+/// Load a template, hiding templates that belong to other users.
+async fn owned_template(
+    state: &AppState,
+    user: &AuthUser,
+    id: Uuid,
+) -> Result<MissionTemplate, (StatusCode, String)> {
+    state
+        .mission_templates
+        .get(id)
+        .await
+        .filter(|t| t.owner_id == user.id)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Template {} not found", id)))
+}
+
+/// GET /api/missions/templates
+async fn list_templates(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+) -> Json<Vec<MissionTemplate>> {
+    Json(state.mission_templates.list(&user.id).await)
+}
+
+/// POST /api/missions/templates
+async fn create_template(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Json(req): Json<NewMissionTemplate>,
+) -> Result<Json<MissionTemplate>, (StatusCode, String)> {
+    state
+        .mission_templates
+        .create(&user.id, req)
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))
+}
+
+/// GET /api/missions/templates/:id
+async fn get_template(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<MissionTemplate>, (StatusCode, String)> {
+    owned_template(&state, &user, id).await.map(Json)
+}
+
+/// PATCH /api/missions/templates/:id
+async fn update_template(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<MissionTemplateUpdate>,
+) -> Result<Json<MissionTemplate>, (StatusCode, String)> {
+    owned_template(&state, &user, id).await?;
+    state
+        .mission_templates
+        .update(id, req)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?
+        .map(Json)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Template {} not found", id)))
+}
+
+/// DELETE /api/missions/templates/:id
+async fn delete_template(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    owned_template(&state, &user, id).await?;
+    state
+        .mission_templates
+        .delete(id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /api/missions/from-template/:id
+/// Create a mission from a template and send it the filled-in prompt.
+pub async fn create_from_template(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+    body: Option<Json<CreateFromTemplateRequest>>,
+) -> Result<Json<Mission>, (StatusCode, String)> {
+    let template = owned_template(&state, &user, id).await?;
+    let req = body.map(|Json(req)| req).unwrap_or_default();
+
+    let prompt = prompt_with_deliverables(&template);
+    let missing = missing_variables(&prompt, &req.variables);
+    if !missing.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("Missing template variables: {}", missing.join(", ")),
+        ));
+    }
+
+    let title = req.title.unwrap_or_else(|| template.name.clone());
+    let Json(mission) = control::create_mission(
+        State(Arc::clone(&state)),
+        Extension(user.clone()),
+        Some(Json(CreateMissionRequest {
+            title: Some(title.clone()),
+            workspace_id: req.workspace_id.or(template.workspace_id),
+            agent: req.agent.or(template.agent),
+            model_override: req.model_override.or(template.model_override),
+            config_profile: None,
+            backend: req.backend.or(template.backend),
+            priority: None,
+            budget_cents: None,
+            timeout_minutes: None,
+            retry_policy: None,
+            max_concurrent_messages: None,
+            tags: None,
+            deliverable_checks: None,
+            verifier: None,
+            stall_thresholds: None,
+        })),
+    )
+    .await?;
+
+    let context = SubstitutionContext::new(mission.id)
+        .with_mission_name(title)
+        .with_custom_variables(req.variables);
+    let _ = control::post_message(
+        State(Arc::clone(&state)),
+        Extension(user),
+        Json(ControlMessageRequest {
+            content: substitute_variables(&prompt, &context),
+            agent: None,
+            mission_id: Some(mission.id),
+            priority: None,
+        }),
+    )
+    .await?;
+
+    tracing::info!(
+        template_id = %template.id,
+        mission_id = %mission.id,
+        "Created mission from template '{}'",
+        template.name
+    );
+    Ok(Json(mission))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_template() -> NewMissionTemplate {
+        NewMissionTemplate {
+            name: "Release notes".to_string(),
+            description: None,
+            prompt: "Write release notes for <repo/> since <tag/> on <date/>".to_string(),
+            workspace_id: None,
+            agent: None,
+            backend: Some("claudecode".to_string()),
+            model_override: None,
+            deliverables: vec!["/root/work/NOTES.md".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_missing_variables() {
+        let vars = HashMap::from([("repo".to_string(), "sandboxed.sh".to_string())]);
+        let prompt = "Write release notes for <repo/> since <tag/> on <date/>, then <tag/>";
+        assert_eq!(missing_variables(prompt, &vars), vec!["tag"]);
+        assert!(missing_variables("<mission_id/> <cwd/>", &HashMap::new()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_templates_persist() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = MissionTemplateStore::new(dir.path()).await;
+
+        let mut relative = new_template();
+        relative.deliverables = vec!["NOTES.md".to_string()];
+        assert!(store.create("u1", relative).await.is_err());
+
+        let template = store.create("u1", new_template()).await.unwrap();
+        let prompt = prompt_with_deliverables(&template);
+        let deliverables = crate::task::extract_deliverables(&prompt);
+        assert_eq!(
+            deliverables.deliverables[0].path().unwrap(),
+            &PathBuf::from("/root/work/NOTES.md")
+        );
+
+        let reloaded = MissionTemplateStore::new(dir.path()).await;
+        assert_eq!(reloaded.list("u1").await.len(), 1);
+        assert!(reloaded.list("u2").await.is_empty());
+
+        let updated = reloaded
+            .update(
+                template.id,
+                MissionTemplateUpdate {
+                    backend: Some(None),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(updated.backend, None);
+        assert!(reloaded.delete(template.id).await.unwrap());
+        assert!(!reloaded.delete(template.id).await.unwrap());
+    }
+}
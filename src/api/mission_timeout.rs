@@ -0,0 +1,82 @@
+//! Wall-clock limits for mission runs.
+//!
+//! A run starts when a mission begins executing and lasts while it has a turn
+//! in flight or messages queued; a follow-up sent after the mission went idle
+//! starts a new run. When a run exceeds its limit (the mission's
+//! `timeout_minutes`, else `MISSION_TIMEOUT_MINUTES`) the backend is
+//! cancelled and the mission fails with `TerminalReason::TimedOut`. Its
+//! history and events are kept. Unlike the stall heuristic, this also stops
+//! missions that are busy but never finish.
+
+use std::time::{Duration, Instant};
+
+/// Limit for a mission: its own override if set, else the server default.
+/// `None` (no limit) when the applicable value is 0.
+pub fn effective_timeout(mission_minutes: Option<u64>, default_minutes: u64) -> Option<Duration> {
+    let minutes = mission_minutes.unwrap_or(default_minutes);
+    (minutes > 0).then(|| Duration::from_secs(minutes.saturating_mul(60)))
+}
+
+/// An in-progress mission run.
+#[derive(Debug, Clone, Copy)]
+pub struct MissionRun {
+    pub started: Instant,
+    pub limit: Option<Duration>,
+    /// The run was cancelled for exceeding its limit
+    pub timed_out: bool,
+}
+
+impl MissionRun {
+    pub fn start(limit: Option<Duration>) -> Self {
+        Self {
+            started: Instant::now(),
+            limit,
+            timed_out: false,
+        }
+    }
+
+    /// Whether the run is over its limit and hasn't been stopped yet.
+    pub fn should_stop(&self) -> bool {
+        !self.timed_out
+            && self
+                .limit
+                .is_some_and(|limit| self.started.elapsed() >= limit)
+    }
+}
+
+/// Human-readable limit, e.g. `90 minutes`.
+pub fn format_limit(limit: Duration) -> String {
+    let minutes = limit.as_secs() / 60;
+    if minutes == 1 {
+        "1 minute".to_string()
+    } else {
+        format!("{} minutes", minutes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_effective_timeout() {
+        assert_eq!(effective_timeout(None, 0), None);
+        assert_eq!(effective_timeout(None, 30), Some(Duration::from_secs(1800)));
+        assert_eq!(
+            effective_timeout(Some(5), 30),
+            Some(Duration::from_secs(300))
+        );
+        // A mission can opt out of the server default.
+        assert_eq!(effective_timeout(Some(0), 30), None);
+    }
+
+    #[test]
+    fn test_run_should_stop() {
+        let mut run = MissionRun::start(Some(Duration::ZERO));
+        assert!(run.should_stop());
+        run.timed_out = true;
+        assert!(!run.should_stop());
+        assert!(!MissionRun::start(None).should_stop());
+        assert_eq!(format_limit(Duration::from_secs(5400)), "90 minutes");
+    }
+}
@@ -0,0 +1,517 @@
+//! Mission transcript export.
+//!
+//! Renders a mission's stored events as a self-contained document: the
+//! conversation, tool calls paired with their results, thinking, errors and
+//! status changes, per-turn and total costs, and the deliverables and
+//! artifacts the mission produced. Served as Markdown (for sharing outside
+//! the dashboard) or JSON. Stores without an event log fall back to the
+//! mission's user/assistant history.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::mission_artifacts::{resolve_artifact_path, MissionArtifact};
+use super::mission_budget::format_cents;
+use super::mission_store::{Mission, StoredEvent};
+use crate::tools::safe_truncate_index;
+use crate::workspace::Workspace;
+
+/// Tool results longer than this are cut in the Markdown rendering.
+const MAX_MARKDOWN_RESULT_BYTES: usize = 4000;
+
+/// Output format of the transcript endpoint.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptFormat {
+    #[default]
+    #[serde(alias = "markdown")]
+    Md,
+    Json,
+}
+
+/// A deliverable requested in the mission's first message.
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscriptDeliverable {
+    pub path: String,
+    pub exists: bool,
+}
+
+/// One step of the mission, in order.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TranscriptEntry {
+    User {
+        timestamp: Option<String>,
+        content: String,
+    },
+    Assistant {
+        timestamp: Option<String>,
+        content: String,
+        model: Option<String>,
+        cost_cents: u64,
+        success: bool,
+    },
+    Thinking {
+        timestamp: Option<String>,
+        content: String,
+    },
+    ToolCall {
+        timestamp: Option<String>,
+        tool_call_id: Option<String>,
+        name: String,
+        args: serde_json::Value,
+        result: Option<String>,
+    },
+    Error {
+        timestamp: Option<String>,
+        message: String,
+    },
+    Status {
+        timestamp: Option<String>,
+        status: String,
+        summary: Option<String>,
+    },
+}
+
+/// A rendered mission.
+#[derive(Debug, Clone, Serialize)]
+pub struct Transcript {
+    pub mission_id: Uuid,
+    pub title: Option<String>,
+    pub status: String,
+    pub backend: String,
+    pub workspace_name: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub total_cost_cents: u64,
+    pub entries: Vec<TranscriptEntry>,
+    pub deliverables: Vec<TranscriptDeliverable>,
+    pub artifacts: Vec<MissionArtifact>,
+}
+
+/// The deliverables requested in the mission's first message, and whether
+/// each exists in the mission's workspace.
+pub fn requested_deliverables(
+    mission: &Mission,
+    workspace: &Workspace,
+) -> Vec<TranscriptDeliverable> {
+    let Some(first) = mission.history.iter().find(|entry| entry.role == "user") else {
+        return Vec::new();
+    };
+    crate::task::extract_deliverables(&first.content)
+        .deliverables
+        .iter()
+        .filter_map(|deliverable| deliverable.path())
+        .map(|path| {
+            let path = path.display().to_string();
+            let exists = resolve_artifact_path(workspace, mission.id, &path).exists();
+            TranscriptDeliverable { path, exists }
+        })
+        .collect()
+}
+
+impl Transcript {
+    /// Assemble a transcript from the mission's events (oldest first).
+    pub fn build(
+        mission: &Mission,
+        events: &[StoredEvent],
+        deliverables: Vec<TranscriptDeliverable>,
+        artifacts: Vec<MissionArtifact>,
+    ) -> Self {
+        let entries = if events.is_empty() {
+            history_entries(mission)
+        } else {
+            event_entries(events)
+        };
+        let total_cost_cents = entries
+            .iter()
+            .map(|entry| match entry {
+                TranscriptEntry::Assistant { cost_cents, .. } => *cost_cents,
+                _ => 0,
+            })
+            .sum();
+        Self {
+            mission_id: mission.id,
+            title: mission.title.clone(),
+            status: mission.status.to_string(),
+            backend: mission.backend.clone(),
+            workspace_name: mission.workspace_name.clone(),
+            created_at: mission.created_at.clone(),
+            updated_at: mission.updated_at.clone(),
+            total_cost_cents,
+            entries,
+            deliverables,
+            artifacts,
+        }
+    }
+
+    /// Render as a Markdown document. Thinking and tool results are folded
+    /// into `<details>` blocks.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let title = self.title.as_deref().unwrap_or("Untitled mission");
+        out.push_str(&format!("# {}\n\n", title));
+        out.push_str(&format!("- **Mission:** `{}`\n", self.mission_id));
+        out.push_str(&format!("- **Status:** {}\n", self.status));
+        out.push_str(&format!("- **Backend:** {}\n", self.backend));
+        if let Some(workspace) = &self.workspace_name {
+            out.push_str(&format!("- **Workspace:** {}\n", workspace));
+        }
+        out.push_str(&format!("- **Created:** {}\n", self.created_at));
+        out.push_str(&format!("- **Updated:** {}\n", self.updated_at));
+        out.push_str(&format!(
+            "- **Total cost:** {}\n",
+            format_cents(self.total_cost_cents)
+        ));
+
+        out.push_str("\n## Conversation\n");
+        for entry in &self.entries {
+            out.push('\n');
+            match entry {
+                TranscriptEntry::User { content, .. } => {
+                    out.push_str("### User\n\n");
+                    out.push_str(content.trim_end());
+                    out.push('\n');
+                }
+                TranscriptEntry::Assistant {
+                    content,
+                    model,
+                    cost_cents,
+                    success,
+                    ..
+                } => {
+                    out.push_str("### Assistant\n\n");
+                    let mut meta = Vec::new();
+                    if let Some(model) = model {
+                        meta.push(format!("model: {}", model));
+                    }
+                    if *cost_cents > 0 {
+                        meta.push(format!("cost: {}", format_cents(*cost_cents)));
+                    }
+                    if !success {
+                        meta.push("failed".to_string());
+                    }
+                    if !meta.is_empty() {
+                        out.push_str(&format!("_{}_\n\n", meta.join(" · ")));
+                    }
+                    out.push_str(content.trim_end());
+                    out.push('\n');
+                }
+                TranscriptEntry::Thinking { content, .. } => {
+                    out.push_str("<details>\n<summary>Thinking</summary>\n\n");
+                    out.push_str(content.trim_end());
+                    out.push_str("\n\n</details>\n");
+                }
+                TranscriptEntry::ToolCall {
+                    name, args, result, ..
+                } => {
+                    out.push_str(&format!("**Tool call:** `{}`\n\n", name));
+                    let args = serde_json::to_string_pretty(args).unwrap_or_default();
+                    out.push_str(&code_block("json", &args));
+                    if let Some(result) = result {
+                        out.push_str("\n<details>\n<summary>Result</summary>\n\n");
+                        let end = safe_truncate_index(result, MAX_MARKDOWN_RESULT_BYTES);
+                        let mut shown = result[..end].to_string();
+                        if end < result.len() {
+                            shown.push_str(&format!("\n… ({} bytes omitted)", result.len() - end));
+                        }
+                        out.push_str(&code_block("", &shown));
+                        out.push_str("\n</details>\n");
+                    }
+                }
+                TranscriptEntry::Error { message, .. } => {
+                    out.push_str(&format!("> **Error:** {}\n", message.trim_end()));
+                }
+                TranscriptEntry::Status {
+                    status, summary, ..
+                } => {
+                    out.push_str(&format!("_Status changed to **{}**_", status));
+                    if let Some(summary) = summary {
+                        out.push_str(&format!(": {}", summary.trim_end()));
+                    }
+                    out.push('\n');
+                }
+            }
+        }
+
+        if !self.deliverables.is_empty() {
+            out.push_str("\n## Deliverables\n\n");
+            for deliverable in &self.deliverables {
+                let mark = if deliverable.exists { "x" } else { " " };
+                out.push_str(&format!("- [{}] `{}`\n", mark, deliverable.path));
+            }
+        }
+
+        if !self.artifacts.is_empty() {
+            out.push_str("\n## Artifacts\n\n");
+            out.push_str("| Name | Size | SHA-256 | Description |\n");
+            out.push_str("| --- | --- | --- | --- |\n");
+            for artifact in &self.artifacts {
+                out.push_str(&format!(
+                    "| `{}` | {} B | `{}` | {} |\n",
+                    artifact.name,
+                    artifact.size,
+                    &artifact.sha256[..artifact.sha256.len().min(12)],
+                    artifact
+                        .description
+                        .as_deref()
+                        .unwrap_or("")
+                        .replace('|', "\\|")
+                ));
+            }
+        }
+        out
+    }
+}
+
+/// Fenced code block whose fence is longer than any backtick run in `content`.
+fn code_block(lang: &str, content: &str) -> String {
+    let longest_run = content.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    format!("{}{}\n{}\n{}\n", fence, lang, content.trim_end(), fence)
+}
+
+fn history_entries(mission: &Mission) -> Vec<TranscriptEntry> {
+    mission
+        .history
+        .iter()
+        .map(|entry| match entry.role.as_str() {
+            "user" => TranscriptEntry::User {
+                timestamp: None,
+                content: entry.content.clone(),
+            },
+            _ => TranscriptEntry::Assistant {
+                timestamp: None,
+                content: entry.content.clone(),
+                model: None,
+                cost_cents: 0,
+                success: true,
+            },
+        })
+        .collect()
+}
+
+fn event_entries(events: &[StoredEvent]) -> Vec<TranscriptEntry> {
+    let mut entries: Vec<TranscriptEntry> = Vec::new();
+    // Index of each tool call's entry, to attach its result
+    let mut tool_calls: HashMap<String, usize> = HashMap::new();
+
+    for event in events {
+        let timestamp = Some(event.timestamp.clone());
+        match event.event_type.as_str() {
+            "user_message" => entries.push(TranscriptEntry::User {
+                timestamp,
+                content: event.content.clone(),
+            }),
+            "assistant_message" => entries.push(TranscriptEntry::Assistant {
+                timestamp,
+                content: event.content.clone(),
+                model: event
+                    .metadata
+                    .get("model")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string),
+                cost_cents: event
+                    .metadata
+                    .get("cost_cents")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(0),
+                success: event
+                    .metadata
+                    .get("success")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(true),
+            }),
+            "thinking" => {
+                // Thinking is streamed as a growing buffer; keep the latest.
+                if let Some(TranscriptEntry::Thinking { content, .. }) = entries.last_mut() {
+                    if event.content.starts_with(content.as_str()) {
+                        *content = event.content.clone();
+                        continue;
+                    }
+                    if event.content.trim().is_empty() {
+                        continue;
+                    }
+                }
+                if !event.content.trim().is_empty() {
+                    entries.push(TranscriptEntry::Thinking {
+                        timestamp,
+                        content: event.content.clone(),
+                    });
+                }
+            }
+            "tool_call" => {
+                if let Some(id) = &event.tool_call_id {
+                    tool_calls.insert(id.clone(), entries.len());
+                }
+                entries.push(TranscriptEntry::ToolCall {
+                    timestamp,
+                    tool_call_id: event.tool_call_id.clone(),
+                    name: event.tool_name.clone().unwrap_or_default(),
+                    args: serde_json::from_str(&event.content)
+                        .unwrap_or_else(|_| serde_json::Value::String(event.content.clone())),
+                    result: None,
+                });
+            }
+            "tool_result" => {
+                let index = event
+                    .tool_call_id
+                    .as_ref()
+                    .and_then(|id| tool_calls.get(id));
+                match index.and_then(|i| entries.get_mut(*i)) {
+                    Some(TranscriptEntry::ToolCall { result, .. }) => {
+                        *result = Some(event.content.clone());
+                    }
+                    _ => entries.push(TranscriptEntry::ToolCall {
+                        timestamp,
+                        tool_call_id: event.tool_call_id.clone(),
+                        name: event.tool_name.clone().unwrap_or_default(),
+                        args: serde_json::Value::Null,
+                        result: Some(event.content.clone()),
+                    }),
+                }
+            }
+            "error" => entries.push(TranscriptEntry::Error {
+                timestamp,
+                message: event.content.clone(),
+            }),
+            "mission_status_changed" => entries.push(TranscriptEntry::Status {
+                timestamp,
+                status: event
+                    .metadata
+                    .get("status")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("unknown")
+                    .to_string(),
+                summary: Some(event.content.clone()).filter(|s| !s.is_empty()),
+            }),
+            _ => {}
+        }
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::control::MissionStatus;
+    use crate::api::mission_store::MissionHistoryEntry;
+
+    fn event(
+        sequence: i64,
+        event_type: &str,
+        content: &str,
+        tool_call_id: Option<&str>,
+        metadata: serde_json::Value,
+    ) -> StoredEvent {
+        StoredEvent {
+            id: sequence,
+            mission_id: Uuid::nil(),
+            sequence,
+            event_type: event_type.to_string(),
+            timestamp: "2026-01-01T00:00:00Z".to_string(),
+            event_id: None,
+            tool_call_id: tool_call_id.map(str::to_string),
+            tool_name: tool_call_id.map(|_| "read_file".to_string()),
+            content: content.to_string(),
+            metadata,
+        }
+    }
+
+    fn mission() -> Mission {
+        let mut mission: Mission = serde_json::from_value(serde_json::json!({
+            "id": Uuid::nil(),
+            "status": "completed",
+            "workspace_id": Uuid::nil(),
+            "backend": "claudecode",
+            "history": [],
+            "created_at": "2026-01-01T00:00:00Z",
+            "updated_at": "2026-01-01T00:00:00Z",
+        }))
+        .unwrap();
+        mission.title = Some("Report".to_string());
+        mission
+    }
+
+    #[test]
+    fn test_events_render_in_order() {
+        let events = vec![
+            event(
+                1,
+                "user_message",
+                "Write the report",
+                None,
+                serde_json::json!({}),
+            ),
+            event(2, "thinking", "Let me", None, serde_json::json!({})),
+            event(3, "thinking", "Let me look", None, serde_json::json!({})),
+            event(
+                4,
+                "tool_call",
+                r#"{"path":"a.md"}"#,
+                Some("t1"),
+                serde_json::json!({}),
+            ),
+            event(
+                5,
+                "tool_result",
+                "```contents```",
+                Some("t1"),
+                serde_json::json!({}),
+            ),
+            event(
+                6,
+                "assistant_message",
+                "Done",
+                None,
+                serde_json::json!({ "cost_cents": 42, "success": true, "model": "m" }),
+            ),
+        ];
+        let transcript = Transcript::build(&mission(), &events, Vec::new(), Vec::new());
+        assert_eq!(transcript.entries.len(), 4);
+        assert_eq!(transcript.total_cost_cents, 42);
+        assert!(matches!(
+            &transcript.entries[1],
+            TranscriptEntry::Thinking { content, .. } if content == "Let me look"
+        ));
+        assert!(matches!(
+            &transcript.entries[2],
+            TranscriptEntry::ToolCall { result: Some(r), .. } if r == "```contents```"
+        ));
+
+        let markdown = transcript.to_markdown();
+        assert!(markdown.starts_with("# Report\n"));
+        assert!(markdown.contains("**Tool call:** `read_file`"));
+        assert!(markdown.contains("````\n```contents```\n````"));
+        assert!(markdown.contains("$0.42"));
+    }
+
+    #[test]
+    fn test_history_fallback() {
+        let mut mission = mission();
+        mission.status = MissionStatus::Active;
+        mission.history = vec![
+            MissionHistoryEntry {
+                role: "user".to_string(),
+                content: "Hi".to_string(),
+            },
+            MissionHistoryEntry {
+                role: "assistant".to_string(),
+                content: "Hello".to_string(),
+            },
+        ];
+        let transcript = Transcript::build(
+            &mission,
+            &[],
+            vec![TranscriptDeliverable {
+                path: "/tmp/report.md".to_string(),
+                exists: false,
+            }],
+            Vec::new(),
+        );
+        assert_eq!(transcript.entries.len(), 2);
+        assert!(transcript.to_markdown().contains("- [ ] `/tmp/report.md`"));
+    }
+}
@@ -0,0 +1,726 @@
+//! Deliverable verification.
+//!
+//! `DeliverableSet::missing_paths` only tells whether a file exists. A
+//! mission can also declare `deliverable_checks` (a file is non-empty, a
+//! command exits 0, a URL answers with a given status, a JSON file matches a
+//! schema). Those checks, plus checks for the paths and URLs named in the
+//! mission's first message, are run when the mission completes. The report is
+//! stored on the mission and included in its health report.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::io::{AsyncRead, AsyncReadExt};
+use uuid::Uuid;
+
+use crate::task::Deliverable;
+use crate::workspace::{self, Workspace};
+use crate::workspace_exec::{kill_process_tree, WorkspaceExec};
+
+use super::control::MissionStatus;
+use super::mission_artifacts::resolve_artifact_path;
+use super::mission_runner::MissionHealth;
+use super::mission_store::{now_string, Mission};
+
+/// Checks a mission may declare.
+const MAX_CHECKS: usize = 32;
+const DEFAULT_COMMAND_TIMEOUT_SECS: u64 = 300;
+const MAX_COMMAND_TIMEOUT_SECS: u64 = 3600;
+const HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+/// Bytes of command output kept in a failed check's detail.
+const OUTPUT_TAIL: usize = 2000;
+
+/// A check run against a mission's output. Paths are relative to the
+/// mission directory unless absolute.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DeliverableCheck {
+    /// The file or directory exists
+    Exists { path: String },
+    /// The file is not empty, or the directory has entries
+    NonEmpty { path: String },
+    /// The command exits 0 when run with `sh -c` in the mission directory
+    Command {
+        command: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timeout_secs: Option<u64>,
+    },
+    /// A GET request to the URL answers with this status
+    HttpStatus {
+        url: String,
+        #[serde(default = "default_http_status")]
+        status: u16,
+    },
+    /// The file is JSON that validates against the schema
+    JsonSchema { path: String, schema: Value },
+}
+
+fn default_http_status() -> u16 {
+    200
+}
+
+impl DeliverableCheck {
+    /// Short description, used in health reports.
+    pub fn describe(&self) -> String {
+        match self {
+            DeliverableCheck::Exists { path } => format!("{} exists", path),
+            DeliverableCheck::NonEmpty { path } => format!("{} is not empty", path),
+            DeliverableCheck::Command { command, .. } => format!("`{}` exits 0", command),
+            DeliverableCheck::HttpStatus { url, status } => {
+                format!("{} returns {}", url, status)
+            }
+            DeliverableCheck::JsonSchema { path, .. } => format!("{} matches its schema", path),
+        }
+    }
+
+    fn path(&self) -> Option<&str> {
+        match self {
+            DeliverableCheck::Exists { path }
+            | DeliverableCheck::NonEmpty { path }
+            | DeliverableCheck::JsonSchema { path, .. } => Some(path),
+            DeliverableCheck::Command { .. } | DeliverableCheck::HttpStatus { .. } => None,
+        }
+    }
+}
+
+/// Validate checks declared on a mission.
+pub fn validate_checks(checks: &[DeliverableCheck]) -> Result<(), String> {
+    if checks.len() > MAX_CHECKS {
+        return Err(format!(
+            "At most {} deliverable checks are allowed",
+            MAX_CHECKS
+        ));
+    }
+    for check in checks {
+        match check {
+            DeliverableCheck::Exists { path }
+            | DeliverableCheck::NonEmpty { path }
+            | DeliverableCheck::JsonSchema { path, .. }
+                if path.trim().is_empty() =>
+            {
+                return Err("Deliverable check path cannot be empty".to_string());
+            }
+            DeliverableCheck::JsonSchema { schema, .. } if !schema.is_object() => {
+                return Err("json_schema check needs a schema object".to_string());
+            }
+            DeliverableCheck::Command { command, .. } if command.trim().is_empty() => {
+                return Err("Deliverable check command cannot be empty".to_string());
+            }
+            DeliverableCheck::Command {
+                timeout_secs: Some(secs),
+                ..
+            } if *secs == 0 || *secs > MAX_COMMAND_TIMEOUT_SECS => {
+                return Err(format!(
+                    "Command check timeout_secs must be between 1 and {}",
+                    MAX_COMMAND_TIMEOUT_SECS
+                ));
+            }
+            DeliverableCheck::HttpStatus { url, .. }
+                if !(url.starts_with("http://") || url.starts_with("https://")) =>
+            {
+                return Err(format!("Invalid URL in http_status check: {}", url));
+            }
+            DeliverableCheck::HttpStatus { status, .. } if !(100..=599).contains(status) => {
+                return Err(format!(
+                    "Invalid HTTP status in http_status check: {}",
+                    status
+                ));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Outcome of one check.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckResult {
+    pub check: DeliverableCheck,
+    pub passed: bool,
+    /// Why the check failed, or what it found
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    pub duration_ms: u64,
+}
+
+/// Outcome of all checks of a mission.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationReport {
+    pub verified_at: String,
+    pub passed: bool,
+    pub results: Vec<CheckResult>,
+}
+
+impl VerificationReport {
+    pub fn failed(&self) -> impl Iterator<Item = &CheckResult> {
+        self.results.iter().filter(|r| !r.passed)
+    }
+
+    /// Health of a finished mission according to this report.
+    pub fn health(&self) -> MissionHealth {
+        if self.passed {
+            return MissionHealth::Healthy;
+        }
+        MissionHealth::FailedChecks {
+            failed: self
+                .failed()
+                .map(|r| match &r.detail {
+                    Some(detail) => format!("{}: {}", r.check.describe(), detail),
+                    None => r.check.describe(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Health of a mission, with its latest verification report.
+#[derive(Debug, Clone, Serialize)]
+pub struct MissionHealthReport {
+    pub mission_id: Uuid,
+    pub status: MissionStatus,
+    /// Whether a runner currently holds the mission
+    pub running: bool,
+    pub health: MissionHealth,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verification: Option<VerificationReport>,
+}
+
+/// The mission's declared checks, followed by checks for the files,
+/// directories and URLs named in its first message that no declared check
+/// covers.
+pub fn checks_for(mission: &Mission) -> Vec<DeliverableCheck> {
+    let mut checks = mission.deliverable_checks.clone();
+    let Some(first) = mission.history.iter().find(|entry| entry.role == "user") else {
+        return checks;
+    };
+    for deliverable in crate::task::extract_deliverables(&first.content).deliverables {
+        let check = match &deliverable {
+            Deliverable::File { path, .. } => DeliverableCheck::NonEmpty {
+                path: path.display().to_string(),
+            },
+            Deliverable::Directory { path } => DeliverableCheck::Exists {
+                path: path.display().to_string(),
+            },
+            Deliverable::Report {
+                expected_path: Some(path),
+                ..
+            } => DeliverableCheck::NonEmpty {
+                path: path.display().to_string(),
+            },
+            Deliverable::Url { url } => DeliverableCheck::HttpStatus {
+                url: url.clone(),
+                status: default_http_status(),
+            },
+            // Looked for in the agent's output by the mission's health check
+            Deliverable::Report { .. }
+            | Deliverable::GitCommit { .. }
+            | Deliverable::PullRequest { .. }
+            | Deliverable::Resource { .. } => continue,
+        };
+        let covered = checks.iter().any(|c| match (c, &check) {
+            (
+                DeliverableCheck::HttpStatus { url: a, .. },
+                DeliverableCheck::HttpStatus { url: b, .. },
+            ) => a == b,
+            _ => c.path().is_some() && c.path() == check.path(),
+        });
+        if !covered {
+            checks.push(check);
+        }
+    }
+    checks
+}
+
+/// Run the mission's checks. `None` if it has none.
+pub async fn verify(mission: &Mission, workspace: &Workspace) -> Option<VerificationReport> {
+    let checks = checks_for(mission);
+    if checks.is_empty() {
+        return None;
+    }
+    let mission_dir = workspace::mission_workspace_dir_for_root(&workspace.path, mission.id);
+    let cwd = if mission_dir.is_dir() {
+        mission_dir
+    } else {
+        workspace.path.clone()
+    };
+    let mut results = Vec::with_capacity(checks.len());
+    for check in checks {
+        let started = Instant::now();
+        let outcome = run_check(&check, mission.id, workspace, &cwd).await;
+        let duration_ms = started.elapsed().as_millis() as u64;
+        let (passed, detail) = match outcome {
+            Ok(detail) => (true, detail),
+            Err(detail) => (false, Some(detail)),
+        };
+        results.push(CheckResult {
+            check,
+            passed,
+            detail,
+            duration_ms,
+        });
+    }
+    Some(VerificationReport {
+        verified_at: now_string(),
+        passed: results.iter().all(|r| r.passed),
+        results,
+    })
+}
+
+async fn run_check(
+    check: &DeliverableCheck,
+    mission_id: Uuid,
+    workspace: &Workspace,
+    cwd: &Path,
+) -> Result<Option<String>, String> {
+    match check {
+        DeliverableCheck::Exists { path } => {
+            let host_path = resolve_artifact_path(workspace, mission_id, path);
+            tokio::fs::metadata(&host_path)
+                .await
+                .map(|_| None)
+                .map_err(|_| "not found".to_string())
+        }
+        DeliverableCheck::NonEmpty { path } => {
+            let host_path = resolve_artifact_path(workspace, mission_id, path);
+            check_non_empty(&host_path).await
+        }
+        DeliverableCheck::Command {
+            command,
+            timeout_secs,
+        } => {
+            let timeout = Duration::from_secs(timeout_secs.unwrap_or(DEFAULT_COMMAND_TIMEOUT_SECS));
+            run_command(workspace, cwd, command, timeout).await
+        }
+        DeliverableCheck::HttpStatus { url, status } => check_http_status(url, *status).await,
+        DeliverableCheck::JsonSchema { path, schema } => {
+            let host_path = resolve_artifact_path(workspace, mission_id, path);
+            let content = tokio::fs::read_to_string(&host_path)
+                .await
+                .map_err(|e| format!("cannot read file: {}", e))?;
+            let value: Value =
+                serde_json::from_str(&content).map_err(|e| format!("invalid JSON: {}", e))?;
+            let errors = validate_json(&value, schema);
+            if errors.is_empty() {
+                Ok(None)
+            } else {
+                Err(errors.join("; "))
+            }
+        }
+    }
+}
+
+async fn check_non_empty(path: &Path) -> Result<Option<String>, String> {
+    let metadata = tokio::fs::metadata(path)
+        .await
+        .map_err(|_| "not found".to_string())?;
+    if metadata.is_dir() {
+        let mut entries = tokio::fs::read_dir(path)
+            .await
+            .map_err(|e| format!("cannot read directory: {}", e))?;
+        return match entries.next_entry().await {
+            Ok(Some(_)) => Ok(None),
+            _ => Err("directory is empty".to_string()),
+        };
+    }
+    if metadata.len() == 0 {
+        return Err("file is empty".to_string());
+    }
+    Ok(Some(format!("{} bytes", metadata.len())))
+}
+
+async fn run_command(
+    workspace: &Workspace,
+    cwd: &Path,
+    command: &str,
+    timeout: Duration,
+) -> Result<Option<String>, String> {
+    let exec = WorkspaceExec::new(workspace.clone());
+    let args = vec!["-c".to_string(), command.to_string()];
+    let mut child = exec
+        .spawn_streaming(cwd, "sh", &args, HashMap::new())
+        .await
+        .map_err(|e| format!("failed to start: {}", e))?;
+    drop(child.stdin.take());
+    let stdout = read_pipe(child.stdout.take());
+    let stderr = read_pipe(child.stderr.take());
+
+    let status = match tokio::time::timeout(timeout, child.wait()).await {
+        Ok(status) => status.map_err(|e| format!("failed to wait: {}", e))?,
+        Err(_) => {
+            kill_process_tree(&mut child).await;
+            return Err(format!("timed out after {}s", timeout.as_secs()));
+        }
+    };
+    let mut output = stdout.await.unwrap_or_default();
+    output.extend(stderr.await.unwrap_or_default());
+    let output = tail(&String::from_utf8_lossy(&output), OUTPUT_TAIL);
+
+    if status.success() {
+        Ok(None)
+    } else {
+        let code = status
+            .code()
+            .map_or_else(|| "a signal".to_string(), |c| c.to_string());
+        if output.trim().is_empty() {
+            Err(format!("exited with {}", code))
+        } else {
+            Err(format!("exited with {}: {}", code, output.trim()))
+        }
+    }
+}
+
+fn read_pipe<R>(pipe: Option<R>) -> tokio::task::JoinHandle<Vec<u8>>
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buf).await;
+        }
+        buf
+    })
+}
+
+/// The last `max` bytes of `s`, cut at a character boundary.
+fn tail(s: &str, max: usize) -> String {
+    if s.len() <= max {
+        return s.to_string();
+    }
+    let mut start = s.len() - max;
+    while !s.is_char_boundary(start) {
+        start += 1;
+    }
+    format!("...{}", &s[start..])
+}
+
+async fn check_http_status(url: &str, expected: u16) -> Result<Option<String>, String> {
+    let client = reqwest::Client::builder()
+        .timeout(HTTP_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("request failed: {}", e))?;
+    let status = response.status().as_u16();
+    if status == expected {
+        Ok(None)
+    } else {
+        Err(format!("returned {}", status))
+    }
+}
+
+/// Validate `value` against a JSON Schema. Supports `type`, `enum`, `const`,
+/// `properties`, `required`, `additionalProperties`, `items`, `minItems`,
+/// `maxItems`, `minLength`, `maxLength`, `pattern`, `minimum`, `maximum`,
+/// `allOf`, `anyOf` and `oneOf`; other keywords are ignored. Returns one
+/// message per violation.
+pub fn validate_json(value: &Value, schema: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+    validate_at(value, schema, "", &mut errors);
+    errors
+}
+
+fn validate_at(value: &Value, schema: &Value, pointer: &str, errors: &mut Vec<String>) {
+    let Some(schema) = schema.as_object() else {
+        // `true` accepts anything, `false` nothing
+        if schema == &Value::Bool(false) {
+            errors.push(format!("{}: not allowed", display_pointer(pointer)));
+        }
+        return;
+    };
+    let at = display_pointer(pointer);
+
+    if let Some(expected) = schema.get("type") {
+        let types: Vec<&str> = match expected {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !types.is_empty() && !types.iter().any(|t| has_type(value, t)) {
+            errors.push(format!(
+                "{}: expected {}, got {}",
+                at,
+                types.join(" or "),
+                type_name(value)
+            ));
+            return;
+        }
+    }
+    if let Some(Value::Array(allowed)) = schema.get("enum") {
+        if !allowed.contains(value) {
+            errors.push(format!("{}: not one of the allowed values", at));
+        }
+    }
+    if let Some(expected) = schema.get("const") {
+        if value != expected {
+            errors.push(format!("{}: expected {}", at, expected));
+        }
+    }
+
+    match value {
+        Value::Object(map) => {
+            if let Some(Value::Array(required)) = schema.get("required") {
+                for key in required.iter().filter_map(Value::as_str) {
+                    if !map.contains_key(key) {
+                        errors.push(format!("{}: missing property `{}`", at, key));
+                    }
+                }
+            }
+            let properties = schema.get("properties").and_then(Value::as_object);
+            for (key, child) in map {
+                let child_pointer = format!("{}/{}", pointer, key);
+                match properties.and_then(|p| p.get(key)) {
+                    Some(child_schema) => {
+                        validate_at(child, child_schema, &child_pointer, errors);
+                    }
+                    None => match schema.get("additionalProperties") {
+                        Some(Value::Bool(false)) => {
+                            errors.push(format!("{}: unexpected property `{}`", at, key));
+                        }
+                        Some(extra @ Value::Object(_)) => {
+                            validate_at(child, extra, &child_pointer, errors);
+                        }
+                        _ => {}
+                    },
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+                if (items.len() as u64) < min {
+                    errors.push(format!("{}: fewer than {} items", at, min));
+                }
+            }
+            if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+                if items.len() as u64 > max {
+                    errors.push(format!("{}: more than {} items", at, max));
+                }
+            }
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    validate_at(item, item_schema, &format!("{}/{}", pointer, i), errors);
+                }
+            }
+        }
+        Value::String(s) => {
+            let len = s.chars().count() as u64;
+            if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+                if len < min {
+                    errors.push(format!("{}: shorter than {} characters", at, min));
+                }
+            }
+            if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+                if len > max {
+                    errors.push(format!("{}: longer than {} characters", at, max));
+                }
+            }
+            if let Some(pattern) = schema.get("pattern").and_then(Value::as_str) {
+                match regex::Regex::new(pattern) {
+                    Ok(re) if !re.is_match(s) => {
+                        errors.push(format!("{}: does not match `{}`", at, pattern));
+                    }
+                    Ok(_) => {}
+                    Err(_) => errors.push(format!("{}: invalid pattern `{}`", at, pattern)),
+                }
+            }
+        }
+        Value::Number(n) => {
+            let n = n.as_f64().unwrap_or_default();
+            if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+                if n < min {
+                    errors.push(format!("{}: less than {}", at, min));
+                }
+            }
+            if let Some(max) = schema.get("maximum").and_then(Value::as_f64) {
+                if n > max {
+                    errors.push(format!("{}: greater than {}", at, max));
+                }
+            }
+        }
+        Value::Null | Value::Bool(_) => {}
+    }
+
+    if let Some(Value::Array(all)) = schema.get("allOf") {
+        for sub in all {
+            validate_at(value, sub, pointer, errors);
+        }
+    }
+    if let Some(Value::Array(any)) = schema.get("anyOf") {
+        if !any.iter().any(|sub| validate_json(value, sub).is_empty()) {
+            errors.push(format!("{}: matches none of anyOf", at));
+        }
+    }
+    if let Some(Value::Array(one)) = schema.get("oneOf") {
+        let matching = one
+            .iter()
+            .filter(|sub| validate_json(value, sub).is_empty())
+            .count();
+        if matching != 1 {
+            errors.push(format!(
+                "{}: matches {} of oneOf instead of exactly one",
+                at, matching
+            ));
+        }
+    }
+}
+
+fn display_pointer(pointer: &str) -> &str {
+    if pointer.is_empty() {
+        "/"
+    } else {
+        pointer
+    }
+}
+
+fn has_type(value: &Value, expected: &str) -> bool {
+    match expected {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Object(_) => "object",
+        Value::Array(_) => "array",
+        Value::String(_) => "string",
+        Value::Number(_) => "number",
+        Value::Bool(_) => "boolean",
+        Value::Null => "null",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_check_serialization() {
+        let checks: Vec<DeliverableCheck> = serde_json::from_value(json!([
+            {"kind": "non_empty", "path": "out/report.md"},
+            {"kind": "command", "command": "cargo test"},
+            {"kind": "http_status", "url": "http://localhost:8080/health"},
+            {"kind": "json_schema", "path": "out.json", "schema": {"type": "object"}}
+        ]))
+        .unwrap();
+        assert_eq!(
+            checks[2],
+            DeliverableCheck::HttpStatus {
+                url: "http://localhost:8080/health".to_string(),
+                status: 200,
+            }
+        );
+        assert!(validate_checks(&checks).is_ok());
+
+        let bad = vec![DeliverableCheck::HttpStatus {
+            url: "file:///etc/passwd".to_string(),
+            status: 200,
+        }];
+        assert!(validate_checks(&bad).is_err());
+        let bad = vec![DeliverableCheck::Command {
+            command: "true".to_string(),
+            timeout_secs: Some(0),
+        }];
+        assert!(validate_checks(&bad).is_err());
+    }
+
+    #[test]
+    fn test_json_schema_validation() {
+        let schema = json!({
+            "type": "object",
+            "required": ["name", "items"],
+            "additionalProperties": false,
+            "properties": {
+                "name": {"type": "string", "minLength": 1},
+                "items": {"type": "array", "minItems": 1, "items": {"type": "integer", "minimum": 0}},
+                "kind": {"enum": ["a", "b"]}
+            }
+        });
+        assert!(validate_json(&json!({"name": "x", "items": [1, 2]}), &schema).is_empty());
+
+        let errors = validate_json(
+            &json!({"name": "", "items": [1, -2, "3"], "kind": "c", "extra": true}),
+            &schema,
+        );
+        assert_eq!(errors.len(), 5, "{:?}", errors);
+        assert!(errors.contains(&"/name: shorter than 1 characters".to_string()));
+        assert!(errors.contains(&"/items/1: less than 0".to_string()));
+        assert!(errors.contains(&"/items/2: expected integer, got string".to_string()));
+        assert!(errors.contains(&"/: unexpected property `extra`".to_string()));
+
+        let errors = validate_json(&json!([]), &schema);
+        assert_eq!(errors, vec!["/: expected object, got array".to_string()]);
+
+        let any = json!({"anyOf": [{"type": "string"}, {"type": "number"}]});
+        assert!(validate_json(&json!(3), &any).is_empty());
+        assert_eq!(validate_json(&json!(null), &any).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_non_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("out.txt");
+        assert_eq!(check_non_empty(&file).await.unwrap_err(), "not found");
+        std::fs::write(&file, "").unwrap();
+        assert_eq!(check_non_empty(&file).await.unwrap_err(), "file is empty");
+        std::fs::write(&file, "done").unwrap();
+        assert!(check_non_empty(&file).await.is_ok());
+
+        let sub = dir.path().join("sub");
+        std::fs::create_dir(&sub).unwrap();
+        assert_eq!(
+            check_non_empty(&sub).await.unwrap_err(),
+            "directory is empty"
+        );
+    }
+
+    #[test]
+    fn test_report_health() {
+        let report = VerificationReport {
+            verified_at: now_string(),
+            passed: false,
+            results: vec![
+                CheckResult {
+                    check: DeliverableCheck::NonEmpty {
+                        path: "a.md".to_string(),
+                    },
+                    passed: true,
+                    detail: None,
+                    duration_ms: 1,
+                },
+                CheckResult {
+                    check: DeliverableCheck::Command {
+                        command: "make test".to_string(),
+                        timeout_secs: None,
+                    },
+                    passed: false,
+                    detail: Some("exited with 2".to_string()),
+                    duration_ms: 1,
+                },
+            ],
+        };
+        match report.health() {
+            MissionHealth::FailedChecks { failed } => {
+                assert_eq!(
+                    failed,
+                    vec!["`make test` exits 0: exited with 2".to_string()]
+                );
+            }
+            other => panic!("unexpected health {:?}", other),
+        }
+        assert_eq!(tail("héllo", 4), "...llo");
+    }
+}
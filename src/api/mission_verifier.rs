@@ -0,0 +1,296 @@
+//! Verifier pass before a mission completes.
+//!
+//! A mission created with a `verifier` isn't completed as soon as a turn
+//! claims to be done. Once the turn is over, its deliverable checks are run
+//! and a second model (usually a cheaper one, possibly from another provider)
+//! reviews the request, the agent's final answer and the check results. On a
+//! pass the mission completes; otherwise the agent gets a corrective
+//! follow-up message. Only one follow-up is sent: the next claim completes
+//! the mission whatever the verdict. Claims and follow-ups are kept in memory
+//! only and don't survive a restart.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, OnceLock};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::ai_providers::ProviderType;
+use crate::tools::result_limits::truncate_head_tail;
+
+use super::mission_store::Mission;
+use super::mission_verification::VerificationReport;
+
+/// Length of the verifier's answer.
+const MAX_TOKENS: u64 = 1024;
+/// Bytes of the request and of the final answer shown to the verifier.
+const MAX_REQUEST_BYTES: usize = 8_000;
+const MAX_ANSWER_BYTES: usize = 12_000;
+
+/// Model reviewing a mission's completion claims.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MissionVerifier {
+    /// AI provider type to ask (e.g. `anthropic`, `openai`); the default
+    /// provider if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    /// Model to ask; the provider's default model if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+/// What the verifier made of a completion claim.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VerifierVerdict {
+    pub passed: bool,
+    /// What is missing or wrong (empty on a pass)
+    pub feedback: String,
+    /// Model that gave the verdict
+    pub model: String,
+}
+
+/// Reject verifiers naming an unknown provider.
+pub fn validate(verifier: &MissionVerifier) -> Result<(), String> {
+    match verifier.provider.as_deref() {
+        Some(provider) if ProviderType::from_id(provider).is_none() => {
+            Err(format!("Unknown verifier provider '{}'", provider))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn claims() -> &'static Mutex<HashMap<Uuid, Option<String>>> {
+    static CLAIMS: OnceLock<Mutex<HashMap<Uuid, Option<String>>>> = OnceLock::new();
+    CLAIMS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn follow_ups() -> &'static Mutex<HashSet<Uuid>> {
+    static FOLLOW_UPS: OnceLock<Mutex<HashSet<Uuid>>> = OnceLock::new();
+    FOLLOW_UPS.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+/// Record that the agent marked the mission complete during a turn, with
+/// its summary. The claim is reviewed once the turn is over.
+pub fn claim(mission_id: Uuid, summary: Option<String>) {
+    claims()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(mission_id, summary);
+}
+
+/// Take the mission's pending claim, if any: `Some(summary)`.
+pub fn take_claim(mission_id: Uuid) -> Option<Option<String>> {
+    claims()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&mission_id)
+}
+
+/// Record a corrective follow-up. Returns `false` if the mission already
+/// had one, in which case it should be completed instead.
+pub fn start_follow_up(mission_id: Uuid) -> bool {
+    follow_ups()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(mission_id)
+}
+
+/// Forget a completed mission's follow-up.
+pub fn finish(mission_id: Uuid) {
+    follow_ups()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&mission_id);
+}
+
+/// Ask the verifier whether the mission is done.
+pub async fn review(
+    mission: &Mission,
+    verifier: &MissionVerifier,
+    report: Option<&VerificationReport>,
+) -> Result<VerifierVerdict, String> {
+    let prompt = build_prompt(mission, report);
+    let (answer, model) = crate::tools::complete_text(
+        &prompt,
+        verifier.provider.as_deref(),
+        verifier.model.as_deref(),
+        MAX_TOKENS,
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+    let (passed, feedback) = parse_verdict(&answer)
+        .ok_or_else(|| format!("Unreadable verdict from {}: {}", model, answer.trim()))?;
+    Ok(VerifierVerdict {
+        passed,
+        feedback,
+        model,
+    })
+}
+
+fn build_prompt(mission: &Mission, report: Option<&VerificationReport>) -> String {
+    let request = mission
+        .history
+        .iter()
+        .find(|entry| entry.role == "user")
+        .map(|entry| entry.content.as_str())
+        .unwrap_or_default();
+    let answer = mission
+        .history
+        .iter()
+        .rev()
+        .find(|entry| entry.role == "assistant")
+        .map(|entry| entry.content.as_str())
+        .unwrap_or_default();
+    let checks = match report {
+        Some(report) => report
+            .results
+            .iter()
+            .map(|result| {
+                let outcome = if result.passed { "passed" } else { "FAILED" };
+                match &result.detail {
+                    Some(detail) => {
+                        format!("- {}: {} ({})", result.check.describe(), outcome, detail)
+                    }
+                    None => format!("- {}: {}", result.check.describe(), outcome),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        None => "No automatic checks for this mission.".to_string(),
+    };
+    format!(
+        "An AI agent says it has finished the task below. Before the task is marked complete, \
+         decide whether it really is: every requested deliverable produced and every acceptance \
+         criterion in the request met. A failed deliverable check means it is not done. Don't \
+         ask for extras the request didn't call for.\n\n\
+         <request>\n{}\n</request>\n\n\
+         <final_answer>\n{}\n</final_answer>\n\n\
+         <deliverable_checks>\n{}\n</deliverable_checks>\n\n\
+         Reply with JSON only: {{\"verdict\": \"pass\" or \"fail\", \"feedback\": \"...\"}}. \
+         On a fail, the feedback goes to the agent: say exactly what is missing or wrong.",
+        truncate_head_tail(request, MAX_REQUEST_BYTES),
+        truncate_head_tail(answer, MAX_ANSWER_BYTES),
+        checks
+    )
+}
+
+/// Read `{"verdict": "pass"|"fail", "feedback": "..."}` from the answer, or
+/// an answer starting with PASS/FAIL.
+fn parse_verdict(answer: &str) -> Option<(bool, String)> {
+    if let (Some(start), Some(end)) = (answer.find('{'), answer.rfind('}')) {
+        if start >= end {
+            return None;
+        }
+        if let Ok(value) = serde_json::from_str::<Value>(&answer[start..=end]) {
+            let passed = match value.get("verdict").and_then(Value::as_str) {
+                Some(v) if v.eq_ignore_ascii_case("pass") => true,
+                Some(v) if v.eq_ignore_ascii_case("fail") => false,
+                _ => return None,
+            };
+            let feedback = value
+                .get("feedback")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .trim()
+                .to_string();
+            return Some((passed, feedback));
+        }
+    }
+    let trimmed = answer.trim();
+    let word = trimmed.get(..4)?;
+    let passed = if word.eq_ignore_ascii_case("pass") {
+        true
+    } else if word.eq_ignore_ascii_case("fail") {
+        false
+    } else {
+        return None;
+    };
+    let feedback = trimmed[4..]
+        .trim_start_matches(|c: char| c == ':' || c == '.' || c == '-' || c.is_whitespace())
+        .to_string();
+    Some((passed, feedback))
+}
+
+/// Message sent to the agent when the verifier rejects its claim.
+pub fn corrective_message(feedback: &str) -> String {
+    let feedback = if feedback.trim().is_empty() {
+        "The reviewer gave no details; re-check the original request."
+    } else {
+        feedback.trim()
+    };
+    format!(
+        "MISSION NOT COMPLETE YET\n\n\
+         A reviewer checked your work against the original request and found problems:\n\n\
+         {}\n\n\
+         Fix them, then mark the mission complete again.",
+        feedback
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_json_verdict() {
+        let answer = "Here is my review:\n```json\n{\"verdict\": \"fail\", \"feedback\": \" report.md is empty \"}\n```";
+        assert_eq!(
+            parse_verdict(answer),
+            Some((false, "report.md is empty".to_string()))
+        );
+        assert_eq!(
+            parse_verdict("{\"verdict\": \"PASS\"}"),
+            Some((true, String::new()))
+        );
+        assert_eq!(parse_verdict("{\"verdict\": \"maybe\"}"), None);
+        assert_eq!(parse_verdict("} pass {"), None);
+    }
+
+    #[test]
+    fn parses_plain_verdict() {
+        assert_eq!(
+            parse_verdict("FAIL: the tests were not run"),
+            Some((false, "the tests were not run".to_string()))
+        );
+        assert_eq!(parse_verdict("Pass."), Some((true, String::new())));
+        assert_eq!(parse_verdict("Looks good"), None);
+        assert_eq!(parse_verdict(""), None);
+    }
+
+    #[test]
+    fn validates_provider() {
+        assert!(validate(&MissionVerifier::default()).is_ok());
+        let verifier = MissionVerifier {
+            provider: Some("not-a-provider".to_string()),
+            model: None,
+        };
+        assert!(validate(&verifier).is_err());
+    }
+
+    #[test]
+    fn one_follow_up_per_mission() {
+        let id = Uuid::new_v4();
+        assert!(start_follow_up(id));
+        assert!(!start_follow_up(id));
+        finish(id);
+        assert!(start_follow_up(id));
+        finish(id);
+    }
+
+    #[test]
+    fn claims_are_taken_once() {
+        let id = Uuid::new_v4();
+        assert_eq!(take_claim(id), None);
+        claim(id, Some("Done".to_string()));
+        assert_eq!(take_claim(id), Some(Some("Done".to_string())));
+        assert_eq!(take_claim(id), None);
+    }
+
+    #[test]
+    fn corrective_message_includes_feedback() {
+        let message = corrective_message("  Add the missing tests.\n");
+        assert!(message.contains("Add the missing tests.\n\nFix them"));
+        assert!(corrective_message("").contains("no details"));
+    }
+}
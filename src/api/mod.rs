@@ -14,30 +14,61 @@
 //! - `POST /api/mcp/{id}/disable` - Disable an MCP server
 //! - `GET /api/tools` - List all tools (built-in + MCP)
 //! - `POST /api/tools/{name}/toggle` - Enable/disable a tool
+//! - `GET /api/audit/tools` - Query the tool execution audit log
+//! - `POST /api/audit/tools` - Record a tool execution run by workspace-mcp
 
+pub mod agent_mention;
 pub mod ai_providers;
 pub mod ampcode;
+mod audit;
 mod auth;
 pub mod automation_variables;
 pub mod backends;
 pub mod claudecode;
 mod console;
 pub mod control;
+pub mod control_history;
+pub mod control_sessions;
+pub mod control_ws;
+pub mod costs;
 pub mod desktop;
 mod desktop_stream;
+pub mod event_journal;
 mod fs;
 pub mod library;
 pub mod mcp;
+pub mod metrics;
+pub mod mission_artifacts;
+pub mod mission_budget;
+pub mod mission_checkpoints;
+pub mod mission_completion;
+pub mod mission_filter;
+pub mod mission_heartbeat;
+pub mod mission_history;
+pub mod mission_pause;
+pub mod mission_retention;
+pub mod mission_retry;
 pub mod mission_runner;
+pub mod mission_snapshots;
+pub mod mission_stall;
+pub mod mission_steer;
 pub mod mission_store;
+pub mod mission_templates;
+pub mod mission_timeout;
+pub mod mission_transcript;
+pub mod mission_verification;
+pub mod mission_verifier;
 mod monitoring;
 pub mod opencode;
 mod providers;
 mod routes;
+mod scheduler;
 pub mod secrets;
 pub mod settings;
+pub mod shutdown;
 pub mod system;
 pub mod types;
+pub mod workspace_slots;
 pub mod workspaces;
 
 pub use routes::serve;
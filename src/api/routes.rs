@@ -41,20 +41,29 @@ fn cli_available(name: &str) -> bool {
 
 use super::ai_providers as ai_providers_api;
 use super::ampcode as ampcode_api;
+use super::audit as audit_api;
 use super::auth::{self, AuthUser};
 use super::backends as backends_api;
 use super::claudecode as claudecode_api;
 use super::console;
 use super::control;
+use super::control_sessions;
+use super::control_ws;
+use super::costs;
 use super::desktop;
 use super::desktop_stream;
 use super::fs;
 use super::library as library_api;
 use super::mcp as mcp_api;
+use super::metrics;
+use super::mission_heartbeat;
+use super::mission_templates as mission_templates_api;
 use super::monitoring;
 use super::opencode as opencode_api;
+use super::scheduler as scheduler_api;
 use super::secrets as secrets_api;
 use super::settings as settings_api;
+use super::shutdown;
 use super::system as system_api;
 use super::types::*;
 use super::workspaces as workspaces_api;
@@ -88,10 +97,16 @@ pub struct AppState {
     pub console_pool: Arc<console::SessionPool>,
     /// Global settings store
     pub settings: Arc<crate::settings::SettingsStore>,
+    /// Scheduled (recurring) jobs
+    pub scheduler: crate::scheduler::SharedSchedulerStore,
+    /// Saved mission prompts
+    pub mission_templates: Arc<mission_templates_api::MissionTemplateStore>,
     /// Backend registry for multi-backend support
     pub backend_registry: Arc<RwLock<BackendRegistry>>,
     /// Backend configuration store
     pub backend_configs: Arc<crate::backend_config::BackendConfigStore>,
+    /// Cached per-backend model catalogs
+    pub model_catalogs: crate::model_catalog::SharedModelCatalogStore,
 }
 
 /// Start the HTTP server.
@@ -133,6 +148,7 @@ pub async fn serve(config: Config) -> anyhow::Result<()> {
         )
         .await,
     );
+    crate::ai_providers::set_global(Arc::clone(&ai_providers));
     let pending_oauth = Arc::new(RwLock::new(HashMap::new()));
 
     // Initialize secrets store
@@ -153,6 +169,15 @@ pub async fn serve(config: Config) -> anyhow::Result<()> {
 
     // Initialize global settings store
     let settings = Arc::new(crate::settings::SettingsStore::new(&config.working_dir).await);
+    crate::settings::set_global(Arc::clone(&settings));
+
+    // Initialize the tool execution audit log
+    crate::tools::audit::set_global(Arc::new(crate::tools::audit::ToolAuditLog::new(
+        &config.working_dir,
+    )));
+    let scheduler = Arc::new(crate::scheduler::SchedulerStore::new(&config.working_dir).await);
+    let mission_templates =
+        Arc::new(mission_templates_api::MissionTemplateStore::new(&config.working_dir).await);
 
     // Initialize backend config store (persisted settings).
     // Probe each CLI binary so backends whose CLI is missing default to disabled.
@@ -208,6 +233,16 @@ pub async fn serve(config: Config) -> anyhow::Result<()> {
         .await,
     );
 
+    // Initialize model catalog cache (refreshed on demand from each CLI)
+    let model_catalogs = Arc::new(
+        crate::model_catalog::ModelCatalogStore::new(
+            config
+                .working_dir
+                .join(".sandboxed-sh/data/model_catalogs.json"),
+        )
+        .await,
+    );
+
     // Apply persisted OpenCode settings (if present)
     if let Some(entry) = backend_configs.get("opencode").await {
         if let Some(settings) = entry.settings.as_object() {
@@ -270,7 +305,7 @@ pub async fn serve(config: Config) -> anyhow::Result<()> {
         opencode_permissive,
     ));
     backend_registry.register(crate::backend::claudecode::registry_entry());
-    backend_registry.register(crate::backend::amp::registry_entry());
+    backend_registry.register(crate::backend::amp::registry_entry(secrets.clone()));
     backend_registry.register(crate::backend::codex::registry_entry());
     let backend_registry = Arc::new(RwLock::new(backend_registry));
     tracing::info!("Backend registry initialized with {} backends", 4);
@@ -285,9 +320,17 @@ pub async fn serve(config: Config) -> anyhow::Result<()> {
     if let Some(library_remote) = library_remote {
         let library_clone = Arc::clone(&library);
         let library_path = config.library_path.clone();
+        let library_layers = settings.get_library_layers().await;
+        let library_read_only = config.library_read_only;
         let workspaces_clone = Arc::clone(&workspaces);
         tokio::spawn(async move {
-            match crate::library::LibraryStore::new(library_path, &library_remote).await {
+            match crate::library::LibraryStore::with_layers(
+                library_path,
+                &library_remote,
+                &library_layers,
+            )
+            .await
+            {
                 Ok(store) => {
                     if let Ok(plugins) = store.get_plugins().await {
                         if let Err(e) = crate::opencode_config::sync_global_plugins(&plugins).await
@@ -296,7 +339,8 @@ pub async fn serve(config: Config) -> anyhow::Result<()> {
                         }
                     }
                     tracing::info!("Configuration library initialized from {}", library_remote);
-                    *library_clone.write().await = Some(Arc::new(store));
+                    *library_clone.write().await =
+                        Some(Arc::new(store.with_read_only(library_read_only)));
 
                     let workspaces = workspaces_clone.list().await;
                     if let Some(library) = library_clone.read().await.as_ref() {
@@ -326,6 +370,20 @@ pub async fn serve(config: Config) -> anyhow::Result<()> {
         tracing::info!("Configuration library disabled (no remote configured)");
     }
 
+    // Kill backend CLIs left behind by a previous run; no mission can be
+    // running yet, so every recorded process is an orphan.
+    {
+        let registry = crate::process_registry::init(&config.working_dir);
+        for record in registry.reap_all() {
+            tracing::warn!(
+                pid = record.pid,
+                mission_id = %record.mission_id,
+                program = %record.program,
+                "Killed orphaned backend process from previous run"
+            );
+        }
+    }
+
     // Spawn the single global control session actor.
     let control_state = control::ControlHub::new(
         config.clone(),
@@ -351,10 +409,44 @@ pub async fn serve(config: Config) -> anyhow::Result<()> {
         secrets,
         console_pool,
         settings,
+        scheduler,
+        mission_templates,
         backend_registry,
         backend_configs,
+        model_catalogs,
     });
 
+    // Periodically reap backend processes whose missions stopped running
+    tokio::spawn(control::process_reaper_loop(state.control.clone()));
+
+    // Keep missions whose backend is busy but silent from looking stalled
+    if config.mission_heartbeat_secs > 0 {
+        tokio::spawn(mission_heartbeat::heartbeat_loop(
+            state.control.clone(),
+            std::time::Duration::from_secs(config.mission_heartbeat_secs),
+        ));
+    }
+
+    // Clean up finished missions according to the retention policy
+    if config.retention.is_enabled() {
+        tokio::spawn(control::retention_loop(state.control.clone()));
+    }
+
+    // Start recurring jobs
+    if config.automations_enabled {
+        tokio::spawn(scheduler_api::scheduler_loop(Arc::clone(&state)));
+    } else {
+        tracing::info!("Job scheduler disabled by config");
+    }
+
+    // Keep the library fresh without manual syncs
+    if config.library_sync_interval_secs > 0 {
+        tokio::spawn(library_api::library_sync_loop(Arc::clone(&state)));
+    }
+    if config.library_auto_commit_secs > 0 && !config.library_read_only {
+        tokio::spawn(library_api::library_auto_commit_loop(Arc::clone(&state)));
+    }
+
     // Start background desktop session cleanup task
     {
         let state_clone = Arc::clone(&state);
@@ -371,6 +463,8 @@ pub async fn serve(config: Config) -> anyhow::Result<()> {
             "/api/webhooks/:mission_id/:webhook_id",
             post(control::webhook_receiver),
         )
+        // Library push webhook (no auth required - uses LIBRARY_WEBHOOK_SECRET)
+        .route("/api/library/webhook", post(library_api::library_webhook))
         // WebSocket console uses subprotocol-based auth (browser can't set Authorization header)
         .route("/api/console/ws", get(console::console_ws))
         // WebSocket workspace shell uses subprotocol-based auth
@@ -384,7 +478,11 @@ pub async fn serve(config: Config) -> anyhow::Result<()> {
             get(desktop_stream::desktop_stream_ws),
         )
         // WebSocket system monitoring uses subprotocol-based auth
-        .route("/api/monitoring/ws", get(monitoring::monitoring_ws));
+        .route("/api/monitoring/ws", get(monitoring::monitoring_ws))
+        // WebSocket control event stream uses subprotocol-based auth
+        .route("/api/control/ws", get(control_ws::control_ws))
+        // Prometheus scrape endpoint (optional METRICS_TOKEN bearer auth)
+        .route("/metrics", get(metrics::metrics_handler));
 
     // File upload routes with increased body limit (10GB)
     let upload_route = Router::new()
@@ -394,6 +492,7 @@ pub async fn serve(config: Config) -> anyhow::Result<()> {
 
     let protected_routes = Router::new()
         .route("/api/stats", get(get_stats))
+        .route("/api/costs", get(costs::get_costs))
         .route("/api/task", post(create_task))
         .route("/api/task/:id", get(get_task))
         .route("/api/task/:id/stop", post(stop_task))
@@ -402,17 +501,27 @@ pub async fn serve(config: Config) -> anyhow::Result<()> {
         // Global control session endpoints
         .route("/api/control/message", post(control::post_message))
         .route("/api/control/tool_result", post(control::post_tool_result))
+        .route(
+            "/api/control/tool_approval",
+            post(control::post_tool_approval),
+        )
         .route("/api/control/stream", get(control::stream))
         .route("/api/control/cancel", post(control::post_cancel))
+        // Named control sessions
+        .nest("/api/control/sessions", control_sessions::routes())
         // Queue management endpoints
+        .route(
+            "/api/control/history",
+            get(control::get_history).delete(control::truncate_history),
+        )
         .route("/api/control/queue", get(control::get_queue))
         .route(
             "/api/control/queue/:id",
-            axum::routing::delete(control::remove_from_queue),
+            axum::routing::delete(control::remove_from_queue).patch(control::update_queued_message),
         )
         .route(
             "/api/control/queue",
-            axum::routing::delete(control::clear_queue),
+            axum::routing::delete(control::clear_queue).put(control::reorder_queue),
         )
         // State snapshots (for refresh resilience)
         .route("/api/control/tree", get(control::get_tree))
@@ -430,6 +539,10 @@ pub async fn serve(config: Config) -> anyhow::Result<()> {
             get(control::get_current_mission),
         )
         .route("/api/control/missions/:id", get(control::get_mission))
+        .route(
+            "/api/control/missions/:id/queue",
+            get(control::get_mission_queue),
+        )
         .route(
             "/api/control/missions/:id/tree",
             get(control::get_mission_tree),
@@ -447,12 +560,20 @@ pub async fn serve(config: Config) -> anyhow::Result<()> {
             post(control::set_mission_status),
         )
         .route(
-            "/api/control/missions/:id/cancel",
-            post(control::cancel_mission),
+            "/api/control/missions/:id/priority",
+            post(control::set_mission_priority),
         )
         .route(
-            "/api/control/missions/:id/resume",
-            post(control::resume_mission),
+            "/api/control/missions/:id/budget",
+            post(control::set_mission_budget),
+        )
+        .route(
+            "/api/control/missions/:id/timeout",
+            post(control::set_mission_timeout),
+        )
+        .route(
+            "/api/control/missions/:id/cancel",
+            post(control::cancel_mission),
         )
         .route(
             "/api/control/missions/:id/parallel",
@@ -517,6 +638,7 @@ pub async fn serve(config: Config) -> anyhow::Result<()> {
         .route("/api/fs/download-url", post(fs::download_from_url))
         .route("/api/fs/mkdir", post(fs::mkdir))
         .route("/api/fs/rm", post(fs::rm))
+        .route("/api/fs/apply-patch", post(fs::apply_patch))
         // MCP management endpoints
         .route("/api/mcp", get(mcp_api::list_mcps))
         .route("/api/mcp", post(mcp_api::add_mcp))
@@ -533,7 +655,10 @@ pub async fn serve(config: Config) -> anyhow::Result<()> {
         // Provider management endpoints
         .route("/api/providers", get(super::providers::list_providers))
         // Library management endpoints
-        .nest("/api/library", library_api::routes())
+        .nest(
+            "/api/library",
+            library_api::routes(config.library_read_only),
+        )
         // Workspace management endpoints
         .nest("/api/workspaces", workspaces_api::routes())
         // OpenCode connection endpoints
@@ -579,6 +704,55 @@ pub async fn serve(config: Config) -> anyhow::Result<()> {
         .nest("/api/secrets", secrets_api::routes())
         // Global settings endpoints
         .nest("/api/settings", settings_api::routes())
+        // Tool execution audit log
+        .nest("/api/audit", audit_api::routes())
+        // Scheduled job endpoints
+        .nest("/api/scheduler", scheduler_api::routes())
+        .nest("/api/missions/schedules", scheduler_api::job_routes())
+        .nest("/api/missions/templates", mission_templates_api::routes())
+        .route("/api/missions/:id/pause", post(control::pause_mission))
+        .route("/api/missions/:id/steer", post(control::steer_mission))
+        .route("/api/missions", get(control::list_missions))
+        .route("/api/missions/retention", get(control::retention_report))
+        .route("/api/missions/retention", post(control::run_retention))
+        .route(
+            "/api/missions/:id/tags",
+            axum::routing::put(control::set_mission_tags),
+        )
+        .route("/api/missions/:id/resume", post(control::resume_mission))
+        .route(
+            "/api/missions/:id/kill-processes",
+            post(control::kill_mission_processes),
+        )
+        .route(
+            "/api/missions/:id/checkpoints",
+            get(control::list_checkpoints),
+        )
+        .route("/api/missions/:id/artifacts", get(control::list_artifacts))
+        .route("/api/missions/:id/transcript", get(control::get_transcript))
+        .route(
+            "/api/missions/:id/checks",
+            axum::routing::put(control::set_deliverable_checks),
+        )
+        .route(
+            "/api/missions/:id/verifier",
+            axum::routing::put(control::set_verifier),
+        )
+        .route(
+            "/api/missions/:id/stall-thresholds",
+            axum::routing::put(control::set_stall_thresholds),
+        )
+        .route("/api/missions/:id/verify", post(control::verify_mission))
+        .route("/api/missions/:id/clone", post(control::clone_mission))
+        .route("/api/missions/:id/health", get(control::get_mission_health))
+        .route(
+            "/api/missions/:id/artifacts/:artifact_id/download",
+            get(control::download_artifact),
+        )
+        .route(
+            "/api/missions/from-template/:id",
+            post(mission_templates_api::create_from_template),
+        )
         // Desktop session management endpoints
         .nest("/api/desktop", desktop::routes())
         // System component management endpoints
@@ -598,6 +772,23 @@ pub async fn serve(config: Config) -> anyhow::Result<()> {
             "/api/backends/:id/config",
             axum::routing::put(backends_api::update_backend_config),
         )
+        .route(
+            "/api/backends/:id/models",
+            get(backends_api::get_backend_models),
+        )
+        .route(
+            "/api/backends/:id/models/refresh",
+            post(backends_api::refresh_backend_models),
+        )
+        .route("/api/backends/amp/login", post(backends_api::amp_login))
+        .route(
+            "/api/backends/opencode/warmup",
+            post(backends_api::warmup_opencode),
+        )
+        .layer(middleware::from_fn_with_state(
+            Arc::clone(&state),
+            control_sessions::select_session,
+        ))
         .layer(middleware::from_fn_with_state(
             Arc::clone(&state),
             auth::require_auth,
@@ -626,7 +817,8 @@ pub async fn serve(config: Config) -> anyhow::Result<()> {
     Ok(())
 }
 
-/// Wait for shutdown signal and mark running missions as interrupted.
+/// Wait for a shutdown signal, then drain the control sessions: let running
+/// turns finish within the grace period, interrupt the rest and flush events.
 async fn shutdown_signal(state: Arc<AppState>) {
     let ctrl_c = async {
         tokio::signal::ctrl_c()
@@ -650,9 +842,14 @@ async fn shutdown_signal(state: Arc<AppState>) {
         _ = terminate => {},
     }
 
-    tracing::info!("Shutdown signal received, marking running missions as interrupted...");
+    let grace = std::time::Duration::from_secs(state.config.shutdown_grace_secs);
+    tracing::info!(
+        "Shutdown signal received, letting running turns finish for up to {}s...",
+        grace.as_secs()
+    );
+    shutdown::drain(&state.control, grace).await;
 
-    // Send graceful shutdown command to all control sessions
+    // Checkpoint, cancel and mark interrupted whatever is still running
     let sessions = state.control.all_sessions().await;
     if sessions.is_empty() {
         tracing::info!("No active control sessions to shut down");
@@ -660,25 +857,8 @@ async fn shutdown_signal(state: Arc<AppState>) {
     }
 
     let mut all_interrupted: Vec<Uuid> = Vec::new();
-    for control in sessions {
-        let (tx, rx) = tokio::sync::oneshot::channel();
-        if let Err(e) = control
-            .cmd_tx
-            .send(control::ControlCommand::GracefulShutdown { respond: tx })
-            .await
-        {
-            tracing::error!("Failed to send shutdown command: {}", e);
-            continue;
-        }
-
-        match rx.await {
-            Ok(mut interrupted_ids) => {
-                all_interrupted.append(&mut interrupted_ids);
-            }
-            Err(e) => {
-                tracing::error!("Failed to receive shutdown response: {}", e);
-            }
-        }
+    for control in &sessions {
+        all_interrupted.extend(shutdown::interrupt(control).await);
     }
 
     if all_interrupted.is_empty() {
@@ -691,6 +871,10 @@ async fn shutdown_signal(state: Arc<AppState>) {
         );
     }
 
+    for control in &sessions {
+        shutdown::flush_events(control).await;
+    }
+
     tracing::info!("Graceful shutdown complete");
 }
 
@@ -0,0 +1,257 @@
+//! API endpoints for scheduled jobs, and the loop that runs them.
+//!
+//! Each run creates a new mission for the job's owner and sends the job's
+//! prompt to it, going through the same paths as the dashboard. The job
+//! routes are served under both `/api/scheduler/jobs` and
+//! `/api/missions/schedules`.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::scheduler::{NewScheduledJob, ScheduledJob, ScheduledJobUpdate};
+
+use super::auth::AuthUser;
+use super::automation_variables::{substitute_variables, SubstitutionContext};
+use super::control::{self, ControlMessageRequest, CreateMissionRequest};
+use super::routes::AppState;
+
+/// How often the scheduler looks for due jobs.
+const CHECK_INTERVAL_SECS: u64 = 30;
+
+/// Create the scheduler API routes.
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new().nest("/jobs", job_routes())
+}
+
+/// Job collection routes, relative to the collection path.
+pub fn job_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(list_jobs).post(create_job))
+        .route("/:id", get(get_job).patch(update_job).delete(delete_job))
+        .route("/:id/run", post(run_job_now))
+}
+
+/// Load a job, hiding jobs that belong to other users.
+async fn owned_job(
+    state: &AppState,
+    user: &AuthUser,
+    id: Uuid,
+) -> Result<ScheduledJob, (StatusCode, String)> {
+    state
+        .scheduler
+        .get(id)
+        .await
+        .filter(|job| job.owner_id == user.id)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Job {} not found", id)))
+}
+
+/// GET /api/scheduler/jobs
+async fn list_jobs(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+) -> Json<Vec<ScheduledJob>> {
+    Json(state.scheduler.list(Some(&user.id)).await)
+}
+
+/// POST /api/scheduler/jobs
+async fn create_job(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Json(req): Json<NewScheduledJob>,
+) -> Result<Json<ScheduledJob>, (StatusCode, String)> {
+    let job = state
+        .scheduler
+        .create(&user.id, &user.username, req)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    tracing::info!(
+        job_id = %job.id,
+        schedule = %job.schedule,
+        next_run_at = ?job.next_run_at,
+        "Created scheduled job '{}'",
+        job.name
+    );
+    Ok(Json(job))
+}
+
+/// GET /api/scheduler/jobs/:id
+async fn get_job(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ScheduledJob>, (StatusCode, String)> {
+    owned_job(&state, &user, id).await.map(Json)
+}
+
+/// PATCH /api/scheduler/jobs/:id
+async fn update_job(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<ScheduledJobUpdate>,
+) -> Result<Json<ScheduledJob>, (StatusCode, String)> {
+    owned_job(&state, &user, id).await?;
+    state
+        .scheduler
+        .update(id, req)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?
+        .map(Json)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Job {} not found", id)))
+}
+
+/// DELETE /api/scheduler/jobs/:id
+async fn delete_job(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    owned_job(&state, &user, id).await?;
+    state
+        .scheduler
+        .delete(id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /api/scheduler/jobs/:id/run
+/// Run a job immediately; its regular schedule is unaffected.
+async fn run_job_now(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ScheduledJob>, (StatusCode, String)> {
+    let job = owned_job(&state, &user, id).await?;
+    let mission_id = start_job_mission(&state, &job)
+        .await
+        .map_err(|e| (StatusCode::BAD_GATEWAY, e))?;
+    state
+        .scheduler
+        .record_run(id, Utc::now(), Ok(mission_id))
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    owned_job(&state, &user, id).await.map(Json)
+}
+
+/// Create a mission for `job` and send it the prompt.
+async fn start_job_mission(state: &Arc<AppState>, job: &ScheduledJob) -> Result<Uuid, String> {
+    let user = AuthUser {
+        id: job.owner_id.clone(),
+        username: job.owner_username.clone(),
+        control_session: None,
+    };
+    let title = format!("{} ({})", job.name, Utc::now().format("%Y-%m-%d %H:%M UTC"));
+    let Json(mission) = control::create_mission(
+        State(Arc::clone(state)),
+        Extension(user.clone()),
+        Some(Json(CreateMissionRequest {
+            title: Some(title),
+            workspace_id: job.workspace_id,
+            agent: job.agent.clone(),
+            model_override: job.model_override.clone(),
+            config_profile: None,
+            backend: job.backend.clone(),
+            priority: None,
+            budget_cents: None,
+            timeout_minutes: None,
+            retry_policy: None,
+            max_concurrent_messages: None,
+            tags: None,
+            deliverable_checks: None,
+            verifier: None,
+            stall_thresholds: None,
+        })),
+    )
+    .await
+    .map_err(|(_, e)| format!("Failed to create mission: {}", e))?;
+
+    let _ = control::post_message(
+        State(Arc::clone(state)),
+        Extension(user),
+        Json(ControlMessageRequest {
+            content: render_prompt(job, mission.id, mission.title.clone()),
+            agent: None,
+            mission_id: Some(mission.id),
+            priority: None,
+        }),
+    )
+    .await
+    .map_err(|(_, e)| format!("Failed to start mission {}: {}", mission.id, e))?;
+
+    Ok(mission.id)
+}
+
+/// Fill in the placeholders of a job's prompt template for a run: the
+/// automation built-ins (`<date/>`, `<mission_id/>`, ...) plus `<job_name/>`,
+/// `<run_number/>` and `<last_run_at/>`.
+fn render_prompt(job: &ScheduledJob, mission_id: Uuid, title: Option<String>) -> String {
+    let variables = HashMap::from([
+        ("job_name".to_string(), job.name.clone()),
+        ("run_number".to_string(), (job.run_count + 1).to_string()),
+        (
+            "last_run_at".to_string(),
+            job.last_run_at
+                .map(|t| t.to_rfc3339())
+                .unwrap_or_else(|| "never".to_string()),
+        ),
+    ]);
+    let mut context = SubstitutionContext::new(mission_id).with_custom_variables(variables);
+    if let Some(title) = title {
+        context = context.with_mission_name(title);
+    }
+    substitute_variables(&job.prompt, &context)
+}
+
+/// Whether the mission from a job's previous run is still going.
+async fn previous_run_active(state: &AppState, job: &ScheduledJob) -> bool {
+    let Some(mission_id) = job.last_mission_id else {
+        return false;
+    };
+    let user = AuthUser {
+        id: job.owner_id.clone(),
+        username: job.owner_username.clone(),
+        control_session: None,
+    };
+    let control = state.control.get_or_spawn(&user).await;
+    let running = control.running_missions.read().await;
+    running.iter().any(|r| r.mission_id == mission_id)
+}
+
+/// Background task that starts due jobs.
+pub async fn scheduler_loop(state: Arc<AppState>) {
+    tracing::info!("Job scheduler task started");
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(CHECK_INTERVAL_SECS));
+    loop {
+        interval.tick().await;
+        let now = Utc::now();
+        for job in state.scheduler.due(now).await {
+            let outcome = if previous_run_active(&state, &job).await {
+                Err("Skipped: the previous run is still in progress".to_string())
+            } else {
+                start_job_mission(&state, &job).await
+            };
+            match &outcome {
+                Ok(mission_id) => tracing::info!(
+                    job_id = %job.id,
+                    mission_id = %mission_id,
+                    "Started scheduled job '{}'",
+                    job.name
+                ),
+                Err(e) => tracing::warn!(job_id = %job.id, "Scheduled job '{}': {}", job.name, e),
+            }
+            if let Err(e) = state.scheduler.record_run(job.id, now, outcome).await {
+                tracing::warn!(job_id = %job.id, "Failed to record scheduled run: {}", e);
+            }
+        }
+    }
+}
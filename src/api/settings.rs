@@ -13,7 +13,9 @@ use axum::{
 };
 use serde::{Deserialize, Serialize};
 
+use crate::library::LibraryLayerConfig;
 use crate::settings::Settings;
+use crate::tools::{ToolPolicy, ToolResultLimits};
 use crate::workspace;
 
 use super::routes::AppState;
@@ -23,6 +25,15 @@ pub fn routes() -> Router<Arc<AppState>> {
     Router::new()
         .route("/", get(get_settings).put(update_settings))
         .route("/library-remote", put(update_library_remote))
+        .route(
+            "/library-layers",
+            get(get_library_layers).put(update_library_layers),
+        )
+        .route("/tool-policy", get(get_tool_policy).put(update_tool_policy))
+        .route(
+            "/tool-result-limits",
+            get(get_tool_result_limits).put(update_tool_result_limits),
+        )
         .route("/backup", get(download_backup))
         .route("/restore", post(restore_backup))
 }
@@ -31,6 +42,7 @@ pub fn routes() -> Router<Arc<AppState>> {
 #[derive(Debug, Serialize)]
 pub struct SettingsResponse {
     pub library_remote: Option<String>,
+    pub library_layers: Vec<LibraryLayerConfig>,
     pub sandboxed_repo_path: Option<String>,
 }
 
@@ -38,6 +50,7 @@ impl From<Settings> for SettingsResponse {
     fn from(settings: Settings) -> Self {
         Self {
             library_remote: settings.library_remote,
+            library_layers: settings.library_layers,
             sandboxed_repo_path: settings.sandboxed_repo_path,
         }
     }
@@ -142,11 +155,157 @@ async fn update_library_remote(
     }))
 }
 
+/// Request to replace the library layers.
+#[derive(Debug, Deserialize)]
+pub struct UpdateLibraryLayersRequest {
+    /// Layers in priority order (highest first, after the primary library).
+    pub library_layers: Vec<LibraryLayerConfig>,
+}
+
+/// Response after updating the library layers.
+#[derive(Debug, Serialize)]
+pub struct UpdateLibraryLayersResponse {
+    pub library_layers: Vec<LibraryLayerConfig>,
+    /// Whether the library was reinitialized.
+    pub library_reinitialized: bool,
+    /// Error message if library initialization failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub library_error: Option<String>,
+}
+
+/// GET /api/settings/library-layers
+/// Get the extra library layers.
+async fn get_library_layers(State(state): State<Arc<AppState>>) -> Json<Vec<LibraryLayerConfig>> {
+    Json(state.settings.get_library_layers().await)
+}
+
+/// PUT /api/settings/library-layers
+/// Replace the extra library layers and reinitialize the library.
+async fn update_library_layers(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<UpdateLibraryLayersRequest>,
+) -> Result<Json<UpdateLibraryLayersResponse>, (StatusCode, String)> {
+    let layers: Vec<LibraryLayerConfig> = req
+        .library_layers
+        .into_iter()
+        .map(|layer| LibraryLayerConfig {
+            name: layer.name.trim().to_string(),
+            remote: layer.remote.trim().to_string(),
+        })
+        .collect();
+
+    for (i, layer) in layers.iter().enumerate() {
+        let valid_name = !layer.name.is_empty()
+            && layer
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid_name {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!(
+                    "Invalid layer name '{}': use letters, digits, '-' and '_'",
+                    layer.name
+                ),
+            ));
+        }
+        if layer.remote.is_empty() {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("Layer '{}' needs a remote", layer.name),
+            ));
+        }
+        if layers[..i].iter().any(|other| other.name == layer.name) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("Duplicate layer name '{}'", layer.name),
+            ));
+        }
+    }
+
+    let changed = state
+        .settings
+        .set_library_layers(layers.clone())
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let (library_reinitialized, library_error) = match state.settings.get_library_remote().await {
+        Some(remote) if changed => match reinitialize_library(&state, &remote).await {
+            Ok(()) => (true, None),
+            Err(e) => (false, Some(e)),
+        },
+        _ => (false, None),
+    };
+
+    Ok(Json(UpdateLibraryLayersResponse {
+        library_layers: layers,
+        library_reinitialized,
+        library_error,
+    }))
+}
+
+/// GET /api/settings/tool-policy
+/// Get the tool permission policy.
+async fn get_tool_policy(State(state): State<Arc<AppState>>) -> Json<ToolPolicy> {
+    Json(state.settings.get_tool_policy().await)
+}
+
+/// PUT /api/settings/tool-policy
+/// Replace the tool permission policy.
+async fn update_tool_policy(
+    State(state): State<Arc<AppState>>,
+    Json(policy): Json<ToolPolicy>,
+) -> Result<Json<ToolPolicy>, (StatusCode, String)> {
+    if policy.rules.iter().any(|r| r.tool.trim().is_empty()) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Every rule needs a non-empty tool pattern".to_string(),
+        ));
+    }
+
+    state
+        .settings
+        .set_tool_policy(policy.clone())
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(policy))
+}
+
+/// GET /api/settings/tool-result-limits
+/// Get the tool result size limits.
+async fn get_tool_result_limits(State(state): State<Arc<AppState>>) -> Json<ToolResultLimits> {
+    Json(state.settings.get_tool_result_limits().await)
+}
+
+/// PUT /api/settings/tool-result-limits
+/// Replace the tool result size limits.
+async fn update_tool_result_limits(
+    State(state): State<Arc<AppState>>,
+    Json(limits): Json<ToolResultLimits>,
+) -> Result<Json<ToolResultLimits>, (StatusCode, String)> {
+    if limits.tools.iter().any(|l| l.tool.trim().is_empty()) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Every override needs a non-empty tool pattern".to_string(),
+        ));
+    }
+
+    state
+        .settings
+        .set_tool_result_limits(limits.clone())
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(limits))
+}
+
 /// Reinitialize the library with a new remote URL.
 async fn reinitialize_library(state: &Arc<AppState>, remote: &str) -> Result<(), String> {
     let library_path = state.config.library_path.clone();
+    let layers = state.settings.get_library_layers().await;
 
-    match crate::library::LibraryStore::new(library_path, remote).await {
+    match crate::library::LibraryStore::with_layers(library_path, remote, &layers).await {
         Ok(store) => {
             // Sync OpenCode plugins
             if let Ok(plugins) = store.get_plugins().await {
@@ -156,7 +315,7 @@ async fn reinitialize_library(state: &Arc<AppState>, remote: &str) -> Result<(),
             }
 
             tracing::info!("Configuration library reinitialized from {}", remote);
-            let library = Arc::new(store);
+            let library = Arc::new(store.with_read_only(state.config.library_read_only));
             *state.library.write().await = Some(Arc::clone(&library));
 
             // Sync skills/tools to all workspaces
@@ -0,0 +1,114 @@
+//! Graceful shutdown.
+//!
+//! On SIGTERM/SIGINT the server first drains: no new turn starts (queued
+//! messages stay queued, new messages are refused with a 503) and running
+//! turns get up to `SHUTDOWN_GRACE_SECS` to finish. Missions still running
+//! after that are checkpointed, cancelled and marked interrupted, so they can
+//! be resumed after the restart. Last, the event logs are given a moment to
+//! catch up with the final events.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use uuid::Uuid;
+
+use super::control::{ControlCommand, ControlHub, ControlState};
+
+/// How often draining sessions are polled.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// How long the event logs get to write the last events.
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(5);
+
+static DRAINING: AtomicBool = AtomicBool::new(false);
+
+/// Stop starting new turns, for good.
+pub fn begin_draining() {
+    DRAINING.store(true, Ordering::SeqCst);
+}
+
+/// Whether the server is shutting down. No new turn starts while it is.
+pub fn is_draining() -> bool {
+    DRAINING.load(Ordering::SeqCst)
+}
+
+/// Whether a running-mission state means a turn is in flight.
+fn is_busy_state(state: &str) -> bool {
+    matches!(state, "running" | "waiting_for_tool")
+}
+
+/// Missions with a turn in flight, across every session.
+async fn busy_missions(control: &ControlHub) -> Vec<Uuid> {
+    control
+        .running_missions()
+        .await
+        .into_iter()
+        .filter(|mission| is_busy_state(&mission.state))
+        .map(|mission| mission.mission_id)
+        .collect()
+}
+
+/// Stop new turns and wait up to `grace` for the running ones to finish.
+pub async fn drain(control: &ControlHub, grace: Duration) {
+    begin_draining();
+    let deadline = Instant::now() + grace;
+    loop {
+        let busy = busy_missions(control).await;
+        if busy.is_empty() {
+            tracing::info!("All running turns finished");
+            return;
+        }
+        if Instant::now() >= deadline {
+            tracing::warn!(
+                "{} missions still running after the {}s grace period",
+                busy.len(),
+                grace.as_secs()
+            );
+            return;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Checkpoint, cancel and mark interrupted whatever a session still runs.
+pub async fn interrupt(session: &ControlState) -> Vec<Uuid> {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    if let Err(e) = session
+        .cmd_tx
+        .send(ControlCommand::GracefulShutdown { respond: tx })
+        .await
+    {
+        tracing::error!("Failed to send shutdown command: {}", e);
+        return Vec::new();
+    }
+    match rx.await {
+        Ok(interrupted) => interrupted,
+        Err(e) => {
+            tracing::error!("Failed to receive shutdown response: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Wait (briefly) until every subscriber, the event logs included, has seen
+/// the session's events.
+pub async fn flush_events(session: &ControlState) {
+    let deadline = Instant::now() + FLUSH_TIMEOUT;
+    while !session.events_tx.is_empty() && Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    // Give the writers time to finish the event they just received
+    tokio::time::sleep(Duration::from_millis(100)).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_turns_in_flight_are_busy() {
+        assert!(is_busy_state("running"));
+        assert!(is_busy_state("waiting_for_tool"));
+        assert!(!is_busy_state("queued"));
+        assert!(!is_busy_state("finished"));
+    }
+}
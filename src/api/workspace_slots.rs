@@ -0,0 +1,87 @@
+//! Per-workspace mission concurrency.
+//!
+//! Missions sharing a workspace share its files, so a workspace's
+//! `max_concurrent_missions` caps how many of them run at once. A mission
+//! takes a slot when its runner starts a turn and gives it back once the
+//! runner is idle again. A mission that finds the workspace full stays in
+//! `waiting_for_workspace` and starts when a slot frees up. Slots are kept in
+//! memory only.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, OnceLock};
+
+use uuid::Uuid;
+
+fn slots() -> &'static Mutex<HashMap<Uuid, HashSet<Uuid>>> {
+    static SLOTS: OnceLock<Mutex<HashMap<Uuid, HashSet<Uuid>>>> = OnceLock::new();
+    SLOTS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Take a slot in `workspace_id` for `mission_id`, unless `limit` missions
+/// already hold one. A mission holding a slot keeps it.
+pub fn try_acquire(workspace_id: Uuid, mission_id: Uuid, limit: Option<u32>) -> bool {
+    let mut slots = slots().lock().unwrap_or_else(|e| e.into_inner());
+    let holders = slots.entry(workspace_id).or_default();
+    if holders.contains(&mission_id) {
+        return true;
+    }
+    if limit.is_some_and(|limit| holders.len() >= limit as usize) {
+        return false;
+    }
+    holders.insert(mission_id);
+    true
+}
+
+/// Give back the mission's slot, if it holds one.
+pub fn release(workspace_id: Uuid, mission_id: Uuid) {
+    let mut slots = slots().lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(holders) = slots.get_mut(&workspace_id) {
+        holders.remove(&mission_id);
+        if holders.is_empty() {
+            slots.remove(&workspace_id);
+        }
+    }
+}
+
+/// How many missions currently run in the workspace.
+pub fn running_in(workspace_id: Uuid) -> usize {
+    slots()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&workspace_id)
+        .map_or(0, HashSet::len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limits_missions_per_workspace() {
+        let workspace = Uuid::new_v4();
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+        assert!(try_acquire(workspace, a, Some(2)));
+        assert!(try_acquire(workspace, a, Some(2)));
+        assert!(try_acquire(workspace, b, Some(2)));
+        assert!(!try_acquire(workspace, c, Some(2)));
+        assert_eq!(running_in(workspace), 2);
+
+        release(workspace, a);
+        assert!(try_acquire(workspace, c, Some(2)));
+        assert!(!try_acquire(workspace, a, Some(2)));
+
+        release(workspace, b);
+        release(workspace, c);
+        assert_eq!(running_in(workspace), 0);
+    }
+
+    #[test]
+    fn unlimited_without_a_limit() {
+        let workspace = Uuid::new_v4();
+        for _ in 0..10 {
+            assert!(try_acquire(workspace, Uuid::new_v4(), None));
+        }
+        assert_eq!(running_in(workspace), 10);
+    }
+}
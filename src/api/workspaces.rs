@@ -75,8 +75,16 @@ pub struct CreateWorkspaceRequest {
     /// Empty = use default MCPs (those with `default_enabled = true`).
     #[serde(default)]
     pub mcps: Vec<String>,
+    /// Tool names available in this workspace (`*` globs allowed).
+    /// Empty = all tools.
+    #[serde(default)]
+    pub tools: Vec<String>,
     /// Optional config profile to apply to this workspace.
     pub config_profile: Option<String>,
+    /// Library branch to pin this workspace to (empty = follow the library checkout).
+    pub library_branch: Option<String>,
+    /// Missions that may run in this workspace at once (0 = no limit).
+    pub max_concurrent_missions: Option<u32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -104,8 +112,14 @@ pub struct UpdateWorkspaceRequest {
     pub tailscale_mode: Option<TailscaleMode>,
     /// MCP server names to enable for this workspace.
     pub mcps: Option<Vec<String>>,
+    /// Tool names available in this workspace (empty = all tools).
+    pub tools: Option<Vec<String>>,
     /// Optional config profile to apply to this workspace.
     pub config_profile: Option<String>,
+    /// Library branch to pin this workspace to (empty = follow the library checkout).
+    pub library_branch: Option<String>,
+    /// Missions that may run in this workspace at once (0 = no limit).
+    pub max_concurrent_missions: Option<u32>,
 }
 
 #[derive(Debug, Serialize)]
@@ -127,7 +141,10 @@ pub struct WorkspaceResponse {
     pub shared_network: Option<bool>,
     pub tailscale_mode: Option<TailscaleMode>,
     pub mcps: Vec<String>,
+    pub tools: Vec<String>,
     pub config_profile: Option<String>,
+    pub library_branch: Option<String>,
+    pub max_concurrent_missions: Option<u32>,
 }
 
 impl From<Workspace> for WorkspaceResponse {
@@ -150,7 +167,10 @@ impl From<Workspace> for WorkspaceResponse {
             shared_network: w.shared_network,
             tailscale_mode: w.tailscale_mode,
             mcps: w.mcps,
+            tools: w.tools,
             config_profile: w.config_profile,
+            library_branch: w.library_branch,
+            max_concurrent_missions: w.max_concurrent_missions,
         }
     }
 }
@@ -390,6 +410,12 @@ async fn create_workspace(
         }
     }
 
+    let library_branch = match req.library_branch.as_deref() {
+        Some(branch) => normalize_library_branch(branch)?,
+        None => None,
+    };
+    let max_concurrent_missions = req.max_concurrent_missions.filter(|max| *max > 0);
+
     let mut workspace = match workspace_type {
         WorkspaceType::Host => Workspace {
             id: Uuid::new_v4(),
@@ -410,7 +436,10 @@ async fn create_workspace(
             shared_network,
             tailscale_mode,
             mcps: mcps.clone(),
+            tools: req.tools.clone(),
             config_profile: config_profile.clone(),
+            library_branch: library_branch.clone(),
+            max_concurrent_missions,
         },
         WorkspaceType::Container => {
             let mut ws = Workspace::new_container(req.name, path);
@@ -424,7 +453,10 @@ async fn create_workspace(
             ws.shared_network = shared_network;
             ws.tailscale_mode = tailscale_mode;
             ws.mcps = mcps;
+            ws.tools = req.tools;
             ws.config_profile = config_profile;
+            ws.library_branch = library_branch;
+            ws.max_concurrent_missions = max_concurrent_missions;
             ws
         }
     };
@@ -614,6 +646,11 @@ async fn update_workspace(
         workspace.mcps = mcps;
     }
 
+    // Update the tool allowlist if provided
+    if let Some(tools) = req.tools {
+        workspace.tools = tools;
+    }
+
     if let Some(config_profile) = req.config_profile {
         let trimmed = config_profile.trim();
         if trimmed.is_empty() {
@@ -623,6 +660,14 @@ async fn update_workspace(
         }
     }
 
+    if let Some(library_branch) = req.library_branch {
+        workspace.library_branch = normalize_library_branch(&library_branch)?;
+    }
+
+    if let Some(max) = req.max_concurrent_missions {
+        workspace.max_concurrent_missions = Some(max).filter(|max| *max > 0);
+    }
+
     // Save the updated workspace
     state.workspaces.update(workspace.clone()).await;
 
@@ -768,6 +813,27 @@ fn normalize_init_script(value: Option<String>) -> Option<String> {
     })
 }
 
+/// Trim a library branch pin; empty clears it. Git re-validates the name
+/// when the branch is checked out.
+fn normalize_library_branch(value: &str) -> Result<Option<String>, (StatusCode, String)> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        return Ok(None);
+    }
+    if trimmed.starts_with('-')
+        || trimmed.contains("..")
+        || trimmed
+            .chars()
+            .any(|c| c.is_whitespace() || c.is_control() || "~^:?*[\\".contains(c))
+    {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("Invalid library branch name: {}", trimmed),
+        ));
+    }
+    Ok(Some(trimmed.to_string()))
+}
+
 fn sanitize_env_vars(env_vars: HashMap<String, String>) -> HashMap<String, String> {
     env_vars
         .into_iter()
@@ -0,0 +1,325 @@
+//! Amp login token lifecycle.
+//!
+//! Runs the OAuth device authorization flow against the Amp service, persists
+//! the resulting tokens in the secrets store, and refreshes them before they
+//! expire so missions never depend on a manually pre-authenticated CLI.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::secrets::{SecretMetadata, SecretType, SecretsStore};
+
+/// Secrets registry holding Amp login tokens.
+pub const AMP_SECRETS_REGISTRY: &str = "amp";
+const ACCESS_TOKEN_KEY: &str = "access_token";
+const REFRESH_TOKEN_KEY: &str = "refresh_token";
+
+const AMP_CLIENT_ID: &str = "amp-cli";
+const DEFAULT_AMP_URL: &str = "https://ampcode.com";
+const DEVICE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+/// Refresh tokens that expire within this window.
+const REFRESH_MARGIN_SECS: i64 = 300;
+
+/// Device authorization returned when a login starts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceAuthorization {
+    #[serde(skip_serializing)]
+    pub device_code: String,
+    pub user_code: String,
+    pub verification_uri: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verification_uri_complete: Option<String>,
+    #[serde(default = "default_device_expires_in")]
+    pub expires_in: u64,
+    #[serde(default = "default_poll_interval")]
+    pub interval: u64,
+}
+
+fn default_device_expires_in() -> u64 {
+    900
+}
+
+fn default_poll_interval() -> u64 {
+    5
+}
+
+/// Amp login tokens.
+#[derive(Debug, Clone)]
+pub struct AmpToken {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    /// Expiry as a unix timestamp in seconds.
+    pub expires_at: Option<i64>,
+}
+
+impl AmpToken {
+    /// Whether the access token is expired or about to expire.
+    pub fn needs_refresh(&self, now: i64) -> bool {
+        self.expires_at
+            .map(|exp| exp - REFRESH_MARGIN_SECS <= now)
+            .unwrap_or(false)
+    }
+
+    fn from_response(value: &serde_json::Value, previous_refresh: Option<&str>) -> Result<Self> {
+        let access_token = value
+            .get("access_token")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| anyhow!("No access token in Amp token response"))?;
+        let refresh_token = value
+            .get("refresh_token")
+            .and_then(|v| v.as_str())
+            .or(previous_refresh)
+            .map(|s| s.to_string());
+        let expires_at = value
+            .get("expires_in")
+            .and_then(|v| v.as_i64())
+            .map(|secs| chrono::Utc::now().timestamp() + secs);
+        Ok(Self {
+            access_token: access_token.to_string(),
+            refresh_token,
+            expires_at,
+        })
+    }
+}
+
+/// Resolve the Amp service URL (`AMP_URL`, default `https://ampcode.com`).
+pub fn amp_service_url() -> String {
+    std::env::var("AMP_URL")
+        .ok()
+        .map(|s| s.trim().trim_end_matches('/').to_string())
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| DEFAULT_AMP_URL.to_string())
+}
+
+/// Start a device authorization flow.
+pub async fn start_device_flow(amp_url: &str) -> Result<DeviceAuthorization> {
+    let client = reqwest::Client::new();
+    let response = client
+        .post(format!("{}/api/auth/device/code", amp_url))
+        .form(&[("client_id", AMP_CLIENT_ID)])
+        .send()
+        .await
+        .map_err(|e| anyhow!("Failed to start Amp login: {}", e))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(anyhow!("Amp login failed to start ({}): {}", status, body));
+    }
+
+    response
+        .json::<DeviceAuthorization>()
+        .await
+        .map_err(|e| anyhow!("Failed to parse Amp device authorization: {}", e))
+}
+
+/// Poll the token endpoint until the user approves the device or the code expires.
+pub async fn poll_device_token(amp_url: &str, auth: &DeviceAuthorization) -> Result<AmpToken> {
+    let client = reqwest::Client::new();
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(auth.expires_in);
+    let mut interval = auth.interval.max(1);
+
+    loop {
+        tokio::time::sleep(Duration::from_secs(interval)).await;
+        if tokio::time::Instant::now() >= deadline {
+            return Err(anyhow!("Amp login expired before it was approved"));
+        }
+
+        let response = client
+            .post(format!("{}/api/auth/device/token", amp_url))
+            .form(&[
+                ("grant_type", DEVICE_GRANT_TYPE),
+                ("device_code", auth.device_code.as_str()),
+                ("client_id", AMP_CLIENT_ID),
+            ])
+            .send()
+            .await
+            .map_err(|e| anyhow!("Failed to poll Amp login: {}", e))?;
+
+        let status = response.status();
+        let body: serde_json::Value = response.json().await.unwrap_or_default();
+        if status.is_success() {
+            return AmpToken::from_response(&body, None);
+        }
+
+        match body.get("error").and_then(|v| v.as_str()) {
+            Some("authorization_pending") => continue,
+            Some("slow_down") => interval += 5,
+            Some(other) => return Err(anyhow!("Amp login failed: {}", other)),
+            None => return Err(anyhow!("Amp login failed ({})", status)),
+        }
+    }
+}
+
+/// Exchange a refresh token for a fresh access token.
+pub async fn refresh_token(amp_url: &str, refresh_token: &str) -> Result<AmpToken> {
+    let client = reqwest::Client::new();
+    let response = client
+        .post(format!("{}/api/auth/token", amp_url))
+        .form(&[
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+            ("client_id", AMP_CLIENT_ID),
+        ])
+        .send()
+        .await
+        .map_err(|e| anyhow!("Failed to refresh Amp token: {}", e))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(anyhow!(
+            "Amp token refresh failed ({}): {}. You may need to log in again.",
+            status,
+            body
+        ));
+    }
+
+    let body: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| anyhow!("Failed to parse Amp token response: {}", e))?;
+    AmpToken::from_response(&body, Some(refresh_token))
+}
+
+/// Persist Amp tokens in the secrets store.
+pub async fn store_token(secrets: &SecretsStore, token: &AmpToken) -> Result<()> {
+    secrets
+        .set_secret(
+            AMP_SECRETS_REGISTRY,
+            ACCESS_TOKEN_KEY,
+            &token.access_token,
+            Some(SecretMetadata {
+                secret_type: Some(SecretType::OAuthAccessToken),
+                expires_at: token.expires_at,
+                ..Default::default()
+            }),
+        )
+        .await?;
+    if let Some(refresh) = &token.refresh_token {
+        secrets
+            .set_secret(
+                AMP_SECRETS_REGISTRY,
+                REFRESH_TOKEN_KEY,
+                refresh,
+                Some(SecretMetadata {
+                    secret_type: Some(SecretType::OAuthRefreshToken),
+                    ..Default::default()
+                }),
+            )
+            .await?;
+    }
+    Ok(())
+}
+
+/// Load Amp tokens from the secrets store, if a login has been completed.
+pub async fn load_token(secrets: &SecretsStore) -> Option<AmpToken> {
+    let access_token = secrets
+        .get_secret(AMP_SECRETS_REGISTRY, ACCESS_TOKEN_KEY)
+        .await
+        .ok()?;
+    let refresh_token = secrets
+        .get_secret(AMP_SECRETS_REGISTRY, REFRESH_TOKEN_KEY)
+        .await
+        .ok();
+    let expires_at = secrets
+        .list_secrets(AMP_SECRETS_REGISTRY)
+        .await
+        .ok()
+        .and_then(|list| {
+            list.into_iter()
+                .find(|s| s.key == ACCESS_TOKEN_KEY)
+                .and_then(|s| s.expires_at)
+        });
+    Some(AmpToken {
+        access_token,
+        refresh_token,
+        expires_at,
+    })
+}
+
+/// Whether a login token (or a refresh token able to mint one) is stored.
+pub async fn has_login(secrets: &SecretsStore) -> bool {
+    match secrets.list_secrets(AMP_SECRETS_REGISTRY).await {
+        Ok(list) => list
+            .iter()
+            .any(|s| (s.key == ACCESS_TOKEN_KEY && !s.is_expired) || s.key == REFRESH_TOKEN_KEY),
+        Err(_) => false,
+    }
+}
+
+/// Return a valid Amp access token, refreshing and re-persisting it when it is
+/// close to expiry.
+pub async fn resolve_access_token(secrets: &SecretsStore) -> Option<String> {
+    let token = load_token(secrets).await?;
+    if !token.needs_refresh(chrono::Utc::now().timestamp()) {
+        return Some(token.access_token);
+    }
+
+    let Some(refresh) = token.refresh_token.as_deref() else {
+        tracing::warn!("Amp login token expired and no refresh token is stored");
+        return None;
+    };
+
+    match refresh_token(&amp_service_url(), refresh).await {
+        Ok(fresh) => {
+            if let Err(e) = store_token(secrets, &fresh).await {
+                tracing::warn!("Failed to persist refreshed Amp token: {}", e);
+            }
+            tracing::info!("Refreshed Amp login token");
+            Some(fresh.access_token)
+        }
+        Err(e) => {
+            tracing::warn!("{}", e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_needs_refresh() {
+        let token = AmpToken {
+            access_token: "a".to_string(),
+            refresh_token: None,
+            expires_at: Some(1_000),
+        };
+        assert!(token.needs_refresh(1_000));
+        assert!(token.needs_refresh(1_000 - REFRESH_MARGIN_SECS));
+        assert!(!token.needs_refresh(1_000 - REFRESH_MARGIN_SECS - 1));
+
+        let no_expiry = AmpToken {
+            expires_at: None,
+            ..token
+        };
+        assert!(!no_expiry.needs_refresh(i64::MAX));
+    }
+
+    #[test]
+    fn test_token_response_keeps_previous_refresh_token() {
+        let body = serde_json::json!({"access_token": "new", "expires_in": 60});
+        let token = AmpToken::from_response(&body, Some("old-refresh")).unwrap();
+        assert_eq!(token.access_token, "new");
+        assert_eq!(token.refresh_token.as_deref(), Some("old-refresh"));
+        assert!(token.expires_at.is_some());
+
+        assert!(AmpToken::from_response(&serde_json::json!({}), None).is_err());
+    }
+
+    #[test]
+    fn test_parse_device_authorization() {
+        let json = r#"{"device_code":"dc","user_code":"ABCD-EFGH","verification_uri":"https://ampcode.com/device"}"#;
+        let auth: DeviceAuthorization = serde_json::from_str(json).unwrap();
+        assert_eq!(auth.interval, 5);
+        assert_eq!(auth.expires_in, 900);
+        let out = serde_json::to_value(&auth).unwrap();
+        assert!(out.get("device_code").is_none());
+        assert_eq!(out["user_code"], "ABCD-EFGH");
+    }
+}
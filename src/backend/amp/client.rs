@@ -21,7 +21,7 @@ pub struct AmpConfig {
     pub default_model: Option<String>,
     /// Default mode (smart, rush)
     pub default_mode: Option<String>,
-    /// Amp API key for authentication
+    /// Amp API key or login access token, injected as `AMP_API_KEY`
     pub api_key: Option<String>,
 }
 
@@ -82,6 +82,10 @@ impl AmpClient {
         // The message is passed as the final argument
         cmd.arg(message);
 
+        if let Some(key) = self.config.api_key.as_deref().filter(|k| !k.is_empty()) {
+            cmd.env("AMP_API_KEY", key);
+        }
+
         cmd.stdin(Stdio::piped());
         cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::piped());
@@ -198,6 +202,10 @@ impl AmpClient {
         // Message
         cmd.arg(message);
 
+        if let Some(key) = self.config.api_key.as_deref().filter(|k| !k.is_empty()) {
+            cmd.env("AMP_API_KEY", key);
+        }
+
         cmd.stdin(Stdio::piped());
         cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::piped());
@@ -1,3 +1,4 @@
+pub mod auth;
 pub mod client;
 
 use anyhow::Error;
@@ -11,6 +12,7 @@ use tracing::debug;
 use crate::backend::events::ExecutionEvent;
 use crate::backend::shared::convert_cli_event;
 use crate::backend::{AgentInfo, Backend, Session, SessionConfig};
+use crate::secrets::SecretsStore;
 
 use client::{AmpClient, AmpConfig};
 
@@ -19,6 +21,8 @@ pub struct AmpBackend {
     id: String,
     name: String,
     config: Arc<RwLock<AmpConfig>>,
+    /// Secrets store holding the Amp login token (if logged in via the API)
+    secrets: Option<Arc<SecretsStore>>,
 }
 
 impl AmpBackend {
@@ -27,6 +31,7 @@ impl AmpBackend {
             id: "amp".to_string(),
             name: "Amp".to_string(),
             config: Arc::new(RwLock::new(AmpConfig::default())),
+            secrets: None,
         }
     }
 
@@ -35,9 +40,16 @@ impl AmpBackend {
             id: "amp".to_string(),
             name: "Amp".to_string(),
             config: Arc::new(RwLock::new(config)),
+            secrets: None,
         }
     }
 
+    /// Use the secrets store to resolve login tokens when no API key is configured.
+    pub fn with_secrets(mut self, secrets: Option<Arc<SecretsStore>>) -> Self {
+        self.secrets = secrets;
+        self
+    }
+
     /// Update the backend configuration.
     pub async fn update_config(&self, config: AmpConfig) {
         let mut cfg = self.config.write().await;
@@ -95,7 +107,12 @@ impl Backend for AmpBackend {
         session: &Session,
         message: &str,
     ) -> Result<(mpsc::Receiver<ExecutionEvent>, JoinHandle<()>), Error> {
-        let config = self.config.read().await.clone();
+        let mut config = self.config.read().await.clone();
+        if config.api_key.is_none() {
+            if let Some(store) = self.secrets.as_ref() {
+                config.api_key = auth::resolve_access_token(store).await;
+            }
+        }
         let client = AmpClient::with_config(config);
 
         let (mut amp_rx, amp_handle) = client
@@ -141,8 +158,8 @@ impl Backend for AmpBackend {
 }
 
 /// Create a registry entry for the Amp backend.
-pub fn registry_entry() -> Arc<dyn Backend> {
-    Arc::new(AmpBackend::new())
+pub fn registry_entry(secrets: Option<Arc<SecretsStore>>) -> Arc<dyn Backend> {
+    Arc::new(AmpBackend::new().with_secrets(secrets))
 }
 
 #[cfg(test)]
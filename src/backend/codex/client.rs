@@ -62,6 +62,7 @@ impl CodexClient {
         _session_id: Option<&str>, // Codex doesn't support session IDs like Claude
         _agent: Option<&str>,      // Codex doesn't have agent types like Claude
         workspace_exec: Option<&WorkspaceExec>,
+        stderr_tx: Option<mpsc::UnboundedSender<String>>,
     ) -> Result<(mpsc::Receiver<CodexEvent>, ProcessHandle)> {
         let (tx, rx) = mpsc::channel(256);
 
@@ -164,6 +165,9 @@ impl CodexClient {
                     continue;
                 }
                 debug!("Codex stderr: {}", trimmed);
+                if let Some(stderr_tx) = &stderr_tx {
+                    let _ = stderr_tx.send(trimmed.to_string());
+                }
 
                 // Keep a small excerpt to surface in "No response" cases.
                 let mut captured = stderr_capture_clone.lock().await;
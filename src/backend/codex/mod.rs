@@ -18,6 +18,10 @@ pub struct CodexBackend {
     name: String,
     config: Arc<RwLock<CodexConfig>>,
     workspace_exec: Option<crate::workspace_exec::WorkspaceExec>,
+    /// Mission the spawned CLI belongs to, for the process registry.
+    mission_id: Option<uuid::Uuid>,
+    /// Receives the CLI's stderr lines as they are printed.
+    stderr_tx: Option<mpsc::UnboundedSender<String>>,
 }
 
 impl CodexBackend {
@@ -27,6 +31,8 @@ impl CodexBackend {
             name: "Codex".to_string(),
             config: Arc::new(RwLock::new(CodexConfig::default())),
             workspace_exec: None,
+            mission_id: None,
+            stderr_tx: None,
         }
     }
 
@@ -36,6 +42,8 @@ impl CodexBackend {
             name: "Codex".to_string(),
             config: Arc::new(RwLock::new(config)),
             workspace_exec: None,
+            mission_id: None,
+            stderr_tx: None,
         }
     }
 
@@ -48,9 +56,23 @@ impl CodexBackend {
             name: "Codex".to_string(),
             config: Arc::new(RwLock::new(config)),
             workspace_exec: Some(workspace_exec),
+            mission_id: None,
+            stderr_tx: None,
         }
     }
 
+    /// Record spawned CLI processes under this mission in the process registry.
+    pub fn with_mission_id(mut self, mission_id: uuid::Uuid) -> Self {
+        self.mission_id = Some(mission_id);
+        self
+    }
+
+    /// Forward the CLI's stderr lines to `stderr_tx`.
+    pub fn with_stderr_sink(mut self, stderr_tx: mpsc::UnboundedSender<String>) -> Self {
+        self.stderr_tx = Some(stderr_tx);
+        self
+    }
+
     /// Update the backend configuration.
     pub async fn update_config(&self, config: CodexConfig) {
         let mut cfg = self.config.write().await;
@@ -115,9 +137,19 @@ impl Backend for CodexBackend {
                 Some(&session.id),
                 session.agent.as_deref(),
                 workspace_exec,
+                self.stderr_tx.clone(),
             )
             .await?;
 
+        let process_guard = match self.mission_id {
+            Some(mission_id) => Some(crate::process_registry::track(
+                codex_handle.pid().await,
+                mission_id,
+                "codex",
+            )),
+            None => None,
+        };
+
         let (tx, rx) = mpsc::channel(256);
         let session_id = session.id.clone();
 
@@ -147,6 +179,7 @@ impl Backend for CodexBackend {
 
             // Drop the codex handle to clean up
             drop(codex_handle);
+            drop(process_guard);
         });
 
         Ok((rx, handle))
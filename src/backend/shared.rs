@@ -12,10 +12,13 @@ use std::sync::Arc;
 use tokio::process::Child;
 use tokio::sync::Mutex;
 use tokio::task::JoinHandle;
-use tracing::{debug, info, warn};
+use tracing::{debug, info};
 
 use super::events::ExecutionEvent;
 
+#[cfg(test)]
+mod fixtures;
+
 // ── Process handle ────────────────────────────────────────────────
 
 /// Handle to a running CLI process (Claude Code or Amp).
@@ -33,14 +36,16 @@ impl ProcessHandle {
         }
     }
 
-    /// Kill the underlying CLI process.
+    /// OS process id of the CLI, if it is still running.
+    pub async fn pid(&self) -> Option<u32> {
+        self.child.lock().await.as_ref().and_then(|c| c.id())
+    }
+
+    /// Kill the underlying CLI process and everything it started.
     pub async fn kill(&self) {
         if let Some(mut child) = self.child.lock().await.take() {
-            if let Err(e) = child.kill().await {
-                warn!("Failed to kill CLI process: {}", e);
-            } else {
-                info!("CLI process killed");
-            }
+            crate::workspace_exec::kill_process_tree(&mut child).await;
+            info!("CLI process killed");
         }
     }
 }
@@ -172,7 +177,7 @@ pub struct AssistantMessage {
     pub model: Option<String>,
     #[serde(default)]
     pub id: Option<String>,
-    /// Amp extension.
+    /// Token usage of the message so far.
     #[serde(default)]
     pub usage: Option<Usage>,
 }
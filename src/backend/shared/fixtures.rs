@@ -0,0 +1,185 @@
+//! Replay recorded CLI transcripts through the event converters.
+//!
+//! A fixture is a raw transcript `<name>.jsonl` (one CLI output line per line)
+//! next to `<name>.expected.json`, the exact `ExecutionEvent` sequence the
+//! converter must produce. Fixtures live in `src/backend/shared/fixtures/<backend>/`
+//! where `<backend>` is `claudecode`, `amp` or `opencode`:
+//!
+//! - Claude Code and Amp transcripts are `stream-json` output, replayed through
+//!   [`convert_cli_event`](super::convert_cli_event).
+//! - OpenCode transcripts are the `data:` payloads of the `/event` SSE stream,
+//!   replayed through the SSE parser. The session is taken from the first event
+//!   that carries a `sessionID`, as it is for a live stream.
+//!
+//! To capture a regression, record the CLI output, drop it in as a new fixture
+//! and write the expected events by hand.
+//!
+//! Expected events pin the converter's current output. In
+//! `claudecode/thinking_and_tool` the reply streamed as `text_delta` shows up
+//! again as `thinking` when its `assistant` message arrives: every assistant
+//! text block is surfaced as thinking, because without partial messages that
+//! block is the only copy of the narration between tool calls (the answer
+//! itself is taken from the `result` line). With partial messages on, the
+//! text is therefore emitted twice.
+
+use anyhow::{anyhow, Context, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::{convert_cli_event, CliEvent};
+use crate::backend::events::ExecutionEvent;
+
+/// Directory holding the crate's built-in fixtures.
+pub fn fixtures_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join("backend")
+        .join("shared")
+        .join("fixtures")
+}
+
+/// A recorded transcript and the events it must convert to.
+#[derive(Debug, Clone)]
+pub struct Fixture {
+    pub name: String,
+    pub backend: String,
+    pub transcript: String,
+    pub expected: Vec<ExecutionEvent>,
+}
+
+impl Fixture {
+    /// Load `<dir>/<backend>/<name>.jsonl` and its `.expected.json`.
+    pub fn load(dir: &Path, backend: &str, name: &str) -> Result<Self> {
+        let base = dir.join(backend);
+        let transcript_path = base.join(format!("{}.jsonl", name));
+        let expected_path = base.join(format!("{}.expected.json", name));
+        let transcript = std::fs::read_to_string(&transcript_path)
+            .with_context(|| format!("Failed to read {}", transcript_path.display()))?;
+        let expected = std::fs::read_to_string(&expected_path)
+            .with_context(|| format!("Failed to read {}", expected_path.display()))?;
+        let expected = serde_json::from_str(&expected)
+            .with_context(|| format!("Invalid events in {}", expected_path.display()))?;
+        Ok(Self {
+            name: name.to_string(),
+            backend: backend.to_string(),
+            transcript,
+            expected,
+        })
+    }
+
+    /// Replay the transcript through the converter for its backend.
+    pub fn replay(&self) -> Result<Vec<ExecutionEvent>> {
+        match self.backend.as_str() {
+            "claudecode" | "amp" => Ok(replay_cli_transcript(&self.transcript)),
+            "opencode" => Ok(replay_opencode_transcript(&self.transcript)),
+            other => Err(anyhow!("No converter for backend {}", other)),
+        }
+    }
+
+    /// Replay the transcript and compare against the expected events.
+    pub fn check(&self) -> Result<()> {
+        let actual = self.replay()?;
+        if actual == self.expected {
+            return Ok(());
+        }
+        let position = actual
+            .iter()
+            .zip(&self.expected)
+            .position(|(a, e)| a != e)
+            .unwrap_or_else(|| actual.len().min(self.expected.len()));
+        Err(anyhow!(
+            "{}/{}: events diverge at index {}\nexpected: {}\n  actual: {}",
+            self.backend,
+            self.name,
+            position,
+            serde_json::to_string_pretty(&self.expected[position..]).unwrap_or_default(),
+            serde_json::to_string_pretty(&actual[position..]).unwrap_or_default(),
+        ))
+    }
+}
+
+/// Load every fixture under `dir`, sorted by backend and name.
+pub fn load_all(dir: &Path) -> Result<Vec<Fixture>> {
+    let mut fixtures = Vec::new();
+    for backend in
+        std::fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))?
+    {
+        let backend = backend?;
+        if !backend.file_type()?.is_dir() {
+            continue;
+        }
+        let backend_name = backend.file_name().to_string_lossy().to_string();
+        for entry in std::fs::read_dir(backend.path())? {
+            let file_name = entry?.file_name().to_string_lossy().to_string();
+            if let Some(name) = file_name.strip_suffix(".jsonl") {
+                fixtures.push(Fixture::load(dir, &backend_name, name)?);
+            }
+        }
+    }
+    fixtures.sort_by(|a, b| (&a.backend, &a.name).cmp(&(&b.backend, &b.name)));
+    Ok(fixtures)
+}
+
+/// Replay a Claude Code / Amp `stream-json` transcript. Lines that are not
+/// CLI events are skipped, matching the live clients.
+pub fn replay_cli_transcript(transcript: &str) -> Vec<ExecutionEvent> {
+    let mut pending_tools = HashMap::new();
+    transcript
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| serde_json::from_str::<CliEvent>(line).ok())
+        .flat_map(|event| convert_cli_event(event, &mut pending_tools))
+        .collect()
+}
+
+/// Replay the SSE `data:` payloads of an OpenCode event stream.
+pub fn replay_opencode_transcript(transcript: &str) -> Vec<ExecutionEvent> {
+    let lines: Vec<&str> = transcript
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+    let session_id = lines
+        .iter()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .find_map(|json| {
+            let props = json.get("properties")?;
+            props
+                .get("sessionID")
+                .or_else(|| props.get("info").and_then(|v| v.get("sessionID")))
+                .or_else(|| props.get("part").and_then(|v| v.get("sessionID")))
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string())
+        })
+        .unwrap_or_default();
+
+    let mut state = crate::opencode::SseState::default();
+    lines
+        .into_iter()
+        .filter_map(|line| crate::opencode::parse_sse_event(line, None, &session_id, &mut state))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_fixtures_replay() {
+        let fixtures = load_all(&fixtures_dir()).expect("load fixtures");
+        assert!(!fixtures.is_empty());
+        for backend in ["claudecode", "amp", "opencode"] {
+            assert!(
+                fixtures.iter().any(|f| f.backend == backend),
+                "no fixtures for {}",
+                backend
+            );
+        }
+        for fixture in &fixtures {
+            if let Err(e) = fixture.check() {
+                panic!("{}", e);
+            }
+        }
+    }
+}
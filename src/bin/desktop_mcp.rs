@@ -10,7 +10,9 @@ use std::path::PathBuf;
 use std::process::Stdio;
 use std::sync::atomic::{AtomicU32, Ordering};
 
-use sandboxed_sh::tools::desktop::find_browser_command;
+use sandboxed_sh::tools::desktop::{
+    annotate_args, find_browser_command, last_capture, record_last_capture, LastCapture, Region,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 
@@ -509,24 +511,17 @@ fn tool_screenshot(args: &Value) -> Result<(String, Option<String>), String> {
         return Err("Screenshot file was not created".to_string());
     }
 
+    let capture = LastCapture {
+        path: filepath.clone(),
+        region: args.get("region").and_then(|r| Region::from_value(r).ok()),
+    };
+    if let Err(e) = record_last_capture(&working_dir, &capture) {
+        eprintln!("[desktop-mcp] Failed to record last capture: {}", e);
+    }
+
     let metadata =
         std::fs::metadata(&filepath).map_err(|e| format!("Failed to read file metadata: {}", e))?;
-
-    // Check if we should return the image data
-    let return_image = args
-        .get("return_image")
-        .and_then(|v| v.as_bool())
-        .unwrap_or(false);
-    let image_data = if return_image {
-        let data =
-            std::fs::read(&filepath).map_err(|e| format!("Failed to read screenshot: {}", e))?;
-        Some(base64::Engine::encode(
-            &base64::engine::general_purpose::STANDARD,
-            &data,
-        ))
-    } else {
-        None
-    };
+    let image_data = image_data_if_requested(args, &filepath)?;
 
     let result = format!(
         "{{\"success\": true, \"path\": \"{}\", \"size_bytes\": {}}}",
@@ -537,6 +532,128 @@ fn tool_screenshot(args: &Value) -> Result<(String, Option<String>), String> {
     Ok((result, image_data))
 }
 
+/// Base64 PNG data for `path` when the caller passed `return_image: true`.
+fn image_data_if_requested(args: &Value, path: &std::path::Path) -> Result<Option<String>, String> {
+    let return_image = args
+        .get("return_image")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    if !return_image {
+        return Ok(None);
+    }
+    let data = std::fs::read(path).map_err(|e| format!("Failed to read image: {}", e))?;
+    Ok(Some(base64::Engine::encode(
+        &base64::engine::general_purpose::STANDARD,
+        &data,
+    )))
+}
+
+// -----------------------------------------------------------------------------
+// Tool: desktop_screenshot_region
+// -----------------------------------------------------------------------------
+
+fn tool_screenshot_region(args: &Value) -> Result<(String, Option<String>), String> {
+    let region = Region::from_value(&json!({
+        "x": args.get("x"),
+        "y": args.get("y"),
+        "width": args.get("w"),
+        "height": args.get("h"),
+    }))
+    .map_err(|e| e.to_string())?;
+
+    let filename = args
+        .get("filename")
+        .and_then(|v| v.as_str())
+        .and_then(|name| std::path::Path::new(name).file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| {
+            format!(
+                "region_{}.png",
+                chrono::Utc::now().format("%Y%m%d_%H%M%S_%3f")
+            )
+        });
+
+    let mut screenshot_args = args.clone();
+    screenshot_args["filename"] = json!(filename);
+    screenshot_args["region"] = serde_json::to_value(region).map_err(|e| e.to_string())?;
+    tool_screenshot(&screenshot_args)
+}
+
+// -----------------------------------------------------------------------------
+// Tool: desktop_annotate
+// -----------------------------------------------------------------------------
+
+fn tool_annotate(args: &Value) -> Result<(String, Option<String>), String> {
+    let annotations = args
+        .get("annotations")
+        .and_then(|v| v.as_array())
+        .ok_or("Missing 'annotations' argument")?;
+
+    let working_dir = get_working_dir();
+    let (input, region) = match args.get("image").and_then(|v| v.as_str()) {
+        Some(image) => {
+            let path = working_dir.join(image);
+            let region = last_capture(&working_dir)
+                .filter(|c| c.path == path)
+                .and_then(|c| c.region);
+            (path, region)
+        }
+        None => {
+            let capture = last_capture(&working_dir)
+                .ok_or("No previous screenshot found; take one first or pass 'image'")?;
+            (capture.path, capture.region)
+        }
+    };
+    if !input.exists() {
+        return Err(format!("Image not found: {}", input.display()));
+    }
+
+    let filename = args
+        .get("filename")
+        .and_then(|v| v.as_str())
+        .and_then(|name| std::path::Path::new(name).file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| {
+            format!(
+                "annotated_{}.png",
+                chrono::Utc::now().format("%Y%m%d_%H%M%S_%3f")
+            )
+        });
+    let output = working_dir.join("screenshots").join(filename);
+
+    let magick_args =
+        annotate_args(&input, &output, annotations, region).map_err(|e| e.to_string())?;
+    let mut last_error = String::new();
+    let mut drawn = false;
+    for program in ["convert", "magick"] {
+        match std::process::Command::new(program)
+            .args(&magick_args)
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .output()
+        {
+            Ok(out) if out.status.success() => {
+                drawn = true;
+                break;
+            }
+            Ok(out) => last_error = String::from_utf8_lossy(&out.stderr).trim().to_string(),
+            Err(e) => last_error = format!("Failed to execute {}: {}", program, e),
+        }
+    }
+    if !drawn {
+        return Err(format!("Annotation failed: {}", last_error));
+    }
+
+    let image_data = image_data_if_requested(args, &output)?;
+    let result = json!({
+        "success": true,
+        "path": output.display().to_string(),
+        "source": input.display().to_string(),
+        "annotations": annotations.len(),
+    });
+    Ok((result.to_string(), image_data))
+}
+
 // -----------------------------------------------------------------------------
 // Tool: desktop_type
 // -----------------------------------------------------------------------------
@@ -817,6 +934,111 @@ fn tool_get_text(args: &Value) -> Result<String, String> {
     Ok(format!("--- OCR Text ---\n{}", text.trim()))
 }
 
+// -----------------------------------------------------------------------------
+// Tools: desktop_set_clipboard / desktop_get_clipboard
+// -----------------------------------------------------------------------------
+
+/// (program, write args, read args); xclip is preferred, xsel is the fallback.
+fn clipboard_backends(primary: bool) -> [(&'static str, Vec<&'static str>, Vec<&'static str>); 2] {
+    let selection = if primary { "primary" } else { "clipboard" };
+    let xsel_flag = if primary { "--primary" } else { "--clipboard" };
+    [
+        (
+            "xclip",
+            vec!["-selection", selection, "-in"],
+            vec!["-selection", selection, "-out"],
+        ),
+        (
+            "xsel",
+            vec![xsel_flag, "--input"],
+            vec![xsel_flag, "--output"],
+        ),
+    ]
+}
+
+fn tool_set_clipboard(args: &Value) -> Result<String, String> {
+    let display_id = args
+        .get("display")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'display' argument")?;
+    let text = args
+        .get("text")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'text' argument")?;
+    let primary = args.get("selection").and_then(|v| v.as_str()) == Some("primary");
+
+    for (program, write_args, _) in clipboard_backends(primary) {
+        // xclip/xsel fork a child that keeps the selection alive and inherits
+        // stdout/stderr, so those must not be pipes we wait on.
+        let mut child = match std::process::Command::new(program)
+            .args(&write_args)
+            .env("DISPLAY", display_id)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(format!("Failed to execute {}: {}", program, e)),
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(text.as_bytes())
+                .map_err(|e| format!("Failed to write to {}: {}", program, e))?;
+        }
+        let status = child
+            .wait()
+            .map_err(|e| format!("Failed to wait for {}: {}", program, e))?;
+        if !status.success() {
+            return Err(format!("{} failed to set the clipboard", program));
+        }
+        return Ok(json!({
+            "success": true,
+            "selection": if primary { "primary" } else { "clipboard" },
+            "chars": text.chars().count(),
+            "via": program,
+        })
+        .to_string());
+    }
+    Err("No clipboard tool found; install xclip or xsel".to_string())
+}
+
+fn tool_get_clipboard(args: &Value) -> Result<String, String> {
+    let display_id = args
+        .get("display")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'display' argument")?;
+    let primary = args.get("selection").and_then(|v| v.as_str()) == Some("primary");
+
+    for (program, _, read_args) in clipboard_backends(primary) {
+        let output = match std::process::Command::new(program)
+            .args(&read_args)
+            .env("DISPLAY", display_id)
+            .stdin(Stdio::null())
+            .output()
+        {
+            Ok(output) => output,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(format!("Failed to execute {}: {}", program, e)),
+        };
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let text = if output.status.success() {
+            String::from_utf8_lossy(&output.stdout).to_string()
+        } else if stderr.contains("not available") {
+            // xclip exits non-zero when nothing owns the selection.
+            String::new()
+        } else {
+            return Err(format!("{} failed: {}", program, stderr.trim()));
+        };
+        if text.is_empty() {
+            return Ok("(clipboard is empty)".to_string());
+        }
+        return Ok(text);
+    }
+    Err("No clipboard tool found; install xclip or xsel".to_string())
+}
+
 // =============================================================================
 // Tool Registry
 // =============================================================================
@@ -891,6 +1113,70 @@ fn get_tool_definitions() -> Vec<ToolDefinition> {
                 "required": ["display"]
             }),
         },
+        ToolDefinition {
+            name: "desktop_screenshot_region".to_string(),
+            description: "Capture a rectangle of the virtual desktop. Cheaper than a full screenshot when only part of the screen matters.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "display": {
+                        "type": "string",
+                        "description": "The display identifier (e.g., ':99')"
+                    },
+                    "x": { "type": "integer", "description": "Left edge in pixels" },
+                    "y": { "type": "integer", "description": "Top edge in pixels" },
+                    "w": { "type": "integer", "description": "Width in pixels" },
+                    "h": { "type": "integer", "description": "Height in pixels" },
+                    "return_image": {
+                        "type": "boolean",
+                        "description": "If true, return the image data as base64 for vision analysis (default: false)"
+                    },
+                    "filename": {
+                        "type": "string",
+                        "description": "Optional filename for the capture"
+                    }
+                },
+                "required": ["display", "x", "y", "w", "h"]
+            }),
+        },
+        ToolDefinition {
+            name: "desktop_annotate".to_string(),
+            description: "Draw boxes, markers and labels onto the last screenshot (or 'image') and save the result under screenshots/. Coordinates are screen pixels.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "annotations": {
+                        "type": "array",
+                        "description": "Shapes to draw. Give x/y plus width/height for a box, or only x/y for a point marker.",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "x": { "type": "integer" },
+                                "y": { "type": "integer" },
+                                "width": { "type": "integer" },
+                                "height": { "type": "integer" },
+                                "label": { "type": "string" },
+                                "color": { "type": "string", "description": "Color name or #rrggbb (default: red)" }
+                            },
+                            "required": ["x", "y"]
+                        }
+                    },
+                    "image": {
+                        "type": "string",
+                        "description": "Image to annotate, relative to the workspace (default: the last screenshot)"
+                    },
+                    "return_image": {
+                        "type": "boolean",
+                        "description": "If true, return the annotated image as base64 (default: false)"
+                    },
+                    "filename": {
+                        "type": "string",
+                        "description": "Optional filename for the annotated image"
+                    }
+                },
+                "required": ["annotations"]
+            }),
+        },
         ToolDefinition {
             name: "desktop_type".to_string(),
             description: "Send keyboard input. Provide 'text' to type characters or 'key' for special keys (Return, Tab, Escape, ctrl+a, alt+F4, etc.).".to_string(),
@@ -1014,6 +1300,48 @@ fn get_tool_definitions() -> Vec<ToolDefinition> {
                 "required": ["display", "command"]
             }),
         },
+        ToolDefinition {
+            name: "desktop_set_clipboard".to_string(),
+            description: "Put text on the desktop clipboard (xclip/xsel). Follow with desktop_type key='ctrl+v' to paste; faster and more reliable than typing long text.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "display": {
+                        "type": "string",
+                        "description": "The display identifier (e.g., ':99')"
+                    },
+                    "text": {
+                        "type": "string",
+                        "description": "Text to place on the clipboard"
+                    },
+                    "selection": {
+                        "type": "string",
+                        "enum": ["clipboard", "primary"],
+                        "description": "Which selection to set (default: clipboard)"
+                    }
+                },
+                "required": ["display", "text"]
+            }),
+        },
+        ToolDefinition {
+            name: "desktop_get_clipboard".to_string(),
+            description: "Read text from the desktop clipboard (xclip/xsel), e.g. after desktop_type key='ctrl+c'.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "display": {
+                        "type": "string",
+                        "description": "The display identifier (e.g., ':99')"
+                    },
+                    "selection": {
+                        "type": "string",
+                        "enum": ["clipboard", "primary"],
+                        "description": "Which selection to read (default: clipboard)"
+                    }
+                },
+                "required": ["display"]
+            }),
+        },
         ToolDefinition {
             name: "desktop_get_text".to_string(),
             description: "Extract visible text from the desktop using OCR (Tesseract).".to_string(),
@@ -1035,36 +1363,39 @@ fn execute_tool(name: &str, args: &Value) -> ToolResult {
     let result = match name {
         "desktop_start_session" => tool_start_session(args),
         "desktop_stop_session" => tool_stop_session(args),
-        "desktop_screenshot" => match tool_screenshot(args) {
-            Ok((text, Some(image_data))) => {
-                return ToolResult {
-                    content: vec![
-                        ToolContent::Text { text },
-                        ToolContent::Image {
-                            data: image_data,
-                            mime_type: "image/png".to_string(),
-                        },
-                    ],
-                    is_error: false,
-                };
-            }
-            Ok((text, None)) => Ok(text),
-            Err(e) => Err(e),
-        },
+        "desktop_screenshot" => return image_tool_result(tool_screenshot(args)),
+        "desktop_screenshot_region" => return image_tool_result(tool_screenshot_region(args)),
+        "desktop_annotate" => return image_tool_result(tool_annotate(args)),
         "desktop_type" => tool_type_text(args),
         "desktop_click" => tool_click(args),
         "desktop_mouse_move" => tool_mouse_move(args),
         "desktop_scroll" => tool_scroll(args),
         "desktop_i3_command" => tool_i3_command(args),
         "desktop_get_text" => tool_get_text(args),
+        "desktop_set_clipboard" => tool_set_clipboard(args),
+        "desktop_get_clipboard" => tool_get_clipboard(args),
         _ => Err(format!("Unknown tool: {}", name)),
     };
 
+    image_tool_result(result.map(|text| (text, None)))
+}
+
+/// Wrap a tool's text and optional base64 PNG into an MCP result.
+fn image_tool_result(result: Result<(String, Option<String>), String>) -> ToolResult {
     match result {
-        Ok(text) => ToolResult {
-            content: vec![ToolContent::Text { text }],
-            is_error: false,
-        },
+        Ok((text, image_data)) => {
+            let mut content = vec![ToolContent::Text { text }];
+            if let Some(data) = image_data {
+                content.push(ToolContent::Image {
+                    data,
+                    mime_type: "image/png".to_string(),
+                });
+            }
+            ToolResult {
+                content,
+                is_error: false,
+            }
+        }
         Err(e) => ToolResult {
             content: vec![ToolContent::Text { text: e }],
             is_error: true,
@@ -2,19 +2,29 @@
 //!
 //! Exposes a minimal set of Open Agent tools to OpenCode via MCP.
 //! Communicates over stdio using JSON-RPC 2.0.
+//!
+//! `tools/call` requests run concurrently (up to `MAX_CONCURRENT_TOOL_CALLS`)
+//! so a client issuing several independent calls at once isn't serialized;
+//! responses carry the request id and may arrive out of order.
 
 use std::collections::HashMap;
 use std::io::{BufRead, BufReader, Write};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::sync::RwLock;
+use std::sync::{Mutex, RwLock};
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use tokio::sync::Semaphore;
+use uuid::Uuid;
 
 use sandboxed_sh::tools;
-use sandboxed_sh::tools::Tool;
+use sandboxed_sh::tools::audit::ToolAuditEntry;
+use sandboxed_sh::tools::{PolicyAction, PolicyScope, Tool, ToolPolicy, ToolResultLimits};
+
+/// Maximum number of `tools/call` requests executing at once.
+const MAX_CONCURRENT_TOOL_CALLS: usize = 8;
 
 // =============================================================================
 // JSON-RPC Types
@@ -586,17 +596,85 @@ fn tool_set() -> HashMap<String, Arc<dyn Tool>> {
 
     tools.insert("read_file".to_string(), Arc::new(tools::ReadFile));
     tools.insert("write_file".to_string(), Arc::new(tools::WriteFile));
+    tools.insert("edit_file".to_string(), Arc::new(tools::EditFile));
+    tools.insert("apply_patch".to_string(), Arc::new(tools::ApplyPatch));
+    tools.insert("move_file".to_string(), Arc::new(tools::MoveFile));
+    tools.insert("copy_file".to_string(), Arc::new(tools::CopyFile));
+    tools.insert("archive_create".to_string(), Arc::new(tools::ArchiveCreate));
+    tools.insert(
+        "archive_extract".to_string(),
+        Arc::new(tools::ArchiveExtract),
+    );
     tools.insert("delete_file".to_string(), Arc::new(tools::DeleteFile));
     tools.insert("list_directory".to_string(), Arc::new(tools::ListDirectory));
     tools.insert("search_files".to_string(), Arc::new(tools::SearchFiles));
     tools.insert("grep_search".to_string(), Arc::new(tools::GrepSearch));
+    tools.insert(
+        "semantic_search".to_string(),
+        Arc::new(tools::SemanticSearch),
+    );
+    tools.insert("ssh_exec".to_string(), Arc::new(tools::SshExec));
+    tools.insert("process_start".to_string(), Arc::new(tools::ProcessStart));
+    tools.insert("process_status".to_string(), Arc::new(tools::ProcessStatus));
+    tools.insert("process_logs".to_string(), Arc::new(tools::ProcessLogs));
+    tools.insert("process_stop".to_string(), Arc::new(tools::ProcessStop));
+    tools.insert("git_branch".to_string(), Arc::new(tools::GitBranch));
+    tools.insert("git_checkout".to_string(), Arc::new(tools::GitCheckout));
+    tools.insert("git_push".to_string(), Arc::new(tools::GitPush));
+    tools.insert("git_stash".to_string(), Arc::new(tools::GitStash));
+    tools.insert(
+        "github_create_pr".to_string(),
+        Arc::new(tools::GitHubCreatePr),
+    );
+    tools.insert("github_comment".to_string(), Arc::new(tools::GitHubComment));
+    tools.insert(
+        "github_get_issue".to_string(),
+        Arc::new(tools::GitHubGetIssue),
+    );
+    tools.insert(
+        "github_list_checks".to_string(),
+        Arc::new(tools::GitHubListChecks),
+    );
+    tools.insert("docker_build".to_string(), Arc::new(tools::DockerBuild));
+    tools.insert("docker_run".to_string(), Arc::new(tools::DockerRun));
+    tools.insert("docker_logs".to_string(), Arc::new(tools::DockerLogs));
+    tools.insert("docker_stop".to_string(), Arc::new(tools::DockerStop));
+    tools.insert("k8s_get".to_string(), Arc::new(tools::K8sGet));
+    tools.insert("k8s_logs".to_string(), Arc::new(tools::K8sLogs));
+    tools.insert("k8s_describe".to_string(), Arc::new(tools::K8sDescribe));
+    // Asks for approval unless the tool policy allows it (see `check_policy`).
+    tools.insert("k8s_apply".to_string(), Arc::new(tools::K8sApply));
+    tools.insert("schedule_task".to_string(), Arc::new(tools::ScheduleTask));
+    tools.insert("notify".to_string(), Arc::new(tools::Notify));
+    tools.insert("ask_user".to_string(), Arc::new(tools::AskUser));
+    tools.insert("ui_progress".to_string(), Arc::new(tools::UiProgress));
+    tools.insert(
+        "register_artifact".to_string(),
+        Arc::new(tools::RegisterArtifact),
+    );
+    tools.insert("sqlite_query".to_string(), Arc::new(tools::SqliteQuery));
+    tools.insert("db_query".to_string(), Arc::new(tools::DbQuery));
+    tools.insert("pdf_extract".to_string(), Arc::new(tools::PdfExtract));
+    tools.insert("analyze_image".to_string(), Arc::new(tools::AnalyzeImage));
     tools.insert("fetch_url".to_string(), Arc::new(tools::FetchUrl));
+    tools.insert("http_request".to_string(), Arc::new(tools::HttpRequest));
+    tools.insert("web_search".to_string(), Arc::new(tools::WebSearch));
     tools.insert("update_skill".to_string(), Arc::new(UpdateSkillTool));
     tools.insert(
         "update_init_script".to_string(),
         Arc::new(UpdateInitScriptTool),
     );
 
+    // Honor the workspace tool allowlist (set when the workspace restricts tools).
+    if let Ok(raw) = std::env::var(tools::policy::TOOL_ALLOWLIST_ENV) {
+        let allowlist: Vec<String> = raw
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+        tools.retain(|name, _| tools::tool_allowed(&allowlist, name));
+    }
+
     tools
 }
 
@@ -613,11 +691,178 @@ fn tool_definitions(tools: &HashMap<String, Arc<dyn Tool>>) -> Vec<ToolDefinitio
     defs
 }
 
-fn execute_tool(
-    runtime: &tokio::runtime::Runtime,
+// =============================================================================
+// Tool Policy
+// =============================================================================
+
+/// Tool policy passed by the server. Without one the default policy applies;
+/// an unreadable one denies every call rather than silently allowing them.
+fn tool_policy() -> ToolPolicy {
+    let Ok(raw) = std::env::var(tools::policy::TOOL_POLICY_ENV) else {
+        return ToolPolicy::default();
+    };
+    serde_json::from_str(&raw).unwrap_or_else(|e| {
+        eprintln!(
+            "[workspace-mcp] Invalid tool policy, denying all tools: {}",
+            e
+        );
+        ToolPolicy {
+            default_action: PolicyAction::Deny,
+            rules: Vec::new(),
+        }
+    })
+}
+
+/// Result limits passed by the server, or the defaults.
+fn result_limits() -> ToolResultLimits {
+    let Ok(raw) = std::env::var(tools::result_limits::TOOL_RESULT_LIMITS_ENV) else {
+        return ToolResultLimits::default();
+    };
+    serde_json::from_str(&raw).unwrap_or_else(|e| {
+        eprintln!(
+            "[workspace-mcp] Invalid tool result limits, using defaults: {}",
+            e
+        );
+        ToolResultLimits::default()
+    })
+}
+
+/// Mission and workspace the current call runs under.
+fn policy_scope() -> PolicyScope {
+    let env_uuid = |key: &str| {
+        std::env::var(key)
+            .ok()
+            .and_then(|v| Uuid::parse_str(v.trim()).ok())
+    };
+    PolicyScope {
+        mission_id: env_uuid("SANDBOXED_SH_MISSION_ID"),
+        workspace_id: env_uuid(tools::policy::WORKSPACE_ID_ENV),
+    }
+}
+
+/// Ask the user, through the server's control session, whether `tool` may
+/// run with `args`.
+async fn request_approval(
+    tool: &str,
+    args: &Value,
+    mission_id: Option<Uuid>,
+) -> anyhow::Result<bool> {
+    let api_base = std::env::var("SANDBOXED_SH_API_URL")
+        .unwrap_or_else(|_| "http://127.0.0.1:3000".to_string());
+    let auth_token = std::env::var("SANDBOXED_SH_API_TOKEN").ok();
+
+    // No client timeout: the request stays open until the user answers or
+    // the server's frontend tool timeout answers for them.
+    let mut request = reqwest::Client::new()
+        .post(format!(
+            "{}/api/control/tool_approval",
+            api_base.trim_end_matches('/')
+        ))
+        .json(&json!({ "tool": tool, "args": args, "mission_id": mission_id }));
+    if let Some(token) = auth_token {
+        request = request.header("Authorization", format!("Bearer {}", token));
+    }
+
+    let response = request.send().await?;
+    let status = response.status();
+    if !status.is_success() {
+        let error_text = response.text().await.unwrap_or_default();
+        return Err(anyhow::anyhow!(
+            "Approval request failed: {} - {}",
+            status,
+            error_text
+        ));
+    }
+    let body: Value = response.json().await?;
+    Ok(body["allowed"].as_bool().unwrap_or(false))
+}
+
+/// Check a call against the tool policy, asking the user when it says `ask`.
+async fn check_policy(policy: &ToolPolicy, name: &str, args: &Value) -> anyhow::Result<()> {
+    let scope = policy_scope();
+    match policy.evaluate(name, &scope) {
+        PolicyAction::Allow => Ok(()),
+        PolicyAction::Deny => Err(anyhow::anyhow!(
+            "Tool '{}' is denied by the tool policy",
+            name
+        )),
+        PolicyAction::Ask => {
+            if request_approval(name, args, scope.mission_id).await? {
+                Ok(())
+            } else {
+                Err(anyhow::anyhow!(
+                    "The user did not approve running '{}'",
+                    name
+                ))
+            }
+        }
+    }
+}
+
+/// Report a finished call to the server's audit log (`GET /api/audit/tools`).
+async fn report_audit(entry: &ToolAuditEntry) {
+    let api_base = std::env::var("SANDBOXED_SH_API_URL")
+        .unwrap_or_else(|_| "http://127.0.0.1:3000".to_string());
+    let auth_token = std::env::var("SANDBOXED_SH_API_TOKEN").ok();
+
+    let Ok(client) = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(5))
+        .build()
+    else {
+        return;
+    };
+    let mut request = client
+        .post(format!(
+            "{}/api/audit/tools",
+            api_base.trim_end_matches('/')
+        ))
+        .json(entry);
+    if let Some(token) = auth_token {
+        request = request.header("Authorization", format!("Bearer {}", token));
+    }
+    if let Err(e) = request.send().await.and_then(|r| r.error_for_status()) {
+        debug_log(
+            "audit",
+            &json!({ "tool": entry.tool, "error": e.to_string() }),
+        );
+    }
+}
+
+/// Run one call: check the policy, execute the tool, record it in the audit
+/// log and shrink an oversized result.
+async fn run_tool(
+    tool: &dyn Tool,
+    policy: &ToolPolicy,
+    limits: &ToolResultLimits,
+    name: &str,
+    args: Value,
+    working_dir: &Path,
+) -> anyhow::Result<String> {
+    check_policy(policy, name, &args).await?;
+
+    let audit_args = args.clone();
+    let started = std::time::Instant::now();
+    let result = tool.execute(args, working_dir).await;
+    let scope = policy_scope();
+    report_audit(&ToolAuditEntry::new(
+        name,
+        &audit_args,
+        working_dir,
+        started.elapsed().as_millis() as u64,
+        &result,
+        scope.mission_id,
+        scope.workspace_id,
+    ))
+    .await;
+    Ok(limits.apply(name, result?).await)
+}
+
+async fn execute_tool(
     tools: &HashMap<String, Arc<dyn Tool>>,
+    policy: &ToolPolicy,
+    limits: &ToolResultLimits,
     name: &str,
-    args: &Value,
+    args: Value,
     working_dir: &Path,
 ) -> ToolResult {
     let Some(tool) = tools.get(name) else {
@@ -629,8 +874,7 @@ fn execute_tool(
         };
     };
 
-    let result = runtime.block_on(tool.execute(args.clone(), working_dir));
-    match result {
+    match run_tool(tool.as_ref(), policy, limits, name, args, working_dir).await {
         Ok(text) => ToolResult {
             content: vec![ToolContent::Text { text }],
             is_error: false,
@@ -644,9 +888,34 @@ fn execute_tool(
     }
 }
 
+/// Resolve the tool name, arguments and working directory of a `tools/call`.
+fn tool_call_params(
+    request: &JsonRpcRequest,
+    working_dir: &Arc<RwLock<PathBuf>>,
+) -> (String, Value, PathBuf) {
+    debug_log("tools/call", &request.params);
+    apply_runtime_workspace(working_dir);
+    let name = request
+        .params
+        .get("name")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+    let args = request
+        .params
+        .get("arguments")
+        .cloned()
+        .unwrap_or(json!({}));
+    let cwd = working_dir
+        .read()
+        .map(|guard| guard.clone())
+        .unwrap_or_else(|_| PathBuf::from("."));
+    (name, args, cwd)
+}
+
+/// Handle every request except `tools/call`, which `main` runs concurrently.
 fn handle_request(
     request: &JsonRpcRequest,
-    runtime: &tokio::runtime::Runtime,
     tools: &HashMap<String, Arc<dyn Tool>>,
     working_dir: &Arc<RwLock<PathBuf>>,
 ) -> Option<JsonRpcResponse> {
@@ -684,26 +953,6 @@ fn handle_request(
                 json!({ "tools": defs }),
             ))
         }
-        "tools/call" => {
-            debug_log("tools/call", &request.params);
-            apply_runtime_workspace(working_dir);
-            let name = request
-                .params
-                .get("name")
-                .and_then(|v| v.as_str())
-                .unwrap_or("");
-            let args = request
-                .params
-                .get("arguments")
-                .cloned()
-                .unwrap_or(json!({}));
-            let cwd = working_dir
-                .read()
-                .map(|guard| guard.clone())
-                .unwrap_or_else(|_| PathBuf::from("."));
-            let result = execute_tool(runtime, tools, name, &args, &cwd);
-            Some(JsonRpcResponse::success(request.id.clone(), json!(result)))
-        }
         _ => Some(JsonRpcResponse::error(
             request.id.clone(),
             -32601,
@@ -720,11 +969,14 @@ fn main() {
         .build()
         .expect("Failed to start tokio runtime");
 
-    let tools = tool_set();
+    let tools = Arc::new(tool_set());
+    let policy = Arc::new(tool_policy());
+    let limits = Arc::new(result_limits());
     let workspace = Arc::new(RwLock::new(hydrate_workspace_env(None)));
+    let tool_slots = Arc::new(Semaphore::new(MAX_CONCURRENT_TOOL_CALLS));
 
     let stdin = std::io::stdin();
-    let mut stdout = std::io::stdout();
+    let stdout = Arc::new(Mutex::new(std::io::stdout()));
     let reader = BufReader::new(stdin.lock());
 
     for line in reader.lines() {
@@ -741,17 +993,119 @@ fn main() {
             Ok(req) => req,
             Err(e) => {
                 let response = JsonRpcResponse::error(Value::Null, -32700, e.to_string());
-                let _ = writeln!(stdout, "{}", serde_json::to_string(&response).unwrap());
-                let _ = stdout.flush();
+                write_response(&stdout, &response);
                 continue;
             }
         };
 
-        if let Some(response) = handle_request(&request, &runtime, &tools, &workspace) {
-            if let Ok(resp) = serde_json::to_string(&response) {
-                let _ = writeln!(stdout, "{}", resp);
-                let _ = stdout.flush();
-            }
+        if request.method == "tools/call" {
+            let (name, args, cwd) = tool_call_params(&request, &workspace);
+            let tools = Arc::clone(&tools);
+            let policy = Arc::clone(&policy);
+            let limits = Arc::clone(&limits);
+            let tool_slots = Arc::clone(&tool_slots);
+            let stdout = Arc::clone(&stdout);
+            runtime.spawn(async move {
+                let _permit = tool_slots.acquire_owned().await;
+                let result = execute_tool(&tools, &policy, &limits, &name, args, &cwd).await;
+                write_response(
+                    &stdout,
+                    &JsonRpcResponse::success(request.id, json!(result)),
+                );
+            });
+            continue;
         }
+
+        if let Some(response) = handle_request(&request, &tools, &workspace) {
+            write_response(&stdout, &response);
+        }
+    }
+
+    // Let in-flight tool calls finish before exiting.
+    let _ = runtime.block_on(tool_slots.acquire_many(MAX_CONCURRENT_TOOL_CALLS as u32));
+}
+
+/// Write one response line; the lock keeps concurrent responses from interleaving.
+fn write_response(stdout: &Mutex<std::io::Stdout>, response: &JsonRpcResponse) {
+    let Ok(resp) = serde_json::to_string(response) else {
+        return;
+    };
+    if let Ok(mut out) = stdout.lock() {
+        let _ = writeln!(out, "{}", resp);
+        let _ = out.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sandboxed_sh::tools::PolicyRule;
+
+    #[tokio::test]
+    async fn test_denied_tool_returns_error() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "hello").unwrap();
+        let tools = tool_set();
+        let policy = ToolPolicy {
+            default_action: PolicyAction::Allow,
+            rules: vec![PolicyRule {
+                tool: "read_file".to_string(),
+                action: PolicyAction::Deny,
+                mission_id: None,
+                workspace_id: None,
+            }],
+        };
+
+        let result = execute_tool(
+            &tools,
+            &policy,
+            &ToolResultLimits::default(),
+            "read_file",
+            json!({ "path": "notes.txt" }),
+            dir.path(),
+        )
+        .await;
+        assert!(result.is_error);
+        let ToolContent::Text { text } = &result.content[0];
+        assert!(text.contains("denied by the tool policy"), "{}", text);
+        assert!(!text.contains("hello"));
+
+        let allowed = execute_tool(
+            &tools,
+            &ToolPolicy::default(),
+            &ToolResultLimits::default(),
+            "read_file",
+            json!({ "path": "notes.txt" }),
+            dir.path(),
+        )
+        .await;
+        assert!(!allowed.is_error);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_result_is_truncated() {
+        let dir = tempfile::tempdir().unwrap();
+        let contents: String = (0..200).map(|i| format!("line {}\n", i)).collect();
+        std::fs::write(dir.path().join("big.txt"), contents).unwrap();
+        let limits = ToolResultLimits {
+            max_bytes: 300,
+            ..Default::default()
+        };
+
+        let result = execute_tool(
+            &tool_set(),
+            &ToolPolicy::default(),
+            &limits,
+            "read_file",
+            json!({ "path": "big.txt" }),
+            dir.path(),
+        )
+        .await;
+        assert!(!result.is_error);
+        let ToolContent::Text { text } = &result.content[0];
+        assert!(text.contains("line 0"));
+        assert!(!text.contains("line 100\n"));
+        assert!(text.contains("bytes omitted"));
+        assert!(text.contains("[Tool output truncated: showing the first 200 and last"));
     }
 }
@@ -17,13 +17,38 @@
 //! - `LIBRARY_REMOTE` - Optional. Initial library remote URL (can be changed via Settings in the dashboard).
 //!   This environment variable is used as the initial default when no settings file exists.
 //!   If not set, defaults to: https://github.com/Th0rgal/sandboxed-library-template.git
+//! - `LIBRARY_WEBHOOK_SECRET` - Optional. Enables `POST /api/library/webhook`, which syncs the library when
+//!   its remote is pushed to. Verified as GitHub's `X-Hub-Signature-256` HMAC or GitLab's `X-Gitlab-Token`.
+//! - `LIBRARY_SYNC_INTERVAL_SECS` - Optional. Seconds between background library syncs, with up to 10% random
+//!   jitter (default: 900; `0` disables).
+//! - `LIBRARY_AUTO_COMMIT_SECS` - Optional. Commits and pushes library edits once the working tree has
+//!   been unchanged for this many seconds (default: `0`, disabled).
+//! - `LIBRARY_ENVIRONMENT` - Optional. Environment name (e.g. `staging`, `prod`) whose `envs` overlays apply to
+//!   library MCP server definitions.
+//! - `LIBRARY_READ_ONLY` - Optional. When true, the library can't be edited, committed or pushed from this
+//!   install and the library write routes are hidden (default: false).
 //! - `DEFAULT_BACKEND` - Optional. Default backend to use (claudecode, opencode, or amp).
 //!   If not set, defaults to the first available backend with priority: claudecode → opencode → amp.
+//! - `SANDBOXED_SH_NOTIFY_CHANNELS` - Optional. JSON array of notification channels for the `notify` tool
+//!   (generic webhook, Slack incoming webhook, or SMTP email).
+//! - `SANDBOXED_SH_SSH_HOSTS` - Optional. JSON array of named host profiles for the `ssh_exec` tool
+//!   (host, user, key from the secrets vault, and a per-host command allowlist).
+//! - `SANDBOXED_SH_DOCKER_MOUNT_ALLOWLIST` - Optional. Comma-separated host paths, besides the workspace,
+//!   that the `docker_*` tools may bind-mount or build from. `SANDBOXED_SH_DOCKER_MAX_MEMORY_MB`,
+//!   `SANDBOXED_SH_DOCKER_MAX_CPUS`, `SANDBOXED_SH_DOCKER_PIDS_LIMIT` and `SANDBOXED_SH_DOCKER_TIMEOUT_SECS`
+//!   cap container resources (defaults: 2048 MB, 2 CPUs, 512 processes, 1800 s).
+//! - `SANDBOXED_SH_SEARCH_PROVIDERS` - Optional. Comma-separated `web_search` providers in the order they
+//!   are tried (`brave`, `searxng`, `google`; default: all three). Brave and Google read their API keys from
+//!   the `search/brave_api_key` and `search/google_api_key` secrets (or `BRAVE_SEARCH_API_KEY` and
+//!   `GOOGLE_CSE_API_KEY`). `SANDBOXED_SH_SEARXNG_URL` points at a self-hosted SearXNG instance and
+//!   `SANDBOXED_SH_GOOGLE_CSE_ID` selects the Google Programmable Search engine.
+//! - `METRICS_TOKEN` - Optional. Bearer token required to scrape `GET /metrics`. Unset leaves it open.
 //!
 //! Note: The agent has **full system access**. It can read/write any file, execute any command,
 //! and search anywhere on the machine. The `WORKING_DIR` is just the default for relative paths.
 
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::path::PathBuf;
 use thiserror::Error;
 
@@ -49,6 +74,12 @@ pub struct ContextConfig {
     pub max_message_chars: usize,
     /// Maximum total characters for conversation context
     pub max_history_total_chars: usize,
+    /// Fold history that doesn't fit into an LLM-written summary instead of
+    /// dropping it
+    pub summarize_history: bool,
+    /// Model writing history summaries (the default provider's default model
+    /// if unset)
+    pub history_summary_model: Option<String>,
 
     // === Memory Retrieval ===
     /// Number of relevant past task chunks to retrieve
@@ -84,6 +115,8 @@ impl Default for ContextConfig {
             max_history_messages: 10,
             max_message_chars: 5000,
             max_history_total_chars: 30000,
+            summarize_history: true,
+            history_summary_model: None,
 
             // Memory retrieval
             memory_chunk_limit: 3,
@@ -125,6 +158,16 @@ impl ContextConfig {
                 config.max_history_total_chars = n;
             }
         }
+        if let Ok(v) = std::env::var("CONTEXT_SUMMARIZE_HISTORY") {
+            if let Ok(b) = parse_bool(&v) {
+                config.summarize_history = b;
+            }
+        }
+        if let Ok(v) = std::env::var("CONTEXT_SUMMARY_MODEL") {
+            if !v.trim().is_empty() {
+                config.history_summary_model = Some(v.trim().to_string());
+            }
+        }
         if let Ok(v) = std::env::var("CONTEXT_MEMORY_CHUNK_LIMIT") {
             if let Ok(n) = v.parse() {
                 config.memory_chunk_limit = n;
@@ -181,7 +224,542 @@ impl ContextConfig {
     }
 }
 
+/// Retention of finished missions, in days since their last update
+/// (0 = keep forever).
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct RetentionConfig {
+    /// Write the transcript to the archive directory
+    pub archive_after_days: u64,
+    /// Delete the mission's directory under its workspace
+    pub workspace_after_days: u64,
+    /// Delete the mission's raw event log
+    pub events_after_days: u64,
+}
+
+impl RetentionConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.archive_after_days > 0 || self.workspace_after_days > 0 || self.events_after_days > 0
+    }
+}
+
+/// Limits applied to `run_command`.
+///
+/// Per-call arguments can lower these but never exceed the maximums.
+#[derive(Debug, Clone)]
+pub struct CommandConfig {
+    /// Timeout used when a call doesn't specify one
+    pub default_timeout_secs: f64,
+    /// Upper bound for per-call timeouts
+    pub max_timeout_secs: f64,
+    /// Bytes of stdout kept per command (the tail is retained)
+    pub max_stdout_bytes: usize,
+    /// Bytes of stderr kept per command (the tail is retained)
+    pub max_stderr_bytes: usize,
+    /// Names accepted in a call's `env` map. Entries ending in `*` match by
+    /// prefix; `*` alone allows everything.
+    pub env_allowlist: Vec<String>,
+}
+
+impl Default for CommandConfig {
+    fn default() -> Self {
+        Self {
+            default_timeout_secs: 300.0,
+            max_timeout_secs: 3600.0,
+            max_stdout_bytes: 1024 * 1024,
+            max_stderr_bytes: 1024 * 1024,
+            env_allowlist: vec!["*".to_string()],
+        }
+    }
+}
+
+impl CommandConfig {
+    /// Load from environment variables, falling back to defaults.
+    ///
+    /// Read by the tool servers on every call, so it stays in sync with the
+    /// environment they were spawned with.
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Ok(v) = std::env::var("SANDBOXED_SH_COMMAND_TIMEOUT_SECS") {
+            if let Ok(n) = v.trim().parse::<f64>() {
+                if n > 0.0 {
+                    config.default_timeout_secs = n;
+                }
+            }
+        }
+        if let Ok(v) = std::env::var("SANDBOXED_SH_COMMAND_MAX_TIMEOUT_SECS") {
+            if let Ok(n) = v.trim().parse::<f64>() {
+                if n > 0.0 {
+                    config.max_timeout_secs = n;
+                }
+            }
+        }
+        if let Ok(v) = std::env::var("SANDBOXED_SH_COMMAND_MAX_STDOUT_BYTES") {
+            if let Ok(n) = v.trim().parse::<usize>() {
+                config.max_stdout_bytes = n.max(1);
+            }
+        }
+        if let Ok(v) = std::env::var("SANDBOXED_SH_COMMAND_MAX_STDERR_BYTES") {
+            if let Ok(n) = v.trim().parse::<usize>() {
+                config.max_stderr_bytes = n.max(1);
+            }
+        }
+        if let Ok(v) = std::env::var("SANDBOXED_SH_COMMAND_ENV_ALLOWLIST") {
+            config.env_allowlist = v
+                .split(',')
+                .map(|entry| entry.trim().to_string())
+                .filter(|entry| !entry.is_empty())
+                .collect();
+        }
+        config.default_timeout_secs = config.default_timeout_secs.min(config.max_timeout_secs);
+
+        config
+    }
+
+    /// Whether a call may set the environment variable `key`.
+    pub fn env_allowed(&self, key: &str) -> bool {
+        self.env_allowlist
+            .iter()
+            .any(|entry| match entry.strip_suffix('*') {
+                Some(prefix) => key.starts_with(prefix),
+                None => entry == key,
+            })
+    }
+}
+
+/// Limits applied to the `docker_*` tools.
+///
+/// Per-call resource limits can lower these but never exceed them.
+#[derive(Debug, Clone)]
+pub struct DockerConfig {
+    /// Host paths (besides the workspace) that may be bind-mounted or used
+    /// as a build context
+    pub mount_allowlist: Vec<PathBuf>,
+    /// Memory cap per container, in bytes
+    pub max_memory_bytes: u64,
+    /// CPU cap per container
+    pub max_cpus: f64,
+    /// Process cap per container
+    pub pids_limit: u32,
+    /// Timeout for `docker_build` and foreground `docker_run`
+    pub timeout_secs: u64,
+}
+
+impl Default for DockerConfig {
+    fn default() -> Self {
+        Self {
+            mount_allowlist: Vec::new(),
+            max_memory_bytes: 2 * 1024 * 1024 * 1024,
+            max_cpus: 2.0,
+            pids_limit: 512,
+            timeout_secs: 1800,
+        }
+    }
+}
+
+impl DockerConfig {
+    /// Load from environment variables, falling back to defaults.
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Ok(v) = std::env::var("SANDBOXED_SH_DOCKER_MOUNT_ALLOWLIST") {
+            config.mount_allowlist = v
+                .split(',')
+                .map(str::trim)
+                .filter(|entry| !entry.is_empty())
+                .map(PathBuf::from)
+                .collect();
+        }
+        if let Ok(v) = std::env::var("SANDBOXED_SH_DOCKER_MAX_MEMORY_MB") {
+            if let Ok(n) = v.trim().parse::<u64>() {
+                config.max_memory_bytes = n.max(6) * 1024 * 1024;
+            }
+        }
+        if let Ok(v) = std::env::var("SANDBOXED_SH_DOCKER_MAX_CPUS") {
+            if let Ok(n) = v.trim().parse::<f64>() {
+                if n > 0.0 {
+                    config.max_cpus = n;
+                }
+            }
+        }
+        if let Ok(v) = std::env::var("SANDBOXED_SH_DOCKER_PIDS_LIMIT") {
+            if let Ok(n) = v.trim().parse::<u32>() {
+                config.pids_limit = n.max(1);
+            }
+        }
+        if let Ok(v) = std::env::var("SANDBOXED_SH_DOCKER_TIMEOUT_SECS") {
+            if let Ok(n) = v.trim().parse::<u64>() {
+                config.timeout_secs = n.max(1);
+            }
+        }
+
+        config
+    }
+}
+
+/// A `web_search` backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchProviderKind {
+    Brave,
+    Searxng,
+    GoogleCse,
+}
+
+impl SearchProviderKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Brave => "brave",
+            Self::Searxng => "searxng",
+            Self::GoogleCse => "google",
+        }
+    }
+}
+
+impl std::str::FromStr for SearchProviderKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "brave" => Ok(Self::Brave),
+            "searxng" | "searx" => Ok(Self::Searxng),
+            "google" | "google_cse" | "cse" => Ok(Self::GoogleCse),
+            other => Err(format!("unknown search provider '{}'", other)),
+        }
+    }
+}
+
+/// Providers used by the `web_search` tool.
+///
+/// Providers without credentials or a URL are skipped, so the default order
+/// works with whichever ones are set up.
+#[derive(Debug, Clone)]
+pub struct SearchConfig {
+    /// Providers in the order they are tried
+    pub providers: Vec<SearchProviderKind>,
+    /// Base URL of a SearXNG instance (JSON output must be enabled)
+    pub searxng_url: Option<String>,
+    /// Google Programmable Search engine ID (`cx`)
+    pub google_cse_id: Option<String>,
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        Self {
+            providers: vec![
+                SearchProviderKind::Brave,
+                SearchProviderKind::Searxng,
+                SearchProviderKind::GoogleCse,
+            ],
+            searxng_url: None,
+            google_cse_id: None,
+        }
+    }
+}
+
+impl SearchConfig {
+    /// Load from environment variables, falling back to defaults.
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let mut config = Self::default();
+
+        if let Ok(v) = std::env::var("SANDBOXED_SH_SEARCH_PROVIDERS") {
+            if !v.trim().is_empty() {
+                config.providers = v
+                    .split(',')
+                    .filter(|entry| !entry.trim().is_empty())
+                    .map(|entry| entry.parse())
+                    .collect::<Result<_, _>>()
+                    .map_err(|e| {
+                        ConfigError::InvalidValue("SANDBOXED_SH_SEARCH_PROVIDERS".to_string(), e)
+                    })?;
+            }
+        }
+        config.searxng_url = std::env::var("SANDBOXED_SH_SEARXNG_URL")
+            .ok()
+            .map(|v| v.trim().trim_end_matches('/').to_string())
+            .filter(|v| !v.is_empty());
+        config.google_cse_id = std::env::var("SANDBOXED_SH_GOOGLE_CSE_ID")
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+
+        Ok(config)
+    }
+}
+
+/// A destination for agent notifications.
+///
+/// Example: `{"name": "ops", "type": "slack", "webhook_url": "https://hooks.slack.com/..."}`
+#[derive(Debug, Clone, Deserialize)]
+pub struct NotifyChannel {
+    /// Name the agent refers to the channel by
+    pub name: String,
+    #[serde(flatten)]
+    pub kind: NotifyChannelKind,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NotifyChannelKind {
+    /// POST a JSON payload to an arbitrary URL
+    Webhook {
+        url: String,
+        #[serde(default)]
+        headers: HashMap<String, String>,
+    },
+    /// Slack incoming webhook
+    Slack { webhook_url: String },
+    /// Email via SMTP
+    Email {
+        smtp_host: String,
+        /// Defaults to 465 for `tls`, 587 for `starttls` and 25 for `none`
+        #[serde(default)]
+        smtp_port: Option<u16>,
+        #[serde(default)]
+        security: SmtpSecurity,
+        #[serde(default)]
+        username: Option<String>,
+        #[serde(default)]
+        password: Option<String>,
+        from: String,
+        to: Vec<String>,
+    },
+}
+
+/// How the SMTP connection is secured.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpSecurity {
+    /// Upgrade a plain connection with STARTTLS
+    #[default]
+    Starttls,
+    /// TLS from the start (SMTPS)
+    Tls,
+    /// No encryption (local relays only)
+    None,
+}
+
+/// Notification channels available to the `notify` tool.
+#[derive(Debug, Clone, Default)]
+pub struct NotifyConfig {
+    pub channels: Vec<NotifyChannel>,
+}
+
+impl NotifyConfig {
+    /// Load channels from `SANDBOXED_SH_NOTIFY_CHANNELS`.
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let Ok(raw) = std::env::var("SANDBOXED_SH_NOTIFY_CHANNELS") else {
+            return Ok(Self::default());
+        };
+        if raw.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        let channels: Vec<NotifyChannel> = serde_json::from_str(&raw).map_err(|e| {
+            ConfigError::InvalidValue("SANDBOXED_SH_NOTIFY_CHANNELS".to_string(), e.to_string())
+        })?;
+        Ok(Self { channels })
+    }
+}
+
+/// A remote machine the `ssh_exec` tool may run commands on.
+///
+/// Example: `{"name": "web1", "host": "10.0.0.5", "user": "deploy", "key_secret": "ssh/web1", "allowed_commands": ["systemctl status *", "journalctl *"]}`
+#[derive(Debug, Clone, Deserialize)]
+pub struct SshHost {
+    /// Name the agent refers to the host by
+    pub name: String,
+    pub host: String,
+    #[serde(default = "default_ssh_port")]
+    pub port: u16,
+    pub user: String,
+    /// Private key as `registry/key` in the secrets vault (default: the
+    /// server's own SSH keys and agent)
+    #[serde(default)]
+    pub key_secret: Option<String>,
+    /// Expected host key (`ssh-ed25519 AAAA...`). Without it, unknown hosts
+    /// are trusted on first use.
+    #[serde(default)]
+    pub host_key: Option<String>,
+    /// Command globs (`*` wildcard) the agent may run; empty allows any
+    #[serde(default)]
+    pub allowed_commands: Vec<String>,
+    /// Bytes of stdout/stderr returned per stream
+    #[serde(default)]
+    pub max_output_bytes: Option<usize>,
+}
+
+fn default_ssh_port() -> u16 {
+    22
+}
+
+/// Hosts available to the `ssh_exec` tool.
+#[derive(Debug, Clone, Default)]
+pub struct SshConfig {
+    pub hosts: Vec<SshHost>,
+}
+
+impl SshConfig {
+    /// Load hosts from `SANDBOXED_SH_SSH_HOSTS`.
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let Ok(raw) = std::env::var("SANDBOXED_SH_SSH_HOSTS") else {
+            return Ok(Self::default());
+        };
+        if raw.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        let hosts: Vec<SshHost> = serde_json::from_str(&raw).map_err(|e| {
+            ConfigError::InvalidValue("SANDBOXED_SH_SSH_HOSTS".to_string(), e.to_string())
+        })?;
+        Ok(Self { hosts })
+    }
+}
+
 /// Agent configuration.
+/// What the control loop does when a running mission stops making progress.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StallAction {
+    /// Queue a message asking the agent to continue or report what blocks it
+    Nudge,
+    /// Cancel the in-flight turn and start a fresh one that continues the task
+    Restart,
+    /// Cancel the mission, mark it failed and alert the notification channels
+    Cancel,
+}
+
+impl StallAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Nudge => "nudge",
+            Self::Restart => "restart",
+            Self::Cancel => "cancel",
+        }
+    }
+}
+
+impl std::str::FromStr for StallAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "nudge" => Ok(Self::Nudge),
+            "restart" => Ok(Self::Restart),
+            "cancel" => Ok(Self::Cancel),
+            other => Err(format!("unknown stall action '{}'", other)),
+        }
+    }
+}
+
+/// What a frontend tool call gets when nobody answers it in time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ToolTimeoutAction {
+    /// Answer that no human is available and the agent should proceed
+    /// conservatively
+    #[default]
+    Proceed,
+    /// Pick the call's default option (its first one unless another is
+    /// marked default), falling back to `Proceed`
+    DefaultOption,
+    /// Leave the call unanswered, failing the turn
+    Fail,
+}
+
+impl std::str::FromStr for ToolTimeoutAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "proceed" => Ok(Self::Proceed),
+            "default" | "default_option" => Ok(Self::DefaultOption),
+            "fail" => Ok(Self::Fail),
+            other => Err(format!("unknown tool timeout action '{}'", other)),
+        }
+    }
+}
+
+/// Time limit of a frontend tool call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ToolTimeout {
+    pub after: std::time::Duration,
+    pub action: ToolTimeoutAction,
+}
+
+/// Time limits for frontend tool calls (`ui_optionList`, `question`,
+/// `ask_user`, ...), so unattended missions don't wait forever.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FrontendToolTimeouts {
+    /// Limit of tools without one of their own (`None` = wait forever)
+    pub default: Option<ToolTimeout>,
+    /// Limits by tool name; `None` makes a tool wait forever
+    pub per_tool: HashMap<String, Option<ToolTimeout>>,
+}
+
+impl FrontendToolTimeouts {
+    /// Load limits from `FRONTEND_TOOL_TIMEOUT_SECS` (0 or unset = wait
+    /// forever), `FRONTEND_TOOL_TIMEOUT_ACTION` (`proceed`, `default` or
+    /// `fail`) and `FRONTEND_TOOL_TIMEOUTS`, a comma-separated list of
+    /// `tool=secs[:action]` overrides.
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let action = match std::env::var("FRONTEND_TOOL_TIMEOUT_ACTION") {
+            Ok(raw) if !raw.trim().is_empty() => raw.parse().map_err(|e| {
+                ConfigError::InvalidValue("FRONTEND_TOOL_TIMEOUT_ACTION".to_string(), e)
+            })?,
+            _ => ToolTimeoutAction::default(),
+        };
+        let secs: u64 = std::env::var("FRONTEND_TOOL_TIMEOUT_SECS")
+            .unwrap_or_else(|_| "0".to_string())
+            .trim()
+            .parse()
+            .map_err(|e| {
+                ConfigError::InvalidValue(
+                    "FRONTEND_TOOL_TIMEOUT_SECS".to_string(),
+                    format!("{}", e),
+                )
+            })?;
+        let overrides = std::env::var("FRONTEND_TOOL_TIMEOUTS").unwrap_or_default();
+        Self::parse(secs, action, &overrides)
+            .map_err(|e| ConfigError::InvalidValue("FRONTEND_TOOL_TIMEOUTS".to_string(), e))
+    }
+
+    fn parse(secs: u64, action: ToolTimeoutAction, overrides: &str) -> Result<Self, String> {
+        let limit = |secs: u64, action| {
+            (secs > 0).then(|| ToolTimeout {
+                after: std::time::Duration::from_secs(secs),
+                action,
+            })
+        };
+        let mut per_tool = HashMap::new();
+        for entry in overrides.split(',').filter(|s| !s.trim().is_empty()) {
+            let (tool, value) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("expected tool=secs, got '{}'", entry.trim()))?;
+            let (tool_secs, tool_action) = match value.split_once(':') {
+                Some((secs, action)) => (secs, action.parse()?),
+                None => (value, action),
+            };
+            let tool_secs: u64 = tool_secs
+                .trim()
+                .parse()
+                .map_err(|e| format!("invalid timeout for '{}': {}", tool.trim(), e))?;
+            per_tool.insert(tool.trim().to_string(), limit(tool_secs, tool_action));
+        }
+        Ok(Self {
+            default: limit(secs, action),
+            per_tool,
+        })
+    }
+
+    /// Limit of a call to `tool` (optionally MCP-qualified, e.g.
+    /// `mcp__workspace__ask_user`).
+    pub fn timeout_for(&self, tool: &str) -> Option<ToolTimeout> {
+        let short = tool.rsplit("__").next().unwrap_or(tool);
+        self.per_tool
+            .get(tool)
+            .or_else(|| self.per_tool.get(short))
+            .copied()
+            .unwrap_or(self.default)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Config {
     /// Optional model override (provider/model format). If None, OpenCode uses its own default.
@@ -203,6 +781,30 @@ pub struct Config {
     /// Hours of inactivity after which an active mission is auto-closed (0 = disabled)
     pub stale_mission_hours: u64,
 
+    /// Default wall-clock limit for a mission run, in minutes (0 = unlimited)
+    pub mission_timeout_minutes: u64,
+
+    /// Escalation steps for stalled missions, taken one after the other
+    /// (empty = stalls are only reported)
+    pub mission_stall_actions: Vec<StallAction>,
+
+    /// Seconds between checks of running missions' backend processes; a
+    /// busy process counts as activity for stall detection (0 = disabled)
+    pub mission_heartbeat_secs: u64,
+
+    /// How long frontend tool calls wait for an answer
+    pub frontend_tool_timeouts: FrontendToolTimeouts,
+
+    /// Seconds running turns get to finish on shutdown before they are
+    /// checkpointed and cancelled
+    pub shutdown_grace_secs: u64,
+
+    /// Cleanup of finished missions
+    pub retention: RetentionConfig,
+
+    /// Bearer token required by `/metrics` (`None` leaves it open)
+    pub metrics_token: Option<String>,
+
     /// Maximum number of missions that can run in parallel (1 = sequential only)
     pub max_parallel_missions: usize,
 
@@ -215,6 +817,15 @@ pub struct Config {
     /// Context injection configuration
     pub context: ContextConfig,
 
+    /// Limits for `run_command`
+    pub command: CommandConfig,
+
+    /// Channels for the `notify` tool
+    pub notify: NotifyConfig,
+
+    /// Host profiles for the `ssh_exec` tool
+    pub ssh: SshConfig,
+
     /// DEPRECATED: OpenCode server base URL (no longer used for mission execution)
     pub opencode_base_url: String,
 
@@ -228,6 +839,22 @@ pub struct Config {
     /// Default: {working_dir}/.sandboxed-sh/library
     pub library_path: PathBuf,
 
+    /// Secret for the library push webhook (`None` disables the endpoint)
+    pub library_webhook_secret: Option<String>,
+
+    /// Seconds between background library syncs (0 disables)
+    pub library_sync_interval_secs: u64,
+
+    /// Seconds library edits must settle before they are auto-committed and
+    /// pushed (0 disables)
+    pub library_auto_commit_secs: u64,
+
+    /// Environment whose MCP server overlays apply (e.g. "staging", "prod")
+    pub library_environment: Option<String>,
+
+    /// Whether the library is read-only (changed only through its remote)
+    pub library_read_only: bool,
+
     /// Default backend to use (if specified in environment)
     pub default_backend: Option<String>,
 
@@ -361,6 +988,61 @@ impl Config {
                 ConfigError::InvalidValue("STALE_MISSION_HOURS".to_string(), format!("{}", e))
             })?;
 
+        // Wall-clock limit for a mission run (turns executed back to back,
+        // including queued follow-ups). Missions can override it.
+        // Default: 0 (unlimited).
+        let mission_timeout_minutes = std::env::var("MISSION_TIMEOUT_MINUTES")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse()
+            .map_err(|e| {
+                ConfigError::InvalidValue("MISSION_TIMEOUT_MINUTES".to_string(), format!("{}", e))
+            })?;
+
+        // Escalation for stalled missions, e.g. "nudge,restart,cancel".
+        // Default: none (stalls are only reported).
+        let mission_stall_actions = std::env::var("MISSION_STALL_ACTIONS")
+            .unwrap_or_default()
+            .split(',')
+            .filter(|s| !s.trim().is_empty())
+            .map(str::parse)
+            .collect::<Result<Vec<StallAction>, _>>()
+            .map_err(|e| ConfigError::InvalidValue("MISSION_STALL_ACTIONS".to_string(), e))?;
+
+        // Heartbeats from busy backend processes. Default: every 30 seconds.
+        let mission_heartbeat_secs = std::env::var("MISSION_HEARTBEAT_SECS")
+            .unwrap_or_else(|_| "30".to_string())
+            .trim()
+            .parse()
+            .map_err(|e| {
+                ConfigError::InvalidValue("MISSION_HEARTBEAT_SECS".to_string(), format!("{}", e))
+            })?;
+
+        // Unanswered ui_optionList/question/... calls. Default: wait forever.
+        let frontend_tool_timeouts = FrontendToolTimeouts::from_env()?;
+
+        // Time running turns get to finish on SIGTERM. Default: 30 seconds.
+        let shutdown_grace_secs = std::env::var("SHUTDOWN_GRACE_SECS")
+            .unwrap_or_else(|_| "30".to_string())
+            .trim()
+            .parse()
+            .map_err(|e| {
+                ConfigError::InvalidValue("SHUTDOWN_GRACE_SECS".to_string(), format!("{}", e))
+            })?;
+
+        // Retention of finished missions, in days. Default: 0 (keep forever).
+        let retention_days = |name: &str| -> Result<u64, ConfigError> {
+            std::env::var(name)
+                .unwrap_or_else(|_| "0".to_string())
+                .trim()
+                .parse()
+                .map_err(|e| ConfigError::InvalidValue(name.to_string(), format!("{}", e)))
+        };
+        let retention = RetentionConfig {
+            archive_after_days: retention_days("MISSION_RETENTION_ARCHIVE_DAYS")?,
+            workspace_after_days: retention_days("MISSION_RETENTION_WORKSPACE_DAYS")?,
+            events_after_days: retention_days("MISSION_RETENTION_EVENTS_DAYS")?,
+        };
+
         // Maximum parallel missions (default: 1 = sequential)
         let max_parallel_missions = std::env::var("MAX_PARALLEL_MISSIONS")
             .unwrap_or_else(|_| "1".to_string())
@@ -461,12 +1143,48 @@ impl Config {
         }
 
         let context = ContextConfig::from_env();
+        let command = CommandConfig::from_env();
+        let notify = NotifyConfig::from_env()?;
+        let ssh = SshConfig::from_env()?;
 
         // Library configuration
         // Note: library_remote is now managed via the settings module (persisted to disk)
         let library_path = std::env::var("LIBRARY_PATH")
             .map(PathBuf::from)
             .unwrap_or_else(|_| working_dir.join(".sandboxed-sh/library"));
+        let metrics_token = std::env::var("METRICS_TOKEN")
+            .ok()
+            .filter(|s| !s.trim().is_empty());
+        let library_webhook_secret = std::env::var("LIBRARY_WEBHOOK_SECRET")
+            .ok()
+            .filter(|s| !s.trim().is_empty());
+        let library_sync_interval_secs = std::env::var("LIBRARY_SYNC_INTERVAL_SECS")
+            .unwrap_or_else(|_| "900".to_string())
+            .parse()
+            .map_err(|e| {
+                ConfigError::InvalidValue(
+                    "LIBRARY_SYNC_INTERVAL_SECS".to_string(),
+                    format!("{}", e),
+                )
+            })?;
+        let library_auto_commit_secs = std::env::var("LIBRARY_AUTO_COMMIT_SECS")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse()
+            .map_err(|e| {
+                ConfigError::InvalidValue("LIBRARY_AUTO_COMMIT_SECS".to_string(), format!("{}", e))
+            })?;
+        let library_environment = std::env::var("LIBRARY_ENVIRONMENT")
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+        let library_read_only = std::env::var("LIBRARY_READ_ONLY")
+            .ok()
+            .map(|v| {
+                parse_bool(&v)
+                    .map_err(|e| ConfigError::InvalidValue("LIBRARY_READ_ONLY".to_string(), e))
+            })
+            .transpose()?
+            .unwrap_or(false);
 
         // Default backend configuration
         let default_backend = std::env::var("DEFAULT_BACKEND").ok().and_then(|v| {
@@ -499,14 +1217,29 @@ impl Config {
             port,
             max_iterations,
             stale_mission_hours,
+            mission_timeout_minutes,
+            mission_stall_actions,
+            mission_heartbeat_secs,
+            frontend_tool_timeouts,
+            shutdown_grace_secs,
+            retention,
+            metrics_token,
             max_parallel_missions,
             dev_mode,
             auth,
             context,
+            command,
+            notify,
+            ssh,
             opencode_base_url,
             opencode_agent,
             opencode_permissive,
             library_path,
+            library_webhook_secret,
+            library_sync_interval_secs,
+            library_auto_commit_secs,
+            library_environment,
+            library_read_only,
             default_backend,
             automations_enabled,
         })
@@ -522,14 +1255,29 @@ impl Config {
             port: 3000,
             max_iterations: 50,
             stale_mission_hours: 2,
+            mission_timeout_minutes: 0,
+            mission_stall_actions: Vec::new(),
+            mission_heartbeat_secs: 30,
+            frontend_tool_timeouts: FrontendToolTimeouts::default(),
+            shutdown_grace_secs: 30,
+            retention: RetentionConfig::default(),
+            metrics_token: None,
             max_parallel_missions: 1,
             dev_mode: true,
             auth: AuthConfig::default(),
             context: ContextConfig::default(),
+            command: CommandConfig::default(),
+            notify: NotifyConfig::default(),
+            ssh: SshConfig::default(),
             opencode_base_url: "http://127.0.0.1:4096".to_string(),
             opencode_agent: None,
             opencode_permissive: true,
             library_path,
+            library_webhook_secret: None,
+            library_sync_interval_secs: 0,
+            library_auto_commit_secs: 0,
+            library_environment: None,
+            library_read_only: false,
             default_backend: None,
             automations_enabled: true,
         }
@@ -42,9 +42,12 @@ pub mod config;
 pub mod cost;
 pub mod library;
 pub mod mcp;
+pub mod model_catalog;
 pub mod nspawn;
 pub mod opencode;
 pub mod opencode_config;
+pub mod process_registry;
+pub mod scheduler;
 pub mod secrets;
 pub mod settings;
 pub mod skills_registry;
@@ -0,0 +1,161 @@
+//! Rendering library agents (`agent/*.md`) for each backend.
+//!
+//! Library agents are markdown with YAML frontmatter (`description`, `model`,
+//! `tools`, optional `prompt`). The system prompt is the `prompt` key when
+//! set, otherwise the markdown body. OpenCode reads them almost as-is from
+//! `.opencode/agent/`; Claude Code subagents in `.claude/agents/` use a
+//! different frontmatter (tool list, model alias), so they are rewritten.
+
+use serde_yaml::{Mapping, Value};
+
+use super::types::{extract_description, extract_model, extract_tools, parse_frontmatter};
+
+/// OpenCode tool names and their Claude Code equivalents.
+const CLAUDE_TOOL_NAMES: &[(&str, &str)] = &[
+    ("read", "Read"),
+    ("write", "Write"),
+    ("edit", "Edit"),
+    ("bash", "Bash"),
+    ("glob", "Glob"),
+    ("grep", "Grep"),
+    ("webfetch", "WebFetch"),
+    ("websearch", "WebSearch"),
+    ("todowrite", "TodoWrite"),
+    ("task", "Task"),
+];
+
+/// The agent's system prompt: the `prompt` frontmatter key, or the body.
+pub fn agent_prompt(content: &str) -> String {
+    let (frontmatter, body) = parse_frontmatter(content);
+    frontmatter
+        .as_ref()
+        .and_then(|fm| fm.get("prompt"))
+        .and_then(|v| v.as_str())
+        .map(|prompt| prompt.trim().to_string())
+        .unwrap_or_else(|| body.trim().to_string())
+}
+
+/// Render an agent for `.opencode/agent/<name>.md`.
+///
+/// Agents without a `prompt` key are written unchanged. Otherwise the prompt
+/// moves into the body, and a list of tools becomes OpenCode's mapping.
+pub fn to_opencode_markdown(content: &str) -> String {
+    let (frontmatter, _) = parse_frontmatter(content);
+    let Some(Value::Mapping(mut mapping)) = frontmatter else {
+        return content.to_string();
+    };
+    if !mapping.contains_key("prompt") {
+        return content.to_string();
+    }
+
+    let prompt = agent_prompt(content);
+    mapping.remove("prompt");
+    if mapping
+        .get("tools")
+        .is_some_and(|tools| !tools.is_mapping())
+    {
+        let tools = extract_tools(&Some(Value::Mapping(mapping.clone())));
+        let mut sorted: Vec<_> = tools.into_iter().collect();
+        sorted.sort();
+        let tools: Mapping = sorted
+            .into_iter()
+            .map(|(tool, enabled)| (Value::String(tool), Value::Bool(enabled)))
+            .collect();
+        mapping.insert("tools".into(), Value::Mapping(tools));
+    }
+    render(&mapping, &prompt)
+}
+
+/// Render an agent as a Claude Code subagent for `.claude/agents/<name>.md`.
+///
+/// Enabled tools are listed with their Claude Code names (globs are dropped;
+/// no list means all tools). Anthropic models map to the `opus`/`sonnet`/
+/// `haiku` aliases; other models are left out so the subagent inherits the
+/// session's model.
+pub fn to_claudecode_markdown(name: &str, content: &str) -> String {
+    let (frontmatter, _) = parse_frontmatter(content);
+    let description =
+        extract_description(&frontmatter).unwrap_or_else(|| format!("Custom agent {}", name));
+
+    let mut tools: Vec<String> = extract_tools(&frontmatter)
+        .into_iter()
+        .filter(|(tool, enabled)| *enabled && !tool.contains('*'))
+        .map(|(tool, _)| claude_tool_name(&tool))
+        .collect();
+    tools.sort();
+    tools.dedup();
+
+    let mut mapping = Mapping::new();
+    mapping.insert("name".into(), name.into());
+    mapping.insert("description".into(), description.into());
+    if !tools.is_empty() {
+        mapping.insert("tools".into(), tools.join(", ").into());
+    }
+    if let Some(model) = extract_model(&frontmatter).and_then(|m| claude_model_alias(&m)) {
+        mapping.insert("model".into(), model.into());
+    }
+    render(&mapping, &agent_prompt(content))
+}
+
+fn claude_tool_name(tool: &str) -> String {
+    CLAUDE_TOOL_NAMES
+        .iter()
+        .find(|(opencode, _)| opencode.eq_ignore_ascii_case(tool))
+        .map(|(_, claude)| claude.to_string())
+        .unwrap_or_else(|| tool.to_string())
+}
+
+fn claude_model_alias(model: &str) -> Option<&'static str> {
+    let model = model.to_ascii_lowercase();
+    ["inherit", "opus", "sonnet", "haiku"]
+        .into_iter()
+        .find(|alias| model.contains(alias))
+}
+
+fn render(frontmatter: &Mapping, prompt: &str) -> String {
+    let yaml = serde_yaml::to_string(frontmatter).unwrap_or_default();
+    format!("---\n{}---\n\n{}\n", yaml, prompt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_claudecode_subagent() {
+        let content = "---\ndescription: Reviews code\nmodel: anthropic/claude-opus-4-1\ntools:\n  read: true\n  grep: true\n  bash: false\n  playwright_*: true\n---\n\nYou review code.\n";
+        let rendered = to_claudecode_markdown("reviewer", content);
+        let (frontmatter, body) = parse_frontmatter(&rendered);
+        let fm = frontmatter.unwrap();
+        assert_eq!(fm["name"].as_str(), Some("reviewer"));
+        assert_eq!(fm["description"].as_str(), Some("Reviews code"));
+        assert_eq!(fm["tools"].as_str(), Some("Grep, Read"));
+        assert_eq!(fm["model"].as_str(), Some("opus"));
+        assert_eq!(body.trim(), "You review code.");
+
+        let other = "---\nmodel: openai/gpt-5\nprompt: Be brief.\n---\n";
+        let rendered = to_claudecode_markdown("brief", other);
+        let (frontmatter, body) = parse_frontmatter(&rendered);
+        let fm = frontmatter.unwrap();
+        assert!(fm.get("model").is_none());
+        assert!(fm.get("tools").is_none());
+        assert_eq!(fm["description"].as_str(), Some("Custom agent brief"));
+        assert_eq!(body.trim(), "Be brief.");
+    }
+
+    #[test]
+    fn test_opencode_agent() {
+        let plain = "---\ndescription: Plain\n---\nBody\n";
+        assert_eq!(to_opencode_markdown(plain), plain);
+
+        let with_prompt =
+            "---\ndescription: Docs\ntools: [read, webfetch]\nprompt: Write docs.\n---\n";
+        let rendered = to_opencode_markdown(with_prompt);
+        let (frontmatter, body) = parse_frontmatter(&rendered);
+        let fm = frontmatter.unwrap();
+        assert!(fm.get("prompt").is_none());
+        assert_eq!(fm["tools"]["read"].as_bool(), Some(true));
+        assert_eq!(fm["tools"]["webfetch"].as_bool(), Some(true));
+        assert_eq!(body.trim(), "Write docs.");
+    }
+}
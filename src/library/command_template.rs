@@ -0,0 +1,169 @@
+//! Rendering commands with typed arguments.
+//!
+//! Commands declare `args` in frontmatter (see [`extract_params`]) and use
+//! them in the body as `{name}` or `{{name}}`. Rendering checks the given
+//! values against the declared types, fills in defaults and substitutes the
+//! placeholders. Braces that don't name a declared argument are left alone.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use super::types::{extract_params, parse_frontmatter, CommandParam, ParamType};
+use super::validate::{ValidationIssue, ValidationReport};
+
+/// Check `value` against `param_type` and return it as substitution text.
+/// Strings that parse as the expected type are accepted too.
+pub fn coerce_value(param_type: Option<ParamType>, value: &Value) -> Result<String, String> {
+    let text = match value {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        _ => return Err("must be a string, number or boolean".to_string()),
+    };
+    match param_type.unwrap_or(ParamType::String) {
+        ParamType::String => Ok(text),
+        ParamType::Integer => text
+            .trim()
+            .parse::<i64>()
+            .map(|n| n.to_string())
+            .map_err(|_| "must be an integer".to_string()),
+        ParamType::Number => text
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|n| n.is_finite())
+            .map(|_| text.trim().to_string())
+            .ok_or_else(|| "must be a number".to_string()),
+        ParamType::Boolean => match text.trim() {
+            "true" => Ok("true".to_string()),
+            "false" => Ok("false".to_string()),
+            _ => Err("must be true or false".to_string()),
+        },
+    }
+}
+
+/// Render a command's body with `values` for its declared arguments.
+///
+/// Fails with one issue per unknown argument, missing required argument or
+/// value of the wrong type.
+pub fn render_command(
+    content: &str,
+    values: &HashMap<String, Value>,
+) -> Result<String, ValidationReport> {
+    let (frontmatter, body) = parse_frontmatter(content);
+    let params = extract_params(&frontmatter);
+
+    let mut issues = Vec::new();
+    let mut unknown: Vec<&String> = values
+        .keys()
+        .filter(|name| !params.iter().any(|p| &p.name == *name))
+        .collect();
+    unknown.sort();
+    for name in unknown {
+        issues.push(issue(name, "unknown argument"));
+    }
+
+    let mut resolved = HashMap::new();
+    for param in &params {
+        match resolve(param, values.get(&param.name)) {
+            Ok(value) => {
+                resolved.insert(param.name.as_str(), value);
+            }
+            Err(message) => issues.push(issue(&param.name, message)),
+        }
+    }
+    if !issues.is_empty() {
+        return Err(ValidationReport {
+            valid: false,
+            issues,
+        });
+    }
+
+    let placeholder = regex::Regex::new(r"\{\{\s*([A-Za-z_][\w-]*)\s*\}\}|\{([A-Za-z_][\w-]*)\}")
+        .expect("Invalid regex");
+    let rendered = placeholder.replace_all(body, |caps: &regex::Captures| {
+        let name = caps
+            .get(1)
+            .or_else(|| caps.get(2))
+            .map_or("", |m| m.as_str());
+        resolved
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| caps[0].to_string())
+    });
+    Ok(rendered.into_owned())
+}
+
+fn resolve(param: &CommandParam, value: Option<&Value>) -> Result<String, String> {
+    match value.filter(|v| !v.is_null()) {
+        Some(value) => coerce_value(param.param_type, value),
+        None => match &param.default {
+            Some(default) => coerce_value(param.param_type, &Value::String(default.clone()))
+                .map_err(|e| format!("default {}", e)),
+            None if param.required => Err("is required".to_string()),
+            None => Ok(String::new()),
+        },
+    }
+}
+
+fn issue(field: &str, message: impl Into<String>) -> ValidationIssue {
+    ValidationIssue {
+        field: Some(field.to_string()),
+        message: message.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const DEPLOY: &str = "---\ndescription: Deploy\nargs:\n  - name: env\n    type: string\n  - name: replicas\n    type: integer\n    default: 2\n  - name: dry_run\n    type: boolean\n    default: false\n---\n\nDeploy to {env} with {{ replicas }} replicas (dry run: {dry_run}). Keep {\"json\": true} and {other}.\n";
+
+    fn values(value: Value) -> HashMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_render_command() {
+        let rendered = render_command(DEPLOY, &values(json!({"env": "staging"}))).unwrap();
+        assert_eq!(
+            rendered,
+            "Deploy to staging with 2 replicas (dry run: false). Keep {\"json\": true} and {other}.\n"
+        );
+
+        let rendered = render_command(
+            DEPLOY,
+            &values(json!({"env": "prod", "replicas": "5", "dry_run": true})),
+        )
+        .unwrap();
+        assert!(rendered.starts_with("Deploy to prod with 5 replicas (dry run: true)"));
+    }
+
+    #[test]
+    fn test_render_command_rejects_bad_values() {
+        let report = render_command(
+            DEPLOY,
+            &values(json!({"replicas": 1.5, "dry_run": "maybe", "region": "eu"})),
+        )
+        .unwrap_err();
+        let fields: Vec<&str> = report
+            .issues
+            .iter()
+            .filter_map(|i| i.field.as_deref())
+            .collect();
+        assert_eq!(fields, vec!["region", "env", "replicas", "dry_run"]);
+    }
+
+    #[test]
+    fn test_coerce_value() {
+        assert_eq!(
+            coerce_value(Some(ParamType::Number), &json!("2.5")).unwrap(),
+            "2.5"
+        );
+        assert!(coerce_value(Some(ParamType::Number), &json!("NaN")).is_err());
+        assert!(coerce_value(None, &json!(["a"])).is_err());
+        assert_eq!(coerce_value(None, &json!(3)).unwrap(), "3");
+    }
+}
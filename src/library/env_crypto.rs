@@ -393,6 +393,21 @@ pub fn strip_encrypted_tags(content: &str) -> String {
     re.replace_all(&content, "$1").to_string()
 }
 
+/// Empty the value of every encrypted tag (versioned, unversioned or failed),
+/// leaving `<encrypted></encrypted>` placeholders. Returns the redacted content
+/// and the number of values removed.
+///
+/// Used when content leaves this server (e.g., skill exports): ciphertext is
+/// useless without this server's key and plaintext must not leak.
+pub fn redact_encrypted_tags(content: &str) -> (String, usize) {
+    let re_failed = regex::Regex::new(FAILED_ENCRYPTED_TAG_REGEX).expect("Invalid regex");
+    let re = regex::Regex::new(ANY_ENCRYPTED_TAG_REGEX).expect("Invalid regex");
+    let count = re_failed.find_iter(content).count() + re.find_iter(content).count();
+    let content = re_failed.replace_all(content, "<encrypted></encrypted>");
+    let content = re.replace_all(&content, "<encrypted></encrypted>");
+    (content.to_string(), count)
+}
+
 /// Encrypt all unversioned <encrypted>value</encrypted> tags in content.
 /// Transforms <encrypted>plaintext</encrypted> to <encrypted v="1">ciphertext</encrypted>.
 pub fn encrypt_content_tags(key: &[u8; KEY_LENGTH], content: &str) -> Result<String> {
@@ -853,6 +868,18 @@ Keys:
         assert_eq!(strip_encrypted_tags(""), "");
     }
 
+    #[test]
+    fn test_redact_encrypted_tags() {
+        let content = "a=<encrypted>plain</encrypted> b=<encrypted v=\"1\">abc</encrypted> c=<encrypted-failed v=\"1\">x</encrypted-failed>";
+        let (redacted, count) = redact_encrypted_tags(content);
+        assert_eq!(count, 3);
+        assert_eq!(
+            redacted,
+            "a=<encrypted></encrypted> b=<encrypted></encrypted> c=<encrypted></encrypted>"
+        );
+        assert_eq!(redact_encrypted_tags("no tags"), ("no tags".to_string(), 0));
+    }
+
     #[test]
     fn test_strip_encrypted_tags_preserves_structure() {
         let content = r#"---
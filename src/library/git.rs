@@ -1,10 +1,13 @@
 //! Git operations for the configuration library.
 
 use anyhow::{Context, Result};
+use std::collections::BTreeMap;
 use std::path::Path;
 use tokio::process::Command;
 
-use super::types::LibraryStatus;
+use super::types::{
+    ConflictResolution, LibraryBranch, LibraryCommit, LibraryConflict, LibraryState, LibraryStatus,
+};
 
 /// Get the GIT_SSH_COMMAND value for git operations.
 ///
@@ -186,6 +189,30 @@ pub async fn status(path: &Path) -> Result<LibraryStatus> {
     // Get ahead/behind counts
     let (ahead, behind) = get_ahead_behind(path).await.unwrap_or((0, 0));
 
+    let files = conflicted_files(path).await?;
+    let state = if files.is_empty() {
+        LibraryState::Ok
+    } else {
+        LibraryState::Conflicted { files }
+    };
+
+    let stashed_changes = sync_stashes(path).await?.len();
+    let warning = if stashed_changes > 0 {
+        Some(
+            "Local changes set aside by a sync are still stashed. The next sync \
+             re-applies them; discarding local changes drops them."
+                .to_string(),
+        )
+    } else if !clean {
+        Some(format!(
+            "{} uncommitted local change(s). Syncing keeps them; discard local \
+             changes to drop them.",
+            modified_files.len()
+        ))
+    } else {
+        None
+    };
+
     Ok(LibraryStatus {
         path: path.to_string_lossy().to_string(),
         remote,
@@ -194,15 +221,117 @@ pub async fn status(path: &Path) -> Result<LibraryStatus> {
         ahead,
         behind,
         modified_files,
+        state,
+        stashed_changes,
+        warning,
+        auto_commit: None,
+        read_only: false,
     })
 }
 
+/// Subject of stashes made around library syncs.
+const SYNC_STASH_MESSAGE: &str = "sandboxed-sh: local changes before sync";
+
+/// Stash uncommitted changes, untracked files included, so a sync can't
+/// overwrite them or fail on them. Returns whether anything was stashed.
+/// A library with unresolved conflicts is left alone.
+pub async fn stash_local_changes(path: &Path) -> Result<bool> {
+    let (clean, _) = get_status(path).await?;
+    if clean || !conflicted_files(path).await?.is_empty() {
+        return Ok(false);
+    }
+    tracing::info!(path = %path.display(), "Stashing local library changes before sync");
+    // Stash commits need an identity, which servers often lack.
+    let output = Command::new("git")
+        .current_dir(path)
+        .args([
+            "stash",
+            "push",
+            "--include-untracked",
+            "-m",
+            SYNC_STASH_MESSAGE,
+        ])
+        .env("GIT_AUTHOR_NAME", "sandboxed.sh")
+        .env("GIT_AUTHOR_EMAIL", "sandboxed-sh@localhost")
+        .env("GIT_COMMITTER_NAME", "sandboxed.sh")
+        .env("GIT_COMMITTER_EMAIL", "sandboxed-sh@localhost")
+        .output()
+        .await
+        .context("Failed to execute git stash")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("git stash failed: {}", stderr.trim());
+    }
+    Ok(true)
+}
+
+/// Re-apply changes stashed by `stash_local_changes`, newest first.
+///
+/// When they conflict with the synced files the stash is dropped and the
+/// conflicts are left for the conflicts API (`STASH_CONFLICT:`). If it could
+/// not be applied at all it stays in the stash.
+pub async fn restore_local_changes(path: &Path) -> Result<()> {
+    while let Some(stash) = sync_stashes(path).await?.into_iter().next() {
+        let output = Command::new("git")
+            .current_dir(path)
+            .args(["stash", "pop", &stash])
+            .output()
+            .await
+            .context("Failed to execute git stash pop")?;
+        if output.status.success() {
+            continue;
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let files = conflicted_files(path).await?;
+        if files.is_empty() {
+            anyhow::bail!(
+                "Could not re-apply local changes; they are still stashed. Git error: {}",
+                stderr.trim()
+            );
+        }
+        run_git(path, &["stash", "drop", &stash]).await?;
+        anyhow::bail!(
+            "STASH_CONFLICT: Local changes conflict with the synced files in {}. \
+             Resolve them through the conflicts API, or discard local changes.",
+            files.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Refs (`stash@{n}`) of stashes made by `stash_local_changes`, newest first.
+async fn sync_stashes(path: &Path) -> Result<Vec<String>> {
+    let list = run_git(path, &["stash", "list", "--format=%gd%x09%s"]).await?;
+    Ok(list
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .filter(|(_, subject)| subject.ends_with(SYNC_STASH_MESSAGE))
+        .map(|(reference, _)| reference.to_string())
+        .collect())
+}
+
+/// Drop uncommitted changes, untracked files and sync stashes, and abandon
+/// any merge in progress. Ignored files are kept.
+pub async fn discard_local_changes(path: &Path) -> Result<()> {
+    tracing::info!(path = %path.display(), "Discarding local library changes");
+    run_git(path, &["reset", "--hard", "HEAD"]).await?;
+    run_git(path, &["clean", "-fd"]).await?;
+    while let Some(stash) = sync_stashes(path).await?.into_iter().next() {
+        run_git(path, &["stash", "drop", &stash]).await?;
+    }
+    Ok(())
+}
+
 /// Error type for git pull operations.
 #[derive(Debug)]
 pub enum PullError {
     /// Pull failed because local and remote histories have diverged.
     /// This happens after a force push on the remote.
     DivergedHistory { message: String },
+    /// Histories diverged and merging them stopped on conflicts. The merge is
+    /// left in progress so the conflicts can be resolved.
+    Conflicted { files: Vec<String> },
     /// Pull failed for another reason.
     Other(anyhow::Error),
 }
@@ -213,6 +342,9 @@ impl std::fmt::Display for PullError {
             PullError::DivergedHistory { message } => {
                 write!(f, "Diverged history: {}", message)
             }
+            PullError::Conflicted { files } => {
+                write!(f, "Merge conflicts in: {}", files.join(", "))
+            }
             PullError::Other(e) => write!(f, "{}", e),
         }
     }
@@ -242,6 +374,13 @@ pub async fn pull(path: &Path) -> Result<(), PullError> {
             || stderr_lower.contains("cannot fast-forward")
             || stderr_lower.contains("refusing to merge unrelated histories")
         {
+            // Try a real merge first; only unmergeable histories need a
+            // force pull or force push.
+            match merge_upstream(path).await {
+                Ok(true) => return Ok(()),
+                Ok(false) => {}
+                Err(e) => return Err(e),
+            }
             return Err(PullError::DivergedHistory {
                 message: format!(
                     "Local and remote histories have diverged (likely due to a force push). \
@@ -261,6 +400,161 @@ pub async fn pull(path: &Path) -> Result<(), PullError> {
     Ok(())
 }
 
+/// Pull for unattended syncs: `git pull --rebase --autostash`.
+///
+/// Uncommitted edits are stashed and re-applied, and unpushed commits are
+/// replayed on top of the remote. A rebase that stops on conflicts is
+/// aborted, leaving the library as it was.
+pub async fn pull_rebase(path: &Path) -> Result<()> {
+    tracing::info!(path = %path.display(), "Pulling library changes (rebase, autostash)");
+
+    let mut cmd = Command::new("git");
+    cmd.current_dir(path)
+        .args(["pull", "--rebase", "--autostash"]);
+    apply_ssh_config(&mut cmd);
+    let output = cmd.output().await.context("Failed to execute git pull")?;
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let git_dir = path.join(".git");
+    if git_dir.join("rebase-merge").exists() || git_dir.join("rebase-apply").exists() {
+        let _ = Command::new("git")
+            .current_dir(path)
+            .args(["rebase", "--abort"])
+            .output()
+            .await;
+        anyhow::bail!(
+            "REBASE_CONFLICT: Local library commits conflict with the remote; \
+             sync manually to merge them. Git error: {}",
+            stderr.trim()
+        );
+    }
+    anyhow::bail!("git pull failed: {}", stderr.trim())
+}
+
+/// Merge the upstream branch into a diverged local branch.
+///
+/// Returns `Ok(false)` (with the merge aborted) when git could not merge at
+/// all, e.g. unrelated histories, and `PullError::Conflicted` when the merge
+/// stopped on conflicts.
+async fn merge_upstream(path: &Path) -> Result<bool, PullError> {
+    tracing::info!(path = %path.display(), "Library history diverged; merging upstream");
+
+    let output = Command::new("git")
+        .current_dir(path)
+        .args(["merge", "--no-edit", "@{u}"])
+        .output()
+        .await
+        .map_err(|e| PullError::Other(anyhow::anyhow!("Failed to execute git merge: {}", e)))?;
+    if output.status.success() {
+        return Ok(true);
+    }
+
+    let files = conflicted_files(path).await.map_err(PullError::Other)?;
+    if !files.is_empty() {
+        tracing::warn!(path = %path.display(), files = ?files, "Library merge has conflicts");
+        return Err(PullError::Conflicted { files });
+    }
+
+    let _ = Command::new("git")
+        .current_dir(path)
+        .args(["merge", "--abort"])
+        .output()
+        .await;
+    Ok(false)
+}
+
+/// Paths with unresolved merge conflicts.
+pub async fn conflicted_files(path: &Path) -> Result<Vec<String>> {
+    let stdout = run_git(path, &["diff", "--name-only", "--diff-filter=U", "-z"]).await?;
+    Ok(stdout
+        .split('\0')
+        .filter(|p| !p.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Base, local and remote versions of a conflicted file.
+pub async fn show_conflict(path: &Path, file: &str) -> Result<LibraryConflict> {
+    ensure_conflicted(path, file).await?;
+
+    // Index stages: 1 = common ancestor, 2 = ours, 3 = theirs.
+    let stage = |n: u8| async move {
+        let output = Command::new("git")
+            .current_dir(path)
+            .args(["show", &format!(":{}:{}", n, file)])
+            .output()
+            .await
+            .ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+    };
+
+    Ok(LibraryConflict {
+        path: file.to_string(),
+        base: stage(1).await,
+        ours: stage(2).await,
+        theirs: stage(3).await,
+        working: tokio::fs::read_to_string(path.join(file)).await.ok(),
+    })
+}
+
+/// Resolve one conflicted file and stage it. Returns the files that are
+/// still conflicted.
+pub async fn resolve_conflict(
+    path: &Path,
+    file: &str,
+    resolution: &ConflictResolution,
+) -> Result<Vec<String>> {
+    ensure_conflicted(path, file).await?;
+    tracing::info!(path = %path.display(), file = %file, resolution = ?resolution, "Resolving library conflict");
+
+    let side = match resolution {
+        ConflictResolution::Ours => Some(("--ours", 2)),
+        ConflictResolution::Theirs => Some(("--theirs", 3)),
+        ConflictResolution::Manual { content } => {
+            tokio::fs::write(path.join(file), content).await?;
+            None
+        }
+    };
+
+    match side {
+        // The chosen side deleted the file: resolve by removing it.
+        Some((_, stage)) if !ref_exists(path, &format!(":{}:{}", stage, file)).await => {
+            run_git(path, &["rm", "--quiet", "--", file]).await?;
+        }
+        Some((flag, _)) => {
+            run_git(path, &["checkout", flag, "--", file]).await?;
+            run_git(path, &["add", "--", file]).await?;
+        }
+        None => {
+            run_git(path, &["add", "--", file]).await?;
+        }
+    }
+
+    conflicted_files(path).await
+}
+
+/// Abort an in-progress merge, restoring the pre-sync state.
+pub async fn abort_merge(path: &Path) -> Result<()> {
+    tracing::info!(path = %path.display(), "Aborting library merge");
+    run_git(path, &["merge", "--abort"]).await?;
+    Ok(())
+}
+
+/// Fail unless `file` is one of the conflicted paths. Guards the conflict
+/// endpoints against arbitrary paths.
+async fn ensure_conflicted(path: &Path, file: &str) -> Result<()> {
+    if !conflicted_files(path).await?.iter().any(|f| f == file) {
+        anyhow::bail!("'{}' has no merge conflict", file);
+    }
+    Ok(())
+}
+
 /// Force pull: reset local branch to match remote (discards local changes).
 /// Use this after a force push on the remote has caused history to diverge.
 pub async fn force_pull(path: &Path) -> Result<()> {
@@ -338,6 +632,15 @@ impl GitAuthor {
 pub async fn commit(path: &Path, message: &str, author: Option<&GitAuthor>) -> Result<()> {
     tracing::info!(path = %path.display(), message = %message, "Committing library changes");
 
+    // `git add -A` would stage conflict markers as resolved content.
+    let conflicts = conflicted_files(path).await?;
+    if !conflicts.is_empty() {
+        anyhow::bail!(
+            "Resolve merge conflicts before committing: {}",
+            conflicts.join(", ")
+        );
+    }
+
     // Stage all changes
     let output = Command::new("git")
         .current_dir(path)
@@ -380,7 +683,9 @@ pub async fn push(path: &Path) -> Result<()> {
     tracing::info!(path = %path.display(), "Pushing library changes");
 
     let mut cmd = Command::new("git");
-    cmd.current_dir(path).args(["push"]);
+    // Push the current branch by name and set its upstream, so branches
+    // created locally can be pushed too.
+    cmd.current_dir(path).args(["push", "-u", "origin", "HEAD"]);
     apply_ssh_config(&mut cmd);
     let output = cmd.output().await.context("Failed to execute git push")?;
 
@@ -508,9 +813,302 @@ pub async fn sparse_clone(path: &Path, remote: &str, subpath: &str) -> Result<()
     Ok(())
 }
 
+/// List local branches and the branches on origin.
+///
+/// Remote branches come from `git ls-remote` because the library is a
+/// single-branch shallow clone; if origin is unreachable the cached
+/// remote-tracking refs are used instead.
+pub async fn list_branches(path: &Path) -> Result<Vec<LibraryBranch>> {
+    let current = get_branch(path).await.ok();
+    let local = run_git(
+        path,
+        &["for-each-ref", "--format=%(refname:short)", "refs/heads"],
+    )
+    .await?;
+
+    let mut cmd = Command::new("git");
+    cmd.current_dir(path)
+        .args(["ls-remote", "--heads", "origin"]);
+    apply_ssh_config(&mut cmd);
+    let output = cmd
+        .output()
+        .await
+        .context("Failed to execute git ls-remote")?;
+    let remote: Vec<String> = if output.status.success() {
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| line.split('\t').nth(1))
+            .filter_map(|r| r.strip_prefix("refs/heads/"))
+            .map(str::to_string)
+            .collect()
+    } else {
+        tracing::warn!(
+            stderr = %String::from_utf8_lossy(&output.stderr).trim(),
+            "git ls-remote failed; listing cached remote branches"
+        );
+        run_git(
+            path,
+            &[
+                "for-each-ref",
+                "--format=%(refname:short)",
+                "refs/remotes/origin",
+            ],
+        )
+        .await?
+        .lines()
+        .filter_map(|r| r.strip_prefix("origin/"))
+        .filter(|r| *r != "HEAD")
+        .map(str::to_string)
+        .collect()
+    };
+
+    let mut branches: BTreeMap<String, LibraryBranch> = BTreeMap::new();
+    for name in local.lines().filter(|l| !l.is_empty()) {
+        branches.insert(
+            name.to_string(),
+            LibraryBranch {
+                name: name.to_string(),
+                current: current.as_deref() == Some(name),
+                local: true,
+                remote: false,
+            },
+        );
+    }
+    for name in remote {
+        branches
+            .entry(name.clone())
+            .or_insert_with(|| LibraryBranch {
+                name,
+                current: false,
+                local: false,
+                remote: false,
+            })
+            .remote = true;
+    }
+
+    Ok(branches.into_values().collect())
+}
+
+/// Switch the library checkout to `branch`, fetching it from origin if it
+/// only exists there. Refuses to switch with uncommitted changes.
+pub async fn checkout_branch(path: &Path, branch: &str) -> Result<()> {
+    validate_branch_name(path, branch).await?;
+    tracing::info!(path = %path.display(), branch = %branch, "Checking out library branch");
+
+    let (clean, modified_files) = get_status(path).await?;
+    if !clean {
+        anyhow::bail!(
+            "Library has uncommitted changes ({} files); commit or discard them before switching branches",
+            modified_files.len()
+        );
+    }
+
+    if ref_exists(path, &format!("refs/heads/{}", branch)).await {
+        run_git(path, &["checkout", branch]).await?;
+        return Ok(());
+    }
+
+    fetch_branch(path, branch).await?;
+    run_git(
+        path,
+        &[
+            "checkout",
+            "-b",
+            branch,
+            &format!("refs/remotes/origin/{}", branch),
+        ],
+    )
+    .await?;
+    // The single-branch fetch refspec doesn't cover this branch, so set the
+    // upstream by hand for pull/push.
+    run_git(
+        path,
+        &["config", &format!("branch.{}.remote", branch), "origin"],
+    )
+    .await?;
+    run_git(
+        path,
+        &[
+            "config",
+            &format!("branch.{}.merge", branch),
+            &format!("refs/heads/{}", branch),
+        ],
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Create `branch` from `start_point` (default: HEAD) without switching to it.
+pub async fn create_branch(path: &Path, branch: &str, start_point: Option<&str>) -> Result<()> {
+    validate_branch_name(path, branch).await?;
+    if ref_exists(path, &format!("refs/heads/{}", branch)).await {
+        anyhow::bail!("Branch '{}' already exists", branch);
+    }
+    let start_point = start_point.unwrap_or("HEAD");
+    if start_point.starts_with('-') {
+        anyhow::bail!("Invalid start point: {}", start_point);
+    }
+    tracing::info!(path = %path.display(), branch = %branch, start_point = %start_point, "Creating library branch");
+
+    run_git(path, &["branch", "--no-track", branch, start_point]).await?;
+    Ok(())
+}
+
+/// Check out `branch` into a detached worktree at `dest`, creating it if
+/// needed. Uses whichever of the local and origin branch is newer.
+pub async fn sync_worktree(path: &Path, branch: &str, dest: &Path) -> Result<()> {
+    validate_branch_name(path, branch).await?;
+
+    if let Err(e) = fetch_branch(path, branch).await {
+        tracing::warn!(branch = %branch, error = %e, "Failed to fetch library branch; using local refs");
+    }
+    let local = format!("refs/heads/{}", branch);
+    let remote = format!("refs/remotes/origin/{}", branch);
+    let rev = match (
+        ref_exists(path, &local).await,
+        ref_exists(path, &remote).await,
+    ) {
+        (true, true) => {
+            let behind = Command::new("git")
+                .current_dir(path)
+                .args(["merge-base", "--is-ancestor", &local, &remote])
+                .output()
+                .await
+                .map(|o| o.status.success())
+                .unwrap_or(false);
+            if behind {
+                remote
+            } else {
+                local
+            }
+        }
+        (true, false) => local,
+        (false, true) => remote,
+        (false, false) => anyhow::bail!("Library branch '{}' not found", branch),
+    };
+
+    if dest.join(".git").exists() {
+        run_git(dest, &["checkout", "--detach", "--force", &rev]).await?;
+        run_git(dest, &["clean", "-fdq"]).await?;
+    } else {
+        if let Some(parent) = dest.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        // Drop registrations for worktrees whose directory was deleted.
+        run_git(path, &["worktree", "prune"]).await?;
+        let dest_str = dest.to_string_lossy();
+        run_git(
+            path,
+            &["worktree", "add", "--detach", "--force", &dest_str, &rev],
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Commits that touched `file`, newest first (following renames).
+///
+/// The library is a shallow clone, so the first call fetches the full history.
+pub async fn file_history(path: &Path, file: &str, limit: usize) -> Result<Vec<LibraryCommit>> {
+    let file = validate_library_path(file)?;
+
+    let shallow = run_git(path, &["rev-parse", "--is-shallow-repository"]).await?;
+    if shallow.trim() == "true" {
+        let mut cmd = Command::new("git");
+        cmd.current_dir(path)
+            .args(["fetch", "--unshallow", "origin"]);
+        apply_ssh_config(&mut cmd);
+        match cmd.output().await {
+            Ok(output) if output.status.success() => {}
+            Ok(output) => tracing::warn!(
+                stderr = %String::from_utf8_lossy(&output.stderr).trim(),
+                "Failed to unshallow library; history may be incomplete"
+            ),
+            Err(e) => tracing::warn!(error = %e, "Failed to execute git fetch --unshallow"),
+        }
+    }
+
+    let stdout = run_git(
+        path,
+        &[
+            "log",
+            "--follow",
+            &format!("--max-count={}", limit),
+            "--format=%H%x1f%h%x1f%an%x1f%aI%x1f%s%x1e",
+            "--",
+            file,
+        ],
+    )
+    .await?;
+
+    Ok(stdout
+        .split('\x1e')
+        .filter_map(|record| {
+            let mut fields = record.trim_start_matches('\n').split('\x1f');
+            Some(LibraryCommit {
+                hash: fields.next().filter(|h| !h.is_empty())?.to_string(),
+                short_hash: fields.next()?.to_string(),
+                author: fields.next()?.to_string(),
+                date: fields.next()?.to_string(),
+                message: fields.next()?.to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Unified diff of `file` between `from` and `to` (default: the working tree).
+pub async fn diff(path: &Path, file: &str, from: &str, to: Option<&str>) -> Result<String> {
+    let file = validate_library_path(file)?;
+    let from = resolve_commit(path, from).await?;
+    let mut args = vec!["diff".to_string(), from];
+    if let Some(to) = to {
+        args.push(resolve_commit(path, to).await?);
+    }
+    args.extend(["--".to_string(), file.to_string()]);
+
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    run_git(path, &args).await
+}
+
+/// Restore `file` to its content at `rev` in the working tree (staged, not
+/// committed). A file that did not exist at `rev` is removed.
+pub async fn revert(path: &Path, file: &str, rev: &str) -> Result<()> {
+    let file = validate_library_path(file)?;
+    let rev = resolve_commit(path, rev).await?;
+    tracing::info!(path = %path.display(), file = %file, rev = %rev, "Reverting library file");
+
+    if ref_exists(path, &format!("{}:{}", rev, file)).await {
+        run_git(path, &["checkout", &rev, "--", file]).await?;
+    } else if ref_exists(path, &format!("HEAD:{}", file)).await {
+        run_git(path, &["rm", "--quiet", "--", file]).await?;
+    } else {
+        anyhow::bail!("'{}' does not exist at {}", file, rev);
+    }
+
+    Ok(())
+}
+
+/// Move `from` to `to` (paths relative to the library root) with `git mv`,
+/// so the rename is staged and `git log --follow` keeps the history.
+/// Untracked paths, and libraries that aren't git checkouts, are moved on
+/// disk instead.
+pub async fn move_path(path: &Path, from: &str, to: &str) -> Result<()> {
+    match run_git(path, &["mv", "--", from, to]).await {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::debug!(from = %from, to = %to, error = %e, "git mv failed, renaming on disk");
+            tokio::fs::rename(path.join(from), path.join(to))
+                .await
+                .context("Failed to rename file/directory")
+        }
+    }
+}
+
 // Helper functions
 
-async fn get_branch(path: &Path) -> Result<String> {
+pub(super) async fn get_branch(path: &Path) -> Result<String> {
     let output = Command::new("git")
         .current_dir(path)
         .args(["rev-parse", "--abbrev-ref", "HEAD"])
@@ -558,6 +1156,16 @@ async fn get_status(path: &Path) -> Result<(bool, Vec<String>)> {
     Ok((lines.is_empty(), lines))
 }
 
+/// `git status --porcelain` lines, listing every untracked file.
+pub async fn changed_files(path: &Path) -> Result<Vec<String>> {
+    let stdout = run_git(path, &["status", "--porcelain", "--untracked-files=all"]).await?;
+    Ok(stdout
+        .lines()
+        .filter(|l| !l.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
 async fn get_ahead_behind(path: &Path) -> Result<(u32, u32)> {
     // First, fetch to update remote tracking branches
     let mut cmd = Command::new("git");
@@ -589,3 +1197,104 @@ async fn get_ahead_behind(path: &Path) -> Result<(u32, u32)> {
         Ok((0, 0))
     }
 }
+
+/// The commit checked out in `path`.
+pub async fn head_commit(path: &Path) -> Result<String> {
+    Ok(run_git(path, &["rev-parse", "HEAD"])
+        .await?
+        .trim()
+        .to_string())
+}
+
+/// Run a git command in `path`, returning stdout or failing with stderr.
+async fn run_git(path: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .current_dir(path)
+        .args(args)
+        .output()
+        .await
+        .with_context(|| format!("Failed to execute git {}", args[0]))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("git {} failed: {}", args[0], stderr.trim());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+async fn ref_exists(path: &Path, reference: &str) -> bool {
+    Command::new("git")
+        .current_dir(path)
+        .args(["rev-parse", "--verify", "--quiet", reference])
+        .output()
+        .await
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+async fn validate_branch_name(path: &Path, branch: &str) -> Result<()> {
+    let valid = !branch.is_empty()
+        && !branch.starts_with('-')
+        && Command::new("git")
+            .current_dir(path)
+            .args(["check-ref-format", "--branch", branch])
+            .output()
+            .await
+            .map(|o| o.status.success())
+            .unwrap_or(false);
+    if !valid {
+        anyhow::bail!("Invalid branch name: {}", branch);
+    }
+    Ok(())
+}
+
+/// Fetch `branch` from origin into `refs/remotes/origin/<branch>`.
+async fn fetch_branch(path: &Path, branch: &str) -> Result<()> {
+    let mut cmd = Command::new("git");
+    cmd.current_dir(path).args([
+        "fetch",
+        "origin",
+        &format!("+refs/heads/{0}:refs/remotes/origin/{0}", branch),
+    ]);
+    apply_ssh_config(&mut cmd);
+    let output = cmd.output().await.context("Failed to execute git fetch")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("git fetch failed: {}", stderr.trim());
+    }
+
+    Ok(())
+}
+
+/// Reject paths that are absolute or leave the library.
+fn validate_library_path(file: &str) -> Result<&str> {
+    let file = file.trim_start_matches("./");
+    let escapes = Path::new(file)
+        .components()
+        .any(|c| !matches!(c, std::path::Component::Normal(_)));
+    if file.is_empty() || escapes || file.starts_with('-') {
+        anyhow::bail!("Invalid library path: {}", file);
+    }
+    Ok(file)
+}
+
+/// Resolve a revision (hash, branch, `HEAD~1`, ...) to a commit hash.
+async fn resolve_commit(path: &Path, rev: &str) -> Result<String> {
+    if rev.is_empty() || rev.starts_with('-') {
+        anyhow::bail!("Invalid revision: {}", rev);
+    }
+    run_git(
+        path,
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{}^{{commit}}", rev),
+        ],
+    )
+    .await
+    .map(|hash| hash.trim().to_string())
+    .map_err(|_| anyhow::anyhow!("Unknown revision: {}", rev))
+}
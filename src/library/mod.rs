@@ -5,6 +5,7 @@
 //! - Skills (`skill/*/SKILL.md` with additional .md files and references)
 //! - Commands/prompts (`command/*.md`)
 //! - Plugins registry (`plugins.json`)
+//! - Skill settings (`skills.json`): enabled flag and workspace allowlist
 //! - Library agents (`agent/*.md`)
 //! - Library tools (`tool/*.ts`)
 //! - Config profiles (`configs/<profile>/`) with harness-specific settings:
@@ -13,15 +14,20 @@
 //!   - `.ampcode/` - Amp settings (settings.json)
 //!   - `.sandboxed-sh/` - Sandboxed config (config.json)
 
+pub mod agents;
+pub mod command_template;
 pub mod env_crypto;
 mod git;
 pub mod rename;
+pub mod search;
 pub mod types;
+pub mod validate;
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tokio::fs;
 
 pub use git::GitAuthor;
@@ -68,9 +74,26 @@ const COMMAND_DIR: &str = "command";
 const AGENT_DIR: &str = "agent";
 const INIT_SCRIPT_DIR: &str = "init-script";
 const PLUGINS_FILE: &str = "plugins.json";
+const SKILL_SETTINGS_FILE: &str = "skills.json";
 const WORKSPACE_TEMPLATE_DIR: &str = "workspace-template";
 const CONFIGS_DIR: &str = "configs";
 const DEFAULT_PROFILE: &str = "default";
+/// Largest skill tarball accepted by `import_skill_from_url`.
+const MAX_SKILL_ARCHIVE_BYTES: u64 = 50 * 1024 * 1024;
+/// Largest extracted size of a skill tarball.
+const MAX_SKILL_EXTRACT_BYTES: u64 = 200 * 1024 * 1024;
+const MAX_SKILL_EXTRACT_FILES: usize = 5_000;
+/// Files larger than this are left out of library search.
+const MAX_SEARCH_FILE_BYTES: u64 = 1024 * 1024;
+/// Push attempts per auto-commit pass, and the first retry delay (doubled
+/// after each failure).
+const AUTO_PUSH_ATTEMPTS: u32 = 4;
+const AUTO_PUSH_BACKOFF_SECS: u64 = 5;
+/// Files named in an auto-commit subject before "and N more".
+const AUTO_COMMIT_SUBJECT_ITEMS: usize = 3;
+
+/// Serializes unattended pulls and pushes (webhook, sync loop, auto-commit).
+static AUTO_SYNC_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
 
 /// Store for managing the configuration library.
 pub struct LibraryStore {
@@ -78,6 +101,29 @@ pub struct LibraryStore {
     path: PathBuf,
     /// Git remote URL
     remote: String,
+    /// Layers requested for this store (including any that failed to open)
+    layer_configs: Vec<LibraryLayerConfig>,
+    /// Read-only libraries merged under this one, in priority order
+    layers: Vec<LibraryLayer>,
+    /// Auto-commit daemon state (see `auto_commit`)
+    auto_commit_state: std::sync::Mutex<AutoCommitState>,
+    /// Reject edits, commits and pushes (see `ensure_writable`)
+    read_only: bool,
+}
+
+/// What the auto-commit daemon remembers between passes.
+#[derive(Default)]
+struct AutoCommitState {
+    /// Working tree seen on the previous pass
+    fingerprint: Option<u64>,
+    /// `None` until the daemon first runs
+    status: Option<AutoCommitStatus>,
+}
+
+/// An opened library layer.
+struct LibraryLayer {
+    name: String,
+    store: Arc<LibraryStore>,
 }
 
 impl LibraryStore {
@@ -87,10 +133,99 @@ impl LibraryStore {
         git::clone_if_needed(&path, remote).await?;
         git::ensure_remote(&path, remote).await?;
 
-        Ok(Self {
+        Ok(Self::unlayered(path, remote.to_string()))
+    }
+
+    /// Create a LibraryStore with extra read-only library layers.
+    ///
+    /// Each layer is cloned to `<library>-layers/<name>`. Layers that fail to
+    /// open are logged and left out; only the primary library is required.
+    pub async fn with_layers(
+        path: PathBuf,
+        remote: &str,
+        layers: &[LibraryLayerConfig],
+    ) -> Result<Self> {
+        let mut store = Self::new(path, remote).await?;
+        store.layer_configs = layers.to_vec();
+
+        for config in layers {
+            if let Err(e) = Self::validate_name(&config.name) {
+                tracing::warn!(layer = %config.name, error = %e, "Invalid library layer name");
+                continue;
+            }
+            if store.layers.iter().any(|l| l.name == config.name) {
+                tracing::warn!(layer = %config.name, "Duplicate library layer name, skipping");
+                continue;
+            }
+            let layer_path = store.layers_dir().join(&config.name);
+            match Self::new(layer_path, &config.remote).await {
+                Ok(layer) => store.layers.push(LibraryLayer {
+                    name: config.name.clone(),
+                    store: Arc::new(layer),
+                }),
+                Err(e) => tracing::warn!(
+                    layer = %config.name,
+                    remote = %config.remote,
+                    error = %e,
+                    "Failed to open library layer, skipping"
+                ),
+            }
+        }
+
+        Ok(store)
+    }
+
+    fn unlayered(path: PathBuf, remote: String) -> Self {
+        Self {
             path,
-            remote: remote.to_string(),
-        })
+            remote,
+            layer_configs: Vec::new(),
+            layers: Vec::new(),
+            auto_commit_state: Default::default(),
+            read_only: false,
+        }
+    }
+
+    /// Make the store read-only: every method that edits, commits or pushes
+    /// fails with a `READ_ONLY:` error. Syncing from the remote still works.
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Whether the store rejects changes.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    fn ensure_writable(&self) -> Result<()> {
+        if self.read_only {
+            anyhow::bail!(
+                "READ_ONLY: The library is read-only; change it through pull requests to {}",
+                self.remote
+            );
+        }
+        Ok(())
+    }
+
+    /// `<library>-layers`, next to the library checkout.
+    fn layers_dir(&self) -> PathBuf {
+        let name = self
+            .path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "library".to_string());
+        self.path.with_file_name(format!("{}-layers", name))
+    }
+
+    /// The layers this store was created with.
+    pub fn layer_configs(&self) -> &[LibraryLayerConfig] {
+        &self.layer_configs
+    }
+
+    /// Names of the layers that opened successfully, in priority order.
+    pub fn layer_names(&self) -> Vec<String> {
+        self.layers.iter().map(|l| l.name.clone()).collect()
     }
 
     /// Get the library path.
@@ -109,55 +244,269 @@ impl LibraryStore {
 
     /// Get the current git status of the library.
     pub async fn status(&self) -> Result<LibraryStatus> {
-        git::status(&self.path).await
+        let mut status = git::status(&self.path).await?;
+        status.auto_commit = self.auto_commit_state.lock().unwrap().status.clone();
+        status.read_only = self.read_only;
+        Ok(status)
     }
 
     /// Pull latest changes from remote.
     /// After pulling, encrypts any unversioned <encrypted> tags in skill files.
     ///
-    /// Returns `Err` with a specific error if the pull fails due to diverged history
-    /// (e.g., after a force push on the remote). In this case, use `force_sync` to
-    /// reset the local branch to match remote.
+    /// Diverged histories are merged. Returns `Err` with a `MERGE_CONFLICT:` error
+    /// if that merge stops on conflicts (the merge is left in progress; see
+    /// `conflicts`), or a `DIVERGED_HISTORY:` error if the histories can't be
+    /// merged at all (e.g., after a force push on the remote). In that case, use
+    /// `force_sync` to reset the local branch to match remote.
+    ///
+    /// Uncommitted changes are stashed around the pull and re-applied after it
+    /// (a `STASH_CONFLICT:` error if they conflict with the pulled files).
+    /// After a merge conflict they stay stashed until the next sync.
     pub async fn sync(&self) -> Result<()> {
+        let stashed = git::stash_local_changes(&self.path).await?;
         match git::pull(&self.path).await {
             Ok(()) => {}
             Err(git::PullError::DivergedHistory { message }) => {
+                git::restore_local_changes(&self.path).await?;
                 // Return a specific error that the API layer can detect
                 anyhow::bail!("DIVERGED_HISTORY: {}", message);
             }
+            Err(git::PullError::Conflicted { files }) => {
+                let stash_note = if stashed {
+                    " Uncommitted local changes stay stashed until the next sync."
+                } else {
+                    ""
+                };
+                anyhow::bail!(
+                    "MERGE_CONFLICT: Merging remote changes stopped on conflicts in {}. \
+                     Resolve them through the conflicts API and commit, or abort the merge.{}",
+                    files.join(", "),
+                    stash_note
+                );
+            }
             Err(git::PullError::Other(e)) => {
+                if let Err(restore) = git::restore_local_changes(&self.path).await {
+                    tracing::warn!(error = %restore, "Failed to restore local library changes");
+                }
                 return Err(e);
             }
         }
+        git::restore_local_changes(&self.path).await?;
 
         // Encrypt any unversioned encrypted tags in all skills
         self.encrypt_all_skill_files().await?;
 
+        self.sync_layers().await;
+
         Ok(())
     }
 
-    /// Force sync: reset local branch to match remote, discarding local changes.
+    /// Sync for webhooks and the background loop, where nobody is around to
+    /// resolve a merge: local edits are stashed and local commits rebased onto
+    /// the remote. Fails without touching the library if it has unresolved
+    /// conflicts or the rebase conflicts (`MERGE_CONFLICT:`/`REBASE_CONFLICT:`).
+    ///
+    /// Returns whether the library or any layer moved to a new commit.
+    pub async fn auto_sync(&self) -> Result<bool> {
+        // Webhooks and the background loops can fire at the same time.
+        let _guard = AUTO_SYNC_LOCK.lock().await;
+
+        let conflicts = git::conflicted_files(&self.path).await?;
+        if !conflicts.is_empty() {
+            anyhow::bail!(
+                "MERGE_CONFLICT: The library has unresolved conflicts in {}",
+                conflicts.join(", ")
+            );
+        }
+        let before = self.head_commits().await;
+        git::pull_rebase(&self.path).await?;
+
+        self.encrypt_all_skill_files().await?;
+        self.sync_layers().await;
+
+        Ok(self.head_commits().await != before)
+    }
+
+    /// One pass of the auto-commit daemon, run every `window_secs`.
+    ///
+    /// Uncommitted changes are committed once the working tree looks the same
+    /// as on the previous pass, so a burst of edits lands in a single commit.
+    /// Commits are then pushed, retrying with backoff and rebasing onto the
+    /// remote if the push is rejected. Returns whether a commit was made; the
+    /// outcome is also kept for `status`.
+    pub async fn auto_commit(&self, window_secs: u64) -> Result<bool> {
+        let result = self.auto_commit_pass(window_secs).await;
+        let mut state = self.auto_commit_state.lock().unwrap();
+        let status = state.status.get_or_insert_with(Default::default);
+        status.last_error = result.as_ref().err().map(|e| e.to_string());
+        result
+    }
+
+    async fn auto_commit_pass(&self, window_secs: u64) -> Result<bool> {
+        self.ensure_writable()?;
+        let conflicts = git::conflicted_files(&self.path).await?;
+        if !conflicts.is_empty() {
+            anyhow::bail!(
+                "MERGE_CONFLICT: The library has unresolved conflicts in {}",
+                conflicts.join(", ")
+            );
+        }
+
+        let files = git::changed_files(&self.path).await?;
+        let fingerprint = self.worktree_fingerprint(&files).await;
+        let (settled, unpushed) = {
+            let mut state = self.auto_commit_state.lock().unwrap();
+            let previous = state.fingerprint.replace(fingerprint);
+            let status = state.status.get_or_insert_with(Default::default);
+            status.window_secs = window_secs;
+            status.pending_files = files.len();
+            if files.is_empty() {
+                status.pending_since = None;
+            } else if status.pending_since.is_none() {
+                status.pending_since = Some(chrono::Utc::now().to_rfc3339());
+            }
+            (
+                !files.is_empty() && previous == Some(fingerprint),
+                status.unpushed,
+            )
+        };
+        if !settled && !unpushed {
+            return Ok(false);
+        }
+
+        if settled {
+            let message = auto_commit_message(&files);
+            {
+                let _guard = AUTO_SYNC_LOCK.lock().await;
+                git::commit(&self.path, &message, None).await?;
+            }
+            let head = git::head_commit(&self.path).await.ok();
+            tracing::info!(commit = ?head, files = files.len(), "Library auto-committed");
+            let mut state = self.auto_commit_state.lock().unwrap();
+            state.fingerprint = None;
+            let status = state.status.get_or_insert_with(Default::default);
+            status.pending_files = 0;
+            status.pending_since = None;
+            status.last_commit = head;
+            status.last_commit_at = Some(chrono::Utc::now().to_rfc3339());
+            status.unpushed = true;
+        }
+
+        self.push_with_retry().await?;
+        let mut state = self.auto_commit_state.lock().unwrap();
+        let status = state.status.get_or_insert_with(Default::default);
+        status.unpushed = false;
+        status.last_push_at = Some(chrono::Utc::now().to_rfc3339());
+        Ok(settled)
+    }
+
+    /// Push, retrying with exponential backoff. A rejected push (the remote
+    /// moved on) is retried after rebasing onto the remote.
+    async fn push_with_retry(&self) -> Result<()> {
+        let mut attempt = 0;
+        loop {
+            let result = {
+                let _guard = AUTO_SYNC_LOCK.lock().await;
+                git::push(&self.path).await
+            };
+            let error = match result {
+                Ok(()) => return Ok(()),
+                Err(e) if attempt + 1 >= AUTO_PUSH_ATTEMPTS => return Err(e),
+                Err(e) => e.to_string(),
+            };
+            let delay = AUTO_PUSH_BACKOFF_SECS << attempt;
+            tracing::warn!(
+                attempt = attempt + 1,
+                delay_secs = delay,
+                error = %error,
+                "Library push failed, retrying"
+            );
+            tokio::time::sleep(std::time::Duration::from_secs(delay)).await;
+            if error.contains("rejected") {
+                let _guard = AUTO_SYNC_LOCK.lock().await;
+                git::pull_rebase(&self.path).await?;
+            }
+            attempt += 1;
+        }
+    }
+
+    /// Hash of the changed files and their sizes and modification times, to
+    /// tell whether the working tree changed since the previous pass.
+    async fn worktree_fingerprint(&self, files: &[String]) -> u64 {
+        use std::hash::{Hash, Hasher};
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        for line in files {
+            line.hash(&mut hasher);
+            let path = porcelain_path(line);
+            if let Ok(metadata) = fs::metadata(self.path.join(path)).await {
+                metadata.len().hash(&mut hasher);
+                metadata.modified().ok().hash(&mut hasher);
+            }
+        }
+        hasher.finish()
+    }
+
+    /// HEAD commit of the library and of each layer.
+    async fn head_commits(&self) -> Vec<Option<String>> {
+        let mut heads = vec![git::head_commit(&self.path).await.ok()];
+        for layer in &self.layers {
+            heads.push(git::head_commit(layer.store.path()).await.ok());
+        }
+        heads
+    }
+
+    /// Reset each library layer to its remote. Layers are read-only mirrors,
+    /// so there is nothing local to keep; failures are logged.
+    async fn sync_layers(&self) {
+        for layer in &self.layers {
+            if let Err(e) = git::force_pull(layer.store.path()).await {
+                tracing::warn!(layer = %layer.name, error = %e, "Failed to sync library layer");
+            }
+        }
+    }
+
+    /// Force sync: reset local branch to match remote, discarding local commits.
     /// Use this after a force push on the remote has caused history to diverge.
+    /// Uncommitted changes are stashed and re-applied like in `sync`; use
+    /// `discard_local_changes` to drop them.
     pub async fn force_sync(&self) -> Result<()> {
-        git::force_pull(&self.path).await?;
+        git::stash_local_changes(&self.path).await?;
+        if let Err(e) = git::force_pull(&self.path).await {
+            if let Err(restore) = git::restore_local_changes(&self.path).await {
+                tracing::warn!(error = %restore, "Failed to restore local library changes");
+            }
+            return Err(e);
+        }
+        git::restore_local_changes(&self.path).await?;
 
         // Encrypt any unversioned encrypted tags in all skills
         self.encrypt_all_skill_files().await?;
 
+        self.sync_layers().await;
+
         Ok(())
     }
 
+    /// Drop uncommitted changes and stashed sync leftovers, and abandon any
+    /// merge in progress. Local commits are kept.
+    pub async fn discard_local_changes(&self) -> Result<()> {
+        git::discard_local_changes(&self.path).await
+    }
+
     /// Force push: overwrite remote with local changes.
     /// Use this when you want to keep local changes and discard remote history.
     pub async fn force_push(&self) -> Result<()> {
+        self.ensure_writable()?;
         git::force_push(&self.path).await
     }
 
     /// Encrypt unversioned <encrypted> tags in all skill files.
     /// This ensures secrets pulled from git are encrypted on disk.
+    /// Read-only libraries are left as pulled.
     pub async fn encrypt_all_skill_files(&self) -> Result<()> {
         let skills_dir = self.skills_dir();
-        if !skills_dir.exists() {
+        if self.read_only || !skills_dir.exists() {
             return Ok(());
         }
 
@@ -186,20 +535,193 @@ impl LibraryStore {
 
     /// Commit all changes with a message and optional author.
     pub async fn commit(&self, message: &str, author: Option<&git::GitAuthor>) -> Result<()> {
+        self.ensure_writable()?;
         git::commit(&self.path, message, author).await
     }
 
     /// Push changes to remote.
     pub async fn push(&self) -> Result<()> {
+        self.ensure_writable()?;
         git::push(&self.path).await
     }
 
+    /// Files with unresolved merge conflicts (empty when no merge is stuck).
+    pub async fn conflicts(&self) -> Result<Vec<String>> {
+        git::conflicted_files(&self.path).await
+    }
+
+    /// Base, local and remote versions of a conflicted file.
+    pub async fn get_conflict(&self, file: &str) -> Result<LibraryConflict> {
+        git::show_conflict(&self.path, file).await
+    }
+
+    /// Resolve a conflicted file. Returns the files still conflicted; once
+    /// none are left, `commit` concludes the merge.
+    pub async fn resolve_conflict(
+        &self,
+        file: &str,
+        resolution: &ConflictResolution,
+    ) -> Result<Vec<String>> {
+        self.ensure_writable()?;
+        git::resolve_conflict(&self.path, file, resolution).await
+    }
+
+    /// Abort an in-progress merge, restoring the library to its pre-sync state.
+    pub async fn abort_merge(&self) -> Result<()> {
+        git::abort_merge(&self.path).await
+    }
+
+    /// Commits that touched `path` (relative to the library root), newest first.
+    pub async fn file_history(&self, path: &str, limit: usize) -> Result<Vec<LibraryCommit>> {
+        git::file_history(&self.path, path, limit).await
+    }
+
+    /// Unified diff of `path` between two revisions; `rev_b = None` compares
+    /// against the working tree.
+    pub async fn diff(&self, path: &str, rev_a: &str, rev_b: Option<&str>) -> Result<String> {
+        git::diff(&self.path, path, rev_a, rev_b).await
+    }
+
+    /// Restore `path` to its content at `rev`. The change is staged but not
+    /// committed, like any other library edit.
+    pub async fn revert(&self, path: &str, rev: &str) -> Result<()> {
+        self.ensure_writable()?;
+        git::revert(&self.path, path, rev).await
+    }
+
+    /// List local and remote branches of the library.
+    pub async fn list_branches(&self) -> Result<Vec<LibraryBranch>> {
+        git::list_branches(&self.path).await
+    }
+
+    /// Switch the library checkout to `branch` (fetching it from the remote
+    /// if needed). Fails if there are uncommitted changes.
+    pub async fn checkout_branch(&self, branch: &str) -> Result<()> {
+        git::checkout_branch(&self.path, branch).await
+    }
+
+    /// Create `branch` from `start_point` (default: the current HEAD).
+    pub async fn create_branch(&self, branch: &str, start_point: Option<&str>) -> Result<()> {
+        self.ensure_writable()?;
+        git::create_branch(&self.path, branch, start_point).await
+    }
+
+    /// The library as of `branch`, for workspaces pinned to a branch.
+    ///
+    /// Returns this store when `branch` is already checked out. Otherwise the
+    /// branch is checked out into its own worktree next to the library, so
+    /// the main checkout (and missions that use it) are unaffected.
+    pub async fn for_branch(&self, branch: &str) -> Result<LibraryStore> {
+        if git::get_branch(&self.path).await? == branch {
+            return Ok(self.with_path(self.path.clone()));
+        }
+
+        // Serialize worktree updates; missions can start concurrently.
+        static WORKTREE_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+        let _guard = WORKTREE_LOCK.lock().await;
+
+        let dest = self.branch_worktree_dir(branch);
+        git::sync_worktree(&self.path, branch, &dest).await?;
+        Ok(self.with_path(dest))
+    }
+
+    /// A store for another checkout of this library, sharing its layers.
+    fn with_path(&self, path: PathBuf) -> Self {
+        Self {
+            path,
+            remote: self.remote.clone(),
+            layer_configs: self.layer_configs.clone(),
+            layers: self
+                .layers
+                .iter()
+                .map(|l| LibraryLayer {
+                    name: l.name.clone(),
+                    store: Arc::clone(&l.store),
+                })
+                .collect(),
+            auto_commit_state: Default::default(),
+            read_only: self.read_only,
+        }
+    }
+
+    /// `<library>-branches/<branch>`, with `/` in branch names flattened.
+    fn branch_worktree_dir(&self, branch: &str) -> PathBuf {
+        let name = self
+            .path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "library".to_string());
+        self.path
+            .with_file_name(format!("{}-branches", name))
+            .join(branch.replace('/', "__"))
+    }
+
     // ─────────────────────────────────────────────────────────────────────────
     // MCP Servers (mcp/servers.json)
     // ─────────────────────────────────────────────────────────────────────────
 
-    /// Get all MCP server definitions.
+    /// Get all MCP server definitions, merged across library layers.
+    ///
+    /// On a name collision the primary library wins, then layers in priority
+    /// order. Use `mcp_server_origins` to see where each server came from.
     pub async fn get_mcp_servers(&self) -> Result<HashMap<String, McpServer>> {
+        let mut servers = self.own_mcp_servers().await?;
+        for (name, (_, server)) in self.layer_mcp_servers().await {
+            servers.entry(name).or_insert(server);
+        }
+        Ok(servers)
+    }
+
+    /// MCP servers as they apply in `environment` (see
+    /// `McpServer::for_environment`), merged across library layers.
+    pub async fn get_mcp_servers_for_environment(
+        &self,
+        environment: Option<&str>,
+    ) -> Result<HashMap<String, McpServer>> {
+        Ok(self
+            .get_mcp_servers()
+            .await?
+            .into_iter()
+            .map(|(name, server)| (name, server.for_environment(environment)))
+            .collect())
+    }
+
+    /// Layer name for each MCP server that comes from a layer. Servers not
+    /// listed are defined in the primary library.
+    pub async fn mcp_server_origins(&self) -> Result<HashMap<String, String>> {
+        let own = self.own_mcp_servers().await?;
+        Ok(self
+            .layer_mcp_servers()
+            .await
+            .into_iter()
+            .filter(|(name, _)| !own.contains_key(name))
+            .map(|(name, (layer, _))| (name, layer))
+            .collect())
+    }
+
+    /// MCP servers defined by the layers, keeping the highest-priority
+    /// definition of each name along with its layer.
+    async fn layer_mcp_servers(&self) -> HashMap<String, (String, McpServer)> {
+        let mut servers = HashMap::new();
+        for layer in &self.layers {
+            match layer.store.own_mcp_servers().await {
+                Ok(layer_servers) => {
+                    for (name, server) in layer_servers {
+                        servers
+                            .entry(name)
+                            .or_insert_with(|| (layer.name.clone(), server));
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!(layer = %layer.name, error = %e, "Failed to read layer MCP servers")
+                }
+            }
+        }
+        servers
+    }
+
+    /// MCP servers defined in this library's own `mcp/servers.json`.
+    async fn own_mcp_servers(&self) -> Result<HashMap<String, McpServer>> {
         let path = self.path.join("mcp/servers.json");
 
         if !path.exists() {
@@ -223,8 +745,25 @@ impl LibraryStore {
         }
     }
 
-    /// Save MCP server definitions.
+    /// Save MCP server definitions to the primary library.
+    ///
+    /// `servers` is usually the merged view from `get_mcp_servers`. Servers
+    /// inherited unchanged from a layer are not copied into the primary
+    /// library; edited ones are saved there and override the layer.
     pub async fn save_mcp_servers(&self, servers: &HashMap<String, McpServer>) -> Result<()> {
+        self.ensure_writable()?;
+        let own = self.own_mcp_servers().await?;
+        let inherited = self.layer_mcp_servers().await;
+        let servers: HashMap<&String, &McpServer> = servers
+            .iter()
+            .filter(|(name, server)| {
+                own.contains_key(*name)
+                    || !inherited.get(*name).is_some_and(|(_, layer_server)| {
+                        serde_json::to_value(layer_server).ok() == serde_json::to_value(server).ok()
+                    })
+            })
+            .collect();
+
         let path = self.path.join("mcp/servers.json");
 
         // Ensure directory exists
@@ -232,7 +771,7 @@ impl LibraryStore {
             fs::create_dir_all(parent).await?;
         }
 
-        let content = serde_json::to_string_pretty(servers)?;
+        let content = serde_json::to_string_pretty(&servers)?;
         fs::write(&path, content)
             .await
             .context("Failed to write mcp/servers.json")?;
@@ -249,8 +788,41 @@ impl LibraryStore {
         self.path.join(SKILL_DIR)
     }
 
-    /// List all skills with their summaries.
+    /// List all skills with their summaries, merged across library layers.
+    ///
+    /// On a name collision the primary library wins, then layers in priority
+    /// order. Skills from a layer have `library` set to the layer name.
     pub async fn list_skills(&self) -> Result<Vec<SkillSummary>> {
+        let mut skills = self.list_own_skills().await?;
+        for layer in &self.layers {
+            let layer_skills = match layer.store.list_own_skills().await {
+                Ok(layer_skills) => layer_skills,
+                Err(e) => {
+                    tracing::warn!(layer = %layer.name, error = %e, "Failed to list layer skills");
+                    continue;
+                }
+            };
+            for mut skill in layer_skills {
+                if skills.iter().any(|s| s.name == skill.name) {
+                    continue;
+                }
+                skill.library = Some(layer.name.clone());
+                skills.push(skill);
+            }
+        }
+        let settings = self.get_skill_settings().await?;
+        for skill in &mut skills {
+            if let Some(settings) = settings.get(&skill.name) {
+                skill.enabled = settings.enabled;
+                skill.workspaces = settings.workspaces.clone();
+            }
+        }
+        skills.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(skills)
+    }
+
+    /// Skills defined in this library's own `skill/` directory.
+    async fn list_own_skills(&self) -> Result<Vec<SkillSummary>> {
         let skills_dir = self.skills_dir();
 
         if !skills_dir.exists() {
@@ -303,6 +875,9 @@ impl LibraryStore {
                 path: format!("{}/{}", SKILL_DIR, entry.file_name().to_string_lossy()),
                 source,
                 setup_commands,
+                library: None,
+                enabled: true,
+                workspaces: Vec::new(),
             });
         }
 
@@ -315,8 +890,32 @@ impl LibraryStore {
     /// Get a skill by name with full content.
     /// Encrypted values in <encrypted v="N">...</encrypted> tags are decrypted
     /// to <encrypted>...</encrypted> format for display/editing.
+    ///
+    /// Falls back to the library layers (in priority order) when the primary
+    /// library has no skill by that name.
     pub async fn get_skill(&self, name: &str) -> Result<Skill> {
         Self::validate_name(name)?;
+        let mut skill = match self.layers.iter().find(|l| l.store.has_own_skill(name)) {
+            Some(layer) if !self.has_own_skill(name) => {
+                let mut skill = layer.store.get_own_skill(name).await?;
+                skill.library = Some(layer.name.clone());
+                skill
+            }
+            _ => self.get_own_skill(name).await?,
+        };
+        if let Some(settings) = self.get_skill_settings().await?.remove(name) {
+            skill.enabled = settings.enabled;
+            skill.workspaces = settings.workspaces;
+        }
+        Ok(skill)
+    }
+
+    fn has_own_skill(&self, name: &str) -> bool {
+        self.skills_dir().join(name).join("SKILL.md").exists()
+    }
+
+    /// Get a skill from this library's own `skill/` directory.
+    async fn get_own_skill(&self, name: &str) -> Result<Skill> {
         let skill_dir = self.skills_dir().join(name);
         let skill_md = skill_dir.join("SKILL.md");
 
@@ -353,8 +952,9 @@ impl LibraryStore {
             SkillSource::default()
         };
 
-        // Extract setup_commands from frontmatter
+        // Extract setup_commands and declared dependencies from frontmatter
         let setup_commands = extract_string_array(&frontmatter, "setup_commands");
+        let requires = extract_requires(&frontmatter);
 
         Ok(Skill {
             name: name.to_string(),
@@ -365,6 +965,10 @@ impl LibraryStore {
             files,
             references,
             setup_commands,
+            requires,
+            library: None,
+            enabled: true,
+            workspaces: Vec::new(),
         })
     }
 
@@ -477,6 +1081,7 @@ impl LibraryStore {
     /// Unversioned <encrypted>value</encrypted> tags are encrypted to
     /// <encrypted v="1">ciphertext</encrypted> format.
     pub async fn save_skill(&self, name: &str, content: &str) -> Result<()> {
+        self.ensure_writable()?;
         Self::validate_name(name)?;
 
         let skill_dir = self.skills_dir().join(name);
@@ -520,8 +1125,69 @@ impl LibraryStore {
         Ok(())
     }
 
+    /// Check `SKILL.md` content against the frontmatter schema. Relative
+    /// links are resolved in the skill's folder.
+    pub fn validate_skill(&self, name: &str, content: &str) -> validate::ValidationReport {
+        validate::validate_skill(name, content, Some(&self.skills_dir().join(name)))
+    }
+
+    /// Per-skill settings from `skills.json` (skills without an entry are
+    /// enabled everywhere).
+    pub async fn get_skill_settings(&self) -> Result<HashMap<String, SkillSettings>> {
+        let path = self.path.join(SKILL_SETTINGS_FILE);
+
+        if !path.exists() {
+            return Ok(HashMap::new());
+        }
+
+        let content = fs::read_to_string(&path)
+            .await
+            .context("Failed to read skills.json")?;
+
+        // Be lenient with parse errors - log warning and return empty
+        match serde_json::from_str::<HashMap<String, SkillSettings>>(&content) {
+            Ok(settings) => Ok(settings),
+            Err(e) => {
+                tracing::warn!("Failed to parse skills.json, returning empty map: {}", e);
+                Ok(HashMap::new())
+            }
+        }
+    }
+
+    /// Set a skill's settings. Default settings remove its entry.
+    pub async fn set_skill_settings(&self, name: &str, settings: SkillSettings) -> Result<()> {
+        self.ensure_writable()?;
+        Self::validate_name(name)?;
+        if !self.has_own_skill(name) && !self.layers.iter().any(|l| l.store.has_own_skill(name)) {
+            anyhow::bail!("Skill not found: {}", name);
+        }
+
+        let mut all = self.get_skill_settings().await?;
+        if settings == SkillSettings::default() {
+            all.remove(name);
+        } else {
+            all.insert(name.to_string(), settings);
+        }
+
+        let path = self.path.join(SKILL_SETTINGS_FILE);
+        if all.is_empty() {
+            if path.exists() {
+                fs::remove_file(&path)
+                    .await
+                    .context("Failed to remove skills.json")?;
+            }
+            return Ok(());
+        }
+        let sorted: std::collections::BTreeMap<_, _> = all.into_iter().collect();
+        fs::write(&path, serde_json::to_string_pretty(&sorted)?)
+            .await
+            .context("Failed to write skills.json")?;
+        Ok(())
+    }
+
     /// Delete a skill and its directory.
     pub async fn delete_skill(&self, name: &str) -> Result<()> {
+        self.ensure_writable()?;
         Self::validate_name(name)?;
 
         let skill_dir = self.skills_dir().join(name);
@@ -629,6 +1295,7 @@ impl LibraryStore {
         ref_path: &str,
         content: &str,
     ) -> Result<()> {
+        self.ensure_writable()?;
         Self::validate_name(skill_name)?;
         let skill_dir = self.skills_dir().join(skill_name);
         let file_path = skill_dir.join(ref_path);
@@ -660,6 +1327,7 @@ impl LibraryStore {
 
     /// Delete a reference file from a skill.
     pub async fn delete_skill_reference(&self, skill_name: &str, ref_path: &str) -> Result<()> {
+        self.ensure_writable()?;
         Self::validate_name(skill_name)?;
         let skill_dir = self.skills_dir().join(skill_name);
         let file_path = skill_dir.join(ref_path);
@@ -699,6 +1367,7 @@ impl LibraryStore {
         skill_path: Option<&str>,
         target_name: &str,
     ) -> Result<Skill> {
+        self.ensure_writable()?;
         Self::validate_name(target_name)?;
 
         // Use new path for imports
@@ -755,20 +1424,339 @@ impl LibraryStore {
             anyhow::bail!("No SKILL.md found at the specified path");
         }
 
-        // Copy the skill directory to target
-        if let Err(e) = Self::copy_dir_recursive(&source_dir, &target_dir).await {
-            let _ = fs::remove_dir_all(&temp_dir).await;
-            return Err(e);
+        // Copy the skill directory to target
+        if let Err(e) = Self::copy_dir_recursive(&source_dir, &target_dir).await {
+            let _ = fs::remove_dir_all(&temp_dir).await;
+            return Err(e);
+        }
+
+        // Clean up temp directory
+        let _ = fs::remove_dir_all(&temp_dir).await;
+
+        // Encrypt any unversioned <encrypted> tags in the imported SKILL.md
+        self.encrypt_skill_file(target_name).await?;
+
+        // Return the imported skill
+        self.get_skill(target_name).await
+    }
+
+    /// Import a skill from a git repository URL or a `.tar.gz`/`.tgz`/`.tar`
+    /// URL, optionally from a subdirectory of it.
+    ///
+    /// Without `target_name`, the skill is named after its frontmatter `name`,
+    /// or else the subdirectory or repository. The frontmatter is validated
+    /// before anything is installed; invalid skills fail with an
+    /// `INVALID_SKILL:` error. Provenance is recorded in `.skill-source.json`.
+    pub async fn import_skill_from_url(
+        &self,
+        url: &str,
+        subdir: Option<&str>,
+        target_name: Option<&str>,
+    ) -> Result<Skill> {
+        self.ensure_writable()?;
+        let url = url.trim();
+        if url.is_empty() {
+            anyhow::bail!("URL is required");
+        }
+        let subdir = subdir
+            .map(|p| p.trim().trim_matches('/'))
+            .filter(|p| !p.is_empty());
+
+        let temp_dir =
+            std::env::temp_dir().join(format!("sandboxed_sh_skill_{}", uuid::Uuid::new_v4()));
+        let result = self
+            .install_skill_from_url(url, subdir, target_name, &temp_dir)
+            .await;
+        let _ = fs::remove_dir_all(&temp_dir).await;
+        let name = result?;
+
+        // Encrypt any unversioned <encrypted> tags in the imported SKILL.md
+        self.encrypt_skill_file(&name).await?;
+
+        self.get_skill(&name).await
+    }
+
+    /// Fetch `url` into `temp_dir`, validate the skill and copy it into the
+    /// skills directory. Returns the installed skill's name.
+    async fn install_skill_from_url(
+        &self,
+        url: &str,
+        subdir: Option<&str>,
+        target_name: Option<&str>,
+        temp_dir: &Path,
+    ) -> Result<String> {
+        let installed_at = Some(chrono::Utc::now().to_rfc3339());
+        let (root, source) = if is_tarball_url(url) {
+            let sha256 = download_tarball(url, temp_dir).await?;
+            let source = SkillSource::Tarball {
+                url: url.to_string(),
+                path: subdir.map(str::to_string),
+                sha256: Some(sha256),
+                installed_at,
+            };
+            // Archives such as GitHub's wrap everything in one top-level folder.
+            (
+                single_subdirectory(temp_dir).unwrap_or_else(|| temp_dir.to_path_buf()),
+                source,
+            )
+        } else {
+            match subdir {
+                Some(path) => git::sparse_clone(temp_dir, url, path).await?,
+                None => git::clone(temp_dir, url).await?,
+            }
+            let source = SkillSource::Git {
+                url: url.to_string(),
+                path: subdir.map(str::to_string),
+                commit: git::head_commit(temp_dir).await.ok(),
+                installed_at,
+            };
+            (temp_dir.to_path_buf(), source)
+        };
+
+        let source_dir = match subdir {
+            Some(path) => {
+                let joined = root.join(path);
+                let canonical_source = joined
+                    .canonicalize()
+                    .map_err(|_| anyhow::anyhow!("Path '{}' not found in {}", path, url))?;
+                if !canonical_source.starts_with(root.canonicalize()?) {
+                    anyhow::bail!("Invalid skill path: path traversal detected");
+                }
+                joined
+            }
+            None => root,
+        };
+
+        let skill_md = source_dir.join("SKILL.md");
+        if !skill_md.is_file() {
+            anyhow::bail!("No SKILL.md found at {}", subdir.unwrap_or("the root"));
+        }
+        let content = fs::read_to_string(&skill_md)
+            .await
+            .context("Failed to read SKILL.md")?;
+
+        let name = match target_name {
+            Some(name) => name.trim().to_string(),
+            None => {
+                let (frontmatter, _) = parse_frontmatter(&content);
+                frontmatter
+                    .as_ref()
+                    .and_then(|fm| fm.get("name"))
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string)
+                    .unwrap_or_else(|| import_name_from_url(url, subdir))
+            }
+        };
+        Self::validate_name(&name)?;
+
+        let report = validate::validate_skill(&name, &content, Some(&source_dir));
+        if !report.valid {
+            anyhow::bail!("INVALID_SKILL: {}", report.summary());
+        }
+
+        let target_dir = self.skills_dir().join(&name);
+        if target_dir.exists() {
+            anyhow::bail!("Skill '{}' already exists", name);
+        }
+        fs::create_dir_all(self.skills_dir()).await?;
+        let installed = async {
+            Self::copy_dir_recursive(&source_dir, &target_dir).await?;
+            let source_json = serde_json::to_string_pretty(&source)?;
+            fs::write(target_dir.join(".skill-source.json"), source_json).await?;
+            anyhow::Ok(())
+        }
+        .await;
+        if let Err(e) = installed {
+            let _ = fs::remove_dir_all(&target_dir).await;
+            return Err(e);
+        }
+
+        Ok(name)
+    }
+
+    /// Package a skill folder (SKILL.md and its references) as a tar.gz.
+    ///
+    /// Files go under `<name>/` next to a `.skill-manifest.json` with the
+    /// skill's provenance and file checksums. Hidden files and symlinks are
+    /// left out, and encrypted values are emptied so no secrets leave the
+    /// server.
+    pub async fn export_skill(&self, name: &str) -> Result<Vec<u8>> {
+        use sha2::{Digest, Sha256};
+
+        Self::validate_name(name)?;
+        let store = if self.has_own_skill(name) {
+            self
+        } else {
+            self.layers
+                .iter()
+                .find(|l| l.store.has_own_skill(name))
+                .map(|l| l.store.as_ref())
+                .ok_or_else(|| anyhow::anyhow!("Skill not found: {}", name))?
+        };
+        let skill_dir = store.skills_dir().join(name);
+
+        let mut paths = Vec::new();
+        collect_skill_files(&skill_dir, &skill_dir, &mut paths)?;
+        paths.sort();
+
+        let mut entries = Vec::new();
+        let mut files = Vec::new();
+        let mut redacted_secrets = 0;
+        let mut description = None;
+        for relative in paths {
+            let mut data = fs::read(skill_dir.join(&relative)).await?;
+            if let Ok(text) = std::str::from_utf8(&data) {
+                if relative == "SKILL.md" {
+                    description = extract_description(&parse_frontmatter(text).0);
+                }
+                // Last use of `text`: `data` is replaced below
+                if env_crypto::has_encrypted_tags(text) {
+                    let (redacted, count) = env_crypto::redact_encrypted_tags(text);
+                    redacted_secrets += count;
+                    data = redacted.into_bytes();
+                }
+            }
+            files.push(SkillExportFile {
+                path: relative.clone(),
+                size: data.len() as u64,
+                sha256: hex::encode(Sha256::digest(&data)),
+            });
+            entries.push((format!("{}/{}", name, relative), data));
+        }
+
+        let source_file = skill_dir.join(".skill-source.json");
+        let source = fs::read_to_string(&source_file)
+            .await
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+
+        let manifest = SkillExportManifest {
+            format_version: 1,
+            name: name.to_string(),
+            description,
+            exported_at: chrono::Utc::now().to_rfc3339(),
+            source,
+            library_commit: git::head_commit(store.path()).await.ok(),
+            files,
+            redacted_secrets,
+        };
+        entries.push((
+            format!("{}/.skill-manifest.json", name),
+            serde_json::to_vec_pretty(&manifest)?,
+        ));
+
+        tokio::task::spawn_blocking(move || crate::tools::create_tarball(&entries)).await?
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────
+    // Search
+    // ─────────────────────────────────────────────────────────────────────────
+
+    /// Full-text search over skills (and their reference files), commands and
+    /// MCP server definitions, across library layers. Returns at most `limit`
+    /// hits, best first.
+    pub async fn search(&self, query: &str, limit: usize) -> Result<Vec<search::SearchHit>> {
+        let terms = search::query_terms(query);
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut hits = self.search_own(&terms).await?;
+        for layer in &self.layers {
+            for mut hit in layer.store.search_own(&terms).await? {
+                hit.library = Some(layer.name.clone());
+                hits.push(hit);
+            }
+        }
+
+        hits.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| a.name.cmp(&b.name))
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        hits.truncate(limit);
+        Ok(hits)
+    }
+
+    async fn search_own(&self, terms: &[String]) -> Result<Vec<search::SearchHit>> {
+        use search::{SearchHit, SearchKind};
+
+        let mut hits = Vec::new();
+        let mut push =
+            |kind, name: &str, path: String, description: Option<&str>, content: &str| {
+                if let Some((score, matches)) =
+                    search::score_document(name, description, content, terms)
+                {
+                    hits.push(SearchHit {
+                        kind,
+                        name: name.to_string(),
+                        path,
+                        library: None,
+                        score,
+                        matches,
+                    });
+                }
+            };
+
+        let skills_dir = self.skills_dir();
+        if skills_dir.exists() {
+            let mut entries = fs::read_dir(&skills_dir).await?;
+            while let Some(entry) = entries.next_entry().await? {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if name.starts_with('.') || !entry.file_type().await?.is_dir() {
+                    continue;
+                }
+                let skill_dir = entry.path();
+                let mut files = Vec::new();
+                collect_skill_files(&skill_dir, &skill_dir, &mut files)?;
+                for relative in files {
+                    let Some(content) = read_searchable(&skill_dir.join(&relative)).await else {
+                        continue;
+                    };
+                    let path = format!("{}/{}/{}", SKILL_DIR, name, relative);
+                    if relative == "SKILL.md" {
+                        let description = extract_description(&parse_frontmatter(&content).0);
+                        push(
+                            SearchKind::Skill,
+                            &name,
+                            path,
+                            description.as_deref(),
+                            &content,
+                        );
+                    } else {
+                        push(SearchKind::SkillReference, &name, path, None, &content);
+                    }
+                }
+            }
+        }
+
+        for command in self.list_commands().await? {
+            let Some(content) = read_searchable(&self.path.join(&command.path)).await else {
+                continue;
+            };
+            push(
+                SearchKind::Command,
+                &command.name,
+                command.path,
+                command.description.as_deref(),
+                &content,
+            );
         }
 
-        // Clean up temp directory
-        let _ = fs::remove_dir_all(&temp_dir).await;
-
-        // Encrypt any unversioned <encrypted> tags in the imported SKILL.md
-        self.encrypt_skill_file(target_name).await?;
+        for (name, server) in self.own_mcp_servers().await? {
+            let content = serde_json::to_string_pretty(&server)?;
+            push(
+                SearchKind::Mcp,
+                &name,
+                "mcp/servers.json".to_string(),
+                None,
+                &content,
+            );
+        }
 
-        // Return the imported skill
-        self.get_skill(target_name).await
+        Ok(hits)
     }
 
     /// Encrypt unversioned <encrypted> tags in a skill's SKILL.md file.
@@ -815,7 +1803,8 @@ impl LibraryStore {
         Ok(())
     }
 
-    /// Recursively copy a directory.
+    /// Recursively copy a directory. Symlinks are skipped so an imported
+    /// repository can't pull in files from outside it.
     #[async_recursion::async_recursion]
     async fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<()> {
         fs::create_dir_all(dst).await?;
@@ -831,8 +1820,11 @@ impl LibraryStore {
                 continue;
             }
 
-            let metadata = fs::metadata(&entry_path).await?;
-            if metadata.is_dir() {
+            let file_type = entry.file_type().await?;
+            if file_type.is_symlink() {
+                continue;
+            }
+            if file_type.is_dir() {
                 Self::copy_dir_recursive(&entry_path, &dst_path).await?;
             } else {
                 fs::copy(&entry_path, &dst_path).await?;
@@ -928,6 +1920,7 @@ impl LibraryStore {
 
     /// Save a command's content.
     pub async fn save_command(&self, name: &str, content: &str) -> Result<()> {
+        self.ensure_writable()?;
         Self::validate_name(name)?;
         let commands_dir = self.commands_dir();
         let command_path = commands_dir.join(format!("{}.md", name));
@@ -942,8 +1935,14 @@ impl LibraryStore {
         Ok(())
     }
 
+    /// Check command content against the frontmatter schema.
+    pub fn validate_command(&self, content: &str) -> validate::ValidationReport {
+        validate::validate_command(content)
+    }
+
     /// Delete a command.
     pub async fn delete_command(&self, name: &str) -> Result<()> {
+        self.ensure_writable()?;
         Self::validate_name(name)?;
 
         let command_path = self.commands_dir().join(format!("{}.md", name));
@@ -985,6 +1984,7 @@ impl LibraryStore {
 
     /// Save all plugins to plugins.json.
     pub async fn save_plugins(&self, plugins: &HashMap<String, Plugin>) -> Result<()> {
+        self.ensure_writable()?;
         let path = self.path.join(PLUGINS_FILE);
 
         let content = serde_json::to_string_pretty(plugins)?;
@@ -1076,6 +2076,7 @@ impl LibraryStore {
 
     /// Save a library agent definition.
     pub async fn save_library_agent(&self, name: &str, agent: &LibraryAgent) -> Result<()> {
+        self.ensure_writable()?;
         Self::validate_name(name)?;
         let agents_dir = self.path.join(AGENT_DIR);
         let agent_path = agents_dir.join(format!("{}.md", name));
@@ -1092,6 +2093,7 @@ impl LibraryStore {
 
     /// Delete a library agent.
     pub async fn delete_library_agent(&self, name: &str) -> Result<()> {
+        self.ensure_writable()?;
         Self::validate_name(name)?;
         let agent_path = self.path.join(AGENT_DIR).join(format!("{}.md", name));
 
@@ -1272,6 +2274,7 @@ impl LibraryStore {
         name: &str,
         template: &WorkspaceTemplate,
     ) -> Result<()> {
+        self.ensure_writable()?;
         Self::validate_name(name)?;
         let templates_dir = self.path.join(WORKSPACE_TEMPLATE_DIR);
         let template_path = templates_dir.join(format!("{}.json", name));
@@ -1326,6 +2329,7 @@ impl LibraryStore {
 
     /// Delete a workspace template.
     pub async fn delete_workspace_template(&self, name: &str) -> Result<()> {
+        self.ensure_writable()?;
         Self::validate_name(name)?;
         let template_path = self
             .path
@@ -1420,6 +2424,7 @@ impl LibraryStore {
 
     /// Save an init script fragment.
     pub async fn save_init_script(&self, name: &str, content: &str) -> Result<()> {
+        self.ensure_writable()?;
         Self::validate_name(name)?;
 
         let script_dir = self.path.join(INIT_SCRIPT_DIR).join(name);
@@ -1437,6 +2442,7 @@ impl LibraryStore {
 
     /// Delete an init script fragment and its directory.
     pub async fn delete_init_script(&self, name: &str) -> Result<()> {
+        self.ensure_writable()?;
         Self::validate_name(name)?;
 
         let script_dir = self.path.join(INIT_SCRIPT_DIR).join(name);
@@ -1544,6 +2550,32 @@ impl LibraryStore {
         Ok(assembled)
     }
 
+    /// Collect declared dependencies from skills by name.
+    /// Returns (skill_name, requirements) pairs for skills that declare any.
+    pub async fn collect_skill_requirements(
+        &self,
+        skill_names: &[String],
+    ) -> Vec<(String, SkillRequirements)> {
+        let mut result = Vec::new();
+        for name in skill_names {
+            match self.get_skill(name).await {
+                Ok(skill) => {
+                    if !skill.requires.is_empty() {
+                        result.push((skill.name, skill.requires));
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        skill = %name,
+                        error = %e,
+                        "Failed to load skill for requirements"
+                    );
+                }
+            }
+        }
+        result
+    }
+
     /// Collect setup commands from skills by name.
     /// Returns a list of (skill_name, setup_commands) pairs.
     pub async fn collect_skill_setup_commands(
@@ -1646,6 +2678,7 @@ impl LibraryStore {
 
     /// Save oh-my-opencode settings to the Library (default profile).
     pub async fn save_opencode_settings(&self, settings: &serde_json::Value) -> Result<()> {
+        self.ensure_writable()?;
         self.save_opencode_settings_for_profile(DEFAULT_PROFILE, settings)
             .await
     }
@@ -1662,6 +2695,7 @@ impl LibraryStore {
 
     /// Save Sandboxed configuration to the Library (default profile).
     pub async fn save_sandboxed_config(&self, config: &SandboxedConfig) -> Result<()> {
+        self.ensure_writable()?;
         self.save_sandboxed_config_for_profile(DEFAULT_PROFILE, config)
             .await
     }
@@ -1679,6 +2713,7 @@ impl LibraryStore {
 
     /// Save Claude Code configuration to the Library (default profile).
     pub async fn save_claudecode_config(&self, config: &ClaudeCodeConfig) -> Result<()> {
+        self.ensure_writable()?;
         self.save_claudecode_config_for_profile(DEFAULT_PROFILE, config)
             .await
     }
@@ -1868,6 +2903,7 @@ impl LibraryStore {
     /// Save a config profile.
     /// Uses new directory structure: .opencode/, .claudecode/, .ampcode/, .sandboxed-sh/
     pub async fn save_config_profile(&self, name: &str, profile: &ConfigProfile) -> Result<()> {
+        self.ensure_writable()?;
         Self::validate_name(name)?;
 
         let profile_dir = self.path.join(CONFIGS_DIR).join(name);
@@ -1912,6 +2948,7 @@ impl LibraryStore {
 
     /// Delete a config profile.
     pub async fn delete_config_profile(&self, name: &str) -> Result<()> {
+        self.ensure_writable()?;
         Self::validate_name(name)?;
 
         // Prevent deleting the default profile
@@ -1938,6 +2975,7 @@ impl LibraryStore {
         name: &str,
         base_profile: Option<&str>,
     ) -> Result<ConfigProfile> {
+        self.ensure_writable()?;
         Self::validate_name(name)?;
 
         let profile_dir = self.path.join(CONFIGS_DIR).join(name);
@@ -2025,6 +3063,7 @@ impl LibraryStore {
         profile: &str,
         settings: &serde_json::Value,
     ) -> Result<()> {
+        self.ensure_writable()?;
         Self::validate_name(profile)?;
 
         let profile_dir = self.path.join(CONFIGS_DIR).join(profile);
@@ -2070,6 +3109,7 @@ impl LibraryStore {
         profile: &str,
         config: &SandboxedConfig,
     ) -> Result<()> {
+        self.ensure_writable()?;
         Self::validate_name(profile)?;
 
         let profile_dir = self.path.join(CONFIGS_DIR).join(profile);
@@ -2118,6 +3158,7 @@ impl LibraryStore {
         profile: &str,
         config: &ClaudeCodeConfig,
     ) -> Result<()> {
+        self.ensure_writable()?;
         Self::validate_name(profile)?;
 
         let profile_dir = self.path.join(CONFIGS_DIR).join(profile);
@@ -2157,6 +3198,7 @@ impl LibraryStore {
         profile: &str,
         config: &AmpCodeConfig,
     ) -> Result<()> {
+        self.ensure_writable()?;
         Self::validate_name(profile)?;
 
         let profile_dir = self.path.join(CONFIGS_DIR).join(profile);
@@ -2195,6 +3237,7 @@ impl LibraryStore {
         file_path: &str,
         content: &str,
     ) -> Result<()> {
+        self.ensure_writable()?;
         Self::validate_name(profile)?;
 
         let profile_dir = self.path.join(CONFIGS_DIR).join(profile);
@@ -2214,6 +3257,7 @@ impl LibraryStore {
 
     /// Delete a specific file from a config profile.
     pub async fn delete_config_profile_file(&self, profile: &str, file_path: &str) -> Result<()> {
+        self.ensure_writable()?;
         Self::validate_name(profile)?;
 
         let profile_dir = self.path.join(CONFIGS_DIR).join(profile);
@@ -2346,6 +3390,7 @@ impl LibraryStore {
         file_name: &str,
         content: &str,
     ) -> Result<()> {
+        self.ensure_writable()?;
         // Validate harness name
         let valid_harnesses = ["opencode", "claudecode", "ampcode", "sandboxed"];
         if !valid_harnesses.contains(&harness) {
@@ -2371,6 +3416,7 @@ impl LibraryStore {
 
     /// Ensure library directory structure exists.
     pub async fn migrate_structure(&self) -> Result<MigrationReport> {
+        self.ensure_writable()?;
         let mut report = MigrationReport::default();
 
         // Ensure directories exist
@@ -2464,11 +3510,194 @@ impl LibraryStore {
 
     #[cfg(test)]
     async fn with_test_store(path: PathBuf) -> LibraryStore {
-        LibraryStore {
-            path,
-            remote: "test-remote".to_string(),
+        LibraryStore::unlayered(path, "test-remote".to_string())
+    }
+}
+
+/// Files under `dir` of a skill, as `/`-separated paths relative to
+/// `base`. Hidden entries and symlinks are skipped.
+fn collect_skill_files(base: &Path, dir: &Path, paths: &mut Vec<String>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect_skill_files(base, &entry.path(), paths)?;
+        } else if file_type.is_file() {
+            let path = entry.path();
+            let relative = path.strip_prefix(base).unwrap_or(&path);
+            let parts: Vec<String> = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .collect();
+            paths.push(parts.join("/"));
+        }
+    }
+    Ok(())
+}
+
+/// Contents of a text file for search; `None` for large or non-UTF-8 files.
+async fn read_searchable(path: &Path) -> Option<String> {
+    let metadata = fs::metadata(path).await.ok()?;
+    if !metadata.is_file() || metadata.len() > MAX_SEARCH_FILE_BYTES {
+        return None;
+    }
+    String::from_utf8(fs::read(path).await.ok()?).ok()
+}
+
+/// Whether a skill import URL points at a tarball rather than a git repository.
+fn is_tarball_url(url: &str) -> bool {
+    let path = url
+        .split(['?', '#'])
+        .next()
+        .unwrap_or(url)
+        .to_ascii_lowercase();
+    path.ends_with(".tar.gz") || path.ends_with(".tgz") || path.ends_with(".tar")
+}
+
+/// Default skill name for an import: the last path segment of the
+/// subdirectory, or else of the URL without archive/git extensions.
+fn import_name_from_url(url: &str, subdir: Option<&str>) -> String {
+    let last = match subdir {
+        Some(path) => path.rsplit('/').next().unwrap_or(path),
+        None => {
+            let path = url.split(['?', '#']).next().unwrap_or(url);
+            let path = path.trim_end_matches('/');
+            path.rsplit(['/', ':']).next().unwrap_or(path)
+        }
+    };
+    let mut name = last.to_ascii_lowercase();
+    for ext in [".tar.gz", ".tgz", ".tar", ".git"] {
+        if let Some(stripped) = name.strip_suffix(ext) {
+            name = stripped.to_string();
+            break;
+        }
+    }
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>()
+        .trim_matches('-')
+        .to_string()
+}
+
+/// The only entry of `dir`, if it is a directory.
+fn single_subdirectory(dir: &Path) -> Option<PathBuf> {
+    let mut entries = std::fs::read_dir(dir).ok()?;
+    let only = entries.next()?.ok()?;
+    if entries.next().is_some() || !only.file_type().ok()?.is_dir() {
+        return None;
+    }
+    Some(only.path())
+}
+
+/// Path of a `git status --porcelain` line (the new path for renames).
+fn porcelain_path(line: &str) -> &str {
+    let path = line.get(3..).unwrap_or_default();
+    path.rsplit_once(" -> ").map_or(path, |(_, to)| to)
+}
+
+/// Commit message for an auto-commit: the touched library items in the
+/// subject, one line per changed file in the body.
+fn auto_commit_message(files: &[String]) -> String {
+    let mut items: Vec<String> = Vec::new();
+    let mut body = Vec::new();
+    for line in files {
+        let path = porcelain_path(line);
+        let item = match path.split('/').collect::<Vec<_>>().as_slice() {
+            [SKILL_DIR, name, _, ..] => format!("{}/{}", SKILL_DIR, name),
+            _ => path.trim_end_matches(".md").to_string(),
+        };
+        if !items.contains(&item) {
+            items.push(item);
+        }
+
+        let code = line.get(..2).unwrap_or_default();
+        let change = if code == "??" || code.contains('A') {
+            "Add"
+        } else if code.contains('D') {
+            "Delete"
+        } else if code.contains('R') {
+            "Rename"
+        } else {
+            "Update"
+        };
+        body.push(format!(
+            "- {} {}",
+            change,
+            line.get(3..).unwrap_or_default()
+        ));
+    }
+
+    let mut subject = format!(
+        "Update {}",
+        items
+            .iter()
+            .take(AUTO_COMMIT_SUBJECT_ITEMS)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    if items.len() > AUTO_COMMIT_SUBJECT_ITEMS {
+        subject.push_str(&format!(
+            " and {} more",
+            items.len() - AUTO_COMMIT_SUBJECT_ITEMS
+        ));
+    }
+    format!("{}\n\n{}\n", subject, body.join("\n"))
+}
+
+/// Download a skill tarball and extract it into `dest`. Returns the SHA-256
+/// of the archive.
+async fn download_tarball(url: &str, dest: &Path) -> Result<String> {
+    use sha2::{Digest, Sha256};
+
+    let mut response = reqwest::Client::new()
+        .get(url)
+        .timeout(std::time::Duration::from_secs(120))
+        .send()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to download {}: {}", url, e.without_url()))?;
+    if !response.status().is_success() {
+        anyhow::bail!("Failed to download {}: HTTP {}", url, response.status());
+    }
+    if response.content_length().unwrap_or(0) > MAX_SKILL_ARCHIVE_BYTES {
+        anyhow::bail!(
+            "Archive is larger than {} MB",
+            MAX_SKILL_ARCHIVE_BYTES / (1024 * 1024)
+        );
+    }
+
+    let mut data = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        data.extend_from_slice(&chunk);
+        if data.len() as u64 > MAX_SKILL_ARCHIVE_BYTES {
+            anyhow::bail!(
+                "Archive is larger than {} MB",
+                MAX_SKILL_ARCHIVE_BYTES / (1024 * 1024)
+            );
         }
     }
+    let sha256 = hex::encode(Sha256::digest(&data));
+
+    let dest = dest.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        crate::tools::extract_tarball(
+            &data,
+            &dest,
+            MAX_SKILL_EXTRACT_BYTES,
+            MAX_SKILL_EXTRACT_FILES,
+        )
+    })
+    .await??;
+    Ok(sha256)
 }
 
 #[cfg(test)]
@@ -2544,6 +3773,778 @@ mod opencode_settings_tests {
 mod tests {
     use super::*;
 
+    fn git(dir: &Path, args: &[&str]) {
+        let status = std::process::Command::new("git")
+            .current_dir(dir)
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    fn write_layer(path: &Path, skills: &[&str], servers: &[&str]) {
+        for skill in skills {
+            let dir = path.join(SKILL_DIR).join(skill);
+            std::fs::create_dir_all(&dir).unwrap();
+            let content = format!("---\ndescription: {} from {}\n---\n", skill, path.display());
+            std::fs::write(dir.join("SKILL.md"), content).unwrap();
+        }
+        let servers: HashMap<&str, McpServer> = servers
+            .iter()
+            .map(|name| {
+                let server = McpServer::Remote {
+                    url: format!("https://{}.example.com", name),
+                    headers: HashMap::new(),
+                    enabled: true,
+                    envs: HashMap::new(),
+                };
+                (*name, server)
+            })
+            .collect();
+        std::fs::create_dir_all(path.join("mcp")).unwrap();
+        std::fs::write(
+            path.join("mcp/servers.json"),
+            serde_json::to_string(&servers).unwrap(),
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_mcp_servers_for_environment() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = LibraryStore::unlayered(tmp.path().to_path_buf(), String::new());
+        std::fs::create_dir_all(tmp.path().join("mcp")).unwrap();
+        std::fs::write(
+            tmp.path().join("mcp/servers.json"),
+            r#"{
+                "api": {
+                    "type": "remote",
+                    "url": "https://staging.example.com/mcp",
+                    "headers": {"X-Team": "core"},
+                    "envs": {"prod": {"url": "https://example.com/mcp", "headers": {"X-Env": "prod"}}}
+                },
+                "debug": {
+                    "type": "local",
+                    "command": ["debug-mcp"],
+                    "env": {"LOG": "debug"},
+                    "envs": {"prod": {"enabled": false, "env": {"LOG": "warn"}}}
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let prod = store
+            .get_mcp_servers_for_environment(Some("prod"))
+            .await
+            .unwrap();
+        let McpServer::Remote {
+            url, headers, envs, ..
+        } = &prod["api"]
+        else {
+            panic!("api should be remote");
+        };
+        assert_eq!(url, "https://example.com/mcp");
+        assert_eq!(headers.len(), 2);
+        assert!(envs.is_empty());
+        let McpServer::Local { env, enabled, .. } = &prod["debug"] else {
+            panic!("debug should be local");
+        };
+        assert_eq!(env["LOG"], "warn");
+        assert!(!enabled);
+
+        // Unknown environments and no environment get the base definition.
+        for environment in [None, Some("dev")] {
+            let servers = store
+                .get_mcp_servers_for_environment(environment)
+                .await
+                .unwrap();
+            let McpServer::Remote { url, .. } = &servers["api"] else {
+                panic!("api should be remote");
+            };
+            assert_eq!(url, "https://staging.example.com/mcp");
+        }
+
+        // The raw definitions keep their overlays.
+        let raw = serde_json::to_value(store.get_mcp_servers().await.unwrap()).unwrap();
+        assert!(raw["api"]["envs"]["prod"].is_object());
+    }
+
+    #[tokio::test]
+    async fn test_layered_skills_and_mcp_servers() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_layer(&tmp.path().join("personal"), &["a"], &["x"]);
+        write_layer(&tmp.path().join("company"), &["a", "b"], &["x", "y"]);
+        write_layer(&tmp.path().join("team"), &["b", "c"], &["y", "z"]);
+
+        let mut store = LibraryStore::unlayered(tmp.path().join("personal"), String::new());
+        for name in ["company", "team"] {
+            store.layers.push(LibraryLayer {
+                name: name.to_string(),
+                store: Arc::new(LibraryStore::unlayered(
+                    tmp.path().join(name),
+                    String::new(),
+                )),
+            });
+        }
+
+        let skills = store.list_skills().await.unwrap();
+        let origins: Vec<(&str, Option<&str>)> = skills
+            .iter()
+            .map(|s| (s.name.as_str(), s.library.as_deref()))
+            .collect();
+        assert_eq!(
+            origins,
+            vec![("a", None), ("b", Some("company")), ("c", Some("team"))]
+        );
+        let b = store.get_skill("b").await.unwrap();
+        assert_eq!(b.library.as_deref(), Some("company"));
+        assert!(b.content.contains("company"));
+        assert!(store.get_skill("a").await.unwrap().library.is_none());
+        assert!(store.get_skill("missing").await.is_err());
+
+        let mut servers = store.get_mcp_servers().await.unwrap();
+        assert_eq!(servers.len(), 3);
+        let server_origins = store.mcp_server_origins().await.unwrap();
+        assert_eq!(server_origins.get("y").map(String::as_str), Some("company"));
+        assert_eq!(server_origins.get("z").map(String::as_str), Some("team"));
+        assert!(!server_origins.contains_key("x"));
+
+        // Saving the merged view only keeps what the primary library owns,
+        // plus layer servers that were edited.
+        servers.insert(
+            "z".to_string(),
+            McpServer::Remote {
+                url: "https://override.example.com".to_string(),
+                headers: HashMap::new(),
+                enabled: false,
+                envs: HashMap::new(),
+            },
+        );
+        store.save_mcp_servers(&servers).await.unwrap();
+        let own = store.own_mcp_servers().await.unwrap();
+        let mut own_names: Vec<&String> = own.keys().collect();
+        own_names.sort();
+        assert_eq!(own_names, vec!["x", "z"]);
+        assert!(!store.mcp_server_origins().await.unwrap().contains_key("z"));
+
+        assert_eq!(
+            LibraryLayerConfig::parse_list(" company = https://a.git ,bad, team=git@b:c.git"),
+            vec![
+                LibraryLayerConfig {
+                    name: "company".to_string(),
+                    remote: "https://a.git".to_string(),
+                },
+                LibraryLayerConfig {
+                    name: "team".to_string(),
+                    remote: "git@b:c.git".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_import_skill_from_git_url() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path().join("skills-repo");
+        std::fs::create_dir_all(repo.join("skills/pdf-tools/references")).unwrap();
+        std::fs::write(
+            repo.join("skills/pdf-tools/SKILL.md"),
+            "---\nname: pdf-tools\ndescription: Work with PDFs\n---\n\nSee [notes](references/notes.md).\n",
+        )
+        .unwrap();
+        std::fs::write(repo.join("skills/pdf-tools/references/notes.md"), "notes").unwrap();
+        std::fs::create_dir_all(repo.join("skills/broken")).unwrap();
+        std::fs::write(repo.join("skills/broken/SKILL.md"), "# No frontmatter\n").unwrap();
+        git(&repo, &["init", "-q", "-b", "main"]);
+        git(&repo, &["add", "-A"]);
+        git(&repo, &["commit", "-q", "-m", "skills"]);
+        let url = format!("file://{}", repo.display());
+
+        let store = LibraryStore::unlayered(tmp.path().join("library"), String::new());
+        let skill = store
+            .import_skill_from_url(&url, Some("skills/pdf-tools/"), None)
+            .await
+            .unwrap();
+        assert_eq!(skill.name, "pdf-tools");
+        assert!(skill.files.iter().any(|f| f.path == "references/notes.md"));
+        match skill.source {
+            SkillSource::Git { path, commit, .. } => {
+                assert_eq!(path.as_deref(), Some("skills/pdf-tools"));
+                assert_eq!(commit.map(|c| c.len()), Some(40));
+            }
+            other => panic!("unexpected source {:?}", other),
+        }
+
+        let err = store
+            .import_skill_from_url(&url, Some("skills/pdf-tools"), None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("already exists"));
+        let err = store
+            .import_skill_from_url(&url, Some("skills/broken"), None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().starts_with("INVALID_SKILL:"));
+        assert!(!store.skills_dir().join("broken").exists());
+        assert!(store
+            .import_skill_from_url(&url, Some("../.."), None)
+            .await
+            .is_err());
+
+        assert!(is_tarball_url("https://example.com/skill.tar.gz?token=1"));
+        assert!(!is_tarball_url("https://github.com/org/skills.git"));
+        assert_eq!(
+            import_name_from_url("git@github.com:org/My_Skill.git", None),
+            "my-skill"
+        );
+        assert_eq!(
+            import_name_from_url("https://example.com/a/b.tgz", None),
+            "b"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_export_skill() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = LibraryStore::unlayered(tmp.path().join("library"), String::new());
+        let skill_dir = store.skills_dir().join("demo");
+        std::fs::create_dir_all(skill_dir.join("references")).unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\ndescription: Demo\n---\nToken: <encrypted v=\"1\">ciphertext</encrypted>\n",
+        )
+        .unwrap();
+        std::fs::write(skill_dir.join("references/guide.md"), "guide").unwrap();
+        std::fs::write(skill_dir.join(".skill-source.json"), "{\"type\":\"Local\"}").unwrap();
+
+        let archive = store.export_skill("demo").await.unwrap();
+        assert!(store.export_skill("missing").await.is_err());
+
+        let out = tmp.path().join("out");
+        crate::tools::extract_tarball(&archive, &out, 1024 * 1024, 100).unwrap();
+        let skill_md = std::fs::read_to_string(out.join("demo/SKILL.md")).unwrap();
+        assert!(skill_md.contains("Token: <encrypted></encrypted>"));
+        assert!(!skill_md.contains("ciphertext"));
+        assert!(out.join("demo/references/guide.md").exists());
+        assert!(!out.join("demo/.skill-source.json").exists());
+
+        let manifest: SkillExportManifest = serde_json::from_str(
+            &std::fs::read_to_string(out.join("demo/.skill-manifest.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(manifest.name, "demo");
+        assert_eq!(manifest.description.as_deref(), Some("Demo"));
+        assert_eq!(manifest.redacted_secrets, 1);
+        let paths: Vec<&str> = manifest.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["SKILL.md", "references/guide.md"]);
+        assert_eq!(manifest.files[0].size, skill_md.len() as u64);
+    }
+
+    #[tokio::test]
+    async fn test_search() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = LibraryStore::unlayered(tmp.path().join("library"), String::new());
+        let skill_dir = store.skills_dir().join("deploy");
+        std::fs::create_dir_all(skill_dir.join("references")).unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\ndescription: Deploy to staging\n---\nUse the staging deploy token.\n",
+        )
+        .unwrap();
+        std::fs::write(
+            skill_dir.join("references/tokens.md"),
+            "The staging deploy token lives in the vault.\n",
+        )
+        .unwrap();
+        std::fs::write(skill_dir.join("references/logo.png"), [0xff, 0xfe, 0x00]).unwrap();
+        std::fs::create_dir_all(store.commands_dir()).unwrap();
+        std::fs::write(
+            store.commands_dir().join("release.md"),
+            "---\ndescription: Release\n---\nRotate the staging token first.\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(store.path().join("mcp")).unwrap();
+        std::fs::write(
+            store.path().join("mcp/servers.json"),
+            r#"{"vault": {"type": "local", "command": ["vault-mcp"], "env": {"VAULT_ADDR": "https://staging.example"}}}"#,
+        )
+        .unwrap();
+
+        let hits = store.search("Staging TOKEN", 10).await.unwrap();
+        let found: Vec<(search::SearchKind, &str)> =
+            hits.iter().map(|h| (h.kind, h.path.as_str())).collect();
+        assert_eq!(found.len(), 3, "{:?}", found);
+        assert_eq!(
+            found[0],
+            (search::SearchKind::Skill, "skill/deploy/SKILL.md")
+        );
+        assert!(found.contains(&(
+            search::SearchKind::SkillReference,
+            "skill/deploy/references/tokens.md"
+        )));
+        assert!(found.contains(&(search::SearchKind::Command, "command/release.md")));
+        assert_eq!(hits[0].matches[0].line, 2);
+
+        let hits = store.search("vault_addr", 10).await.unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].kind, search::SearchKind::Mcp);
+        assert_eq!(hits[0].name, "vault");
+
+        assert_eq!(store.search("staging", 1).await.unwrap().len(), 1);
+        assert!(store.search("   ", 10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_collect_skill_requirements() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = LibraryStore::unlayered(tmp.path().join("library"), String::new());
+        for (name, content) in [
+            (
+                "deploy",
+                "---\ndescription: Deploy\nrequires:\n  mcp: [github]\n  env: [GITHUB_TOKEN]\n---\n",
+            ),
+            ("notes", "---\ndescription: Notes\n---\n"),
+        ] {
+            let dir = store.skills_dir().join(name);
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join("SKILL.md"), content).unwrap();
+        }
+
+        let names = vec!["deploy".to_string(), "notes".to_string()];
+        let requirements = store.collect_skill_requirements(&names).await;
+        assert_eq!(requirements.len(), 1);
+        let (skill, requires) = &requirements[0];
+        assert_eq!(skill, "deploy");
+        assert_eq!(requires.mcp, vec!["github"]);
+        assert!(requires.tools.is_empty());
+        assert_eq!(requires.env, vec!["GITHUB_TOKEN"]);
+    }
+
+    #[tokio::test]
+    async fn test_skill_settings() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = LibraryStore::unlayered(tmp.path().join("library"), String::new());
+        let skill_dir = store.skills_dir().join("deploy");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\ndescription: Deploy\n---\n",
+        )
+        .unwrap();
+
+        assert!(store
+            .set_skill_settings("missing", SkillSettings::default())
+            .await
+            .is_err());
+
+        let settings = SkillSettings {
+            enabled: true,
+            workspaces: vec!["dev".to_string()],
+        };
+        store.set_skill_settings("deploy", settings).await.unwrap();
+        let saved = store.get_skill_settings().await.unwrap();
+        assert!(saved["deploy"].allows("id-1", "dev"));
+        assert!(!saved["deploy"].allows("id-2", "prod"));
+        assert_eq!(
+            store.list_skills().await.unwrap()[0].workspaces,
+            vec!["dev"]
+        );
+
+        let disabled = SkillSettings {
+            enabled: false,
+            workspaces: Vec::new(),
+        };
+        store.set_skill_settings("deploy", disabled).await.unwrap();
+        assert!(!store.list_skills().await.unwrap()[0].enabled);
+
+        // Back to the defaults: nothing left to store.
+        store
+            .set_skill_settings("deploy", SkillSettings::default())
+            .await
+            .unwrap();
+        assert!(!store.path().join(SKILL_SETTINGS_FILE).exists());
+    }
+
+    #[tokio::test]
+    async fn test_read_only_library() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = LibraryStore::unlayered(tmp.path().join("library"), "origin-url".to_string())
+            .with_read_only(true);
+        let skill_dir = store.skills_dir().join("deploy");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\ndescription: Deploy\n---\n",
+        )
+        .unwrap();
+
+        let err = store
+            .save_skill("deploy", "---\ndescription: Changed\n---\n")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().starts_with("READ_ONLY:"));
+        assert!(err.to_string().contains("origin-url"));
+        assert!(store.delete_skill("deploy").await.is_err());
+        assert!(store.commit("message", None).await.is_err());
+        assert!(store.rename_skill("deploy", "ship", false).await.is_err());
+
+        // Reads still work.
+        assert_eq!(store.list_skills().await.unwrap().len(), 1);
+        assert!(store.is_read_only());
+    }
+
+    #[tokio::test]
+    async fn test_branches_and_pinned_worktree() {
+        let tmp = tempfile::tempdir().unwrap();
+        let origin = tmp.path().join("origin.git");
+        let path = tmp.path().join("library");
+        std::fs::create_dir_all(&origin).unwrap();
+        git(&origin, &["init", "--bare", "-q", "-b", "main"]);
+        git(
+            tmp.path(),
+            &["clone", "-q", origin.to_str().unwrap(), "library"],
+        );
+        git(&path, &["checkout", "-q", "-b", "main"]);
+        std::fs::write(path.join("README.md"), "library\n").unwrap();
+        git(&path, &["add", "-A"]);
+        git(&path, &["commit", "-q", "-m", "init"]);
+        git(&path, &["push", "-q", "origin", "main"]);
+
+        let store = LibraryStore::unlayered(path.clone(), origin.to_string_lossy().to_string());
+        assert!(store.create_branch("--force", None).await.is_err());
+        store.create_branch("experiment", None).await.unwrap();
+        assert!(store.create_branch("experiment", None).await.is_err());
+
+        let branches = store.list_branches().await.unwrap();
+        let experiment = branches.iter().find(|b| b.name == "experiment").unwrap();
+        assert!(experiment.local && !experiment.remote && !experiment.current);
+        assert!(branches
+            .iter()
+            .any(|b| b.name == "main" && b.current && b.remote));
+
+        // Commit a skill edit on the experimental branch, then go back to main.
+        store.checkout_branch("experiment").await.unwrap();
+        std::fs::write(path.join("EXPERIMENT.md"), "wip\n").unwrap();
+        assert!(store.checkout_branch("main").await.is_err());
+        git(&path, &["add", "-A"]);
+        git(&path, &["commit", "-q", "-m", "experiment"]);
+        store.checkout_branch("main").await.unwrap();
+        assert!(!path.join("EXPERIMENT.md").exists());
+
+        let pinned = store.for_branch("experiment").await.unwrap();
+        assert_ne!(pinned.path(), store.path());
+        assert!(pinned.path().join("EXPERIMENT.md").exists());
+        // Refreshing an existing worktree works too.
+        let again = store.for_branch("experiment").await.unwrap();
+        assert_eq!(again.path(), pinned.path());
+
+        assert_eq!(store.for_branch("main").await.unwrap().path(), store.path());
+        assert!(store.for_branch("missing").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_auto_sync_keeps_local_edits() {
+        let tmp = tempfile::tempdir().unwrap();
+        let origin = tmp.path().join("origin.git");
+        std::fs::create_dir_all(&origin).unwrap();
+        git(&origin, &["init", "--bare", "-q", "-b", "main"]);
+        for clone in ["library", "upstream"] {
+            git(
+                tmp.path(),
+                &["clone", "-q", origin.to_str().unwrap(), clone],
+            );
+            let path = tmp.path().join(clone);
+            git(&path, &["checkout", "-q", "-B", "main"]);
+            git(&path, &["config", "user.name", "test"]);
+            git(&path, &["config", "user.email", "test@example.com"]);
+        }
+        let path = tmp.path().join("library");
+        let upstream = tmp.path().join("upstream");
+        std::fs::write(upstream.join("README.md"), "v1\n").unwrap();
+        git(&upstream, &["add", "-A"]);
+        git(&upstream, &["commit", "-q", "-m", "init"]);
+        git(&upstream, &["push", "-q", "origin", "main"]);
+        git(&path, &["pull", "-q", "origin", "main"]);
+        git(&path, &["branch", "-q", "--set-upstream-to=origin/main"]);
+
+        let store = LibraryStore::unlayered(path.clone(), origin.to_string_lossy().to_string());
+        assert!(!store.auto_sync().await.unwrap());
+
+        // A local edit survives a sync that pulls a remote change.
+        std::fs::write(path.join("NOTES.md"), "local\n").unwrap();
+        std::fs::write(upstream.join("README.md"), "v2\n").unwrap();
+        git(&upstream, &["commit", "-q", "-am", "update"]);
+        git(&upstream, &["push", "-q", "origin", "main"]);
+        assert!(store.auto_sync().await.unwrap());
+        assert_eq!(
+            std::fs::read_to_string(path.join("README.md")).unwrap(),
+            "v2\n"
+        );
+        assert!(path.join("NOTES.md").exists());
+
+        // A conflicting local commit is left in place.
+        std::fs::write(path.join("README.md"), "local\n").unwrap();
+        git(&path, &["commit", "-q", "-am", "local"]);
+        std::fs::write(upstream.join("README.md"), "v3\n").unwrap();
+        git(&upstream, &["commit", "-q", "-am", "conflict"]);
+        git(&upstream, &["push", "-q", "origin", "main"]);
+        let err = store.auto_sync().await.unwrap_err().to_string();
+        assert!(err.starts_with("REBASE_CONFLICT:"), "{}", err);
+        assert_eq!(
+            std::fs::read_to_string(path.join("README.md")).unwrap(),
+            "local\n"
+        );
+        assert!(!path.join(".git/rebase-merge").exists());
+    }
+
+    #[test]
+    fn test_auto_commit_message() {
+        let files = [
+            " M skill/deploy/SKILL.md",
+            "?? skill/deploy/references/api.md",
+            " D command/old.md",
+            "R  command/a.md -> command/b.md",
+            "?? mcp/servers.json",
+        ]
+        .map(str::to_string);
+        let message = auto_commit_message(&files);
+        let (subject, body) = message.split_once("\n\n").unwrap();
+        assert_eq!(
+            subject,
+            "Update skill/deploy, command/old, command/b and 1 more"
+        );
+        assert!(body.contains("- Add skill/deploy/references/api.md\n"));
+        assert!(body.contains("- Delete command/old.md\n"));
+        assert!(body.contains("- Rename command/a.md -> command/b.md\n"));
+    }
+
+    #[tokio::test]
+    async fn test_auto_commit_waits_for_edits_to_settle() {
+        let tmp = tempfile::tempdir().unwrap();
+        let origin = tmp.path().join("origin.git");
+        std::fs::create_dir_all(&origin).unwrap();
+        git(&origin, &["init", "--bare", "-q", "-b", "main"]);
+        git(
+            tmp.path(),
+            &["clone", "-q", origin.to_str().unwrap(), "library"],
+        );
+        let path = tmp.path().join("library");
+        git(&path, &["checkout", "-q", "-B", "main"]);
+        git(&path, &["config", "user.name", "test"]);
+        git(&path, &["config", "user.email", "test@example.com"]);
+        std::fs::write(path.join("README.md"), "v1\n").unwrap();
+        git(&path, &["add", "-A"]);
+        git(&path, &["commit", "-q", "-m", "init"]);
+        git(&path, &["push", "-q", "-u", "origin", "main"]);
+
+        let store = LibraryStore::unlayered(path.clone(), origin.to_string_lossy().to_string());
+        assert!(!store.auto_commit(60).await.unwrap());
+
+        // The first pass only notices the edit; the next one commits it.
+        std::fs::create_dir_all(path.join("command")).unwrap();
+        std::fs::write(path.join("command/release.md"), "Release\n").unwrap();
+        assert!(!store.auto_commit(60).await.unwrap());
+        assert_eq!(
+            store
+                .status()
+                .await
+                .unwrap()
+                .auto_commit
+                .unwrap()
+                .pending_files,
+            1
+        );
+        assert!(store.auto_commit(60).await.unwrap());
+
+        let status = store.status().await.unwrap();
+        let auto_commit = status.auto_commit.unwrap();
+        assert!(status.clean);
+        assert_eq!(status.ahead, 0);
+        assert!(!auto_commit.unpushed);
+        assert!(auto_commit.last_error.is_none());
+        assert_eq!(
+            auto_commit.last_commit,
+            git::head_commit(&origin).await.ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sync_stashes_local_changes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let origin = tmp.path().join("origin.git");
+        std::fs::create_dir_all(&origin).unwrap();
+        git(&origin, &["init", "--bare", "-q", "-b", "main"]);
+        for clone in ["library", "upstream"] {
+            git(
+                tmp.path(),
+                &["clone", "-q", origin.to_str().unwrap(), clone],
+            );
+            git(&tmp.path().join(clone), &["checkout", "-q", "-B", "main"]);
+        }
+        let path = tmp.path().join("library");
+        let upstream = tmp.path().join("upstream");
+        std::fs::write(upstream.join("README.md"), "v1\n").unwrap();
+        std::fs::write(upstream.join("NOTES.md"), "v1\n").unwrap();
+        git(&upstream, &["add", "-A"]);
+        git(&upstream, &["commit", "-q", "-m", "init"]);
+        git(&upstream, &["push", "-q", "origin", "main"]);
+        git(&path, &["pull", "-q", "origin", "main"]);
+        git(&path, &["branch", "-q", "--set-upstream-to=origin/main"]);
+        let store = LibraryStore::unlayered(path.clone(), origin.to_string_lossy().to_string());
+
+        // Pulled changes land even when local edits conflict with them.
+        std::fs::write(path.join("NOTES.md"), "local\n").unwrap();
+        std::fs::write(path.join("DRAFT.md"), "draft\n").unwrap();
+        let status = store.status().await.unwrap();
+        assert!(!status.clean && status.warning.is_some());
+        std::fs::write(upstream.join("README.md"), "v2\n").unwrap();
+        std::fs::write(upstream.join("NOTES.md"), "remote\n").unwrap();
+        git(&upstream, &["add", "-A"]);
+        git(&upstream, &["commit", "-q", "-m", "update"]);
+        git(&upstream, &["push", "-q", "origin", "main"]);
+        let err = store.sync().await.unwrap_err().to_string();
+        assert!(err.starts_with("STASH_CONFLICT:"), "{}", err);
+        assert_eq!(
+            std::fs::read_to_string(path.join("README.md")).unwrap(),
+            "v2\n"
+        );
+        assert!(path.join("DRAFT.md").exists());
+        assert_eq!(store.conflicts().await.unwrap(), vec!["NOTES.md"]);
+
+        store.discard_local_changes().await.unwrap();
+        let status = store.status().await.unwrap();
+        assert!(status.clean && status.warning.is_none());
+        assert_eq!(status.stashed_changes, 0);
+        assert!(!path.join("DRAFT.md").exists());
+        assert_eq!(
+            std::fs::read_to_string(path.join("NOTES.md")).unwrap(),
+            "remote\n"
+        );
+
+        // Edits to files the remote didn't touch survive a sync.
+        std::fs::write(path.join("NOTES.md"), "local\n").unwrap();
+        std::fs::write(upstream.join("README.md"), "v3\n").unwrap();
+        git(&upstream, &["commit", "-q", "-am", "again"]);
+        git(&upstream, &["push", "-q", "origin", "main"]);
+        store.sync().await.unwrap();
+        assert_eq!(
+            std::fs::read_to_string(path.join("README.md")).unwrap(),
+            "v3\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(path.join("NOTES.md")).unwrap(),
+            "local\n"
+        );
+        assert_eq!(store.status().await.unwrap().stashed_changes, 0);
+    }
+
+    #[tokio::test]
+    async fn test_file_history_diff_and_revert() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().to_path_buf();
+        git(&path, &["init", "-q", "-b", "main"]);
+        std::fs::create_dir_all(path.join("skill/demo")).unwrap();
+        for (content, message) in [("v1\n", "first"), ("v2\n", "second")] {
+            std::fs::write(path.join("skill/demo/SKILL.md"), content).unwrap();
+            git(&path, &["add", "-A"]);
+            git(&path, &["commit", "-q", "-m", message]);
+        }
+
+        let store = LibraryStore::unlayered(path.clone(), String::new());
+        let history = store.file_history("skill/demo/SKILL.md", 10).await.unwrap();
+        let messages: Vec<&str> = history.iter().map(|c| c.message.as_str()).collect();
+        assert_eq!(messages, vec!["second", "first"]);
+        assert_eq!(history[0].author, "test");
+
+        let diff = store
+            .diff("skill/demo/SKILL.md", &history[1].hash, Some("HEAD"))
+            .await
+            .unwrap();
+        assert!(diff.contains("-v1") && diff.contains("+v2"));
+        assert!(store.diff("../outside", "HEAD", None).await.is_err());
+        assert!(store
+            .diff("skill/demo/SKILL.md", "--output=/tmp/x", None)
+            .await
+            .is_err());
+
+        store
+            .revert("skill/demo/SKILL.md", &history[1].short_hash)
+            .await
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(path.join("skill/demo/SKILL.md")).unwrap(),
+            "v1\n"
+        );
+        assert!(store.revert("skill/missing.md", "HEAD").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_sync_conflicts_can_be_resolved() {
+        let tmp = tempfile::tempdir().unwrap();
+        let origin = tmp.path().join("origin.git");
+        std::fs::create_dir_all(&origin).unwrap();
+        git(&origin, &["init", "--bare", "-q", "-b", "main"]);
+        let clone = |name: &str| {
+            git(tmp.path(), &["clone", "-q", origin.to_str().unwrap(), name]);
+            let dir = tmp.path().join(name);
+            git(&dir, &["config", "user.name", "test"]);
+            git(&dir, &["config", "user.email", "test@example.com"]);
+            dir
+        };
+
+        let path = clone("library");
+        git(&path, &["checkout", "-q", "-b", "main"]);
+        std::fs::write(path.join("SKILL.md"), "base\n").unwrap();
+        git(&path, &["add", "-A"]);
+        git(&path, &["commit", "-q", "-m", "base"]);
+        git(&path, &["push", "-q", "-u", "origin", "main"]);
+
+        let other = clone("other");
+        std::fs::write(other.join("SKILL.md"), "theirs\n").unwrap();
+        git(&other, &["commit", "-q", "-am", "theirs"]);
+        git(&other, &["push", "-q"]);
+
+        std::fs::write(path.join("SKILL.md"), "ours\n").unwrap();
+        git(&path, &["commit", "-q", "-am", "ours"]);
+
+        let store = LibraryStore::unlayered(path.clone(), origin.to_string_lossy().to_string());
+        let err = store.sync().await.unwrap_err().to_string();
+        assert!(err.starts_with("MERGE_CONFLICT:"), "{}", err);
+        assert_eq!(store.conflicts().await.unwrap(), vec!["SKILL.md"]);
+        assert_eq!(
+            store.status().await.unwrap().state,
+            LibraryState::Conflicted {
+                files: vec!["SKILL.md".to_string()]
+            }
+        );
+        assert!(store.commit("merge", None).await.is_err());
+
+        let conflict = store.get_conflict("SKILL.md").await.unwrap();
+        assert_eq!(conflict.base.as_deref(), Some("base\n"));
+        assert_eq!(conflict.ours.as_deref(), Some("ours\n"));
+        assert_eq!(conflict.theirs.as_deref(), Some("theirs\n"));
+        assert!(conflict.working.unwrap().contains("<<<<<<<"));
+        assert!(store.get_conflict("../etc/passwd").await.is_err());
+
+        let remaining = store
+            .resolve_conflict(
+                "SKILL.md",
+                &ConflictResolution::Manual {
+                    content: "merged\n".to_string(),
+                },
+            )
+            .await
+            .unwrap();
+        assert!(remaining.is_empty());
+        store.commit("Merge remote changes", None).await.unwrap();
+        assert_eq!(store.status().await.unwrap().state, LibraryState::Ok);
+        assert_eq!(
+            std::fs::read_to_string(path.join("SKILL.md")).unwrap(),
+            "merged\n"
+        );
+    }
+
     #[test]
     fn test_parse_frontmatter() {
         let content = r#"---
@@ -3,13 +3,13 @@
 //! This module handles renaming library items (skills, commands, agents, tools,
 //! workspace templates) while automatically updating all cross-references.
 
-use anyhow::{Context, Result};
+use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 use tokio::fs;
 
 use super::types::SandboxedConfig;
-use super::LibraryStore;
+use super::{git, LibraryStore, SKILL_SETTINGS_FILE};
 
 // ─────────────────────────────────────────────────────────────────────────────
 // Types
@@ -90,7 +90,16 @@ impl LibraryStore {
             ItemType::Skill => {
                 // Skills are referenced by:
                 // 1. workspace-template/*.json -> skills array
+                // 2. skills.json -> settings keyed by skill name
                 refs.extend(self.find_skill_refs_in_templates(name).await?);
+                if self.get_skill_settings().await?.contains_key(name) {
+                    refs.push(RenameChange::UpdateReference {
+                        file: SKILL_SETTINGS_FILE.to_string(),
+                        field: "settings".to_string(),
+                        old_value: name.to_string(),
+                        new_value: String::new(),
+                    });
+                }
             }
             ItemType::Agent => {
                 // Agents are referenced by:
@@ -177,6 +186,29 @@ impl LibraryStore {
         Ok(refs)
     }
 
+    /// Rename a skill folder. The move goes through `git mv` so the skill's
+    /// history follows it, and a frontmatter `name` is updated to match.
+    pub async fn rename_skill(
+        &self,
+        old_name: &str,
+        new_name: &str,
+        dry_run: bool,
+    ) -> Result<RenameResult> {
+        self.rename_item(ItemType::Skill, old_name, new_name, dry_run)
+            .await
+    }
+
+    /// Rename a command file, keeping its git history.
+    pub async fn rename_command(
+        &self,
+        old_name: &str,
+        new_name: &str,
+        dry_run: bool,
+    ) -> Result<RenameResult> {
+        self.rename_item(ItemType::Command, old_name, new_name, dry_run)
+            .await
+    }
+
     /// Rename an item and update all references.
     pub async fn rename_item(
         &self,
@@ -185,6 +217,9 @@ impl LibraryStore {
         new_name: &str,
         dry_run: bool,
     ) -> Result<RenameResult> {
+        if !dry_run {
+            self.ensure_writable()?;
+        }
         // Validate names
         Self::validate_name(old_name)?;
         Self::validate_name(new_name)?;
@@ -239,11 +274,31 @@ impl LibraryStore {
             .to_string_lossy()
             .to_string();
 
+        // Skills and commands may repeat their name in frontmatter.
+        let markdown = match item_type {
+            ItemType::Skill => Some((old_path.join("SKILL.md"), format!("{}/SKILL.md", new_rel))),
+            ItemType::Command => Some((old_path.clone(), new_rel.clone())),
+            _ => None,
+        };
+
         changes.push(RenameChange::RenameFile {
             from: old_rel,
             to: new_rel,
         });
 
+        if let Some((old_md, new_md)) = markdown {
+            if let Ok(content) = fs::read_to_string(&old_md).await {
+                if replace_frontmatter_name(&content, old_name, new_name).is_some() {
+                    changes.push(RenameChange::UpdateReference {
+                        file: new_md,
+                        field: "name".to_string(),
+                        old_value: old_name.to_string(),
+                        new_value: new_name.to_string(),
+                    });
+                }
+            }
+        }
+
         // Find and add reference updates
         let refs = self.find_references(item_type, old_name).await?;
         for mut ref_change in refs {
@@ -361,10 +416,14 @@ impl LibraryStore {
             }
         }
 
-        // Perform the rename
-        fs::rename(old_path, new_path)
-            .await
-            .context("Failed to rename file/directory")?;
+        // Perform the rename, staged in git so history follows the item
+        let rel = |path: &Path| {
+            path.strip_prefix(&self.path)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .to_string()
+        };
+        git::move_path(&self.path, &rel(old_path), &rel(new_path)).await?;
 
         Ok(())
     }
@@ -384,7 +443,14 @@ impl LibraryStore {
             let content = fs::read_to_string(&file_path).await?;
             let mut data: serde_json::Value = serde_json::from_str(&content)?;
 
-            if field == "skills" || field == "hidden_agents" {
+            if field == "settings" {
+                // Object keyed by item name (skills.json)
+                if let Some(obj) = data.as_object_mut() {
+                    if let Some(value) = obj.remove(old_value) {
+                        obj.insert(new_value.to_string(), value);
+                    }
+                }
+            } else if field == "skills" || field == "hidden_agents" {
                 // Array field
                 if let Some(arr) = data.get_mut(field).and_then(|a| a.as_array_mut()) {
                     for item in arr.iter_mut() {
@@ -402,6 +468,12 @@ impl LibraryStore {
 
             let updated = serde_json::to_string_pretty(&data)?;
             fs::write(&file_path, updated).await?;
+        } else if file.ends_with(".md") && field == "name" {
+            // The item's own frontmatter name (skill or command)
+            let content = fs::read_to_string(&file_path).await?;
+            if let Some(updated) = replace_frontmatter_name(&content, old_value, new_value) {
+                fs::write(&file_path, updated).await?;
+            }
         } else if file.ends_with(".md") {
             // Markdown file with YAML frontmatter (agent)
             let content = fs::read_to_string(&file_path).await?;
@@ -447,3 +519,105 @@ impl LibraryStore {
         Ok(content.to_string())
     }
 }
+
+/// Point a frontmatter `name: <old>` line at `new`. Only that line changes,
+/// so the rename stays a one-line diff. Returns `None` when the frontmatter
+/// has no such line.
+fn replace_frontmatter_name(content: &str, old: &str, new: &str) -> Option<String> {
+    let mut lines = content.split_inclusive('\n');
+    let first = lines.next()?;
+    if first.trim_end() != "---" {
+        return None;
+    }
+
+    let mut out = String::with_capacity(content.len());
+    out.push_str(first);
+    let mut replaced = false;
+    let mut in_frontmatter = true;
+    for line in lines {
+        if in_frontmatter && line.trim_end() == "---" {
+            in_frontmatter = false;
+        } else if in_frontmatter && !replaced {
+            if let Some(value) = line.strip_prefix("name:") {
+                let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+                if value == old {
+                    let ending = &line[line.trim_end().len()..];
+                    out.push_str(&format!("name: {}{}", new, ending));
+                    replaced = true;
+                    continue;
+                }
+            }
+        }
+        out.push_str(line);
+    }
+    replaced.then_some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn git_in(dir: &Path, args: &[&str]) {
+        let status = std::process::Command::new("git")
+            .current_dir(dir)
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    #[test]
+    fn test_replace_frontmatter_name() {
+        let content = "---\nname: \"deploy\"\ndescription: Deploy\n---\nname: deploy\n";
+        assert_eq!(
+            replace_frontmatter_name(content, "deploy", "ship").unwrap(),
+            "---\nname: ship\ndescription: Deploy\n---\nname: deploy\n"
+        );
+        assert!(replace_frontmatter_name(content, "other", "ship").is_none());
+        assert!(replace_frontmatter_name("name: deploy\n", "deploy", "ship").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_rename_skill_keeps_history() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = LibraryStore::unlayered(tmp.path().to_path_buf(), String::new());
+        let dir = store.skills_dir().join("deploy");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("SKILL.md"),
+            "---\nname: deploy\ndescription: Deploy\n---\nShip it.\n",
+        )
+        .unwrap();
+        std::fs::write(
+            tmp.path().join(SKILL_SETTINGS_FILE),
+            r#"{"deploy": {"enabled": false}}"#,
+        )
+        .unwrap();
+        git_in(tmp.path(), &["init", "-q"]);
+        git_in(tmp.path(), &["add", "-A"]);
+        git_in(tmp.path(), &["commit", "-qm", "Add deploy"]);
+
+        let preview = store.rename_skill("deploy", "ship", true).await.unwrap();
+        assert_eq!(preview.changes.len(), 3);
+        assert!(preview.changes.iter().any(|change| matches!(
+            change,
+            RenameChange::UpdateReference { field, new_value, .. }
+                if field == "name" && new_value == "ship"
+        )));
+        assert!(dir.exists());
+
+        let result = store.rename_skill("deploy", "ship", false).await.unwrap();
+        assert!(result.success, "{:?}", result.error);
+        let content = std::fs::read_to_string(store.skills_dir().join("ship/SKILL.md")).unwrap();
+        assert!(content.starts_with("---\nname: ship\n"));
+        assert!(!store.get_skill_settings().await.unwrap()["ship"].enabled);
+
+        git_in(tmp.path(), &["add", "-A"]);
+        git_in(tmp.path(), &["commit", "-qm", "Rename deploy"]);
+        let history = git::file_history(tmp.path(), "skill/ship/SKILL.md", 10)
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 2);
+    }
+}
@@ -0,0 +1,173 @@
+//! Full-text search over library content.
+//!
+//! Documents (skill files, commands, MCP definitions) match when they contain
+//! every query term, case-insensitively. Hits are ranked by where the terms
+//! appear (item name, then frontmatter description, then body) and by how
+//! often, and carry a few matching lines as snippets.
+
+use serde::{Deserialize, Serialize};
+
+use super::env_crypto;
+
+/// Matching lines returned per hit.
+const MAX_SNIPPETS: usize = 3;
+/// Longest snippet, in characters.
+const SNIPPET_CHARS: usize = 160;
+
+/// What kind of library item a hit is in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchKind {
+    Skill,
+    SkillReference,
+    Command,
+    Mcp,
+}
+
+/// A matching line.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchMatch {
+    /// 1-based line number in the file (in the server's JSON definition for
+    /// MCP hits)
+    pub line: usize,
+    pub text: String,
+}
+
+/// A document that matched the query.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub kind: SearchKind,
+    /// Skill, command or MCP server name
+    pub name: String,
+    /// Path relative to the library root
+    pub path: String,
+    /// Library layer the item comes from (`None` for the primary library)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub library: Option<String>,
+    pub score: u32,
+    pub matches: Vec<SearchMatch>,
+}
+
+/// Lowercased, de-duplicated query terms.
+pub fn query_terms(query: &str) -> Vec<String> {
+    let mut terms: Vec<String> = Vec::new();
+    for term in query.split_whitespace().map(str::to_lowercase) {
+        if !terms.contains(&term) {
+            terms.push(term);
+        }
+    }
+    terms
+}
+
+/// Score `content` against `terms`. Returns `None` unless every term occurs
+/// in the content or the item name. Encrypted values are never searched or
+/// shown.
+pub fn score_document(
+    name: &str,
+    description: Option<&str>,
+    content: &str,
+    terms: &[String],
+) -> Option<(u32, Vec<SearchMatch>)> {
+    if terms.is_empty() {
+        return None;
+    }
+    let (content, _) = env_crypto::redact_encrypted_tags(content);
+    let lower = content.to_lowercase();
+    let name = name.to_lowercase();
+    let description = description.map(str::to_lowercase).unwrap_or_default();
+
+    let mut score = 0u32;
+    for term in terms {
+        let occurrences = lower.matches(term.as_str()).count() as u32;
+        let in_name = name.contains(term.as_str());
+        if occurrences == 0 && !in_name {
+            return None;
+        }
+        if in_name {
+            score += 20;
+        }
+        if description.contains(term.as_str()) {
+            score += 5;
+        }
+        score += occurrences.min(10);
+    }
+
+    let matches = content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| {
+            let line = line.to_lowercase();
+            terms.iter().any(|term| line.contains(term.as_str()))
+        })
+        .take(MAX_SNIPPETS)
+        .map(|(i, line)| SearchMatch {
+            line: i + 1,
+            text: snippet(line, terms),
+        })
+        .collect();
+
+    Some((score, matches))
+}
+
+/// Trim `line` to about `SNIPPET_CHARS` characters around the first term.
+fn snippet(line: &str, terms: &[String]) -> String {
+    let line = line.trim();
+    let chars: Vec<char> = line.chars().collect();
+    if chars.len() <= SNIPPET_CHARS {
+        return line.to_string();
+    }
+    let lower = line.to_lowercase();
+    let first = terms
+        .iter()
+        .filter_map(|term| lower.find(term.as_str()))
+        .min()
+        .map(|byte| lower[..byte].chars().count())
+        .unwrap_or(0);
+    let start = first.saturating_sub(SNIPPET_CHARS / 3);
+    let end = (start + SNIPPET_CHARS).min(chars.len());
+    let start = end.saturating_sub(SNIPPET_CHARS);
+
+    let mut text: String = chars[start..end].iter().collect();
+    if start > 0 {
+        text.insert_str(0, "...");
+    }
+    if end < chars.len() {
+        text.push_str("...");
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score_document() {
+        let terms = query_terms("Staging  token staging");
+        assert_eq!(terms, vec!["staging", "token"]);
+
+        let content = "---\ndescription: Deploys to staging\n---\nUse the staging deploy token.\nToken: <encrypted v=\"1\">c2VjcmV0</encrypted>\n";
+        let (score, matches) =
+            score_document("deploy", Some("Deploys to staging"), content, &terms).unwrap();
+        assert!(score > 0);
+        assert_eq!(matches.len(), 3);
+        assert_eq!(matches[1].line, 4);
+        assert!(matches.iter().all(|m| !m.text.contains("c2VjcmV0")));
+
+        // Every term must match.
+        assert!(score_document("deploy", None, content, &query_terms("staging prod")).is_none());
+        // Name matches rank above body-only matches.
+        let (named, _) = score_document("staging", None, "token", &terms).unwrap();
+        let (body, _) = score_document("other", None, "staging token", &terms).unwrap();
+        assert!(named > body);
+    }
+
+    #[test]
+    fn test_snippet_is_trimmed_around_match() {
+        let line = format!("{} needle {}", "a".repeat(300), "b".repeat(300));
+        let text = snippet(&line, &["needle".to_string()]);
+        assert!(text.contains("needle"));
+        assert!(text.starts_with("...") && text.ends_with("..."));
+        assert!(text.chars().count() <= SNIPPET_CHARS + 6);
+    }
+}
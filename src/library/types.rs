@@ -15,6 +15,9 @@ fn default_true() -> bool {
 
 /// MCP server definition from mcp/servers.json.
 /// Aligned with OpenCode format: "local" (stdio) and "remote" (http).
+///
+/// `envs` holds per-environment overlays (`{"prod": {"url": "..."}}`),
+/// applied by `for_environment`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum McpServer {
@@ -26,6 +29,8 @@ pub enum McpServer {
         env: HashMap<String, String>,
         #[serde(default = "default_true")]
         enabled: bool,
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        envs: HashMap<String, McpServerOverlay>,
     },
     /// Remote MCP server (HTTP-based)
     Remote {
@@ -34,9 +39,79 @@ pub enum McpServer {
         headers: HashMap<String, String>,
         #[serde(default = "default_true")]
         enabled: bool,
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        envs: HashMap<String, McpServerOverlay>,
     },
 }
 
+impl McpServer {
+    /// The definition for `environment`: its overlay applied on top of the
+    /// base fields, with the overlays themselves dropped. Environments
+    /// without an overlay (and `None`) get the base definition.
+    pub fn for_environment(&self, environment: Option<&str>) -> McpServer {
+        let overlay = |envs: &HashMap<String, McpServerOverlay>| {
+            environment
+                .and_then(|name| envs.get(name))
+                .cloned()
+                .unwrap_or_default()
+        };
+        match self {
+            McpServer::Local {
+                command,
+                env,
+                enabled,
+                envs,
+            } => {
+                let overlay = overlay(envs);
+                let mut env = env.clone();
+                env.extend(overlay.env);
+                McpServer::Local {
+                    command: overlay.command.unwrap_or_else(|| command.clone()),
+                    env,
+                    enabled: overlay.enabled.unwrap_or(*enabled),
+                    envs: HashMap::new(),
+                }
+            }
+            McpServer::Remote {
+                url,
+                headers,
+                enabled,
+                envs,
+            } => {
+                let overlay = overlay(envs);
+                let mut headers = headers.clone();
+                headers.extend(overlay.headers);
+                McpServer::Remote {
+                    url: overlay.url.unwrap_or_else(|| url.clone()),
+                    headers,
+                    enabled: overlay.enabled.unwrap_or(*enabled),
+                    envs: HashMap::new(),
+                }
+            }
+        }
+    }
+}
+
+/// Per-environment changes to an MCP server. `env` and `headers` are merged
+/// into the base definition; the other fields replace it when set.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct McpServerOverlay {
+    /// Command array (local servers)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<Vec<String>>,
+    /// Extra environment variables (local servers)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub env: HashMap<String, String>,
+    /// Server URL (remote servers)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Extra HTTP headers (remote servers)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Plugin Types
 // ─────────────────────────────────────────────────────────────────────────────
@@ -230,7 +305,7 @@ pub struct SkillFile {
     pub content: String,
 }
 
-/// Source/provenance of a skill - local or from skills.sh registry.
+/// Source/provenance of a skill - local, from skills.sh registry, or imported from a URL.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "type")]
 pub enum SkillSource {
@@ -253,6 +328,34 @@ pub enum SkillSource {
         #[serde(skip_serializing_if = "Option::is_none")]
         updated_at: Option<String>,
     },
+    /// Skill imported from a git repository URL
+    Git {
+        /// Repository URL
+        url: String,
+        /// Subdirectory of the repository holding the skill
+        #[serde(skip_serializing_if = "Option::is_none")]
+        path: Option<String>,
+        /// Commit the skill was imported from
+        #[serde(skip_serializing_if = "Option::is_none")]
+        commit: Option<String>,
+        /// When the skill was imported
+        #[serde(skip_serializing_if = "Option::is_none")]
+        installed_at: Option<String>,
+    },
+    /// Skill imported from a tarball URL
+    Tarball {
+        /// Archive URL
+        url: String,
+        /// Subdirectory of the archive holding the skill
+        #[serde(skip_serializing_if = "Option::is_none")]
+        path: Option<String>,
+        /// SHA-256 of the downloaded archive
+        #[serde(skip_serializing_if = "Option::is_none")]
+        sha256: Option<String>,
+        /// When the skill was imported
+        #[serde(skip_serializing_if = "Option::is_none")]
+        installed_at: Option<String>,
+    },
 }
 
 impl Default for SkillSource {
@@ -277,6 +380,15 @@ pub struct SkillSummary {
     /// Shell commands to run during workspace setup (e.g., install dependencies)
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub setup_commands: Vec<String>,
+    /// Library layer the skill comes from (`None` for the primary library)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub library: Option<String>,
+    /// Whether missions get this skill (from `skills.json`)
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Workspaces (names or IDs) limited to; empty means all
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub workspaces: Vec<String>,
 }
 
 /// Full skill with content.
@@ -303,12 +415,130 @@ pub struct Skill {
     /// Shell commands to run during workspace setup (e.g., install dependencies)
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub setup_commands: Vec<String>,
+    /// Dependencies declared in the `requires` frontmatter
+    #[serde(default, skip_serializing_if = "SkillRequirements::is_empty")]
+    pub requires: SkillRequirements,
+    /// Library layer the skill comes from (`None` for the primary library)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub library: Option<String>,
+    /// Whether missions get this skill (from `skills.json`)
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Workspaces (names or IDs) limited to; empty means all
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub workspaces: Vec<String>,
+}
+
+/// What a skill needs from the mission workspace, from SKILL.md frontmatter:
+/// `requires: { mcp: [...], tools: [...], env: [...] }`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkillRequirements {
+    /// MCP servers that must be enabled in the workspace
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mcp: Vec<String>,
+    /// Tools that must pass the workspace tool allowlist
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<String>,
+    /// Environment variables that must be set
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env: Vec<String>,
+}
+
+impl SkillRequirements {
+    pub fn is_empty(&self) -> bool {
+        self.mcp.is_empty() && self.tools.is_empty() && self.env.is_empty()
+    }
+}
+
+/// Per-skill settings, stored in the library's `skills.json` keyed by skill
+/// name. They apply to skills from library layers too.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkillSettings {
+    /// Disabled skills stay in the library but are not given to missions
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Workspace names or IDs that may use the skill; empty means all
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub workspaces: Vec<String>,
+}
+
+impl Default for SkillSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            workspaces: Vec::new(),
+        }
+    }
+}
+
+impl SkillSettings {
+    /// Whether a workspace may use the skill.
+    pub fn allows(&self, workspace_id: &str, workspace_name: &str) -> bool {
+        self.enabled
+            && (self.workspaces.is_empty()
+                || self
+                    .workspaces
+                    .iter()
+                    .any(|w| w == workspace_id || w == workspace_name))
+    }
+}
+
+/// Manifest stored as `<name>/.skill-manifest.json` in a skill export.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillExportManifest {
+    /// Export format version
+    pub format_version: u32,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// When the export was made (RFC 3339)
+    pub exported_at: String,
+    /// Provenance of the skill in the exporting library
+    pub source: SkillSource,
+    /// Library commit the skill was exported from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub library_commit: Option<String>,
+    /// Exported files, relative to the skill folder
+    pub files: Vec<SkillExportFile>,
+    /// Number of encrypted values emptied from the exported files
+    #[serde(default)]
+    pub redacted_secrets: usize,
+}
+
+/// A file in a skill export.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillExportFile {
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
 // Command Types
 // ─────────────────────────────────────────────────────────────────────────────
 
+/// Value type of a typed command argument.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParamType {
+    String,
+    Integer,
+    Number,
+    Boolean,
+}
+
+impl ParamType {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "string" => Some(Self::String),
+            "integer" => Some(Self::Integer),
+            "number" => Some(Self::Number),
+            "boolean" => Some(Self::Boolean),
+            _ => None,
+        }
+    }
+}
+
 /// A single command parameter definition.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CommandParam {
@@ -320,6 +550,12 @@ pub struct CommandParam {
     /// Description of the parameter
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    /// Value type, for typed `args` (untyped values are strings)
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub param_type: Option<ParamType>,
+    /// Value used when the argument is not given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
 }
 
 /// Command summary for listing.
@@ -376,6 +612,143 @@ pub struct LibraryStatus {
     pub behind: u32,
     /// List of modified/untracked files
     pub modified_files: Vec<String>,
+    /// Whether a sync left the library mid-merge
+    #[serde(default)]
+    pub state: LibraryState,
+    /// Stashes of local changes a sync could not re-apply
+    #[serde(default)]
+    pub stashed_changes: usize,
+    /// Set when local changes are uncommitted or still stashed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
+    /// Background auto-commit state (`None` when it is not running)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_commit: Option<AutoCommitStatus>,
+    /// True when the library can only change through its remote
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+/// State of the library auto-commit daemon.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AutoCommitStatus {
+    /// Seconds the working tree must stay unchanged before it is committed
+    pub window_secs: u64,
+    /// Changed files waiting to be committed
+    pub pending_files: usize,
+    /// When the pending changes were first seen (RFC 3339)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pending_since: Option<String>,
+    /// Last auto-commit (hash)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_commit: Option<String>,
+    /// When the last auto-commit was made (RFC 3339)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_commit_at: Option<String>,
+    /// When auto-commits were last pushed (RFC 3339)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_push_at: Option<String>,
+    /// Whether auto-commits are waiting to be pushed
+    #[serde(default)]
+    pub unpushed: bool,
+    /// Error from the last pass, if it failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+/// Merge state of the library working tree.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LibraryState {
+    /// No merge in progress
+    #[default]
+    Ok,
+    /// A sync merge stopped on conflicts in these files; resolve them through
+    /// the conflicts API, then commit (or abort the merge)
+    Conflicted { files: Vec<String> },
+}
+
+/// Both sides of a conflicted library file. A side is `None` when the file
+/// does not exist there (e.g. deleted on one side).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LibraryConflict {
+    pub path: String,
+    /// Common ancestor version
+    pub base: Option<String>,
+    /// Local version
+    pub ours: Option<String>,
+    /// Remote version
+    pub theirs: Option<String>,
+    /// Working tree content, with conflict markers
+    pub working: Option<String>,
+}
+
+/// How to resolve a conflicted library file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "resolution", rename_all = "snake_case")]
+pub enum ConflictResolution {
+    /// Keep the local version
+    Ours,
+    /// Take the remote version
+    Theirs,
+    /// Use hand-merged content
+    Manual { content: String },
+}
+
+/// A library commit that touched a file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LibraryCommit {
+    pub hash: String,
+    pub short_hash: String,
+    pub author: String,
+    /// Author date (RFC 3339)
+    pub date: String,
+    /// Commit subject line
+    pub message: String,
+}
+
+/// A branch of the library repository.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LibraryBranch {
+    pub name: String,
+    /// True if this is the branch the library is checked out on
+    pub current: bool,
+    /// True if the branch exists locally
+    pub local: bool,
+    /// True if the branch exists on origin
+    pub remote: bool,
+}
+
+/// An extra read-only library layered under the primary one.
+///
+/// Layers are listed in priority order: when several define an item with
+/// the same name, the primary library wins, then the first layer listed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LibraryLayerConfig {
+    /// Short name shown as the item's origin (e.g., "company")
+    pub name: String,
+    /// Git remote URL
+    pub remote: String,
+}
+
+impl LibraryLayerConfig {
+    /// Parse a comma-separated `name=remote` list (the `LIBRARY_LAYERS` format).
+    pub fn parse_list(value: &str) -> Vec<Self> {
+        value
+            .split(',')
+            .filter_map(|entry| {
+                let (name, remote) = entry.split_once('=')?;
+                let (name, remote) = (name.trim(), remote.trim());
+                if name.is_empty() || remote.is_empty() {
+                    return None;
+                }
+                Some(Self {
+                    name: name.to_string(),
+                    remote: remote.to_string(),
+                })
+            })
+            .collect()
+    }
 }
 
 /// Migration report showing what changed during library structure migration.
@@ -732,22 +1105,35 @@ pub fn extract_model(frontmatter: &Option<serde_yaml::Value>) -> Option<String>
 }
 
 /// Extract tools map from YAML frontmatter.
+///
+/// Accepts the OpenCode mapping form (`{read: true, bash: false}`) as well as
+/// a list or comma-separated string of enabled tools (Claude Code style).
 pub fn extract_tools(frontmatter: &Option<serde_yaml::Value>) -> HashMap<String, bool> {
-    frontmatter
-        .as_ref()
-        .and_then(|fm| fm.get("tools"))
-        .and_then(|v| v.as_mapping())
-        .map(|mapping| {
-            mapping
-                .iter()
-                .filter_map(|(k, v)| {
-                    let key = k.as_str()?.to_string();
-                    let value = v.as_bool()?;
-                    Some((key, value))
-                })
-                .collect()
-        })
-        .unwrap_or_default()
+    let Some(tools) = frontmatter.as_ref().and_then(|fm| fm.get("tools")) else {
+        return HashMap::new();
+    };
+    match tools {
+        serde_yaml::Value::Mapping(mapping) => mapping
+            .iter()
+            .filter_map(|(k, v)| {
+                let key = k.as_str()?.to_string();
+                let value = v.as_bool()?;
+                Some((key, value))
+            })
+            .collect(),
+        serde_yaml::Value::Sequence(items) => items
+            .iter()
+            .filter_map(|item| item.as_str())
+            .map(|tool| (tool.trim().to_string(), true))
+            .collect(),
+        serde_yaml::Value::String(list) => list
+            .split(',')
+            .map(str::trim)
+            .filter(|tool| !tool.is_empty())
+            .map(|tool| (tool.to_string(), true))
+            .collect(),
+        _ => HashMap::new(),
+    }
 }
 
 /// Extract permissions map from YAML frontmatter.
@@ -783,16 +1169,33 @@ pub fn extract_string_array(frontmatter: &Option<serde_yaml::Value>, field: &str
         .unwrap_or_default()
 }
 
-/// Extract command params from YAML frontmatter.
+/// Extract the `requires` mapping from skill frontmatter.
+pub fn extract_requires(frontmatter: &Option<serde_yaml::Value>) -> SkillRequirements {
+    let requires = frontmatter
+        .as_ref()
+        .and_then(|fm| fm.get("requires"))
+        .cloned();
+    SkillRequirements {
+        mcp: extract_string_array(&requires, "mcp"),
+        tools: extract_string_array(&requires, "tools"),
+        env: extract_string_array(&requires, "env"),
+    }
+}
+
+/// Extract command params from YAML frontmatter, from both `params` and the
+/// typed `args` list.
 /// Supports two formats:
 /// 1. Simple list: `params: [repo-path, pr-number]`
 /// 2. Detailed objects: `params: [{name: repo-path, required: true, description: "..."}]`
+///
+/// `args` entries may also set `type` (string, integer, number, boolean) and
+/// `default`; an argument with a default is optional unless `required` says
+/// otherwise.
 pub fn extract_params(frontmatter: &Option<serde_yaml::Value>) -> Vec<CommandParam> {
-    frontmatter
-        .as_ref()
-        .and_then(|fm| fm.get("params"))
-        .and_then(|v| v.as_sequence())
-        .map(|seq| {
+    ["params", "args"]
+        .into_iter()
+        .filter_map(|key| frontmatter.as_ref()?.get(key)?.as_sequence())
+        .flat_map(|seq| {
             seq.iter()
                 .filter_map(|item| {
                     // Format 1: Simple string
@@ -801,6 +1204,8 @@ pub fn extract_params(frontmatter: &Option<serde_yaml::Value>) -> Vec<CommandPar
                             name: name.to_string(),
                             required: true, // Default to required for simple format
                             description: None,
+                            param_type: None,
+                            default: None,
                         });
                     }
 
@@ -811,26 +1216,45 @@ pub fn extract_params(frontmatter: &Option<serde_yaml::Value>) -> Vec<CommandPar
                             .and_then(|v| v.as_str())
                             .map(|s| s.to_string())?;
 
+                        let default = mapping.get("default").and_then(yaml_scalar_string);
+
                         let required = mapping
                             .get(serde_yaml::Value::String("required".to_string()))
                             .and_then(|v| v.as_bool())
-                            .unwrap_or(true);
+                            .unwrap_or(default.is_none());
 
                         let description = mapping
                             .get(serde_yaml::Value::String("description".to_string()))
                             .and_then(|v| v.as_str())
                             .map(|s| s.to_string());
 
+                        let param_type = mapping
+                            .get("type")
+                            .and_then(|v| v.as_str())
+                            .and_then(ParamType::parse);
+
                         return Some(CommandParam {
                             name,
                             required,
                             description,
+                            param_type,
+                            default,
                         });
                     }
 
                     None
                 })
-                .collect()
+                .collect::<Vec<_>>()
         })
-        .unwrap_or_default()
+        .collect()
+}
+
+/// A YAML string, number or boolean as text.
+pub(crate) fn yaml_scalar_string(value: &serde_yaml::Value) -> Option<String> {
+    match value {
+        serde_yaml::Value::String(s) => Some(s.clone()),
+        serde_yaml::Value::Number(n) => Some(n.to_string()),
+        serde_yaml::Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
 }
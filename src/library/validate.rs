@@ -0,0 +1,423 @@
+//! Schema checks for skill (`SKILL.md`) and command markdown.
+//!
+//! Validates the YAML frontmatter (required `description`, allowed keys,
+//! length limits, field types) and, for skills, that relative links in the
+//! body point at files that exist in the skill folder.
+
+use std::path::{Component, Path};
+
+use serde::{Deserialize, Serialize};
+
+use super::command_template::coerce_value;
+use super::types::{parse_frontmatter, yaml_scalar_string, ParamType};
+
+/// Longest accepted `description`.
+pub const MAX_DESCRIPTION_LEN: usize = 1024;
+/// Longest accepted skill `name`.
+pub const MAX_NAME_LEN: usize = 64;
+
+const SKILL_KEYS: &[&str] = &[
+    "name",
+    "description",
+    "license",
+    "allowed-tools",
+    "metadata",
+    "compatibility",
+    "version",
+    "setup_commands",
+    "requires",
+];
+
+/// Keys of a skill's `requires` mapping.
+const REQUIRES_KEYS: &[&str] = &["mcp", "tools", "env"];
+
+const COMMAND_KEYS: &[&str] = &[
+    "description",
+    "agent",
+    "model",
+    "subtask",
+    "params",
+    "args",
+    "argument-hint",
+    "allowed-tools",
+    "disable-model-invocation",
+];
+
+/// What kind of library item is being validated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemKind {
+    Skill,
+    Command,
+}
+
+/// A single validation problem.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationIssue {
+    /// Frontmatter key or link target the issue is about
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    pub message: String,
+}
+
+/// Result of validating one skill or command.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ValidationReport {
+    pub valid: bool,
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    fn from_issues(issues: Vec<ValidationIssue>) -> Self {
+        Self {
+            valid: issues.is_empty(),
+            issues,
+        }
+    }
+
+    /// One-line summary of the issues, for error messages.
+    pub fn summary(&self) -> String {
+        self.issues
+            .iter()
+            .map(|issue| match &issue.field {
+                Some(field) => format!("{}: {}", field, issue.message),
+                None => issue.message.clone(),
+            })
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+fn issue(field: Option<&str>, message: impl Into<String>) -> ValidationIssue {
+    ValidationIssue {
+        field: field.map(str::to_string),
+        message: message.into(),
+    }
+}
+
+/// `requires` must map `mcp`, `tools` and `env` to lists of strings.
+fn check_requires(value: &serde_yaml::Value, issues: &mut Vec<ValidationIssue>) {
+    let Some(requires) = value.as_mapping() else {
+        issues.push(issue(
+            Some("requires"),
+            "must be a mapping of mcp, tools and env",
+        ));
+        return;
+    };
+    for (key, items) in requires {
+        let key = key.as_str().unwrap_or_default();
+        if !REQUIRES_KEYS.contains(&key) {
+            issues.push(issue(
+                Some("requires"),
+                format!("unknown key '{}' (expected mcp, tools or env)", key),
+            ));
+            continue;
+        }
+        let all_strings = items
+            .as_sequence()
+            .map(|items| items.iter().all(|item| item.is_string()))
+            .unwrap_or(false);
+        if !all_strings {
+            issues.push(issue(
+                Some(&format!("requires.{}", key)),
+                "must be a list of strings",
+            ));
+        }
+    }
+}
+
+/// Validate a skill's `SKILL.md`. When `skill_dir` is given, relative links
+/// in the body must point at files inside it.
+pub fn validate_skill(name: &str, content: &str, skill_dir: Option<&Path>) -> ValidationReport {
+    let mut issues = Vec::new();
+    let Some(frontmatter) = frontmatter_mapping(content, &mut issues) else {
+        return ValidationReport::from_issues(issues);
+    };
+
+    check_keys(&frontmatter, SKILL_KEYS, &mut issues);
+    check_description(&frontmatter, &mut issues);
+
+    if let Some(value) = frontmatter.get("name") {
+        match value.as_str() {
+            Some(fm_name) if fm_name != name => issues.push(issue(
+                Some("name"),
+                format!("must match the skill folder name '{}'", name),
+            )),
+            Some(fm_name) if fm_name.len() > MAX_NAME_LEN => issues.push(issue(
+                Some("name"),
+                format!("must be at most {} characters", MAX_NAME_LEN),
+            )),
+            Some(_) => {}
+            None => issues.push(issue(Some("name"), "must be a string")),
+        }
+    }
+
+    if let Some(value) = frontmatter.get("setup_commands") {
+        let all_strings = value
+            .as_sequence()
+            .map(|items| items.iter().all(|item| item.is_string()))
+            .unwrap_or(false);
+        if !all_strings {
+            issues.push(issue(Some("setup_commands"), "must be a list of strings"));
+        }
+    }
+
+    if let Some(value) = frontmatter.get("requires") {
+        check_requires(value, &mut issues);
+    }
+
+    if let Some(dir) = skill_dir {
+        let (_, body) = parse_frontmatter(content);
+        for target in relative_links(body) {
+            if !link_target_exists(dir, &target) {
+                issues.push(issue(Some(&target), "broken link: file not found in skill"));
+            }
+        }
+    }
+
+    ValidationReport::from_issues(issues)
+}
+
+/// Validate a command's markdown.
+pub fn validate_command(content: &str) -> ValidationReport {
+    let mut issues = Vec::new();
+    let Some(frontmatter) = frontmatter_mapping(content, &mut issues) else {
+        return ValidationReport::from_issues(issues);
+    };
+
+    check_keys(&frontmatter, COMMAND_KEYS, &mut issues);
+    check_description(&frontmatter, &mut issues);
+
+    if let Some(value) = frontmatter.get("params") {
+        let valid = value
+            .as_sequence()
+            .map(|params| {
+                params
+                    .iter()
+                    .all(|param| param.get("name").and_then(|n| n.as_str()).is_some())
+            })
+            .unwrap_or(false);
+        if !valid {
+            issues.push(issue(
+                Some("params"),
+                "must be a list of mappings with a 'name'",
+            ));
+        }
+    }
+    if let Some(value) = frontmatter.get("args") {
+        check_args(value, &mut issues);
+    }
+    if let Some(value) = frontmatter.get("subtask") {
+        if !value.is_bool() {
+            issues.push(issue(Some("subtask"), "must be true or false"));
+        }
+    }
+
+    ValidationReport::from_issues(issues)
+}
+
+/// `args` must list names or mappings with a `name`, a known `type` and a
+/// `default` of that type.
+fn check_args(value: &serde_yaml::Value, issues: &mut Vec<ValidationIssue>) {
+    let Some(args) = value.as_sequence() else {
+        issues.push(issue(Some("args"), "must be a list"));
+        return;
+    };
+    for arg in args {
+        if arg.is_string() {
+            continue;
+        }
+        let Some(name) = arg.get("name").and_then(|n| n.as_str()) else {
+            issues.push(issue(
+                Some("args"),
+                "entries must be names or mappings with a 'name'",
+            ));
+            continue;
+        };
+        let field = format!("args.{}", name);
+        let param_type = match arg.get("type") {
+            None => None,
+            Some(t) => match t.as_str().and_then(ParamType::parse) {
+                Some(param_type) => Some(param_type),
+                None => {
+                    issues.push(issue(
+                        Some(&field),
+                        "type must be string, integer, number or boolean",
+                    ));
+                    continue;
+                }
+            },
+        };
+        if let Some(default) = arg.get("default") {
+            let valid = yaml_scalar_string(default).is_some_and(|default| {
+                coerce_value(param_type, &serde_json::Value::String(default)).is_ok()
+            });
+            if !valid {
+                issues.push(issue(Some(&field), "default does not match the type"));
+            }
+        }
+    }
+}
+
+fn frontmatter_mapping(
+    content: &str,
+    issues: &mut Vec<ValidationIssue>,
+) -> Option<serde_yaml::Mapping> {
+    if !content.trim_start().starts_with("---") {
+        issues.push(issue(None, "missing YAML frontmatter (--- ... ---)"));
+        return None;
+    }
+    match parse_frontmatter(content.trim_start()).0 {
+        Some(serde_yaml::Value::Mapping(mapping)) => Some(mapping),
+        Some(_) => {
+            issues.push(issue(None, "frontmatter must be a mapping of keys"));
+            None
+        }
+        None => {
+            issues.push(issue(None, "frontmatter is not valid YAML"));
+            None
+        }
+    }
+}
+
+fn check_keys(
+    frontmatter: &serde_yaml::Mapping,
+    allowed: &[&str],
+    issues: &mut Vec<ValidationIssue>,
+) {
+    for key in frontmatter.keys() {
+        match key.as_str() {
+            Some(key) if allowed.contains(&key) => {}
+            Some(key) => issues.push(issue(
+                Some(key),
+                format!("unknown key (allowed: {})", allowed.join(", ")),
+            )),
+            None => issues.push(issue(None, "frontmatter keys must be strings")),
+        }
+    }
+}
+
+fn check_description(frontmatter: &serde_yaml::Mapping, issues: &mut Vec<ValidationIssue>) {
+    match frontmatter.get("description").map(|v| v.as_str()) {
+        None => issues.push(issue(Some("description"), "is required")),
+        Some(None) => issues.push(issue(Some("description"), "must be a string")),
+        Some(Some(description)) if description.trim().is_empty() => {
+            issues.push(issue(Some("description"), "must not be empty"))
+        }
+        Some(Some(description)) if description.chars().count() > MAX_DESCRIPTION_LEN => issues
+            .push(issue(
+                Some("description"),
+                format!("must be at most {} characters", MAX_DESCRIPTION_LEN),
+            )),
+        Some(Some(_)) => {}
+    }
+}
+
+/// Relative markdown link targets in `body`, outside code fences, without
+/// `#fragment` suffixes.
+fn relative_links(body: &str) -> Vec<String> {
+    let link =
+        regex::Regex::new(r#"\]\(\s*<?([^)\s>]+)>?(?:\s+"[^"]*")?\s*\)"#).expect("Invalid regex");
+    let mut in_fence = false;
+    let mut targets = Vec::new();
+    for line in body.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        for capture in link.captures_iter(line) {
+            let target = &capture[1];
+            if target.starts_with('#') || target.starts_with('/') || target.contains("://") {
+                continue;
+            }
+            if target.starts_with("mailto:") {
+                continue;
+            }
+            let target = target.split('#').next().unwrap_or(target);
+            if !target.is_empty() && !targets.iter().any(|t| t == target) {
+                targets.push(target.to_string());
+            }
+        }
+    }
+    targets
+}
+
+/// Whether `target` names an existing file inside `dir`. Links that climb
+/// out of the skill folder count as broken.
+fn link_target_exists(dir: &Path, target: &str) -> bool {
+    let mut depth = 0i32;
+    for component in Path::new(target).components() {
+        match component {
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {}
+            Component::ParentDir => depth -= 1,
+            _ => return false,
+        }
+        if depth < 0 {
+            return false;
+        }
+    }
+    dir.join(target).exists()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_skill() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("references")).unwrap();
+        std::fs::write(dir.path().join("references/api.md"), "api").unwrap();
+
+        let good = "---\nname: demo\ndescription: Does things\nsetup_commands:\n  - npm i\n---\n\nSee [API](references/api.md#auth) and [site](https://example.com).\n\n```md\n[ignored](missing.md)\n```\n";
+        let report = validate_skill("demo", good, Some(dir.path()));
+        assert!(report.valid, "{}", report.summary());
+
+        let bad = "---\nname: other\ntags: [x]\nsetup_commands: npm i\n---\n\n[a](missing.md) [b](../../etc/passwd)\n";
+        let report = validate_skill("demo", bad, Some(dir.path()));
+        let fields: Vec<Option<&str>> = report.issues.iter().map(|i| i.field.as_deref()).collect();
+        assert!(!report.valid);
+        assert!(fields.contains(&Some("description")));
+        assert!(fields.contains(&Some("name")));
+        assert!(fields.contains(&Some("tags")));
+        assert!(fields.contains(&Some("setup_commands")));
+        assert!(fields.contains(&Some("missing.md")));
+        assert!(fields.contains(&Some("../../etc/passwd")));
+
+        let requires = "---\ndescription: Deploys\nrequires:\n  mcp: [github]\n  env: GITHUB_TOKEN\n  apps: [x]\n---\n";
+        let report = validate_skill("demo", requires, None);
+        let fields: Vec<Option<&str>> = report.issues.iter().map(|i| i.field.as_deref()).collect();
+        assert_eq!(fields, vec![Some("requires.env"), Some("requires")]);
+
+        assert!(!validate_skill("demo", "# No frontmatter", None).valid);
+        let long = format!(
+            "---\ndescription: {}\n---\n",
+            "x".repeat(MAX_DESCRIPTION_LEN + 1)
+        );
+        assert!(!validate_skill("demo", &long, None).valid);
+    }
+
+    #[test]
+    fn test_validate_command() {
+        let good = "---\ndescription: Review a PR\nparams:\n  - name: repo\n    required: true\nsubtask: true\n---\nReview {{repo}}\n";
+        assert!(validate_command(good).valid);
+
+        let bad = "---\ndescription: \"\"\nparams: [repo]\nsubtask: maybe\n---\n";
+        let report = validate_command(bad);
+        assert_eq!(report.issues.len(), 3, "{}", report.summary());
+
+        let args = "---\ndescription: Deploy\nargs:\n  - env\n  - name: replicas\n    type: integer\n    default: 2\n---\n";
+        assert!(validate_command(args).valid);
+        let bad_args = "---\ndescription: Deploy\nargs:\n  - name: replicas\n    type: integer\n    default: two\n  - name: mode\n    type: enum\n  - type: string\n---\n";
+        let report = validate_command(bad_args);
+        let fields: Vec<Option<&str>> = report.issues.iter().map(|i| i.field.as_deref()).collect();
+        assert_eq!(
+            fields,
+            vec![Some("args.replicas"), Some("args.mode"), Some("args")]
+        );
+    }
+}
@@ -0,0 +1,397 @@
+//! Backend model catalogs discovered from each CLI, cached on disk with a TTL.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+/// How long a refreshed catalog is considered fresh.
+pub const DEFAULT_CATALOG_TTL_SECS: i64 = 6 * 60 * 60;
+
+/// Timeout for a single CLI model listing.
+const CLI_LIST_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CatalogModel {
+    pub id: String,
+    pub name: String,
+}
+
+impl CatalogModel {
+    fn new(id: impl Into<String>) -> Self {
+        let id = id.into();
+        Self {
+            name: id.clone(),
+            id,
+        }
+    }
+}
+
+/// Where a catalog's models came from.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CatalogSource {
+    /// Listed by the backend CLI
+    Cli,
+    /// Built-in list, used when the CLI could not list its models
+    Builtin,
+    /// Cached CLI listing served past its TTL because the refresh failed
+    Stale,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelCatalog {
+    pub backend_id: String,
+    pub models: Vec<CatalogModel>,
+    pub source: CatalogSource,
+    pub fetched_at: DateTime<Utc>,
+    pub ttl_secs: i64,
+}
+
+impl ModelCatalog {
+    pub fn is_stale(&self, now: DateTime<Utc>) -> bool {
+        (now - self.fetched_at).num_seconds() >= self.ttl_secs
+    }
+}
+
+/// Parse `opencode models` output (one `provider/model` per line).
+pub fn parse_opencode_models(output: &str) -> Vec<CatalogModel> {
+    let mut seen = std::collections::HashSet::new();
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| {
+            !line.is_empty()
+                && !line.contains(char::is_whitespace)
+                && line.contains('/')
+                && seen.insert(line.to_string())
+        })
+        .map(CatalogModel::new)
+        .collect()
+}
+
+/// Parse a generic `<cli> models` listing: either JSON (an array of ids or
+/// `{id, name}` objects, optionally under `models`) or one model id per line.
+pub fn parse_model_list(output: &str) -> Vec<CatalogModel> {
+    let mut seen = std::collections::HashSet::new();
+    if let Ok(value) = serde_json::from_str::<serde_json::Value>(output.trim()) {
+        let entries = value
+            .get("models")
+            .and_then(|v| v.as_array())
+            .or_else(|| value.as_array())
+            .cloned()
+            .unwrap_or_default();
+        return entries
+            .iter()
+            .filter_map(|entry| {
+                let id = entry
+                    .as_str()
+                    .or_else(|| entry.get("id").and_then(|v| v.as_str()))?;
+                let name = entry.get("name").and_then(|v| v.as_str()).unwrap_or(id);
+                Some(CatalogModel {
+                    id: id.to_string(),
+                    name: name.to_string(),
+                })
+            })
+            .filter(|model| !model.id.is_empty() && seen.insert(model.id.clone()))
+            .collect();
+    }
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| {
+            !line.is_empty()
+                && !line.ends_with(':')
+                && line
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "-._/:@[]".contains(c))
+                && seen.insert(line.to_string())
+        })
+        .map(CatalogModel::new)
+        .collect()
+}
+
+/// Fallback models for when a CLI can't list its own.
+fn builtin_models(backend_id: &str) -> Vec<CatalogModel> {
+    let ids: &[&str] = match backend_id {
+        "claudecode" => &["opus", "sonnet", "haiku"],
+        "codex" => &["gpt-5-codex", "gpt-5"],
+        _ => &[],
+    };
+    ids.iter().copied().map(CatalogModel::new).collect()
+}
+
+/// Run `cli` with `args` and return its stdout.
+async fn run_cli(cli: &str, args: &[&str]) -> anyhow::Result<String> {
+    let command = format!("{} {}", cli, args.join(" "));
+    let output = tokio::time::timeout(
+        CLI_LIST_TIMEOUT,
+        tokio::process::Command::new(cli)
+            .args(args)
+            .stdin(std::process::Stdio::null())
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .map_err(|_| anyhow::anyhow!("Timed out running '{}'", command))?
+    .map_err(|e| anyhow::anyhow!("Failed to run '{}': {}", command, e))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "'{}' failed: {}",
+            command,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Whether `--help` output lists a `models` subcommand.
+fn help_lists_models_command(help: &str) -> bool {
+    help.lines().any(|line| {
+        line.starts_with(char::is_whitespace)
+            && line
+                .split_whitespace()
+                .next()
+                .map(|word| word.trim_end_matches(','))
+                == Some("models")
+    })
+}
+
+/// Run `<cli> models` and parse its output.
+async fn list_cli_models(backend_id: &str, cli: &str) -> anyhow::Result<Vec<CatalogModel>> {
+    // Most CLIs treat an unknown first argument as a prompt, so only call
+    // `models` when the CLI advertises it.
+    if backend_id != "opencode" && !help_lists_models_command(&run_cli(cli, &["--help"]).await?) {
+        anyhow::bail!("'{}' has no models command", cli);
+    }
+    let stdout = run_cli(cli, &["models"]).await?;
+    let models = match backend_id {
+        "opencode" => parse_opencode_models(&stdout),
+        _ => parse_model_list(&stdout),
+    };
+    if models.is_empty() {
+        anyhow::bail!("'{} models' returned no models", cli);
+    }
+    Ok(models)
+}
+
+/// Ask a backend CLI for its current model list.
+///
+/// `cli_path` overrides the default binary name for the backend. Backends
+/// with a built-in list fall back to it when the CLI fails; opencode has none,
+/// so its errors are returned.
+pub async fn fetch_models(
+    backend_id: &str,
+    cli_path: Option<&str>,
+) -> anyhow::Result<(Vec<CatalogModel>, CatalogSource)> {
+    let (default_cli, has_builtin) = match backend_id {
+        "opencode" => ("opencode", false),
+        "claudecode" => ("claude", true),
+        "codex" => ("codex", true),
+        "amp" => ("amp", true),
+        other => anyhow::bail!("Model listing not supported for backend {}", other),
+    };
+    match list_cli_models(backend_id, cli_path.unwrap_or(default_cli)).await {
+        Ok(models) => Ok((models, CatalogSource::Cli)),
+        Err(e) if has_builtin => {
+            tracing::warn!(
+                backend = %backend_id,
+                error = %e,
+                "Model listing failed, using built-in models"
+            );
+            Ok((builtin_models(backend_id), CatalogSource::Builtin))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+#[derive(Debug)]
+pub struct ModelCatalogStore {
+    catalogs: Arc<RwLock<HashMap<String, ModelCatalog>>>,
+    storage_path: PathBuf,
+    ttl_secs: i64,
+}
+
+impl ModelCatalogStore {
+    pub async fn new(storage_path: PathBuf) -> Self {
+        let catalogs = if storage_path.exists() {
+            Self::load_from_disk(&storage_path).unwrap_or_else(|e| {
+                tracing::warn!("Failed to load model catalogs: {}", e);
+                HashMap::new()
+            })
+        } else {
+            HashMap::new()
+        };
+
+        Self {
+            catalogs: Arc::new(RwLock::new(catalogs)),
+            storage_path,
+            ttl_secs: DEFAULT_CATALOG_TTL_SECS,
+        }
+    }
+
+    fn load_from_disk(path: &Path) -> Result<HashMap<String, ModelCatalog>, std::io::Error> {
+        let contents = std::fs::read_to_string(path)?;
+        let entries: Vec<ModelCatalog> = serde_json::from_str(&contents)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        Ok(entries
+            .into_iter()
+            .map(|entry| (entry.backend_id.clone(), entry))
+            .collect())
+    }
+
+    async fn save_to_disk(&self) -> Result<(), std::io::Error> {
+        let catalogs = self.catalogs.read().await;
+        let mut entries: Vec<ModelCatalog> = catalogs.values().cloned().collect();
+        entries.sort_by(|a, b| a.backend_id.cmp(&b.backend_id));
+
+        if let Some(parent) = self.storage_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let contents = serde_json::to_string_pretty(&entries)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        std::fs::write(&self.storage_path, contents)?;
+        Ok(())
+    }
+
+    pub async fn get(&self, backend_id: &str) -> Option<ModelCatalog> {
+        self.catalogs.read().await.get(backend_id).cloned()
+    }
+
+    /// Re-list models from the backend CLI and persist the result.
+    pub async fn refresh(
+        &self,
+        backend_id: &str,
+        cli_path: Option<&str>,
+    ) -> anyhow::Result<ModelCatalog> {
+        let (models, source) = fetch_models(backend_id, cli_path).await?;
+        let catalog = ModelCatalog {
+            backend_id: backend_id.to_string(),
+            models,
+            source,
+            fetched_at: Utc::now(),
+            ttl_secs: self.ttl_secs,
+        };
+        self.catalogs
+            .write()
+            .await
+            .insert(backend_id.to_string(), catalog.clone());
+        self.save_to_disk().await?;
+        Ok(catalog)
+    }
+
+    /// Return the cached catalog, refreshing it first when missing or stale.
+    /// Falls back to a stale catalog if the refresh fails.
+    pub async fn get_or_refresh(
+        &self,
+        backend_id: &str,
+        cli_path: Option<&str>,
+    ) -> anyhow::Result<ModelCatalog> {
+        let cached = self.get(backend_id).await;
+        if let Some(catalog) = &cached {
+            if !catalog.is_stale(Utc::now()) {
+                return Ok(catalog.clone());
+            }
+        }
+        match self.refresh(backend_id, cli_path).await {
+            Ok(catalog) => Ok(catalog),
+            Err(e) => match cached {
+                Some(mut catalog) => {
+                    tracing::warn!(
+                        backend = %backend_id,
+                        error = %e,
+                        "Model catalog refresh failed, serving stale catalog"
+                    );
+                    if catalog.source == CatalogSource::Cli {
+                        catalog.source = CatalogSource::Stale;
+                    }
+                    Ok(catalog)
+                }
+                None => Err(e),
+            },
+        }
+    }
+}
+
+pub type SharedModelCatalogStore = Arc<ModelCatalogStore>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_opencode_models() {
+        let output = "anthropic/claude-sonnet-4-5\nopenai/gpt-5\n\nWarning: something\nanthropic/claude-sonnet-4-5\n";
+        let models = parse_opencode_models(output);
+        assert_eq!(
+            models,
+            vec![
+                CatalogModel::new("anthropic/claude-sonnet-4-5"),
+                CatalogModel::new("openai/gpt-5"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_model_list() {
+        let lines = "Available models:\nopus\nsonnet\n\nclaude-sonnet-4-5[1m]\nopus\n";
+        assert_eq!(
+            parse_model_list(lines),
+            vec![
+                CatalogModel::new("opus"),
+                CatalogModel::new("sonnet"),
+                CatalogModel::new("claude-sonnet-4-5[1m]"),
+            ]
+        );
+
+        let json = r#"{"models": [{"id": "gpt-5-codex", "name": "GPT-5 Codex"}, "gpt-5"]}"#;
+        assert_eq!(
+            parse_model_list(json),
+            vec![
+                CatalogModel {
+                    id: "gpt-5-codex".to_string(),
+                    name: "GPT-5 Codex".to_string(),
+                },
+                CatalogModel::new("gpt-5"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_help_lists_models_command() {
+        let help = "Usage: cli [options] [command] [prompt]\n\nCommands:\n  mcp             Configure MCP servers\n  models          List available models\n";
+        assert!(help_lists_models_command(help));
+        assert!(!help_lists_models_command(
+            "Usage: cli [prompt]\n\nCommands:\n  mcp    Configure MCP servers\nmodels are picked with --model\n"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_models_falls_back_to_builtin() {
+        let missing = "/nonexistent/sandboxed-sh-test-cli";
+        let (models, source) = fetch_models("claudecode", Some(missing)).await.unwrap();
+        assert_eq!(source, CatalogSource::Builtin);
+        assert_eq!(models, builtin_models("claudecode"));
+
+        assert!(fetch_models("opencode", Some(missing)).await.is_err());
+        assert!(fetch_models("unknown", None).await.is_err());
+    }
+
+    #[test]
+    fn test_catalog_staleness() {
+        let now = Utc::now();
+        let catalog = ModelCatalog {
+            backend_id: "opencode".to_string(),
+            models: vec![],
+            source: CatalogSource::Cli,
+            fetched_at: now - chrono::Duration::seconds(100),
+            ttl_secs: 100,
+        };
+        assert!(catalog.is_stale(now));
+        assert!(!catalog.is_stale(now - chrono::Duration::seconds(1)));
+    }
+}
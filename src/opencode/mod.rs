@@ -704,6 +704,28 @@ impl OpenCodeClient {
         Ok(())
     }
 
+    /// Reject a pending question; the tool call fails and the agent stops.
+    pub async fn reject_question(&self, directory: &str, request_id: &str) -> anyhow::Result<()> {
+        let mut url = format!("{}/question/{}/reject", self.base_url, request_id);
+        if !directory.is_empty() {
+            url.push_str("?directory=");
+            url.push_str(&urlencoding::encode(directory));
+        }
+
+        let resp = self
+            .client
+            .post(&url)
+            .send()
+            .await
+            .context("Failed to reject OpenCode question")?;
+
+        if !resp.status().is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            anyhow::bail!("OpenCode question reject failed: {}", text);
+        }
+        Ok(())
+    }
+
     /// List all sessions in OpenCode.
     /// Returns session metadata including id, title, directory, and timestamps.
     pub async fn list_sessions(&self) -> anyhow::Result<Vec<OpenCodeSessionInfo>> {
@@ -810,7 +832,7 @@ pub struct ToolStatusInfo {
 pub type OpenCodeEvent = crate::backend::events::ExecutionEvent;
 
 #[derive(Debug, Default)]
-struct SseState {
+pub(crate) struct SseState {
     message_roles: HashMap<String, String>,
     part_buffers: HashMap<String, String>,
     emitted_tool_calls: HashMap<String, ()>,
@@ -1041,7 +1063,7 @@ fn handle_tool_part_update(
 }
 
 /// Parse an SSE event line into an OpenCodeEvent.
-fn parse_sse_event(
+pub(crate) fn parse_sse_event(
     data_str: &str,
     event_name: Option<&str>,
     session_id: &str,
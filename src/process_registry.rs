@@ -0,0 +1,412 @@
+//! Registry of backend CLI processes spawned for missions.
+//!
+//! Every harness process (`claude`, `amp`, `oh-my-opencode`, `codex`) is recorded
+//! with its mission id in `.sandboxed-sh/runtime/processes.json`. If the server
+//! crashes those children keep running and burning tokens, so the reaper kills
+//! recorded processes whose missions are no longer active — all of them at
+//! startup, and any that outlive their mission while the server runs.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sysinfo::{Pid, ProcessesToUpdate, Signal, System};
+use uuid::Uuid;
+
+/// Don't reap processes younger than this; the mission may not be listed as
+/// running yet.
+const REAP_GRACE_SECS: i64 = 60;
+
+static PROCESS_REGISTRY: OnceLock<Arc<ProcessRegistry>> = OnceLock::new();
+
+/// A spawned backend process.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessRecord {
+    pub pid: u32,
+    pub mission_id: Uuid,
+    pub program: String,
+    /// Process start time reported by the OS (seconds since epoch), used to
+    /// detect PID reuse before killing.
+    #[serde(default)]
+    pub os_start_time: u64,
+    pub registered_at: DateTime<Utc>,
+}
+
+/// Work done so far by a mission's processes and their descendants.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProcessActivity {
+    /// Live processes in the trees
+    pub processes: usize,
+    /// CPU time used, including exited children
+    pub cpu_ms: u64,
+    /// Bytes read from storage
+    pub bytes_read: u64,
+}
+
+impl ProcessActivity {
+    /// Whether this sample shows work done since `earlier`.
+    pub fn advanced_since(&self, earlier: &ProcessActivity) -> bool {
+        self.cpu_ms > earlier.cpu_ms || self.bytes_read > earlier.bytes_read
+    }
+}
+
+pub struct ProcessRegistry {
+    records: Mutex<HashMap<u32, ProcessRecord>>,
+    storage_path: PathBuf,
+}
+
+/// Initialize the global registry, loading records left by a previous run.
+pub fn init(working_dir: &Path) -> Arc<ProcessRegistry> {
+    Arc::clone(PROCESS_REGISTRY.get_or_init(|| {
+        Arc::new(ProcessRegistry::load(
+            working_dir
+                .join(".sandboxed-sh")
+                .join("runtime")
+                .join("processes.json"),
+        ))
+    }))
+}
+
+/// The global registry, if initialized.
+pub fn global() -> Option<Arc<ProcessRegistry>> {
+    PROCESS_REGISTRY.get().cloned()
+}
+
+/// Record a spawned process for a mission. The returned guard removes the
+/// record when dropped (i.e. when the turn that owns the process ends).
+pub fn track(pid: Option<u32>, mission_id: Uuid, program: &str) -> ProcessGuard {
+    let pid = match (pid, global()) {
+        (Some(pid), Some(registry)) => {
+            registry.register(pid, mission_id, program);
+            Some(pid)
+        }
+        _ => None,
+    };
+    ProcessGuard { pid }
+}
+
+/// Unregisters a tracked process on drop.
+pub struct ProcessGuard {
+    pid: Option<u32>,
+}
+
+impl Drop for ProcessGuard {
+    fn drop(&mut self) {
+        if let (Some(pid), Some(registry)) = (self.pid, global()) {
+            registry.unregister(pid);
+        }
+    }
+}
+
+impl ProcessRegistry {
+    fn load(storage_path: PathBuf) -> Self {
+        let records = std::fs::read_to_string(&storage_path)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<Vec<ProcessRecord>>(&contents).ok())
+            .unwrap_or_default()
+            .into_iter()
+            .map(|record| (record.pid, record))
+            .collect();
+        Self {
+            records: Mutex::new(records),
+            storage_path,
+        }
+    }
+
+    fn save(&self, records: &HashMap<u32, ProcessRecord>) {
+        let mut entries: Vec<&ProcessRecord> = records.values().collect();
+        entries.sort_by_key(|r| r.pid);
+        if let Some(parent) = self.storage_path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        match serde_json::to_string_pretty(&entries) {
+            Ok(contents) => {
+                if let Err(e) = std::fs::write(&self.storage_path, contents) {
+                    tracing::warn!("Failed to persist process registry: {}", e);
+                }
+            }
+            Err(e) => tracing::warn!("Failed to serialize process registry: {}", e),
+        }
+    }
+
+    pub fn register(&self, pid: u32, mission_id: Uuid, program: &str) {
+        let os_start_time = process_start_time(pid).unwrap_or(0);
+        let mut records = self.records.lock().unwrap_or_else(|e| e.into_inner());
+        records.insert(
+            pid,
+            ProcessRecord {
+                pid,
+                mission_id,
+                program: program.to_string(),
+                os_start_time,
+                registered_at: Utc::now(),
+            },
+        );
+        self.save(&records);
+    }
+
+    pub fn unregister(&self, pid: u32) {
+        let mut records = self.records.lock().unwrap_or_else(|e| e.into_inner());
+        if records.remove(&pid).is_some() {
+            self.save(&records);
+        }
+    }
+
+    pub fn list(&self) -> Vec<ProcessRecord> {
+        let records = self.records.lock().unwrap_or_else(|e| e.into_inner());
+        records.values().cloned().collect()
+    }
+
+    /// Activity of each mission's live recorded processes, descendants
+    /// included.
+    pub fn activity(&self) -> HashMap<Uuid, ProcessActivity> {
+        let records = self.list();
+        let mut activity: HashMap<Uuid, ProcessActivity> = HashMap::new();
+        if records.is_empty() {
+            return activity;
+        }
+        let mut system = System::new();
+        system.refresh_processes(ProcessesToUpdate::All, true);
+        for record in records {
+            let alive = system.process(Pid::from_u32(record.pid)).is_some_and(|p| {
+                record.os_start_time == 0 || p.start_time() == record.os_start_time
+            });
+            if !alive {
+                continue;
+            }
+            let entry = activity.entry(record.mission_id).or_default();
+            for pid in descendants(&system, record.pid) {
+                let Some(process) = system.process(pid) else {
+                    continue;
+                };
+                entry.processes += 1;
+                entry.cpu_ms += cpu_time_ms(pid.as_u32()).unwrap_or(0);
+                entry.bytes_read += process.disk_usage().total_read_bytes;
+            }
+        }
+        activity
+    }
+
+    /// Kill every recorded process (and its descendants) for a mission.
+    /// Returns the PIDs of the recorded processes that were killed.
+    pub fn kill_mission(&self, mission_id: Uuid) -> Vec<u32> {
+        let targets: Vec<ProcessRecord> = self
+            .list()
+            .into_iter()
+            .filter(|r| r.mission_id == mission_id)
+            .collect();
+        self.kill_records(targets)
+    }
+
+    /// Kill recorded processes whose mission is not in `active_missions`.
+    /// Records for processes that already exited are dropped.
+    pub fn reap(&self, active_missions: &HashSet<Uuid>) -> Vec<ProcessRecord> {
+        let now = Utc::now();
+        let orphans: Vec<ProcessRecord> = self
+            .list()
+            .into_iter()
+            .filter(|r| {
+                !active_missions.contains(&r.mission_id)
+                    && (now - r.registered_at).num_seconds() >= REAP_GRACE_SECS
+            })
+            .collect();
+        let killed = self.kill_records(orphans.clone());
+        orphans
+            .into_iter()
+            .filter(|r| killed.contains(&r.pid))
+            .collect()
+    }
+
+    /// Kill all recorded processes regardless of age (used at startup, when no
+    /// mission can be running yet).
+    pub fn reap_all(&self) -> Vec<ProcessRecord> {
+        let records = self.list();
+        let killed = self.kill_records(records.clone());
+        records
+            .into_iter()
+            .filter(|r| killed.contains(&r.pid))
+            .collect()
+    }
+
+    fn kill_records(&self, targets: Vec<ProcessRecord>) -> Vec<u32> {
+        if targets.is_empty() {
+            return Vec::new();
+        }
+        let mut system = System::new();
+        system.refresh_processes(ProcessesToUpdate::All, true);
+
+        let mut killed = Vec::new();
+        let mut records = self.records.lock().unwrap_or_else(|e| e.into_inner());
+        for record in targets {
+            match system.process(Pid::from_u32(record.pid)) {
+                // Without a start time the PID may belong to another process by now.
+                Some(_) if record.os_start_time == 0 => {
+                    tracing::warn!(
+                        pid = record.pid,
+                        mission_id = %record.mission_id,
+                        program = %record.program,
+                        "Not killing backend process with unknown start time"
+                    );
+                }
+                Some(process) if process.start_time() == record.os_start_time => {
+                    kill_tree(&system, record.pid);
+                    tracing::info!(
+                        pid = record.pid,
+                        mission_id = %record.mission_id,
+                        program = %record.program,
+                        "Killed backend process"
+                    );
+                    killed.push(record.pid);
+                }
+                _ => {}
+            }
+            records.remove(&record.pid);
+        }
+        self.save(&records);
+        killed
+    }
+}
+
+fn process_start_time(pid: u32) -> Option<u64> {
+    let pid = Pid::from_u32(pid);
+    let mut system = System::new();
+    system.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+    system.process(pid).map(|p| p.start_time())
+}
+
+/// CPU time of a process and its waited-for children, from `/proc`.
+fn cpu_time_ms(pid: u32) -> Option<u64> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    // Fields after the command name, which may contain spaces
+    let fields: Vec<&str> = stat
+        .get(stat.rfind(')')? + 1..)?
+        .split_whitespace()
+        .collect();
+    // utime, stime, cutime and cstime (fields 14 to 17)
+    let ticks: u64 = fields
+        .get(11..15)?
+        .iter()
+        .map(|field| field.parse::<u64>().unwrap_or(0))
+        .sum();
+    let ticks_per_sec = match unsafe { libc::sysconf(libc::_SC_CLK_TCK) } {
+        n if n > 0 => n as u64,
+        _ => 100,
+    };
+    Some(ticks * 1000 / ticks_per_sec)
+}
+
+/// Collect `root` and all of its descendants.
+fn descendants(system: &System, root: u32) -> Vec<Pid> {
+    let mut children: HashMap<Pid, Vec<Pid>> = HashMap::new();
+    for (pid, process) in system.processes() {
+        if let Some(parent) = process.parent() {
+            children.entry(parent).or_default().push(*pid);
+        }
+    }
+    let mut out = Vec::new();
+    let mut stack = vec![Pid::from_u32(root)];
+    while let Some(pid) = stack.pop() {
+        out.push(pid);
+        if let Some(kids) = children.get(&pid) {
+            stack.extend(kids.iter().copied());
+        }
+    }
+    out
+}
+
+/// SIGKILL a process and all of its descendants, including processes running
+/// inside a container namespace that it spawned. Returns how many processes
+/// were signalled.
+pub fn kill_process_tree(root: u32) -> usize {
+    let mut system = System::new();
+    system.refresh_processes(ProcessesToUpdate::All, true);
+    kill_tree(&system, root)
+}
+
+/// SIGKILL a process and all of its descendants (children first).
+fn kill_tree(system: &System, root: u32) -> usize {
+    let mut killed = 0;
+    for pid in descendants(system, root).into_iter().rev() {
+        if let Some(process) = system.process(pid) {
+            if process.kill_with(Signal::Kill).unwrap_or(false) {
+                killed += 1;
+            }
+        }
+    }
+    killed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reap_kills_orphans_and_keeps_active() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = ProcessRegistry::load(dir.path().join("processes.json"));
+
+        let mut orphan = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .unwrap();
+        let mut active = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .unwrap();
+        let orphan_mission = Uuid::new_v4();
+        let active_mission = Uuid::new_v4();
+        registry.register(orphan.id(), orphan_mission, "sleep");
+        registry.register(active.id(), active_mission, "sleep");
+
+        // Newly registered processes are within the grace period.
+        let active_set: HashSet<Uuid> = [active_mission].into_iter().collect();
+        assert!(registry.reap(&active_set).is_empty());
+
+        for record in registry.records.lock().unwrap().values_mut() {
+            record.registered_at = Utc::now() - chrono::Duration::seconds(REAP_GRACE_SECS);
+        }
+        let reaped = registry.reap(&active_set);
+        assert_eq!(reaped.len(), 1);
+        assert_eq!(reaped[0].mission_id, orphan_mission);
+        assert!(orphan.wait().unwrap().code().is_none());
+
+        // Records survive a reload from disk.
+        let reloaded = ProcessRegistry::load(dir.path().join("processes.json"));
+        let remaining = reloaded.list();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].mission_id, active_mission);
+
+        let activity = registry.activity();
+        assert_eq!(activity.len(), 1);
+        assert_eq!(activity[&active_mission].processes, 1);
+
+        assert_eq!(registry.kill_mission(active_mission), vec![active.id()]);
+        assert!(active.wait().unwrap().code().is_none());
+        assert!(registry.list().is_empty());
+    }
+
+    #[test]
+    fn test_kill_skips_records_without_start_time() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = ProcessRegistry::load(dir.path().join("processes.json"));
+
+        let mut child = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .unwrap();
+        let mission = Uuid::new_v4();
+        registry.register(child.id(), mission, "sleep");
+        for record in registry.records.lock().unwrap().values_mut() {
+            record.os_start_time = 0;
+        }
+
+        assert!(registry.kill_mission(mission).is_empty());
+        assert!(registry.list().is_empty());
+        assert!(child.try_wait().unwrap().is_none());
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+}
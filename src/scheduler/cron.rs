@@ -0,0 +1,284 @@
+//! Five-field cron expressions (`minute hour day-of-month month day-of-week`).
+//!
+//! Supports `*`, lists, ranges, steps, month/weekday names and the usual
+//! `@daily`-style shortcuts. Schedules are evaluated at a fixed UTC offset.
+
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, TimeZone, Timelike, Utc};
+
+/// Upper bound on search steps in `next_after`; an expression that can never
+/// fire (e.g. `0 0 30 2 *`) gives up instead of looping forever.
+const MAX_SEARCH_STEPS: usize = 20_000;
+
+const MONTH_NAMES: &[&str] = &[
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAY_NAMES: &[&str] = &["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// A parsed cron schedule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u32,
+    days_of_month: u32,
+    months: u16,
+    days_of_week: u8,
+    /// Cron semantics: when both day fields are restricted, a day matches if
+    /// either one does.
+    dom_restricted: bool,
+    dow_restricted: bool,
+    offset: FixedOffset,
+}
+
+impl CronSchedule {
+    /// Parse a cron expression evaluated at `offset` from UTC.
+    pub fn parse(expr: &str, offset: FixedOffset) -> Result<Self, String> {
+        let expr = expr.trim();
+        let expanded = match expr.to_ascii_lowercase().as_str() {
+            "@hourly" => "0 * * * *".to_string(),
+            "@daily" | "@midnight" => "0 0 * * *".to_string(),
+            "@weekly" => "0 0 * * 0".to_string(),
+            "@monthly" => "0 0 1 * *".to_string(),
+            "@yearly" | "@annually" => "0 0 1 1 *".to_string(),
+            other if other.starts_with('@') => {
+                return Err(format!("Unknown schedule shortcut '{}'", expr))
+            }
+            _ => expr.to_string(),
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!(
+                "Expected 5 fields (minute hour day-of-month month day-of-week), got {}",
+                fields.len()
+            ));
+        }
+
+        let minutes = parse_field(fields[0], 0, 59, &[])?;
+        let hours = parse_field(fields[1], 0, 23, &[])?;
+        let days_of_month = parse_field(fields[2], 1, 31, &[])?;
+        let months = parse_field(fields[3], 1, 12, MONTH_NAMES)?;
+        // 7 is an alias for Sunday.
+        let mut days_of_week = parse_field(fields[4], 0, 7, WEEKDAY_NAMES)?;
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            minutes,
+            hours: hours as u32,
+            days_of_month: days_of_month as u32,
+            months: months as u16,
+            days_of_week: days_of_week as u8,
+            dom_restricted: !fields[2].starts_with('*'),
+            dow_restricted: !fields[4].starts_with('*'),
+            offset,
+        })
+    }
+
+    /// The first time strictly after `after` at which the schedule fires.
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let local = after.with_timezone(&self.offset);
+        let mut t = local
+            .with_second(0)?
+            .with_nanosecond(0)?
+            .checked_add_signed(Duration::minutes(1))?;
+
+        for _ in 0..MAX_SEARCH_STEPS {
+            if self.months & (1 << t.month()) == 0 {
+                let (year, month) = if t.month() == 12 {
+                    (t.year() + 1, 1)
+                } else {
+                    (t.year(), t.month() + 1)
+                };
+                t = self.midnight(NaiveDate::from_ymd_opt(year, month, 1)?)?;
+                continue;
+            }
+            if !self.day_matches(t.day(), t.weekday().num_days_from_sunday()) {
+                t = self.midnight(t.date_naive().succ_opt()?)?;
+                continue;
+            }
+            if self.hours & (1 << t.hour()) == 0 {
+                t = t.with_minute(0)?.checked_add_signed(Duration::hours(1))?;
+                continue;
+            }
+            if self.minutes & (1 << t.minute()) == 0 {
+                t = t.checked_add_signed(Duration::minutes(1))?;
+                continue;
+            }
+            return Some(t.with_timezone(&Utc));
+        }
+        None
+    }
+
+    fn midnight(&self, date: NaiveDate) -> Option<DateTime<FixedOffset>> {
+        self.offset
+            .from_local_datetime(&date.and_hms_opt(0, 0, 0)?)
+            .single()
+    }
+
+    fn day_matches(&self, day: u32, weekday: u32) -> bool {
+        let dom = self.days_of_month & (1 << day) != 0;
+        let dow = self.days_of_week & (1 << weekday) != 0;
+        if self.dom_restricted && self.dow_restricted {
+            dom || dow
+        } else {
+            dom && dow
+        }
+    }
+}
+
+/// Parse a UTC offset such as `+02:00`, `-0530`, `Z` or `UTC`.
+pub fn parse_utc_offset(raw: &str) -> Result<FixedOffset, String> {
+    let raw = raw.trim();
+    if raw.is_empty() || raw.eq_ignore_ascii_case("utc") || raw.eq_ignore_ascii_case("z") {
+        return Ok(FixedOffset::east_opt(0).expect("zero offset"));
+    }
+    let invalid = || format!("Invalid UTC offset '{}'; use a form like +02:00", raw);
+    let (sign, rest) = match raw.as_bytes()[0] {
+        b'+' => (1, &raw[1..]),
+        b'-' => (-1, &raw[1..]),
+        _ => return Err(invalid()),
+    };
+    let digits: String = rest.chars().filter(|c| *c != ':').collect();
+    if !digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let (hours, minutes) = match digits.len() {
+        1 | 2 => (digits.parse::<i32>().map_err(|_| invalid())?, 0),
+        4 => (
+            digits[..2].parse::<i32>().map_err(|_| invalid())?,
+            digits[2..].parse::<i32>().map_err(|_| invalid())?,
+        ),
+        _ => return Err(invalid()),
+    };
+    if hours > 14 || minutes > 59 {
+        return Err(invalid());
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60)).ok_or_else(invalid)
+}
+
+/// Parse one cron field into a bitmask where bit `n` means value `n` matches.
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<u64, String> {
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .map_err(|_| format!("Invalid step in '{}'", part))?;
+                if step == 0 {
+                    return Err(format!("Step must be positive in '{}'", part));
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            (
+                parse_value(a, min, max, names)?,
+                parse_value(b, min, max, names)?,
+            )
+        } else {
+            let value = parse_value(range, min, max, names)?;
+            // `5/15` means "from 5 to the end, every 15".
+            (value, if step > 1 { max } else { value })
+        };
+        if start > end {
+            return Err(format!("Range '{}' is backwards", range));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Ok(mask)
+}
+
+fn parse_value(raw: &str, min: u32, max: u32, names: &[&str]) -> Result<u32, String> {
+    let lower = raw.to_ascii_lowercase();
+    if let Some(idx) = names.iter().position(|name| *name == lower) {
+        // Month names start at 1, weekday names at 0.
+        return Ok(idx as u32 + min);
+    }
+    let value: u32 = raw
+        .parse()
+        .map_err(|_| format!("Invalid value '{}'", raw))?;
+    if value < min || value > max {
+        return Err(format!("Value {} is out of range ({}-{})", value, min, max));
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_daily_at_nine_with_offset() {
+        let offset = parse_utc_offset("+02:00").unwrap();
+        let schedule = CronSchedule::parse("0 9 * * *", offset).unwrap();
+        // 06:30 UTC is 08:30 local; next run is 09:00 local = 07:00 UTC.
+        assert_eq!(
+            schedule.next_after(utc("2026-03-10T06:30:00Z")),
+            Some(utc("2026-03-10T07:00:00Z"))
+        );
+        // Exactly at the fire time, the next run is tomorrow.
+        assert_eq!(
+            schedule.next_after(utc("2026-03-10T07:00:00Z")),
+            Some(utc("2026-03-11T07:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn test_steps_ranges_and_names() {
+        let utc0 = parse_utc_offset("UTC").unwrap();
+        let weekdays = CronSchedule::parse("*/15 8-17 * * mon-fri", utc0).unwrap();
+        // Saturday 2026-03-14 -> Monday 08:00.
+        assert_eq!(
+            weekdays.next_after(utc("2026-03-14T12:00:00Z")),
+            Some(utc("2026-03-16T08:00:00Z"))
+        );
+        assert_eq!(
+            weekdays.next_after(utc("2026-03-16T08:01:00Z")),
+            Some(utc("2026-03-16T08:15:00Z"))
+        );
+
+        let monthly = CronSchedule::parse("@monthly", utc0).unwrap();
+        assert_eq!(
+            monthly.next_after(utc("2026-12-05T00:00:00Z")),
+            Some(utc("2027-01-01T00:00:00Z"))
+        );
+        let sunday = CronSchedule::parse("30 6 * * 7", utc0).unwrap();
+        assert_eq!(
+            sunday.next_after(utc("2026-03-10T00:00:00Z")),
+            Some(utc("2026-03-15T06:30:00Z"))
+        );
+    }
+
+    #[test]
+    fn test_day_fields_are_ored_when_both_restricted() {
+        let utc0 = parse_utc_offset("Z").unwrap();
+        // The 1st of the month or any Friday.
+        let schedule = CronSchedule::parse("0 0 1 * 5", utc0).unwrap();
+        assert_eq!(
+            schedule.next_after(utc("2026-03-10T00:00:00Z")),
+            Some(utc("2026-03-13T00:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn test_invalid_expressions() {
+        let utc0 = parse_utc_offset("").unwrap();
+        assert!(CronSchedule::parse("0 9 * *", utc0).is_err());
+        assert!(CronSchedule::parse("60 * * * *", utc0).is_err());
+        assert!(CronSchedule::parse("*/0 * * * *", utc0).is_err());
+        assert!(CronSchedule::parse("@sometimes", utc0).is_err());
+        assert!(parse_utc_offset("Europe/Paris").is_err());
+        let never = CronSchedule::parse("0 0 30 2 *", utc0).unwrap();
+        assert_eq!(never.next_after(utc("2026-01-01T00:00:00Z")), None);
+    }
+}
@@ -0,0 +1,13 @@
+//! Scheduler for recurring agent jobs.
+//!
+//! A job pairs a cron schedule with a mission prompt ("every weekday at 9:00,
+//! summarize open PRs"). Jobs are persisted to disk; the server's scheduler
+//! loop (`api::scheduler`) creates a fresh mission for each run.
+
+mod cron;
+mod store;
+mod types;
+
+pub use cron::{parse_utc_offset, CronSchedule};
+pub use store::{SchedulerStore, SharedSchedulerStore};
+pub use types::{NewScheduledJob, ScheduledJob, ScheduledJobUpdate};
@@ -0,0 +1,305 @@
+//! Persistence for scheduled jobs.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use super::cron::{parse_utc_offset, CronSchedule};
+use super::types::{NewScheduledJob, ScheduledJob, ScheduledJobUpdate};
+
+/// Jobs stored in `{working_dir}/.sandboxed-sh/scheduled_jobs.json`.
+pub struct SchedulerStore {
+    jobs: RwLock<Vec<ScheduledJob>>,
+    storage_path: PathBuf,
+}
+
+/// Shared scheduler store wrapped in Arc for concurrent access.
+pub type SharedSchedulerStore = Arc<SchedulerStore>;
+
+/// Validate a job's schedule and compute when it fires after `after`.
+fn next_run(job: &ScheduledJob, after: DateTime<Utc>) -> Result<Option<DateTime<Utc>>, String> {
+    let offset = parse_utc_offset(&job.utc_offset)?;
+    let schedule = CronSchedule::parse(&job.schedule, offset)?;
+    Ok(if job.enabled {
+        schedule.next_after(after)
+    } else {
+        None
+    })
+}
+
+impl SchedulerStore {
+    /// Create a store, loading existing jobs from disk.
+    pub async fn new(working_dir: &Path) -> Self {
+        let storage_path = working_dir.join(".sandboxed-sh/scheduled_jobs.json");
+        let jobs = match std::fs::read_to_string(&storage_path) {
+            Ok(contents) => match serde_json::from_str::<Vec<ScheduledJob>>(&contents) {
+                Ok(jobs) => {
+                    tracing::info!(
+                        "Loaded {} scheduled jobs from {}",
+                        jobs.len(),
+                        storage_path.display()
+                    );
+                    jobs
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to parse scheduled jobs from {}: {}",
+                        storage_path.display(),
+                        e
+                    );
+                    Vec::new()
+                }
+            },
+            Err(_) => Vec::new(),
+        };
+
+        Self {
+            jobs: RwLock::new(jobs),
+            storage_path,
+        }
+    }
+
+    async fn save_to_disk(&self, jobs: &[ScheduledJob]) -> Result<(), String> {
+        if let Some(parent) = self.storage_path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let contents = serde_json::to_string_pretty(jobs).map_err(|e| e.to_string())?;
+        tokio::fs::write(&self.storage_path, contents)
+            .await
+            .map_err(|e| format!("Failed to write {}: {}", self.storage_path.display(), e))
+    }
+
+    /// List jobs, optionally only those owned by `owner_id`.
+    pub async fn list(&self, owner_id: Option<&str>) -> Vec<ScheduledJob> {
+        let mut jobs: Vec<ScheduledJob> = self
+            .jobs
+            .read()
+            .await
+            .iter()
+            .filter(|job| owner_id.is_none() || owner_id == Some(job.owner_id.as_str()))
+            .cloned()
+            .collect();
+        jobs.sort_by_key(|job| job.created_at);
+        jobs
+    }
+
+    pub async fn get(&self, id: Uuid) -> Option<ScheduledJob> {
+        self.jobs.read().await.iter().find(|j| j.id == id).cloned()
+    }
+
+    /// Create a job owned by the given user.
+    pub async fn create(
+        &self,
+        owner_id: &str,
+        owner_username: &str,
+        new: NewScheduledJob,
+    ) -> Result<ScheduledJob, String> {
+        if new.name.trim().is_empty() {
+            return Err("name is required".to_string());
+        }
+        if new.prompt.trim().is_empty() {
+            return Err("prompt is required".to_string());
+        }
+        let now = Utc::now();
+        let mut job = ScheduledJob {
+            id: Uuid::new_v4(),
+            name: new.name.trim().to_string(),
+            schedule: new.schedule.trim().to_string(),
+            utc_offset: new.utc_offset,
+            prompt: new.prompt,
+            workspace_id: new.workspace_id,
+            agent: new.agent,
+            backend: new.backend,
+            model_override: new.model_override,
+            enabled: new.enabled,
+            owner_id: owner_id.to_string(),
+            owner_username: owner_username.to_string(),
+            created_at: now,
+            next_run_at: None,
+            last_run_at: None,
+            last_mission_id: None,
+            last_error: None,
+            run_count: 0,
+        };
+        job.next_run_at = next_run(&job, now)?;
+        if job.enabled && job.next_run_at.is_none() {
+            return Err(format!("Schedule '{}' never fires", job.schedule));
+        }
+
+        let mut jobs = self.jobs.write().await;
+        jobs.push(job.clone());
+        self.save_to_disk(&jobs).await?;
+        Ok(job)
+    }
+
+    /// Apply a partial update. Returns `Ok(None)` if the job doesn't exist.
+    pub async fn update(
+        &self,
+        id: Uuid,
+        update: ScheduledJobUpdate,
+    ) -> Result<Option<ScheduledJob>, String> {
+        let mut jobs = self.jobs.write().await;
+        let Some(existing) = jobs.iter().position(|j| j.id == id) else {
+            return Ok(None);
+        };
+        let mut job = jobs[existing].clone();
+        if let Some(name) = update.name {
+            job.name = name.trim().to_string();
+        }
+        if let Some(schedule) = update.schedule {
+            job.schedule = schedule.trim().to_string();
+        }
+        if let Some(offset) = update.utc_offset {
+            job.utc_offset = offset;
+        }
+        if let Some(prompt) = update.prompt {
+            job.prompt = prompt;
+        }
+        if let Some(workspace_id) = update.workspace_id {
+            job.workspace_id = workspace_id;
+        }
+        if let Some(agent) = update.agent {
+            job.agent = agent;
+        }
+        if let Some(backend) = update.backend {
+            job.backend = backend;
+        }
+        if let Some(model_override) = update.model_override {
+            job.model_override = model_override;
+        }
+        if let Some(enabled) = update.enabled {
+            job.enabled = enabled;
+        }
+        if job.name.is_empty() || job.prompt.trim().is_empty() {
+            return Err("name and prompt must not be empty".to_string());
+        }
+        job.next_run_at = next_run(&job, Utc::now())?;
+
+        jobs[existing] = job.clone();
+        self.save_to_disk(&jobs).await?;
+        Ok(Some(job))
+    }
+
+    /// Delete a job. Returns whether it existed.
+    pub async fn delete(&self, id: Uuid) -> Result<bool, String> {
+        let mut jobs = self.jobs.write().await;
+        let before = jobs.len();
+        jobs.retain(|j| j.id != id);
+        if jobs.len() == before {
+            return Ok(false);
+        }
+        self.save_to_disk(&jobs).await?;
+        Ok(true)
+    }
+
+    /// Enabled jobs whose next run is at or before `now`.
+    ///
+    /// Runs missed while the server was down collapse into a single run.
+    pub async fn due(&self, now: DateTime<Utc>) -> Vec<ScheduledJob> {
+        self.jobs
+            .read()
+            .await
+            .iter()
+            .filter(|job| job.enabled && job.next_run_at.is_some_and(|next| next <= now))
+            .cloned()
+            .collect()
+    }
+
+    /// Record the outcome of a run and schedule the next one.
+    pub async fn record_run(
+        &self,
+        id: Uuid,
+        now: DateTime<Utc>,
+        outcome: Result<Uuid, String>,
+    ) -> Result<(), String> {
+        let mut jobs = self.jobs.write().await;
+        let Some(job) = jobs.iter_mut().find(|j| j.id == id) else {
+            return Ok(());
+        };
+        job.last_run_at = Some(now);
+        match outcome {
+            Ok(mission_id) => {
+                job.last_mission_id = Some(mission_id);
+                job.last_error = None;
+                job.run_count += 1;
+            }
+            Err(error) => job.last_error = Some(error),
+        }
+        job.next_run_at = next_run(job, now).unwrap_or(None);
+        self.save_to_disk(&jobs).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_job(schedule: &str) -> NewScheduledJob {
+        NewScheduledJob {
+            name: "Daily report".to_string(),
+            schedule: schedule.to_string(),
+            utc_offset: "+00:00".to_string(),
+            prompt: "Summarize yesterday's commits".to_string(),
+            workspace_id: None,
+            agent: None,
+            backend: None,
+            model_override: None,
+            enabled: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_jobs_persist_and_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SchedulerStore::new(dir.path()).await;
+        assert!(store
+            .create("u1", "alice", new_job("0 9 * *"))
+            .await
+            .is_err());
+
+        let job = store
+            .create("u1", "alice", new_job("0 9 * * *"))
+            .await
+            .unwrap();
+        let next = job.next_run_at.unwrap();
+        assert!(store
+            .due(next - chrono::Duration::seconds(1))
+            .await
+            .is_empty());
+        assert_eq!(store.due(next).await.len(), 1);
+
+        let mission_id = Uuid::new_v4();
+        store
+            .record_run(job.id, next, Ok(mission_id))
+            .await
+            .unwrap();
+        assert!(store.due(next).await.is_empty());
+
+        let reloaded = SchedulerStore::new(dir.path()).await;
+        let saved = reloaded.get(job.id).await.unwrap();
+        assert_eq!(saved.last_mission_id, Some(mission_id));
+        assert_eq!(saved.run_count, 1);
+        assert_eq!(saved.next_run_at, Some(next + chrono::Duration::days(1)));
+        assert!(reloaded.list(Some("u2")).await.is_empty());
+
+        let paused = reloaded
+            .update(
+                job.id,
+                ScheduledJobUpdate {
+                    enabled: Some(false),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(paused.next_run_at, None);
+        assert!(reloaded.delete(job.id).await.unwrap());
+        assert!(reloaded.list(None).await.is_empty());
+    }
+}
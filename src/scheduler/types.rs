@@ -0,0 +1,106 @@
+//! Types for scheduled jobs.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A recurring job: run `prompt` in a new mission whenever `schedule` fires.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledJob {
+    pub id: Uuid,
+    /// Human-readable name, also used as the mission title prefix
+    pub name: String,
+    /// Cron expression (`minute hour day-of-month month day-of-week`)
+    pub schedule: String,
+    /// Offset the schedule is evaluated at (e.g. `+02:00`)
+    #[serde(default = "default_utc_offset")]
+    pub utc_offset: String,
+    /// Message sent to each new mission. Placeholders such as `<date/>`,
+    /// `<job_name/>` and `<run_number/>` are filled in for each run.
+    pub prompt: String,
+    /// Workspace to run missions in (defaults to the host workspace)
+    #[serde(default)]
+    pub workspace_id: Option<Uuid>,
+    #[serde(default)]
+    pub agent: Option<String>,
+    #[serde(default)]
+    pub backend: Option<String>,
+    #[serde(default)]
+    pub model_override: Option<String>,
+    pub enabled: bool,
+    /// User whose control session runs the missions
+    pub owner_id: String,
+    #[serde(default)]
+    pub owner_username: String,
+    pub created_at: DateTime<Utc>,
+    /// When the job fires next (None when disabled or the schedule never fires)
+    #[serde(default)]
+    pub next_run_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_run_at: Option<DateTime<Utc>>,
+    /// Mission created by the most recent run
+    #[serde(default)]
+    pub last_mission_id: Option<Uuid>,
+    /// Why the most recent run didn't start, if it didn't
+    #[serde(default)]
+    pub last_error: Option<String>,
+    #[serde(default)]
+    pub run_count: u64,
+}
+
+fn default_utc_offset() -> String {
+    "+00:00".to_string()
+}
+
+/// Fields accepted when creating a job.
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewScheduledJob {
+    pub name: String,
+    #[serde(alias = "cron")]
+    pub schedule: String,
+    #[serde(default = "default_utc_offset")]
+    pub utc_offset: String,
+    pub prompt: String,
+    #[serde(default)]
+    pub workspace_id: Option<Uuid>,
+    #[serde(default)]
+    pub agent: Option<String>,
+    #[serde(default)]
+    pub backend: Option<String>,
+    #[serde(default, alias = "model")]
+    pub model_override: Option<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Partial update of a job; absent fields are left unchanged.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ScheduledJobUpdate {
+    pub name: Option<String>,
+    pub schedule: Option<String>,
+    pub utc_offset: Option<String>,
+    pub prompt: Option<String>,
+    /// `null` clears the field
+    #[serde(default, deserialize_with = "nullable")]
+    pub workspace_id: Option<Option<Uuid>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub agent: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub backend: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub model_override: Option<Option<String>>,
+    pub enabled: Option<bool>,
+}
+
+/// Distinguish an explicit `null` (`Some(None)`) from an absent field (`None`).
+fn nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
@@ -5,9 +5,12 @@
 
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use tokio::sync::RwLock;
 
+use crate::library::LibraryLayerConfig;
+use crate::tools::{ToolPolicy, ToolResultLimits};
+
 /// Default repo path for sandboxed.sh source (used for self-updates).
 pub const DEFAULT_SANDBOXED_REPO_PATH: &str = "/opt/sandboxed-sh/vaduz-v1";
 
@@ -17,9 +20,18 @@ pub struct Settings {
     /// Git remote URL for the configuration library.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub library_remote: Option<String>,
+    /// Extra read-only libraries merged under the primary one, in priority order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub library_layers: Vec<LibraryLayerConfig>,
     /// Path to the sandboxed.sh source repo (used for self-updates).
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub sandboxed_repo_path: Option<String>,
+    /// Allow/deny/ask rules applied to tool executions.
+    #[serde(default, skip_serializing_if = "ToolPolicy::is_empty")]
+    pub tool_policy: ToolPolicy,
+    /// Size limits applied to tool results.
+    #[serde(default, skip_serializing_if = "ToolResultLimits::is_default")]
+    pub tool_result_limits: ToolResultLimits,
 }
 
 /// In-memory store for global settings with disk persistence.
@@ -34,6 +46,7 @@ impl SettingsStore {
     ///
     /// If no settings file exists, uses environment variables as defaults:
     /// - `LIBRARY_REMOTE` - Git remote URL for the configuration library
+    /// - `LIBRARY_LAYERS` - Extra libraries as comma-separated `name=remote` pairs
     pub async fn new(working_dir: &PathBuf) -> Self {
         let storage_path = working_dir.join(".sandboxed-sh/settings.json");
 
@@ -72,10 +85,15 @@ impl SettingsStore {
             library_remote: std::env::var("LIBRARY_REMOTE").ok().or_else(|| {
                 Some("https://github.com/Th0rgal/sandboxed-library-template.git".to_string())
             }),
+            library_layers: std::env::var("LIBRARY_LAYERS")
+                .map(|value| LibraryLayerConfig::parse_list(&value))
+                .unwrap_or_default(),
             sandboxed_repo_path: std::env::var("SANDBOXED_SH_REPO_PATH")
                 .or_else(|_| std::env::var("SANDBOXED_REPO_PATH"))
                 .ok()
                 .or_else(|| Some(DEFAULT_SANDBOXED_REPO_PATH.to_string())),
+            tool_policy: ToolPolicy::default(),
+            tool_result_limits: ToolResultLimits::default(),
         }
     }
 
@@ -113,6 +131,26 @@ impl SettingsStore {
         self.settings.read().await.library_remote.clone()
     }
 
+    /// Get the extra library layers, in priority order.
+    pub async fn get_library_layers(&self) -> Vec<LibraryLayerConfig> {
+        self.settings.read().await.library_layers.clone()
+    }
+
+    /// Replace the extra library layers. Returns whether they changed.
+    pub async fn set_library_layers(
+        &self,
+        layers: Vec<LibraryLayerConfig>,
+    ) -> Result<bool, std::io::Error> {
+        let mut settings = self.settings.write().await;
+        if settings.library_layers == layers {
+            return Ok(false);
+        }
+        settings.library_layers = layers;
+        drop(settings); // Release lock before saving
+        self.save_to_disk().await?;
+        Ok(true)
+    }
+
     /// Get the configured sandboxed.sh repo path.
     pub async fn get_sandboxed_repo_path(&self) -> Option<String> {
         self.settings.read().await.sandboxed_repo_path.clone()
@@ -138,6 +176,35 @@ impl SettingsStore {
         }
     }
 
+    /// Get the tool permission policy.
+    pub async fn get_tool_policy(&self) -> ToolPolicy {
+        self.settings.read().await.tool_policy.clone()
+    }
+
+    /// Replace the tool permission policy.
+    pub async fn set_tool_policy(&self, policy: ToolPolicy) -> Result<(), std::io::Error> {
+        let mut settings = self.settings.write().await;
+        settings.tool_policy = policy;
+        drop(settings); // Release lock before saving
+        self.save_to_disk().await
+    }
+
+    /// Get the tool result size limits.
+    pub async fn get_tool_result_limits(&self) -> ToolResultLimits {
+        self.settings.read().await.tool_result_limits.clone()
+    }
+
+    /// Replace the tool result size limits.
+    pub async fn set_tool_result_limits(
+        &self,
+        limits: ToolResultLimits,
+    ) -> Result<(), std::io::Error> {
+        let mut settings = self.settings.write().await;
+        settings.tool_result_limits = limits;
+        drop(settings); // Release lock before saving
+        self.save_to_disk().await
+    }
+
     /// Update multiple settings at once.
     pub async fn update(&self, new_settings: Settings) -> Result<(), std::io::Error> {
         let mut settings = self.settings.write().await;
@@ -162,3 +229,16 @@ impl SettingsStore {
 
 /// Shared settings store wrapped in Arc for concurrent access.
 pub type SharedSettingsStore = Arc<SettingsStore>;
+
+static GLOBAL_SETTINGS: OnceLock<SharedSettingsStore> = OnceLock::new();
+
+/// Make the settings store available to code that isn't handed `AppState`
+/// (e.g. the control loop building an agent's tool policy).
+pub fn set_global(store: SharedSettingsStore) {
+    let _ = GLOBAL_SETTINGS.set(store);
+}
+
+/// The server's settings store, if one was registered.
+pub fn global() -> Option<SharedSettingsStore> {
+    GLOBAL_SETTINGS.get().cloned()
+}
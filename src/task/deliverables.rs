@@ -1,13 +1,17 @@
 //! Deliverable extraction from user prompts.
 //!
-//! Parses user messages to identify expected deliverables (files, reports, etc.)
-//! that must exist for a task to be considered complete.
+//! Parses user messages to identify expected deliverables (files, reports,
+//! commits, pull requests, created resources, published URLs) that must exist
+//! for a task to be considered complete. Files and directories are checked on
+//! disk; the others are looked for in the agent's output.
 
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
 /// A deliverable that the user expects from the task.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
 pub enum Deliverable {
     /// A file that should be created at a specific path.
     File {
@@ -21,6 +25,18 @@ pub enum Deliverable {
         topic: String,
         expected_path: Option<PathBuf>,
     },
+    /// Changes committed (and possibly pushed) with git.
+    GitCommit { branch: Option<String> },
+    /// A pull or merge request.
+    PullRequest { repo: Option<String> },
+    /// Something created outside the filesystem, e.g. a workspace or a
+    /// release.
+    Resource {
+        resource_type: String,
+        name: Option<String>,
+    },
+    /// Content published or deployed at a URL.
+    Url { url: String },
 }
 
 impl Deliverable {
@@ -30,6 +46,89 @@ impl Deliverable {
             Deliverable::File { path, .. } => Some(path),
             Deliverable::Directory { path } => Some(path),
             Deliverable::Report { expected_path, .. } => expected_path.as_ref(),
+            Deliverable::GitCommit { .. }
+            | Deliverable::PullRequest { .. }
+            | Deliverable::Resource { .. }
+            | Deliverable::Url { .. } => None,
+        }
+    }
+
+    /// Short description: the path for files and directories.
+    pub fn describe(&self) -> String {
+        match self {
+            Deliverable::File { path, .. } | Deliverable::Directory { path } => {
+                path.display().to_string()
+            }
+            Deliverable::Report {
+                expected_path: Some(path),
+                ..
+            } => path.display().to_string(),
+            Deliverable::Report { topic, .. } => format!("report on {}", topic),
+            Deliverable::GitCommit {
+                branch: Some(branch),
+            } => {
+                format!("git commit on {}", branch)
+            }
+            Deliverable::GitCommit { branch: None } => "git commit".to_string(),
+            Deliverable::PullRequest { repo: Some(repo) } => format!("pull request on {}", repo),
+            Deliverable::PullRequest { repo: None } => "pull request".to_string(),
+            Deliverable::Resource {
+                resource_type,
+                name: Some(name),
+            } => format!("new {} `{}`", resource_type, name),
+            Deliverable::Resource {
+                resource_type,
+                name: None,
+            } => format!("new {}", resource_type),
+            Deliverable::Url { url } => url.clone(),
+        }
+    }
+
+    /// Whether this deliverable was produced. Files and directories are
+    /// checked on disk; commits, pull requests, resources and URLs must show
+    /// up in `output` (the agent's messages).
+    pub async fn is_delivered(&self, output: &str) -> bool {
+        match self {
+            Deliverable::File { .. }
+            | Deliverable::Directory { .. }
+            | Deliverable::Report { .. } => self.exists().await,
+            _ => self.found_in(output),
+        }
+    }
+
+    /// Evidence of a non-file deliverable in the agent's output.
+    fn found_in(&self, output: &str) -> bool {
+        match self {
+            Deliverable::GitCommit { .. } => Regex::new(
+                r"(?i)\bcommit(?:ted)?\b[^\n]{0,80}?\b[0-9a-f]{7,40}\b|\[[\w./-]+(?: \(root-commit\))? [0-9a-f]{7,40}\]",
+            )
+            .unwrap()
+            .is_match(output),
+            Deliverable::PullRequest { .. } => {
+                Regex::new(r"https?://\S+/(?:pull|pulls|merge_requests)/\d+")
+                    .unwrap()
+                    .is_match(output)
+            }
+            Deliverable::Resource {
+                resource_type,
+                name,
+            } => {
+                let output = output.to_lowercase();
+                match name {
+                    Some(name) => output.contains(&name.to_lowercase()),
+                    None => Regex::new(&format!(
+                        r"\b(?:created|set up|provisioned)\b[^\n]*\b{}\b|\b{}\b[^\n]*\b(?:created|set up|provisioned)\b",
+                        regex::escape(resource_type),
+                        regex::escape(resource_type)
+                    ))
+                    .map(|re| re.is_match(&output))
+                    .unwrap_or(false),
+                }
+            }
+            Deliverable::Url { url } => output.contains(url.trim_end_matches('/')),
+            Deliverable::File { .. } | Deliverable::Directory { .. } | Deliverable::Report { .. } => {
+                false
+            }
         }
     }
 
@@ -49,12 +148,17 @@ impl Deliverable {
                     true
                 }
             }
+            // Not on the filesystem; see `is_delivered`
+            Deliverable::GitCommit { .. }
+            | Deliverable::PullRequest { .. }
+            | Deliverable::Resource { .. }
+            | Deliverable::Url { .. } => true,
         }
     }
 }
 
 /// Result of deliverable extraction.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct DeliverableSet {
     pub deliverables: Vec<Deliverable>,
     /// Keywords that suggest the task is research/analysis (may not have file deliverables)
@@ -85,6 +189,18 @@ impl DeliverableSet {
         true
     }
 
+    /// Describe the deliverables not produced yet, given the agent's output
+    /// so far.
+    pub async fn missing_descriptions(&self, output: &str) -> Vec<String> {
+        let mut missing = Vec::new();
+        for d in &self.deliverables {
+            if !d.is_delivered(output).await {
+                missing.push(d.describe());
+            }
+        }
+        missing
+    }
+
     /// Get paths of missing deliverables.
     pub async fn missing_paths(&self) -> Vec<String> {
         let mut paths = Vec::new();
@@ -208,6 +324,68 @@ pub fn extract_deliverables(message: &str) -> DeliverableSet {
         }
     }
 
+    // Pattern 5: Git commits, e.g. "commit the changes to the main branch"
+    let commit_pattern = Regex::new(
+        r"(?i)\b(?:commit|push)\s+(?:the\s+|your\s+|all\s+|these\s+|those\s+)?(?:changes|work|code|fixes|fix|results?|updates?)\b",
+    )
+    .unwrap();
+    if commit_pattern.is_match(message) {
+        let branch = Regex::new(r"(?i)\bbranch\s+[`'\x22]?([\w./-]+[\w/-])|\b(?:to|on)\s+(?:the\s+)?[`'\x22]?([\w./-]+)[`'\x22]?\s+branch\b")
+            .unwrap()
+            .captures(message)
+            .and_then(|cap| cap.get(1).or_else(|| cap.get(2)))
+            .map(|m| m.as_str().to_string());
+        deliverables.push(Deliverable::GitCommit { branch });
+    }
+
+    // Pattern 6: Pull/merge requests, e.g. "open a PR against github.com/org/repo"
+    let pr_pattern = Regex::new(
+        r"(?i)\b(?:open|create|submit|make|file|raise)\s+(?:a\s+|an\s+|the\s+)?(?:draft\s+)?(?:pull\s+request|merge\s+request|PR|MR)\b",
+    )
+    .unwrap();
+    if pr_pattern.is_match(message) {
+        let repo = Regex::new(r"(?:https?://)?((?:github\.com|gitlab\.com)/[\w.-]+/[\w.-]+[\w-])")
+            .unwrap()
+            .captures(message)
+            .map(|cap| cap[1].to_string());
+        deliverables.push(Deliverable::PullRequest { repo });
+    }
+
+    // Pattern 7: Created resources, e.g. "create a new workspace called staging"
+    let resource_pattern = Regex::new(
+        r"(?i)\b(?:create|set\s+up|provision|spin\s+up|add)\s+(?:a\s+|an\s+)?(?:new\s+)?(workspace|automation|database|bucket|repository|repo|issue|release|tag|webhook|branch)\b(?:\s+(?:called|named)\s+[`'\x22]?([\w.-]+[\w-]))?",
+    )
+    .unwrap();
+    for cap in resource_pattern.captures_iter(message) {
+        let kind = cap[1].to_lowercase();
+        let kind = if kind == "repo" {
+            "repository".to_string()
+        } else {
+            kind
+        };
+        let name = cap.get(2).map(|m| m.as_str().to_string());
+        let resource = Deliverable::Resource {
+            resource_type: kind,
+            name,
+        };
+        if !deliverables.contains(&resource) {
+            deliverables.push(resource);
+        }
+    }
+
+    // Pattern 8: URLs something is published at, e.g. "deploy the site to https://..."
+    let url_pattern = Regex::new(
+        r"(?i)\b(?:deploy|publish|upload|host|serve|release)\w*(?:\s+\w+)*?\s+(?:at|to|on)\s+(https?://[^\s<>`'\x22)]+)",
+    )
+    .unwrap();
+    for cap in url_pattern.captures_iter(message) {
+        let url = cap[1].trim_end_matches(['.', ',', ';', ':']).to_string();
+        let deliverable = Deliverable::Url { url };
+        if !deliverables.contains(&deliverable) {
+            deliverables.push(deliverable);
+        }
+    }
+
     // If requires_report but no explicit path found, add a generic report expectation
     if requires_report
         && !deliverables
@@ -302,4 +480,51 @@ Tasks:
         let result = extract_deliverables(msg);
         assert!(result.deliverables.len() >= 2);
     }
+
+    #[test]
+    fn test_extract_non_file_deliverables() {
+        let msg = "Fix the flaky test, commit the changes to the `release/1.2` branch and \
+                   open a PR on github.com/acme/api. Then create a new workspace called \
+                   staging-eu and deploy the docs to https://docs.acme.dev/api.";
+        let result = extract_deliverables(msg);
+        assert!(result.deliverables.contains(&Deliverable::GitCommit {
+            branch: Some("release/1.2".to_string())
+        }));
+        assert!(result.deliverables.contains(&Deliverable::PullRequest {
+            repo: Some("github.com/acme/api".to_string())
+        }));
+        assert!(result.deliverables.contains(&Deliverable::Resource {
+            resource_type: "workspace".to_string(),
+            name: Some("staging-eu".to_string())
+        }));
+        assert!(result.deliverables.contains(&Deliverable::Url {
+            url: "https://docs.acme.dev/api".to_string()
+        }));
+        assert!(result.deliverables.iter().all(|d| d.path().is_none()));
+    }
+
+    #[tokio::test]
+    async fn test_non_file_deliverables_found_in_output() {
+        let set = DeliverableSet {
+            deliverables: vec![
+                Deliverable::GitCommit { branch: None },
+                Deliverable::PullRequest { repo: None },
+                Deliverable::Resource {
+                    resource_type: "release".to_string(),
+                    name: None,
+                },
+            ],
+            is_research_task: false,
+            requires_report: false,
+        };
+        assert_eq!(
+            set.missing_descriptions("Working on it.").await,
+            vec!["git commit", "pull request", "new release"]
+        );
+
+        let output = "[main 3f2a9c1] Fix flaky test\n\
+                      Opened https://github.com/acme/api/pull/42\n\
+                      Release v1.2.0 created.";
+        assert!(set.missing_descriptions(output).await.is_empty());
+    }
 }
@@ -0,0 +1,993 @@
+//! Archive tools: create and extract zip, tar and tar.gz files.
+//!
+//! Extraction never writes outside the destination directory: entries with
+//! absolute paths or `..` components (zip-slip) are skipped, as are symlinks,
+//! hard links and device files. The amount of data written is counted as it
+//! is decompressed, so archive bombs stop at the size limit.
+
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use super::{resolve_path, Tool};
+
+/// Default cap on bytes written by one extraction.
+const DEFAULT_MAX_EXTRACT_BYTES: u64 = 1024 * 1024 * 1024;
+/// Default cap on files written by one extraction.
+const DEFAULT_MAX_EXTRACT_FILES: usize = 10_000;
+/// Cap on the uncompressed size of a created archive.
+const MAX_CREATE_BYTES: u64 = 4 * 1024 * 1024 * 1024;
+/// Entries shown when listing an archive.
+const MAX_LISTED_ENTRIES: usize = 500;
+const TAR_BLOCK: usize = 512;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArchiveFormat {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+impl ArchiveFormat {
+    fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+        if name == "zip" || name.ends_with(".zip") {
+            Some(Self::Zip)
+        } else if name == "tar.gz"
+            || name == "tgz"
+            || name.ends_with(".tar.gz")
+            || name.ends_with(".tgz")
+        {
+            Some(Self::TarGz)
+        } else if name == "tar" || name.ends_with(".tar") {
+            Some(Self::Tar)
+        } else {
+            None
+        }
+    }
+
+    /// Detect the format from the first bytes of a file.
+    fn sniff(head: &[u8]) -> Option<Self> {
+        if head.starts_with(b"PK\x03\x04") || head.starts_with(b"PK\x05\x06") {
+            Some(Self::Zip)
+        } else if head.starts_with(&[0x1f, 0x8b]) {
+            Some(Self::TarGz)
+        } else if head.get(257..262) == Some(b"ustar") {
+            Some(Self::Tar)
+        } else {
+            None
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Zip => "zip",
+            Self::Tar => "tar",
+            Self::TarGz => "tar.gz",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EntryKind {
+    File,
+    Dir,
+    /// Symlinks, hard links and special files; never extracted.
+    Other,
+}
+
+/// Normalize an archive entry name into a relative path, or `None` if it
+/// would escape the destination (absolute path, drive prefix or `..`).
+fn safe_entry_path(name: &str) -> Option<PathBuf> {
+    let name = name.replace('\\', "/");
+    if name.starts_with('/') {
+        return None;
+    }
+    let mut path = PathBuf::new();
+    for (i, part) in name.split('/').enumerate() {
+        match part {
+            "" | "." => {}
+            ".." => return None,
+            _ if i == 0 && part.len() == 2 && part.ends_with(':') => return None,
+            _ => path.push(part),
+        }
+    }
+    (!path.as_os_str().is_empty()).then_some(path)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Extraction
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Writes entries under a destination directory within the configured limits.
+struct Extractor {
+    dest: PathBuf,
+    /// Only record entries instead of writing them.
+    list_only: bool,
+    overwrite: bool,
+    max_bytes: u64,
+    max_files: usize,
+    files: usize,
+    dirs: usize,
+    bytes: u64,
+    listing: Vec<String>,
+    skipped: Vec<String>,
+}
+
+impl Extractor {
+    fn entry(
+        &mut self,
+        name: &str,
+        kind: EntryKind,
+        size: u64,
+        mode: Option<u32>,
+        data: &mut dyn Read,
+    ) -> anyhow::Result<()> {
+        if self.list_only {
+            let label = match kind {
+                EntryKind::File => "file",
+                EntryKind::Dir => "dir ",
+                EntryKind::Other => "link",
+            };
+            self.listing
+                .push(format!("{} {:>12}  {}", label, size, name));
+            if kind == EntryKind::Dir {
+                self.dirs += 1;
+            } else {
+                self.files += 1;
+                self.bytes += size;
+            }
+            return Ok(());
+        }
+
+        let Some(relative) = safe_entry_path(name) else {
+            self.skipped
+                .push(format!("{} (path escapes the destination)", name));
+            return Ok(());
+        };
+        match kind {
+            EntryKind::Other => {
+                self.skipped.push(format!(
+                    "{} (links and special files are not extracted)",
+                    name
+                ));
+            }
+            EntryKind::Dir => {
+                let target = self.checked_target(&relative)?;
+                std::fs::create_dir_all(&target)?;
+                self.dirs += 1;
+            }
+            EntryKind::File => {
+                if self.files >= self.max_files {
+                    return Err(anyhow::anyhow!(
+                        "Archive has more than {} files; raise max_files to extract it",
+                        self.max_files
+                    ));
+                }
+                let target = self.checked_target(&relative)?;
+                if target.exists() && !self.overwrite {
+                    return Err(anyhow::anyhow!(
+                        "{} already exists; pass overwrite=true to replace existing files",
+                        target.display()
+                    ));
+                }
+                if let Some(parent) = target.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                let remaining = self.max_bytes - self.bytes;
+                let mut out = File::create(&target)?;
+                // Count what is actually decompressed; declared sizes can lie.
+                let written = io::copy(&mut data.take(remaining + 1), &mut out)?;
+                if written > remaining {
+                    drop(out);
+                    let _ = std::fs::remove_file(&target);
+                    return Err(anyhow::anyhow!(
+                        "Extraction stopped: archive expands beyond {} bytes (raise max_bytes if this is expected)",
+                        self.max_bytes
+                    ));
+                }
+                #[cfg(unix)]
+                if let Some(mode) = mode {
+                    use std::os::unix::fs::PermissionsExt;
+                    // Keep permission bits only; never setuid/setgid.
+                    let perms = std::fs::Permissions::from_mode((mode & 0o777) | 0o600);
+                    std::fs::set_permissions(&target, perms)?;
+                }
+                #[cfg(not(unix))]
+                let _ = mode;
+                self.files += 1;
+                self.bytes += written;
+            }
+        }
+        Ok(())
+    }
+
+    /// Join an entry path onto the destination, refusing to pass through
+    /// existing symlinks (which could point outside it).
+    fn checked_target(&self, relative: &Path) -> anyhow::Result<PathBuf> {
+        let mut current = self.dest.clone();
+        for component in relative.components() {
+            current.push(component);
+            if std::fs::symlink_metadata(&current)
+                .map(|m| m.file_type().is_symlink())
+                .unwrap_or(false)
+            {
+                return Err(anyhow::anyhow!(
+                    "Refusing to extract through symlink {}",
+                    current.display()
+                ));
+            }
+        }
+        Ok(current)
+    }
+}
+
+fn extract_zip(path: &Path, extractor: &mut Extractor) -> anyhow::Result<()> {
+    let mut archive = zip::ZipArchive::new(BufReader::new(File::open(path)?))
+        .map_err(|e| anyhow::anyhow!("Invalid zip file: {}", e))?;
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        let name = file.name().to_string();
+        let kind = if file.is_dir() {
+            EntryKind::Dir
+        } else if file.is_symlink() {
+            EntryKind::Other
+        } else {
+            EntryKind::File
+        };
+        let (size, mode) = (file.size(), file.unix_mode());
+        extractor.entry(&name, kind, size, mode, &mut file)?;
+    }
+    Ok(())
+}
+
+/// Extract a tar or tar.gz archive held in memory into `dest`, with the same
+/// path, link and size checks as `archive_extract`. Used by callers outside
+/// the tool (e.g. skill imports) that download archives themselves.
+pub fn extract_tarball(
+    data: &[u8],
+    dest: &Path,
+    max_bytes: u64,
+    max_files: usize,
+) -> anyhow::Result<()> {
+    let mut extractor = Extractor {
+        dest: dest.to_path_buf(),
+        list_only: false,
+        overwrite: false,
+        max_bytes,
+        max_files,
+        files: 0,
+        dirs: 0,
+        bytes: 0,
+        listing: Vec::new(),
+        skipped: Vec::new(),
+    };
+    std::fs::create_dir_all(dest)?;
+    match ArchiveFormat::sniff(data) {
+        Some(ArchiveFormat::TarGz) => {
+            extract_tar(&mut flate2::read::GzDecoder::new(data), &mut extractor)
+        }
+        Some(ArchiveFormat::Tar) => extract_tar(&mut &data[..], &mut extractor),
+        _ => Err(anyhow::anyhow!("Not a tar or tar.gz archive")),
+    }
+}
+
+fn parse_octal(field: &[u8]) -> u64 {
+    // GNU base-256 encoding for large values.
+    if field.first().is_some_and(|b| b & 0x80 != 0) {
+        return field[1..]
+            .iter()
+            .fold(u64::from(field[0] & 0x7f), |acc, &b| {
+                acc << 8 | u64::from(b)
+            });
+    }
+    let text: String = field
+        .iter()
+        .take_while(|&&b| b != 0)
+        .map(|&b| b as char)
+        .collect();
+    u64::from_str_radix(text.trim(), 8).unwrap_or(0)
+}
+
+fn header_string(field: &[u8]) -> String {
+    let end = field.iter().position(|&b| b == 0).unwrap_or(field.len());
+    String::from_utf8_lossy(&field[..end]).into_owned()
+}
+
+fn read_block(reader: &mut dyn Read, block: &mut [u8; TAR_BLOCK]) -> anyhow::Result<bool> {
+    let mut filled = 0;
+    while filled < TAR_BLOCK {
+        match reader.read(&mut block[filled..])? {
+            0 if filled == 0 => return Ok(false),
+            0 => return Err(anyhow::anyhow!("Truncated tar archive")),
+            n => filled += n,
+        }
+    }
+    Ok(true)
+}
+
+fn padding(size: u64) -> u64 {
+    (TAR_BLOCK as u64 - size % TAR_BLOCK as u64) % TAR_BLOCK as u64
+}
+
+/// Walk a tar stream (ustar, GNU long names and pax paths).
+fn extract_tar(reader: &mut dyn Read, extractor: &mut Extractor) -> anyhow::Result<()> {
+    let mut block = [0u8; TAR_BLOCK];
+    let mut long_name: Option<String> = None;
+    while read_block(reader, &mut block)? {
+        if block.iter().all(|&b| b == 0) {
+            break;
+        }
+        let stored_sum = parse_octal(&block[148..156]);
+        let actual_sum: u64 = block
+            .iter()
+            .enumerate()
+            .map(|(i, &b)| {
+                if (148..156).contains(&i) {
+                    32
+                } else {
+                    u64::from(b)
+                }
+            })
+            .sum();
+        if stored_sum != actual_sum {
+            return Err(anyhow::anyhow!("Invalid tar header (checksum mismatch)"));
+        }
+
+        let mut name = header_string(&block[..100]);
+        if &block[257..262] == b"ustar" {
+            let prefix = header_string(&block[345..500]);
+            if !prefix.is_empty() {
+                name = format!("{}/{}", prefix, name);
+            }
+        }
+        let size = parse_octal(&block[124..136]);
+        let mode = parse_octal(&block[100..108]) as u32;
+        let typeflag = block[156];
+
+        let mut data = (&mut *reader).take(size);
+        match typeflag {
+            b'L' => {
+                let mut buf = Vec::new();
+                data.read_to_end(&mut buf)?;
+                long_name = Some(header_string(&buf));
+            }
+            b'x' => {
+                let mut buf = Vec::new();
+                data.read_to_end(&mut buf)?;
+                if let Some(path) = pax_path(&buf) {
+                    long_name = Some(path);
+                }
+            }
+            b'g' => {
+                io::copy(&mut data, &mut io::sink())?;
+            }
+            _ => {
+                let name = long_name.take().unwrap_or(name);
+                let kind = match typeflag {
+                    b'0' | 0 | b'7' => EntryKind::File,
+                    b'5' => EntryKind::Dir,
+                    _ => EntryKind::Other,
+                };
+                let size = if kind == EntryKind::File { size } else { 0 };
+                extractor.entry(&name, kind, size, Some(mode), &mut data)?;
+                io::copy(&mut data, &mut io::sink())?;
+            }
+        }
+        io::copy(&mut (&mut *reader).take(padding(size)), &mut io::sink())?;
+    }
+    Ok(())
+}
+
+/// The `path` record of a pax extended header.
+fn pax_path(records: &[u8]) -> Option<String> {
+    let text = String::from_utf8_lossy(records);
+    text.lines().find_map(|line| {
+        let (_, record) = line.split_once(' ')?;
+        record.strip_prefix("path=").map(str::to_string)
+    })
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Creation
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// A file or directory to add, with its name inside the archive.
+struct Input {
+    path: PathBuf,
+    name: String,
+    is_dir: bool,
+    size: u64,
+    mode: u32,
+    mtime: u64,
+}
+
+/// Expand the requested paths into archive entries, named relative to
+/// `base` (or by file name when outside it). Symlinks are skipped.
+fn collect_inputs(
+    paths: &[PathBuf],
+    base: &Path,
+    output: &Path,
+) -> anyhow::Result<(Vec<Input>, usize)> {
+    let mut inputs = Vec::new();
+    let mut skipped_links = 0;
+    for root in paths {
+        let root_name = match root.strip_prefix(base) {
+            Ok(relative) if !relative.as_os_str().is_empty() => relative.to_path_buf(),
+            _ => PathBuf::from(
+                root.file_name()
+                    .ok_or_else(|| anyhow::anyhow!("Cannot archive {}", root.display()))?,
+            ),
+        };
+        for entry in walkdir::WalkDir::new(root)
+            .follow_links(false)
+            .sort_by_file_name()
+        {
+            let entry = entry?;
+            if entry.path_is_symlink() {
+                skipped_links += 1;
+                continue;
+            }
+            if entry.path() == output {
+                continue;
+            }
+            let metadata = entry.metadata()?;
+            let relative = entry.path().strip_prefix(root).unwrap_or(Path::new(""));
+            let name = root_name
+                .join(relative)
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+                .join("/");
+            #[cfg(unix)]
+            let mode = {
+                use std::os::unix::fs::PermissionsExt;
+                metadata.permissions().mode() & 0o7777
+            };
+            #[cfg(not(unix))]
+            let mode = if metadata.is_dir() { 0o755 } else { 0o644 };
+            inputs.push(Input {
+                path: entry.path().to_path_buf(),
+                name,
+                is_dir: metadata.is_dir(),
+                size: if metadata.is_dir() { 0 } else { metadata.len() },
+                mode,
+                mtime: metadata
+                    .modified()
+                    .ok()
+                    .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                    .map_or(0, |d| d.as_secs()),
+            });
+        }
+    }
+    Ok((inputs, skipped_links))
+}
+
+fn write_zip(output: &Path, inputs: &[Input]) -> anyhow::Result<()> {
+    let mut writer = zip::ZipWriter::new(BufWriter::new(File::create(output)?));
+    for input in inputs {
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated)
+            .unix_permissions(input.mode)
+            .large_file(input.size >= u64::from(u32::MAX));
+        if input.is_dir {
+            writer.add_directory(format!("{}/", input.name), options)?;
+        } else {
+            writer.start_file(input.name.as_str(), options)?;
+            io::copy(&mut File::open(&input.path)?, &mut writer)?;
+        }
+    }
+    writer.finish()?.flush()?;
+    Ok(())
+}
+
+/// Build the header block(s) for one tar entry, using a GNU long-name record
+/// when the name doesn't fit in 100 bytes.
+fn tar_headers(name: &str, size: u64, mode: u32, mtime: u64, typeflag: u8) -> Vec<u8> {
+    fn header(name: &[u8], size: u64, mode: u32, mtime: u64, typeflag: u8) -> [u8; TAR_BLOCK] {
+        let mut block = [0u8; TAR_BLOCK];
+        let name_len = name.len().min(100);
+        block[..name_len].copy_from_slice(&name[..name_len]);
+        block[100..108].copy_from_slice(format!("{:07o}\0", mode & 0o7777).as_bytes());
+        block[108..116].copy_from_slice(b"0000000\0");
+        block[116..124].copy_from_slice(b"0000000\0");
+        block[124..136].copy_from_slice(format!("{:011o}\0", size).as_bytes());
+        block[136..148].copy_from_slice(format!("{:011o}\0", mtime & 0o77777777777).as_bytes());
+        block[148..156].copy_from_slice(b"        ");
+        block[156] = typeflag;
+        block[257..263].copy_from_slice(b"ustar\0");
+        block[263..265].copy_from_slice(b"00");
+        let sum: u64 = block.iter().map(|&b| u64::from(b)).sum();
+        block[148..156].copy_from_slice(format!("{:06o}\0 ", sum).as_bytes());
+        block
+    }
+
+    let mut out = Vec::new();
+    if name.len() > 100 {
+        let mut long = name.as_bytes().to_vec();
+        long.push(0);
+        out.extend(header(b"././@LongLink", long.len() as u64, 0o644, 0, b'L'));
+        let padded = long.len() + padding(long.len() as u64) as usize;
+        long.resize(padded, 0);
+        out.extend(long);
+    }
+    out.extend(header(name.as_bytes(), size, mode, mtime, typeflag));
+    out
+}
+
+fn write_tar(writer: &mut dyn Write, inputs: &[Input]) -> anyhow::Result<()> {
+    for input in inputs {
+        if input.is_dir {
+            writer.write_all(&tar_headers(
+                &format!("{}/", input.name),
+                0,
+                input.mode,
+                input.mtime,
+                b'5',
+            ))?;
+            continue;
+        }
+        writer.write_all(&tar_headers(
+            &input.name,
+            input.size,
+            input.mode,
+            input.mtime,
+            b'0',
+        ))?;
+        // Write exactly the size recorded in the header even if the file changed.
+        let copied = io::copy(&mut File::open(&input.path)?.take(input.size), writer)?;
+        io::copy(&mut io::repeat(0).take(input.size - copied), writer)?;
+        writer.write_all(&vec![0u8; padding(input.size) as usize])?;
+    }
+    writer.write_all(&[0u8; TAR_BLOCK * 2])?;
+    Ok(())
+}
+
+/// Build a tar.gz archive in memory from `(path, contents)` entries. Used by
+/// callers outside the tool (e.g. skill exports) that serve archives directly.
+pub fn create_tarball(entries: &[(String, Vec<u8>)]) -> anyhow::Result<Vec<u8>> {
+    let mtime = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    for (name, data) in entries {
+        let size = data.len() as u64;
+        encoder.write_all(&tar_headers(name, size, 0o644, mtime, b'0'))?;
+        encoder.write_all(data)?;
+        encoder.write_all(&vec![0u8; padding(size) as usize])?;
+    }
+    encoder.write_all(&[0u8; TAR_BLOCK * 2])?;
+    Ok(encoder.finish()?)
+}
+
+fn create_archive(output: &Path, format: ArchiveFormat, inputs: &[Input]) -> anyhow::Result<()> {
+    match format {
+        ArchiveFormat::Zip => write_zip(output, inputs),
+        ArchiveFormat::Tar => {
+            let mut writer = BufWriter::new(File::create(output)?);
+            write_tar(&mut writer, inputs)?;
+            writer.flush()?;
+            Ok(())
+        }
+        ArchiveFormat::TarGz => {
+            let file = BufWriter::new(File::create(output)?);
+            let mut encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+            write_tar(&mut encoder, inputs)?;
+            encoder.finish()?.flush()?;
+            Ok(())
+        }
+    }
+}
+
+/// Bundle files and directories into a zip, tar or tar.gz archive.
+pub struct ArchiveCreate;
+
+#[async_trait]
+impl Tool for ArchiveCreate {
+    fn name(&self) -> &str {
+        "archive_create"
+    }
+
+    fn description(&self) -> &str {
+        "Bundle files and directories into a .zip, .tar or .tar.gz archive (format chosen from the output name unless 'format' is given). Entries are named relative to the workspace (or 'base_dir'). Symlinks are skipped."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "output": {
+                    "type": "string",
+                    "description": "Archive to create, e.g. 'output/report.zip' or 'dist/site.tar.gz'"
+                },
+                "paths": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Files and directories to include"
+                },
+                "format": {
+                    "type": "string",
+                    "enum": ["zip", "tar", "tar.gz"],
+                    "description": "Optional: archive format (default: from the output extension)"
+                },
+                "base_dir": {
+                    "type": "string",
+                    "description": "Optional: directory entry names are relative to (default: the workspace)"
+                },
+                "overwrite": {
+                    "type": "boolean",
+                    "description": "Optional: replace an existing output file (default: false)"
+                }
+            },
+            "required": ["output", "paths"]
+        })
+    }
+
+    async fn execute(&self, args: Value, working_dir: &Path) -> anyhow::Result<String> {
+        let output = args["output"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing 'output' argument"))?;
+        let paths: Vec<&str> = args["paths"]
+            .as_array()
+            .ok_or_else(|| anyhow::anyhow!("Missing 'paths' argument"))?
+            .iter()
+            .filter_map(Value::as_str)
+            .collect();
+        if paths.is_empty() {
+            return Err(anyhow::anyhow!(
+                "'paths' must list at least one file or directory"
+            ));
+        }
+        let format = ArchiveFormat::from_name(args["format"].as_str().unwrap_or(output))
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Unknown archive format for '{}': use .zip, .tar or .tar.gz",
+                    output
+                )
+            })?;
+        let overwrite = args["overwrite"].as_bool().unwrap_or(false);
+
+        let output_path = resolve_path(output, working_dir).resolved;
+        if output_path.exists() && !overwrite {
+            return Err(anyhow::anyhow!(
+                "{} already exists; pass overwrite=true to replace it",
+                output
+            ));
+        }
+        let base = match args["base_dir"].as_str() {
+            Some(dir) => resolve_path(dir, working_dir).resolved,
+            None => working_dir.to_path_buf(),
+        };
+        let mut roots = Vec::new();
+        for path in paths {
+            let resolved = resolve_path(path, working_dir).resolved;
+            if !resolved.exists() {
+                return Err(anyhow::anyhow!(
+                    "Path not found: {} (resolved to: {})",
+                    path,
+                    resolved.display()
+                ));
+            }
+            roots.push(resolved);
+        }
+
+        let target = output_path.clone();
+        let (files, dirs, bytes, skipped_links) = tokio::task::spawn_blocking(move || {
+            let (inputs, skipped_links) = collect_inputs(&roots, &base, &target)?;
+            let bytes: u64 = inputs.iter().map(|i| i.size).sum();
+            if bytes > MAX_CREATE_BYTES {
+                return Err(anyhow::anyhow!(
+                    "Inputs total {} bytes, over the {} byte archive limit",
+                    bytes,
+                    MAX_CREATE_BYTES
+                ));
+            }
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            if let Err(e) = create_archive(&target, format, &inputs) {
+                let _ = std::fs::remove_file(&target);
+                return Err(e);
+            }
+            let files = inputs.iter().filter(|i| !i.is_dir).count();
+            anyhow::Ok((files, inputs.len() - files, bytes, skipped_links))
+        })
+        .await??;
+
+        let archive_size = tokio::fs::metadata(&output_path).await?.len();
+        let mut result = format!(
+            "Created {} ({}): {} file{}, {} director{}, {} bytes → {} bytes",
+            output,
+            format.label(),
+            files,
+            if files == 1 { "" } else { "s" },
+            dirs,
+            if dirs == 1 { "y" } else { "ies" },
+            bytes,
+            archive_size
+        );
+        if skipped_links > 0 {
+            result.push_str(&format!("\n({} symlink(s) skipped)", skipped_links));
+        }
+        Ok(result)
+    }
+}
+
+/// Extract or list a zip, tar or tar.gz archive.
+pub struct ArchiveExtract;
+
+#[async_trait]
+impl Tool for ArchiveExtract {
+    fn name(&self) -> &str {
+        "archive_extract"
+    }
+
+    fn description(&self) -> &str {
+        "Extract a .zip, .tar or .tar.gz archive into a directory, or list its contents with list_only=true. Entries that would land outside the destination, symlinks and special files are skipped; extraction stops at max_bytes / max_files."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Archive file to extract"
+                },
+                "destination": {
+                    "type": "string",
+                    "description": "Optional: directory to extract into (default: next to the archive, named after it)"
+                },
+                "list_only": {
+                    "type": "boolean",
+                    "description": "Optional: only list entries without extracting (default: false)"
+                },
+                "overwrite": {
+                    "type": "boolean",
+                    "description": "Optional: replace existing files (default: false)"
+                },
+                "max_bytes": {
+                    "type": "integer",
+                    "description": "Optional: maximum total bytes to write (default: 1 GiB)"
+                },
+                "max_files": {
+                    "type": "integer",
+                    "description": "Optional: maximum number of files to write (default: 10000)"
+                }
+            },
+            "required": ["path"]
+        })
+    }
+
+    async fn execute(&self, args: Value, working_dir: &Path) -> anyhow::Result<String> {
+        let path = args["path"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing 'path' argument"))?;
+        let archive_path = resolve_path(path, working_dir).resolved;
+        if !archive_path.is_file() {
+            return Err(anyhow::anyhow!(
+                "Archive not found: {} (resolved to: {})",
+                path,
+                archive_path.display()
+            ));
+        }
+
+        let mut head = [0u8; TAR_BLOCK];
+        let head_len = {
+            let mut file = tokio::fs::File::open(&archive_path).await?;
+            tokio::io::AsyncReadExt::read(&mut file, &mut head).await?
+        };
+        let format = ArchiveFormat::sniff(&head[..head_len])
+            .or_else(|| ArchiveFormat::from_name(path))
+            .ok_or_else(|| anyhow::anyhow!("{} is not a zip, tar or tar.gz archive", path))?;
+
+        let dest = match args["destination"].as_str() {
+            Some(dir) => resolve_path(dir, working_dir).resolved,
+            None => {
+                let file_name = archive_path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                let lower = file_name.to_ascii_lowercase();
+                let stem_len = [".tar.gz", ".tgz", ".tar", ".zip"]
+                    .iter()
+                    .find(|ext| lower.ends_with(*ext))
+                    .map_or(file_name.len(), |ext| file_name.len() - ext.len());
+                archive_path
+                    .parent()
+                    .unwrap_or(working_dir)
+                    .join(&file_name[..stem_len])
+            }
+        };
+
+        let mut extractor = Extractor {
+            dest: dest.clone(),
+            list_only: args["list_only"].as_bool().unwrap_or(false),
+            overwrite: args["overwrite"].as_bool().unwrap_or(false),
+            max_bytes: args["max_bytes"]
+                .as_u64()
+                .unwrap_or(DEFAULT_MAX_EXTRACT_BYTES),
+            max_files: args["max_files"]
+                .as_u64()
+                .map(|n| n as usize)
+                .unwrap_or(DEFAULT_MAX_EXTRACT_FILES),
+            files: 0,
+            dirs: 0,
+            bytes: 0,
+            listing: Vec::new(),
+            skipped: Vec::new(),
+        };
+
+        let extractor = tokio::task::spawn_blocking(move || {
+            if !extractor.list_only {
+                std::fs::create_dir_all(&extractor.dest)?;
+            }
+            match format {
+                ArchiveFormat::Zip => extract_zip(&archive_path, &mut extractor)?,
+                ArchiveFormat::Tar => {
+                    let mut reader = BufReader::new(File::open(&archive_path)?);
+                    extract_tar(&mut reader, &mut extractor)?;
+                }
+                ArchiveFormat::TarGz => {
+                    let file = BufReader::new(File::open(&archive_path)?);
+                    let mut reader = flate2::read::GzDecoder::new(file);
+                    extract_tar(&mut reader, &mut extractor)?;
+                }
+            }
+            anyhow::Ok(extractor)
+        })
+        .await??;
+
+        let mut result = if extractor.list_only {
+            let total = extractor.listing.len();
+            let mut listing = format!(
+                "{} ({}): {} file{}, {} director{}, {} bytes uncompressed\n\n",
+                path,
+                format.label(),
+                extractor.files,
+                if extractor.files == 1 { "" } else { "s" },
+                extractor.dirs,
+                if extractor.dirs == 1 { "y" } else { "ies" },
+                extractor.bytes
+            );
+            listing.push_str(
+                &extractor
+                    .listing
+                    .iter()
+                    .take(MAX_LISTED_ENTRIES)
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            );
+            if total > MAX_LISTED_ENTRIES {
+                listing.push_str(&format!(
+                    "\n... ({} more entries)",
+                    total - MAX_LISTED_ENTRIES
+                ));
+            }
+            listing
+        } else {
+            format!(
+                "Extracted {} file{} ({} bytes) and {} director{} to {}",
+                extractor.files,
+                if extractor.files == 1 { "" } else { "s" },
+                extractor.bytes,
+                extractor.dirs,
+                if extractor.dirs == 1 { "y" } else { "ies" },
+                dest.display()
+            )
+        };
+        if !extractor.skipped.is_empty() {
+            result.push_str(&format!(
+                "\n\nSkipped {} entr{}:\n{}",
+                extractor.skipped.len(),
+                if extractor.skipped.len() == 1 {
+                    "y"
+                } else {
+                    "ies"
+                },
+                extractor
+                    .skipped
+                    .iter()
+                    .take(20)
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ));
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_archive_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let long_dir = "d".repeat(120);
+        std::fs::create_dir_all(dir.path().join("site/assets").join(&long_dir)).unwrap();
+        std::fs::write(dir.path().join("site/index.html"), "<h1>hi</h1>").unwrap();
+        std::fs::write(
+            dir.path()
+                .join("site/assets")
+                .join(&long_dir)
+                .join("app.js"),
+            "console.log(1)",
+        )
+        .unwrap();
+
+        for output in ["out/site.zip", "out/site.tar.gz", "out/site.tar"] {
+            let created = ArchiveCreate
+                .execute(json!({"output": output, "paths": ["site"]}), dir.path())
+                .await
+                .unwrap();
+            assert!(created.contains("2 files"), "{}", created);
+
+            let listed = ArchiveExtract
+                .execute(json!({"path": output, "list_only": true}), dir.path())
+                .await
+                .unwrap();
+            assert!(listed.contains("site/index.html"), "{}", listed);
+
+            let dest = format!("unpacked-{}", output.replace(['/', '.'], "-"));
+            ArchiveExtract
+                .execute(json!({"path": output, "destination": dest}), dir.path())
+                .await
+                .unwrap();
+            let js = dir
+                .path()
+                .join(&dest)
+                .join("site/assets")
+                .join(&long_dir)
+                .join("app.js");
+            assert_eq!(std::fs::read_to_string(js).unwrap(), "console.log(1)");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_archive_extract_blocks_zip_slip_and_bombs() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut tar = tar_headers("../escape.txt", 4, 0o644, 0, b'0');
+        tar.extend(b"evil");
+        tar.extend(vec![0u8; padding(4) as usize]);
+        tar.extend(tar_headers("ok.txt", 2, 0o644, 0, b'0'));
+        tar.extend(b"ok");
+        tar.extend(vec![0u8; padding(2) as usize + TAR_BLOCK * 2]);
+        std::fs::write(dir.path().join("bad.tar"), &tar).unwrap();
+
+        let result = ArchiveExtract
+            .execute(json!({"path": "bad.tar", "destination": "out"}), dir.path())
+            .await
+            .unwrap();
+        assert!(result.contains("../escape.txt (path escapes the destination)"));
+        assert!(!dir.path().join("escape.txt").exists());
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("out/ok.txt")).unwrap(),
+            "ok"
+        );
+
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        writer
+            .start_file("big.bin", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(&[0u8; 4096]).unwrap();
+        let zip = writer.finish().unwrap().into_inner();
+        std::fs::write(dir.path().join("big.zip"), zip).unwrap();
+        let err = ArchiveExtract
+            .execute(json!({"path": "big.zip", "max_bytes": 1000}), dir.path())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("beyond 1000 bytes"));
+        assert!(!dir.path().join("big/big.bin").exists());
+
+        assert_eq!(safe_entry_path("a/./b//c"), Some(PathBuf::from("a/b/c")));
+        assert_eq!(safe_entry_path("/etc/passwd"), None);
+        assert_eq!(safe_entry_path("C:\\windows"), None);
+    }
+}
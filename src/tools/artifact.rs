@@ -0,0 +1,100 @@
+//! Artifact registration: lets the agent mark output files as results of the
+//! mission. The control loop picks up each call and records the file's path,
+//! size and hash, so it can be listed and downloaded from the dashboard.
+
+use std::path::Path;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use super::{resolve_path_simple, Tool};
+
+/// Register an output file of the mission.
+pub struct RegisterArtifact;
+
+#[async_trait]
+impl Tool for RegisterArtifact {
+    fn name(&self) -> &str {
+        "register_artifact"
+    }
+
+    fn description(&self) -> &str {
+        "Register a file you produced (report, build output, dataset, ...) as a result of this mission so the user can download it. Call it once the file is complete; registering the same path again updates it."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "required": ["path"],
+            "properties": {
+                "path": { "type": "string", "description": "Path of the file, relative to the workspace or absolute." },
+                "description": { "type": "string", "description": "Optional: what the file contains." }
+            }
+        })
+    }
+
+    async fn execute(&self, args: Value, workspace: &Path) -> anyhow::Result<String> {
+        let (path, _) = parse_artifact_args("register_artifact", &args)
+            .ok_or_else(|| anyhow::anyhow!("register_artifact needs a 'path'"))?;
+        let resolved = resolve_path_simple(&path, workspace);
+        let metadata = tokio::fs::metadata(&resolved)
+            .await
+            .map_err(|e| anyhow::anyhow!("Cannot register {}: {}", resolved.display(), e))?;
+        if !metadata.is_file() {
+            anyhow::bail!("Cannot register {}: not a file", resolved.display());
+        }
+        Ok(format!(
+            "Registered artifact {} ({} bytes)",
+            resolved.display(),
+            metadata.len()
+        ))
+    }
+}
+
+/// Extract `(path, description)` from a `register_artifact` call (optionally
+/// MCP-qualified). Returns `None` for other tools or a missing path.
+pub fn parse_artifact_args(name: &str, args: &Value) -> Option<(String, Option<String>)> {
+    if name != "register_artifact" && !name.ends_with("__register_artifact") {
+        return None;
+    }
+    let path = args
+        .get("path")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|p| !p.is_empty())?;
+    let description = args
+        .get("description")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(|d| d.chars().take(500).collect());
+    Some((path.to_string(), description))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_artifact_args() {
+        assert_eq!(
+            parse_artifact_args(
+                "mcp__workspace__register_artifact",
+                &json!({ "path": "out/report.md", "description": "Report" })
+            ),
+            Some(("out/report.md".to_string(), Some("Report".to_string())))
+        );
+        assert_eq!(
+            parse_artifact_args("register_artifact", &json!({ "path": " a.csv " })),
+            Some(("a.csv".to_string(), None))
+        );
+        assert_eq!(
+            parse_artifact_args("register_artifact", &json!({ "path": "" })),
+            None
+        );
+        assert_eq!(
+            parse_artifact_args("write_file", &json!({ "path": "a.csv" })),
+            None
+        );
+    }
+}
@@ -0,0 +1,261 @@
+//! Tool execution audit log.
+//!
+//! Every `ToolRegistry::execute` call is appended as one JSON line to
+//! `{working_dir}/.sandboxed-sh/audit/tools.jsonl`. The workspace MCP server
+//! reports its calls through `POST /api/audit/tools`. The file is append-only;
+//! entries record an args digest rather than the args themselves so secrets
+//! passed to tools don't end up on disk.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+/// Longest error message kept per entry.
+const MAX_ERROR_CHARS: usize = 500;
+
+/// One tool execution.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolAuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub tool: String,
+    /// SHA-256 of the JSON-encoded arguments.
+    pub args_digest: String,
+    pub working_dir: String,
+    pub duration_ms: u64,
+    /// Size of the tool output in bytes (0 on failure).
+    pub result_bytes: usize,
+    pub success: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mission_id: Option<Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_id: Option<Uuid>,
+}
+
+impl ToolAuditEntry {
+    /// Build an entry for a finished call.
+    pub fn new(
+        tool: &str,
+        args: &Value,
+        working_dir: &Path,
+        duration_ms: u64,
+        result: &anyhow::Result<String>,
+        mission_id: Option<Uuid>,
+        workspace_id: Option<Uuid>,
+    ) -> Self {
+        let (result_bytes, error) = match result {
+            Ok(output) => (output.len(), None),
+            Err(e) => (
+                0,
+                Some(e.to_string().chars().take(MAX_ERROR_CHARS).collect()),
+            ),
+        };
+        Self {
+            timestamp: Utc::now(),
+            tool: tool.to_string(),
+            args_digest: args_digest(args),
+            working_dir: working_dir.to_string_lossy().to_string(),
+            duration_ms,
+            result_bytes,
+            success: result.is_ok(),
+            error,
+            mission_id,
+            workspace_id,
+        }
+    }
+}
+
+/// Hex SHA-256 of the JSON-encoded arguments.
+pub fn args_digest(args: &Value) -> String {
+    hex::encode(Sha256::digest(args.to_string().as_bytes()))
+}
+
+/// Filters for reading the audit log.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ToolAuditQuery {
+    pub mission_id: Option<Uuid>,
+    pub workspace_id: Option<Uuid>,
+    pub tool: Option<String>,
+    pub success: Option<bool>,
+    /// Only entries at or after this time.
+    pub since: Option<DateTime<Utc>>,
+    /// Maximum number of (most recent) entries to return.
+    pub limit: Option<usize>,
+}
+
+impl ToolAuditQuery {
+    fn matches(&self, entry: &ToolAuditEntry) -> bool {
+        self.mission_id
+            .is_none_or(|id| entry.mission_id == Some(id))
+            && self
+                .workspace_id
+                .is_none_or(|id| entry.workspace_id == Some(id))
+            && self.tool.as_ref().is_none_or(|t| &entry.tool == t)
+            && self.success.is_none_or(|s| entry.success == s)
+            && self.since.is_none_or(|since| entry.timestamp >= since)
+    }
+}
+
+/// Append-only JSONL audit log.
+#[derive(Debug)]
+pub struct ToolAuditLog {
+    path: PathBuf,
+    write_lock: Mutex<()>,
+}
+
+impl ToolAuditLog {
+    pub const DEFAULT_LIMIT: usize = 200;
+    pub const MAX_LIMIT: usize = 5000;
+
+    /// Log stored under `{working_dir}/.sandboxed-sh/audit/tools.jsonl`.
+    pub fn new(working_dir: &Path) -> Self {
+        Self::at_path(working_dir.join(".sandboxed-sh/audit/tools.jsonl"))
+    }
+
+    pub fn at_path(path: PathBuf) -> Self {
+        Self {
+            path,
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    /// Append one entry.
+    pub async fn append(&self, entry: &ToolAuditEntry) -> std::io::Result<()> {
+        let mut line = serde_json::to_string(entry)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        line.push('\n');
+
+        let _guard = self.write_lock.lock().await;
+        if let Some(parent) = self.path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+        file.write_all(line.as_bytes()).await
+    }
+
+    /// Read entries matching `query`, oldest first, keeping the most recent
+    /// `limit`. Unparseable lines are skipped.
+    pub async fn query(&self, query: &ToolAuditQuery) -> std::io::Result<Vec<ToolAuditEntry>> {
+        let contents = match tokio::fs::read_to_string(&self.path).await {
+            Ok(c) => c,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        let limit = query
+            .limit
+            .unwrap_or(Self::DEFAULT_LIMIT)
+            .clamp(1, Self::MAX_LIMIT);
+
+        let mut entries: Vec<ToolAuditEntry> = contents
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .filter(|entry| query.matches(entry))
+            .collect();
+        if entries.len() > limit {
+            entries.drain(..entries.len() - limit);
+        }
+        Ok(entries)
+    }
+}
+
+static GLOBAL_AUDIT_LOG: OnceLock<Arc<ToolAuditLog>> = OnceLock::new();
+
+/// Make the server's audit log available to every `ToolRegistry`.
+pub fn set_global(log: Arc<ToolAuditLog>) {
+    let _ = GLOBAL_AUDIT_LOG.set(log);
+}
+
+/// The server's audit log, if one was registered.
+pub fn global() -> Option<Arc<ToolAuditLog>> {
+    GLOBAL_AUDIT_LOG.get().cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_append_and_query() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = ToolAuditLog::new(dir.path());
+        let mission = Uuid::new_v4();
+
+        let ok: anyhow::Result<String> = Ok("hello".to_string());
+        let failed: anyhow::Result<String> = Err(anyhow::anyhow!("boom"));
+        for (tool, result, mission_id) in [
+            ("read_file", &ok, Some(mission)),
+            ("run_command", &failed, Some(mission)),
+            ("read_file", &ok, None),
+        ] {
+            let entry = ToolAuditEntry::new(
+                tool,
+                &json!({"path": "a.txt"}),
+                dir.path(),
+                3,
+                result,
+                mission_id,
+                None,
+            );
+            log.append(&entry).await.unwrap();
+        }
+
+        let all = log.query(&ToolAuditQuery::default()).await.unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].result_bytes, 5);
+        assert_eq!(all[0].args_digest, args_digest(&json!({"path": "a.txt"})));
+
+        let by_mission = log
+            .query(&ToolAuditQuery {
+                mission_id: Some(mission),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(by_mission.len(), 2);
+
+        let failures = log
+            .query(&ToolAuditQuery {
+                success: Some(false),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].tool, "run_command");
+        assert_eq!(failures[0].error.as_deref(), Some("boom"));
+
+        let latest = log
+            .query(&ToolAuditQuery {
+                limit: Some(1),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(latest.len(), 1);
+        assert_eq!(latest[0].mission_id, None);
+    }
+
+    #[tokio::test]
+    async fn test_query_missing_log_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = ToolAuditLog::new(dir.path());
+        assert!(log
+            .query(&ToolAuditQuery::default())
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,751 @@
+//! Headless browser tools driven over the Chrome DevTools Protocol (CDP).
+//!
+//! This module provides tools for:
+//! - Navigating to a URL (`browser_navigate`)
+//! - Clicking elements by CSS selector (`browser_click_selector`)
+//! - Extracting text, HTML or attributes (`browser_extract`)
+//! - Capturing PNG screenshots (`browser_screenshot`)
+//! - Closing the browser (`browser_close`)
+//!
+//! Each working directory (i.e. mission workspace) gets its own headless
+//! Chromium, started on first use with a throwaway profile. Browsers idle for
+//! [`BROWSER_IDLE_TIMEOUT_SECS`] are shut down, as are browsers whose process
+//! died; the next tool call starts a fresh one.
+//!
+//! Requires: Chromium or Chrome (see [`super::desktop::find_browser_command`]).
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use futures::stream::SplitSink;
+use futures::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::process::{Child, Command};
+use tokio::sync::{oneshot, Mutex};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+use super::desktop::find_browser_command;
+use super::Tool;
+
+/// Shut down browsers that haven't been used for this long.
+const BROWSER_IDLE_TIMEOUT_SECS: u64 = 600;
+/// How long to wait for Chromium to print its DevTools endpoint.
+const BROWSER_STARTUP_TIMEOUT_SECS: u64 = 20;
+/// Timeout for a single CDP command.
+const CDP_COMMAND_TIMEOUT_SECS: u64 = 30;
+/// Default timeout for a page load in `browser_navigate`.
+const NAVIGATE_DEFAULT_TIMEOUT_SECS: u64 = 30;
+/// Default cap on text returned by `browser_extract`.
+const EXTRACT_DEFAULT_MAX_CHARS: usize = 20000;
+
+type WsSink = SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>;
+type Pending = Arc<std::sync::Mutex<HashMap<u64, oneshot::Sender<Result<Value, String>>>>>;
+
+static BROWSERS: OnceLock<Mutex<HashMap<PathBuf, Arc<BrowserSession>>>> = OnceLock::new();
+static IDLE_REAPER: OnceLock<()> = OnceLock::new();
+
+fn browsers() -> &'static Mutex<HashMap<PathBuf, Arc<BrowserSession>>> {
+    BROWSERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Minimal CDP client attached to a single page target.
+struct CdpClient {
+    sink: Mutex<WsSink>,
+    pending: Pending,
+    next_id: AtomicU64,
+    reader: tokio::task::JoinHandle<()>,
+}
+
+impl CdpClient {
+    async fn connect(ws_url: &str) -> anyhow::Result<Self> {
+        let (stream, _) = tokio_tungstenite::connect_async(ws_url)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to connect to DevTools: {}", e))?;
+        let (sink, mut source) = stream.split();
+        let pending: Pending = Arc::new(std::sync::Mutex::new(HashMap::new()));
+
+        let reader_pending = Arc::clone(&pending);
+        let reader = tokio::spawn(async move {
+            while let Some(Ok(message)) = source.next().await {
+                let Message::Text(text) = message else {
+                    continue;
+                };
+                let Ok(value) = serde_json::from_str::<Value>(&text) else {
+                    continue;
+                };
+                // Events carry no id; only command responses are routed.
+                let Some(id) = value.get("id").and_then(|v| v.as_u64()) else {
+                    continue;
+                };
+                let responder = reader_pending
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .remove(&id);
+                if let Some(responder) = responder {
+                    let result = match value.get("error") {
+                        Some(error) => Err(error
+                            .get("message")
+                            .and_then(|m| m.as_str())
+                            .unwrap_or("CDP error")
+                            .to_string()),
+                        None => Ok(value.get("result").cloned().unwrap_or(Value::Null)),
+                    };
+                    let _ = responder.send(result);
+                }
+            }
+            // Connection closed: fail everything still waiting.
+            reader_pending
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .clear();
+        });
+
+        Ok(Self {
+            sink: Mutex::new(sink),
+            pending,
+            next_id: AtomicU64::new(1),
+            reader,
+        })
+    }
+
+    async fn send(&self, method: &str, params: Value) -> anyhow::Result<Value> {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let (tx, rx) = oneshot::channel();
+        self.pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(id, tx);
+
+        let message = json!({ "id": id, "method": method, "params": params }).to_string();
+        if let Err(e) = self.sink.lock().await.send(Message::Text(message)).await {
+            self.pending
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .remove(&id);
+            return Err(anyhow::anyhow!("Browser connection lost: {}", e));
+        }
+
+        match tokio::time::timeout(Duration::from_secs(CDP_COMMAND_TIMEOUT_SECS), rx).await {
+            Ok(Ok(Ok(result))) => Ok(result),
+            Ok(Ok(Err(e))) => Err(anyhow::anyhow!("{} failed: {}", method, e)),
+            Ok(Err(_)) => Err(anyhow::anyhow!("Browser connection lost")),
+            Err(_) => {
+                self.pending
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .remove(&id);
+                Err(anyhow::anyhow!("{} timed out", method))
+            }
+        }
+    }
+
+    /// Evaluate a JavaScript expression in the page and return its value.
+    async fn evaluate(&self, expression: &str) -> anyhow::Result<Value> {
+        let result = self
+            .send(
+                "Runtime.evaluate",
+                json!({
+                    "expression": expression,
+                    "returnByValue": true,
+                    "awaitPromise": true,
+                }),
+            )
+            .await?;
+        if let Some(details) = result.get("exceptionDetails") {
+            let text = details["exception"]["description"]
+                .as_str()
+                .or_else(|| details["text"].as_str())
+                .unwrap_or("JavaScript exception");
+            return Err(anyhow::anyhow!("{}", text));
+        }
+        Ok(result["result"]["value"].clone())
+    }
+}
+
+impl Drop for CdpClient {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+/// A headless Chromium owned by one working directory.
+struct BrowserSession {
+    child: Mutex<Child>,
+    cdp: CdpClient,
+    profile_dir: PathBuf,
+    last_used: std::sync::Mutex<Instant>,
+}
+
+impl BrowserSession {
+    async fn launch() -> anyhow::Result<Self> {
+        let browser = find_browser_command().ok_or_else(|| {
+            anyhow::anyhow!("No Chromium/Chrome found. Install chromium or set CHROMIUM_BIN.")
+        })?;
+        let profile_dir =
+            std::env::temp_dir().join(format!("sandboxed-browser-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&profile_dir)?;
+
+        let mut child = Command::new(&browser)
+            .args([
+                "--headless=new",
+                "--no-sandbox",
+                "--disable-gpu",
+                "--disable-dev-shm-usage",
+                "--no-first-run",
+                "--no-default-browser-check",
+                "--window-size=1280,720",
+                "--remote-debugging-port=0",
+            ])
+            .arg(format!("--user-data-dir={}", profile_dir.display()))
+            .arg("about:blank")
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| anyhow::anyhow!("Failed to start {}: {}", browser, e))?;
+
+        let stderr = child
+            .stderr
+            .take()
+            .ok_or_else(|| anyhow::anyhow!("Failed to capture browser stderr"))?;
+        let mut lines = BufReader::new(stderr).lines();
+        let browser_ws =
+            tokio::time::timeout(Duration::from_secs(BROWSER_STARTUP_TIMEOUT_SECS), async {
+                while let Ok(Some(line)) = lines.next_line().await {
+                    if let Some(url) = parse_devtools_url(&line) {
+                        return Some(url);
+                    }
+                }
+                None
+            })
+            .await
+            .ok()
+            .flatten();
+        let Some(browser_ws) = browser_ws else {
+            let _ = child.kill().await;
+            let _ = std::fs::remove_dir_all(&profile_dir);
+            return Err(anyhow::anyhow!(
+                "Browser did not report a DevTools endpoint within {}s",
+                BROWSER_STARTUP_TIMEOUT_SECS
+            ));
+        };
+        // Keep draining stderr so Chromium never blocks on a full pipe.
+        tokio::spawn(async move {
+            while let Ok(Some(line)) = lines.next_line().await {
+                tracing::trace!(target: "browser", "{}", line);
+            }
+        });
+
+        let page_ws = match find_page_target(&browser_ws).await {
+            Ok(url) => url,
+            Err(e) => {
+                let _ = child.kill().await;
+                let _ = std::fs::remove_dir_all(&profile_dir);
+                return Err(e);
+            }
+        };
+        let cdp = CdpClient::connect(&page_ws).await?;
+        cdp.send("Page.enable", json!({})).await?;
+        cdp.send("Runtime.enable", json!({})).await?;
+
+        tracing::info!(browser = %browser, "Started headless browser");
+        Ok(Self {
+            child: Mutex::new(child),
+            cdp,
+            profile_dir,
+            last_used: std::sync::Mutex::new(Instant::now()),
+        })
+    }
+
+    fn touch(&self) {
+        *self.last_used.lock().unwrap_or_else(|e| e.into_inner()) = Instant::now();
+    }
+
+    fn idle_for(&self) -> Duration {
+        self.last_used
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .elapsed()
+    }
+
+    async fn is_alive(&self) -> bool {
+        matches!(self.child.lock().await.try_wait(), Ok(None))
+    }
+
+    async fn shutdown(&self) {
+        let _ = self.child.lock().await.kill().await;
+        let _ = tokio::fs::remove_dir_all(&self.profile_dir).await;
+    }
+}
+
+/// Extract the browser WebSocket URL from Chromium's startup banner.
+fn parse_devtools_url(line: &str) -> Option<String> {
+    line.trim()
+        .strip_prefix("DevTools listening on ")
+        .map(|url| url.trim().to_string())
+        .filter(|url| url.starts_with("ws://"))
+}
+
+/// Find the WebSocket URL of the initial page target via `/json/list`.
+async fn find_page_target(browser_ws: &str) -> anyhow::Result<String> {
+    let parsed = url::Url::parse(browser_ws)?;
+    let host = parsed.host_str().unwrap_or("127.0.0.1");
+    let port = parsed
+        .port()
+        .ok_or_else(|| anyhow::anyhow!("DevTools URL has no port: {}", browser_ws))?;
+    let list_url = format!("http://{}:{}/json/list", host, port);
+
+    let targets: Vec<Value> = reqwest::get(&list_url).await?.json().await?;
+    targets
+        .iter()
+        .find(|t| t["type"].as_str() == Some("page"))
+        .and_then(|t| t["webSocketDebuggerUrl"].as_str())
+        .map(str::to_string)
+        .ok_or_else(|| anyhow::anyhow!("Browser has no page target"))
+}
+
+/// Get (or start) the browser for a working directory.
+async fn session_for(working_dir: &Path) -> anyhow::Result<Arc<BrowserSession>> {
+    IDLE_REAPER.get_or_init(|| {
+        tokio::spawn(idle_reaper_loop());
+    });
+
+    let mut sessions = browsers().lock().await;
+    if let Some(session) = sessions.get(working_dir) {
+        if session.is_alive().await {
+            session.touch();
+            return Ok(Arc::clone(session));
+        }
+        if let Some(dead) = sessions.remove(working_dir) {
+            dead.shutdown().await;
+        }
+    }
+    let session = Arc::new(BrowserSession::launch().await?);
+    sessions.insert(working_dir.to_path_buf(), Arc::clone(&session));
+    Ok(session)
+}
+
+/// Close the browser for a working directory, if one is running.
+pub async fn close_browser(working_dir: &Path) -> bool {
+    let session = browsers().lock().await.remove(working_dir);
+    match session {
+        Some(session) => {
+            session.shutdown().await;
+            true
+        }
+        None => false,
+    }
+}
+
+async fn idle_reaper_loop() {
+    let mut interval = tokio::time::interval(Duration::from_secs(60));
+    loop {
+        interval.tick().await;
+        let idle: Vec<Arc<BrowserSession>> = {
+            let mut sessions = browsers().lock().await;
+            let expired: Vec<PathBuf> = sessions
+                .iter()
+                .filter(|(_, s)| s.idle_for() >= Duration::from_secs(BROWSER_IDLE_TIMEOUT_SECS))
+                .map(|(path, _)| path.clone())
+                .collect();
+            expired
+                .iter()
+                .filter_map(|path| sessions.remove(path))
+                .collect()
+        };
+        for session in idle {
+            tracing::info!("Closing idle headless browser");
+            session.shutdown().await;
+        }
+    }
+}
+
+/// Navigate the page to a URL and wait for it to load.
+pub struct Navigate;
+
+#[async_trait]
+impl Tool for Navigate {
+    fn name(&self) -> &str {
+        "browser_navigate"
+    }
+
+    fn description(&self) -> &str {
+        "Open a URL in a headless browser (started automatically, one per workspace) and wait for the page to load. Much faster than desktop automation for web tasks. Returns the final URL and page title. Follow up with browser_extract, browser_click_selector or browser_screenshot."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "url": {
+                    "type": "string",
+                    "description": "The URL to open"
+                },
+                "timeout_secs": {
+                    "type": "integer",
+                    "description": "Optional: how long to wait for the page to load (default: 30)"
+                }
+            },
+            "required": ["url"]
+        })
+    }
+
+    async fn execute(&self, args: Value, working_dir: &Path) -> anyhow::Result<String> {
+        let url = args["url"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing 'url' argument"))?;
+        let timeout = args["timeout_secs"]
+            .as_u64()
+            .unwrap_or(NAVIGATE_DEFAULT_TIMEOUT_SECS);
+
+        let session = session_for(working_dir).await?;
+        let result = session
+            .cdp
+            .send("Page.navigate", json!({ "url": url }))
+            .await?;
+        if let Some(error) = result["errorText"].as_str() {
+            return Err(anyhow::anyhow!("Navigation to {} failed: {}", url, error));
+        }
+
+        let loaded = wait_for_load(&session.cdp, Duration::from_secs(timeout)).await;
+        let info = session
+            .cdp
+            .evaluate("({ url: location.href, title: document.title })")
+            .await?;
+        Ok(json!({
+            "success": true,
+            "url": info["url"],
+            "title": info["title"],
+            "loaded": loaded,
+        })
+        .to_string())
+    }
+}
+
+/// Poll `document.readyState` until the page has loaded.
+async fn wait_for_load(cdp: &CdpClient, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        if let Ok(state) = cdp.evaluate("document.readyState").await {
+            if state.as_str() == Some("complete") {
+                return true;
+            }
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+    false
+}
+
+/// Click the first element matching a CSS selector.
+pub struct ClickSelector;
+
+#[async_trait]
+impl Tool for ClickSelector {
+    fn name(&self) -> &str {
+        "browser_click_selector"
+    }
+
+    fn description(&self) -> &str {
+        "Click the first element matching a CSS selector in the headless browser page (e.g. 'button[type=submit]', '#login', 'a[href*=docs]'). Waits up to wait_secs for the element to appear."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "selector": {
+                    "type": "string",
+                    "description": "CSS selector of the element to click"
+                },
+                "wait_secs": {
+                    "type": "number",
+                    "description": "Optional: how long to wait for the element to appear (default: 5)"
+                }
+            },
+            "required": ["selector"]
+        })
+    }
+
+    async fn execute(&self, args: Value, working_dir: &Path) -> anyhow::Result<String> {
+        let selector = args["selector"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing 'selector' argument"))?;
+        let wait = args["wait_secs"].as_f64().unwrap_or(5.0).max(0.0);
+
+        let session = session_for(working_dir).await?;
+        let selector_js = serde_json::to_string(selector)?;
+        let script = format!(
+            "(async () => {{
+                const deadline = Date.now() + {wait_ms};
+                let el = document.querySelector({sel});
+                while (!el && Date.now() < deadline) {{
+                    await new Promise(r => setTimeout(r, 100));
+                    el = document.querySelector({sel});
+                }}
+                if (!el) return {{ found: false }};
+                el.scrollIntoView({{ block: 'center' }});
+                el.click();
+                return {{ found: true, tag: el.tagName.toLowerCase(), text: (el.innerText || el.value || '').slice(0, 100) }};
+            }})()",
+            wait_ms = (wait * 1000.0) as u64,
+            sel = selector_js
+        );
+        let result = session.cdp.evaluate(&script).await?;
+        if result["found"].as_bool() != Some(true) {
+            return Err(anyhow::anyhow!("No element matches selector: {}", selector));
+        }
+        // Give navigation or handlers triggered by the click a moment to run.
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let _ = wait_for_load(&session.cdp, Duration::from_secs(5)).await;
+
+        Ok(json!({
+            "success": true,
+            "clicked": { "tag": result["tag"], "text": result["text"] },
+        })
+        .to_string())
+    }
+}
+
+/// Extract text, HTML or an attribute from the page.
+pub struct Extract;
+
+#[async_trait]
+impl Tool for Extract {
+    fn name(&self) -> &str {
+        "browser_extract"
+    }
+
+    fn description(&self) -> &str {
+        "Extract content from the headless browser page: visible text (default), HTML, or an attribute of every element matching a CSS selector (default: the whole body). Output is truncated to max_chars."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "selector": {
+                    "type": "string",
+                    "description": "Optional CSS selector (default: 'body')"
+                },
+                "mode": {
+                    "type": "string",
+                    "enum": ["text", "html", "attribute"],
+                    "description": "What to extract (default: text)"
+                },
+                "attribute": {
+                    "type": "string",
+                    "description": "Attribute name when mode is 'attribute' (e.g. 'href')"
+                },
+                "max_chars": {
+                    "type": "integer",
+                    "description": "Optional: maximum characters to return (default: 20000)"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: Value, working_dir: &Path) -> anyhow::Result<String> {
+        let selector = args["selector"].as_str().unwrap_or("body");
+        let mode = args["mode"].as_str().unwrap_or("text");
+        let max_chars = args["max_chars"]
+            .as_u64()
+            .map(|n| n as usize)
+            .unwrap_or(EXTRACT_DEFAULT_MAX_CHARS);
+
+        let getter = match mode {
+            "text" => "el.innerText".to_string(),
+            "html" => "el.outerHTML".to_string(),
+            "attribute" => {
+                let attribute = args["attribute"].as_str().ok_or_else(|| {
+                    anyhow::anyhow!("'attribute' is required when mode is 'attribute'")
+                })?;
+                format!("el.getAttribute({})", serde_json::to_string(attribute)?)
+            }
+            other => {
+                return Err(anyhow::anyhow!(
+                    "Unknown mode '{}': use text, html or attribute",
+                    other
+                ))
+            }
+        };
+
+        let session = session_for(working_dir).await?;
+        let script = format!(
+            "Array.from(document.querySelectorAll({sel})).map(el => {getter}).filter(v => v != null)",
+            sel = serde_json::to_string(selector)?,
+            getter = getter
+        );
+        let values = session.cdp.evaluate(&script).await?;
+        let values: Vec<String> = values
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .map(|v| {
+                        v.as_str()
+                            .map(str::to_string)
+                            .unwrap_or_else(|| v.to_string())
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        if values.is_empty() {
+            return Ok(format!("No elements match selector: {}", selector));
+        }
+
+        let joined = values.join("\n\n");
+        let end = super::safe_truncate_index(&joined, max_chars);
+        let mut output = joined[..end].to_string();
+        if end < joined.len() {
+            output.push_str(&format!(
+                "\n\n[Truncated: showing {} of {} characters from {} element(s)]",
+                end,
+                joined.len(),
+                values.len()
+            ));
+        }
+        Ok(output)
+    }
+}
+
+/// Capture a PNG screenshot of the page.
+pub struct BrowserScreenshot;
+
+#[async_trait]
+impl Tool for BrowserScreenshot {
+    fn name(&self) -> &str {
+        "browser_screenshot"
+    }
+
+    fn description(&self) -> &str {
+        "Take a PNG screenshot of the headless browser page and save it under screenshots/ in the workspace. Set full_page=true to capture beyond the viewport, and return_image=true to SEE the screenshot yourself (vision)."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "full_page": {
+                    "type": "boolean",
+                    "description": "Capture the whole scrollable page instead of the viewport (default: false)"
+                },
+                "return_image": {
+                    "type": "boolean",
+                    "description": "If true, the screenshot image will be included in your context (requires vision model). Default: false"
+                },
+                "filename": {
+                    "type": "string",
+                    "description": "Optional filename for the screenshot (default: auto-generated with timestamp)"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: Value, working_dir: &Path) -> anyhow::Result<String> {
+        let full_page = args["full_page"].as_bool().unwrap_or(false);
+        let session = session_for(working_dir).await?;
+
+        let mut params = json!({ "format": "png" });
+        if full_page {
+            let metrics = session.cdp.send("Page.getLayoutMetrics", json!({})).await?;
+            let size = if metrics["cssContentSize"].is_object() {
+                &metrics["cssContentSize"]
+            } else {
+                &metrics["contentSize"]
+            };
+            params["captureBeyondViewport"] = json!(true);
+            params["clip"] = json!({
+                "x": 0,
+                "y": 0,
+                "width": size["width"].as_f64().unwrap_or(1280.0),
+                "height": size["height"].as_f64().unwrap_or(720.0),
+                "scale": 1,
+            });
+        }
+        let result = session.cdp.send("Page.captureScreenshot", params).await?;
+        let data = result["data"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Browser returned no screenshot data"))?;
+        let bytes = BASE64.decode(data)?;
+
+        let filename = args["filename"]
+            .as_str()
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| {
+                let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
+                format!("browser_{}.png", timestamp)
+            });
+        let screenshots_dir = working_dir.join("screenshots");
+        std::fs::create_dir_all(&screenshots_dir)?;
+        let filepath = screenshots_dir.join(&filename);
+        tokio::fs::write(&filepath, &bytes).await?;
+
+        let vision_marker = if args["return_image"].as_bool().unwrap_or(false) {
+            format!("\n\n[VISION_IMAGE:file://{}]", filepath.display())
+        } else {
+            String::new()
+        };
+        Ok(format!(
+            "{{\"success\": true, \"path\": \"{}\", \"size_bytes\": {}}}{}",
+            filepath.display(),
+            bytes.len(),
+            vision_marker
+        ))
+    }
+}
+
+/// Close the workspace's headless browser.
+pub struct Close;
+
+#[async_trait]
+impl Tool for Close {
+    fn name(&self) -> &str {
+        "browser_close"
+    }
+
+    fn description(&self) -> &str {
+        "Close the headless browser for this workspace. It is also closed automatically after 10 minutes of inactivity."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {}
+        })
+    }
+
+    async fn execute(&self, _args: Value, working_dir: &Path) -> anyhow::Result<String> {
+        let closed = close_browser(working_dir).await;
+        Ok(json!({ "success": true, "closed": closed }).to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_devtools_url() {
+        assert_eq!(
+            parse_devtools_url(
+                "DevTools listening on ws://127.0.0.1:39877/devtools/browser/5d1e-42\n"
+            )
+            .as_deref(),
+            Some("ws://127.0.0.1:39877/devtools/browser/5d1e-42")
+        );
+        assert_eq!(
+            parse_devtools_url("[0101/000000.000:ERROR:gpu_init.cc] something"),
+            None
+        );
+    }
+}
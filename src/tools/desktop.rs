@@ -2,12 +2,14 @@
 //!
 //! This module provides tools for:
 //! - Managing Xvfb virtual display sessions
-//! - Taking screenshots
+//! - Taking screenshots (full screen or a region) and annotating captures
 //! - Keyboard input (typing)
 //! - Mouse operations (clicking)
+//! - Clipboard access (xclip/xsel)
 //! - Extracting visible text (AT-SPI + OCR)
 //!
-//! Requires: Xvfb, i3, xdotool, scrot, tesseract, AT-SPI2
+//! Requires: Xvfb, i3, xdotool, scrot, tesseract, AT-SPI2, xclip or xsel,
+//! ImageMagick (annotations)
 //! Only available when DESKTOP_ENABLED=true
 
 use std::path::{Path, PathBuf};
@@ -163,6 +165,276 @@ async fn run_with_display(
     Ok((stdout, stderr, exit_code))
 }
 
+/// A screen rectangle in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Region {
+    pub x: i64,
+    pub y: i64,
+    pub width: i64,
+    pub height: i64,
+}
+
+impl Region {
+    /// Read `x`, `y`, `width` and `height` from `value`.
+    pub fn from_value(value: &Value) -> anyhow::Result<Self> {
+        let field = |name: &str| {
+            value[name]
+                .as_i64()
+                .ok_or_else(|| anyhow::anyhow!("Missing or invalid '{}'", name))
+        };
+        let region = Self {
+            x: field("x")?,
+            y: field("y")?,
+            width: field("width")?,
+            height: field("height")?,
+        };
+        if region.x < 0 || region.y < 0 || region.width <= 0 || region.height <= 0 {
+            return Err(anyhow::anyhow!(
+                "Region must have non-negative x/y and positive width/height"
+            ));
+        }
+        Ok(region)
+    }
+
+    /// `scrot -a` geometry.
+    pub fn scrot_geometry(&self) -> String {
+        format!("{},{},{},{}", self.x, self.y, self.width, self.height)
+    }
+
+    /// ImageMagick `-crop` geometry.
+    pub fn crop_geometry(&self) -> String {
+        format!("{}x{}+{}+{}", self.width, self.height, self.x, self.y)
+    }
+}
+
+/// The most recent screenshot taken in a workspace, for `desktop_annotate`.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct LastCapture {
+    pub path: PathBuf,
+    /// Screen area the image covers (`None` = the full screen).
+    pub region: Option<Region>,
+}
+
+fn last_capture_path(working_dir: &Path) -> PathBuf {
+    working_dir.join("screenshots").join(".last_capture.json")
+}
+
+/// Remember `capture` as the workspace's latest screenshot.
+pub fn record_last_capture(working_dir: &Path, capture: &LastCapture) -> std::io::Result<()> {
+    let path = last_capture_path(working_dir);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_vec_pretty(capture)?)
+}
+
+/// The workspace's latest screenshot, if it still exists.
+pub fn last_capture(working_dir: &Path) -> Option<LastCapture> {
+    let raw = std::fs::read(last_capture_path(working_dir)).ok()?;
+    let capture: LastCapture = serde_json::from_slice(&raw).ok()?;
+    capture.path.exists().then_some(capture)
+}
+
+/// Colors accepted by `desktop_annotate` (names or `#rrggbb`).
+fn valid_color(color: &str) -> bool {
+    !color.is_empty()
+        && color.len() <= 32
+        && color.chars().all(|c| c.is_ascii_alphanumeric() || c == '#')
+}
+
+/// Make label text literal for ImageMagick: `@` at the start reads a file and
+/// `%` starts an escape sequence.
+fn literal_label(text: &str) -> String {
+    let text: String = text.chars().filter(|c| !c.is_control()).take(200).collect();
+    let text = text.replace('%', "%%");
+    match text.strip_prefix('@') {
+        Some(rest) => format!(" @{}", rest),
+        None => text,
+    }
+}
+
+/// ImageMagick `convert` arguments that draw `annotations` onto `input` and
+/// write `output`.
+///
+/// Each annotation is a box (`x`, `y`, `width`, `height`), a point (`x`,
+/// `y`) or either with a `label`. Coordinates are screen pixels and are
+/// shifted by the capture's region so they line up with a region capture.
+pub fn annotate_args(
+    input: &Path,
+    output: &Path,
+    annotations: &[Value],
+    region: Option<Region>,
+) -> anyhow::Result<Vec<String>> {
+    if annotations.is_empty() {
+        return Err(anyhow::anyhow!("'annotations' must not be empty"));
+    }
+    let (dx, dy) = region.map(|r| (r.x, r.y)).unwrap_or((0, 0));
+    let mut args = vec![input.display().to_string()];
+
+    for (i, annotation) in annotations.iter().enumerate() {
+        let x = annotation["x"]
+            .as_i64()
+            .ok_or_else(|| anyhow::anyhow!("Annotation {} is missing 'x'", i))?
+            - dx;
+        let y = annotation["y"]
+            .as_i64()
+            .ok_or_else(|| anyhow::anyhow!("Annotation {} is missing 'y'", i))?
+            - dy;
+        let color = annotation["color"].as_str().unwrap_or("red");
+        if !valid_color(color) {
+            return Err(anyhow::anyhow!("Invalid color '{}'", color));
+        }
+
+        args.extend(["-fill".to_string(), "none".to_string()]);
+        args.extend(["-stroke".to_string(), color.to_string()]);
+        args.extend(["-strokewidth".to_string(), "3".to_string()]);
+        let label_y = match (annotation["width"].as_i64(), annotation["height"].as_i64()) {
+            (Some(w), Some(h)) => {
+                args.extend([
+                    "-draw".to_string(),
+                    format!("rectangle {},{} {},{}", x, y, x + w, y + h),
+                ]);
+                y - 6
+            }
+            _ => {
+                args.extend([
+                    "-draw".to_string(),
+                    format!("circle {},{} {},{}", x, y, x + 6, y),
+                ]);
+                y - 10
+            }
+        };
+
+        if let Some(label) = annotation["label"].as_str().filter(|l| !l.is_empty()) {
+            args.extend(["-stroke".to_string(), "none".to_string()]);
+            args.extend(["-fill".to_string(), color.to_string()]);
+            args.extend(["-undercolor".to_string(), "#000000A0".to_string()]);
+            args.extend(["-pointsize".to_string(), "16".to_string()]);
+            args.extend([
+                "-annotate".to_string(),
+                format!("+{}+{}", x.max(0), label_y.max(16)),
+                literal_label(label),
+            ]);
+            args.extend(["-undercolor".to_string(), "none".to_string()]);
+        }
+    }
+
+    args.push(output.display().to_string());
+    Ok(args)
+}
+
+/// A clipboard program and its arguments for the CLIPBOARD and PRIMARY
+/// selections (indexed by `primary as usize`).
+struct ClipboardBackend {
+    program: &'static str,
+    write_args: [&'static [&'static str]; 2],
+    read_args: [&'static [&'static str]; 2],
+}
+
+/// Clipboard programs, tried in order.
+const CLIPBOARD_BACKENDS: &[ClipboardBackend] = &[
+    ClipboardBackend {
+        program: "xclip",
+        write_args: [
+            &["-selection", "clipboard", "-in"],
+            &["-selection", "primary", "-in"],
+        ],
+        read_args: [
+            &["-selection", "clipboard", "-out"],
+            &["-selection", "primary", "-out"],
+        ],
+    },
+    ClipboardBackend {
+        program: "xsel",
+        write_args: [&["--clipboard", "--input"], &["--primary", "--input"]],
+        read_args: [&["--clipboard", "--output"], &["--primary", "--output"]],
+    },
+];
+
+/// Set the clipboard (or PRIMARY selection) of a display using xclip or xsel.
+///
+/// Returns the name of the program that was used.
+pub async fn set_clipboard(
+    display: &str,
+    text: &str,
+    primary: bool,
+) -> anyhow::Result<&'static str> {
+    use tokio::io::AsyncWriteExt;
+
+    for ClipboardBackend {
+        program,
+        write_args,
+        ..
+    } in CLIPBOARD_BACKENDS
+    {
+        // Both tools fork a child that keeps serving the selection and holds
+        // on to inherited stdout/stderr, so don't pipe those.
+        let mut child = match Command::new(program)
+            .args(write_args[primary as usize])
+            .env("DISPLAY", display)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(anyhow::anyhow!("Failed to execute {}: {}", program, e)),
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text.as_bytes()).await?;
+        }
+        let status = tokio::time::timeout(std::time::Duration::from_secs(10), child.wait())
+            .await
+            .map_err(|_| anyhow::anyhow!("{} timed out", program))??;
+        if !status.success() {
+            return Err(anyhow::anyhow!("{} failed to set the clipboard", program));
+        }
+        return Ok(program);
+    }
+    Err(anyhow::anyhow!(
+        "No clipboard tool found; install xclip or xsel"
+    ))
+}
+
+/// Read the clipboard (or PRIMARY selection) of a display using xclip or xsel.
+///
+/// An empty selection reads as an empty string.
+pub async fn get_clipboard(display: &str, primary: bool) -> anyhow::Result<String> {
+    for ClipboardBackend {
+        program, read_args, ..
+    } in CLIPBOARD_BACKENDS
+    {
+        let output = match tokio::time::timeout(
+            std::time::Duration::from_secs(10),
+            Command::new(program)
+                .args(read_args[primary as usize])
+                .env("DISPLAY", display)
+                .stdin(Stdio::null())
+                .output(),
+        )
+        .await
+        {
+            Ok(Ok(output)) => output,
+            Ok(Err(e)) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Ok(Err(e)) => return Err(anyhow::anyhow!("Failed to execute {}: {}", program, e)),
+            Err(_) => return Err(anyhow::anyhow!("{} timed out", program)),
+        };
+        if output.status.success() {
+            return Ok(String::from_utf8_lossy(&output.stdout).to_string());
+        }
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        // xclip exits non-zero when nothing owns the selection.
+        if stderr.contains("not available") {
+            return Ok(String::new());
+        }
+        return Err(anyhow::anyhow!("{} failed: {}", program, stderr.trim()));
+    }
+    Err(anyhow::anyhow!(
+        "No clipboard tool found; install xclip or xsel"
+    ))
+}
+
 /// Start a new desktop session with Xvfb and i3.
 ///
 /// Creates a virtual X11 display and starts the i3 window manager.
@@ -428,6 +700,63 @@ impl Tool for StopSession {
     }
 }
 
+/// Capture the screen (or `region` of it) to `filepath` with scrot, falling
+/// back to ImageMagick's `import`.
+async fn capture_screen(
+    display_id: &str,
+    filepath: &Path,
+    region: Option<Region>,
+) -> anyhow::Result<()> {
+    let path = filepath.to_string_lossy().to_string();
+    let mut scrot_args = vec!["-o".to_string(), path.clone()];
+    if let Some(region) = region {
+        scrot_args.push("-a".to_string());
+        scrot_args.push(region.scrot_geometry());
+    }
+
+    let (_stdout, stderr, exit_code) = run_with_display(
+        display_id,
+        "scrot",
+        &scrot_args.iter().map(|s| s.as_str()).collect::<Vec<_>>(),
+        30,
+    )
+    .await?;
+
+    if exit_code != 0 {
+        // Try import as fallback
+        let mut import_args = vec!["-window".to_string(), "root".to_string()];
+        if let Some(region) = region {
+            import_args.extend([
+                "-crop".to_string(),
+                region.crop_geometry(),
+                "+repage".to_string(),
+            ]);
+        }
+        import_args.push(path);
+        let import_result = run_with_display(
+            display_id,
+            "import",
+            &import_args.iter().map(|s| s.as_str()).collect::<Vec<_>>(),
+            30,
+        )
+        .await;
+
+        if let Err(e) = import_result {
+            return Err(anyhow::anyhow!(
+                "Screenshot failed. scrot error: {}. import error: {}",
+                stderr,
+                e
+            ));
+        }
+    }
+
+    // Verify file exists
+    if !filepath.exists() {
+        return Err(anyhow::anyhow!("Screenshot file was not created"));
+    }
+    Ok(())
+}
+
 /// Take a screenshot of the desktop.
 pub struct Screenshot;
 
@@ -513,51 +842,26 @@ Set return_image=true to SEE the screenshot yourself (vision). This lets you ver
 
         tracing::info!(display = %display_id, path = %filepath.display(), "Taking screenshot");
 
-        // Build scrot command
-        let mut scrot_args = vec!["-o".to_string(), filepath.to_string_lossy().to_string()];
-
         // Add region if specified
-        if let Some(region) = args.get("region") {
-            if region.is_object() {
-                let x = region["x"].as_i64().unwrap_or(0);
-                let y = region["y"].as_i64().unwrap_or(0);
-                let w = region["width"].as_i64().unwrap_or(100);
-                let h = region["height"].as_i64().unwrap_or(100);
-                scrot_args.push("-a".to_string());
-                scrot_args.push(format!("{},{},{},{}", x, y, w, h));
-            }
-        }
-
-        let (_stdout, stderr, exit_code) = run_with_display(
-            display_id,
-            "scrot",
-            &scrot_args.iter().map(|s| s.as_str()).collect::<Vec<_>>(),
-            30,
-        )
-        .await?;
-
-        if exit_code != 0 {
-            // Try import as fallback
-            let import_result = run_with_display(
-                display_id,
-                "import",
-                &["-window", "root", filepath.to_string_lossy().as_ref()],
-                30,
-            )
-            .await;
-
-            if let Err(e) = import_result {
-                return Err(anyhow::anyhow!(
-                    "Screenshot failed. scrot error: {}. import error: {}",
-                    stderr,
-                    e
-                ));
-            }
-        }
+        let region = args
+            .get("region")
+            .filter(|region| region.is_object())
+            .map(|region| Region {
+                x: region["x"].as_i64().unwrap_or(0),
+                y: region["y"].as_i64().unwrap_or(0),
+                width: region["width"].as_i64().unwrap_or(100),
+                height: region["height"].as_i64().unwrap_or(100),
+            });
 
-        // Verify file exists
-        if !filepath.exists() {
-            return Err(anyhow::anyhow!("Screenshot file was not created"));
+        capture_screen(display_id, &filepath, region).await?;
+        if let Err(e) = record_last_capture(
+            working_dir,
+            &LastCapture {
+                path: filepath.clone(),
+                region,
+            },
+        ) {
+            tracing::warn!("Failed to record last capture: {}", e);
         }
 
         let metadata = std::fs::metadata(&filepath)?;
@@ -579,6 +883,212 @@ Set return_image=true to SEE the screenshot yourself (vision). This lets you ver
     }
 }
 
+/// Keep only the final component of a user-supplied filename.
+fn screenshot_filename(requested: Option<&str>, prefix: &str) -> String {
+    requested
+        .and_then(|name| Path::new(name).file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| {
+            format!(
+                "{}_{}.png",
+                prefix,
+                chrono::Utc::now().format("%Y%m%d_%H%M%S_%3f")
+            )
+        })
+}
+
+/// Capture part of the desktop.
+pub struct ScreenshotRegion;
+
+#[async_trait]
+impl Tool for ScreenshotRegion {
+    fn name(&self) -> &str {
+        "desktop_screenshot_region"
+    }
+
+    fn description(&self) -> &str {
+        "Capture only a rectangle of the virtual desktop (screen pixels). Much smaller than a full screenshot, so prefer it in automation loops once you know where to look. Set return_image=true to see it. desktop_annotate can then mark up the capture using screen coordinates."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "display": {
+                    "type": "string",
+                    "description": "The display identifier (e.g., ':99') from desktop_start_session"
+                },
+                "x": { "type": "integer", "description": "Left edge in screen pixels" },
+                "y": { "type": "integer", "description": "Top edge in screen pixels" },
+                "w": { "type": "integer", "description": "Width in pixels" },
+                "h": { "type": "integer", "description": "Height in pixels" },
+                "return_image": {
+                    "type": "boolean",
+                    "description": "Include the capture in your context (requires vision model). Default: false"
+                },
+                "filename": {
+                    "type": "string",
+                    "description": "Optional filename (saved under screenshots/)"
+                }
+            },
+            "required": ["display", "x", "y", "w", "h"]
+        })
+    }
+
+    async fn execute(&self, args: Value, working_dir: &Path) -> anyhow::Result<String> {
+        let display_id = args["display"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing 'display' argument"))?;
+        let region = Region::from_value(&json!({
+            "x": args["x"],
+            "y": args["y"],
+            "width": args["w"],
+            "height": args["h"],
+        }))?;
+
+        let screenshots_dir = working_dir.join("screenshots");
+        std::fs::create_dir_all(&screenshots_dir)?;
+        let filepath =
+            screenshots_dir.join(screenshot_filename(args["filename"].as_str(), "region"));
+
+        capture_screen(display_id, &filepath, Some(region)).await?;
+        record_last_capture(
+            working_dir,
+            &LastCapture {
+                path: filepath.clone(),
+                region: Some(region),
+            },
+        )?;
+
+        let metadata = std::fs::metadata(&filepath)?;
+        let vision_marker = if args["return_image"].as_bool().unwrap_or(false) {
+            format!("\n\n[VISION_IMAGE:file://{}]", filepath.display())
+        } else {
+            String::new()
+        };
+        Ok(format!(
+            "{}{}",
+            json!({
+                "success": true,
+                "path": filepath.display().to_string(),
+                "size_bytes": metadata.len(),
+                "region": region,
+            }),
+            vision_marker
+        ))
+    }
+}
+
+/// Run ImageMagick (`convert`, or `magick` on version 7).
+async fn run_imagemagick(args: &[String]) -> anyhow::Result<()> {
+    let mut last_error = String::new();
+    for program in ["convert", "magick"] {
+        match Command::new(program).args(args).output().await {
+            Ok(output) if output.status.success() => return Ok(()),
+            Ok(output) => {
+                last_error = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            }
+            Err(e) => last_error = e.to_string(),
+        }
+    }
+    Err(anyhow::anyhow!(
+        "ImageMagick failed (is it installed?): {}",
+        last_error
+    ))
+}
+
+/// Draw boxes and labels onto the last capture.
+pub struct Annotate;
+
+#[async_trait]
+impl Tool for Annotate {
+    fn name(&self) -> &str {
+        "desktop_annotate"
+    }
+
+    fn description(&self) -> &str {
+        "Draw boxes, points and labels onto the last desktop capture (or a given image) and save the result under screenshots/. Coordinates are screen pixels, so the same numbers work for desktop_click; they are shifted automatically for region captures. Useful to check a planned click target before acting."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "annotations": {
+                    "type": "array",
+                    "description": "Shapes to draw. With width and height it is a box, otherwise a point",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "x": { "type": "integer" },
+                            "y": { "type": "integer" },
+                            "width": { "type": "integer" },
+                            "height": { "type": "integer" },
+                            "label": { "type": "string" },
+                            "color": { "type": "string", "description": "Color name or #rrggbb (default: red)" }
+                        },
+                        "required": ["x", "y"]
+                    }
+                },
+                "image": {
+                    "type": "string",
+                    "description": "Image to annotate (default: the last desktop capture). Its coordinates are used as-is"
+                },
+                "return_image": {
+                    "type": "boolean",
+                    "description": "Include the annotated image in your context (requires vision model). Default: false"
+                },
+                "filename": {
+                    "type": "string",
+                    "description": "Optional output filename (saved under screenshots/)"
+                }
+            },
+            "required": ["annotations"]
+        })
+    }
+
+    async fn execute(&self, args: Value, working_dir: &Path) -> anyhow::Result<String> {
+        let annotations = args["annotations"]
+            .as_array()
+            .ok_or_else(|| anyhow::anyhow!("Missing 'annotations' argument"))?;
+        let (input, region) = match args["image"].as_str().filter(|s| !s.is_empty()) {
+            Some(image) => (super::resolve_path_simple(image, working_dir), None),
+            None => {
+                let capture = last_capture(working_dir).ok_or_else(|| {
+                    anyhow::anyhow!("No previous capture; take a screenshot first or pass 'image'")
+                })?;
+                (capture.path, capture.region)
+            }
+        };
+        if !input.exists() {
+            return Err(anyhow::anyhow!("Image not found: {}", input.display()));
+        }
+
+        let screenshots_dir = working_dir.join("screenshots");
+        std::fs::create_dir_all(&screenshots_dir)?;
+        let output =
+            screenshots_dir.join(screenshot_filename(args["filename"].as_str(), "annotated"));
+
+        run_imagemagick(&annotate_args(&input, &output, annotations, region)?).await?;
+
+        let vision_marker = if args["return_image"].as_bool().unwrap_or(false) {
+            format!("\n\n[VISION_IMAGE:file://{}]", output.display())
+        } else {
+            String::new()
+        };
+        Ok(format!(
+            "{}{}",
+            json!({
+                "success": true,
+                "path": output.display().to_string(),
+                "source": input.display().to_string(),
+                "annotations": annotations.len(),
+            }),
+            vision_marker
+        ))
+    }
+}
+
 /// Send keyboard input to the desktop.
 pub struct TypeText;
 
@@ -1221,3 +1731,170 @@ Layout example (chrome left, terminal right):
         Ok(result)
     }
 }
+
+/// Replace the desktop clipboard contents.
+pub struct SetClipboard;
+
+#[async_trait]
+impl Tool for SetClipboard {
+    fn name(&self) -> &str {
+        "desktop_set_clipboard"
+    }
+
+    fn description(&self) -> &str {
+        "Put text on the virtual desktop's clipboard (via xclip or xsel). Follow with desktop_type key='ctrl+v' to paste it; much faster and more reliable than typing long text."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "display": {
+                    "type": "string",
+                    "description": "The display identifier (e.g., ':99')"
+                },
+                "text": {
+                    "type": "string",
+                    "description": "Text to place on the clipboard"
+                },
+                "selection": {
+                    "type": "string",
+                    "enum": ["clipboard", "primary"],
+                    "description": "Which selection to set (default: clipboard). 'primary' is pasted with a middle click."
+                }
+            },
+            "required": ["display", "text"]
+        })
+    }
+
+    async fn execute(&self, args: Value, _working_dir: &Path) -> anyhow::Result<String> {
+        let display_id = args["display"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing 'display' argument"))?;
+        let text = args["text"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing 'text' argument"))?;
+        let primary = args["selection"].as_str() == Some("primary");
+
+        tracing::info!(display = %display_id, chars = text.chars().count(), "Setting clipboard");
+
+        let program = set_clipboard(display_id, text, primary).await?;
+        Ok(json!({
+            "success": true,
+            "selection": if primary { "primary" } else { "clipboard" },
+            "chars": text.chars().count(),
+            "via": program,
+        })
+        .to_string())
+    }
+}
+
+/// Read the desktop clipboard contents.
+pub struct GetClipboard;
+
+#[async_trait]
+impl Tool for GetClipboard {
+    fn name(&self) -> &str {
+        "desktop_get_clipboard"
+    }
+
+    fn description(&self) -> &str {
+        "Read text from the virtual desktop's clipboard (via xclip or xsel). Use after copying in an application (e.g. desktop_type key='ctrl+c') to get exact text instead of OCR."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "display": {
+                    "type": "string",
+                    "description": "The display identifier (e.g., ':99')"
+                },
+                "selection": {
+                    "type": "string",
+                    "enum": ["clipboard", "primary"],
+                    "description": "Which selection to read (default: clipboard). 'primary' holds the current text selection."
+                }
+            },
+            "required": ["display"]
+        })
+    }
+
+    async fn execute(&self, args: Value, _working_dir: &Path) -> anyhow::Result<String> {
+        let display_id = args["display"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing 'display' argument"))?;
+        let primary = args["selection"].as_str() == Some("primary");
+
+        let text = get_clipboard(display_id, primary).await?;
+        if text.is_empty() {
+            return Ok("(clipboard is empty)".to_string());
+        }
+        Ok(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_region_from_value() {
+        let region =
+            Region::from_value(&json!({"x": 10, "y": 20, "width": 300, "height": 200})).unwrap();
+        assert_eq!(region.scrot_geometry(), "10,20,300,200");
+        assert_eq!(region.crop_geometry(), "300x200+10+20");
+        assert!(Region::from_value(&json!({"x": 0, "y": 0, "width": 0, "height": 5})).is_err());
+        assert!(Region::from_value(&json!({"x": 0, "y": 0})).is_err());
+    }
+
+    #[test]
+    fn test_annotate_args_shift_region_and_escape_labels() {
+        let region = Region {
+            x: 100,
+            y: 50,
+            width: 400,
+            height: 300,
+        };
+        let args = annotate_args(
+            Path::new("in.png"),
+            Path::new("out.png"),
+            &[
+                json!({"x": 120, "y": 80, "width": 40, "height": 20, "label": "@/etc/passwd 100%"}),
+                json!({"x": 300, "y": 200, "color": "#00ff00"}),
+            ],
+            Some(region),
+        )
+        .unwrap();
+        assert_eq!(args.first().unwrap(), "in.png");
+        assert_eq!(args.last().unwrap(), "out.png");
+        assert!(args.contains(&"rectangle 20,30 60,50".to_string()));
+        assert!(args.contains(&"circle 200,150 206,150".to_string()));
+        assert!(args.contains(&" @/etc/passwd 100%%".to_string()));
+
+        let bad_color = [json!({"x": 1, "y": 1, "color": "red;rm"})];
+        assert!(annotate_args(Path::new("a"), Path::new("b"), &bad_color, None).is_err());
+        assert!(annotate_args(Path::new("a"), Path::new("b"), &[], None).is_err());
+    }
+
+    #[test]
+    fn test_last_capture_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(last_capture(dir.path()).is_none());
+        let image = dir.path().join("shot.png");
+        std::fs::write(&image, b"png").unwrap();
+        record_last_capture(
+            dir.path(),
+            &LastCapture {
+                path: image.clone(),
+                region: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(last_capture(dir.path()).unwrap().path, image);
+        assert_eq!(
+            screenshot_filename(Some("../../etc/evil.png"), "region"),
+            "evil.png"
+        );
+    }
+}
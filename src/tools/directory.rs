@@ -1,17 +1,25 @@
 //! Directory operation tools: list directory, search files by name.
 //!
+//! `search_files` lists candidates with `rg --files` when ripgrep is installed
+//! (so `.gitignore`/`.ignore` are honored) and falls back to walking the tree.
+//!
 //! ## Workspace-First Design
 //!
 //! These tools work relative to the workspace by default:
 //! - `src/` → lists `{workspace}/src/`
 //! - `/var/log` → absolute path for system directories
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use regex::Regex;
 use serde_json::{json, Value};
+use tokio::process::Command;
 use walkdir::WalkDir;
 
+use super::search::which_exists;
 use super::{resolve_path, Tool};
 
 /// List contents of a directory.
@@ -98,6 +106,21 @@ impl Tool for ListDirectory {
     }
 }
 
+/// Default number of files returned by `search_files`.
+const DEFAULT_MAX_RESULTS: usize = 100;
+const MAX_RESULTS_CAP: usize = 1000;
+/// Directories skipped by the walkdir fallback (ripgrep uses ignore files instead).
+const FALLBACK_IGNORED_DIRS: &[&str] = &[
+    ".git",
+    "node_modules",
+    "target",
+    "dist",
+    "build",
+    "__pycache__",
+    ".venv",
+    "venv",
+];
+
 /// Search for files by name pattern.
 pub struct SearchFiles;
 
@@ -108,7 +131,7 @@ impl Tool for SearchFiles {
     }
 
     fn description(&self) -> &str {
-        "Search for files by name pattern (glob-style). Searches workspace by default, or specify a path."
+        "Search for files by glob pattern (e.g. '*.rs', '**/tests/*.py', 'src/**/mod.rs'). Respects .gitignore/.ignore, supports max depth and result limits, and returns each file's size and modification time. Searches workspace by default, or specify a path."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -117,11 +140,27 @@ impl Tool for SearchFiles {
             "properties": {
                 "pattern": {
                     "type": "string",
-                    "description": "File name pattern to search for (e.g., '*.rs', 'test_*.py', 'README*')"
+                    "description": "Glob to match (e.g., '*.rs', 'test_*.py', '**/src/**/*.ts'). Patterns containing '/' match the path relative to the search directory; others match the file name. Without wildcards, matches file names containing the text. Case-insensitive."
                 },
                 "path": {
                     "type": "string",
                     "description": "Directory to search in. Defaults to workspace ('.'). Use relative paths or absolute for system-wide search."
+                },
+                "max_depth": {
+                    "type": "integer",
+                    "description": "Maximum directory depth to descend (default: unlimited)"
+                },
+                "max_results": {
+                    "type": "integer",
+                    "description": "Maximum number of files to return (default: 100, max: 1000)"
+                },
+                "include_hidden": {
+                    "type": "boolean",
+                    "description": "Include hidden files and directories (default: false)"
+                },
+                "no_ignore": {
+                    "type": "boolean",
+                    "description": "Don't respect .gitignore/.ignore files (default: false)"
                 }
             },
             "required": ["pattern"]
@@ -133,6 +172,14 @@ impl Tool for SearchFiles {
             .as_str()
             .ok_or_else(|| anyhow::anyhow!("Missing 'pattern' argument"))?;
         let path = args["path"].as_str().unwrap_or(".");
+        let max_depth = args["max_depth"].as_u64().map(|d| d as usize);
+        let max_results = args["max_results"]
+            .as_u64()
+            .map(|n| n as usize)
+            .unwrap_or(DEFAULT_MAX_RESULTS)
+            .clamp(1, MAX_RESULTS_CAP);
+        let include_hidden = args["include_hidden"].as_bool().unwrap_or(false);
+        let no_ignore = args["no_ignore"].as_bool().unwrap_or(false);
 
         let resolution = resolve_path(path, working_dir);
         let full_path = resolution.resolved;
@@ -145,78 +192,234 @@ impl Tool for SearchFiles {
             ));
         }
 
-        // Convert glob pattern to simple matching
-        let pattern_lower = pattern.to_lowercase();
-        let is_glob = pattern.contains('*');
-
-        let mut matches = Vec::new();
-        let walker = WalkDir::new(&full_path).into_iter().filter_map(|e| e.ok());
-
-        for entry in walker {
-            if !entry.file_type().is_file() {
+        let matcher = FileMatcher::new(pattern)?;
+        let candidates = if which_exists("rg") {
+            list_files_rg(&full_path, max_depth, include_hidden, no_ignore).await?
+        } else {
+            list_files_walkdir(&full_path, max_depth, include_hidden, no_ignore)
+        };
+
+        let mut total = 0usize;
+        let mut files = Vec::new();
+        for file in candidates {
+            let relative = file.strip_prefix(&full_path).unwrap_or(&file);
+            if !matcher.is_match(relative) {
                 continue;
             }
-
-            let file_name = entry.file_name().to_string_lossy().to_lowercase();
-
-            let matched = if is_glob {
-                // Simple glob matching
-                glob_match(&pattern_lower, &file_name)
-            } else {
-                file_name.contains(&pattern_lower)
-            };
-
-            if matched {
-                // Show absolute path for system-wide clarity
-                matches.push(entry.path().to_string_lossy().to_string());
-            }
-
-            // Limit results
-            if matches.len() >= 100 {
-                matches.push("... (results truncated, showing first 100)".to_string());
-                break;
+            total += 1;
+            if files.len() >= max_results {
+                continue;
             }
+            let metadata = tokio::fs::metadata(&file).await.ok();
+            files.push(json!({
+                // Show absolute path for system-wide clarity
+                "path": file.to_string_lossy(),
+                "size": metadata.as_ref().map(|m| m.len()),
+                "modified": metadata
+                    .and_then(|m| m.modified().ok())
+                    .map(|t| DateTime::<Utc>::from(t).to_rfc3339()),
+            }));
         }
 
-        if matches.is_empty() {
-            Ok(format!("No files matching '{}' found", pattern))
-        } else {
-            Ok(matches.join("\n"))
-        }
+        Ok(serde_json::to_string_pretty(&json!({
+            "pattern": pattern,
+            "root": full_path.to_string_lossy(),
+            "total_matches": total,
+            "truncated": total > files.len(),
+            "files": files,
+        }))?)
     }
 }
 
-/// Simple glob pattern matching.
-fn glob_match(pattern: &str, text: &str) -> bool {
-    let parts: Vec<&str> = pattern.split('*').collect();
+/// Matches file paths against a `search_files` pattern.
+struct FileMatcher {
+    regex: Regex,
+    /// Match against the relative path rather than the file name.
+    full_path: bool,
+}
 
-    if parts.len() == 1 {
-        // No wildcards
-        return pattern == text;
+impl FileMatcher {
+    fn new(pattern: &str) -> anyhow::Result<Self> {
+        let pattern = pattern.trim().trim_start_matches("./");
+        let is_glob = pattern.contains(['*', '?']);
+        let source = if is_glob {
+            glob_to_regex(pattern)
+        } else {
+            // Plain text: file names containing it
+            format!(".*{}.*", regex::escape(pattern))
+        };
+        Ok(Self {
+            regex: Regex::new(&format!("(?i)^{}$", source))
+                .map_err(|e| anyhow::anyhow!("Invalid pattern '{}': {}", pattern, e))?,
+            full_path: is_glob && pattern.contains('/'),
+        })
     }
 
-    let mut pos = 0;
-    for (i, part) in parts.iter().enumerate() {
-        if part.is_empty() {
-            continue;
+    fn is_match(&self, relative: &Path) -> bool {
+        if self.full_path {
+            self.regex.is_match(&relative.to_string_lossy())
+        } else {
+            relative
+                .file_name()
+                .map(|name| self.regex.is_match(&name.to_string_lossy()))
+                .unwrap_or(false)
         }
+    }
+}
 
-        match text[pos..].find(part) {
-            Some(idx) => {
-                // First part must be at start if pattern doesn't start with *
-                if i == 0 && idx != 0 {
-                    return false;
+/// Translate a glob (`**`, `*`, `?`) into a regex body.
+fn glob_to_regex(glob: &str) -> String {
+    let mut out = String::new();
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    // `**/` matches zero or more directories
+                    chars.next();
+                    out.push_str("(?:.*/)?");
+                } else {
+                    out.push_str(".*");
                 }
-                pos += idx + part.len();
             }
-            None => return false,
+            '*' => out.push_str("[^/]*"),
+            '?' => out.push_str("[^/]"),
+            c => out.push_str(&regex::escape(&c.to_string())),
         }
     }
+    out
+}
+
+/// List files with `rg --files`, which honors .gitignore/.ignore.
+async fn list_files_rg(
+    root: &Path,
+    max_depth: Option<usize>,
+    include_hidden: bool,
+    no_ignore: bool,
+) -> anyhow::Result<Vec<PathBuf>> {
+    let mut cmd = Command::new("rg");
+    cmd.arg("--files").arg("--sort").arg("path");
+    if let Some(depth) = max_depth {
+        cmd.arg("--max-depth").arg(depth.to_string());
+    }
+    if include_hidden {
+        cmd.arg("--hidden").arg("-g").arg("!.git/");
+    }
+    if no_ignore {
+        cmd.arg("--no-ignore");
+    }
+    let output = cmd
+        .arg("--")
+        .arg(root)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to list files: {}", e))?;
+
+    // Exit code 1 means no files; 2 can come with partial results.
+    if !output.status.success() && output.stdout.is_empty() && output.status.code() != Some(1) {
+        return Err(anyhow::anyhow!(
+            "File listing failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(PathBuf::from)
+        .collect())
+}
 
-    // Last part must be at end if pattern doesn't end with *
-    if !pattern.ends_with('*') && !parts.last().unwrap().is_empty() {
-        return text.ends_with(parts.last().unwrap());
+/// Walk the tree without ripgrep, skipping hidden and common build directories.
+fn list_files_walkdir(
+    root: &Path,
+    max_depth: Option<usize>,
+    include_hidden: bool,
+    no_ignore: bool,
+) -> Vec<PathBuf> {
+    let mut walker = WalkDir::new(root).sort_by_file_name();
+    if let Some(depth) = max_depth {
+        walker = walker.max_depth(depth);
     }
+    walker
+        .into_iter()
+        .filter_entry(|entry| {
+            if entry.depth() == 0 {
+                return true;
+            }
+            let name = entry.file_name().to_string_lossy();
+            if !include_hidden && name.starts_with('.') {
+                return false;
+            }
+            no_ignore
+                || !entry.file_type().is_dir()
+                || !FALLBACK_IGNORED_DIRS.contains(&name.as_ref())
+        })
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.into_path())
+        .collect()
+}
 
-    true
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_matcher() {
+        let by_name = FileMatcher::new("*.rs").unwrap();
+        assert!(by_name.is_match(Path::new("src/tools/mod.rs")));
+        assert!(by_name.is_match(Path::new("MAIN.RS")));
+        assert!(!by_name.is_match(Path::new("src/main.rsx")));
+
+        let by_path = FileMatcher::new("src/**/mod.rs").unwrap();
+        assert!(by_path.is_match(Path::new("src/mod.rs")));
+        assert!(by_path.is_match(Path::new("src/tools/deep/mod.rs")));
+        assert!(!by_path.is_match(Path::new("lib/src/mod.rs")));
+
+        let anywhere = FileMatcher::new("**/tests/test_?.py").unwrap();
+        assert!(anywhere.is_match(Path::new("tests/test_a.py")));
+        assert!(anywhere.is_match(Path::new("pkg/tests/test_b.py")));
+        assert!(!anywhere.is_match(Path::new("pkg/tests/test_ab.py")));
+
+        let plain = FileMatcher::new("readme").unwrap();
+        assert!(plain.is_match(Path::new("docs/README.md")));
+        assert!(!plain.is_match(Path::new("readme/index.md")));
+    }
+
+    #[test]
+    fn test_walkdir_fallback_skips_hidden_and_build_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        for file in [
+            "src/main.rs",
+            ".hidden/a.rs",
+            "target/debug/b.rs",
+            "deep/x/y/z.rs",
+        ] {
+            let path = dir.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "").unwrap();
+        }
+
+        let relative = |files: Vec<PathBuf>| -> Vec<String> {
+            files
+                .iter()
+                .map(|f| {
+                    f.strip_prefix(dir.path())
+                        .unwrap()
+                        .to_string_lossy()
+                        .to_string()
+                })
+                .collect()
+        };
+        assert_eq!(
+            relative(list_files_walkdir(dir.path(), None, false, false)),
+            vec!["deep/x/y/z.rs", "src/main.rs"]
+        );
+        assert_eq!(
+            relative(list_files_walkdir(dir.path(), Some(2), true, true)),
+            vec![".hidden/a.rs", "src/main.rs"]
+        );
+    }
 }
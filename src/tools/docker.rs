@@ -0,0 +1,626 @@
+//! Docker tools: build images, run containers, read logs and stop them.
+//!
+//! Containers started here carry a `sandboxed.sh.managed` label and always
+//! get memory, CPU and process limits (see [`DockerConfig`]). Bind mounts
+//! and build contexts must live in the workspace or in a path from
+//! `SANDBOXED_SH_DOCKER_MOUNT_ALLOWLIST`. `docker_logs` and `docker_stop`
+//! only touch containers carrying the label.
+
+use std::path::{Path, PathBuf};
+use std::process::Output;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use tokio::process::Command;
+
+use super::{resolve_path_simple, safe_truncate_index, Tool};
+use crate::config::DockerConfig;
+
+/// Label marking containers started by `docker_run`.
+const MANAGED_LABEL: &str = "sandboxed.sh.managed";
+const QUICK_TIMEOUT_SECS: u64 = 60;
+/// Bytes of build/run output returned (the tail is kept).
+const MAX_OUTPUT_BYTES: usize = 20_000;
+const DEFAULT_LOG_LINES: u64 = 200;
+const MAX_LOG_LINES: u64 = 5000;
+
+/// Image tags, container names and similar identifiers.
+fn validate_name(kind: &str, name: &str) -> anyhow::Result<()> {
+    let valid = !name.is_empty()
+        && !name.starts_with('-')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_.:/@".contains(c));
+    if !valid {
+        return Err(anyhow::anyhow!("Invalid {}: '{}'", kind, name));
+    }
+    Ok(())
+}
+
+/// Parse a Docker memory size (`512m`, `2g`, `1048576`) into bytes.
+fn parse_memory(value: &str) -> Option<u64> {
+    let value = value.trim().to_ascii_lowercase();
+    let value = value.strip_suffix('b').unwrap_or(value.as_str());
+    let (digits, multiplier) = match value.chars().last()? {
+        'k' => (&value[..value.len() - 1], 1024),
+        'm' => (&value[..value.len() - 1], 1024 * 1024),
+        'g' => (&value[..value.len() - 1], 1024 * 1024 * 1024),
+        _ => (value, 1),
+    };
+    digits.parse::<u64>().ok()?.checked_mul(multiplier)
+}
+
+/// Canonicalize `path` and require it to be under the workspace or an
+/// allowlisted directory.
+fn allowed_path(path: &Path, workspace: &Path, config: &DockerConfig) -> anyhow::Result<PathBuf> {
+    let canonical = path
+        .canonicalize()
+        .map_err(|e| anyhow::anyhow!("Cannot use {}: {}", path.display(), e))?;
+    let allowed = std::iter::once(workspace)
+        .chain(config.mount_allowlist.iter().map(PathBuf::as_path))
+        .filter_map(|root| root.canonicalize().ok())
+        .any(|root| canonical.starts_with(root));
+    if !allowed {
+        return Err(anyhow::anyhow!(
+            "{} is outside the workspace and SANDBOXED_SH_DOCKER_MOUNT_ALLOWLIST",
+            canonical.display()
+        ));
+    }
+    Ok(canonical)
+}
+
+/// Keep the end of long output, where errors and results usually are.
+fn tail(text: &str) -> String {
+    if text.len() <= MAX_OUTPUT_BYTES {
+        return text.to_string();
+    }
+    let mut start = text.len() - MAX_OUTPUT_BYTES;
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    format!("[... {} bytes omitted ...]\n{}", start, &text[start..])
+}
+
+fn combined_output(output: &Output) -> String {
+    let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !stderr.trim().is_empty() {
+        if !text.is_empty() && !text.ends_with('\n') {
+            text.push('\n');
+        }
+        text.push_str(&stderr);
+    }
+    text
+}
+
+/// Run the docker CLI, returning the output even on a non-zero exit.
+async fn docker(args: &[String], timeout_secs: u64) -> anyhow::Result<Output> {
+    let mut cmd = Command::new("docker");
+    cmd.args(args)
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true);
+    tokio::time::timeout(std::time::Duration::from_secs(timeout_secs), cmd.output())
+        .await
+        .map_err(|_| anyhow::anyhow!("docker {} timed out after {}s", args[0], timeout_secs))?
+        .map_err(|e| anyhow::anyhow!("Failed to run docker (is it installed?): {}", e))
+}
+
+/// Run the docker CLI and fail with its output on a non-zero exit.
+async fn docker_ok(args: &[String], timeout_secs: u64) -> anyhow::Result<Output> {
+    let output = docker(args, timeout_secs).await?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "docker {} failed: {}",
+            args[0],
+            tail(combined_output(&output).trim())
+        ));
+    }
+    Ok(output)
+}
+
+/// Fail unless `container` was started by `docker_run`.
+async fn ensure_managed(container: &str) -> anyhow::Result<()> {
+    validate_name("container", container)?;
+    let output = docker_ok(
+        &[
+            "inspect".to_string(),
+            "--type".to_string(),
+            "container".to_string(),
+            "--format".to_string(),
+            format!("{{{{index .Config.Labels \"{}\"}}}}", MANAGED_LABEL),
+            container.to_string(),
+        ],
+        QUICK_TIMEOUT_SECS,
+    )
+    .await?;
+    if String::from_utf8_lossy(&output.stdout).trim() != "true" {
+        return Err(anyhow::anyhow!(
+            "Container '{}' was not started by docker_run",
+            container
+        ));
+    }
+    Ok(())
+}
+
+/// String pairs from a JSON object argument (`env`, `build_args`).
+fn string_map(args: &Value, key: &str) -> anyhow::Result<Vec<(String, String)>> {
+    let Some(map) = args[key].as_object() else {
+        return Ok(Vec::new());
+    };
+    map.iter()
+        .map(|(k, v)| {
+            if k.is_empty() || k.contains('=') || k.starts_with('-') {
+                return Err(anyhow::anyhow!("Invalid {} name '{}'", key, k));
+            }
+            let value = match v {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            Ok((k.clone(), value))
+        })
+        .collect()
+}
+
+/// Resource limit flags, clamped to the configured maximums.
+fn limit_flags(args: &Value, config: &DockerConfig) -> anyhow::Result<Vec<String>> {
+    let memory = match args["memory"].as_str() {
+        Some(value) => parse_memory(value)
+            .ok_or_else(|| anyhow::anyhow!("Invalid memory '{}'", value))?
+            .min(config.max_memory_bytes),
+        None => config.max_memory_bytes,
+    };
+    let cpus = args["cpus"]
+        .as_f64()
+        .filter(|n| *n > 0.0)
+        .unwrap_or(config.max_cpus)
+        .min(config.max_cpus);
+    Ok(vec![
+        format!("--memory={}", memory),
+        format!("--memory-swap={}", memory),
+        format!("--cpus={}", cpus),
+        format!("--pids-limit={}", config.pids_limit),
+    ])
+}
+
+/// Parse a `mounts` entry into a `--mount` flag value.
+fn mount_flag(mount: &Value, workspace: &Path, config: &DockerConfig) -> anyhow::Result<String> {
+    let source = mount["source"]
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("Mount is missing 'source'"))?;
+    let target = mount["target"]
+        .as_str()
+        .filter(|t| t.starts_with('/'))
+        .ok_or_else(|| anyhow::anyhow!("Mount target must be an absolute container path"))?;
+    if target.contains(',') {
+        return Err(anyhow::anyhow!("Invalid mount target '{}'", target));
+    }
+    let source = allowed_path(&resolve_path_simple(source, workspace), workspace, config)?;
+    let source = source.to_string_lossy();
+    if source.contains(',') {
+        return Err(anyhow::anyhow!("Mount source may not contain ','"));
+    }
+    let mut flag = format!("type=bind,source={},target={}", source, target);
+    if mount["read_only"].as_bool().unwrap_or(false) {
+        flag.push_str(",readonly");
+    }
+    Ok(flag)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// docker_build
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Build an image from a context directory.
+pub struct DockerBuild;
+
+#[async_trait]
+impl Tool for DockerBuild {
+    fn name(&self) -> &str {
+        "docker_build"
+    }
+
+    fn description(&self) -> &str {
+        "Build a Docker image from a directory in the workspace. Returns the tail of the build log. The context and Dockerfile must be inside the workspace or an allowlisted path."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "tag": { "type": "string", "description": "Image tag, e.g. 'myapp:dev'" },
+                "context": { "type": "string", "description": "Build context directory (default: working directory)" },
+                "dockerfile": { "type": "string", "description": "Dockerfile path relative to the context (default: Dockerfile)" },
+                "build_args": { "type": "object", "description": "Build-time variables (--build-arg)" },
+                "target": { "type": "string", "description": "Stage to build in a multi-stage Dockerfile" },
+                "no_cache": { "type": "boolean", "description": "Ignore cached layers (default: false)" },
+                "pull": { "type": "boolean", "description": "Always pull newer base images (default: false)" }
+            },
+            "required": ["tag"]
+        })
+    }
+
+    async fn execute(&self, args: Value, working_dir: &Path) -> anyhow::Result<String> {
+        let config = DockerConfig::from_env();
+        let tag = args["tag"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing 'tag' argument"))?;
+        validate_name("tag", tag)?;
+        let context = allowed_path(
+            &resolve_path_simple(args["context"].as_str().unwrap_or("."), working_dir),
+            working_dir,
+            &config,
+        )?;
+
+        let mut docker_args = vec!["build".to_string(), "--tag".to_string(), tag.to_string()];
+        if let Some(dockerfile) = args["dockerfile"].as_str() {
+            let path = allowed_path(&context.join(dockerfile), working_dir, &config)?;
+            docker_args.extend(["--file".to_string(), path.display().to_string()]);
+        }
+        if let Some(target) = args["target"].as_str() {
+            validate_name("target", target)?;
+            docker_args.extend(["--target".to_string(), target.to_string()]);
+        }
+        for (key, value) in string_map(&args, "build_args")? {
+            docker_args.extend(["--build-arg".to_string(), format!("{}={}", key, value)]);
+        }
+        if args["no_cache"].as_bool().unwrap_or(false) {
+            docker_args.push("--no-cache".to_string());
+        }
+        if args["pull"].as_bool().unwrap_or(false) {
+            docker_args.push("--pull".to_string());
+        }
+        docker_args.extend(["--label".to_string(), format!("{}=true", MANAGED_LABEL)]);
+        docker_args.push(context.display().to_string());
+
+        let output = docker_ok(&docker_args, config.timeout_secs).await?;
+        Ok(format!(
+            "Built image '{}'\n\n{}",
+            tag,
+            tail(combined_output(&output).trim())
+        ))
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// docker_run
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Start a container with resource limits and vetted mounts.
+pub struct DockerRun;
+
+#[async_trait]
+impl Tool for DockerRun {
+    fn name(&self) -> &str {
+        "docker_run"
+    }
+
+    fn description(&self) -> &str {
+        "Run a Docker container. By default it runs in the background and returns the container ID (check it with docker_logs, end it with docker_stop); set detach=false to wait for it and get its output. Memory, CPU and process limits always apply. Bind mounts must be inside the workspace or an allowlisted path."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "image": { "type": "string", "description": "Image to run" },
+                "command": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Command and arguments (default: the image's CMD)"
+                },
+                "name": { "type": "string", "description": "Container name" },
+                "detach": { "type": "boolean", "description": "Run in the background (default: true)" },
+                "env": { "type": "object", "description": "Environment variables" },
+                "ports": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Port mappings like '8080:80' or '127.0.0.1:8080:80'"
+                },
+                "mounts": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "source": { "type": "string" },
+                            "target": { "type": "string" },
+                            "read_only": { "type": "boolean" }
+                        },
+                        "required": ["source", "target"]
+                    },
+                    "description": "Bind mounts from the workspace (or allowlisted paths)"
+                },
+                "workdir": { "type": "string", "description": "Working directory inside the container" },
+                "network": { "type": "string", "description": "Network to attach to (e.g. 'none', 'bridge')" },
+                "memory": { "type": "string", "description": "Memory limit like '512m' (capped by server config)" },
+                "cpus": { "type": "number", "description": "CPU limit (capped by server config)" },
+                "timeout_secs": { "type": "integer", "description": "Timeout when detach=false (capped by server config)" }
+            },
+            "required": ["image"]
+        })
+    }
+
+    async fn execute(&self, args: Value, working_dir: &Path) -> anyhow::Result<String> {
+        let config = DockerConfig::from_env();
+        let image = args["image"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing 'image' argument"))?;
+        validate_name("image", image)?;
+        let detach = args["detach"].as_bool().unwrap_or(true);
+
+        let mut docker_args = vec![
+            "run".to_string(),
+            "--label".to_string(),
+            format!("{}=true", MANAGED_LABEL),
+        ];
+        docker_args.push(if detach { "--detach" } else { "--rm" }.to_string());
+        docker_args.extend(limit_flags(&args, &config)?);
+        if let Some(name) = args["name"].as_str() {
+            validate_name("name", name)?;
+            docker_args.extend(["--name".to_string(), name.to_string()]);
+        }
+        for (key, value) in string_map(&args, "env")? {
+            docker_args.extend(["--env".to_string(), format!("{}={}", key, value)]);
+        }
+        for port in args["ports"].as_array().into_iter().flatten() {
+            let port = port
+                .as_str()
+                .filter(|p| {
+                    !p.is_empty()
+                        && p.chars()
+                            .all(|c| c.is_ascii_digit() || ".:/-[]tcpud".contains(c))
+                })
+                .ok_or_else(|| anyhow::anyhow!("Invalid port mapping {}", port))?;
+            docker_args.extend(["--publish".to_string(), port.to_string()]);
+        }
+        for mount in args["mounts"].as_array().into_iter().flatten() {
+            docker_args.extend([
+                "--mount".to_string(),
+                mount_flag(mount, working_dir, &config)?,
+            ]);
+        }
+        if let Some(workdir) = args["workdir"].as_str() {
+            if !workdir.starts_with('/') {
+                return Err(anyhow::anyhow!("'workdir' must be an absolute path"));
+            }
+            docker_args.extend(["--workdir".to_string(), workdir.to_string()]);
+        }
+        if let Some(network) = args["network"].as_str() {
+            validate_name("network", network)?;
+            if network == "host" || network.starts_with("container:") {
+                return Err(anyhow::anyhow!("Network '{}' is not allowed", network));
+            }
+            docker_args.extend(["--network".to_string(), network.to_string()]);
+        }
+        docker_args.push(image.to_string());
+        for part in args["command"].as_array().into_iter().flatten() {
+            let part = part
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("'command' must be an array of strings"))?;
+            docker_args.push(part.to_string());
+        }
+
+        if detach {
+            let output = docker_ok(&docker_args, QUICK_TIMEOUT_SECS * 5).await?;
+            let id = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            return Ok(serde_json::to_string_pretty(&json!({
+                "container_id": &id[..safe_truncate_index(&id, 12)],
+                "name": args["name"],
+                "image": image,
+                "status": "running",
+            }))?);
+        }
+
+        let timeout = args["timeout_secs"]
+            .as_u64()
+            .unwrap_or(config.timeout_secs)
+            .min(config.timeout_secs);
+        let output = docker(&docker_args, timeout).await?;
+        Ok(format!(
+            "Exit code: {}\n\n{}",
+            output
+                .status
+                .code()
+                .map(|c| c.to_string())
+                .unwrap_or_else(|| "killed".to_string()),
+            tail(combined_output(&output).trim())
+        ))
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// docker_logs
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Read a managed container's logs and state.
+pub struct DockerLogs;
+
+#[async_trait]
+impl Tool for DockerLogs {
+    fn name(&self) -> &str {
+        "docker_logs"
+    }
+
+    fn description(&self) -> &str {
+        "Show the state and recent logs of a container started with docker_run."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "container": { "type": "string", "description": "Container name or ID" },
+                "tail": { "type": "integer", "description": "Lines from the end (default: 200, max: 5000)" },
+                "since": { "type": "string", "description": "Only logs since this time (RFC3339 or relative like '10m')" }
+            },
+            "required": ["container"]
+        })
+    }
+
+    async fn execute(&self, args: Value, _working_dir: &Path) -> anyhow::Result<String> {
+        let container = args["container"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing 'container' argument"))?;
+        ensure_managed(container).await?;
+
+        let state = docker_ok(
+            &[
+                "inspect".to_string(),
+                "--format".to_string(),
+                "{{.State.Status}} (exit code {{.State.ExitCode}})".to_string(),
+                container.to_string(),
+            ],
+            QUICK_TIMEOUT_SECS,
+        )
+        .await?;
+
+        let lines = args["tail"]
+            .as_u64()
+            .unwrap_or(DEFAULT_LOG_LINES)
+            .min(MAX_LOG_LINES);
+        let mut docker_args = vec![
+            "logs".to_string(),
+            "--timestamps".to_string(),
+            format!("--tail={}", lines),
+        ];
+        if let Some(since) = args["since"].as_str() {
+            validate_name("since", since)?;
+            docker_args.push(format!("--since={}", since));
+        }
+        docker_args.push(container.to_string());
+        let logs = docker_ok(&docker_args, QUICK_TIMEOUT_SECS).await?;
+
+        Ok(format!(
+            "Container '{}': {}\n\n{}",
+            container,
+            String::from_utf8_lossy(&state.stdout).trim(),
+            tail(combined_output(&logs).trim())
+        ))
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// docker_stop
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Stop (and by default remove) a managed container.
+pub struct DockerStop;
+
+#[async_trait]
+impl Tool for DockerStop {
+    fn name(&self) -> &str {
+        "docker_stop"
+    }
+
+    fn description(&self) -> &str {
+        "Stop a container started with docker_run and remove it (set remove=false to keep it for inspection)."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "container": { "type": "string", "description": "Container name or ID" },
+                "remove": { "type": "boolean", "description": "Remove the container after stopping (default: true)" },
+                "timeout_secs": { "type": "integer", "description": "Seconds to wait before killing (default: 10)" }
+            },
+            "required": ["container"]
+        })
+    }
+
+    async fn execute(&self, args: Value, _working_dir: &Path) -> anyhow::Result<String> {
+        let container = args["container"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing 'container' argument"))?;
+        ensure_managed(container).await?;
+
+        let grace = args["timeout_secs"].as_u64().unwrap_or(10).min(300);
+        docker_ok(
+            &[
+                "stop".to_string(),
+                format!("--time={}", grace),
+                container.to_string(),
+            ],
+            grace + QUICK_TIMEOUT_SECS,
+        )
+        .await?;
+
+        if args["remove"].as_bool().unwrap_or(true) {
+            docker_ok(
+                &["rm".to_string(), container.to_string()],
+                QUICK_TIMEOUT_SECS,
+            )
+            .await?;
+            Ok(format!("Stopped and removed container '{}'", container))
+        } else {
+            Ok(format!("Stopped container '{}'", container))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_memory() {
+        assert_eq!(parse_memory("512m"), Some(512 * 1024 * 1024));
+        assert_eq!(parse_memory("2G"), Some(2 * 1024 * 1024 * 1024));
+        assert_eq!(parse_memory("64kb"), Some(64 * 1024));
+        assert_eq!(parse_memory("1048576"), Some(1048576));
+        assert_eq!(parse_memory("lots"), None);
+        assert_eq!(parse_memory(""), None);
+    }
+
+    #[test]
+    fn test_limit_flags_are_capped() {
+        let config = DockerConfig::default();
+        let flags = limit_flags(&json!({"memory": "64g", "cpus": 32.0}), &config).unwrap();
+        assert!(flags.contains(&format!("--memory={}", config.max_memory_bytes)));
+        assert!(flags.contains(&format!("--cpus={}", config.max_cpus)));
+
+        let flags = limit_flags(&json!({"memory": "256m", "cpus": 0.5}), &config).unwrap();
+        assert!(flags.contains(&format!("--memory={}", 256 * 1024 * 1024)));
+        assert!(flags.contains(&"--cpus=0.5".to_string()));
+    }
+
+    #[test]
+    fn test_mount_allowlist() {
+        let workspace = tempfile::tempdir().unwrap();
+        let extra = tempfile::tempdir().unwrap();
+        std::fs::create_dir(workspace.path().join("data")).unwrap();
+        let mut config = DockerConfig::default();
+
+        let flag = mount_flag(
+            &json!({"source": "data", "target": "/data", "read_only": true}),
+            workspace.path(),
+            &config,
+        )
+        .unwrap();
+        assert!(flag.ends_with("target=/data,readonly"));
+
+        let outside = json!({"source": extra.path().to_str().unwrap(), "target": "/x"});
+        assert!(mount_flag(&outside, workspace.path(), &config).is_err());
+        assert!(mount_flag(
+            &json!({"source": "../", "target": "/x"}),
+            workspace.path(),
+            &config
+        )
+        .is_err());
+
+        config.mount_allowlist.push(extra.path().to_path_buf());
+        assert!(mount_flag(&outside, workspace.path(), &config).is_ok());
+        assert!(mount_flag(
+            &json!({"source": "data", "target": "relative"}),
+            workspace.path(),
+            &config
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("image", "ghcr.io/org/app:1.2@sha256:abc").is_ok());
+        assert!(validate_name("image", "--privileged").is_err());
+        assert!(validate_name("image", "bad name").is_err());
+    }
+}
@@ -0,0 +1,227 @@
+//! Text embeddings through the configured AI providers.
+//!
+//! Uses the OpenAI-compatible `/embeddings` endpoint (OpenAI, OpenRouter,
+//! Google, Mistral or a custom provider). Like the vision tool, it falls back
+//! to API keys from the environment when no provider store is available.
+
+use serde_json::{json, Value};
+
+use crate::ai_providers::{AIProvider, ProviderType};
+
+const REQUEST_TIMEOUT_SECS: u64 = 120;
+/// Inputs sent per request; providers cap batch sizes.
+const BATCH_SIZE: usize = 64;
+
+/// A resolved embeddings endpoint.
+#[derive(Debug)]
+struct EmbeddingProvider {
+    label: String,
+    base_url: String,
+    api_key: Option<String>,
+    model: String,
+}
+
+/// Default base URL and embedding model for a provider type.
+fn embedding_defaults(provider_type: ProviderType) -> Option<(&'static str, &'static str)> {
+    Some(match provider_type {
+        ProviderType::OpenAI => ("https://api.openai.com/v1", "text-embedding-3-small"),
+        ProviderType::OpenRouter => (
+            "https://openrouter.ai/api/v1",
+            "openai/text-embedding-3-small",
+        ),
+        ProviderType::Google => (
+            "https://generativelanguage.googleapis.com/v1beta/openai",
+            "text-embedding-004",
+        ),
+        ProviderType::Mistral => ("https://api.mistral.ai/v1", "mistral-embed"),
+        ProviderType::Custom => ("", ""),
+        _ => return None,
+    })
+}
+
+fn provider_from_config(provider: &AIProvider, model: Option<&str>) -> Option<EmbeddingProvider> {
+    let (base_url, default_model) = embedding_defaults(provider.provider_type)?;
+    if provider.api_key.is_none() && provider.provider_type != ProviderType::Custom {
+        return None;
+    }
+    let base_url = provider
+        .base_url
+        .clone()
+        .unwrap_or_else(|| base_url.to_string());
+    let model = model
+        .map(str::to_string)
+        .or_else(|| {
+            provider
+                .custom_models
+                .as_ref()
+                .and_then(|models| models.iter().find(|m| m.id.contains("embed")))
+                .map(|m| m.id.clone())
+        })
+        .unwrap_or_else(|| default_model.to_string());
+    if base_url.is_empty() || model.is_empty() {
+        return None;
+    }
+    Some(EmbeddingProvider {
+        label: provider.name.clone(),
+        base_url,
+        api_key: provider.api_key.clone(),
+        model,
+    })
+}
+
+/// Pick the default provider if it supports embeddings, then any other
+/// usable one, then API keys from the environment.
+async fn resolve_provider(model: Option<&str>) -> anyhow::Result<EmbeddingProvider> {
+    if let Some(store) = crate::ai_providers::global() {
+        let default = store.get_default().await;
+        let mut providers = store.list().await;
+        providers.sort_by_key(|p| Some(p.id) != default.as_ref().map(|d| d.id));
+        for provider in providers.iter().filter(|p| p.enabled) {
+            if let Some(resolved) = provider_from_config(provider, model) {
+                return Ok(resolved);
+            }
+        }
+    }
+
+    for provider_type in [
+        ProviderType::OpenAI,
+        ProviderType::OpenRouter,
+        ProviderType::Google,
+        ProviderType::Mistral,
+    ] {
+        let Some(key) = provider_type
+            .env_var_name()
+            .and_then(|var| std::env::var(var).ok())
+            .filter(|key| !key.trim().is_empty())
+        else {
+            continue;
+        };
+        let mut provider = AIProvider::new(provider_type, provider_type.display_name().to_string());
+        provider.api_key = Some(key);
+        if let Some(resolved) = provider_from_config(&provider, model) {
+            return Ok(resolved);
+        }
+    }
+
+    Err(anyhow::anyhow!(
+        "No embeddings-capable AI provider is configured. Add an OpenAI, OpenRouter, Google, Mistral or custom OpenAI-compatible provider with an API key."
+    ))
+}
+
+fn embeddings_url(base_url: &str) -> String {
+    let base = base_url.trim_end_matches('/');
+    if base.ends_with("/embeddings") {
+        base.to_string()
+    } else {
+        format!("{}/embeddings", base)
+    }
+}
+
+/// Pull vectors out of an embeddings response, ordered by input index.
+fn parse_embeddings(response: &Value) -> Option<Vec<Vec<f32>>> {
+    let mut items: Vec<(u64, Vec<f32>)> = response["data"]
+        .as_array()?
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let vector = item["embedding"]
+                .as_array()?
+                .iter()
+                .map(|v| v.as_f64().map(|f| f as f32))
+                .collect::<Option<Vec<f32>>>()?;
+            Some((item["index"].as_u64().unwrap_or(i as u64), vector))
+        })
+        .collect::<Option<_>>()?;
+    items.sort_by_key(|(index, _)| *index);
+    Some(items.into_iter().map(|(_, v)| v).collect())
+}
+
+/// Embed `texts`. Returns one vector per input and the model used.
+pub(super) async fn embed(
+    texts: &[String],
+    model: Option<&str>,
+) -> anyhow::Result<(Vec<Vec<f32>>, String)> {
+    let provider = resolve_provider(model).await?;
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .build()?;
+    let url = embeddings_url(&provider.base_url);
+
+    let mut vectors = Vec::with_capacity(texts.len());
+    for batch in texts.chunks(BATCH_SIZE) {
+        let mut request = client
+            .post(&url)
+            .json(&json!({ "model": provider.model, "input": batch }));
+        if let Some(key) = &provider.api_key {
+            request = request.bearer_auth(key);
+        }
+        let response = request.send().await?;
+        let status = response.status();
+        let text = response.text().await?;
+        if !status.is_success() {
+            let end = super::safe_truncate_index(&text, 500);
+            return Err(anyhow::anyhow!(
+                "Embeddings request to {} failed ({}): {}",
+                provider.label,
+                status,
+                &text[..end]
+            ));
+        }
+        let parsed = parse_embeddings(&serde_json::from_str(&text)?)
+            .filter(|v| v.len() == batch.len())
+            .ok_or_else(|| anyhow::anyhow!("{} returned malformed embeddings", provider.label))?;
+        vectors.extend(parsed);
+    }
+    Ok((vectors, provider.model))
+}
+
+/// Cosine similarity of two vectors (0 when either is empty or zero).
+pub(super) fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let (mut dot, mut norm_a, mut norm_b) = (0f32, 0f32, 0f32);
+    for (x, y) in a.iter().zip(b) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a.sqrt() * norm_b.sqrt())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provider_resolution_and_parsing() {
+        let anthropic = AIProvider::new(ProviderType::Anthropic, "Claude".to_string());
+        assert!(provider_from_config(&anthropic, None).is_none());
+
+        let mut openai = AIProvider::new(ProviderType::OpenAI, "Work".to_string());
+        openai.api_key = Some("key".to_string());
+        let resolved = provider_from_config(&openai, None).unwrap();
+        assert_eq!(resolved.model, "text-embedding-3-small");
+        assert_eq!(
+            embeddings_url(&resolved.base_url),
+            "https://api.openai.com/v1/embeddings"
+        );
+
+        let response = json!({"data": [
+            {"index": 1, "embedding": [0.0, 1.0]},
+            {"index": 0, "embedding": [1.0, 0.0]}
+        ]});
+        assert_eq!(
+            parse_embeddings(&response).unwrap(),
+            vec![vec![1.0, 0.0], vec![0.0, 1.0]]
+        );
+    }
+
+    #[test]
+    fn test_cosine_similarity() {
+        assert!((cosine_similarity(&[1.0, 0.0], &[2.0, 0.0]) - 1.0).abs() < 1e-6);
+        assert!(cosine_similarity(&[1.0, 0.0], &[0.0, 3.0]).abs() < 1e-6);
+        assert_eq!(cosine_similarity(&[], &[]), 0.0);
+    }
+}
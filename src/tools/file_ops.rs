@@ -1,4 +1,4 @@
-//! File operation tools: read, write, delete files.
+//! File operation tools: read, write, edit, move, copy, delete files.
 //!
 //! ## Workspace-First Design
 //!
@@ -9,9 +9,34 @@
 use std::path::Path;
 
 use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use serde_json::{json, Value};
 
-use super::{resolve_path, Tool};
+use super::{resolve_path, PathResolution, Tool};
+
+/// Content encoding accepted by `read_file`/`write_file`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    Utf8,
+    Base64,
+}
+
+impl Encoding {
+    fn from_args(args: &Value) -> anyhow::Result<Self> {
+        match args["encoding"].as_str().map(|s| s.to_ascii_lowercase()) {
+            None => Ok(Self::Utf8),
+            Some(e) if e == "utf8" || e == "utf-8" => Ok(Self::Utf8),
+            Some(e) if e == "base64" => Ok(Self::Base64),
+            Some(e) => Err(anyhow::anyhow!(
+                "Unsupported encoding '{}': use 'utf8' or 'base64'",
+                e
+            )),
+        }
+    }
+}
+
+/// Output cap for `read_file` when the caller doesn't pass `max_bytes`.
+const READ_FILE_DEFAULT_MAX_BYTES: usize = 64 * 1024;
 
 /// Read the contents of a file.
 pub struct ReadFile;
@@ -23,7 +48,7 @@ impl Tool for ReadFile {
     }
 
     fn description(&self) -> &str {
-        "Read a file's contents. Use relative paths like 'src/main.rs' (recommended) or absolute paths like '/etc/hosts' for system files."
+        "Read a file's contents with line numbers. Use relative paths like 'src/main.rs' (recommended) or absolute paths like '/etc/hosts' for system files. Large files are truncated (64 KB by default) with a notice giving the total line count; page through them with offset/limit instead of reading everything. Use encoding 'base64' to read binary files byte-exactly."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -41,6 +66,27 @@ impl Tool for ReadFile {
                 "end_line": {
                     "type": "integer",
                     "description": "Optional: stop reading at this line number (inclusive)"
+                },
+                "offset": {
+                    "type": "integer",
+                    "description": "Optional: line number to start reading from (1-indexed); same as start_line"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Optional: maximum number of lines to read"
+                },
+                "max_bytes": {
+                    "type": "integer",
+                    "description": "Optional: maximum bytes of output (default: 65536). With base64 encoding, caps the raw bytes read."
+                },
+                "encoding": {
+                    "type": "string",
+                    "enum": ["utf8", "base64"],
+                    "description": "Optional: 'base64' returns the raw bytes base64-encoded, for images, archives and other binary files (default: utf8)"
+                },
+                "byte_offset": {
+                    "type": "integer",
+                    "description": "Optional: with base64 encoding, byte position to start reading from (default: 0)"
                 }
             },
             "required": ["path"]
@@ -62,8 +108,41 @@ impl Tool for ReadFile {
             ));
         }
 
+        let encoding = Encoding::from_args(&args)?;
+
         // Try to read as UTF-8 text, detect binary files
         let bytes = tokio::fs::read(&resolution.resolved).await?;
+
+        if encoding == Encoding::Base64 {
+            let total = bytes.len();
+            let start = args["byte_offset"]
+                .as_u64()
+                .map(|n| n as usize)
+                .unwrap_or(0)
+                .min(total);
+            let max_bytes = args["max_bytes"]
+                .as_u64()
+                .map(|n| n as usize)
+                .unwrap_or(READ_FILE_DEFAULT_MAX_BYTES)
+                .max(1);
+            let end = total.min(start.saturating_add(max_bytes));
+            let mut output = format!(
+                "Base64 content of {} (bytes {}-{} of {}):\n{}",
+                resolution.resolved.display(),
+                start,
+                end,
+                total,
+                BASE64.encode(&bytes[start..end])
+            );
+            if end < total {
+                output.push_str(&format!(
+                    "\n\n[Truncated. Use byte_offset={} to continue reading.]",
+                    end
+                ));
+            }
+            return Ok(output);
+        }
+
         let content = match String::from_utf8(bytes) {
             Ok(text) => text,
             Err(_) => {
@@ -74,55 +153,90 @@ impl Tool for ReadFile {
                     - .jar/.zip: Use `run_command` with `unzip -l` to list contents, or `jar tf` for JAR files\n\
                     - .class: Use `run_command` with a Java decompiler like `javap -c` or `cfr`\n\
                     - Images: Use appropriate tools to process\n\
-                    - Executables: Use `file` command to identify, `strings` to extract text",
+                    - Executables: Use `file` command to identify, `strings` to extract text\n\
+                    - To copy or inspect the raw bytes, call read_file again with encoding \"base64\"",
                     resolution.resolved.display(),
                     resolution.resolved.metadata().map(|m| m.len()).unwrap_or(0)
                 ));
             }
         };
 
-        // Handle optional line range
-        let start_line = args["start_line"].as_u64().map(|n| n as usize);
-        let end_line = args["end_line"].as_u64().map(|n| n as usize);
+        // Line range: start_line/end_line, or offset/limit for paging.
+        let start_line = args["start_line"]
+            .as_u64()
+            .or_else(|| args["offset"].as_u64())
+            .map(|n| n as usize);
+        let limit = args["limit"].as_u64().map(|n| n as usize);
+        let end_line = args["end_line"]
+            .as_u64()
+            .map(|n| n as usize)
+            .or_else(|| limit.map(|l| start_line.unwrap_or(1).max(1) + l.saturating_sub(1)));
+        let max_bytes = args["max_bytes"]
+            .as_u64()
+            .map(|n| n as usize)
+            .unwrap_or(READ_FILE_DEFAULT_MAX_BYTES)
+            .max(1);
+
+        let lines: Vec<&str> = content.lines().collect();
+        let total_lines = lines.len();
+        if total_lines == 0 {
+            return Ok(String::new());
+        }
+        let start = start_line.unwrap_or(1).saturating_sub(1).min(total_lines);
+        let end = end_line.unwrap_or(total_lines).min(total_lines);
 
-        if start_line.is_some() || end_line.is_some() {
-            let lines: Vec<&str> = content.lines().collect();
-            let total_lines = lines.len();
-            let start = start_line.unwrap_or(1).saturating_sub(1).min(total_lines);
-            let end = end_line.unwrap_or(total_lines).min(total_lines);
+        // Ensure start <= end
+        let (start, end) = if start > end {
+            (end, start)
+        } else {
+            (start, end)
+        };
 
-            // Ensure start <= end
-            let (start, end) = if start > end {
-                (end, start)
-            } else {
-                (start, end)
-            };
+        if start >= total_lines {
+            return Ok(format!(
+                "File has {} lines, requested start line {} is beyond end of file",
+                total_lines,
+                start + 1
+            ));
+        }
 
-            if start >= total_lines {
-                return Ok(format!(
-                    "File has {} lines, requested start line {} is beyond end of file",
-                    total_lines,
-                    start + 1
-                ));
+        // Return with line numbers for context, stopping at the byte budget.
+        let mut output = String::new();
+        let mut last_shown = start;
+        for (i, line) in lines[start..end].iter().enumerate() {
+            let numbered = format!("{:4}| {}", start + i + 1, line);
+            let needed = numbered.len() + usize::from(!output.is_empty());
+            if output.len() + needed > max_bytes {
+                if output.is_empty() {
+                    // A single oversized line (minified file): show its start.
+                    let mut cut = max_bytes.min(numbered.len());
+                    while !numbered.is_char_boundary(cut) {
+                        cut -= 1;
+                    }
+                    output.push_str(&numbered[..cut]);
+                    output.push_str(" …[line truncated]");
+                    last_shown = start + i + 1;
+                }
+                break;
             }
-
-            let selected: Vec<String> = lines[start..end]
-                .iter()
-                .enumerate()
-                .map(|(i, line)| format!("{:4}| {}", start + i + 1, line))
-                .collect();
-
-            return Ok(selected.join("\n"));
+            if !output.is_empty() {
+                output.push('\n');
+            }
+            output.push_str(&numbered);
+            last_shown = start + i + 1;
         }
 
-        // Return with line numbers for context
-        let numbered: Vec<String> = content
-            .lines()
-            .enumerate()
-            .map(|(i, line)| format!("{:4}| {}", i + 1, line))
-            .collect();
+        if last_shown < total_lines {
+            output.push_str(&format!(
+                "\n\n[Showing lines {}-{} of {} total. Use offset={} to continue reading.]",
+                start + 1,
+                last_shown,
+                total_lines,
+                last_shown + 1
+            ));
+        }
 
-        Ok(numbered.join("\n"))
+        Ok(output)
     }
 }
 
@@ -136,7 +250,7 @@ impl Tool for WriteFile {
     }
 
     fn description(&self) -> &str {
-        "Write content to a file. Use relative paths like 'output/report.md' (recommended) to stay in your workspace. Creates parent directories as needed."
+        "Write content to a file. Use relative paths like 'output/report.md' (recommended) to stay in your workspace. Creates parent directories as needed. Set encoding 'base64' to write binary files."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -150,6 +264,11 @@ impl Tool for WriteFile {
                 "content": {
                     "type": "string",
                     "description": "The content to write to the file"
+                },
+                "encoding": {
+                    "type": "string",
+                    "enum": ["utf8", "base64"],
+                    "description": "Optional: 'base64' if content is base64-encoded binary data, e.g. an image or archive (default: utf8)"
                 }
             },
             "required": ["path", "content"]
@@ -164,6 +283,8 @@ impl Tool for WriteFile {
             .as_str()
             .ok_or_else(|| anyhow::anyhow!("Missing 'content' argument"))?;
 
+        let encoding = Encoding::from_args(&args)?;
+
         let resolution = resolve_path(path, working_dir);
 
         // Create parent directories if needed
@@ -171,6 +292,28 @@ impl Tool for WriteFile {
             tokio::fs::create_dir_all(parent).await?;
         }
 
+        if encoding == Encoding::Base64 {
+            // Tolerate line-wrapped base64.
+            let compact: String = content.split_whitespace().collect();
+            let bytes = BASE64
+                .decode(compact.as_bytes())
+                .map_err(|e| anyhow::anyhow!("Invalid base64 content: {}", e))?;
+            tokio::fs::write(&resolution.resolved, &bytes).await?;
+            let written = tokio::fs::metadata(&resolution.resolved).await?.len();
+            if written != bytes.len() as u64 {
+                return Err(anyhow::anyhow!(
+                    "Write verification failed: expected {} bytes, got {}",
+                    bytes.len(),
+                    written
+                ));
+            }
+            return Ok(format!(
+                "Successfully wrote {} bytes to {}",
+                bytes.len(),
+                resolution.resolved.display()
+            ));
+        }
+
         tokio::fs::write(&resolution.resolved, content).await?;
 
         // Verify write by reading back
@@ -243,6 +386,384 @@ impl Tool for WriteFile {
     }
 }
 
+/// Lines of unchanged context shown around each edit in the returned diff.
+const EDIT_DIFF_CONTEXT: usize = 3;
+
+/// Replace a string in a file without rewriting the whole file.
+pub struct EditFile;
+
+#[async_trait]
+impl Tool for EditFile {
+    fn name(&self) -> &str {
+        "edit_file"
+    }
+
+    fn description(&self) -> &str {
+        "Edit a file by replacing an exact string. Prefer this over write_file for changes to existing files. By default old_string must match exactly once; set 'occurrence' to pick one match or 'replace_all' to replace every match. Returns a diff of the change."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "File path. Use relative paths (e.g., 'src/main.rs') for workspace files."
+                },
+                "old_string": {
+                    "type": "string",
+                    "description": "Exact text to replace, including whitespace and indentation. Include enough surrounding lines to make it unique."
+                },
+                "new_string": {
+                    "type": "string",
+                    "description": "Text to replace it with"
+                },
+                "occurrence": {
+                    "type": "integer",
+                    "description": "Optional: replace only the Nth match (1-indexed) when old_string appears more than once"
+                },
+                "replace_all": {
+                    "type": "boolean",
+                    "description": "Optional: replace every match (default: false)"
+                }
+            },
+            "required": ["path", "old_string", "new_string"]
+        })
+    }
+
+    async fn execute(&self, args: Value, working_dir: &Path) -> anyhow::Result<String> {
+        let path = args["path"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing 'path' argument"))?;
+        let old_string = args["old_string"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing 'old_string' argument"))?;
+        let new_string = args["new_string"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing 'new_string' argument"))?;
+        let occurrence = args["occurrence"].as_u64().map(|n| n as usize);
+        let replace_all = args["replace_all"].as_bool().unwrap_or(false);
+
+        let resolution = resolve_path(path, working_dir);
+
+        if !resolution.resolved.exists() {
+            return Err(anyhow::anyhow!(
+                "File not found: {} (resolved to: {})",
+                path,
+                resolution.resolved.display()
+            ));
+        }
+
+        let content = tokio::fs::read_to_string(&resolution.resolved)
+            .await
+            .map_err(|e| anyhow::anyhow!("Cannot edit {}: {}", path, e))?;
+
+        let offsets = select_matches(&content, old_string, occurrence, replace_all)?;
+        let updated = replace_at(&content, old_string, new_string, &offsets);
+        tokio::fs::write(&resolution.resolved, &updated).await?;
+
+        let path_display = if resolution.was_absolute {
+            path.to_string()
+        } else {
+            resolution.resolved.display().to_string()
+        };
+        Ok(format!(
+            "Successfully edited {} ({} replacement{})\n\n```diff\n{}```",
+            path_display,
+            offsets.len(),
+            if offsets.len() == 1 { "" } else { "s" },
+            render_edit_diff(path, &content, old_string, new_string, &offsets)
+        ))
+    }
+}
+
+/// Byte offsets of the matches of `old_string` that should be replaced.
+fn select_matches(
+    content: &str,
+    old_string: &str,
+    occurrence: Option<usize>,
+    replace_all: bool,
+) -> anyhow::Result<Vec<usize>> {
+    if old_string.is_empty() {
+        return Err(anyhow::anyhow!(
+            "'old_string' must not be empty; use write_file to create a file"
+        ));
+    }
+    let matches: Vec<usize> = content.match_indices(old_string).map(|(i, _)| i).collect();
+    if matches.is_empty() {
+        return Err(anyhow::anyhow!(
+            "'old_string' not found in file. Re-read the file with read_file and copy the text exactly, including whitespace."
+        ));
+    }
+    if replace_all {
+        return Ok(matches);
+    }
+    match occurrence {
+        Some(n) if n == 0 || n > matches.len() => Err(anyhow::anyhow!(
+            "occurrence {} is out of range: 'old_string' matches {} time(s)",
+            n,
+            matches.len()
+        )),
+        Some(n) => Ok(vec![matches[n - 1]]),
+        None if matches.len() > 1 => Err(anyhow::anyhow!(
+            "'old_string' matches {} times. Add surrounding context to make it unique, or set 'occurrence' or 'replace_all'.",
+            matches.len()
+        )),
+        None => Ok(matches),
+    }
+}
+
+fn replace_at(content: &str, old_string: &str, new_string: &str, offsets: &[usize]) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut cursor = 0;
+    for &offset in offsets {
+        out.push_str(&content[cursor..offset]);
+        out.push_str(new_string);
+        cursor = offset + old_string.len();
+    }
+    out.push_str(&content[cursor..]);
+    out
+}
+
+/// Unified diff with one hunk per replacement.
+fn render_edit_diff(
+    path: &str,
+    content: &str,
+    old_string: &str,
+    new_string: &str,
+    offsets: &[usize],
+) -> String {
+    let lines: Vec<&str> = content.split('\n').collect();
+    let line_of = |offset: usize| content[..offset].matches('\n').count();
+    let line_start = |line: usize| -> usize { lines[..line].iter().map(|l| l.len() + 1).sum() };
+
+    // A newline shared by both ends belongs to the last changed line; keeping
+    // it would pull the following, unchanged line into the hunk.
+    let (old_string, new_string) =
+        match (old_string.strip_suffix('\n'), new_string.strip_suffix('\n')) {
+            (Some(old), Some(new)) => (old, new),
+            _ => (old_string, new_string),
+        };
+
+    let mut diff = format!("--- a/{}\n+++ b/{}\n", path, path);
+    // Net line count change from earlier hunks, to number lines in the new file.
+    let mut shift: isize = 0;
+    for &offset in offsets {
+        let first = line_of(offset);
+        let last = line_of(offset + old_string.len());
+        let old_block = lines[first..=last].join("\n");
+        let local = offset - line_start(first);
+        let new_block = format!(
+            "{}{}{}",
+            &old_block[..local],
+            new_string,
+            &old_block[local + old_string.len()..]
+        );
+        let new_lines: Vec<&str> = new_block.split('\n').collect();
+
+        let before = first.saturating_sub(EDIT_DIFF_CONTEXT);
+        let after = (last + 1 + EDIT_DIFF_CONTEXT).min(lines.len());
+        let context = (first - before) + (after - last - 1);
+        let old_len = last + 1 - first;
+        diff.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            before + 1,
+            old_len + context,
+            (before as isize + shift) + 1,
+            new_lines.len() + context
+        ));
+        for line in &lines[before..first] {
+            diff.push_str(&format!(" {}\n", line));
+        }
+        for line in &lines[first..=last] {
+            diff.push_str(&format!("-{}\n", line));
+        }
+        for line in &new_lines {
+            diff.push_str(&format!("+{}\n", line));
+        }
+        for line in &lines[last + 1..after] {
+            diff.push_str(&format!(" {}\n", line));
+        }
+        shift += new_lines.len() as isize - old_len as isize;
+    }
+    diff
+}
+
+/// Resolve the `source`/`destination` arguments shared by move and copy, and
+/// refuse to replace an existing destination unless `overwrite` is set.
+fn resolve_transfer(
+    args: &Value,
+    working_dir: &Path,
+) -> anyhow::Result<(PathResolution, PathResolution)> {
+    let source = args["source"]
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("Missing 'source' argument"))?;
+    let destination = args["destination"]
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("Missing 'destination' argument"))?;
+    let overwrite = args["overwrite"].as_bool().unwrap_or(false);
+
+    let from = resolve_path(source, working_dir);
+    let to = resolve_path(destination, working_dir);
+
+    if !from.resolved.exists() {
+        return Err(anyhow::anyhow!(
+            "File not found: {} (resolved to: {})",
+            source,
+            from.resolved.display()
+        ));
+    }
+    if from.resolved == to.resolved {
+        return Err(anyhow::anyhow!("Source and destination are the same path"));
+    }
+    if to.resolved.exists() {
+        if !overwrite {
+            return Err(anyhow::anyhow!(
+                "Destination already exists: {} (set overwrite to replace it)",
+                to.resolved.display()
+            ));
+        }
+        if to.resolved.is_dir() != from.resolved.is_dir() {
+            return Err(anyhow::anyhow!(
+                "Cannot overwrite {} with a {}",
+                to.resolved.display(),
+                if from.resolved.is_dir() {
+                    "directory"
+                } else {
+                    "file"
+                }
+            ));
+        }
+    }
+    if from.resolved.is_dir() && to.resolved.starts_with(&from.resolved) {
+        return Err(anyhow::anyhow!(
+            "Cannot move or copy a directory into itself"
+        ));
+    }
+    Ok((from, to))
+}
+
+fn transfer_schema(verb: &str) -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "source": {
+                "type": "string",
+                "description": format!("File or directory to {}. Use relative paths for workspace files.", verb)
+            },
+            "destination": {
+                "type": "string",
+                "description": "Target path, including the new file name. Parent directories are created as needed."
+            },
+            "overwrite": {
+                "type": "boolean",
+                "description": "Optional: replace an existing destination (default: false)"
+            }
+        },
+        "required": ["source", "destination"]
+    })
+}
+
+/// Recursively copy a file or directory. Returns the number of files copied.
+async fn copy_recursive(from: &Path, to: &Path) -> anyhow::Result<usize> {
+    if !from.is_dir() {
+        tokio::fs::copy(from, to).await?;
+        return Ok(1);
+    }
+    tokio::fs::create_dir_all(to).await?;
+    let mut copied = 0;
+    let mut entries = tokio::fs::read_dir(from).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        copied += Box::pin(copy_recursive(&entry.path(), &to.join(entry.file_name()))).await?;
+    }
+    Ok(copied)
+}
+
+/// Move or rename a file or directory.
+pub struct MoveFile;
+
+#[async_trait]
+impl Tool for MoveFile {
+    fn name(&self) -> &str {
+        "move_file"
+    }
+
+    fn description(&self) -> &str {
+        "Move or rename a file or directory. Refuses to replace an existing destination unless overwrite is true. Creates parent directories as needed."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        transfer_schema("move")
+    }
+
+    async fn execute(&self, args: Value, working_dir: &Path) -> anyhow::Result<String> {
+        let (from, to) = resolve_transfer(&args, working_dir)?;
+
+        if let Some(parent) = to.resolved.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        if to.resolved.is_dir() {
+            tokio::fs::remove_dir_all(&to.resolved).await?;
+        }
+        if let Err(e) = tokio::fs::rename(&from.resolved, &to.resolved).await {
+            // rename() can't cross filesystems; fall back to copy + delete.
+            if e.raw_os_error() != Some(libc::EXDEV) {
+                return Err(e.into());
+            }
+            copy_recursive(&from.resolved, &to.resolved).await?;
+            if from.resolved.is_dir() {
+                tokio::fs::remove_dir_all(&from.resolved).await?;
+            } else {
+                tokio::fs::remove_file(&from.resolved).await?;
+            }
+        }
+
+        Ok(format!(
+            "Successfully moved {} to {}",
+            from.resolved.display(),
+            to.resolved.display()
+        ))
+    }
+}
+
+/// Copy a file or directory.
+pub struct CopyFile;
+
+#[async_trait]
+impl Tool for CopyFile {
+    fn name(&self) -> &str {
+        "copy_file"
+    }
+
+    fn description(&self) -> &str {
+        "Copy a file or directory (recursively). Refuses to replace an existing destination unless overwrite is true. Creates parent directories as needed."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        transfer_schema("copy")
+    }
+
+    async fn execute(&self, args: Value, working_dir: &Path) -> anyhow::Result<String> {
+        let (from, to) = resolve_transfer(&args, working_dir)?;
+
+        if let Some(parent) = to.resolved.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        if to.resolved.is_dir() {
+            tokio::fs::remove_dir_all(&to.resolved).await?;
+        }
+        let copied = copy_recursive(&from.resolved, &to.resolved).await?;
+
+        Ok(format!(
+            "Successfully copied {} to {} ({} file{})",
+            from.resolved.display(),
+            to.resolved.display(),
+            copied,
+            if copied == 1 { "" } else { "s" }
+        ))
+    }
+}
+
 /// Delete a file.
 pub struct DeleteFile;
 
@@ -292,3 +813,148 @@ impl Tool for DeleteFile {
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_matches_occurrence_control() {
+        let content = "a = 1;\nb = 1;\nc = 1;\n";
+        assert!(select_matches(content, "= 1", None, false).is_err());
+        assert!(select_matches(content, "= 2", None, false).is_err());
+        assert!(select_matches(content, "= 1", Some(4), false).is_err());
+        assert_eq!(
+            select_matches(content, "= 1", Some(2), false).unwrap(),
+            vec![9]
+        );
+        assert_eq!(select_matches(content, "= 1", None, true).unwrap().len(), 3);
+        assert_eq!(
+            select_matches(content, "b = 1", None, false).unwrap(),
+            vec![7]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_file_pages_with_offset_limit_and_max_bytes() {
+        let dir = tempfile::tempdir().unwrap();
+        let content: String = (1..=10).map(|i| format!("line {}\n", i)).collect();
+        std::fs::write(dir.path().join("log.txt"), content).unwrap();
+
+        let page = ReadFile
+            .execute(
+                json!({"path": "log.txt", "offset": 4, "limit": 2}),
+                dir.path(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            page,
+            "   4| line 4\n   5| line 5\n\n[Showing lines 4-5 of 10 total. Use offset=6 to continue reading.]"
+        );
+
+        let capped = ReadFile
+            .execute(json!({"path": "log.txt", "max_bytes": 30}), dir.path())
+            .await
+            .unwrap();
+        assert!(
+            capped.starts_with("   1| line 1\n   2| line 2\n\n"),
+            "{}",
+            capped
+        );
+        assert!(capped.contains("of 10 total. Use offset=3"), "{}", capped);
+
+        let all = ReadFile
+            .execute(json!({"path": "log.txt"}), dir.path())
+            .await
+            .unwrap();
+        assert!(all.ends_with("  10| line 10"), "{}", all);
+    }
+
+    #[tokio::test]
+    async fn test_base64_round_trips_binary_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let bytes: Vec<u8> = vec![0x89, b'P', b'N', b'G', 0x00, 0xff, 0xfe, b'\n'];
+
+        WriteFile
+            .execute(
+                json!({"path": "img.png", "content": BASE64.encode(&bytes), "encoding": "base64"}),
+                dir.path(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(dir.path().join("img.png")).unwrap(), bytes);
+
+        let read = ReadFile
+            .execute(
+                json!({"path": "img.png", "encoding": "base64", "byte_offset": 4, "max_bytes": 2}),
+                dir.path(),
+            )
+            .await
+            .unwrap();
+        let encoded = read.lines().nth(1).unwrap();
+        assert_eq!(BASE64.decode(encoded).unwrap(), vec![0x00, 0xff]);
+        assert!(read.contains("byte_offset=6"), "{}", read);
+    }
+
+    #[tokio::test]
+    async fn test_move_and_copy_refuse_to_overwrite() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+        std::fs::write(dir.path().join("b.txt"), "b").unwrap();
+
+        let args = json!({"source": "a.txt", "destination": "b.txt"});
+        assert!(CopyFile.execute(args.clone(), dir.path()).await.is_err());
+        assert!(MoveFile.execute(args, dir.path()).await.is_err());
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("b.txt")).unwrap(),
+            "b"
+        );
+
+        CopyFile
+            .execute(
+                json!({"source": "a.txt", "destination": "nested/c.txt"}),
+                dir.path(),
+            )
+            .await
+            .unwrap();
+        MoveFile
+            .execute(
+                json!({"source": "a.txt", "destination": "b.txt", "overwrite": true}),
+                dir.path(),
+            )
+            .await
+            .unwrap();
+        assert!(!dir.path().join("a.txt").exists());
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("b.txt")).unwrap(),
+            "a"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("nested/c.txt")).unwrap(),
+            "a"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_edit_file_replaces_and_returns_diff() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("lib.rs");
+        std::fs::write(&file, "fn a() {}\nfn b() {\n    1\n}\nfn c() {}\n").unwrap();
+
+        let result = EditFile
+            .execute(
+                json!({"path": "lib.rs", "old_string": "    1\n", "new_string": "    2\n    + 3\n"}),
+                dir.path(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&file).unwrap(),
+            "fn a() {}\nfn b() {\n    2\n    + 3\n}\nfn c() {}\n"
+        );
+        assert!(result.contains("@@ -1,6 +1,7 @@"), "{}", result);
+        assert!(result.contains("-    1\n+    2\n+    + 3\n"), "{}", result);
+    }
+}
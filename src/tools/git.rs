@@ -0,0 +1,695 @@
+//! Git tools: branches, checkout, push and stash.
+//!
+//! Every tool runs `git -C <repo>` with prompts disabled, so a missing
+//! credential fails fast instead of hanging the agent. `git_push` can take
+//! an HTTPS token from the secrets vault; it is handed to git through a
+//! throwaway `GIT_ASKPASS` helper and the environment, never the command
+//! line, and is redacted from the output.
+
+use std::path::{Path, PathBuf};
+use std::process::Output;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use tokio::process::Command;
+
+use super::web::{redact_secrets, resolve_secret};
+use super::{resolve_path_simple, Tool};
+
+pub(super) const GIT_TIMEOUT_SECS: u64 = 120;
+const PUSH_TIMEOUT_SECS: u64 = 300;
+/// Username sent with a token; GitHub, GitLab and Gitea all accept it.
+const DEFAULT_TOKEN_USERNAME: &str = "x-access-token";
+const ASKPASS_USER_ENV: &str = "SANDBOXED_GIT_USERNAME";
+const ASKPASS_PASS_ENV: &str = "SANDBOXED_GIT_PASSWORD";
+
+/// Resolve the `repo` argument (defaults to the working directory).
+fn repo_dir(args: &Value, working_dir: &Path) -> PathBuf {
+    resolve_path_simple(args["repo"].as_str().unwrap_or("."), working_dir)
+}
+
+/// Reject ref names that git would parse as options or that are malformed.
+fn validate_ref(kind: &str, name: &str) -> anyhow::Result<()> {
+    let invalid = name.is_empty()
+        || name.starts_with('-')
+        || name.contains("..")
+        || name.ends_with('/')
+        || name.ends_with(".lock")
+        || name
+            .chars()
+            .any(|c| c.is_whitespace() || c.is_control() || "~^:?*[\\".contains(c));
+    if invalid {
+        return Err(anyhow::anyhow!("Invalid {} name: '{}'", kind, name));
+    }
+    Ok(())
+}
+
+fn required_ref<'a>(args: &'a Value, key: &str) -> anyhow::Result<&'a str> {
+    let name = args[key]
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("Missing '{}' argument", key))?;
+    validate_ref(key, name)?;
+    Ok(name)
+}
+
+fn optional_ref<'a>(args: &'a Value, key: &str) -> anyhow::Result<Option<&'a str>> {
+    match args[key].as_str().filter(|s| !s.is_empty()) {
+        Some(name) => validate_ref(key, name).map(|_| Some(name)),
+        None => Ok(None),
+    }
+}
+
+/// Run git in `repo`, failing with git's stderr on a non-zero exit.
+pub(super) async fn run_git(
+    repo: &Path,
+    args: &[&str],
+    envs: &[(&str, &str)],
+    timeout_secs: u64,
+) -> anyhow::Result<Output> {
+    let mut cmd = Command::new("git");
+    cmd.arg("-C")
+        .arg(repo)
+        .args(args)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("GIT_PAGER", "cat")
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true);
+    for (key, value) in envs {
+        cmd.env(key, value);
+    }
+    let output = tokio::time::timeout(std::time::Duration::from_secs(timeout_secs), cmd.output())
+        .await
+        .map_err(|_| anyhow::anyhow!("git {} timed out after {}s", args[0], timeout_secs))?
+        .map_err(|e| anyhow::anyhow!("Failed to run git: {}", e))?;
+
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "git {} failed: {}",
+            args[0],
+            combined_output(&output).trim()
+        ));
+    }
+    Ok(output)
+}
+
+fn combined_output(output: &Output) -> String {
+    let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !stderr.trim().is_empty() {
+        if !text.is_empty() && !text.ends_with('\n') {
+            text.push('\n');
+        }
+        text.push_str(&stderr);
+    }
+    text
+}
+
+/// Output of a mutating command, or a fallback when git printed nothing.
+fn summarize(output: &Output, fallback: String) -> String {
+    let text = combined_output(output);
+    if text.trim().is_empty() {
+        fallback
+    } else {
+        text.trim_end().to_string()
+    }
+}
+
+pub(super) async fn current_branch(repo: &Path) -> anyhow::Result<String> {
+    let output = run_git(
+        repo,
+        &["rev-parse", "--abbrev-ref", "HEAD"],
+        &[],
+        GIT_TIMEOUT_SECS,
+    )
+    .await?;
+    let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if branch == "HEAD" {
+        return Err(anyhow::anyhow!("HEAD is detached; pass an explicit branch"));
+    }
+    Ok(branch)
+}
+
+/// Separator for `git branch --format` fields (not valid in ref names).
+const FIELD_SEP: char = '\x1f';
+
+/// Parse `git branch --format` output produced with [`FIELD_SEP`] between
+/// HEAD marker, ref name, short hash, upstream and tracking state.
+fn parse_branch_list(stdout: &str) -> Vec<Value> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split(FIELD_SEP);
+            let head = fields.next()?;
+            let refname = fields.next()?;
+            let commit = fields.next().unwrap_or("");
+            let upstream = fields.next().unwrap_or("");
+            let track = fields.next().unwrap_or("");
+            // `origin/HEAD` is a pointer, not a branch.
+            if refname.ends_with("/HEAD") {
+                return None;
+            }
+            let (name, remote) = match refname.strip_prefix("refs/remotes/") {
+                Some(name) => (name, true),
+                None => (
+                    refname.strip_prefix("refs/heads/").unwrap_or(refname),
+                    false,
+                ),
+            };
+            Some(json!({
+                "name": name,
+                "commit": commit,
+                "current": head == "*",
+                "remote": remote,
+                "upstream": (!upstream.is_empty()).then_some(upstream),
+                "tracking": (!track.is_empty()).then_some(track),
+            }))
+        })
+        .collect()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// git_branch
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// List, create, delete or rename branches.
+pub struct GitBranch;
+
+#[async_trait]
+impl Tool for GitBranch {
+    fn name(&self) -> &str {
+        "git_branch"
+    }
+
+    fn description(&self) -> &str {
+        "List, create, delete or rename git branches. 'list' returns JSON with each branch's commit, upstream and ahead/behind state; 'create' does not switch to the new branch (use git_checkout with create=true for that)."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["list", "create", "delete", "rename"],
+                    "description": "Operation to perform (default: list)"
+                },
+                "name": {
+                    "type": "string",
+                    "description": "Branch to create, delete or rename"
+                },
+                "new_name": {
+                    "type": "string",
+                    "description": "New name for 'rename'"
+                },
+                "start_point": {
+                    "type": "string",
+                    "description": "Commit or branch the new branch starts from (default: HEAD)"
+                },
+                "all": {
+                    "type": "boolean",
+                    "description": "Include remote-tracking branches when listing (default: false)"
+                },
+                "force": {
+                    "type": "boolean",
+                    "description": "Delete even if unmerged, or overwrite an existing branch (default: false)"
+                },
+                "repo": {
+                    "type": "string",
+                    "description": "Repository path (default: working directory)"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: Value, working_dir: &Path) -> anyhow::Result<String> {
+        let repo = repo_dir(&args, working_dir);
+        let force = args["force"].as_bool().unwrap_or(false);
+
+        match args["action"].as_str().unwrap_or("list") {
+            "list" => {
+                let format = format!(
+                    "--format=%(HEAD){sep}%(refname){sep}%(objectname:short){sep}%(upstream:short){sep}%(upstream:track,nobracket)",
+                    sep = FIELD_SEP
+                );
+                let mut git_args = vec!["branch", format.as_str()];
+                if args["all"].as_bool().unwrap_or(false) {
+                    git_args.push("--all");
+                }
+                let output = run_git(&repo, &git_args, &[], GIT_TIMEOUT_SECS).await?;
+                let branches = parse_branch_list(&String::from_utf8_lossy(&output.stdout));
+                let current = branches
+                    .iter()
+                    .find(|b| b["current"] == true)
+                    .map(|b| b["name"].clone())
+                    .unwrap_or(Value::Null);
+                Ok(serde_json::to_string_pretty(&json!({
+                    "current": current,
+                    "branches": branches,
+                }))?)
+            }
+            "create" => {
+                let name = required_ref(&args, "name")?;
+                let mut git_args = vec!["branch"];
+                if force {
+                    git_args.push("--force");
+                }
+                git_args.extend(["--", name]);
+                if let Some(start) = optional_ref(&args, "start_point")? {
+                    git_args.push(start);
+                }
+                let output = run_git(&repo, &git_args, &[], GIT_TIMEOUT_SECS).await?;
+                Ok(summarize(&output, format!("Created branch '{}'", name)))
+            }
+            "delete" => {
+                let name = required_ref(&args, "name")?;
+                let flag = if force { "-D" } else { "-d" };
+                let output =
+                    run_git(&repo, &["branch", flag, "--", name], &[], GIT_TIMEOUT_SECS).await?;
+                Ok(summarize(&output, format!("Deleted branch '{}'", name)))
+            }
+            "rename" => {
+                let name = required_ref(&args, "name")?;
+                let new_name = required_ref(&args, "new_name")?;
+                let flag = if force { "-M" } else { "-m" };
+                let output = run_git(
+                    &repo,
+                    &["branch", flag, name, new_name],
+                    &[],
+                    GIT_TIMEOUT_SECS,
+                )
+                .await?;
+                Ok(summarize(
+                    &output,
+                    format!("Renamed branch '{}' to '{}'", name, new_name),
+                ))
+            }
+            other => Err(anyhow::anyhow!("Unknown action '{}'", other)),
+        }
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// git_checkout
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Switch branches (optionally creating one) or detach at a commit.
+pub struct GitCheckout;
+
+#[async_trait]
+impl Tool for GitCheckout {
+    fn name(&self) -> &str {
+        "git_checkout"
+    }
+
+    fn description(&self) -> &str {
+        "Switch to a branch, tag or commit. Set create=true to create the branch first. Refuses to discard local changes unless force=true; use git_stash to set them aside."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "target": {
+                    "type": "string",
+                    "description": "Branch, tag or commit to check out"
+                },
+                "create": {
+                    "type": "boolean",
+                    "description": "Create 'target' as a new branch (default: false)"
+                },
+                "start_point": {
+                    "type": "string",
+                    "description": "Where a created branch starts (default: HEAD)"
+                },
+                "force": {
+                    "type": "boolean",
+                    "description": "Discard local changes that would be overwritten (default: false)"
+                },
+                "repo": {
+                    "type": "string",
+                    "description": "Repository path (default: working directory)"
+                }
+            },
+            "required": ["target"]
+        })
+    }
+
+    async fn execute(&self, args: Value, working_dir: &Path) -> anyhow::Result<String> {
+        let repo = repo_dir(&args, working_dir);
+        let target = required_ref(&args, "target")?;
+        let create = args["create"].as_bool().unwrap_or(false);
+
+        let mut git_args = vec!["checkout"];
+        if args["force"].as_bool().unwrap_or(false) {
+            git_args.push("--force");
+        }
+        if create {
+            git_args.extend(["-b", target]);
+            if let Some(start) = optional_ref(&args, "start_point")? {
+                git_args.push(start);
+            }
+        } else {
+            git_args.push(target);
+        }
+        // Without a trailing `--`, git could treat the target as a path.
+        git_args.push("--");
+
+        let output = run_git(&repo, &git_args, &[], GIT_TIMEOUT_SECS).await?;
+        Ok(summarize(&output, format!("Checked out '{}'", target)))
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// git_push
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Push a branch, optionally authenticating with a token from the vault.
+pub struct GitPush;
+
+/// Temporary `GIT_ASKPASS` helper that answers prompts from the environment.
+struct AskPass {
+    path: PathBuf,
+}
+
+impl AskPass {
+    fn create() -> anyhow::Result<Self> {
+        let path = std::env::temp_dir().join(format!(
+            "sandboxed-askpass-{}.sh",
+            uuid::Uuid::new_v4().simple()
+        ));
+        let script = format!(
+            "#!/bin/sh\ncase \"$1\" in\n  Username*) printf '%s\\n' \"${user}\" ;;\n  *) printf '%s\\n' \"${pass}\" ;;\nesac\n",
+            user = ASKPASS_USER_ENV,
+            pass = ASKPASS_PASS_ENV
+        );
+        std::fs::write(&path, script)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o700))?;
+        }
+        Ok(Self { path })
+    }
+}
+
+impl Drop for AskPass {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+#[async_trait]
+impl Tool for GitPush {
+    fn name(&self) -> &str {
+        "git_push"
+    }
+
+    fn description(&self) -> &str {
+        "Push a branch to a remote. For HTTPS remotes that need auth, pass 'secret' as a 'registry/key' reference to a token in the secrets vault; the token is never placed on the command line or shown in output. force=true uses --force-with-lease."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "remote": {
+                    "type": "string",
+                    "description": "Remote name (default: origin)"
+                },
+                "branch": {
+                    "type": "string",
+                    "description": "Branch to push (default: current branch)"
+                },
+                "set_upstream": {
+                    "type": "boolean",
+                    "description": "Track the pushed branch (-u) (default: false)"
+                },
+                "force": {
+                    "type": "boolean",
+                    "description": "Overwrite the remote branch if nobody else updated it (--force-with-lease) (default: false)"
+                },
+                "tags": {
+                    "type": "boolean",
+                    "description": "Also push tags (default: false)"
+                },
+                "secret": {
+                    "type": "string",
+                    "description": "Token to authenticate with, as 'registry/key' in the secrets vault (e.g. 'github/token')"
+                },
+                "username": {
+                    "type": "string",
+                    "description": "Username sent with the token (default: x-access-token)"
+                },
+                "repo": {
+                    "type": "string",
+                    "description": "Repository path (default: working directory)"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: Value, working_dir: &Path) -> anyhow::Result<String> {
+        let repo = repo_dir(&args, working_dir);
+        let remote = optional_ref(&args, "remote")?.unwrap_or("origin");
+        let branch = match optional_ref(&args, "branch")? {
+            Some(branch) => branch.to_string(),
+            None => current_branch(&repo).await?,
+        };
+
+        let mut git_args = vec!["push"];
+        if args["set_upstream"].as_bool().unwrap_or(false) {
+            git_args.push("--set-upstream");
+        }
+        if args["force"].as_bool().unwrap_or(false) {
+            git_args.push("--force-with-lease");
+        }
+        if args["tags"].as_bool().unwrap_or(false) {
+            git_args.push("--tags");
+        }
+        git_args.extend(["--", remote, branch.as_str()]);
+
+        let mut secrets = Vec::new();
+        let mut askpass = None;
+        let mut envs: Vec<(&str, String)> = Vec::new();
+        if let Some(reference) = args["secret"].as_str().filter(|s| !s.is_empty()) {
+            let token = resolve_secret(reference).await?;
+            let helper = AskPass::create()?;
+            envs.push(("GIT_ASKPASS", helper.path.display().to_string()));
+            envs.push((
+                ASKPASS_USER_ENV,
+                args["username"]
+                    .as_str()
+                    .unwrap_or(DEFAULT_TOKEN_USERNAME)
+                    .to_string(),
+            ));
+            envs.push((ASKPASS_PASS_ENV, token.clone()));
+            secrets.push(token);
+            askpass = Some(helper);
+        }
+        let env_refs: Vec<(&str, &str)> = envs.iter().map(|(k, v)| (*k, v.as_str())).collect();
+
+        let result = run_git(&repo, &git_args, &env_refs, PUSH_TIMEOUT_SECS).await;
+        drop(askpass);
+        match result {
+            Ok(output) => Ok(redact_secrets(
+                &summarize(&output, format!("Pushed '{}' to '{}'", branch, remote)),
+                &secrets,
+            )),
+            Err(e) => Err(anyhow::anyhow!(
+                "{}",
+                redact_secrets(&e.to_string(), &secrets)
+            )),
+        }
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// git_stash
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Save, restore, list and drop stashed changes.
+pub struct GitStash;
+
+/// Stash reference for an `index` argument (default: the latest stash).
+fn stash_ref(args: &Value) -> String {
+    format!("stash@{{{}}}", args["index"].as_u64().unwrap_or(0))
+}
+
+#[async_trait]
+impl Tool for GitStash {
+    fn name(&self) -> &str {
+        "git_stash"
+    }
+
+    fn description(&self) -> &str {
+        "Set aside uncommitted changes and restore them later. Actions: push (save changes), pop (restore and drop), apply (restore and keep), list, show (diff stat of a stash), drop."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["push", "pop", "apply", "list", "show", "drop"],
+                    "description": "Operation to perform (default: push)"
+                },
+                "message": {
+                    "type": "string",
+                    "description": "Description for 'push'"
+                },
+                "include_untracked": {
+                    "type": "boolean",
+                    "description": "Also stash untracked files on 'push' (default: false)"
+                },
+                "index": {
+                    "type": "integer",
+                    "description": "Stash to pop/apply/show/drop, 0 = most recent (default: 0)"
+                },
+                "repo": {
+                    "type": "string",
+                    "description": "Repository path (default: working directory)"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: Value, working_dir: &Path) -> anyhow::Result<String> {
+        let repo = repo_dir(&args, working_dir);
+        let action = args["action"].as_str().unwrap_or("push");
+
+        match action {
+            "push" => {
+                let mut git_args = vec!["stash", "push"];
+                if args["include_untracked"].as_bool().unwrap_or(false) {
+                    git_args.push("--include-untracked");
+                }
+                if let Some(message) = args["message"].as_str().filter(|m| !m.is_empty()) {
+                    git_args.extend(["--message", message]);
+                }
+                let output = run_git(&repo, &git_args, &[], GIT_TIMEOUT_SECS).await?;
+                Ok(summarize(&output, "Stashed changes".to_string()))
+            }
+            "list" => {
+                let output = run_git(&repo, &["stash", "list"], &[], GIT_TIMEOUT_SECS).await?;
+                let text = String::from_utf8_lossy(&output.stdout);
+                if text.trim().is_empty() {
+                    Ok("No stashes".to_string())
+                } else {
+                    Ok(text.trim_end().to_string())
+                }
+            }
+            "pop" | "apply" | "show" | "drop" => {
+                let stash = stash_ref(&args);
+                let mut git_args = vec!["stash", action];
+                if action == "show" {
+                    git_args.push("--stat");
+                }
+                git_args.push(stash.as_str());
+                let output = run_git(&repo, &git_args, &[], GIT_TIMEOUT_SECS).await?;
+                Ok(summarize(&output, format!("{} {}", action, stash)))
+            }
+            other => Err(anyhow::anyhow!("Unknown action '{}'", other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_ref() {
+        assert!(validate_ref("branch", "feature/login").is_ok());
+        assert!(validate_ref("branch", "v1.2.3").is_ok());
+        assert!(validate_ref("branch", "--upload-pack=evil").is_err());
+        assert!(validate_ref("branch", "a..b").is_err());
+        assert!(validate_ref("branch", "has space").is_err());
+        assert!(validate_ref("branch", "topic.lock").is_err());
+        assert!(validate_ref("branch", "").is_err());
+    }
+
+    #[test]
+    fn test_parse_branch_list() {
+        let sep = FIELD_SEP;
+        let stdout = format!(
+            "*{s}refs/heads/main{s}abc1234{s}origin/main{s}ahead 1\n {s}refs/heads/dev{s}def5678{s}{s}\n {s}refs/remotes/origin/HEAD{s}abc1234{s}{s}\n {s}refs/remotes/origin/main{s}abc1234{s}{s}\n",
+            s = sep
+        );
+        let branches = parse_branch_list(&stdout);
+        assert_eq!(branches.len(), 3);
+        assert_eq!(branches[0]["name"], "main");
+        assert_eq!(branches[0]["current"], true);
+        assert_eq!(branches[0]["upstream"], "origin/main");
+        assert_eq!(branches[0]["tracking"], "ahead 1");
+        assert_eq!(branches[1]["upstream"], Value::Null);
+        assert_eq!(branches[2]["name"], "origin/main");
+        assert_eq!(branches[2]["remote"], true);
+    }
+
+    #[tokio::test]
+    async fn test_branch_checkout_and_stash_roundtrip() {
+        if !super::super::search::which_exists("git") {
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path();
+        let identity = [
+            ("GIT_AUTHOR_NAME", "Test"),
+            ("GIT_AUTHOR_EMAIL", "test@example.com"),
+            ("GIT_COMMITTER_NAME", "Test"),
+            ("GIT_COMMITTER_EMAIL", "test@example.com"),
+        ];
+        run_git(repo, &["init", "-q", "-b", "main"], &[], 10)
+            .await
+            .unwrap();
+        std::fs::write(repo.join("a.txt"), "one\n").unwrap();
+        run_git(repo, &["add", "a.txt"], &[], 10).await.unwrap();
+        run_git(repo, &["commit", "-q", "-m", "init"], &identity, 10)
+            .await
+            .unwrap();
+
+        GitCheckout
+            .execute(json!({"target": "feature", "create": true}), repo)
+            .await
+            .unwrap();
+        let listing: Value = serde_json::from_str(
+            &GitBranch
+                .execute(json!({"action": "list"}), repo)
+                .await
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(listing["current"], "feature");
+        assert_eq!(listing["branches"].as_array().unwrap().len(), 2);
+
+        std::fs::write(repo.join("a.txt"), "two\n").unwrap();
+        GitStash
+            .execute(json!({"action": "push", "message": "wip"}), repo)
+            .await
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(repo.join("a.txt")).unwrap(),
+            "one\n"
+        );
+        let list = GitStash
+            .execute(json!({"action": "list"}), repo)
+            .await
+            .unwrap();
+        assert!(list.contains("wip"));
+        GitStash
+            .execute(json!({"action": "pop"}), repo)
+            .await
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(repo.join("a.txt")).unwrap(),
+            "two\n"
+        );
+
+        let err = GitCheckout
+            .execute(json!({"target": "--orphan"}), repo)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Invalid"));
+    }
+}
@@ -1,34 +1,58 @@
 //! Local filesystem indexing tools.
 //!
 //! These tools exist to make "search the machine" fast without repeatedly walking very large
-//! directory trees. They build and query a simple on-disk index under:
-//! `{working_dir}/.sandboxed_sh/index/`
+//! directory trees. They maintain a SQLite index (path, size, mtime, content hash) under:
+//! `{working_dir}/.sandboxed_sh/index/files.db`
+//!
+//! The index persists across missions in the same workspace. `index_files` refreshes it
+//! incrementally (only new or changed files are re-hashed, vanished files are dropped), and
+//! with `watch: true` an inotify watcher keeps it current while the process runs.
 //!
 //! Note: the agent still has full system access; indexing is an optimization and a convention.
 
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
 use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
 
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection};
 use serde_json::{json, Value};
-use tokio::io::AsyncWriteExt;
+use sha2::{Digest, Sha256};
 use walkdir::WalkDir;
 
 use super::{resolve_path_simple as resolve_path, Tool};
 
+const DEFAULT_MAX_FILES: usize = 200_000;
+/// Files larger than this are indexed without a content hash.
+const MAX_HASH_BYTES: u64 = 8 * 1024 * 1024;
+
+const SCHEMA: &str = r#"
+PRAGMA journal_mode = WAL;
+CREATE TABLE IF NOT EXISTS files (
+    path TEXT PRIMARY KEY,
+    size INTEGER NOT NULL,
+    mtime INTEGER NOT NULL,
+    hash TEXT
+);
+CREATE INDEX IF NOT EXISTS idx_files_mtime ON files(mtime);
+CREATE INDEX IF NOT EXISTS idx_files_hash ON files(hash);
+CREATE TABLE IF NOT EXISTS meta (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+"#;
+
 fn default_index_dir(working_dir: &Path) -> PathBuf {
     working_dir.join(".sandboxed_sh").join("index")
 }
 
 fn default_index_file(working_dir: &Path) -> PathBuf {
-    default_index_dir(working_dir).join("files.txt")
-}
-
-fn default_meta_file(working_dir: &Path) -> PathBuf {
-    default_index_dir(working_dir).join("meta.json")
+    default_index_dir(working_dir).join("files.db")
 }
 
-fn default_ignore_dirs() -> Vec<String> {
+pub(super) fn default_ignore_dirs() -> Vec<String> {
     vec![
         ".git".into(),
         ".sandboxed_sh".into(),
@@ -52,7 +76,229 @@ fn is_ignored_dir(name: &str, ignore_dirs: &[String]) -> bool {
     ignore_dirs.iter().any(|d| d == name)
 }
 
-/// Build/refresh an on-disk index of file paths under a directory.
+/// What to index and how.
+#[derive(Debug, Clone)]
+struct IndexOptions {
+    root: PathBuf,
+    max_depth: Option<usize>,
+    max_files: usize,
+    include_hidden: bool,
+    ignore_dirs: Vec<String>,
+    hash_contents: bool,
+}
+
+impl IndexOptions {
+    /// Whether a directory (by name) is skipped.
+    fn skips_dir(&self, name: &str) -> bool {
+        (!self.include_hidden && name.starts_with('.')) || is_ignored_dir(name, &self.ignore_dirs)
+    }
+
+    /// Whether `path` lies in a skipped directory below the root.
+    fn skips_path(&self, path: &Path) -> bool {
+        let Ok(relative) = path.strip_prefix(&self.root) else {
+            return true;
+        };
+        let mut components: Vec<_> = relative.components().collect();
+        components.pop(); // the entry itself
+        components
+            .iter()
+            .any(|c| self.skips_dir(&c.as_os_str().to_string_lossy()))
+    }
+}
+
+/// Counts from one incremental refresh.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct RefreshStats {
+    total: usize,
+    added: usize,
+    updated: usize,
+    removed: usize,
+    truncated: bool,
+}
+
+fn open_index(path: &Path) -> anyhow::Result<Connection> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let conn = Connection::open(path)?;
+    conn.busy_timeout(std::time::Duration::from_secs(5))?;
+    conn.execute_batch(SCHEMA)?;
+    Ok(conn)
+}
+
+fn mtime_secs(metadata: &std::fs::Metadata) -> i64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Size, mtime (unix seconds) and optional SHA-256 of a regular file.
+fn file_entry(path: &Path, hash_contents: bool) -> Option<(i64, i64, Option<String>)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    if !metadata.is_file() {
+        return None;
+    }
+    let mtime = mtime_secs(&metadata);
+    let hash = if hash_contents && metadata.len() <= MAX_HASH_BYTES {
+        hash_file(path)
+    } else {
+        None
+    };
+    Some((metadata.len() as i64, mtime, hash))
+}
+
+fn hash_file(path: &Path) -> Option<String> {
+    let mut file = std::fs::File::open(path).ok()?;
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf).ok()?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Some(hex::encode(hasher.finalize()))
+}
+
+/// Prefix used to select everything indexed under `root`.
+fn root_prefix(root: &Path) -> String {
+    let root = root.to_string_lossy();
+    if root.ends_with('/') {
+        root.to_string()
+    } else {
+        format!("{}/", root)
+    }
+}
+
+fn upsert_file(
+    conn: &Connection,
+    path: &str,
+    size: i64,
+    mtime: i64,
+    hash: Option<&str>,
+) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO files (path, size, mtime, hash) VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(path) DO UPDATE SET size = excluded.size, mtime = excluded.mtime, hash = excluded.hash",
+        params![path, size, mtime, hash],
+    )?;
+    Ok(())
+}
+
+/// Remove a file, or everything under a directory, from the index.
+fn remove_path(conn: &Connection, path: &Path) -> rusqlite::Result<usize> {
+    let exact = path.to_string_lossy();
+    let prefix = root_prefix(path);
+    conn.execute(
+        "DELETE FROM files WHERE path = ?1 OR substr(path, 1, length(?2)) = ?2",
+        params![exact, prefix],
+    )
+}
+
+/// Bring the index for `opts.root` up to date, re-hashing only files whose
+/// size or mtime changed.
+fn refresh_index(conn: &mut Connection, opts: &IndexOptions) -> anyhow::Result<RefreshStats> {
+    let prefix = root_prefix(&opts.root);
+    let mut existing: HashMap<String, (i64, i64)> = HashMap::new();
+    {
+        let mut stmt = conn.prepare(
+            "SELECT path, size, mtime FROM files WHERE substr(path, 1, length(?1)) = ?1",
+        )?;
+        let rows = stmt.query_map(params![prefix], |row| {
+            Ok((row.get::<_, String>(0)?, (row.get(1)?, row.get(2)?)))
+        })?;
+        for row in rows {
+            let (path, entry) = row?;
+            existing.insert(path, entry);
+        }
+    }
+
+    let mut stats = RefreshStats::default();
+    let mut seen: HashSet<String> = HashSet::new();
+    let tx = conn.transaction()?;
+
+    let walker = WalkDir::new(&opts.root)
+        .follow_links(false)
+        .max_depth(opts.max_depth.unwrap_or(usize::MAX))
+        .into_iter()
+        .filter_entry(|e| {
+            e.depth() == 0
+                || !e.file_type().is_dir()
+                || !opts.skips_dir(&e.file_name().to_string_lossy())
+        });
+
+    for entry in walker.filter_map(|e| e.ok()) {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        if stats.total >= opts.max_files {
+            stats.truncated = true;
+            break;
+        }
+        stats.total += 1;
+
+        let path = entry.path().to_string_lossy().to_string();
+        let metadata = match entry.metadata() {
+            Ok(m) => m,
+            Err(_) => continue,
+        };
+        let mtime = mtime_secs(&metadata);
+        let size = metadata.len() as i64;
+
+        match existing.get(&path) {
+            Some(&(old_size, old_mtime)) if old_size == size && old_mtime == mtime => {}
+            previous => {
+                let hash = if opts.hash_contents && metadata.len() <= MAX_HASH_BYTES {
+                    hash_file(entry.path())
+                } else {
+                    None
+                };
+                upsert_file(&tx, &path, size, mtime, hash.as_deref())?;
+                if previous.is_some() {
+                    stats.updated += 1;
+                } else {
+                    stats.added += 1;
+                }
+            }
+        }
+        seen.insert(path);
+    }
+
+    // A truncated walk didn't see everything, so keep rows it didn't reach.
+    if !stats.truncated {
+        for path in existing.keys().filter(|p| !seen.contains(*p)) {
+            tx.execute("DELETE FROM files WHERE path = ?1", params![path])?;
+            stats.removed += 1;
+        }
+    }
+
+    let meta = json!({
+        "root": opts.root.to_string_lossy(),
+        "updated_at": Utc::now().to_rfc3339(),
+        "file_count": stats.total,
+        "max_files": opts.max_files,
+        "max_depth": opts.max_depth,
+        "include_hidden": opts.include_hidden,
+        "ignore_dirs": opts.ignore_dirs,
+        "hash_contents": opts.hash_contents,
+    });
+    tx.execute(
+        "INSERT INTO meta (key, value) VALUES (?1, ?2)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![
+            format!("root:{}", opts.root.to_string_lossy()),
+            meta.to_string()
+        ],
+    )?;
+    tx.commit()?;
+    Ok(stats)
+}
+
+/// Build/refresh an on-disk index of files under a directory.
 pub struct IndexFiles;
 
 #[async_trait]
@@ -62,7 +308,7 @@ impl Tool for IndexFiles {
     }
 
     fn description(&self) -> &str {
-        "Build or refresh a file-path index for fast machine search. Writes an index file under {working_dir}/.sandboxed_sh/index/ by default. Use this before searching huge directories repeatedly."
+        "Build or refresh a persistent file index (path, size, mtime, content hash) for fast machine search. Stored under {working_dir}/.sandboxed_sh/index/ and refreshed incrementally, so re-running it is cheap. Set watch=true to keep it updated automatically via inotify. Use this before searching huge directories repeatedly."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -75,7 +321,7 @@ impl Tool for IndexFiles {
                 },
                 "output_path": {
                     "type": "string",
-                    "description": "Optional: where to write the index file (one path per line). Defaults to {working_dir}/.sandboxed_sh/index/files.txt"
+                    "description": "Optional: index database to write. Defaults to {working_dir}/.sandboxed_sh/index/files.db"
                 },
                 "max_depth": {
                     "type": "integer",
@@ -93,6 +339,14 @@ impl Tool for IndexFiles {
                 "include_hidden": {
                     "type": "boolean",
                     "description": "Whether to include hidden directories (starting with '.') (default: false; except '.' itself)."
+                },
+                "hash_contents": {
+                    "type": "boolean",
+                    "description": "Whether to store a SHA-256 of each file up to 8 MB for content-hash queries (default: true)."
+                },
+                "watch": {
+                    "type": "boolean",
+                    "description": "Keep the index updated from filesystem events (inotify, Linux only) for as long as this tool server runs (default: false)."
                 }
             },
             "required": []
@@ -102,17 +356,7 @@ impl Tool for IndexFiles {
     async fn execute(&self, args: Value, working_dir: &Path) -> anyhow::Result<String> {
         let path = args["path"].as_str().unwrap_or(".");
         let out_path = args["output_path"].as_str();
-        let max_depth = args["max_depth"].as_u64().map(|n| n as usize);
-        let max_files = args["max_files"].as_u64().unwrap_or(200_000) as usize;
-        let include_hidden = args["include_hidden"].as_bool().unwrap_or(false);
-        let ignore_dirs: Vec<String> = args["ignore_dirs"]
-            .as_array()
-            .map(|a| {
-                a.iter()
-                    .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                    .collect()
-            })
-            .unwrap_or_else(default_ignore_dirs);
+        let watch = args["watch"].as_bool().unwrap_or(false);
 
         let root = resolve_path(path, working_dir);
         if !root.exists() {
@@ -122,79 +366,61 @@ impl Tool for IndexFiles {
             return Err(anyhow::anyhow!("Not a directory: {}", path));
         }
 
+        let opts = IndexOptions {
+            root: root.canonicalize().unwrap_or(root),
+            max_depth: args["max_depth"].as_u64().map(|n| n as usize),
+            max_files: args["max_files"]
+                .as_u64()
+                .map(|n| n as usize)
+                .unwrap_or(DEFAULT_MAX_FILES),
+            include_hidden: args["include_hidden"].as_bool().unwrap_or(false),
+            ignore_dirs: args["ignore_dirs"]
+                .as_array()
+                .map(|a| {
+                    a.iter()
+                        .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                        .collect()
+                })
+                .unwrap_or_else(default_ignore_dirs),
+            hash_contents: args["hash_contents"].as_bool().unwrap_or(true),
+        };
         let index_path = out_path
             .map(|p| resolve_path(p, working_dir))
             .unwrap_or_else(|| default_index_file(working_dir));
 
-        if let Some(parent) = index_path.parent() {
-            tokio::fs::create_dir_all(parent).await?;
-        }
-
-        let mut f = tokio::fs::File::create(&index_path).await?;
-
-        let mut count = 0usize;
-        let walker = WalkDir::new(&root)
-            .follow_links(false)
-            .max_depth(max_depth.unwrap_or(usize::MAX))
-            .into_iter()
-            .filter_entry(|e| {
-                if e.depth() == 0 {
-                    return true;
-                }
-                if e.file_type().is_dir() {
-                    let name = e.file_name().to_string_lossy();
-                    if !include_hidden && name.starts_with('.') {
-                        return false;
-                    }
-                    if is_ignored_dir(&name, &ignore_dirs) {
-                        return false;
-                    }
-                }
-                true
-            });
-
-        for entry in walker {
-            let entry = match entry {
-                Ok(e) => e,
-                Err(_) => continue,
-            };
-            if !entry.file_type().is_file() {
-                continue;
-            }
-            let p = entry.path().to_string_lossy();
-            f.write_all(p.as_bytes()).await?;
-            f.write_all(b"\n").await?;
-            count += 1;
-            if count >= max_files {
-                break;
+        let started = std::time::Instant::now();
+        let (stats, opts, index_path) = tokio::task::spawn_blocking(move || {
+            let mut conn = open_index(&index_path)?;
+            let stats = refresh_index(&mut conn, &opts)?;
+            anyhow::Ok((stats, opts, index_path))
+        })
+        .await??;
+
+        let mut summary = format!(
+            "Indexed {} files under {} into {} in {} ms ({} added, {} updated, {} removed){}",
+            stats.total,
+            opts.root.to_string_lossy(),
+            index_path.to_string_lossy(),
+            started.elapsed().as_millis(),
+            stats.added,
+            stats.updated,
+            stats.removed,
+            if stats.truncated {
+                format!("; stopped at max_files={}", opts.max_files)
+            } else {
+                String::new()
             }
-        }
+        );
 
-        f.flush().await?;
-
-        // Write a small metadata file next to the index (best effort).
-        let meta_path = default_meta_file(working_dir);
-        if let Some(parent) = meta_path.parent() {
-            let _ = tokio::fs::create_dir_all(parent).await;
+        if watch {
+            match watcher::watch(index_path, opts) {
+                Ok(true) => summary.push_str("\nWatching for changes."),
+                Ok(false) => summary.push_str("\nAlready watching this directory."),
+                Err(e) => summary.push_str(&format!("\nCould not start watcher: {}", e)),
+            }
         }
-        let meta = json!({
-            "root": root.to_string_lossy(),
-            "index_path": index_path.to_string_lossy(),
-            "created_at": Utc::now().to_rfc3339(),
-            "file_count": count,
-            "max_files": max_files,
-            "max_depth": max_depth,
-            "include_hidden": include_hidden,
-            "ignore_dirs": ignore_dirs,
-        });
-        let _ = tokio::fs::write(&meta_path, serde_json::to_vec_pretty(&meta)?).await;
 
-        Ok(format!(
-            "Indexed {} files under {} into {}",
-            count,
-            root.to_string_lossy(),
-            index_path.to_string_lossy()
-        ))
+        Ok(summary)
     }
 }
 
@@ -208,7 +434,7 @@ impl Tool for SearchFileIndex {
     }
 
     fn description(&self) -> &str {
-        "Search the file-path index produced by index_files. Much faster than walking the filesystem repeatedly. Supports substring search or simple '*' glob patterns."
+        "Search the persistent file index produced by index_files. Much faster than walking the filesystem repeatedly. Match paths by substring or simple '*' glob, and/or filter by modification time or content hash (e.g. find duplicates or files changed since a point in time)."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -219,9 +445,17 @@ impl Tool for SearchFileIndex {
                     "type": "string",
                     "description": "Substring or simple glob (supports '*') to match against indexed paths"
                 },
+                "modified_since": {
+                    "type": "string",
+                    "description": "Optional: only files modified at or after this RFC 3339 timestamp (e.g. 2026-01-31T12:00:00Z)"
+                },
+                "hash": {
+                    "type": "string",
+                    "description": "Optional: only files whose SHA-256 content hash starts with this hex string"
+                },
                 "index_path": {
                     "type": "string",
-                    "description": "Optional: index file to read. Defaults to {working_dir}/.sandboxed_sh/index/files.txt"
+                    "description": "Optional: index database to read. Defaults to {working_dir}/.sandboxed_sh/index/files.db"
                 },
                 "limit": {
                     "type": "integer",
@@ -232,14 +466,29 @@ impl Tool for SearchFileIndex {
                     "description": "Whether matching is case-sensitive (default: false)"
                 }
             },
-            "required": ["query"]
+            "required": []
         })
     }
 
     async fn execute(&self, args: Value, working_dir: &Path) -> anyhow::Result<String> {
-        let query = args["query"]
+        let query = args["query"].as_str().filter(|q| !q.is_empty());
+        let hash = args["hash"]
             .as_str()
-            .ok_or_else(|| anyhow::anyhow!("Missing 'query' argument"))?;
+            .map(|h| h.trim().to_lowercase())
+            .filter(|h| !h.is_empty());
+        let modified_since = match args["modified_since"].as_str() {
+            Some(ts) => Some(
+                DateTime::parse_from_rfc3339(ts)
+                    .map_err(|e| anyhow::anyhow!("Invalid modified_since '{}': {}", ts, e))?
+                    .timestamp(),
+            ),
+            None => None,
+        };
+        if query.is_none() && hash.is_none() && modified_since.is_none() {
+            return Err(anyhow::anyhow!(
+                "Provide at least one of 'query', 'modified_since' or 'hash'"
+            ));
+        }
         let index_path = args["index_path"]
             .as_str()
             .map(|p| resolve_path(p, working_dir))
@@ -249,57 +498,321 @@ impl Tool for SearchFileIndex {
 
         if !index_path.exists() {
             return Ok(format!(
-                "Index file not found: {}.\nRun index_files first (e.g., index_files {{\"path\":\"/root\"}}).",
+                "Index not found: {}.\nRun index_files first (e.g., index_files {{\"path\":\"/root\"}}).",
                 index_path.to_string_lossy()
             ));
         }
 
-        let content = tokio::fs::read_to_string(&index_path).await?;
-        let is_glob = query.contains('*');
-
-        let q = if case_sensitive {
-            query.to_string()
-        } else {
-            query.to_lowercase()
+        let filter = IndexQuery {
+            query: query.map(|q| {
+                if case_sensitive {
+                    q.to_string()
+                } else {
+                    q.to_lowercase()
+                }
+            }),
+            case_sensitive,
+            modified_since,
+            hash,
+            limit,
         };
+        let db_path = index_path.clone();
+        let (matches, total) =
+            tokio::task::spawn_blocking(move || query_index(&open_index(&db_path)?, &filter))
+                .await??;
+
+        Ok(serde_json::to_string_pretty(&json!({
+            "index": index_path.to_string_lossy(),
+            "total_matches": total,
+            "truncated": total > matches.len(),
+            "files": matches,
+        }))?)
+    }
+}
 
-        let mut matches = Vec::new();
-        for line in content.lines() {
-            let hay = if case_sensitive {
-                line.to_string()
+/// Filters for `search_file_index`.
+#[derive(Debug)]
+struct IndexQuery {
+    /// Lowercased unless `case_sensitive`.
+    query: Option<String>,
+    case_sensitive: bool,
+    modified_since: Option<i64>,
+    hash: Option<String>,
+    limit: usize,
+}
+
+/// Run `filter` against the index, returning up to `limit` matches and the total count.
+fn query_index(conn: &Connection, filter: &IndexQuery) -> anyhow::Result<(Vec<Value>, usize)> {
+    let mut stmt = conn.prepare(
+        "SELECT path, size, mtime, hash FROM files
+         WHERE (?1 IS NULL OR mtime >= ?1)
+           AND (?2 IS NULL OR substr(hash, 1, length(?2)) = ?2)
+         ORDER BY path",
+    )?;
+    let rows = stmt.query_map(params![filter.modified_since, filter.hash], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, i64>(1)?,
+            row.get::<_, i64>(2)?,
+            row.get::<_, Option<String>>(3)?,
+        ))
+    })?;
+
+    let mut matches = Vec::new();
+    let mut total = 0usize;
+    for row in rows {
+        let (path, size, mtime, hash) = row?;
+        if let Some(q) = &filter.query {
+            let hay = if filter.case_sensitive {
+                path.clone()
             } else {
-                line.to_lowercase()
+                path.to_lowercase()
             };
-
-            let matched = if is_glob {
-                glob_match(&q, &hay)
+            let matched = if q.contains('*') {
+                glob_match(q, &hay)
             } else {
-                hay.contains(&q)
+                hay.contains(q.as_str())
             };
+            if !matched {
+                continue;
+            }
+        }
+        total += 1;
+        if matches.len() < filter.limit {
+            matches.push(json!({
+                "path": path,
+                "size": size,
+                "modified": DateTime::<Utc>::from_timestamp(mtime, 0).map(|t| t.to_rfc3339()),
+                "hash": hash,
+            }));
+        }
+    }
+    Ok((matches, total))
+}
+
+/// Keeps an index current from inotify events.
+#[cfg(target_os = "linux")]
+mod watcher {
+    use std::collections::{HashMap, HashSet};
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::{Path, PathBuf};
+    use std::sync::{Mutex, OnceLock};
+
+    use rusqlite::Connection;
+    use walkdir::WalkDir;
+
+    use super::{file_entry, open_index, refresh_index, remove_path, upsert_file, IndexOptions};
+
+    /// inotify watches are per directory; stay well under the usual
+    /// `max_user_watches` default.
+    const MAX_WATCHED_DIRS: usize = 8192;
+    const EVENT_MASK: u32 = libc::IN_CREATE
+        | libc::IN_CLOSE_WRITE
+        | libc::IN_ATTRIB
+        | libc::IN_DELETE
+        | libc::IN_MOVED_FROM
+        | libc::IN_MOVED_TO
+        | libc::IN_DELETE_SELF;
+
+    /// (index, root) pairs with a running watcher.
+    static WATCHING: OnceLock<Mutex<HashSet<(PathBuf, PathBuf)>>> = OnceLock::new();
+
+    fn unregister(key: &(PathBuf, PathBuf)) {
+        if let Some(watching) = WATCHING.get() {
+            watching
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .remove(key);
+        }
+    }
+
+    struct Watcher {
+        fd: i32,
+        dirs: HashMap<i32, PathBuf>,
+        opts: IndexOptions,
+        index_path: PathBuf,
+    }
+
+    impl Drop for Watcher {
+        /// However the watcher stops, close the inotify instance (which drops
+        /// its watches) and let the root be watched again.
+        fn drop(&mut self) {
+            self.dirs.clear();
+            unsafe { libc::close(self.fd) };
+            unregister(&(self.index_path.clone(), self.opts.root.clone()));
+        }
+    }
+
+    impl Watcher {
+        fn add_tree(&mut self, dir: &Path) {
+            let walker = WalkDir::new(dir)
+                .follow_links(false)
+                .into_iter()
+                .filter_entry(|e| {
+                    e.file_type().is_dir()
+                        && (e.depth() == 0
+                            || !self.opts.skips_dir(&e.file_name().to_string_lossy()))
+                });
+            for entry in walker.filter_map(|e| e.ok()) {
+                if self.dirs.len() >= MAX_WATCHED_DIRS {
+                    tracing::warn!(
+                        "File index watcher for {} reached {} directories; deeper changes need index_files",
+                        self.opts.root.display(),
+                        MAX_WATCHED_DIRS
+                    );
+                    return;
+                }
+                let Ok(c_path) = CString::new(entry.path().as_os_str().as_bytes()) else {
+                    continue;
+                };
+                let wd = unsafe { libc::inotify_add_watch(self.fd, c_path.as_ptr(), EVENT_MASK) };
+                if wd >= 0 {
+                    self.dirs.insert(wd, entry.into_path());
+                }
+            }
+        }
+
+        fn handle(&mut self, conn: &mut Connection, wd: i32, mask: u32, name: &[u8]) {
+            if mask & libc::IN_Q_OVERFLOW != 0 {
+                if let Err(e) = refresh_index(conn, &self.opts) {
+                    tracing::warn!("File index refresh after overflow failed: {}", e);
+                }
+                return;
+            }
+            if mask & libc::IN_IGNORED != 0 {
+                self.dirs.remove(&wd);
+                return;
+            }
+            let Some(dir) = self.dirs.get(&wd) else {
+                return;
+            };
+            if name.is_empty() {
+                return;
+            }
+            let path = dir.join(std::ffi::OsStr::from_bytes(name));
+            if self.opts.skips_path(&path) {
+                return;
+            }
+
+            let is_dir = mask & libc::IN_ISDIR != 0;
+            if mask & (libc::IN_DELETE | libc::IN_MOVED_FROM) != 0 {
+                let _ = remove_path(conn, &path);
+            } else if is_dir && mask & (libc::IN_CREATE | libc::IN_MOVED_TO) != 0 {
+                if self
+                    .opts
+                    .skips_dir(&path.file_name().unwrap_or_default().to_string_lossy())
+                {
+                    return;
+                }
+                self.add_tree(&path);
+                let opts = IndexOptions {
+                    root: path,
+                    ..self.opts.clone()
+                };
+                let _ = refresh_index(conn, &opts);
+            } else if !is_dir {
+                match file_entry(&path, self.opts.hash_contents) {
+                    Some((size, mtime, hash)) => {
+                        let _ = upsert_file(
+                            conn,
+                            &path.to_string_lossy(),
+                            size,
+                            mtime,
+                            hash.as_deref(),
+                        );
+                    }
+                    None => {
+                        let _ = remove_path(conn, &path);
+                    }
+                }
+            }
+        }
 
-            if matched {
-                matches.push(line.to_string());
-                if matches.len() >= limit {
+        fn run(mut self) {
+            let mut conn = match open_index(&self.index_path) {
+                Ok(conn) => conn,
+                Err(e) => {
+                    tracing::warn!(
+                        "File index watcher could not open {}: {}",
+                        self.index_path.display(),
+                        e
+                    );
+                    return;
+                }
+            };
+            let mut buf = vec![0u8; 64 * 1024];
+            let header = std::mem::size_of::<libc::inotify_event>();
+            loop {
+                let n = unsafe { libc::read(self.fd, buf.as_mut_ptr().cast(), buf.len()) };
+                if n <= 0 {
+                    if std::io::Error::last_os_error().kind() == std::io::ErrorKind::Interrupted {
+                        continue;
+                    }
+                    break;
+                }
+                let n = n as usize;
+                let mut offset = 0;
+                while offset + header <= n {
+                    // SAFETY: the kernel writes whole events; the header fits in the buffer.
+                    let event: libc::inotify_event =
+                        unsafe { std::ptr::read_unaligned(buf[offset..].as_ptr().cast()) };
+                    let name_start = offset + header;
+                    let name_end = (name_start + event.len as usize).min(n);
+                    let name = &buf[name_start..name_end];
+                    let name = &name[..name.iter().position(|&b| b == 0).unwrap_or(name.len())];
+                    self.handle(&mut conn, event.wd, event.mask, name);
+                    offset = name_start + event.len as usize;
+                }
+                if self.dirs.is_empty() {
                     break;
                 }
             }
         }
+    }
 
-        if matches.is_empty() {
-            Ok(format!(
-                "No matches for '{}' in {}",
-                query,
-                index_path.to_string_lossy()
-            ))
-        } else if matches.len() >= limit {
-            Ok(format!(
-                "{}\n\n... (showing first {} matches)",
-                matches.join("\n"),
-                limit
-            ))
-        } else {
-            Ok(matches.join("\n"))
+    /// Start a background watcher for `opts.root`. Returns `false` if one is already running.
+    pub fn watch(index_path: PathBuf, opts: IndexOptions) -> anyhow::Result<bool> {
+        let key = (index_path.clone(), opts.root.clone());
+        {
+            let mut watching = WATCHING
+                .get_or_init(|| Mutex::new(HashSet::new()))
+                .lock()
+                .map_err(|_| anyhow::anyhow!("watcher registry poisoned"))?;
+            if !watching.insert(key.clone()) {
+                return Ok(false);
+            }
         }
+
+        let fd = unsafe { libc::inotify_init1(libc::IN_CLOEXEC) };
+        if fd < 0 {
+            let err = std::io::Error::last_os_error();
+            unregister(&key);
+            return Err(err.into());
+        }
+        let root = opts.root.clone();
+        let mut watcher = Watcher {
+            fd,
+            dirs: HashMap::new(),
+            opts,
+            index_path,
+        };
+        watcher.add_tree(&root);
+        std::thread::Builder::new()
+            .name("file-index-watcher".to_string())
+            .spawn(move || watcher.run())?;
+        Ok(true)
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod watcher {
+    use std::path::PathBuf;
+
+    use super::IndexOptions;
+
+    pub fn watch(_index_path: PathBuf, _opts: IndexOptions) -> anyhow::Result<bool> {
+        Err(anyhow::anyhow!("file watching requires Linux (inotify)"))
     }
 }
 
@@ -332,3 +845,168 @@ fn glob_match(pattern: &str, text: &str) -> bool {
 
     true
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(root: &Path) -> IndexOptions {
+        IndexOptions {
+            root: root.to_path_buf(),
+            max_depth: None,
+            max_files: DEFAULT_MAX_FILES,
+            include_hidden: false,
+            ignore_dirs: default_ignore_dirs(),
+            hash_contents: true,
+        }
+    }
+
+    #[test]
+    fn test_incremental_refresh() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("tree");
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("node_modules/pkg")).unwrap();
+        std::fs::write(root.join("src/a.rs"), "fn a() {}").unwrap();
+        std::fs::write(root.join("src/b.rs"), "fn b() {}").unwrap();
+        std::fs::write(root.join("node_modules/pkg/index.js"), "").unwrap();
+
+        let mut conn = open_index(&dir.path().join("files.db")).unwrap();
+        let opts = options(&root);
+        let first = refresh_index(&mut conn, &opts).unwrap();
+        assert_eq!((first.total, first.added), (2, 2));
+
+        let second = refresh_index(&mut conn, &opts).unwrap();
+        assert_eq!(
+            second,
+            RefreshStats {
+                total: 2,
+                ..Default::default()
+            }
+        );
+
+        std::fs::write(root.join("src/a.rs"), "fn a() { changed() }").unwrap();
+        std::fs::remove_file(root.join("src/b.rs")).unwrap();
+        let third = refresh_index(&mut conn, &opts).unwrap();
+        assert_eq!((third.updated, third.removed), (1, 1));
+
+        let (matches, total) = query_index(
+            &conn,
+            &IndexQuery {
+                query: None,
+                case_sensitive: false,
+                modified_since: None,
+                hash: Some(hash_file(&root.join("src/a.rs")).unwrap()[..12].to_string()),
+                limit: 10,
+            },
+        )
+        .unwrap();
+        assert_eq!(total, 1);
+        assert!(matches[0]["path"].as_str().unwrap().ends_with("src/a.rs"));
+    }
+
+    #[test]
+    fn test_query_filters() {
+        let dir = tempfile::tempdir().unwrap();
+        let conn = open_index(&dir.path().join("files.db")).unwrap();
+        upsert_file(&conn, "/w/src/Main.rs", 10, 1_000, Some("aa11")).unwrap();
+        upsert_file(&conn, "/w/src/lib.rs", 10, 2_000, Some("bb22")).unwrap();
+        upsert_file(&conn, "/w/README.md", 10, 3_000, None).unwrap();
+
+        let run = |query: Option<&str>, modified_since: Option<i64>| {
+            query_index(
+                &conn,
+                &IndexQuery {
+                    query: query.map(str::to_string),
+                    case_sensitive: false,
+                    modified_since,
+                    hash: None,
+                    limit: 10,
+                },
+            )
+            .unwrap()
+            .1
+        };
+        assert_eq!(run(Some("*.rs"), None), 2);
+        assert_eq!(run(Some("main"), None), 1);
+        assert_eq!(run(None, Some(2_000)), 2);
+        assert_eq!(run(Some("*.rs"), Some(2_000)), 1);
+
+        assert_eq!(remove_path(&conn, Path::new("/w/src")).unwrap(), 2);
+        assert_eq!(run(Some("*"), None), 1);
+    }
+
+    /// Poll until `check` passes; watcher events arrive asynchronously.
+    #[cfg(target_os = "linux")]
+    fn eventually(mut check: impl FnMut() -> bool) -> bool {
+        for _ in 0..100 {
+            if check() {
+                return true;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(50));
+        }
+        false
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_watcher_follows_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("tree");
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("keep.rs"), "fn keep() {}").unwrap();
+        std::fs::write(root.join("old.rs"), "fn old() {}").unwrap();
+
+        let index_path = dir.path().join("files.db");
+        let mut conn = open_index(&index_path).unwrap();
+        let opts = options(&root);
+        refresh_index(&mut conn, &opts).unwrap();
+        assert!(watcher::watch(index_path.clone(), opts.clone()).unwrap());
+        assert!(!watcher::watch(index_path.clone(), opts).unwrap());
+
+        std::fs::write(root.join("new.rs"), "fn new() {}").unwrap();
+        std::fs::write(root.join("keep.rs"), "fn keep() { changed() }").unwrap();
+        std::fs::remove_file(root.join("old.rs")).unwrap();
+        std::fs::create_dir(root.join("sub")).unwrap();
+        std::fs::write(root.join("sub/inner.rs"), "fn inner() {}").unwrap();
+
+        let keep_hash = hash_file(&root.join("keep.rs")).unwrap();
+        let indexed = || -> Vec<(String, Option<String>)> {
+            let mut stmt = conn
+                .prepare("SELECT path, hash FROM files ORDER BY path")
+                .unwrap();
+            stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+                .unwrap()
+                .map(|row| row.unwrap())
+                .collect()
+        };
+        let path = |name: &str| root.join(name).to_string_lossy().into_owned();
+        let expected_paths = vec![path("keep.rs"), path("new.rs"), path("sub/inner.rs")];
+        assert!(
+            eventually(|| {
+                let files = indexed();
+                files.iter().map(|(p, _)| p.clone()).collect::<Vec<_>>() == expected_paths
+                    && files[0].1.as_deref() == Some(keep_hash.as_str())
+            }),
+            "index did not follow the changes: {:?}",
+            indexed()
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_watcher_unregisters_when_it_stops() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("tree");
+        std::fs::create_dir_all(&root).unwrap();
+        // The index can't be opened: its parent directory is a file
+        std::fs::write(dir.path().join("not-a-dir"), "").unwrap();
+        let index_path = dir.path().join("not-a-dir/files.db");
+
+        assert!(watcher::watch(index_path.clone(), options(&root)).unwrap());
+        assert!(
+            eventually(|| watcher::watch(index_path.clone(), options(&root)).unwrap()),
+            "a failed watcher kept its root registered"
+        );
+    }
+}
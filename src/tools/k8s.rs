@@ -0,0 +1,423 @@
+//! Kubernetes tools: read-only `kubectl get/logs/describe` plus `k8s_apply`.
+//!
+//! The kubeconfig comes from the secrets vault (`kubernetes/kubeconfig`
+//! unless the call names another `registry/key`) and is written to a
+//! private temporary file for the duration of the call. Without a stored
+//! kubeconfig, kubectl's own defaults (`KUBECONFIG`, `~/.kube/config`) apply.
+//!
+//! `k8s_apply` changes cluster state, so the tool policy asks for approval
+//! before it runs unless a rule explicitly allows it
+//! (see [`super::policy::requires_approval`]).
+
+use std::path::Path;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use tokio::process::Command;
+
+use super::web::resolve_secret;
+use super::{resolve_path_simple, PrivateTempFile, Tool};
+
+const DEFAULT_KUBECONFIG_SECRET: &str = "kubernetes/kubeconfig";
+const KUBECTL_TIMEOUT_SECS: u64 = 60;
+const APPLY_TIMEOUT_SECS: u64 = 300;
+const DEFAULT_LOG_LINES: u64 = 200;
+const MAX_LOG_LINES: u64 = 5000;
+
+/// Reject values kubectl would parse as flags.
+fn validate_arg(kind: &str, value: &str) -> anyhow::Result<()> {
+    let valid = !value.is_empty()
+        && !value.starts_with('-')
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:".contains(c));
+    if !valid {
+        return Err(anyhow::anyhow!("Invalid {}: '{}'", kind, value));
+    }
+    Ok(())
+}
+
+fn required_arg<'a>(args: &'a Value, key: &str) -> anyhow::Result<&'a str> {
+    let value = args[key]
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("Missing '{}' argument", key))?;
+    validate_arg(key, value)?;
+    Ok(value)
+}
+
+fn optional_arg<'a>(args: &'a Value, key: &str) -> anyhow::Result<Option<&'a str>> {
+    match args[key].as_str().filter(|s| !s.is_empty()) {
+        Some(value) => validate_arg(key, value).map(|_| Some(value)),
+        None => Ok(None),
+    }
+}
+
+/// Flags shared by every call: `--context` and `--namespace`
+/// (or `--all-namespaces` when `allow_all` and requested).
+fn scope_flags(args: &Value, allow_all: bool) -> anyhow::Result<Vec<String>> {
+    let mut flags = Vec::new();
+    if let Some(context) = optional_arg(args, "context")? {
+        flags.push(format!("--context={}", context));
+    }
+    if allow_all && args["all_namespaces"].as_bool().unwrap_or(false) {
+        flags.push("--all-namespaces".to_string());
+    } else if let Some(namespace) = optional_arg(args, "namespace")? {
+        flags.push(format!("--namespace={}", namespace));
+    }
+    Ok(flags)
+}
+
+/// Run kubectl in `working_dir` with the kubeconfig from the vault (if
+/// stored) and return stdout, failing with stderr on a non-zero exit.
+async fn kubectl(
+    args: &Value,
+    kubectl_args: &[String],
+    working_dir: &Path,
+    timeout_secs: u64,
+) -> anyhow::Result<String> {
+    let explicit = args["kubeconfig_secret"].as_str().filter(|s| !s.is_empty());
+    let kubeconfig = match resolve_secret(explicit.unwrap_or(DEFAULT_KUBECONFIG_SECRET)).await {
+        Ok(contents) => Some(PrivateTempFile::create("kubeconfig", &contents)?),
+        // Only a secret the caller named is mandatory.
+        Err(e) if explicit.is_some() => return Err(e),
+        Err(_) => None,
+    };
+
+    let mut cmd = Command::new("kubectl");
+    cmd.args(kubectl_args)
+        .arg("--request-timeout=30s")
+        .current_dir(working_dir)
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true);
+    if let Some(file) = &kubeconfig {
+        cmd.env("KUBECONFIG", &file.path);
+    }
+    let output = tokio::time::timeout(std::time::Duration::from_secs(timeout_secs), cmd.output())
+        .await
+        .map_err(|_| {
+            anyhow::anyhow!(
+                "kubectl {} timed out after {}s",
+                kubectl_args[0],
+                timeout_secs
+            )
+        })?
+        .map_err(|e| anyhow::anyhow!("Failed to run kubectl (is it installed?): {}", e))?;
+    drop(kubeconfig);
+
+    let stdout = String::from_utf8_lossy(&output.stdout)
+        .trim_end()
+        .to_string();
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "kubectl {} failed: {}",
+            kubectl_args[0],
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(if stdout.is_empty() {
+        "No output".to_string()
+    } else {
+        stdout
+    })
+}
+
+/// Schema properties shared by every Kubernetes tool.
+fn schema(properties: Value, required: &[&str]) -> Value {
+    let mut all = serde_json::Map::new();
+    all.insert(
+        "namespace".to_string(),
+        json!({ "type": "string", "description": "Namespace (default: the kubeconfig's)" }),
+    );
+    all.insert(
+        "context".to_string(),
+        json!({ "type": "string", "description": "kubeconfig context (default: current context)" }),
+    );
+    all.insert(
+        "kubeconfig_secret".to_string(),
+        json!({
+            "type": "string",
+            "description": "Kubeconfig as 'registry/key' in the secrets vault (default: kubernetes/kubeconfig)"
+        }),
+    );
+    if let Value::Object(extra) = properties {
+        all.extend(extra);
+    }
+    json!({ "type": "object", "properties": all, "required": required })
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// k8s_get
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// List or fetch resources.
+pub struct K8sGet;
+
+#[async_trait]
+impl Tool for K8sGet {
+    fn name(&self) -> &str {
+        "k8s_get"
+    }
+
+    fn description(&self) -> &str {
+        "List or fetch Kubernetes resources (kubectl get). Read-only. Use output=yaml or json for the full object."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        schema(
+            json!({
+                "resource": { "type": "string", "description": "Resource type, e.g. 'pods', 'deployments', 'events'" },
+                "name": { "type": "string", "description": "Resource name (default: all)" },
+                "selector": { "type": "string", "description": "Label selector, e.g. 'app=web'" },
+                "all_namespaces": { "type": "boolean", "description": "Search every namespace (default: false)" },
+                "output": {
+                    "type": "string",
+                    "enum": ["wide", "yaml", "json", "name"],
+                    "description": "Output format (default: wide)"
+                }
+            }),
+            &["resource"],
+        )
+    }
+
+    async fn execute(&self, args: Value, working_dir: &Path) -> anyhow::Result<String> {
+        let mut kubectl_args = vec![
+            "get".to_string(),
+            required_arg(&args, "resource")?.to_string(),
+        ];
+        if let Some(name) = optional_arg(&args, "name")? {
+            kubectl_args.push(name.to_string());
+        }
+        kubectl_args.extend(scope_flags(&args, true)?);
+        if let Some(selector) = args["selector"].as_str().filter(|s| !s.is_empty()) {
+            kubectl_args.push(format!("--selector={}", selector));
+        }
+        let output = match args["output"].as_str().unwrap_or("wide") {
+            format @ ("wide" | "yaml" | "json" | "name") => format,
+            other => return Err(anyhow::anyhow!("Unknown output format '{}'", other)),
+        };
+        kubectl_args.push(format!("--output={}", output));
+
+        kubectl(&args, &kubectl_args, working_dir, KUBECTL_TIMEOUT_SECS).await
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// k8s_logs
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Read container logs.
+pub struct K8sLogs;
+
+#[async_trait]
+impl Tool for K8sLogs {
+    fn name(&self) -> &str {
+        "k8s_logs"
+    }
+
+    fn description(&self) -> &str {
+        "Read logs from a pod (or 'deployment/name', 'job/name', ...). For crashlooping containers set previous=true to see the output of the last crashed run."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        schema(
+            json!({
+                "pod": { "type": "string", "description": "Pod name or type/name" },
+                "container": { "type": "string", "description": "Container (default: the pod's only/default container)" },
+                "previous": { "type": "boolean", "description": "Logs of the previous, terminated container (default: false)" },
+                "tail": { "type": "integer", "description": "Lines from the end (default: 200, max: 5000)" },
+                "since": { "type": "string", "description": "Only logs newer than a duration like '10m' or '1h'" }
+            }),
+            &["pod"],
+        )
+    }
+
+    async fn execute(&self, args: Value, working_dir: &Path) -> anyhow::Result<String> {
+        let lines = args["tail"]
+            .as_u64()
+            .unwrap_or(DEFAULT_LOG_LINES)
+            .min(MAX_LOG_LINES);
+        let mut kubectl_args = vec![
+            "logs".to_string(),
+            required_arg(&args, "pod")?.to_string(),
+            format!("--tail={}", lines),
+            "--timestamps".to_string(),
+        ];
+        kubectl_args.extend(scope_flags(&args, false)?);
+        if let Some(container) = optional_arg(&args, "container")? {
+            kubectl_args.push(format!("--container={}", container));
+        }
+        if args["previous"].as_bool().unwrap_or(false) {
+            kubectl_args.push("--previous".to_string());
+        }
+        if let Some(since) = optional_arg(&args, "since")? {
+            kubectl_args.push(format!("--since={}", since));
+        }
+
+        kubectl(&args, &kubectl_args, working_dir, KUBECTL_TIMEOUT_SECS).await
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// k8s_describe
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Describe a resource, including its recent events.
+pub struct K8sDescribe;
+
+#[async_trait]
+impl Tool for K8sDescribe {
+    fn name(&self) -> &str {
+        "k8s_describe"
+    }
+
+    fn description(&self) -> &str {
+        "Describe Kubernetes resources (kubectl describe): status, conditions, container states, restart counts and recent events. Read-only."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        schema(
+            json!({
+                "resource": { "type": "string", "description": "Resource type, e.g. 'pod', 'deployment', 'node'" },
+                "name": { "type": "string", "description": "Resource name (default: all of that type)" },
+                "selector": { "type": "string", "description": "Label selector, e.g. 'app=web'" }
+            }),
+            &["resource"],
+        )
+    }
+
+    async fn execute(&self, args: Value, working_dir: &Path) -> anyhow::Result<String> {
+        let mut kubectl_args = vec![
+            "describe".to_string(),
+            required_arg(&args, "resource")?.to_string(),
+        ];
+        if let Some(name) = optional_arg(&args, "name")? {
+            kubectl_args.push(name.to_string());
+        }
+        kubectl_args.extend(scope_flags(&args, false)?);
+        if let Some(selector) = args["selector"].as_str().filter(|s| !s.is_empty()) {
+            kubectl_args.push(format!("--selector={}", selector));
+        }
+
+        kubectl(&args, &kubectl_args, working_dir, KUBECTL_TIMEOUT_SECS).await
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// k8s_apply
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Apply manifests to the cluster (approval-gated by the tool policy).
+pub struct K8sApply;
+
+#[async_trait]
+impl Tool for K8sApply {
+    fn name(&self) -> &str {
+        "k8s_apply"
+    }
+
+    fn description(&self) -> &str {
+        "Apply Kubernetes manifests (kubectl apply) from inline YAML or a file in the workspace. Changes the cluster, so the user is asked to approve it unless the tool policy allows it. Use dry_run='server' to validate first."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        schema(
+            json!({
+                "manifest": { "type": "string", "description": "Inline YAML/JSON manifest(s)" },
+                "path": { "type": "string", "description": "Manifest file or directory (alternative to 'manifest')" },
+                "dry_run": {
+                    "type": "string",
+                    "enum": ["none", "server", "client"],
+                    "description": "Validate without persisting (default: none)"
+                },
+                "server_side": { "type": "boolean", "description": "Use server-side apply (default: false)" }
+            }),
+            &[],
+        )
+    }
+
+    async fn execute(&self, args: Value, working_dir: &Path) -> anyhow::Result<String> {
+        let manifest = args["manifest"].as_str().filter(|m| !m.trim().is_empty());
+        let path = args["path"].as_str().filter(|p| !p.is_empty());
+
+        if manifest.is_some() == path.is_some() {
+            return Err(anyhow::anyhow!("Pass exactly one of 'manifest' or 'path'"));
+        }
+        // Kept alive until kubectl has read it.
+        let inline = manifest
+            .map(|manifest| PrivateTempFile::create("manifest.yaml", manifest))
+            .transpose()?;
+        let filename = match (&inline, path) {
+            (Some(file), _) => file.path.clone(),
+            (None, path) => {
+                let resolved = resolve_path_simple(path.unwrap_or_default(), working_dir);
+                if !resolved.exists() {
+                    return Err(anyhow::anyhow!(
+                        "Manifest not found: {}",
+                        resolved.display()
+                    ));
+                }
+                resolved
+            }
+        };
+
+        let mut kubectl_args = vec![
+            "apply".to_string(),
+            format!("--filename={}", filename.display()),
+        ];
+        kubectl_args.extend(scope_flags(&args, false)?);
+        match args["dry_run"].as_str().unwrap_or("none") {
+            "none" => {}
+            mode @ ("server" | "client") => kubectl_args.push(format!("--dry-run={}", mode)),
+            other => return Err(anyhow::anyhow!("Unknown dry_run mode '{}'", other)),
+        }
+        if args["server_side"].as_bool().unwrap_or(false) {
+            kubectl_args.push("--server-side".to_string());
+        }
+
+        let result = kubectl(&args, &kubectl_args, working_dir, APPLY_TIMEOUT_SECS).await;
+        drop(inline);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_arg() {
+        assert!(validate_arg("resource", "deployments.apps").is_ok());
+        assert!(validate_arg("pod", "deployment/web-7c9f").is_ok());
+        assert!(validate_arg("pod", "--kubeconfig=/etc/x").is_err());
+        assert!(validate_arg("name", "a b").is_err());
+    }
+
+    #[test]
+    fn test_scope_flags() {
+        let args = json!({"namespace": "prod", "context": "eu", "all_namespaces": true});
+        assert_eq!(
+            scope_flags(&args, true).unwrap(),
+            vec!["--context=eu", "--all-namespaces"]
+        );
+        assert_eq!(
+            scope_flags(&args, false).unwrap(),
+            vec!["--context=eu", "--namespace=prod"]
+        );
+        assert!(scope_flags(&json!({"namespace": "-x"}), false).is_err());
+    }
+
+    #[test]
+    fn test_private_temp_file_is_removed() {
+        let file = PrivateTempFile::create("test", "apiVersion: v1").unwrap();
+        let path = file.path.clone();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "apiVersion: v1");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        drop(file);
+        assert!(!path.exists());
+    }
+}
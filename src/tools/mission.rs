@@ -9,6 +9,7 @@ use tokio::sync::{mpsc, RwLock};
 use uuid::Uuid;
 
 use super::Tool;
+use crate::api::mission_completion::{self, CompletionReport};
 
 /// Command sent by the mission tool to the control session.
 #[derive(Debug, Clone)]
@@ -16,6 +17,7 @@ pub enum MissionControlCommand {
     SetStatus {
         status: MissionStatusValue,
         summary: Option<String>,
+        report: CompletionReport,
     },
 }
 
@@ -77,6 +79,18 @@ struct CompleteMissionArgs {
     blocker_type: Option<String>,
     /// List of approaches attempted before giving up
     attempted: Option<Vec<String>>,
+    /// Files delivered, relative to the working directory
+    #[serde(default)]
+    deliverables: Vec<DeliverableArg>,
+    /// Suggested next steps
+    #[serde(default)]
+    follow_ups: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeliverableArg {
+    path: String,
+    description: Option<String>,
 }
 
 #[async_trait]
@@ -92,6 +106,8 @@ impl Tool for CompleteMission {
 - 'blocked': Cannot proceed due to blockers (wrong project type, access denied, etc.)
 - 'not_feasible': Task cannot be done as specified (wrong assumptions in request)
 
+List the files you delivered in 'deliverables' and any suggested next steps in 'follow_ups'.
+
 IMPORTANT: Use 'blocked' or 'not_feasible' instead of producing fake/placeholder content!"#
     }
 
@@ -117,6 +133,23 @@ IMPORTANT: Use 'blocked' or 'not_feasible' instead of producing fake/placeholder
                     "type": "array",
                     "items": { "type": "string" },
                     "description": "For blocked/not_feasible: list of approaches you tried before giving up"
+                },
+                "deliverables": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "path": { "type": "string", "description": "File path, relative to the working directory (e.g. output/report.md)" },
+                            "description": { "type": "string" }
+                        },
+                        "required": ["path"]
+                    },
+                    "description": "Files you delivered. Each must exist; its size and hash are recorded."
+                },
+                "follow_ups": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Suggested next steps for the user"
                 }
             },
             "required": ["status"]
@@ -164,8 +197,29 @@ IMPORTANT: Use 'blocked' or 'not_feasible' instead of producing fake/placeholder
             ));
         }
 
+        // Every listed deliverable must exist
+        let mut deliverables = Vec::with_capacity(args.deliverables.len());
+        let mut missing = Vec::new();
+        for deliverable in args.deliverables {
+            match mission_completion::describe_file(
+                working_dir,
+                &deliverable.path,
+                deliverable.description,
+            ) {
+                Ok(entry) => deliverables.push(entry),
+                Err(e) => missing.push(e),
+            }
+        }
+        if !missing.is_empty() {
+            return Ok(format!(
+                "⚠️ Some listed deliverables can't be read:\n- {}\n\n\
+                Create them (or fix their paths) and call complete_mission again.",
+                missing.join("\n- ")
+            ));
+        }
+
         // Validate completion: check if output folder has any files
-        if status == MissionStatusValue::Completed {
+        if status == MissionStatusValue::Completed && deliverables.is_empty() {
             let output_dir = working_dir.join("output");
             let output_empty = if output_dir.exists() {
                 std::fs::read_dir(&output_dir)
@@ -220,15 +274,27 @@ IMPORTANT: Use 'blocked' or 'not_feasible' instead of producing fake/placeholder
             );
         }
 
+        let report = CompletionReport {
+            status: status.to_string(),
+            summary: enhanced_summary.clone(),
+            deliverables,
+            follow_ups: args.follow_ups,
+            reported_at: chrono::Utc::now().to_rfc3339(),
+        };
+
         // Send the command
         control
             .cmd_tx
             .send(MissionControlCommand::SetStatus {
                 status,
                 summary: enhanced_summary.clone(),
+                report,
             })
             .await
             .map_err(|_| anyhow::anyhow!("Failed to send mission control command"))?;
+        if let Some(mission_id) = mission_id {
+            mission_completion::mark_reported(mission_id);
+        }
 
         let summary_msg = enhanced_summary
             .map(|s| format!(" Summary: {}", s))
@@ -12,22 +12,62 @@
 //! This encourages agents to stay within their assigned workspace while preserving
 //! flexibility for tasks that require broader access.
 
+mod archive;
+mod artifact;
+pub mod audit;
+pub mod browser;
 mod composite;
+mod database;
 pub mod desktop;
 mod directory;
+mod docker;
+mod embeddings;
 mod file_ops;
+mod git;
+mod github;
 mod index;
+mod k8s;
 pub mod mission;
+mod notify;
+pub mod patch;
+mod pdf;
+pub mod policy;
+mod process;
+pub mod result_limits;
+mod scheduler;
 mod search;
+mod semantic;
+mod sqlite;
+mod ssh;
 mod terminal;
 mod ui;
+mod vision;
 mod web;
 
+pub use archive::{create_tarball, extract_tarball, ArchiveCreate, ArchiveExtract};
+pub use artifact::{parse_artifact_args, RegisterArtifact};
+pub use database::DbQuery;
 pub use directory::{ListDirectory, SearchFiles};
-pub use file_ops::{DeleteFile, ReadFile, WriteFile};
+pub use docker::{DockerBuild, DockerLogs, DockerRun, DockerStop};
+pub use file_ops::{CopyFile, DeleteFile, EditFile, MoveFile, ReadFile, WriteFile};
+pub use git::{GitBranch, GitCheckout, GitPush, GitStash};
+pub use github::{GitHubComment, GitHubCreatePr, GitHubGetIssue, GitHubListChecks};
+pub use k8s::{K8sApply, K8sDescribe, K8sGet, K8sLogs};
+pub use notify::{send_alert, Notify};
+pub use patch::ApplyPatch;
+pub use pdf::PdfExtract;
+pub use policy::{tool_allowed, PolicyAction, PolicyApprover, PolicyRule, PolicyScope, ToolPolicy};
+pub use process::{ProcessLogs, ProcessStart, ProcessStatus, ProcessStop};
+pub use result_limits::{ResultStrategy, ToolResultLimit, ToolResultLimits};
+pub use scheduler::ScheduleTask;
 pub use search::GrepSearch;
+pub use semantic::SemanticSearch;
+pub use sqlite::SqliteQuery;
+pub use ssh::SshExec;
 pub use terminal::RunCommand;
-pub use web::FetchUrl;
+pub use ui::{is_frontend_tool, parse_progress_args, unattended_answer, AskUser, UiProgress};
+pub use vision::{complete_text, AnalyzeImage};
+pub use web::{FetchUrl, HttpRequest, WebSearch};
 
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -124,6 +164,40 @@ pub fn safe_truncate_index(s: &str, max: usize) -> usize {
     idx
 }
 
+/// A file under the temp dir that only the current user can read, removed
+/// on drop.
+pub(crate) struct PrivateTempFile {
+    pub(crate) path: PathBuf,
+}
+
+impl PrivateTempFile {
+    pub(crate) fn create(prefix: &str, contents: &str) -> anyhow::Result<Self> {
+        use std::io::Write;
+
+        let path = std::env::temp_dir().join(format!(
+            "sandboxed-{}-{}",
+            prefix,
+            uuid::Uuid::new_v4().simple()
+        ));
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&path)?;
+        file.write_all(contents.as_bytes())?;
+        Ok(Self { path })
+    }
+}
+
+impl Drop for PrivateTempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
 // ============================================================================
 // Tool Trait and Registry
 // ============================================================================
@@ -131,6 +205,23 @@ pub fn safe_truncate_index(s: &str, max: usize) -> usize {
 use async_trait::async_trait;
 use serde_json::Value;
 
+use crate::secrets::SecretsStore;
+
+tokio::task_local! {
+    /// Secrets vault of the registry running the current tool call. Tools only
+    /// receive their arguments and working directory, so secret lookups read it
+    /// from here (see `web::resolve_secret`).
+    static CALL_SECRETS: Option<Arc<SecretsStore>>;
+}
+
+/// The secrets vault available to the tool call in progress, if any.
+pub(crate) fn call_secrets() -> Option<Arc<SecretsStore>> {
+    CALL_SECRETS
+        .try_with(|secrets| secrets.clone())
+        .ok()
+        .flatten()
+}
+
 /// Information about a tool for display purposes.
 #[derive(Debug, Clone)]
 pub struct ToolInfo {
@@ -160,6 +251,17 @@ pub trait Tool: Send + Sync {
 /// Registry of available tools.
 pub struct ToolRegistry {
     tools: HashMap<String, Arc<dyn Tool>>,
+    /// Permission policy checked before every execution (`None` = allow all
+    /// but the tools that always need approval).
+    policy: Option<ToolPolicy>,
+    /// Mission/workspace calls run under (for policy rules and the audit log).
+    scope: PolicyScope,
+    /// Resolves `ask` decisions; without it `ask` behaves like `deny`.
+    approver: Option<PolicyApprover>,
+    /// Caps applied to successful results (`None` = unlimited).
+    result_limits: Option<ToolResultLimits>,
+    /// Vault that `registry/key` secret references are resolved against.
+    secrets: Option<Arc<SecretsStore>>,
 }
 
 impl ToolRegistry {
@@ -172,6 +274,11 @@ impl ToolRegistry {
     pub fn empty() -> Self {
         Self {
             tools: HashMap::new(),
+            policy: None,
+            scope: PolicyScope::default(),
+            approver: None,
+            result_limits: None,
+            secrets: None,
         }
     }
 
@@ -184,6 +291,18 @@ impl ToolRegistry {
         // File operations
         tools.insert("read_file".to_string(), Arc::new(file_ops::ReadFile));
         tools.insert("write_file".to_string(), Arc::new(file_ops::WriteFile));
+        tools.insert("edit_file".to_string(), Arc::new(file_ops::EditFile));
+        tools.insert("apply_patch".to_string(), Arc::new(patch::ApplyPatch));
+        tools.insert("move_file".to_string(), Arc::new(file_ops::MoveFile));
+        tools.insert("copy_file".to_string(), Arc::new(file_ops::CopyFile));
+        tools.insert(
+            "archive_create".to_string(),
+            Arc::new(archive::ArchiveCreate),
+        );
+        tools.insert(
+            "archive_extract".to_string(),
+            Arc::new(archive::ArchiveExtract),
+        );
         tools.insert("delete_file".to_string(), Arc::new(file_ops::DeleteFile));
 
         // Directory operations
@@ -202,16 +321,92 @@ impl ToolRegistry {
 
         // Terminal
         tools.insert("run_command".to_string(), Arc::new(terminal::RunCommand));
+        tools.insert("ssh_exec".to_string(), Arc::new(ssh::SshExec));
+        tools.insert("process_start".to_string(), Arc::new(process::ProcessStart));
+        tools.insert(
+            "process_status".to_string(),
+            Arc::new(process::ProcessStatus),
+        );
+        tools.insert("process_logs".to_string(), Arc::new(process::ProcessLogs));
+        tools.insert("process_stop".to_string(), Arc::new(process::ProcessStop));
+
+        // Git
+        tools.insert("git_branch".to_string(), Arc::new(git::GitBranch));
+        tools.insert("git_checkout".to_string(), Arc::new(git::GitCheckout));
+        tools.insert("git_push".to_string(), Arc::new(git::GitPush));
+        tools.insert("git_stash".to_string(), Arc::new(git::GitStash));
+
+        // GitHub
+        tools.insert(
+            "github_create_pr".to_string(),
+            Arc::new(github::GitHubCreatePr),
+        );
+        tools.insert(
+            "github_comment".to_string(),
+            Arc::new(github::GitHubComment),
+        );
+        tools.insert(
+            "github_get_issue".to_string(),
+            Arc::new(github::GitHubGetIssue),
+        );
+        tools.insert(
+            "github_list_checks".to_string(),
+            Arc::new(github::GitHubListChecks),
+        );
+
+        // Docker
+        tools.insert("docker_build".to_string(), Arc::new(docker::DockerBuild));
+        tools.insert("docker_run".to_string(), Arc::new(docker::DockerRun));
+        tools.insert("docker_logs".to_string(), Arc::new(docker::DockerLogs));
+        tools.insert("docker_stop".to_string(), Arc::new(docker::DockerStop));
+
+        // Kubernetes (k8s_apply is approval-gated by the tool policy)
+        tools.insert("k8s_get".to_string(), Arc::new(k8s::K8sGet));
+        tools.insert("k8s_logs".to_string(), Arc::new(k8s::K8sLogs));
+        tools.insert("k8s_describe".to_string(), Arc::new(k8s::K8sDescribe));
+        tools.insert("k8s_apply".to_string(), Arc::new(k8s::K8sApply));
+
+        // Scheduling
+        tools.insert(
+            "schedule_task".to_string(),
+            Arc::new(scheduler::ScheduleTask),
+        );
+
+        // Notifications
+        tools.insert("notify".to_string(), Arc::new(notify::Notify));
 
         // Search
         tools.insert("grep_search".to_string(), Arc::new(search::GrepSearch));
+        tools.insert(
+            "semantic_search".to_string(),
+            Arc::new(semantic::SemanticSearch),
+        );
+
+        // Data
+        tools.insert("sqlite_query".to_string(), Arc::new(sqlite::SqliteQuery));
+        tools.insert("db_query".to_string(), Arc::new(database::DbQuery));
+        tools.insert("pdf_extract".to_string(), Arc::new(pdf::PdfExtract));
 
         // Web (fetch only; web search removed in favor of OMO/Exa)
         tools.insert("fetch_url".to_string(), Arc::new(web::FetchUrl));
+        tools.insert("http_request".to_string(), Arc::new(web::HttpRequest));
+        tools.insert("web_search".to_string(), Arc::new(web::WebSearch));
 
         // Frontend Tool UI (schemas for rich rendering in the dashboard)
         tools.insert("ui_optionList".to_string(), Arc::new(ui::UiOptionList));
         tools.insert("ui_dataTable".to_string(), Arc::new(ui::UiDataTable));
+        tools.insert("ui_chart".to_string(), Arc::new(ui::UiChart));
+        tools.insert("ui_fileTree".to_string(), Arc::new(ui::UiFileTree));
+        tools.insert("ui_progress".to_string(), Arc::new(ui::UiProgress));
+        tools.insert("ui_form".to_string(), Arc::new(ui::UiForm));
+        tools.insert("ui_diffViewer".to_string(), Arc::new(ui::UiDiffViewer));
+        tools.insert("ask_user".to_string(), Arc::new(ui::AskUser));
+
+        // Mission output files
+        tools.insert(
+            "register_artifact".to_string(),
+            Arc::new(artifact::RegisterArtifact),
+        );
 
         // Composite tools (higher-level workflow operations)
         tools.insert(
@@ -225,6 +420,24 @@ impl ToolRegistry {
         );
         tools.insert("debug_error".to_string(), Arc::new(composite::DebugError));
 
+        // Vision
+        tools.insert("analyze_image".to_string(), Arc::new(vision::AnalyzeImage));
+
+        // Headless browser automation (conditional on a Chromium install)
+        if desktop::find_browser_command().is_some() {
+            tools.insert("browser_navigate".to_string(), Arc::new(browser::Navigate));
+            tools.insert(
+                "browser_click_selector".to_string(),
+                Arc::new(browser::ClickSelector),
+            );
+            tools.insert("browser_extract".to_string(), Arc::new(browser::Extract));
+            tools.insert(
+                "browser_screenshot".to_string(),
+                Arc::new(browser::BrowserScreenshot),
+            );
+            tools.insert("browser_close".to_string(), Arc::new(browser::Close));
+        }
+
         // Desktop automation (conditional on DESKTOP_ENABLED)
         if desktop::desktop_enabled() {
             tools.insert(
@@ -239,6 +452,11 @@ impl ToolRegistry {
                 "desktop_screenshot".to_string(),
                 Arc::new(desktop::Screenshot),
             );
+            tools.insert(
+                "desktop_screenshot_region".to_string(),
+                Arc::new(desktop::ScreenshotRegion),
+            );
+            tools.insert("desktop_annotate".to_string(), Arc::new(desktop::Annotate));
             tools.insert("desktop_type".to_string(), Arc::new(desktop::TypeText));
             tools.insert("desktop_click".to_string(), Arc::new(desktop::Click));
             tools.insert("desktop_get_text".to_string(), Arc::new(desktop::GetText));
@@ -251,6 +469,14 @@ impl ToolRegistry {
                 "desktop_i3_command".to_string(),
                 Arc::new(desktop::I3Command),
             );
+            tools.insert(
+                "desktop_set_clipboard".to_string(),
+                Arc::new(desktop::SetClipboard),
+            );
+            tools.insert(
+                "desktop_get_clipboard".to_string(),
+                Arc::new(desktop::GetClipboard),
+            );
         }
 
         // Mission control (allows agent to complete/fail missions)
@@ -265,7 +491,51 @@ impl ToolRegistry {
             registry_id,
             tools.len()
         );
-        Self { tools }
+        Self {
+            tools,
+            policy: None,
+            scope: PolicyScope::default(),
+            approver: None,
+            result_limits: None,
+            secrets: None,
+        }
+    }
+
+    /// Enforce `policy` on every call.
+    pub fn with_policy(mut self, policy: ToolPolicy) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+
+    /// Shrink results that exceed `limits`.
+    pub fn with_result_limits(mut self, limits: ToolResultLimits) -> Self {
+        self.result_limits = Some(limits);
+        self
+    }
+
+    /// Set the mission/workspace calls are attributed to.
+    pub fn with_scope(mut self, scope: PolicyScope) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    /// Keep only the tools matched by a workspace allowlist (empty = all).
+    /// Removed tools are neither listed nor executable.
+    pub fn with_allowlist(mut self, allowlist: &[String]) -> Self {
+        self.tools.retain(|name, _| tool_allowed(allowlist, name));
+        self
+    }
+
+    /// Resolve secret references against `secrets` during tool calls.
+    pub fn with_secrets(mut self, secrets: Option<Arc<SecretsStore>>) -> Self {
+        self.secrets = secrets;
+        self
+    }
+
+    /// Route `ask` decisions to the dashboard through `approver`.
+    pub fn with_approver(mut self, approver: PolicyApprover) -> Self {
+        self.approver = Some(approver);
+        self
     }
 
     /// List all available tools.
@@ -288,6 +558,10 @@ impl ToolRegistry {
     ///
     /// The `working_dir` is the default directory for relative paths.
     /// Tools accept absolute paths to operate anywhere on the system.
+    /// Calls are checked against the tool policy first (the default policy
+    /// when none is set) and recorded in the audit log when one is
+    /// registered. Oversized results are truncated or summarized according
+    /// to the result limits.
     pub async fn execute(
         &self,
         name: &str,
@@ -299,7 +573,53 @@ impl ToolRegistry {
             .get(name)
             .ok_or_else(|| anyhow::anyhow!("Unknown tool: {}", name))?;
 
-        tool.execute(args, working_dir).await
+        let action = match &self.policy {
+            Some(policy) => policy.evaluate(name, &self.scope),
+            None => ToolPolicy::default().evaluate(name, &self.scope),
+        };
+        match action {
+            PolicyAction::Allow => {}
+            PolicyAction::Deny => {
+                anyhow::bail!("Tool '{}' is denied by the tool policy", name)
+            }
+            PolicyAction::Ask => {
+                let Some(approver) = &self.approver else {
+                    anyhow::bail!(
+                        "Tool '{}' requires approval but no approver is available",
+                        name
+                    );
+                };
+                if !approver.request(name, &args).await? {
+                    anyhow::bail!("The user did not approve running '{}'", name);
+                }
+            }
+        }
+
+        let audit_log = audit::global();
+        let args_for_audit = audit_log.as_ref().map(|_| args.clone());
+        let started = std::time::Instant::now();
+        let result = CALL_SECRETS
+            .scope(self.secrets.clone(), tool.execute(args, working_dir))
+            .await;
+        if let (Some(audit_log), Some(args)) = (audit_log, args_for_audit) {
+            let entry = audit::ToolAuditEntry::new(
+                name,
+                &args,
+                working_dir,
+                started.elapsed().as_millis() as u64,
+                &result,
+                self.scope.mission_id,
+                self.scope.workspace_id,
+            );
+            if let Err(e) = audit_log.append(&entry).await {
+                tracing::warn!("Failed to write tool audit entry for {}: {}", name, e);
+            }
+        }
+
+        match (result, &self.result_limits) {
+            (Ok(output), Some(limits)) => Ok(limits.apply(name, output).await),
+            (result, _) => result,
+        }
     }
 }
 
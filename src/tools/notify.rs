@@ -0,0 +1,632 @@
+//! Notification tool: lets long-running missions report progress or ask for
+//! human attention through channels configured by the operator.
+//!
+//! Channels come from `SANDBOXED_SH_NOTIFY_CHANNELS` (see `config::NotifyConfig`);
+//! the agent can only pick among them, never supply its own destination.
+
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use base64::Engine;
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+
+use super::Tool;
+use crate::config::{NotifyChannel, NotifyChannelKind, NotifyConfig, SmtpSecurity};
+
+/// Notifications allowed per tool server per hour, to stop a looping agent
+/// from flooding a channel.
+const MAX_NOTIFICATIONS_PER_HOUR: usize = 30;
+const MAX_MESSAGE_CHARS: usize = 4000;
+const SEND_TIMEOUT: Duration = Duration::from_secs(30);
+
+static RECENT_SENDS: OnceLock<Mutex<VecDeque<Instant>>> = OnceLock::new();
+
+/// Reserve a slot in the hourly budget, or return how long until one frees up.
+fn take_rate_limit_slot() -> Result<(), Duration> {
+    let mut sends = RECENT_SENDS
+        .get_or_init(|| Mutex::new(VecDeque::new()))
+        .lock()
+        .unwrap();
+    let hour = Duration::from_secs(3600);
+    while sends.front().is_some_and(|t| t.elapsed() >= hour) {
+        sends.pop_front();
+    }
+    if sends.len() >= MAX_NOTIFICATIONS_PER_HOUR {
+        let oldest = *sends.front().expect("non-empty");
+        return Err(hour.saturating_sub(oldest.elapsed()));
+    }
+    sends.push_back(Instant::now());
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Level {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl Level {
+    fn parse(raw: Option<&str>) -> anyhow::Result<Self> {
+        match raw.unwrap_or("info") {
+            "info" => Ok(Self::Info),
+            "success" => Ok(Self::Success),
+            "warning" => Ok(Self::Warning),
+            "error" => Ok(Self::Error),
+            other => Err(anyhow::anyhow!(
+                "Unknown level '{}'; use info, success, warning or error",
+                other
+            )),
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Info => "info",
+            Self::Success => "success",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        }
+    }
+
+    fn emoji(self) -> &'static str {
+        match self {
+            Self::Info => ":information_source:",
+            Self::Success => ":white_check_mark:",
+            Self::Warning => ":warning:",
+            Self::Error => ":x:",
+        }
+    }
+}
+
+/// What gets sent, independent of channel.
+struct Notification {
+    title: String,
+    message: String,
+    level: Level,
+    mission_id: Option<String>,
+}
+
+impl Notification {
+    fn webhook_payload(&self) -> Value {
+        json!({
+            "title": self.title,
+            "message": self.message,
+            "level": self.level.as_str(),
+            "mission_id": self.mission_id,
+            "sent_at": chrono::Utc::now().to_rfc3339(),
+        })
+    }
+
+    fn slack_payload(&self) -> Value {
+        let mut text = format!("{} *{}*\n{}", self.level.emoji(), self.title, self.message);
+        if let Some(mission_id) = &self.mission_id {
+            text.push_str(&format!("\n_Mission {}_", mission_id));
+        }
+        json!({ "text": text })
+    }
+
+    fn email_body(&self) -> String {
+        let mut body = self.message.clone();
+        if let Some(mission_id) = &self.mission_id {
+            body.push_str(&format!("\n\n--\nMission {}", mission_id));
+        }
+        body
+    }
+
+    fn email_subject(&self) -> String {
+        match self.level {
+            Level::Info | Level::Success => format!("[sandboxed.sh] {}", self.title),
+            Level::Warning | Level::Error => format!(
+                "[sandboxed.sh] {}: {}",
+                self.level.as_str().to_uppercase(),
+                self.title
+            ),
+        }
+    }
+}
+
+async fn post_json(
+    url: &str,
+    headers: &std::collections::HashMap<String, String>,
+    body: &Value,
+) -> anyhow::Result<()> {
+    let client = reqwest::Client::builder().timeout(SEND_TIMEOUT).build()?;
+    let mut request = client.post(url).json(body);
+    for (key, value) in headers {
+        request = request.header(key, value);
+    }
+    let response = request.send().await?;
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        return Err(anyhow::anyhow!(
+            "HTTP {}: {}",
+            status,
+            text.chars().take(200).collect::<String>()
+        ));
+    }
+    Ok(())
+}
+
+async fn send_to_channel(
+    channel: &NotifyChannel,
+    notification: &Notification,
+) -> anyhow::Result<()> {
+    match &channel.kind {
+        NotifyChannelKind::Webhook { url, headers } => {
+            post_json(url, headers, &notification.webhook_payload()).await
+        }
+        NotifyChannelKind::Slack { webhook_url } => {
+            post_json(
+                webhook_url,
+                &Default::default(),
+                &notification.slack_payload(),
+            )
+            .await
+        }
+        NotifyChannelKind::Email {
+            smtp_host,
+            smtp_port,
+            security,
+            username,
+            password,
+            from,
+            to,
+        } => {
+            let port = smtp_port.unwrap_or(match security {
+                SmtpSecurity::Tls => 465,
+                SmtpSecurity::Starttls => 587,
+                SmtpSecurity::None => 25,
+            });
+            let credentials = match (username, password) {
+                (Some(user), Some(pass)) => Some((user.as_str(), pass.as_str())),
+                _ => None,
+            };
+            let message = build_email(
+                from,
+                to,
+                &notification.email_subject(),
+                &notification.email_body(),
+            );
+            tokio::time::timeout(
+                SEND_TIMEOUT,
+                smtp_send(smtp_host, port, *security, credentials, from, to, &message),
+            )
+            .await
+            .map_err(|_| anyhow::anyhow!("SMTP timed out"))?
+        }
+    }
+}
+
+// ============================================================================
+// SMTP
+// ============================================================================
+
+/// Encode a header value as RFC 2047 if it isn't plain ASCII.
+fn encode_header(value: &str) -> String {
+    if value.is_ascii() {
+        value.to_string()
+    } else {
+        format!(
+            "=?UTF-8?B?{}?=",
+            base64::engine::general_purpose::STANDARD.encode(value)
+        )
+    }
+}
+
+/// Build an RFC 5322 message with CRLF line endings and dot-stuffing applied,
+/// ready to send after `DATA`.
+fn build_email(from: &str, to: &[String], subject: &str, body: &str) -> String {
+    let domain = from.rsplit('@').next().unwrap_or("localhost");
+    let mut message = format!(
+        "From: {}\r\nTo: {}\r\nSubject: {}\r\nDate: {}\r\nMessage-ID: <{}@{}>\r\nMIME-Version: 1.0\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Transfer-Encoding: 8bit\r\n\r\n",
+        from,
+        to.join(", "),
+        encode_header(subject),
+        chrono::Utc::now().to_rfc2822(),
+        uuid::Uuid::new_v4(),
+        domain
+    );
+    for line in body.replace("\r\n", "\n").split('\n') {
+        if line.starts_with('.') {
+            message.push('.');
+        }
+        message.push_str(line);
+        message.push_str("\r\n");
+    }
+    message
+}
+
+/// Read a (possibly multi-line) SMTP reply and check its code.
+async fn smtp_expect<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: &mut BufReader<S>,
+    expected: u16,
+) -> anyhow::Result<String> {
+    let mut reply = String::new();
+    loop {
+        let mut line = String::new();
+        if stream.read_line(&mut line).await? == 0 {
+            return Err(anyhow::anyhow!("SMTP server closed the connection"));
+        }
+        reply.push_str(&line);
+        // "250-..." continues, "250 ..." ends the reply.
+        if line.len() < 4 || line.as_bytes()[3] != b'-' {
+            break;
+        }
+    }
+    let code: u16 = reply.get(..3).and_then(|c| c.parse().ok()).unwrap_or(0);
+    if code != expected {
+        return Err(anyhow::anyhow!(
+            "SMTP error (expected {}): {}",
+            expected,
+            reply.trim()
+        ));
+    }
+    Ok(reply)
+}
+
+async fn smtp_command<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: &mut BufReader<S>,
+    command: &str,
+    expected: u16,
+) -> anyhow::Result<String> {
+    stream.get_mut().write_all(command.as_bytes()).await?;
+    stream.get_mut().write_all(b"\r\n").await?;
+    stream.get_mut().flush().await?;
+    smtp_expect(stream, expected).await
+}
+
+/// Everything after the greeting (and after STARTTLS, if used).
+async fn smtp_session<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: &mut BufReader<S>,
+    credentials: Option<(&str, &str)>,
+    from: &str,
+    to: &[String],
+    message: &str,
+) -> anyhow::Result<()> {
+    smtp_command(stream, "EHLO sandboxed.sh", 250).await?;
+    if let Some((user, pass)) = credentials {
+        let token =
+            base64::engine::general_purpose::STANDARD.encode(format!("\0{}\0{}", user, pass));
+        smtp_command(stream, &format!("AUTH PLAIN {}", token), 235).await?;
+    }
+    smtp_command(stream, &format!("MAIL FROM:<{}>", from), 250).await?;
+    for rcpt in to {
+        smtp_command(stream, &format!("RCPT TO:<{}>", rcpt), 250).await?;
+    }
+    smtp_command(stream, "DATA", 354).await?;
+    stream.get_mut().write_all(message.as_bytes()).await?;
+    smtp_command(stream, ".", 250).await?;
+    let _ = smtp_command(stream, "QUIT", 221).await;
+    Ok(())
+}
+
+async fn smtp_send(
+    host: &str,
+    port: u16,
+    security: SmtpSecurity,
+    credentials: Option<(&str, &str)>,
+    from: &str,
+    to: &[String],
+    message: &str,
+) -> anyhow::Result<()> {
+    if to.is_empty() {
+        return Err(anyhow::anyhow!("Email channel has no recipients"));
+    }
+    if credentials.is_some() && security == SmtpSecurity::None {
+        return Err(anyhow::anyhow!(
+            "Refusing to send SMTP credentials over an unencrypted connection"
+        ));
+    }
+    let tcp = TcpStream::connect((host, port)).await?;
+    let connector = || -> anyhow::Result<tokio_native_tls::TlsConnector> {
+        Ok(tokio_native_tls::native_tls::TlsConnector::new()?.into())
+    };
+
+    match security {
+        SmtpSecurity::Tls => {
+            let tls = connector()?.connect(host, tcp).await?;
+            let mut stream = BufReader::new(tls);
+            smtp_expect(&mut stream, 220).await?;
+            smtp_session(&mut stream, credentials, from, to, message).await
+        }
+        SmtpSecurity::Starttls => {
+            let mut plain = BufReader::new(tcp);
+            smtp_expect(&mut plain, 220).await?;
+            smtp_command(&mut plain, "EHLO sandboxed.sh", 250).await?;
+            smtp_command(&mut plain, "STARTTLS", 220).await?;
+            let tls = connector()?.connect(host, plain.into_inner()).await?;
+            let mut stream = BufReader::new(tls);
+            smtp_session(&mut stream, credentials, from, to, message).await
+        }
+        SmtpSecurity::None => {
+            let mut stream = BufReader::new(tcp);
+            smtp_expect(&mut stream, 220).await?;
+            smtp_session(&mut stream, credentials, from, to, message).await
+        }
+    }
+}
+
+// ============================================================================
+// Tool
+// ============================================================================
+
+/// Send a warning raised by the server itself (not by an agent) to every
+/// configured channel. Failures are logged; the agent rate limit doesn't apply.
+pub async fn send_alert(config: &NotifyConfig, title: &str, message: &str, mission_id: uuid::Uuid) {
+    let notification = Notification {
+        title: title.to_string(),
+        message: message.chars().take(MAX_MESSAGE_CHARS).collect(),
+        level: Level::Warning,
+        mission_id: Some(mission_id.to_string()),
+    };
+    for channel in &config.channels {
+        if let Err(e) = send_to_channel(channel, &notification).await {
+            tracing::warn!(channel = %channel.name, "Alert failed: {}", e);
+        }
+    }
+}
+
+/// Send a notification to a configured channel.
+pub struct Notify;
+
+#[async_trait]
+impl Tool for Notify {
+    fn name(&self) -> &str {
+        "notify"
+    }
+
+    fn description(&self) -> &str {
+        "Send a notification to a human through a configured channel (webhook, Slack, or email). Use it to report milestones of long tasks or to ask someone to check something; don't use it for routine progress. Call with action='list' to see available channels."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "message": {
+                    "type": "string",
+                    "description": "The notification text"
+                },
+                "title": {
+                    "type": "string",
+                    "description": "Optional: short summary line (default: first line of message)"
+                },
+                "level": {
+                    "type": "string",
+                    "enum": ["info", "success", "warning", "error"],
+                    "description": "Optional: severity (default: info)"
+                },
+                "channel": {
+                    "type": "string",
+                    "description": "Optional: channel name. Defaults to all configured channels."
+                },
+                "action": {
+                    "type": "string",
+                    "enum": ["send", "list"],
+                    "description": "Optional: 'list' shows configured channels instead of sending (default: send)"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: Value, _working_dir: &Path) -> anyhow::Result<String> {
+        let config = NotifyConfig::from_env().map_err(|e| anyhow::anyhow!("{}", e))?;
+        if config.channels.is_empty() {
+            return Err(anyhow::anyhow!(
+                "No notification channels are configured (set SANDBOXED_SH_NOTIFY_CHANNELS)"
+            ));
+        }
+
+        if args["action"].as_str() == Some("list") {
+            return Ok(config
+                .channels
+                .iter()
+                .map(|c| {
+                    let kind = match c.kind {
+                        NotifyChannelKind::Webhook { .. } => "webhook",
+                        NotifyChannelKind::Slack { .. } => "slack",
+                        NotifyChannelKind::Email { .. } => "email",
+                    };
+                    format!("- {} ({})", c.name, kind)
+                })
+                .collect::<Vec<_>>()
+                .join("\n"));
+        }
+
+        let message = args["message"]
+            .as_str()
+            .filter(|m| !m.trim().is_empty())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'message' argument"))?;
+        let message: String = message.chars().take(MAX_MESSAGE_CHARS).collect();
+        let title = args["title"]
+            .as_str()
+            .filter(|t| !t.trim().is_empty())
+            .map(|t| t.trim().to_string())
+            .unwrap_or_else(|| {
+                message
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .chars()
+                    .take(80)
+                    .collect()
+            });
+        let notification = Notification {
+            title,
+            message,
+            level: Level::parse(args["level"].as_str())?,
+            mission_id: std::env::var("SANDBOXED_SH_MISSION_ID")
+                .ok()
+                .filter(|id| !id.trim().is_empty()),
+        };
+
+        let targets: Vec<&NotifyChannel> = match args["channel"].as_str() {
+            Some(name) => {
+                vec![config
+                    .channels
+                    .iter()
+                    .find(|c| c.name == name)
+                    .ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Unknown channel '{}'. Available: {}",
+                            name,
+                            config
+                                .channels
+                                .iter()
+                                .map(|c| c.name.as_str())
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        )
+                    })?]
+            }
+            None => config.channels.iter().collect(),
+        };
+
+        if let Err(wait) = take_rate_limit_slot() {
+            return Err(anyhow::anyhow!(
+                "Notification limit reached ({} per hour); try again in {} minutes",
+                MAX_NOTIFICATIONS_PER_HOUR,
+                wait.as_secs() / 60 + 1
+            ));
+        }
+
+        let mut sent = Vec::new();
+        let mut failed = Vec::new();
+        for channel in targets {
+            match send_to_channel(channel, &notification).await {
+                Ok(()) => sent.push(channel.name.clone()),
+                Err(e) => {
+                    tracing::warn!(channel = %channel.name, "Notification failed: {}", e);
+                    failed.push(format!("{}: {}", channel.name, e));
+                }
+            }
+        }
+
+        if sent.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Notification failed:\n{}",
+                failed.join("\n")
+            ));
+        }
+        let mut result = format!("Notification sent to {}", sent.join(", "));
+        if !failed.is_empty() {
+            result.push_str(&format!("\nFailed:\n{}", failed.join("\n")));
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn test_build_email_dot_stuffs_and_encodes_subject() {
+        let message = build_email(
+            "bot@example.com",
+            &["ops@example.com".to_string()],
+            "Build fertig ✓",
+            "line one\n.hidden\nend",
+        );
+        assert!(message.contains("Subject: =?UTF-8?B?"));
+        assert!(message.ends_with("\r\n\r\nline one\r\n..hidden\r\nend\r\n"));
+        assert!(message.contains("Message-ID: <"));
+    }
+
+    #[tokio::test]
+    async fn test_smtp_send_plain() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut stream = BufReader::new(socket);
+            let mut transcript = Vec::new();
+            stream
+                .get_mut()
+                .write_all(b"220 test ready\r\n")
+                .await
+                .unwrap();
+            let mut in_data = false;
+            loop {
+                let mut line = String::new();
+                if stream.read_line(&mut line).await.unwrap() == 0 {
+                    break;
+                }
+                let line = line.trim_end().to_string();
+                let reply: &[u8] = if in_data {
+                    if line == "." {
+                        in_data = false;
+                        b"250 queued\r\n"
+                    } else {
+                        transcript.push(line);
+                        continue;
+                    }
+                } else if line.starts_with("EHLO") {
+                    b"250-test\r\n250 8BITMIME\r\n"
+                } else if line == "DATA" {
+                    in_data = true;
+                    b"354 go ahead\r\n"
+                } else if line == "QUIT" {
+                    stream.get_mut().write_all(b"221 bye\r\n").await.unwrap();
+                    break;
+                } else {
+                    transcript.push(line);
+                    b"250 ok\r\n"
+                };
+                stream.get_mut().write_all(reply).await.unwrap();
+            }
+            transcript
+        });
+
+        let to = vec!["ops@example.com".to_string()];
+        let message = build_email("bot@example.com", &to, "Done", "All tests pass");
+        smtp_send(
+            "127.0.0.1",
+            port,
+            SmtpSecurity::None,
+            None,
+            "bot@example.com",
+            &to,
+            &message,
+        )
+        .await
+        .unwrap();
+
+        let transcript = server.await.unwrap();
+        assert_eq!(transcript[0], "MAIL FROM:<bot@example.com>");
+        assert_eq!(transcript[1], "RCPT TO:<ops@example.com>");
+        assert!(transcript.contains(&"Subject: Done".to_string()));
+        assert_eq!(transcript.last().unwrap(), "All tests pass");
+    }
+
+    #[test]
+    fn test_channel_config_parsing() {
+        let channels: Vec<NotifyChannel> = serde_json::from_value(json!([
+            {"name": "ops", "type": "slack", "webhook_url": "https://hooks.slack.com/x"},
+            {"name": "mail", "type": "email", "smtp_host": "smtp.example.com",
+             "from": "bot@example.com", "to": ["a@example.com"]}
+        ]))
+        .unwrap();
+        assert!(matches!(channels[0].kind, NotifyChannelKind::Slack { .. }));
+        match &channels[1].kind {
+            NotifyChannelKind::Email {
+                security,
+                smtp_port,
+                ..
+            } => {
+                assert_eq!(*security, SmtpSecurity::Starttls);
+                assert_eq!(*smtp_port, None);
+            }
+            other => panic!("unexpected channel {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,582 @@
+//! Apply unified diffs to the filesystem.
+//!
+//! Patches are parsed leniently, since model-written diffs often carry wrong hunk
+//! counts or stale line numbers:
+//! - Hunk line counts in `@@` headers are ignored; a hunk runs until the next
+//!   hunk or file header.
+//! - Each hunk is searched for around its stated position (offset tolerance),
+//!   first exactly, then ignoring trailing whitespace, then with up to
+//!   [`MAX_FUZZ`] context lines dropped from either end (fuzz).
+//! - `/dev/null` as the old path creates a file, as the new path deletes it.
+//!
+//! Every file is patched in memory before anything is written, so a patch either
+//! applies completely or not at all.
+
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use super::{resolve_path, Tool};
+
+/// Maximum number of context lines dropped from each end of a hunk.
+const MAX_FUZZ: usize = 2;
+
+#[derive(Debug, Clone, PartialEq)]
+enum HunkLine {
+    Context(String),
+    Remove(String),
+    Add(String),
+}
+
+#[derive(Debug, Clone)]
+struct Hunk {
+    /// 1-indexed start line in the original file (0 for an empty file).
+    old_start: usize,
+    lines: Vec<HunkLine>,
+    /// The patched file should not end with a newline.
+    no_newline_at_end: bool,
+}
+
+#[derive(Debug, Clone)]
+struct FilePatch {
+    old_path: Option<String>,
+    new_path: Option<String>,
+    hunks: Vec<Hunk>,
+}
+
+/// Outcome for one file touched by a patch.
+#[derive(Debug, Clone, Serialize)]
+pub struct PatchedFile {
+    pub path: String,
+    /// `created`, `modified`, `deleted` or `renamed`.
+    pub action: String,
+    pub hunks: usize,
+    /// Notes about hunks that needed an offset or fuzz to apply.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<String>,
+}
+
+/// Apply a unified diff. `resolve` maps a path from the patch to a file on disk
+/// and can reject paths that are not allowed.
+pub async fn apply_patch<F>(patch: &str, resolve: F) -> anyhow::Result<Vec<PatchedFile>>
+where
+    F: Fn(&str) -> anyhow::Result<PathBuf>,
+{
+    let file_patches = parse_patch(patch)?;
+    if file_patches.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No file changes found in patch; expected unified diff headers ('--- a/file', '+++ b/file') and '@@' hunks"
+        ));
+    }
+
+    // (path to write, new content or None to delete)
+    let mut writes: Vec<(PathBuf, Option<String>)> = Vec::new();
+    let mut results = Vec::new();
+    for fp in &file_patches {
+        let display = fp
+            .new_path
+            .as_deref()
+            .or(fp.old_path.as_deref())
+            .unwrap_or_default()
+            .to_string();
+        let source = match &fp.old_path {
+            Some(old) => Some(resolve(old)?),
+            None => None,
+        };
+        let target = match &fp.new_path {
+            Some(new) => Some(resolve(new)?),
+            None => None,
+        };
+
+        let original = match &source {
+            Some(path) => tokio::fs::read_to_string(path)
+                .await
+                .map_err(|e| anyhow::anyhow!("Cannot read {}: {}", display, e))?,
+            None => {
+                if let Some(path) = &target {
+                    if path.exists() {
+                        return Err(anyhow::anyhow!(
+                            "{} already exists; the patch creates it from /dev/null",
+                            display
+                        ));
+                    }
+                }
+                String::new()
+            }
+        };
+
+        let (patched, notes) =
+            apply_hunks(&original, &fp.hunks).map_err(|e| anyhow::anyhow!("{}: {}", display, e))?;
+
+        let action = match (&source, &target) {
+            (None, _) => "created",
+            (Some(_), None) => "deleted",
+            (Some(s), Some(t)) if s != t => "renamed",
+            _ => "modified",
+        };
+        match (&source, &target) {
+            (Some(s), None) => writes.push((s.clone(), None)),
+            (Some(s), Some(t)) if s != t => {
+                writes.push((t.clone(), Some(patched)));
+                writes.push((s.clone(), None));
+            }
+            (_, Some(t)) => writes.push((t.clone(), Some(patched))),
+            (None, None) => {}
+        }
+        results.push(PatchedFile {
+            path: display,
+            action: action.to_string(),
+            hunks: fp.hunks.len(),
+            notes,
+        });
+    }
+
+    write_all(writes).await?;
+    Ok(results)
+}
+
+/// Write every file, restoring the ones already written if any write fails.
+async fn write_all(writes: Vec<(PathBuf, Option<String>)>) -> anyhow::Result<()> {
+    let mut done: Vec<(PathBuf, Option<Vec<u8>>)> = Vec::new();
+    for (path, content) in writes {
+        let previous = tokio::fs::read(&path).await.ok();
+        let result = match &content {
+            Some(content) => {
+                if let Some(parent) = path.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+                tokio::fs::write(&path, content).await
+            }
+            None => tokio::fs::remove_file(&path).await,
+        };
+        if let Err(e) = result {
+            for (path, previous) in done.into_iter().rev() {
+                let _ = match previous {
+                    Some(bytes) => tokio::fs::write(&path, bytes).await,
+                    None => tokio::fs::remove_file(&path).await,
+                };
+            }
+            return Err(anyhow::anyhow!(
+                "Failed to write {}: {} (no changes were kept)",
+                path.display(),
+                e
+            ));
+        }
+        done.push((path, previous));
+    }
+    Ok(())
+}
+
+fn parse_header_path(rest: &str) -> Option<String> {
+    // Drop a trailing timestamp ("file\t2024-01-01 ...").
+    let path = rest.split('\t').next().unwrap_or(rest).trim();
+    if path == "/dev/null" {
+        None
+    } else {
+        Some(path.to_string())
+    }
+}
+
+fn strip_git_prefix(path: Option<String>, prefix: &str) -> Option<String> {
+    path.map(|p| p.strip_prefix(prefix).map(str::to_string).unwrap_or(p))
+}
+
+fn parse_hunk_start(header: &str) -> anyhow::Result<usize> {
+    // "@@ -12,5 +12,7 @@ optional section"
+    let old = header
+        .trim_start_matches('@')
+        .split_whitespace()
+        .find(|part| part.starts_with('-'))
+        .ok_or_else(|| anyhow::anyhow!("Malformed hunk header: {}", header))?;
+    old[1..]
+        .split(',')
+        .next()
+        .and_then(|n| n.parse().ok())
+        .ok_or_else(|| anyhow::anyhow!("Malformed hunk header: {}", header))
+}
+
+fn parse_patch(patch: &str) -> anyhow::Result<Vec<FilePatch>> {
+    let lines: Vec<&str> = patch.lines().collect();
+    let mut files: Vec<FilePatch> = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        let is_file_header = line.starts_with("--- ")
+            && lines
+                .get(i + 1)
+                .is_some_and(|next| next.starts_with("+++ "));
+        if is_file_header {
+            let old_path = strip_git_prefix(parse_header_path(&line[4..]), "a/");
+            let new_path = strip_git_prefix(parse_header_path(&lines[i + 1][4..]), "b/");
+            if old_path.is_none() && new_path.is_none() {
+                return Err(anyhow::anyhow!("Both sides of a file header are /dev/null"));
+            }
+            files.push(FilePatch {
+                old_path,
+                new_path,
+                hunks: Vec::new(),
+            });
+            i += 2;
+            continue;
+        }
+        if line.starts_with("@@") {
+            let file = files
+                .last_mut()
+                .ok_or_else(|| anyhow::anyhow!("Hunk before any '---'/'+++' file header"))?;
+            let mut hunk = Hunk {
+                old_start: parse_hunk_start(line)?,
+                lines: Vec::new(),
+                no_newline_at_end: false,
+            };
+            i += 1;
+            while i < lines.len() {
+                let line = lines[i];
+                let next_is_header = line.starts_with("@@")
+                    || line.starts_with("diff ")
+                    || (line.starts_with("--- ")
+                        && lines
+                            .get(i + 1)
+                            .is_some_and(|next| next.starts_with("+++ ")));
+                if next_is_header {
+                    break;
+                }
+                if let Some(rest) = line.strip_prefix('+') {
+                    hunk.lines.push(HunkLine::Add(rest.to_string()));
+                    hunk.no_newline_at_end = false;
+                } else if let Some(rest) = line.strip_prefix('-') {
+                    hunk.lines.push(HunkLine::Remove(rest.to_string()));
+                } else if let Some(rest) = line.strip_prefix(' ') {
+                    hunk.lines.push(HunkLine::Context(rest.to_string()));
+                    hunk.no_newline_at_end = false;
+                } else if line.starts_with('\\') {
+                    // "\ No newline at end of file" applies to the new side
+                    // only when it follows an added or context line.
+                    if matches!(
+                        hunk.lines.last(),
+                        Some(HunkLine::Add(_)) | Some(HunkLine::Context(_))
+                    ) {
+                        hunk.no_newline_at_end = true;
+                    }
+                } else if line.is_empty() {
+                    // Editors often strip the space from empty context lines.
+                    hunk.lines.push(HunkLine::Context(String::new()));
+                } else {
+                    break;
+                }
+                i += 1;
+            }
+            while hunk.lines.last() == Some(&HunkLine::Context(String::new())) {
+                hunk.lines.pop();
+            }
+            file.hunks.push(hunk);
+            continue;
+        }
+        // Anything else ("diff --git", "index ...", prose) is ignored.
+        i += 1;
+    }
+    files.retain(|f| !f.hunks.is_empty() || f.new_path.is_none());
+    Ok(files)
+}
+
+/// Find `needle` in `haystack` at or after `min`, closest to `hint`.
+fn find_block(
+    haystack: &[String],
+    needle: &[&str],
+    hint: usize,
+    min: usize,
+    loose: bool,
+) -> Option<usize> {
+    if haystack.len() < needle.len() {
+        return None;
+    }
+    let last = haystack.len() - needle.len();
+    if min > last {
+        return None;
+    }
+    let matches_at = |pos: usize| {
+        needle.iter().enumerate().all(|(i, line)| {
+            if loose {
+                haystack[pos + i].trim_end() == line.trim_end()
+            } else {
+                haystack[pos + i] == *line
+            }
+        })
+    };
+    let hint = hint.clamp(min, last);
+    for distance in 0..=last - min {
+        if hint + distance <= last && matches_at(hint + distance) {
+            return Some(hint + distance);
+        }
+        if distance > 0 && hint >= min + distance && matches_at(hint - distance) {
+            return Some(hint - distance);
+        }
+    }
+    None
+}
+
+/// Apply hunks in order to `content`. Returns the new content and notes for
+/// hunks that applied at an offset or with fuzz.
+fn apply_hunks(content: &str, hunks: &[Hunk]) -> anyhow::Result<(String, Vec<String>)> {
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let mut trailing_newline = content.is_empty() || content.ends_with('\n');
+    let mut notes = Vec::new();
+    // Where the previous hunk ended; hunks must not overlap.
+    let mut min = 0;
+    // Difference between line numbers in the patched and original file.
+    let mut shift: isize = 0;
+
+    for (index, hunk) in hunks.iter().enumerate() {
+        let old: Vec<&str> = hunk
+            .lines
+            .iter()
+            .filter_map(|l| match l {
+                HunkLine::Context(s) | HunkLine::Remove(s) => Some(s.as_str()),
+                HunkLine::Add(_) => None,
+            })
+            .collect();
+        let new: Vec<&str> = hunk
+            .lines
+            .iter()
+            .filter_map(|l| match l {
+                HunkLine::Context(s) | HunkLine::Add(s) => Some(s.as_str()),
+                HunkLine::Remove(_) => None,
+            })
+            .collect();
+        let leading_context = hunk
+            .lines
+            .iter()
+            .take_while(|l| matches!(l, HunkLine::Context(_)))
+            .count();
+        let trailing_context = hunk
+            .lines
+            .iter()
+            .rev()
+            .take_while(|l| matches!(l, HunkLine::Context(_)))
+            .count()
+            .min(old.len() - leading_context.min(old.len()));
+
+        let hint = (hunk.old_start.saturating_sub(1) as isize + shift).max(0) as usize;
+
+        let mut found = None;
+        'search: for fuzz in 0..=MAX_FUZZ {
+            let front = fuzz.min(leading_context);
+            let back = fuzz.min(trailing_context);
+            if fuzz > 0 && front == 0 && back == 0 {
+                break;
+            }
+            let old_part = &old[front..old.len() - back];
+            if old_part.is_empty() && !old.is_empty() {
+                break;
+            }
+            for loose in [false, true] {
+                if let Some(pos) = find_block(&lines, old_part, hint + front, min, loose) {
+                    found = Some((pos, front, back, fuzz, loose));
+                    break 'search;
+                }
+            }
+        }
+        let (pos, front, back, fuzz, loose) = found.ok_or_else(|| {
+            anyhow::anyhow!(
+                "hunk {} (at line {}) does not match the file",
+                index + 1,
+                hunk.old_start
+            )
+        })?;
+
+        let replaced = old.len() - front - back;
+        let replacement: Vec<String> = new[front..new.len() - back]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let inserted = replacement.len();
+        lines.splice(pos..pos + replaced, replacement);
+
+        let offset = pos as isize - (hint + front) as isize;
+        if offset != 0 || fuzz > 0 || loose {
+            let mut parts = Vec::new();
+            if offset != 0 {
+                parts.push(format!("offset {:+} lines", offset));
+            }
+            if fuzz > 0 {
+                parts.push(format!("fuzz {}", fuzz));
+            }
+            if loose {
+                parts.push("ignoring trailing whitespace".to_string());
+            }
+            notes.push(format!(
+                "hunk {} applied with {}",
+                index + 1,
+                parts.join(", ")
+            ));
+        }
+
+        min = pos + inserted;
+        shift += offset + inserted as isize - replaced as isize;
+        if hunk.no_newline_at_end {
+            trailing_newline = false;
+        } else if pos + inserted == lines.len() && inserted > 0 {
+            trailing_newline = true;
+        }
+    }
+
+    let mut out = lines.join("\n");
+    if trailing_newline && !lines.is_empty() {
+        out.push('\n');
+    }
+    Ok((out, notes))
+}
+
+/// Apply a unified diff to one or more files.
+pub struct ApplyPatch;
+
+#[async_trait]
+impl Tool for ApplyPatch {
+    fn name(&self) -> &str {
+        "apply_patch"
+    }
+
+    fn description(&self) -> &str {
+        "Apply a unified diff (as produced by `git diff` or `diff -u`) to one or more files at once. Paths are relative to the workspace. Use '--- /dev/null' to create a file and '+++ /dev/null' to delete one. Hunks tolerate shifted line numbers and small context mismatches. Either every file is changed or none is."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "patch": {
+                    "type": "string",
+                    "description": "Unified diff with '--- a/path' / '+++ b/path' headers and '@@' hunks"
+                }
+            },
+            "required": ["patch"]
+        })
+    }
+
+    async fn execute(&self, args: Value, working_dir: &Path) -> anyhow::Result<String> {
+        let patch = args["patch"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing 'patch' argument"))?;
+
+        let files = apply_patch(patch, |path| Ok(resolve_path(path, working_dir).resolved)).await?;
+
+        let mut result = format!("Applied patch to {} file(s):\n", files.len());
+        for file in &files {
+            result.push_str(&format!(
+                "- {} {} ({} hunk{})\n",
+                file.action,
+                file.path,
+                file.hunks,
+                if file.hunks == 1 { "" } else { "s" }
+            ));
+            for note in &file.notes {
+                result.push_str(&format!("  - {}\n", note));
+            }
+        }
+        Ok(result.trim_end().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_hunks_with_offset_and_fuzz() {
+        let content =
+            "// header\n// added later\nfn a() {\n    one();\n}\n\nfn b() {\n    two();\n}\n";
+        // Line numbers are stale (file gained two lines) and the first
+        // context line no longer matches.
+        let patch = "\
+--- a/lib.rs
++++ b/lib.rs
+@@ -1,4 +1,4 @@
+ fn a_renamed() {
+     one();
+-}
++}  // end a
+@@ -5,3 +5,4 @@ fn a() {
+ fn b() {
+     two();
++    three();
+ }
+";
+        let files = parse_patch(patch).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].new_path.as_deref(), Some("lib.rs"));
+
+        let (patched, notes) = apply_hunks(content, &files[0].hunks).unwrap();
+        assert_eq!(
+            patched,
+            "// header\n// added later\nfn a() {\n    one();\n}  // end a\n\nfn b() {\n    two();\n    three();\n}\n"
+        );
+        // The second hunk lines up once the first hunk's offset is carried over.
+        assert_eq!(notes, vec!["hunk 1 applied with offset +2 lines, fuzz 1"]);
+    }
+
+    #[test]
+    fn test_apply_hunks_rejects_mismatch() {
+        let patch = "--- a/x\n+++ b/x\n@@ -1,2 +1,2 @@\n-missing\n+line\n";
+        let files = parse_patch(patch).unwrap();
+        assert!(apply_hunks("something else\n", &files[0].hunks).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_apply_patch_is_atomic_across_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("keep.txt"), "a\nb\n").unwrap();
+        std::fs::write(dir.path().join("old.txt"), "bye\n").unwrap();
+        let resolve = |p: &str| Ok(dir.path().join(p));
+
+        let bad = "\
+--- a/keep.txt
++++ b/keep.txt
+@@ -1,2 +1,2 @@
+ a
+-b
++c
+--- a/old.txt
++++ b/old.txt
+@@ -1 +1 @@
+-not there
++x
+";
+        assert!(apply_patch(bad, resolve).await.is_err());
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("keep.txt")).unwrap(),
+            "a\nb\n"
+        );
+
+        let good = "\
+diff --git a/keep.txt b/keep.txt
+--- a/keep.txt
++++ b/keep.txt
+@@ -1,2 +1,2 @@
+ a
+-b
++c
+--- /dev/null
++++ b/new/file.txt
+@@ -0,0 +1,2 @@
++hello
++world
+--- a/old.txt
++++ /dev/null
+@@ -1 +0,0 @@
+-bye
+";
+        let files = apply_patch(good, resolve).await.unwrap();
+        let actions: Vec<&str> = files.iter().map(|f| f.action.as_str()).collect();
+        assert_eq!(actions, vec!["modified", "created", "deleted"]);
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("keep.txt")).unwrap(),
+            "a\nc\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("new/file.txt")).unwrap(),
+            "hello\nworld\n"
+        );
+        assert!(!dir.path().join("old.txt").exists());
+    }
+}
@@ -0,0 +1,1488 @@
+//! PDF tool: extract text and embedded images from PDF files.
+//!
+//! Uses a small built-in parser (object scan, object streams, Flate-encoded
+//! content streams, ToUnicode maps) so missions don't need poppler or other
+//! external tools. Line breaks are inferred from text positioning operators,
+//! which is good enough for reading but won't reproduce complex layouts.
+//! Scanned documents have no text layer; their page images can still be
+//! extracted when they are stored as JPEG.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::io::Read;
+use std::path::Path;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use super::{resolve_path, Tool};
+
+/// Characters returned when the caller doesn't pass `max_chars`.
+const DEFAULT_MAX_CHARS: usize = 20_000;
+/// Larger files are rejected before parsing.
+const MAX_PDF_BYTES: u64 = 200 * 1024 * 1024;
+/// Limit for reference chains, page tree nesting and nested form XObjects.
+const MAX_DEPTH: usize = 32;
+/// Limit for arrays and dictionaries nested in one another.
+const MAX_NESTING: usize = 256;
+/// Limit for one decompressed stream.
+const MAX_INFLATED_BYTES: u64 = 256 * 1024 * 1024;
+
+type Dict = HashMap<String, Obj>;
+
+static NULL: Obj = Obj::Null;
+
+/// A PDF object. Generation numbers are ignored; the newest definition of an
+/// object number wins.
+#[derive(Debug, Clone, PartialEq)]
+enum Obj {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Real(f64),
+    Name(String),
+    Str(Vec<u8>),
+    Array(Vec<Obj>),
+    Dict(Dict),
+    Stream(Dict, Vec<u8>),
+    Ref(u32),
+    /// Bare keyword: a content stream operator or CMap keyword.
+    Op(String),
+}
+
+impl Obj {
+    fn as_dict(&self) -> Option<&Dict> {
+        match self {
+            Obj::Dict(dict) | Obj::Stream(dict, _) => Some(dict),
+            _ => None,
+        }
+    }
+
+    fn as_name(&self) -> Option<&str> {
+        match self {
+            Obj::Name(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            Obj::Int(i) => Some(*i as f64),
+            Obj::Real(r) => Some(*r),
+            _ => None,
+        }
+    }
+}
+
+fn is_whitespace(b: u8) -> bool {
+    matches!(b, 0 | 9 | 10 | 12 | 13 | 32)
+}
+
+fn is_delimiter(b: u8) -> bool {
+    matches!(
+        b,
+        b'(' | b')' | b'<' | b'>' | b'[' | b']' | b'{' | b'}' | b'/' | b'%'
+    )
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+/// Tokenizer for PDF object syntax, also used for content streams and CMaps.
+struct Lexer<'a> {
+    data: &'a [u8],
+    pos: usize,
+    /// Arrays and dictionaries currently open.
+    nesting: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(data: &'a [u8], pos: usize) -> Self {
+        Self {
+            data,
+            pos,
+            nesting: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.data.get(self.pos).copied()
+    }
+
+    fn skip_ws(&mut self) {
+        while let Some(b) = self.peek() {
+            if is_whitespace(b) {
+                self.pos += 1;
+            } else if b == b'%' {
+                while self.peek().is_some_and(|b| b != b'\n' && b != b'\r') {
+                    self.pos += 1;
+                }
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn regular_token(&mut self) -> &'a [u8] {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|b| !is_whitespace(b) && !is_delimiter(b))
+        {
+            self.pos += 1;
+        }
+        &self.data[start..self.pos]
+    }
+
+    /// Parse the next object; `None` at end of input or on malformed syntax.
+    fn next(&mut self) -> Option<Obj> {
+        self.skip_ws();
+        let b = self.peek()?;
+        match b {
+            b'/' => {
+                self.pos += 1;
+                Some(Obj::Name(decode_name(self.regular_token())))
+            }
+            b'(' => {
+                self.pos += 1;
+                Some(Obj::Str(self.literal_string()))
+            }
+            b'<' if self.data.get(self.pos + 1) == Some(&b'<') => {
+                self.pos += 2;
+                self.nested(Self::dict)
+            }
+            b'<' => {
+                self.pos += 1;
+                Some(Obj::Str(self.hex_string()))
+            }
+            b'[' => {
+                self.pos += 1;
+                self.nested(Self::array)
+            }
+            b']' | b'>' | b')' | b'{' | b'}' => {
+                self.pos += 1;
+                Some(Obj::Op((b as char).to_string()))
+            }
+            b'0'..=b'9' | b'+' | b'-' | b'.' => Some(self.number()),
+            _ => {
+                let token = self.regular_token();
+                if token.is_empty() {
+                    self.pos += 1;
+                    return Some(Obj::Op(String::new()));
+                }
+                Some(match token {
+                    b"true" => Obj::Bool(true),
+                    b"false" => Obj::Bool(false),
+                    b"null" => Obj::Null,
+                    _ => Obj::Op(String::from_utf8_lossy(token).into_owned()),
+                })
+            }
+        }
+    }
+
+    /// Parse an array or dictionary, giving up past `MAX_NESTING` levels.
+    fn nested(&mut self, parse: fn(&mut Self) -> Option<Obj>) -> Option<Obj> {
+        if self.nesting >= MAX_NESTING {
+            return None;
+        }
+        self.nesting += 1;
+        let obj = parse(self);
+        self.nesting -= 1;
+        obj
+    }
+
+    fn array(&mut self) -> Option<Obj> {
+        let mut items = Vec::new();
+        loop {
+            self.skip_ws();
+            if self.peek()? == b']' {
+                self.pos += 1;
+                break;
+            }
+            items.push(self.next()?);
+        }
+        Some(Obj::Array(items))
+    }
+
+    /// A number, or an indirect reference (`12 0 R`).
+    fn number(&mut self) -> Obj {
+        let token = self.regular_token();
+        let text = std::str::from_utf8(token).unwrap_or_default();
+        let Ok(n) = text.parse::<i64>() else {
+            return Obj::Real(text.parse().unwrap_or_default());
+        };
+        if let Ok(num) = u32::try_from(n) {
+            let save = self.pos;
+            self.skip_ws();
+            let generation = self.regular_token();
+            if !generation.is_empty() && generation.iter().all(u8::is_ascii_digit) {
+                self.skip_ws();
+                if self.regular_token() == b"R" {
+                    return Obj::Ref(num);
+                }
+            }
+            self.pos = save;
+        }
+        Obj::Int(n)
+    }
+
+    fn literal_string(&mut self) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut depth = 0;
+        while let Some(b) = self.peek() {
+            self.pos += 1;
+            match b {
+                b'(' => {
+                    depth += 1;
+                    out.push(b);
+                }
+                b')' if depth == 0 => break,
+                b')' => {
+                    depth -= 1;
+                    out.push(b);
+                }
+                b'\\' => {
+                    let Some(escaped) = self.peek() else { break };
+                    self.pos += 1;
+                    match escaped {
+                        b'n' => out.push(b'\n'),
+                        b'r' => out.push(b'\r'),
+                        b't' => out.push(b'\t'),
+                        b'b' => out.push(8),
+                        b'f' => out.push(12),
+                        b'0'..=b'7' => {
+                            let mut value = u32::from(escaped - b'0');
+                            for _ in 0..2 {
+                                match self.peek() {
+                                    Some(d @ b'0'..=b'7') => {
+                                        value = value * 8 + u32::from(d - b'0');
+                                        self.pos += 1;
+                                    }
+                                    _ => break,
+                                }
+                            }
+                            out.push(value as u8);
+                        }
+                        // Line continuation.
+                        b'\r' => {
+                            if self.peek() == Some(b'\n') {
+                                self.pos += 1;
+                            }
+                        }
+                        b'\n' => {}
+                        other => out.push(other),
+                    }
+                }
+                _ => out.push(b),
+            }
+        }
+        out
+    }
+
+    fn hex_string(&mut self) -> Vec<u8> {
+        let mut digits = Vec::new();
+        while let Some(b) = self.peek() {
+            self.pos += 1;
+            if b == b'>' {
+                break;
+            }
+            if let Some(d) = (b as char).to_digit(16) {
+                digits.push(d as u8);
+            }
+        }
+        if digits.len() % 2 == 1 {
+            digits.push(0);
+        }
+        digits
+            .chunks(2)
+            .map(|pair| pair[0] << 4 | pair[1])
+            .collect()
+    }
+
+    fn dict(&mut self) -> Option<Obj> {
+        let mut dict = Dict::new();
+        loop {
+            self.skip_ws();
+            match self.peek()? {
+                b'>' => {
+                    self.pos = (self.pos + 2).min(self.data.len());
+                    break;
+                }
+                b'/' => {
+                    self.pos += 1;
+                    let key = decode_name(self.regular_token());
+                    let value = self.next()?;
+                    dict.insert(key, value);
+                }
+                _ => {
+                    self.next()?;
+                }
+            }
+        }
+
+        let after_dict = self.pos;
+        self.skip_ws();
+        if !self.data[self.pos.min(self.data.len())..].starts_with(b"stream") {
+            self.pos = after_dict;
+            return Some(Obj::Dict(dict));
+        }
+
+        let mut start = self.pos + 6;
+        if self.data.get(start) == Some(&b'\r') {
+            start += 1;
+        }
+        if self.data.get(start) == Some(&b'\n') {
+            start += 1;
+        }
+        // Trust /Length when it's direct and lands on `endstream`; otherwise
+        // (indirect or wrong lengths) search for the keyword.
+        let declared_end = match dict.get("Length") {
+            Some(Obj::Int(n)) => usize::try_from(*n)
+                .ok()
+                .map(|n| start.saturating_add(n))
+                .filter(|&end| {
+                    end <= self.data.len() && {
+                        let mut lexer = Lexer::new(self.data, end);
+                        lexer.skip_ws();
+                        self.data[lexer.pos..].starts_with(b"endstream")
+                    }
+                }),
+            _ => None,
+        };
+        let end = match declared_end {
+            Some(end) => end,
+            None => {
+                let mut end = start + find(&self.data[start.min(self.data.len())..], b"endstream")?;
+                if end > start && self.data[end - 1] == b'\n' {
+                    end -= 1;
+                }
+                if end > start && self.data[end - 1] == b'\r' {
+                    end -= 1;
+                }
+                end
+            }
+        };
+        let raw = self.data[start..end].to_vec();
+        self.pos = end + find(&self.data[end..], b"endstream").map_or(0, |i| i + 9);
+        Some(Obj::Stream(dict, raw))
+    }
+
+    /// Skip inline image data after an `ID` operator, up to its `EI`.
+    fn skip_inline_image(&mut self) {
+        let mut i = self.pos + 1;
+        while i + 2 <= self.data.len() {
+            if &self.data[i..i + 2] == b"EI"
+                && is_whitespace(self.data[i - 1])
+                && !matches!(self.data.get(i + 2), Some(&b) if !is_whitespace(b))
+            {
+                self.pos = i + 2;
+                return;
+            }
+            i += 1;
+        }
+        self.pos = self.data.len();
+    }
+}
+
+fn decode_name(raw: &[u8]) -> String {
+    let mut out = Vec::with_capacity(raw.len());
+    let mut i = 0;
+    while i < raw.len() {
+        if raw[i] == b'#' {
+            if let Some(byte) = raw
+                .get(i + 1..i + 3)
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                out.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(raw[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Object number of an `N G obj` header ending just before `at`.
+fn object_number_before(data: &[u8], at: usize) -> Option<u32> {
+    let skip_ws_back = |i: &mut usize| {
+        let start = *i;
+        while *i > 0 && is_whitespace(data[*i - 1]) {
+            *i -= 1;
+        }
+        *i < start
+    };
+    let digits_back = |i: &mut usize| {
+        let end = *i;
+        while *i > 0 && data[*i - 1].is_ascii_digit() {
+            *i -= 1;
+        }
+        *i < end
+    };
+
+    let mut i = at;
+    if !skip_ws_back(&mut i) || !digits_back(&mut i) || !skip_ws_back(&mut i) {
+        return None;
+    }
+    let num_end = i;
+    if !digits_back(&mut i) {
+        return None;
+    }
+    if i > 0 && !is_whitespace(data[i - 1]) && !is_delimiter(data[i - 1]) {
+        return None;
+    }
+    std::str::from_utf8(&data[i..num_end]).ok()?.parse().ok()
+}
+
+fn inflate(data: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    let mut decoder = flate2::read::ZlibDecoder::new(data).take(MAX_INFLATED_BYTES);
+    // Keep whatever decoded before an error; truncated streams are common.
+    match decoder.read_to_end(&mut out) {
+        Ok(_) => Some(out),
+        Err(_) if !out.is_empty() => Some(out),
+        Err(_) => None,
+    }
+}
+
+/// Font state needed to turn shown strings into text.
+#[derive(Debug)]
+struct Font {
+    code_bytes: usize,
+    to_unicode: HashMap<u32, String>,
+}
+
+impl Default for Font {
+    fn default() -> Self {
+        Self {
+            code_bytes: 1,
+            to_unicode: HashMap::new(),
+        }
+    }
+}
+
+impl Font {
+    fn decode(&self, bytes: &[u8], out: &mut String) {
+        for chunk in bytes.chunks(self.code_bytes) {
+            match self.to_unicode.get(&code_value(chunk)) {
+                Some(text) => out.push_str(text),
+                None if self.code_bytes == 1 => out.push(win_ansi_char(chunk[0])),
+                // Multi-byte codes without a map carry no recoverable text.
+                None => {}
+            }
+        }
+    }
+}
+
+fn code_value(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0, |acc, &b| acc << 8 | u32::from(b))
+}
+
+fn utf16_units(bytes: &[u8]) -> Vec<u16> {
+    bytes
+        .chunks_exact(2)
+        .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+        .collect()
+}
+
+/// Approximate WinAnsiEncoding, the usual encoding of simple fonts.
+fn win_ansi_char(b: u8) -> char {
+    match b {
+        0x80 => '€',
+        0x85 => '…',
+        0x91 => '\u{2018}',
+        0x92 => '\u{2019}',
+        0x93 => '\u{201C}',
+        0x94 => '\u{201D}',
+        0x95 => '•',
+        0x96 => '–',
+        0x97 => '—',
+        0x00..=0x1f => ' ',
+        _ => char::from(b),
+    }
+}
+
+/// Read code space and `bfchar`/`bfrange` mappings from a ToUnicode CMap.
+fn parse_cmap(data: &[u8], font: &mut Font) {
+    let mut lexer = Lexer::new(data, 0);
+    let mut operands: Vec<Obj> = Vec::new();
+    while let Some(obj) = lexer.next() {
+        let Obj::Op(op) = obj else {
+            operands.push(obj);
+            continue;
+        };
+        match op.as_str() {
+            "endcodespacerange" => {
+                if let Some(Obj::Str(low)) = operands.first() {
+                    font.code_bytes = low.len().clamp(1, 4);
+                }
+            }
+            "endbfchar" => {
+                for pair in operands.chunks(2) {
+                    if let [Obj::Str(src), Obj::Str(dst)] = pair {
+                        font.to_unicode
+                            .insert(code_value(src), String::from_utf16_lossy(&utf16_units(dst)));
+                    }
+                }
+            }
+            "endbfrange" => {
+                for triple in operands.chunks(3) {
+                    let [Obj::Str(low), Obj::Str(high), dst] = triple else {
+                        continue;
+                    };
+                    let (low, high) = (code_value(low), code_value(high));
+                    if high < low || high - low > 0xFFFF {
+                        continue;
+                    }
+                    match dst {
+                        Obj::Str(base) => {
+                            let base = utf16_units(base);
+                            for (offset, code) in (low..=high).enumerate() {
+                                let mut units = base.clone();
+                                if let Some(last) = units.last_mut() {
+                                    *last = last.wrapping_add(offset as u16);
+                                }
+                                font.to_unicode
+                                    .insert(code, String::from_utf16_lossy(&units));
+                            }
+                        }
+                        Obj::Array(items) => {
+                            for (code, item) in (low..=high).zip(items) {
+                                if let Obj::Str(s) = item {
+                                    font.to_unicode
+                                        .insert(code, String::from_utf16_lossy(&utf16_units(s)));
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+        operands.clear();
+    }
+}
+
+fn new_line(out: &mut String) {
+    let trimmed = out.trim_end_matches(' ').len();
+    out.truncate(trimmed);
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+}
+
+fn space(out: &mut String) {
+    if !out.is_empty() && !out.ends_with(char::is_whitespace) {
+        out.push(' ');
+    }
+}
+
+struct Page<'a> {
+    dict: &'a Dict,
+    resources: Option<&'a Dict>,
+}
+
+/// A parsed PDF file.
+struct Document {
+    objects: HashMap<u32, Obj>,
+}
+
+impl Document {
+    fn parse(data: &[u8]) -> anyhow::Result<Self> {
+        if find(&data[..data.len().min(1024)], b"%PDF").is_none() {
+            return Err(anyhow::anyhow!("Not a PDF file (missing %PDF header)"));
+        }
+        if find(data, b"/Encrypt").is_some() {
+            return Err(anyhow::anyhow!("Encrypted PDFs are not supported"));
+        }
+
+        // Scan for `N G obj` headers instead of trusting the xref table, which
+        // is frequently broken and may itself be compressed.
+        let mut objects = HashMap::new();
+        let mut pos = 0;
+        while let Some(i) = find(&data[pos..], b"obj") {
+            let at = pos + i;
+            pos = at + 3;
+            if data
+                .get(pos)
+                .is_some_and(|&b| !is_whitespace(b) && !is_delimiter(b))
+            {
+                continue;
+            }
+            let Some(num) = object_number_before(data, at) else {
+                continue;
+            };
+            let mut lexer = Lexer::new(data, pos);
+            if let Some(obj) = lexer.next() {
+                objects.insert(num, obj);
+                pos = lexer.pos;
+            }
+        }
+
+        let object_streams: Vec<(Dict, Vec<u8>)> = objects
+            .values()
+            .filter_map(|obj| match obj {
+                Obj::Stream(dict, raw)
+                    if dict.get("Type").and_then(Obj::as_name) == Some("ObjStm") =>
+                {
+                    Some((dict.clone(), raw.clone()))
+                }
+                _ => None,
+            })
+            .collect();
+        let mut doc = Document { objects };
+        for (dict, raw) in object_streams {
+            doc.load_object_stream(&dict, &raw);
+        }
+        Ok(doc)
+    }
+
+    fn load_object_stream(&mut self, dict: &Dict, raw: &[u8]) {
+        let Some(data) = self.decode_stream(dict, raw) else {
+            return;
+        };
+        let count = self.get(dict, "N").as_f64().unwrap_or_default() as usize;
+        let first = self.get(dict, "First").as_f64().unwrap_or_default() as usize;
+        let mut header = Lexer::new(&data, 0);
+        let mut entries = Vec::new();
+        for _ in 0..count {
+            match (header.next(), header.next()) {
+                (Some(Obj::Int(num)), Some(Obj::Int(offset))) => entries.push((num, offset)),
+                _ => break,
+            }
+        }
+        for (num, offset) in entries {
+            let (Ok(num), Ok(offset)) = (u32::try_from(num), usize::try_from(offset)) else {
+                continue;
+            };
+            let start = first.saturating_add(offset);
+            if self.objects.contains_key(&num) || start >= data.len() {
+                continue;
+            }
+            if let Some(obj) = Lexer::new(&data, start).next() {
+                self.objects.insert(num, obj);
+            }
+        }
+    }
+
+    fn resolve<'a>(&'a self, mut obj: &'a Obj) -> &'a Obj {
+        for _ in 0..MAX_DEPTH {
+            match obj {
+                Obj::Ref(num) => obj = self.objects.get(num).unwrap_or(&NULL),
+                _ => return obj,
+            }
+        }
+        &NULL
+    }
+
+    fn get<'a>(&'a self, dict: &'a Dict, key: &str) -> &'a Obj {
+        dict.get(key).map_or(&NULL, |obj| self.resolve(obj))
+    }
+
+    fn decode_stream(&self, dict: &Dict, raw: &[u8]) -> Option<Vec<u8>> {
+        let filters: Vec<&str> = match self.get(dict, "Filter") {
+            Obj::Name(name) => vec![name.as_str()],
+            Obj::Array(items) => items
+                .iter()
+                .filter_map(|item| self.resolve(item).as_name())
+                .collect(),
+            _ => Vec::new(),
+        };
+        let mut data = raw.to_vec();
+        for filter in filters {
+            data = match filter {
+                "FlateDecode" | "Fl" => inflate(&data)?,
+                "ASCIIHexDecode" | "AHx" => Lexer::new(&data, 0).hex_string(),
+                _ => return None,
+            };
+        }
+        Some(data)
+    }
+
+    fn pages(&self) -> Vec<Page<'_>> {
+        let mut pages = Vec::new();
+        let catalog = self
+            .objects
+            .iter()
+            .filter(|(_, obj)| {
+                obj.as_dict()
+                    .and_then(|d| d.get("Type"))
+                    .and_then(Obj::as_name)
+                    == Some("Catalog")
+            })
+            .max_by_key(|(num, _)| **num)
+            .and_then(|(_, obj)| obj.as_dict());
+        if let Some(catalog) = catalog {
+            self.collect_pages(
+                self.get(catalog, "Pages"),
+                None,
+                0,
+                &mut HashSet::new(),
+                &mut pages,
+            );
+        }
+        if pages.is_empty() {
+            // No usable page tree: fall back to page objects in file order.
+            let mut nums: Vec<u32> = self
+                .objects
+                .iter()
+                .filter(|(_, obj)| {
+                    obj.as_dict()
+                        .and_then(|d| d.get("Type"))
+                        .and_then(Obj::as_name)
+                        == Some("Page")
+                })
+                .map(|(num, _)| *num)
+                .collect();
+            nums.sort_unstable();
+            for num in nums {
+                if let Some(dict) = self.objects[&num].as_dict() {
+                    pages.push(Page {
+                        dict,
+                        resources: self.get(dict, "Resources").as_dict(),
+                    });
+                }
+            }
+        }
+        pages
+    }
+
+    fn collect_pages<'a>(
+        &'a self,
+        node: &'a Obj,
+        inherited: Option<&'a Dict>,
+        depth: usize,
+        seen: &mut HashSet<*const Dict>,
+        pages: &mut Vec<Page<'a>>,
+    ) {
+        let Some(dict) = node.as_dict() else {
+            return;
+        };
+        // A node listed twice (or among its own kids) would be walked again
+        // and again.
+        if depth > MAX_DEPTH || !seen.insert(dict) {
+            return;
+        }
+        let resources = self.get(dict, "Resources").as_dict().or(inherited);
+        match self.get(dict, "Kids") {
+            Obj::Array(kids) => {
+                for kid in kids {
+                    self.collect_pages(self.resolve(kid), resources, depth + 1, seen, pages);
+                }
+            }
+            _ => pages.push(Page { dict, resources }),
+        }
+    }
+
+    fn page_content(&self, page: &Dict) -> Vec<u8> {
+        let streams: Vec<&Obj> = match self.get(page, "Contents") {
+            Obj::Array(items) => items.iter().map(|item| self.resolve(item)).collect(),
+            other => vec![other],
+        };
+        let mut content = Vec::new();
+        for stream in streams {
+            if let Obj::Stream(dict, raw) = stream {
+                if let Some(data) = self.decode_stream(dict, raw) {
+                    content.extend(data);
+                    content.push(b'\n');
+                }
+            }
+        }
+        content
+    }
+
+    fn load_fonts(&self, resources: Option<&Dict>) -> HashMap<String, Font> {
+        let Some(fonts) = resources.and_then(|r| self.get(r, "Font").as_dict()) else {
+            return HashMap::new();
+        };
+        fonts
+            .iter()
+            .filter_map(|(name, font)| {
+                let dict = self.resolve(font).as_dict()?;
+                let mut font = Font::default();
+                if self.get(dict, "Subtype").as_name() == Some("Type0") {
+                    font.code_bytes = 2;
+                }
+                if let Obj::Stream(cmap_dict, raw) = self.get(dict, "ToUnicode") {
+                    if let Some(cmap) = self.decode_stream(cmap_dict, raw) {
+                        parse_cmap(&cmap, &mut font);
+                    }
+                }
+                Some((name.clone(), font))
+            })
+            .collect()
+    }
+
+    fn extract_text(
+        &self,
+        content: &[u8],
+        resources: Option<&Dict>,
+        open_forms: &mut Vec<*const Dict>,
+        out: &mut String,
+    ) {
+        let fonts = self.load_fonts(resources);
+        let fallback = Font::default();
+        let mut font = &fallback;
+        let mut line_y: Option<f64> = None;
+        let mut operands: Vec<Obj> = Vec::new();
+        let mut lexer = Lexer::new(content, 0);
+        while let Some(obj) = lexer.next() {
+            let Obj::Op(op) = obj else {
+                operands.push(obj);
+                continue;
+            };
+            match op.as_str() {
+                "Tf" => {
+                    if let Some(name) = operands.first().and_then(Obj::as_name) {
+                        font = fonts.get(name).unwrap_or(&fallback);
+                    }
+                }
+                "Td" | "TD" => {
+                    let tx = operands.first().and_then(Obj::as_f64).unwrap_or_default();
+                    let ty = operands.get(1).and_then(Obj::as_f64).unwrap_or_default();
+                    if ty.abs() > 0.01 {
+                        new_line(out);
+                    } else if tx > 0.0 {
+                        space(out);
+                    }
+                }
+                "Tm" => {
+                    let y = operands.get(5).and_then(Obj::as_f64);
+                    if let (Some(y), Some(previous)) = (y, line_y) {
+                        if (y - previous).abs() > 0.01 {
+                            new_line(out);
+                        } else {
+                            space(out);
+                        }
+                    }
+                    line_y = y;
+                }
+                "T*" => new_line(out),
+                "Tj" => {
+                    if let Some(Obj::Str(s)) = operands.last() {
+                        font.decode(s, out);
+                    }
+                }
+                "'" | "\"" => {
+                    new_line(out);
+                    if let Some(Obj::Str(s)) = operands.last() {
+                        font.decode(s, out);
+                    }
+                }
+                "TJ" => {
+                    if let Some(Obj::Array(items)) = operands.last() {
+                        for item in items {
+                            match item {
+                                Obj::Str(s) => font.decode(s, out),
+                                // Large negative adjustments are word gaps.
+                                other if other.as_f64().is_some_and(|n| n < -200.0) => space(out),
+                                _ => {}
+                            }
+                        }
+                    }
+                }
+                "Do" if open_forms.len() < MAX_DEPTH => {
+                    let xobject = operands.first().and_then(Obj::as_name).and_then(|name| {
+                        let xobjects = resources.and_then(|r| self.get(r, "XObject").as_dict())?;
+                        Some(self.get(xobjects, name))
+                    });
+                    if let Some(Obj::Stream(dict, raw)) = xobject {
+                        // Skip a form that (indirectly) draws itself.
+                        if self.get(dict, "Subtype").as_name() == Some("Form")
+                            && !open_forms.contains(&(dict as *const Dict))
+                        {
+                            if let Some(data) = self.decode_stream(dict, raw) {
+                                let form_resources =
+                                    self.get(dict, "Resources").as_dict().or(resources);
+                                open_forms.push(dict);
+                                self.extract_text(&data, form_resources, open_forms, out);
+                                open_forms.pop();
+                            }
+                        }
+                    }
+                }
+                "ID" => lexer.skip_inline_image(),
+                _ => {}
+            }
+            operands.clear();
+        }
+    }
+
+    fn page_text(&self, page: &Page<'_>) -> String {
+        let mut out = String::new();
+        self.extract_text(
+            &self.page_content(page.dict),
+            page.resources,
+            &mut Vec::new(),
+            &mut out,
+        );
+        out.lines()
+            .map(str::trim_end)
+            .collect::<Vec<_>>()
+            .join("\n")
+            .trim()
+            .to_string()
+    }
+
+    /// Embedded JPEG / JPEG 2000 images drawn on a page, plus how many other
+    /// images were skipped (those would need re-encoding).
+    fn page_images<'a>(&'a self, page: &Page<'a>) -> (Vec<(&'static str, &'a [u8])>, usize) {
+        let Some(xobjects) = page
+            .resources
+            .and_then(|r| self.get(r, "XObject").as_dict())
+        else {
+            return (Vec::new(), 0);
+        };
+        let mut names: Vec<&String> = xobjects.keys().collect();
+        names.sort();
+        let mut images = Vec::new();
+        let mut skipped = 0;
+        for name in names {
+            let Obj::Stream(dict, raw) = self.get(xobjects, name) else {
+                continue;
+            };
+            if self.get(dict, "Subtype").as_name() != Some("Image") {
+                continue;
+            }
+            let filter = match self.get(dict, "Filter") {
+                Obj::Name(name) => Some(name.as_str()),
+                Obj::Array(items) if items.len() == 1 => self.resolve(&items[0]).as_name(),
+                _ => None,
+            };
+            match filter {
+                Some("DCTDecode") => images.push(("jpg", raw.as_slice())),
+                Some("JPXDecode") => images.push(("jp2", raw.as_slice())),
+                _ => skipped += 1,
+            }
+        }
+        (images, skipped)
+    }
+}
+
+/// Parse a page selection like "1-3,7,10-" into sorted page numbers.
+fn parse_page_ranges(spec: &str, page_count: usize) -> anyhow::Result<Vec<usize>> {
+    let invalid = || {
+        anyhow::anyhow!(
+            "Invalid page range '{}': use e.g. '1-3,7' (the document has {} page{})",
+            spec,
+            page_count,
+            if page_count == 1 { "" } else { "s" }
+        )
+    };
+    let number = |s: &str, default: usize| -> anyhow::Result<usize> {
+        let s = s.trim();
+        if s.is_empty() {
+            Ok(default)
+        } else {
+            s.parse().map_err(|_| invalid())
+        }
+    };
+
+    let mut pages = BTreeSet::new();
+    for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (start, end) = match part.split_once('-') {
+            Some((start, end)) => (number(start, 1)?, number(end, page_count)?),
+            None => {
+                let page = number(part, 0)?;
+                (page, page)
+            }
+        };
+        if start == 0 || start > end || end > page_count {
+            return Err(invalid());
+        }
+        pages.extend(start..=end);
+    }
+    if pages.is_empty() {
+        return Err(invalid());
+    }
+    Ok(pages.into_iter().collect())
+}
+
+/// Format sorted page numbers back into a compact selection ("1-3,7").
+fn format_page_ranges(pages: &[usize]) -> String {
+    let mut ranges: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < pages.len() {
+        let start = pages[i];
+        while i + 1 < pages.len() && pages[i + 1] == pages[i] + 1 {
+            i += 1;
+        }
+        ranges.push(if pages[i] == start {
+            start.to_string()
+        } else {
+            format!("{}-{}", start, pages[i])
+        });
+        i += 1;
+    }
+    ranges.join(",")
+}
+
+/// Text and images for the selected pages of a document.
+struct Extraction {
+    page_count: usize,
+    pages: Vec<(usize, String)>,
+    images: Vec<(usize, &'static str, Vec<u8>)>,
+    skipped_images: usize,
+}
+
+fn extract(data: &[u8], pages: Option<&str>, with_images: bool) -> anyhow::Result<Extraction> {
+    let doc = Document::parse(data)?;
+    let all_pages = doc.pages();
+    if all_pages.is_empty() {
+        return Err(anyhow::anyhow!("No pages found in PDF"));
+    }
+    let selected = match pages {
+        Some(spec) => parse_page_ranges(spec, all_pages.len())?,
+        None => (1..=all_pages.len()).collect(),
+    };
+
+    let mut extraction = Extraction {
+        page_count: all_pages.len(),
+        pages: Vec::new(),
+        images: Vec::new(),
+        skipped_images: 0,
+    };
+    for number in selected {
+        let page = &all_pages[number - 1];
+        extraction.pages.push((number, doc.page_text(page)));
+        if with_images {
+            let (images, skipped) = doc.page_images(page);
+            extraction.skipped_images += skipped;
+            extraction.images.extend(
+                images
+                    .into_iter()
+                    .map(|(ext, bytes)| (number, ext, bytes.to_vec())),
+            );
+        }
+    }
+    Ok(extraction)
+}
+
+/// Extract text (and optionally images) from a PDF file.
+pub struct PdfExtract;
+
+#[async_trait]
+impl Tool for PdfExtract {
+    fn name(&self) -> &str {
+        "pdf_extract"
+    }
+
+    fn description(&self) -> &str {
+        "Extract text from a PDF file, page by page, without external tools. Select pages with 'pages' (e.g. '1-5,9') and continue long documents with the page range suggested at the end of the output. Set extract_images to save embedded JPEG images (e.g. scanned pages) into a directory."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "PDF file. Use relative paths (e.g., 'docs/paper.pdf') for workspace files."
+                },
+                "pages": {
+                    "type": "string",
+                    "description": "Optional: pages to extract, 1-based, e.g. '1-3,7' or '10-' (default: all)"
+                },
+                "max_chars": {
+                    "type": "integer",
+                    "description": "Optional: maximum characters of text to return (default: 20000)"
+                },
+                "extract_images": {
+                    "type": "boolean",
+                    "description": "Optional: save embedded JPEG/JPEG 2000 images from the selected pages (default: false)"
+                },
+                "images_dir": {
+                    "type": "string",
+                    "description": "Optional: directory for extracted images (default: '<pdf name>_images' next to the PDF)"
+                }
+            },
+            "required": ["path"]
+        })
+    }
+
+    async fn execute(&self, args: Value, working_dir: &Path) -> anyhow::Result<String> {
+        let path = args["path"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing 'path' argument"))?;
+        let pages = args["pages"].as_str().map(str::to_string);
+        let max_chars = args["max_chars"]
+            .as_u64()
+            .map(|n| n as usize)
+            .unwrap_or(DEFAULT_MAX_CHARS)
+            .max(1);
+        let with_images = args["extract_images"].as_bool().unwrap_or(false);
+
+        let resolution = resolve_path(path, working_dir);
+        let pdf_path = resolution.resolved;
+        let metadata = tokio::fs::metadata(&pdf_path).await.map_err(|_| {
+            anyhow::anyhow!(
+                "File not found: {} (resolved to: {})",
+                path,
+                pdf_path.display()
+            )
+        })?;
+        if metadata.len() > MAX_PDF_BYTES {
+            return Err(anyhow::anyhow!(
+                "PDF is too large ({} bytes, max {})",
+                metadata.len(),
+                MAX_PDF_BYTES
+            ));
+        }
+
+        let data = tokio::fs::read(&pdf_path).await?;
+        let extraction =
+            tokio::task::spawn_blocking(move || extract(&data, pages.as_deref(), with_images))
+                .await??;
+
+        let mut output = format!(
+            "{} ({} page{})\n",
+            path,
+            extraction.page_count,
+            if extraction.page_count == 1 { "" } else { "s" }
+        );
+        let mut remaining: Vec<usize> = Vec::new();
+        for (index, (number, text)) in extraction.pages.iter().enumerate() {
+            let text = if text.is_empty() {
+                "(no extractable text; this page may be a scanned image)"
+            } else {
+                text.as_str()
+            };
+            let section = format!("\n--- Page {} ---\n{}\n", number, text);
+            if output.len() + section.len() <= max_chars {
+                output.push_str(&section);
+                continue;
+            }
+            if index == 0 {
+                // Always show something, even if the first page alone is too long.
+                let budget = max_chars.saturating_sub(output.len());
+                let end = super::safe_truncate_index(&section, budget);
+                output.push_str(&section[..end]);
+                output.push_str(&format!(
+                    "\n[Page {} truncated at {} characters. Raise max_chars to see the whole page.]\n",
+                    number, max_chars
+                ));
+                remaining.extend(extraction.pages[1..].iter().map(|(n, _)| *n));
+            } else {
+                remaining.extend(extraction.pages[index..].iter().map(|(n, _)| *n));
+            }
+            break;
+        }
+        if !remaining.is_empty() {
+            output.push_str(&format!(
+                "\n[Output limited to {} characters. Use pages=\"{}\" to continue reading.]\n",
+                max_chars,
+                format_page_ranges(&remaining)
+            ));
+        }
+
+        if with_images {
+            let images_dir = match args["images_dir"].as_str() {
+                Some(dir) => resolve_path(dir, working_dir).resolved,
+                None => {
+                    let stem = pdf_path
+                        .file_stem()
+                        .map(|s| s.to_string_lossy().to_string())
+                        .unwrap_or_else(|| "pdf".to_string());
+                    pdf_path
+                        .parent()
+                        .unwrap_or(working_dir)
+                        .join(format!("{}_images", stem))
+                }
+            };
+            let mut written = Vec::new();
+            let mut per_page: HashMap<usize, usize> = HashMap::new();
+            for (number, ext, bytes) in &extraction.images {
+                let index = per_page.entry(*number).or_default();
+                *index += 1;
+                let name = format!("page-{}-{}.{}", number, index, ext);
+                if written.is_empty() {
+                    tokio::fs::create_dir_all(&images_dir).await?;
+                }
+                tokio::fs::write(images_dir.join(&name), bytes).await?;
+                written.push(name);
+            }
+            if written.is_empty() {
+                output.push_str("\nNo embedded JPEG images found on the selected pages.");
+            } else {
+                output.push_str(&format!(
+                    "\nExtracted {} image{} to {}:\n{}",
+                    written.len(),
+                    if written.len() == 1 { "" } else { "s" },
+                    images_dir.display(),
+                    written.join("\n")
+                ));
+            }
+            if extraction.skipped_images > 0 {
+                output.push_str(&format!(
+                    "\n({} image{} in other formats skipped)",
+                    extraction.skipped_images,
+                    if extraction.skipped_images == 1 {
+                        ""
+                    } else {
+                        "s"
+                    }
+                ));
+            }
+        }
+
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    /// Build a two-page PDF: a simple font on page 1 and a compressed page 2
+    /// using a Type0 font with a ToUnicode map.
+    fn sample_pdf() -> Vec<u8> {
+        let mut encoder =
+            flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(b"BT /F2 12 Tf <00010002> Tj ET").unwrap();
+        let page2 = encoder.finish().unwrap();
+        let cmap = b"begincmap 1 begincodespacerange <0000> <FFFF> endcodespacerange \
+            2 beginbfchar <0001> <0048> <0002> <0069> endbfchar endcmap";
+        let page1 = b"BT /F1 12 Tf 72 720 Td (Hello, \\(PDF\\)) Tj 0 -14 Td [(Sec) 20 (ond) -300 (line)] TJ ET";
+
+        let mut pdf = b"%PDF-1.5\n".to_vec();
+        pdf.extend(b"1 0 obj << /Type /Catalog /Pages 2 0 R >> endobj\n");
+        pdf.extend(b"2 0 obj << /Type /Pages /Kids [3 0 R 5 0 R] /Count 2 /Resources << /Font << /F1 7 0 R >> >> >> endobj\n");
+        pdf.extend(b"3 0 obj << /Type /Page /Parent 2 0 R /Contents 4 0 R >> endobj\n");
+        pdf.extend(format!("4 0 obj << /Length {} >>\nstream\n", page1.len()).as_bytes());
+        pdf.extend(page1);
+        pdf.extend(b"\nendstream endobj\n");
+        pdf.extend(b"5 0 obj << /Type /Page /Parent 2 0 R /Contents 6 0 R /Resources << /Font << /F2 8 0 R >> >> >> endobj\n");
+        pdf.extend(b"6 0 obj << /Length 9 0 R /Filter /FlateDecode >>\nstream\n");
+        pdf.extend(&page2);
+        pdf.extend(b"\nendstream endobj\n");
+        pdf.extend(b"7 0 obj << /Type /Font /Subtype /Type1 /BaseFont /Helvetica >> endobj\n");
+        pdf.extend(b"8 0 obj << /Type /Font /Subtype /Type0 /ToUnicode 10 0 R >> endobj\n");
+        pdf.extend(format!("9 0 obj {} endobj\n", page2.len()).as_bytes());
+        pdf.extend(format!("10 0 obj << /Length {} >>\nstream\n", cmap.len()).as_bytes());
+        pdf.extend(cmap);
+        pdf.extend(b"\nendstream endobj\ntrailer << /Root 1 0 R >>\n%%EOF\n");
+        pdf
+    }
+
+    #[test]
+    fn test_extract_text_from_pages() {
+        let extraction = extract(&sample_pdf(), None, false).unwrap();
+        assert_eq!(extraction.page_count, 2);
+        assert_eq!(
+            extraction.pages,
+            vec![
+                (1, "Hello, (PDF)\nSecond line".to_string()),
+                (2, "Hi".to_string())
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pdf_extract_page_selection_and_chunking() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("doc.pdf"), sample_pdf()).unwrap();
+
+        let second = PdfExtract
+            .execute(json!({"path": "doc.pdf", "pages": "2-"}), dir.path())
+            .await
+            .unwrap();
+        assert_eq!(second, "doc.pdf (2 pages)\n\n--- Page 2 ---\nHi\n");
+
+        let limited = PdfExtract
+            .execute(json!({"path": "doc.pdf", "max_chars": 60}), dir.path())
+            .await
+            .unwrap();
+        assert!(limited.contains("--- Page 1 ---\nHello, (PDF)"));
+        assert!(!limited.contains("--- Page 2 ---"));
+        assert!(limited.contains("Use pages=\"2\" to continue reading."));
+
+        assert!(PdfExtract
+            .execute(json!({"path": "doc.pdf", "pages": "3"}), dir.path())
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_page_ranges() {
+        assert_eq!(
+            parse_page_ranges("1-3, 7,9-", 10).unwrap(),
+            vec![1, 2, 3, 7, 9, 10]
+        );
+        assert_eq!(parse_page_ranges("-2", 10).unwrap(), vec![1, 2]);
+        assert!(parse_page_ranges("0", 10).is_err());
+        assert!(parse_page_ranges("4-2", 10).is_err());
+        assert_eq!(format_page_ranges(&[1, 2, 3, 7, 9, 10]), "1-3,7,9-10");
+    }
+
+    fn deflate(data: &[u8]) -> Vec<u8> {
+        let mut encoder =
+            flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    /// A stream object body with a direct `/Length`.
+    fn stream(dict: &str, data: &[u8]) -> Vec<u8> {
+        let mut body = format!("<< {} /Length {} >>\nstream\n", dict, data.len()).into_bytes();
+        body.extend(data);
+        body.extend(b"\nendstream");
+        body
+    }
+
+    /// Assemble a PDF from numbered object bodies.
+    fn pdf_from(objects: &[(u32, Vec<u8>)]) -> Vec<u8> {
+        let mut pdf = b"%PDF-1.5\n".to_vec();
+        for (num, body) in objects {
+            pdf.extend(format!("{} 0 obj ", num).as_bytes());
+            pdf.extend(body);
+            pdf.extend(b"\nendobj\n");
+        }
+        pdf.extend(b"trailer << /Root 1 0 R >>\n%%EOF\n");
+        pdf
+    }
+
+    /// A one-page document whose page content is object 4.
+    fn single_page(content: Vec<u8>) -> Vec<u8> {
+        pdf_from(&[
+            (1, b"<< /Type /Catalog /Pages 2 0 R >>".to_vec()),
+            (2, b"<< /Type /Pages /Kids [3 0 R] >>".to_vec()),
+            (3, b"<< /Type /Page /Contents 4 0 R >>".to_vec()),
+            (4, content),
+        ])
+    }
+
+    /// Errors are fine on broken input; panics and hangs are not.
+    fn extract_all(data: &[u8]) {
+        let _ = extract(data, None, false);
+        let _ = extract(data, None, true);
+    }
+
+    #[test]
+    fn test_malformed_input_does_not_panic() {
+        let inputs: Vec<Vec<u8>> = vec![
+            Vec::new(),
+            b"%PDF".to_vec(),
+            b"%PDF-1.4\n1 0 obj <<>".to_vec(),
+            b"%PDF-1.4\n1 0 obj << /Kids [[[ (unterminated \\".to_vec(),
+            b"%PDF-1.4\n1 0 obj << /a 99999999999999999999 0 R /b -.-+ /c <0g1> /d #zz >>".to_vec(),
+            b"%PDF-1.4\n1 0 obj 2 0 R endobj 2 0 obj 1 0 R endobj\n3 0 obj << /Type /Catalog /Pages 1 0 R >> endobj".to_vec(),
+            b"%PDF-1.4\n1 0 obj << /Type /Page /Contents 2 0 R >> endobj\n2 0 obj << /Length 999 >>\nstream\nBT (cut".to_vec(),
+            b"%PDF-1.4\n1 0 obj << /Type /ObjStm /N 99999999 /First 18446744073709551615 /Length 24 >>\nstream\n1 9223372036854775807 2\nendstream endobj".to_vec(),
+            single_page(stream("", b"BT /F9 Tf Tj TJ ' \" Td Tm 1 2 3 4 5 6 Tm ID \x00\xff no end")),
+            single_page(stream("/Filter /ASCIIHexDecode", b"4g 4")),
+            format!("%PDF-1.4\n1 0 obj {}", "[".repeat(100_000)).into_bytes(),
+            format!("%PDF-1.4\n1 0 obj {}", "<< /a ".repeat(100_000)).into_bytes(),
+        ];
+        for input in &inputs {
+            extract_all(input);
+        }
+    }
+
+    #[test]
+    fn test_truncated_input_does_not_panic() {
+        let pdf = sample_pdf();
+        for len in 0..pdf.len() {
+            extract_all(&pdf[..len]);
+        }
+    }
+
+    #[test]
+    fn test_self_referencing_page_tree_and_forms() {
+        let pages = pdf_from(&[
+            (1, b"<< /Type /Catalog /Pages 2 0 R >>".to_vec()),
+            (2, b"<< /Type /Pages /Kids [2 0 R 3 0 R 2 0 R] >>".to_vec()),
+            (3, b"<< /Type /Page /Contents 4 0 R >>".to_vec()),
+            (4, stream("", b"BT (only page) Tj ET")),
+        ]);
+        let extraction = extract(&pages, None, false).unwrap();
+        assert_eq!(extraction.pages, vec![(1, "only page".to_string())]);
+
+        let forms = pdf_from(&[
+            (1, b"<< /Type /Catalog /Pages 2 0 R >>".to_vec()),
+            (2, b"<< /Type /Pages /Kids [3 0 R] >>".to_vec()),
+            (
+                3,
+                b"<< /Type /Page /Contents 4 0 R /Resources << /XObject << /X 5 0 R >> >> >>"
+                    .to_vec(),
+            ),
+            (4, stream("", b"/X Do")),
+            (5, stream("/Subtype /Form", b"BT (loop) Tj ET /X Do /X Do")),
+        ]);
+        let extraction = extract(&forms, None, false).unwrap();
+        assert_eq!(extraction.pages, vec![(1, "loop".to_string())]);
+    }
+
+    #[test]
+    fn test_damaged_compressed_streams() {
+        let content: String = (0..500).map(|i| format!("(Line {}) Tj T* ", i)).collect();
+        let compressed = deflate(format!("BT {}ET", content).as_bytes());
+        let page_text = |data: &[u8]| {
+            let pdf = single_page(stream("/Filter /FlateDecode", data));
+            extract(&pdf, None, false).unwrap().pages[0].1.clone()
+        };
+
+        assert!(page_text(&compressed).ends_with("Line 498\nLine 499"));
+
+        // A stream cut short keeps what decoded before the cut
+        let cut = page_text(&compressed[..compressed.len() / 2]);
+        assert!(cut.starts_with("Line 0\nLine 1\n"), "{}", cut);
+        assert!(!cut.contains("Line 499"));
+
+        assert_eq!(page_text(&b"not zlib data"[..]), "");
+        let mut corrupted = compressed.clone();
+        for b in &mut corrupted[20..60] {
+            *b ^= 0x5a;
+        }
+        extract_all(&single_page(stream("/Filter /FlateDecode", &corrupted)));
+        extract_all(&single_page(stream(
+            "/Filter [/FlateDecode /LZWDecode]",
+            &compressed,
+        )));
+    }
+
+    #[test]
+    fn test_object_streams() {
+        let members: [(u32, &[u8]); 3] = [
+            (1, b"<< /Type /Catalog /Pages 2 0 R >>"),
+            (2, b"<< /Type /Pages /Kids [3 0 R] >>"),
+            (3, b"<< /Type /Page /Contents 4 0 R >>"),
+        ];
+        let mut header = String::new();
+        let mut body = Vec::new();
+        for (num, member) in members {
+            header.push_str(&format!("{} {} ", num, body.len()));
+            body.extend(member);
+            body.push(b' ');
+        }
+        let mut data = header.clone().into_bytes();
+        data.extend(&body);
+        let compressed = deflate(&data);
+        let document = |object_stream: Vec<u8>| {
+            pdf_from(&[
+                (4, stream("", b"BT (From an object stream) Tj ET")),
+                (5, object_stream),
+            ])
+        };
+
+        let dict = format!(
+            "/Type /ObjStm /N 3 /First {} /Filter /FlateDecode",
+            header.len()
+        );
+        let extraction = extract(&document(stream(&dict, &compressed)), None, false).unwrap();
+        assert_eq!(
+            extraction.pages,
+            vec![(1, "From an object stream".to_string())]
+        );
+
+        // Wrong counts and offsets, or a truncated stream: no pages, no panic
+        let bogus = "/Type /ObjStm /N 99 /First 99999 /Filter /FlateDecode";
+        assert!(extract(&document(stream(bogus, &compressed)), None, false).is_err());
+        for len in 0..compressed.len() {
+            extract_all(&document(stream(&dict, &compressed[..len])));
+        }
+    }
+}
@@ -0,0 +1,355 @@
+//! Tool permission policy.
+//!
+//! A `ToolPolicy` is an ordered list of rules mapping tool-name globs to
+//! `allow`, `deny` or `ask`. Rules can be scoped to a mission or a workspace;
+//! the most specific scope with a matching rule wins (mission, then workspace,
+//! then global), and within a scope the first matching rule wins. Tools that
+//! match no rule fall back to `default_action`, except tools that change
+//! external systems (see [`requires_approval`]), which fall back to `ask`
+//! while the default is `allow`.
+//!
+//! `ask` is resolved by a human through the dashboard: the registry emits a
+//! `ui_optionList` tool call and waits on the `FrontendToolHub` for the answer.
+//! The workspace MCP server gets the policy through [`TOOL_POLICY_ENV`] and
+//! asks through `POST /api/control/tool_approval`.
+
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::api::control::{AgentEvent, FrontendToolHub};
+
+/// Env var carrying a workspace's tool allowlist (comma-separated globs) to
+/// the workspace MCP server.
+pub const TOOL_ALLOWLIST_ENV: &str = "SANDBOXED_SH_TOOL_ALLOWLIST";
+
+/// Env var carrying the tool policy (JSON) to the workspace MCP server.
+pub const TOOL_POLICY_ENV: &str = "SANDBOXED_SH_TOOL_POLICY";
+
+/// Env var carrying the workspace id to the workspace MCP server, so
+/// workspace-scoped rules apply there.
+pub const WORKSPACE_ID_ENV: &str = "SANDBOXED_SH_WORKSPACE_ID";
+
+/// Tools that need approval unless a rule explicitly allows them.
+const APPROVAL_REQUIRED: &[&str] = &["k8s_apply"];
+
+/// What to do with a tool call.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PolicyAction {
+    #[default]
+    Allow,
+    Deny,
+    Ask,
+}
+
+/// A single policy rule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PolicyRule {
+    /// Tool name glob (`*` wildcard), e.g. `run_command`, `desktop_*`, `*`.
+    pub tool: String,
+    pub action: PolicyAction,
+    /// Only apply to this mission.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mission_id: Option<Uuid>,
+    /// Only apply to missions running in this workspace.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_id: Option<Uuid>,
+}
+
+/// Configurable ruleset evaluated before every tool execution.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolPolicy {
+    /// Action for tools that match no rule.
+    #[serde(default)]
+    pub default_action: PolicyAction,
+    #[serde(default)]
+    pub rules: Vec<PolicyRule>,
+}
+
+/// Mission/workspace a tool call runs under.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PolicyScope {
+    pub mission_id: Option<Uuid>,
+    pub workspace_id: Option<Uuid>,
+}
+
+impl ToolPolicy {
+    /// Whether this policy has no rules and allows by default.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty() && self.default_action == PolicyAction::Allow
+    }
+
+    /// Decide what to do with a call to `tool` under `scope`.
+    pub fn evaluate(&self, tool: &str, scope: &PolicyScope) -> PolicyAction {
+        let matching = |rule: &&PolicyRule| tool_matches(&rule.tool, tool);
+
+        if let Some(mission_id) = scope.mission_id {
+            if let Some(rule) = self
+                .rules
+                .iter()
+                .filter(|r| r.mission_id == Some(mission_id))
+                .find(matching)
+            {
+                return rule.action;
+            }
+        }
+        if let Some(workspace_id) = scope.workspace_id {
+            if let Some(rule) = self
+                .rules
+                .iter()
+                .filter(|r| r.mission_id.is_none() && r.workspace_id == Some(workspace_id))
+                .find(matching)
+            {
+                return rule.action;
+            }
+        }
+        self.rules
+            .iter()
+            .filter(|r| r.mission_id.is_none() && r.workspace_id.is_none())
+            .find(matching)
+            .map(|r| r.action)
+            .unwrap_or(match self.default_action {
+                PolicyAction::Allow if requires_approval(tool) => PolicyAction::Ask,
+                action => action,
+            })
+    }
+}
+
+/// Whether `tool` asks for approval when no rule matches it.
+pub fn requires_approval(tool: &str) -> bool {
+    APPROVAL_REQUIRED
+        .iter()
+        .any(|gated| tool_matches(gated, tool))
+}
+
+/// Whether `tool` is available under a workspace tool allowlist.
+/// An empty allowlist allows every tool.
+pub fn tool_allowed(allowlist: &[String], tool: &str) -> bool {
+    allowlist.is_empty()
+        || allowlist
+            .iter()
+            .any(|pattern| tool_matches(pattern.trim(), tool))
+}
+
+/// Match a rule glob against a tool name. MCP-qualified names
+/// (`mcp__server__tool`) also match on their bare tool name.
+pub(crate) fn tool_matches(pattern: &str, tool: &str) -> bool {
+    if glob_match(pattern, tool) {
+        return true;
+    }
+    match tool.rsplit_once("__") {
+        Some((_, bare)) => glob_match(pattern, bare),
+        None => false,
+    }
+}
+
+/// Simple glob pattern matching (supports '*' only).
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == text;
+    }
+
+    let mut pos = 0usize;
+    for (i, part) in parts.iter().enumerate() {
+        if part.is_empty() {
+            continue;
+        }
+        match text[pos..].find(part) {
+            Some(idx) => {
+                if i == 0 && idx != 0 {
+                    return false;
+                }
+                pos += idx + part.len();
+            }
+            None => return false,
+        }
+    }
+
+    if !pattern.ends_with('*') && !parts.last().unwrap().is_empty() {
+        return text.ends_with(parts.last().unwrap());
+    }
+
+    true
+}
+
+/// Routes `ask` decisions to the dashboard.
+#[derive(Clone)]
+pub struct PolicyApprover {
+    pub hub: Arc<FrontendToolHub>,
+    pub events: broadcast::Sender<AgentEvent>,
+    pub mission_id: Option<Uuid>,
+}
+
+impl PolicyApprover {
+    /// Ask the user whether `tool` may run with `args`. Resolves to `true`
+    /// only when the user explicitly picks "Allow".
+    pub async fn request(&self, tool: &str, args: &Value) -> anyhow::Result<bool> {
+        let tool_call_id = format!("policy-{}", Uuid::new_v4());
+        let mut preview = serde_json::to_string_pretty(args).unwrap_or_default();
+        if preview.len() > 2000 {
+            preview.truncate(super::safe_truncate_index(&preview, 2000));
+            preview.push_str("\n…");
+        }
+
+        // Unanswered approvals default to denying the call
+        let args = json!({
+            "id": tool_call_id,
+            "title": format!("Allow the agent to run `{}`?", tool),
+            "description": preview,
+            "confirmLabel": "Submit",
+            "options": [
+                { "id": "allow", "label": "Allow" },
+                { "id": "deny", "label": "Deny", "default": true }
+            ]
+        });
+        let rx = self
+            .hub
+            .register(tool_call_id.clone(), "ui_optionList", &args)
+            .await;
+        let _ = self.events.send(AgentEvent::ToolCall {
+            tool_call_id: tool_call_id.clone(),
+            name: "ui_optionList".to_string(),
+            args,
+            mission_id: self.mission_id,
+        });
+
+        let answer = rx
+            .await
+            .map_err(|_| anyhow::anyhow!("Approval channel closed for tool '{}'", tool))?;
+        let _ = self.events.send(AgentEvent::ToolResult {
+            tool_call_id,
+            name: "ui_optionList".to_string(),
+            result: answer.clone(),
+            mission_id: self.mission_id,
+        });
+
+        Ok(match &answer {
+            Value::String(choice) => choice == "allow",
+            Value::Array(choices) => choices.iter().any(|c| c == "allow"),
+            _ => false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(tool: &str, action: PolicyAction) -> PolicyRule {
+        PolicyRule {
+            tool: tool.to_string(),
+            action,
+            mission_id: None,
+            workspace_id: None,
+        }
+    }
+
+    #[test]
+    fn test_global_rules_first_match_wins() {
+        let policy = ToolPolicy {
+            default_action: PolicyAction::Allow,
+            rules: vec![
+                rule("run_command", PolicyAction::Ask),
+                rule("desktop_*", PolicyAction::Deny),
+                rule("*", PolicyAction::Deny),
+            ],
+        };
+        let scope = PolicyScope::default();
+        assert_eq!(policy.evaluate("run_command", &scope), PolicyAction::Ask);
+        assert_eq!(policy.evaluate("desktop_click", &scope), PolicyAction::Deny);
+        assert_eq!(
+            policy.evaluate("mcp__workspace__run_command", &scope),
+            PolicyAction::Ask
+        );
+        assert_eq!(policy.evaluate("read_file", &scope), PolicyAction::Deny);
+    }
+
+    #[test]
+    fn test_scoped_rules_take_precedence() {
+        let mission = Uuid::new_v4();
+        let workspace = Uuid::new_v4();
+        let policy = ToolPolicy {
+            default_action: PolicyAction::Ask,
+            rules: vec![
+                rule("write_file", PolicyAction::Deny),
+                PolicyRule {
+                    workspace_id: Some(workspace),
+                    ..rule("write_*", PolicyAction::Ask)
+                },
+                PolicyRule {
+                    mission_id: Some(mission),
+                    ..rule("write_file", PolicyAction::Allow)
+                },
+            ],
+        };
+
+        let global = PolicyScope::default();
+        let in_workspace = PolicyScope {
+            mission_id: Some(Uuid::new_v4()),
+            workspace_id: Some(workspace),
+        };
+        let in_mission = PolicyScope {
+            mission_id: Some(mission),
+            workspace_id: Some(workspace),
+        };
+        assert_eq!(policy.evaluate("write_file", &global), PolicyAction::Deny);
+        assert_eq!(
+            policy.evaluate("write_file", &in_workspace),
+            PolicyAction::Ask
+        );
+        assert_eq!(
+            policy.evaluate("write_file", &in_mission),
+            PolicyAction::Allow
+        );
+        assert_eq!(policy.evaluate("read_file", &in_mission), PolicyAction::Ask);
+    }
+
+    #[test]
+    fn test_policy_deserializes_with_defaults() {
+        let policy: ToolPolicy =
+            serde_json::from_value(json!({ "rules": [{ "tool": "*", "action": "ask" }] })).unwrap();
+        assert_eq!(policy.default_action, PolicyAction::Allow);
+        assert_eq!(policy.rules, vec![rule("*", PolicyAction::Ask)]);
+        assert!(ToolPolicy::default().is_empty());
+    }
+
+    #[test]
+    fn test_gated_tools_ask_without_a_rule() {
+        let scope = PolicyScope::default();
+        assert_eq!(
+            ToolPolicy::default().evaluate("k8s_apply", &scope),
+            PolicyAction::Ask
+        );
+        assert_eq!(
+            ToolPolicy::default().evaluate("k8s_get", &scope),
+            PolicyAction::Allow
+        );
+
+        let allowed = ToolPolicy {
+            default_action: PolicyAction::Allow,
+            rules: vec![rule("k8s_*", PolicyAction::Allow)],
+        };
+        assert_eq!(allowed.evaluate("k8s_apply", &scope), PolicyAction::Allow);
+        let denied = ToolPolicy {
+            default_action: PolicyAction::Deny,
+            rules: vec![],
+        };
+        assert_eq!(denied.evaluate("k8s_apply", &scope), PolicyAction::Deny);
+    }
+
+    #[test]
+    fn test_tool_allowlist() {
+        let allowlist = vec!["read_file".to_string(), "fetch_*".to_string()];
+        assert!(tool_allowed(&[], "run_command"));
+        assert!(tool_allowed(&allowlist, "read_file"));
+        assert!(tool_allowed(&allowlist, "fetch_url"));
+        assert!(tool_allowed(&allowlist, "mcp__workspace__read_file"));
+        assert!(!tool_allowed(&allowlist, "write_file"));
+        assert!(!tool_allowed(&allowlist, "run_command"));
+    }
+}
@@ -0,0 +1,589 @@
+//! Background process tools: start long-running commands and poll them later.
+//!
+//! `run_command` blocks until the command exits, which doesn't work for dev
+//! servers or long builds. These tools launch the command in its own process
+//! group with output going to a log file, and return immediately; the agent
+//! can then check status, read logs incrementally and stop the process.
+//!
+//! Processes are scoped to the workspace that started them and are tracked in
+//! memory, so they don't survive a restart of the tool server.
+
+use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+use tokio::process::Command;
+
+use super::terminal::{
+    container_cwd, container_root_from_env, nsenter_command, parse_command_options, parse_env,
+    resolve_shell, running_container_leader, validate_command, workspace_env_vars,
+};
+use super::{resolve_path_simple, Tool};
+use crate::process_registry::ProcessGuard;
+
+/// Running background processes allowed per workspace.
+const MAX_RUNNING_PER_WORKSPACE: usize = 10;
+/// Lines returned by `process_logs` when the caller doesn't ask for more.
+const DEFAULT_TAIL_LINES: usize = 100;
+/// Upper bound on characters returned by one `process_logs` call.
+const MAX_LOG_CHARS: usize = 20_000;
+/// How long `process_stop` waits after SIGTERM before killing.
+const DEFAULT_STOP_GRACE_SECS: u64 = 5;
+const MAX_READY_WAIT_SECS: u64 = 300;
+
+static PROCESSES: OnceLock<Mutex<HashMap<String, ManagedProcess>>> = OnceLock::new();
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+fn processes() -> &'static Mutex<HashMap<String, ManagedProcess>> {
+    PROCESSES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProcessState {
+    Running,
+    Exited(Option<i32>),
+    Stopped,
+}
+
+struct ManagedProcess {
+    id: String,
+    command: String,
+    workspace: PathBuf,
+    pid: Option<u32>,
+    started_at: DateTime<Utc>,
+    log_path: PathBuf,
+    state: Arc<Mutex<ProcessState>>,
+    /// Keeps the process in the server's registry so it is reaped with its mission.
+    _guard: ProcessGuard,
+}
+
+impl ManagedProcess {
+    fn state(&self) -> ProcessState {
+        *self.state.lock().unwrap()
+    }
+
+    fn summary(&self) -> String {
+        let state = match self.state() {
+            ProcessState::Running => "running".to_string(),
+            ProcessState::Exited(Some(code)) => format!("exited with code {}", code),
+            ProcessState::Exited(None) => "killed by a signal".to_string(),
+            ProcessState::Stopped => "stopped".to_string(),
+        };
+        let uptime = (Utc::now() - self.started_at).num_seconds();
+        format!(
+            "{} (pid {}): {}, started {}s ago — {}",
+            self.id,
+            self.pid.map_or_else(|| "?".to_string(), |p| p.to_string()),
+            state,
+            uptime,
+            self.command
+        )
+    }
+}
+
+fn log_dir() -> PathBuf {
+    std::env::temp_dir()
+        .join("sandboxed-sh-processes")
+        .join(std::process::id().to_string())
+}
+
+/// Look up a process started from the same workspace.
+fn with_process<T>(
+    id: &str,
+    workspace: &Path,
+    f: impl FnOnce(&ManagedProcess) -> T,
+) -> anyhow::Result<T> {
+    let map = processes().lock().unwrap();
+    match map.get(id) {
+        Some(process) if process.workspace == workspace => Ok(f(process)),
+        _ => Err(anyhow::anyhow!(
+            "No background process '{}' in this workspace; use process_status to list them",
+            id
+        )),
+    }
+}
+
+/// Read the last `lines` lines of a log, or everything after `offset`.
+/// Returns the text and the offset to continue from.
+fn read_log(path: &Path, offset: Option<u64>, lines: usize) -> std::io::Result<(String, u64)> {
+    let mut file = std::fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    let start = match offset {
+        Some(offset) => offset.min(len),
+        // Tail: read a window large enough for the requested lines.
+        None => len.saturating_sub((MAX_LOG_CHARS as u64) * 4),
+    };
+    file.seek(SeekFrom::Start(start))?;
+    let mut bytes = Vec::new();
+    file.take(len - start).read_to_end(&mut bytes)?;
+    let text = String::from_utf8_lossy(&bytes);
+
+    let text = if offset.is_some() {
+        let end = super::safe_truncate_index(&text, MAX_LOG_CHARS);
+        let consumed = start + text[..end].len() as u64;
+        return Ok((text[..end].to_string(), consumed));
+    } else {
+        let all: Vec<&str> = text.lines().collect();
+        all[all.len().saturating_sub(lines)..].join("\n")
+    };
+    let skip = text.len().saturating_sub(MAX_LOG_CHARS);
+    let mut skip_at = skip;
+    while !text.is_char_boundary(skip_at) {
+        skip_at += 1;
+    }
+    Ok((text[skip_at..].to_string(), len))
+}
+
+/// Start a command in the background.
+pub struct ProcessStart;
+
+#[async_trait]
+impl Tool for ProcessStart {
+    fn name(&self) -> &str {
+        "process_start"
+    }
+
+    fn description(&self) -> &str {
+        "Start a long-running command (dev server, watcher, long build) in the background and return immediately with a process id. Output goes to a log you can read with process_logs; check it with process_status and end it with process_stop. Use ready_pattern to wait until the log shows the process is up (e.g. 'Listening on')."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "command": {
+                    "type": "string",
+                    "description": "The shell command to run"
+                },
+                "cwd": {
+                    "type": "string",
+                    "description": "Optional: working directory. Defaults to workspace."
+                },
+                "env": {
+                    "type": "object",
+                    "description": "Environment variables to set for the command.",
+                    "additionalProperties": { "type": "string" }
+                },
+                "ready_pattern": {
+                    "type": "string",
+                    "description": "Optional: wait until this text appears in the output before returning"
+                },
+                "ready_timeout_secs": {
+                    "type": "integer",
+                    "description": "Optional: how long to wait for ready_pattern (default: 30, max: 300)"
+                }
+            },
+            "required": ["command"]
+        })
+    }
+
+    async fn execute(&self, args: Value, working_dir: &Path) -> anyhow::Result<String> {
+        let command = args["command"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing 'command' argument"))?;
+        let container_root = container_root_from_env();
+        if container_root.is_none() {
+            validate_command(command).map_err(|msg| anyhow::anyhow!("{}", msg))?;
+        }
+        let running = processes()
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|p| p.workspace == working_dir && p.state() == ProcessState::Running)
+            .count();
+        if running >= MAX_RUNNING_PER_WORKSPACE {
+            return Err(anyhow::anyhow!(
+                "{} background processes are already running; stop one with process_stop first",
+                running
+            ));
+        }
+
+        let cwd = args["cwd"]
+            .as_str()
+            .map(|p| resolve_path_simple(p, working_dir))
+            .unwrap_or_else(|| working_dir.to_path_buf());
+        let id = format!("p{}", NEXT_ID.fetch_add(1, Ordering::Relaxed));
+        std::fs::create_dir_all(log_dir())?;
+        let log_path = log_dir().join(format!("{}.log", id));
+        let log = std::fs::File::create(&log_path)?;
+
+        let mut cmd = match &container_root {
+            Some(root) => {
+                // Background processes join the running container; starting a
+                // fresh nspawn here would lock the container tree.
+                let machine = std::env::var("SANDBOXED_SH_WORKSPACE_NAME").unwrap_or_default();
+                let options = parse_command_options(&args);
+                let leader = match machine.trim() {
+                    "" => None,
+                    name => running_container_leader(name, &options).await,
+                }
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Background processes need the workspace container to be running"
+                    )
+                })?;
+                let root = root.canonicalize().unwrap_or_else(|_| root.clone());
+                let mut cmd = Command::new("nsenter");
+                cmd.args([
+                    "--target", &leader, "--mount", "--uts", "--ipc", "--net", "--pid", "/bin/sh",
+                    "-lc",
+                ])
+                .arg(nsenter_command(&container_cwd(&root, &cwd), command));
+                cmd
+            }
+            None => {
+                let mut cmd = Command::new(resolve_shell(None, None));
+                cmd.arg("-c").arg(command).current_dir(&cwd);
+                cmd
+            }
+        };
+        cmd.envs(workspace_env_vars())
+            .envs(parse_env(&args))
+            .stdin(Stdio::null())
+            .stdout(log.try_clone()?)
+            .stderr(log);
+        #[cfg(unix)]
+        cmd.process_group(0);
+
+        let mut child = cmd
+            .spawn()
+            .map_err(|e| anyhow::anyhow!("Failed to start command: {}", e))?;
+        let pid = child.id();
+        let mission_id = std::env::var("SANDBOXED_SH_MISSION_ID")
+            .ok()
+            .and_then(|id| uuid::Uuid::parse_str(id.trim()).ok())
+            .unwrap_or_default();
+        let guard = crate::process_registry::track(pid, mission_id, "process_start");
+
+        let state = Arc::new(Mutex::new(ProcessState::Running));
+        let waiter_state = Arc::clone(&state);
+        tokio::spawn(async move {
+            let code = child.wait().await.ok().and_then(|status| status.code());
+            let mut state = waiter_state.lock().unwrap();
+            if *state == ProcessState::Running {
+                *state = ProcessState::Exited(code);
+            }
+        });
+
+        tracing::info!(id = %id, pid = ?pid, "Started background process: {}", command);
+        processes().lock().unwrap().insert(
+            id.clone(),
+            ManagedProcess {
+                id: id.clone(),
+                command: command.to_string(),
+                workspace: working_dir.to_path_buf(),
+                pid,
+                started_at: Utc::now(),
+                log_path: log_path.clone(),
+                state: Arc::clone(&state),
+                _guard: guard,
+            },
+        );
+
+        let mut result = format!(
+            "Started background process {} (pid {}).\nLogs: {}\nUse process_logs / process_status / process_stop with id=\"{}\".",
+            id,
+            pid.map_or_else(|| "?".to_string(), |p| p.to_string()),
+            log_path.display(),
+            id
+        );
+
+        if let Some(pattern) = args["ready_pattern"].as_str() {
+            let wait = args["ready_timeout_secs"]
+                .as_u64()
+                .unwrap_or(30)
+                .min(MAX_READY_WAIT_SECS);
+            let deadline = tokio::time::Instant::now() + Duration::from_secs(wait);
+            let outcome = loop {
+                let log = tokio::fs::read_to_string(&log_path)
+                    .await
+                    .unwrap_or_default();
+                if log.contains(pattern) {
+                    break format!("Ready: output contains '{}'.", pattern);
+                }
+                let current = *state.lock().unwrap();
+                if current != ProcessState::Running {
+                    let (tail, _) = read_log(&log_path, None, 20).unwrap_or_default();
+                    break format!(
+                        "Process exited before '{}' appeared. Last output:\n{}",
+                        pattern, tail
+                    );
+                }
+                if tokio::time::Instant::now() >= deadline {
+                    break format!(
+                        "'{}' did not appear within {}s; the process is still running.",
+                        pattern, wait
+                    );
+                }
+                tokio::time::sleep(Duration::from_millis(250)).await;
+            };
+            result.push_str("\n\n");
+            result.push_str(&outcome);
+        }
+
+        Ok(result)
+    }
+}
+
+/// Show the state of background processes.
+pub struct ProcessStatus;
+
+#[async_trait]
+impl Tool for ProcessStatus {
+    fn name(&self) -> &str {
+        "process_status"
+    }
+
+    fn description(&self) -> &str {
+        "Show whether background processes started with process_start are still running (with exit codes for finished ones). Omit id to list all processes in this workspace."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "id": {
+                    "type": "string",
+                    "description": "Optional: process id from process_start"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: Value, working_dir: &Path) -> anyhow::Result<String> {
+        if let Some(id) = args["id"].as_str() {
+            return with_process(id, working_dir, ManagedProcess::summary);
+        }
+        let map = processes().lock().unwrap();
+        let mut list: Vec<&ManagedProcess> = map
+            .values()
+            .filter(|p| p.workspace == working_dir)
+            .collect();
+        if list.is_empty() {
+            return Ok("No background processes in this workspace.".to_string());
+        }
+        list.sort_by_key(|p| p.started_at);
+        Ok(list
+            .iter()
+            .map(|p| p.summary())
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
+
+/// Read the output of a background process.
+pub struct ProcessLogs;
+
+#[async_trait]
+impl Tool for ProcessLogs {
+    fn name(&self) -> &str {
+        "process_logs"
+    }
+
+    fn description(&self) -> &str {
+        "Read the combined stdout/stderr of a background process. Returns the last lines by default; pass the offset from a previous call to get only new output."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "id": {
+                    "type": "string",
+                    "description": "Process id from process_start"
+                },
+                "tail_lines": {
+                    "type": "integer",
+                    "description": "Optional: number of trailing lines to return (default: 100)"
+                },
+                "offset": {
+                    "type": "integer",
+                    "description": "Optional: byte offset from a previous call; returns output written since then"
+                }
+            },
+            "required": ["id"]
+        })
+    }
+
+    async fn execute(&self, args: Value, working_dir: &Path) -> anyhow::Result<String> {
+        let id = args["id"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing 'id' argument"))?;
+        let (log_path, summary) =
+            with_process(id, working_dir, |p| (p.log_path.clone(), p.summary()))?;
+        let offset = args["offset"].as_u64();
+        let lines = args["tail_lines"]
+            .as_u64()
+            .map(|n| n as usize)
+            .unwrap_or(DEFAULT_TAIL_LINES);
+        let (text, next_offset) =
+            tokio::task::spawn_blocking(move || read_log(&log_path, offset, lines)).await??;
+
+        let body = if text.trim().is_empty() {
+            "(no new output)".to_string()
+        } else {
+            text
+        };
+        Ok(format!(
+            "{}\n\n{}\n\n[next offset: {}]",
+            summary, body, next_offset
+        ))
+    }
+}
+
+/// Stop a background process and its children.
+pub struct ProcessStop;
+
+#[async_trait]
+impl Tool for ProcessStop {
+    fn name(&self) -> &str {
+        "process_stop"
+    }
+
+    fn description(&self) -> &str {
+        "Stop a background process started with process_start, including any child processes. Sends SIGTERM, then kills it if it hasn't exited after grace_secs."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "id": {
+                    "type": "string",
+                    "description": "Process id from process_start"
+                },
+                "grace_secs": {
+                    "type": "integer",
+                    "description": "Optional: seconds to wait after SIGTERM before killing (default: 5)"
+                }
+            },
+            "required": ["id"]
+        })
+    }
+
+    async fn execute(&self, args: Value, working_dir: &Path) -> anyhow::Result<String> {
+        let id = args["id"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing 'id' argument"))?;
+        let grace = args["grace_secs"]
+            .as_u64()
+            .unwrap_or(DEFAULT_STOP_GRACE_SECS);
+        let (pid, state) = with_process(id, working_dir, |p| (p.pid, Arc::clone(&p.state)))?;
+
+        let current = *state.lock().unwrap();
+        if current != ProcessState::Running {
+            return with_process(id, working_dir, ManagedProcess::summary);
+        }
+        let Some(pid) = pid else {
+            return Err(anyhow::anyhow!("Process {} has no pid to signal", id));
+        };
+
+        #[cfg(unix)]
+        unsafe {
+            libc::killpg(pid as libc::pid_t, libc::SIGTERM);
+        }
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(grace);
+        while *state.lock().unwrap() == ProcessState::Running
+            && tokio::time::Instant::now() < deadline
+        {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        let forced = *state.lock().unwrap() == ProcessState::Running;
+        if forced {
+            tokio::task::spawn_blocking(move || crate::workspace_exec::kill_tree_by_pid(pid))
+                .await?;
+        }
+        *state.lock().unwrap() = ProcessState::Stopped;
+
+        Ok(format!(
+            "Stopped {}{}.",
+            id,
+            if forced {
+                " (killed after it ignored SIGTERM)"
+            } else {
+                ""
+            }
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_background_process_lifecycle() {
+        let dir = tempfile::tempdir().unwrap();
+        let started = ProcessStart
+            .execute(
+                json!({
+                    "command": "echo booting; echo ready; sleep 30",
+                    "ready_pattern": "ready",
+                    "ready_timeout_secs": 10
+                }),
+                dir.path(),
+            )
+            .await
+            .unwrap();
+        assert!(
+            started.contains("Ready: output contains 'ready'."),
+            "{}",
+            started
+        );
+        let id = started
+            .split_whitespace()
+            .nth(3)
+            .unwrap()
+            .trim_end_matches(|c: char| !c.is_alphanumeric())
+            .to_string();
+
+        let status = ProcessStatus.execute(json!({}), dir.path()).await.unwrap();
+        assert!(status.contains(&format!("{} (pid", id)) && status.contains("running"));
+
+        let logs = ProcessLogs
+            .execute(json!({"id": id, "tail_lines": 1}), dir.path())
+            .await
+            .unwrap();
+        assert!(logs.ends_with("\n\nready\n\n[next offset: 14]"), "{}", logs);
+
+        // Other workspaces can't see or stop the process.
+        let other = tempfile::tempdir().unwrap();
+        assert!(ProcessStop
+            .execute(json!({"id": id}), other.path())
+            .await
+            .is_err());
+
+        let stopped = ProcessStop
+            .execute(json!({"id": id}), dir.path())
+            .await
+            .unwrap();
+        assert_eq!(stopped, format!("Stopped {}.", id));
+        let status = ProcessStatus
+            .execute(json!({"id": id}), dir.path())
+            .await
+            .unwrap();
+        assert!(status.contains("stopped"));
+    }
+
+    #[test]
+    fn test_read_log_offsets() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("p.log");
+        std::fs::write(&path, "one\ntwo\nthree\n").unwrap();
+        let (tail, offset) = read_log(&path, None, 2).unwrap();
+        assert_eq!(tail, "two\nthree");
+        assert_eq!(offset, 14);
+
+        std::fs::write(&path, "one\ntwo\nthree\nfour\n").unwrap();
+        let (new, offset) = read_log(&path, Some(offset), 100).unwrap();
+        assert_eq!(new, "four\n");
+        assert_eq!(offset, 19);
+    }
+}
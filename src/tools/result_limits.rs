@@ -0,0 +1,226 @@
+//! Size limits for tool results.
+//!
+//! Outputs larger than the configured cap are cut down before they reach the
+//! agent, either by keeping the head and tail or by summarizing them with a
+//! model from the configured AI providers. Either way a note is appended so
+//! the agent knows it isn't seeing the full output.
+
+use serde::{Deserialize, Serialize};
+
+use super::policy::tool_matches;
+use super::safe_truncate_index;
+
+/// Env var carrying the result limits (JSON) to the workspace MCP server.
+pub const TOOL_RESULT_LIMITS_ENV: &str = "SANDBOXED_SH_TOOL_RESULT_LIMITS";
+
+/// Default cap on a tool result, in bytes.
+pub const DEFAULT_MAX_RESULT_BYTES: usize = 100_000;
+/// Largest input sent to the summarizer; anything beyond is head/tail truncated first.
+const MAX_SUMMARY_INPUT_BYTES: usize = 400_000;
+const MAX_SUMMARY_TOKENS: u64 = 4096;
+
+/// How to shrink an oversized result.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResultStrategy {
+    /// Keep the beginning and the end of the output.
+    #[default]
+    Truncate,
+    /// Summarize with a model; falls back to truncation if that fails.
+    Summarize,
+}
+
+/// Per-tool override.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolResultLimit {
+    /// Tool name glob (`*` wildcard), e.g. `grep_search`, `desktop_*`.
+    pub tool: String,
+    /// Cap in bytes (0 = unlimited).
+    pub max_bytes: usize,
+    /// Strategy for this tool (defaults to the global one).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strategy: Option<ResultStrategy>,
+}
+
+/// Result size limits applied by `ToolRegistry::execute` and the workspace
+/// MCP server.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolResultLimits {
+    /// Cap in bytes for tools without an override (0 = unlimited).
+    #[serde(default = "default_max_bytes")]
+    pub max_bytes: usize,
+    #[serde(default)]
+    pub strategy: ResultStrategy,
+    /// Model used for summaries (defaults to the provider's default model).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary_model: Option<String>,
+    /// Per-tool overrides; the first matching entry wins.
+    #[serde(default)]
+    pub tools: Vec<ToolResultLimit>,
+}
+
+fn default_max_bytes() -> usize {
+    DEFAULT_MAX_RESULT_BYTES
+}
+
+impl Default for ToolResultLimits {
+    fn default() -> Self {
+        Self {
+            max_bytes: DEFAULT_MAX_RESULT_BYTES,
+            strategy: ResultStrategy::default(),
+            summary_model: None,
+            tools: Vec::new(),
+        }
+    }
+}
+
+impl ToolResultLimits {
+    pub fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Cap and strategy for `tool`.
+    pub fn limit_for(&self, tool: &str) -> (usize, ResultStrategy) {
+        match self.tools.iter().find(|l| tool_matches(&l.tool, tool)) {
+            Some(limit) => (limit.max_bytes, limit.strategy.unwrap_or(self.strategy)),
+            None => (self.max_bytes, self.strategy),
+        }
+    }
+
+    /// Shrink `output` if it exceeds the cap for `tool`.
+    pub async fn apply(&self, tool: &str, output: String) -> String {
+        let (max_bytes, strategy) = self.limit_for(tool);
+        if max_bytes == 0 || output.len() <= max_bytes {
+            return output;
+        }
+
+        if strategy == ResultStrategy::Summarize {
+            match self.summarize(tool, &output, max_bytes).await {
+                Ok(summary) => return summary,
+                Err(e) => {
+                    tracing::warn!("Failed to summarize {} output, truncating: {}", tool, e)
+                }
+            }
+        }
+        truncate_head_tail(&output, max_bytes)
+    }
+
+    async fn summarize(
+        &self,
+        tool: &str,
+        output: &str,
+        max_bytes: usize,
+    ) -> anyhow::Result<String> {
+        let input = if output.len() > MAX_SUMMARY_INPUT_BYTES {
+            truncate_head_tail(output, MAX_SUMMARY_INPUT_BYTES)
+        } else {
+            output.to_string()
+        };
+        let max_tokens = ((max_bytes / 4) as u64).clamp(256, MAX_SUMMARY_TOKENS);
+        let prompt = format!(
+            "The `{}` tool returned the output below, which is too large to show an AI agent in full. \
+             Summarize it for that agent. Keep exact file paths, line numbers, identifiers, error \
+             messages and figures that are likely to matter, and say what kind of content was left out.\n\n\
+             <output>\n{}\n</output>",
+            tool, input
+        );
+        let (summary, model) =
+            super::vision::complete_text(&prompt, None, self.summary_model.as_deref(), max_tokens)
+                .await?;
+        Ok(format!(
+            "{}\n\n[Tool output summarized by {}: the original was {} bytes and is not shown in full]",
+            summary,
+            model,
+            output.len()
+        ))
+    }
+}
+
+/// Keep roughly the first two thirds and the last third of `max_bytes`,
+/// with a note about what was dropped.
+pub fn truncate_head_tail(output: &str, max_bytes: usize) -> String {
+    if output.len() <= max_bytes {
+        return output.to_string();
+    }
+    let head_end = safe_truncate_index(output, max_bytes * 2 / 3);
+    let mut tail_start = output.len() - (max_bytes - head_end);
+    while !output.is_char_boundary(tail_start) {
+        tail_start += 1;
+    }
+    format!(
+        "{}\n\n[... {} bytes omitted ...]\n\n{}\n\n[Tool output truncated: showing the first {} and last {} of {} bytes]",
+        &output[..head_end],
+        tail_start - head_end,
+        &output[tail_start..],
+        head_end,
+        output.len() - tail_start,
+        output.len()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limit_for_uses_first_matching_override() {
+        let limits = ToolResultLimits {
+            tools: vec![
+                ToolResultLimit {
+                    tool: "grep_search".to_string(),
+                    max_bytes: 2000,
+                    strategy: Some(ResultStrategy::Summarize),
+                },
+                ToolResultLimit {
+                    tool: "read_*".to_string(),
+                    max_bytes: 0,
+                    strategy: None,
+                },
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            limits.limit_for("mcp__workspace__grep_search"),
+            (2000, ResultStrategy::Summarize)
+        );
+        assert_eq!(limits.limit_for("read_file"), (0, ResultStrategy::Truncate));
+        assert_eq!(
+            limits.limit_for("run_command"),
+            (DEFAULT_MAX_RESULT_BYTES, ResultStrategy::Truncate)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_apply_truncates_head_and_tail() {
+        let limits = ToolResultLimits {
+            max_bytes: 30,
+            ..Default::default()
+        };
+        let output = format!("{}{}", "a".repeat(50), "z".repeat(50));
+        let shrunk = limits.apply("run_command", output).await;
+        assert!(shrunk.starts_with(&"a".repeat(20)));
+        assert!(shrunk.contains("[... 70 bytes omitted ...]"));
+        assert!(shrunk.contains(&format!("{}\n\n[Tool output truncated", "z".repeat(10))));
+        assert!(shrunk.ends_with("showing the first 20 and last 10 of 100 bytes]"));
+
+        let small = limits.apply("run_command", "ok".to_string()).await;
+        assert_eq!(small, "ok");
+    }
+
+    #[test]
+    fn test_truncate_respects_char_boundaries() {
+        let output = "é".repeat(20);
+        let shrunk = truncate_head_tail(&output, 9);
+        assert!(shrunk.starts_with("ééé\n"));
+        assert!(shrunk.contains("\n\né\n\n[Tool output truncated"));
+    }
+
+    #[test]
+    fn test_limits_deserialize_with_defaults() {
+        let limits: ToolResultLimits =
+            serde_json::from_str(r#"{"strategy": "summarize"}"#).unwrap();
+        assert_eq!(limits.max_bytes, DEFAULT_MAX_RESULT_BYTES);
+        assert_eq!(limits.strategy, ResultStrategy::Summarize);
+        assert!(ToolResultLimits::default().is_default());
+    }
+}
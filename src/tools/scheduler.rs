@@ -0,0 +1,209 @@
+//! Recurring job tool: lets the agent schedule missions through the server API.
+
+use std::path::Path;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use super::Tool;
+
+/// Send a request to the scheduler API and return the JSON body.
+async fn scheduler_request(
+    method: reqwest::Method,
+    path: &str,
+    body: Option<Value>,
+) -> anyhow::Result<Value> {
+    let api_base = std::env::var("SANDBOXED_SH_API_URL")
+        .unwrap_or_else(|_| "http://127.0.0.1:3000".to_string());
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(30))
+        .build()?;
+    let mut request = client.request(
+        method,
+        format!("{}/api/scheduler{}", api_base.trim_end_matches('/'), path),
+    );
+    if let Ok(token) = std::env::var("SANDBOXED_SH_API_TOKEN") {
+        request = request.header("Authorization", format!("Bearer {}", token));
+    }
+    if let Some(body) = body {
+        request = request.json(&body);
+    }
+
+    let response = request.send().await?;
+    let status = response.status();
+    let text = response.text().await.unwrap_or_default();
+    if !status.is_success() {
+        return Err(anyhow::anyhow!(
+            "Scheduler API error ({}): {}",
+            status,
+            text
+        ));
+    }
+    if text.trim().is_empty() {
+        return Ok(Value::Null);
+    }
+    Ok(serde_json::from_str(&text)?)
+}
+
+fn describe_job(job: &Value) -> String {
+    let mut line = format!(
+        "{} — \"{}\" [{} {}] {}",
+        job["id"].as_str().unwrap_or("?"),
+        job["name"].as_str().unwrap_or(""),
+        job["schedule"].as_str().unwrap_or(""),
+        job["utc_offset"].as_str().unwrap_or("+00:00"),
+        if job["enabled"].as_bool().unwrap_or(false) {
+            "enabled"
+        } else {
+            "paused"
+        }
+    );
+    if let Some(next) = job["next_run_at"].as_str() {
+        line.push_str(&format!(", next run {}", next));
+    }
+    if let Some(last) = job["last_run_at"].as_str() {
+        line.push_str(&format!(", last run {}", last));
+    }
+    if let Some(error) = job["last_error"].as_str() {
+        line.push_str(&format!(" (last error: {})", error));
+    }
+    line
+}
+
+/// Create and manage recurring missions.
+pub struct ScheduleTask;
+
+#[async_trait]
+impl Tool for ScheduleTask {
+    fn name(&self) -> &str {
+        "schedule_task"
+    }
+
+    fn description(&self) -> &str {
+        "Schedule a prompt to run as a new mission on a recurring cron schedule (e.g. '0 9 * * *' = every day at 9:00). Actions: create (default), list, pause, resume, delete, run_now. Schedules use standard 5-field cron syntax evaluated at utc_offset."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["create", "list", "pause", "resume", "delete", "run_now"],
+                    "description": "What to do (default: create)"
+                },
+                "job_id": {
+                    "type": "string",
+                    "description": "Job id, for pause/resume/delete/run_now"
+                },
+                "name": {
+                    "type": "string",
+                    "description": "Short name for the job (create)"
+                },
+                "schedule": {
+                    "type": "string",
+                    "description": "Cron expression: minute hour day-of-month month day-of-week, or @hourly/@daily/@weekly/@monthly (create)"
+                },
+                "utc_offset": {
+                    "type": "string",
+                    "description": "Optional: offset the schedule is evaluated at, e.g. '+02:00' (default: UTC)"
+                },
+                "prompt": {
+                    "type": "string",
+                    "description": "The message each new mission starts with (create)"
+                },
+                "workspace_id": {
+                    "type": "string",
+                    "description": "Optional: workspace to run missions in (default: host workspace)"
+                },
+                "agent": {
+                    "type": "string",
+                    "description": "Optional: agent to use"
+                },
+                "backend": {
+                    "type": "string",
+                    "description": "Optional: backend to use (e.g. 'claudecode', 'opencode')"
+                },
+                "model_override": {
+                    "type": "string",
+                    "description": "Optional: model override (provider/model)"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: Value, _working_dir: &Path) -> anyhow::Result<String> {
+        let action = args["action"].as_str().unwrap_or("create");
+        let job_id = || {
+            args["job_id"]
+                .as_str()
+                .filter(|id| uuid::Uuid::parse_str(id).is_ok())
+                .ok_or_else(|| anyhow::anyhow!("'{}' needs a valid 'job_id'", action))
+        };
+
+        match action {
+            "create" => {
+                let mut body = json!({});
+                for key in ["name", "schedule", "prompt"] {
+                    let value = args[key]
+                        .as_str()
+                        .filter(|v| !v.trim().is_empty())
+                        .ok_or_else(|| anyhow::anyhow!("Missing '{}' argument", key))?;
+                    body[key] = json!(value);
+                }
+                for key in [
+                    "utc_offset",
+                    "workspace_id",
+                    "agent",
+                    "backend",
+                    "model_override",
+                ] {
+                    if let Some(value) = args[key].as_str() {
+                        body[key] = json!(value);
+                    }
+                }
+                let job = scheduler_request(reqwest::Method::POST, "/jobs", Some(body)).await?;
+                Ok(format!("Scheduled job created:\n{}", describe_job(&job)))
+            }
+            "list" => {
+                let jobs = scheduler_request(reqwest::Method::GET, "/jobs", None).await?;
+                let jobs = jobs.as_array().cloned().unwrap_or_default();
+                if jobs.is_empty() {
+                    return Ok("No scheduled jobs.".to_string());
+                }
+                Ok(jobs.iter().map(describe_job).collect::<Vec<_>>().join("\n"))
+            }
+            "pause" | "resume" => {
+                let job = scheduler_request(
+                    reqwest::Method::PATCH,
+                    &format!("/jobs/{}", job_id()?),
+                    Some(json!({ "enabled": action == "resume" })),
+                )
+                .await?;
+                Ok(describe_job(&job))
+            }
+            "delete" => {
+                let id = job_id()?;
+                scheduler_request(reqwest::Method::DELETE, &format!("/jobs/{}", id), None).await?;
+                Ok(format!("Deleted scheduled job {}", id))
+            }
+            "run_now" => {
+                let job = scheduler_request(
+                    reqwest::Method::POST,
+                    &format!("/jobs/{}/run", job_id()?),
+                    None,
+                )
+                .await?;
+                Ok(format!(
+                    "Started mission {}\n{}",
+                    job["last_mission_id"].as_str().unwrap_or("?"),
+                    describe_job(&job)
+                ))
+            }
+            other => Err(anyhow::anyhow!(
+                "Unknown action '{}'; use create, list, pause, resume, delete or run_now",
+                other
+            )),
+        }
+    }
+}
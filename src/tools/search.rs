@@ -5,15 +5,185 @@
 //! Searches workspace by default:
 //! - `grep_search("TODO")` → searches in `{workspace}/`
 //! - `grep_search("error", "/var/log")` → searches system logs
+//!
+//! Uses ripgrep when installed (regex, `.gitignore` awareness, file types) and
+//! falls back to `grep -rE`. Either way results come back as JSON grouped by
+//! file, with optional context lines around each match.
 
 use std::path::Path;
 use std::process::Stdio;
 
 use async_trait::async_trait;
+use serde::Serialize;
 use serde_json::{json, Value};
 use tokio::process::Command;
 
-use super::{resolve_path, Tool};
+use super::{resolve_path, safe_truncate_index, Tool};
+
+const DEFAULT_MAX_RESULTS: usize = 100;
+const MAX_RESULTS_CAP: usize = 1000;
+const MAX_CONTEXT_LINES: u64 = 10;
+/// Longest line kept in results; minified files would otherwise flood the output.
+const MAX_LINE_BYTES: usize = 500;
+
+/// A line of surrounding context.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct ContextLine {
+    line: u64,
+    text: String,
+}
+
+/// A matching line with its context.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct LineMatch {
+    line: u64,
+    text: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    before: Vec<ContextLine>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    after: Vec<ContextLine>,
+}
+
+/// All matches in one file.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct FileMatches {
+    path: String,
+    matches: Vec<LineMatch>,
+}
+
+/// Groups match and context lines (in output order) into per-file lists.
+#[derive(Debug)]
+struct MatchCollector {
+    files: Vec<FileMatches>,
+    after_context: usize,
+    max_results: usize,
+    pending_before: Vec<ContextLine>,
+    total: usize,
+    truncated: bool,
+}
+
+impl MatchCollector {
+    fn new(after_context: usize, max_results: usize) -> Self {
+        Self {
+            files: Vec::new(),
+            after_context,
+            max_results,
+            pending_before: Vec::new(),
+            total: 0,
+            truncated: false,
+        }
+    }
+
+    fn clip(text: &str) -> String {
+        let text = text.trim_end_matches(['\n', '\r']);
+        if text.len() > MAX_LINE_BYTES {
+            format!("{}…", &text[..safe_truncate_index(text, MAX_LINE_BYTES)])
+        } else {
+            text.to_string()
+        }
+    }
+
+    fn file_mut(&mut self, path: &str) -> &mut FileMatches {
+        if self.files.last().map(|f| f.path.as_str()) != Some(path) {
+            self.pending_before.clear();
+            self.files.push(FileMatches {
+                path: path.to_string(),
+                matches: Vec::new(),
+            });
+        }
+        self.files.last_mut().expect("file was just pushed")
+    }
+
+    fn push_match(&mut self, path: &str, line: u64, text: &str) {
+        if self.total >= self.max_results {
+            self.truncated = true;
+            return;
+        }
+        self.total += 1;
+        // Pending context only belongs to this match if it came from the same file.
+        let same_file = self.files.last().map(|f| f.path.as_str()) == Some(path);
+        let before = if same_file {
+            std::mem::take(&mut self.pending_before)
+        } else {
+            Vec::new()
+        };
+        self.file_mut(path).matches.push(LineMatch {
+            line,
+            text: Self::clip(text),
+            before,
+            after: Vec::new(),
+        });
+    }
+
+    fn push_context(&mut self, path: &str, line: u64, text: &str) {
+        if self.truncated {
+            return;
+        }
+        let context = ContextLine {
+            line,
+            text: Self::clip(text),
+        };
+        let after_context = self.after_context;
+        let file = self.file_mut(path);
+        if let Some(last) = file.matches.last_mut() {
+            if line > last.line && last.after.len() < after_context {
+                last.after.push(context);
+                return;
+            }
+        }
+        self.pending_before.push(context);
+    }
+
+    fn into_json(self, pattern: &str) -> Value {
+        json!({
+            "pattern": pattern,
+            "total_matches": self.total,
+            "truncated": self.truncated,
+            "files": self.files,
+        })
+    }
+}
+
+/// Parse `rg --json` output.
+fn parse_rg_json(output: &str, collector: &mut MatchCollector) {
+    for line in output.lines() {
+        let Ok(event) = serde_json::from_str::<Value>(line) else {
+            continue;
+        };
+        let data = &event["data"];
+        let (Some(path), Some(text), Some(line_number)) = (
+            data["path"]["text"].as_str(),
+            data["lines"]["text"].as_str(),
+            data["line_number"].as_u64(),
+        ) else {
+            continue;
+        };
+        match event["type"].as_str() {
+            Some("match") => collector.push_match(path, line_number, text),
+            Some("context") => collector.push_context(path, line_number, text),
+            _ => {}
+        }
+    }
+}
+
+/// Parse `grep -rHnZ` output: `path\0line:text` for matches and
+/// `path\0line-text` for context lines.
+fn parse_grep_output(output: &str, collector: &mut MatchCollector) {
+    for line in output.lines() {
+        let Some((path, rest)) = line.split_once('\0') else {
+            continue;
+        };
+        let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+        let Ok(line_number) = rest[..digits].parse::<u64>() else {
+            continue;
+        };
+        match rest.as_bytes().get(digits) {
+            Some(b':') => collector.push_match(path, line_number, &rest[digits + 1..]),
+            Some(b'-') => collector.push_context(path, line_number, &rest[digits + 1..]),
+            _ => {}
+        }
+    }
+}
 
 /// Search file contents with regex/grep.
 pub struct GrepSearch;
@@ -25,7 +195,7 @@ impl Tool for GrepSearch {
     }
 
     fn description(&self) -> &str {
-        "Search for a pattern in file contents using regex. Searches workspace by default. Great for finding function definitions, usages, or patterns."
+        "Search for a regex in file contents (ripgrep when available). Searches workspace by default and respects .gitignore. Returns JSON grouped by file with line numbers and optional context lines. Great for finding function definitions, usages, or patterns."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -38,15 +208,47 @@ impl Tool for GrepSearch {
                 },
                 "path": {
                     "type": "string",
-                    "description": "Directory to search. Defaults to workspace ('.'). Use relative paths for subdirectories or absolute for system search."
+                    "description": "Directory or file to search. Defaults to workspace ('.'). Use relative paths for subdirectories or absolute for system search."
                 },
                 "file_pattern": {
                     "type": "string",
                     "description": "Optional: only search files matching this glob (e.g., '*.rs', '*.py', '*.log')"
                 },
+                "file_type": {
+                    "type": "string",
+                    "description": "Optional: ripgrep file type (e.g., 'rust', 'py', 'ts'); ignored without ripgrep"
+                },
                 "case_sensitive": {
                     "type": "boolean",
                     "description": "Whether search is case-sensitive (default: false)"
+                },
+                "fixed_strings": {
+                    "type": "boolean",
+                    "description": "Treat the pattern as a literal string instead of a regex (default: false)"
+                },
+                "context": {
+                    "type": "integer",
+                    "description": "Lines of context before and after each match (default: 0, max: 10)"
+                },
+                "before_context": {
+                    "type": "integer",
+                    "description": "Lines of context before each match (overrides 'context')"
+                },
+                "after_context": {
+                    "type": "integer",
+                    "description": "Lines of context after each match (overrides 'context')"
+                },
+                "max_results": {
+                    "type": "integer",
+                    "description": "Maximum number of matches to return (default: 100, max: 1000)"
+                },
+                "include_hidden": {
+                    "type": "boolean",
+                    "description": "Also search hidden files and directories (default: false)"
+                },
+                "no_ignore": {
+                    "type": "boolean",
+                    "description": "Don't respect .gitignore/.ignore files (default: false)"
                 }
             },
             "required": ["pattern"]
@@ -59,41 +261,76 @@ impl Tool for GrepSearch {
             .ok_or_else(|| anyhow::anyhow!("Missing 'pattern' argument"))?;
         let path = args["path"].as_str().unwrap_or(".");
         let file_pattern = args["file_pattern"].as_str();
+        let file_type = args["file_type"].as_str();
         let case_sensitive = args["case_sensitive"].as_bool().unwrap_or(false);
+        let fixed_strings = args["fixed_strings"].as_bool().unwrap_or(false);
+        let include_hidden = args["include_hidden"].as_bool().unwrap_or(false);
+        let no_ignore = args["no_ignore"].as_bool().unwrap_or(false);
+        let context = args["context"].as_u64().unwrap_or(0);
+        let before = args["before_context"]
+            .as_u64()
+            .unwrap_or(context)
+            .min(MAX_CONTEXT_LINES);
+        let after = args["after_context"]
+            .as_u64()
+            .unwrap_or(context)
+            .min(MAX_CONTEXT_LINES);
+        let max_results = args["max_results"]
+            .as_u64()
+            .map(|n| n as usize)
+            .unwrap_or(DEFAULT_MAX_RESULTS)
+            .clamp(1, MAX_RESULTS_CAP);
 
         let resolution = resolve_path(path, working_dir);
         let search_path = resolution.resolved;
 
         // Try to use ripgrep (rg) if available, fall back to grep
-        let mut cmd = if which_exists("rg") {
+        let use_rg = which_exists("rg");
+        let mut cmd = if use_rg {
             let mut c = Command::new("rg");
-            c.arg("--line-number");
-            c.arg("--no-heading");
-            c.arg("--color=never");
+            c.arg("--json");
+            c.arg("--max-count").arg(max_results.to_string());
 
             if !case_sensitive {
                 c.arg("-i");
             }
-
+            if fixed_strings {
+                c.arg("-F");
+            }
+            if include_hidden {
+                c.arg("--hidden");
+            }
+            if no_ignore {
+                c.arg("--no-ignore");
+            }
             if let Some(fp) = file_pattern {
                 c.arg("-g").arg(fp);
             }
+            if let Some(ft) = file_type {
+                c.arg("-t").arg(ft);
+            }
 
+            c.arg("-B").arg(before.to_string());
+            c.arg("-A").arg(after.to_string());
             c.arg("--").arg(pattern).arg(&search_path);
             c
         } else {
             let mut c = Command::new("grep");
-            c.arg("-rn");
+            c.arg("-rHnZ");
+            c.arg(if fixed_strings { "-F" } else { "-E" });
+            c.arg("-m").arg(max_results.to_string());
+            c.arg("--exclude-dir=.git");
 
             if !case_sensitive {
                 c.arg("-i");
             }
-
             if let Some(fp) = file_pattern {
                 c.arg("--include").arg(fp);
             }
 
-            c.arg(pattern).arg(&search_path);
+            c.arg("-B").arg(before.to_string());
+            c.arg("-A").arg(after.to_string());
+            c.arg("-e").arg(pattern).arg(&search_path);
             c
         };
 
@@ -107,38 +344,77 @@ impl Tool for GrepSearch {
         let stdout = String::from_utf8_lossy(&output.stdout);
         let stderr = String::from_utf8_lossy(&output.stderr);
 
-        // grep returns exit code 1 when no matches found
-        if !output.status.success() && output.status.code() != Some(1) {
-            if !stderr.is_empty() {
-                return Err(anyhow::anyhow!("Search error: {}", stderr));
-            }
+        let mut collector = MatchCollector::new(after as usize, max_results);
+        if use_rg {
+            parse_rg_json(&stdout, &mut collector);
+        } else {
+            parse_grep_output(&stdout, &mut collector);
         }
 
-        if stdout.is_empty() {
-            return Ok(format!("No matches found for pattern: {}", pattern));
+        // Exit code 1 means no matches; 2 can still come with partial results
+        // (e.g. unreadable files), so only fail when nothing matched.
+        if !output.status.success()
+            && output.status.code() != Some(1)
+            && collector.total == 0
+            && !stderr.is_empty()
+        {
+            return Err(anyhow::anyhow!("Search error: {}", stderr.trim()));
         }
 
-        // Show results with full paths for system-wide clarity
-        let result: String = stdout
-            .lines()
-            .take(100) // Limit results
-            .collect::<Vec<_>>()
-            .join("\n");
-
-        let line_count = result.lines().count();
-        if line_count >= 100 {
-            Ok(format!("{}\n\n... (showing first 100 matches)", result))
-        } else {
-            Ok(result)
-        }
+        Ok(serde_json::to_string_pretty(&collector.into_json(pattern))?)
     }
 }
 
 /// Check if a command exists in PATH.
-fn which_exists(cmd: &str) -> bool {
+pub(super) fn which_exists(cmd: &str) -> bool {
     std::process::Command::new("which")
         .arg(cmd)
         .output()
         .map(|o| o.status.success())
         .unwrap_or(false)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rg_json_groups_matches_with_context() {
+        let output = [
+            r#"{"type":"begin","data":{"path":{"text":"src/a.rs"}}}"#,
+            r#"{"type":"context","data":{"path":{"text":"src/a.rs"},"lines":{"text":"use x;\n"},"line_number":1}}"#,
+            r#"{"type":"match","data":{"path":{"text":"src/a.rs"},"lines":{"text":"fn main() {\n"},"line_number":2}}"#,
+            r#"{"type":"context","data":{"path":{"text":"src/a.rs"},"lines":{"text":"    run();\n"},"line_number":3}}"#,
+            r#"{"type":"end","data":{"path":{"text":"src/a.rs"}}}"#,
+            r#"{"type":"match","data":{"path":{"text":"src/b.rs"},"lines":{"text":"fn main() {}\n"},"line_number":7}}"#,
+            r#"{"type":"summary","data":{}}"#,
+        ]
+        .join("\n");
+        let mut collector = MatchCollector::new(1, 100);
+        parse_rg_json(&output, &mut collector);
+
+        assert_eq!(collector.total, 2);
+        assert_eq!(collector.files.len(), 2);
+        let first = &collector.files[0].matches[0];
+        assert_eq!(first.line, 2);
+        assert_eq!(first.text, "fn main() {");
+        assert_eq!(first.before[0].text, "use x;");
+        assert_eq!(first.after[0].line, 3);
+        assert_eq!(collector.files[1].path, "src/b.rs");
+        assert!(collector.files[1].matches[0].before.is_empty());
+    }
+
+    #[test]
+    fn test_parse_grep_output_and_cap() {
+        let output = "a.txt\u{0}1-before\na.txt\u{0}2:hit one\na.txt\u{0}3-after\n--\nb-c.txt\u{0}10:hit: two\nb-c.txt\u{0}11:hit three\n";
+        let mut collector = MatchCollector::new(1, 2);
+        parse_grep_output(output, &mut collector);
+
+        assert_eq!(collector.total, 2);
+        assert!(collector.truncated);
+        assert_eq!(collector.files[0].matches[0].before[0].text, "before");
+        assert_eq!(collector.files[0].matches[0].after[0].text, "after");
+        assert_eq!(collector.files[1].path, "b-c.txt");
+        assert_eq!(collector.files[1].matches[0].text, "hit: two");
+    }
+}
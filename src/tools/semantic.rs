@@ -0,0 +1,537 @@
+//! Semantic code search backed by embeddings.
+//!
+//! Workspace files are split into overlapping line chunks, embedded through the
+//! configured AI providers and stored in
+//! `{working_dir}/.sandboxed_sh/index/semantic.db`. Each search first embeds
+//! files that are new or changed since the last run, then ranks every chunk
+//! under the search root by cosine similarity to the query.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use async_trait::async_trait;
+use rusqlite::{params, Connection};
+use serde_json::{json, Value};
+use walkdir::WalkDir;
+
+use super::embeddings::{cosine_similarity, embed};
+use super::index::default_ignore_dirs;
+use super::{resolve_path_simple as resolve_path, safe_truncate_index, Tool};
+
+const CHUNK_LINES: usize = 40;
+const CHUNK_OVERLAP: usize = 8;
+const MAX_CHUNK_BYTES: usize = 3000;
+/// Larger files are skipped (generated code, data dumps).
+const MAX_FILE_BYTES: u64 = 256 * 1024;
+const MAX_FILES: usize = 5000;
+/// Chunks embedded per call; the rest are picked up by the next search.
+const MAX_NEW_CHUNKS_PER_CALL: usize = 4000;
+const DEFAULT_TOP_K: usize = 8;
+const MAX_TOP_K: usize = 50;
+
+const SCHEMA: &str = r#"
+PRAGMA journal_mode = WAL;
+CREATE TABLE IF NOT EXISTS files (
+    path TEXT PRIMARY KEY,
+    size INTEGER NOT NULL,
+    mtime INTEGER NOT NULL,
+    model TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS chunks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    path TEXT NOT NULL,
+    start_line INTEGER NOT NULL,
+    end_line INTEGER NOT NULL,
+    content TEXT NOT NULL,
+    embedding BLOB NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_chunks_path ON chunks(path);
+"#;
+
+fn default_index_file(working_dir: &Path) -> PathBuf {
+    working_dir
+        .join(".sandboxed_sh")
+        .join("index")
+        .join("semantic.db")
+}
+
+fn open_index(path: &Path) -> anyhow::Result<Connection> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let conn = Connection::open(path)?;
+    conn.busy_timeout(std::time::Duration::from_secs(5))?;
+    conn.execute_batch(SCHEMA)?;
+    Ok(conn)
+}
+
+fn prefix_of(root: &Path) -> String {
+    let root = root.to_string_lossy();
+    if root.ends_with('/') {
+        root.to_string()
+    } else {
+        format!("{}/", root)
+    }
+}
+
+/// A chunk of a file, 1-based inclusive line range.
+#[derive(Debug, Clone, PartialEq)]
+struct Chunk {
+    start_line: usize,
+    end_line: usize,
+    content: String,
+}
+
+/// Split text into overlapping line windows.
+fn chunk_text(text: &str) -> Vec<Chunk> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < lines.len() {
+        let end = (start + CHUNK_LINES).min(lines.len());
+        let mut content = lines[start..end].join("\n");
+        content.truncate(safe_truncate_index(&content, MAX_CHUNK_BYTES));
+        if !content.trim().is_empty() {
+            chunks.push(Chunk {
+                start_line: start + 1,
+                end_line: end,
+                content,
+            });
+        }
+        if end == lines.len() {
+            break;
+        }
+        start = end - CHUNK_OVERLAP;
+    }
+    chunks
+}
+
+fn encode_vector(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn decode_vector(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect()
+}
+
+/// A file that needs (re-)embedding.
+#[derive(Debug)]
+struct PendingFile {
+    path: String,
+    size: i64,
+    mtime: i64,
+    chunks: Vec<Chunk>,
+}
+
+/// Result of comparing the tree with the index.
+#[derive(Debug, Default)]
+struct ScanOutcome {
+    files: usize,
+    pending: Vec<PendingFile>,
+    /// Changed files left for a later call because of the per-call cap.
+    deferred: usize,
+    removed: Vec<String>,
+}
+
+/// Walk `root` and collect files that are new, changed or embedded with another model.
+fn scan(
+    conn: &Connection,
+    root: &Path,
+    model_key: &str,
+    reindex: bool,
+) -> anyhow::Result<ScanOutcome> {
+    let prefix = prefix_of(root);
+    let mut indexed: HashMap<String, (i64, i64, String)> = HashMap::new();
+    {
+        let mut stmt = conn.prepare(
+            "SELECT path, size, mtime, model FROM files WHERE substr(path, 1, length(?1)) = ?1",
+        )?;
+        let rows = stmt.query_map(params![prefix], |row| {
+            Ok((row.get(0)?, (row.get(1)?, row.get(2)?, row.get(3)?)))
+        })?;
+        for row in rows {
+            let (path, entry): (String, _) = row?;
+            indexed.insert(path, entry);
+        }
+    }
+
+    let ignore_dirs = default_ignore_dirs();
+    let walker = WalkDir::new(root)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|e| {
+            if e.depth() == 0 || !e.file_type().is_dir() {
+                return true;
+            }
+            let name = e.file_name().to_string_lossy();
+            !name.starts_with('.') && !ignore_dirs.iter().any(|d| d == name.as_ref())
+        });
+
+    let mut outcome = ScanOutcome::default();
+    let mut seen = HashSet::new();
+    let mut new_chunks = 0usize;
+    for entry in walker.filter_map(|e| e.ok()) {
+        if !entry.file_type().is_file() || outcome.files >= MAX_FILES {
+            continue;
+        }
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.len() == 0 || metadata.len() > MAX_FILE_BYTES {
+            continue;
+        }
+        let path = entry.path().to_string_lossy().to_string();
+        let size = metadata.len() as i64;
+        let mtime = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+
+        let unchanged = matches!(
+            indexed.get(&path),
+            Some((s, m, model)) if *s == size && *m == mtime && model.as_str() == model_key
+        );
+        if unchanged && !reindex {
+            outcome.files += 1;
+            seen.insert(path);
+            continue;
+        }
+
+        // Skip binary files.
+        let Ok(bytes) = std::fs::read(entry.path()) else {
+            continue;
+        };
+        if bytes[..bytes.len().min(8192)].contains(&0) {
+            continue;
+        }
+        let Ok(text) = String::from_utf8(bytes) else {
+            continue;
+        };
+        outcome.files += 1;
+        seen.insert(path.clone());
+
+        let chunks = chunk_text(&text);
+        if new_chunks + chunks.len() > MAX_NEW_CHUNKS_PER_CALL {
+            outcome.deferred += 1;
+            continue;
+        }
+        new_chunks += chunks.len();
+        outcome.pending.push(PendingFile {
+            path,
+            size,
+            mtime,
+            chunks,
+        });
+    }
+
+    if outcome.files < MAX_FILES {
+        outcome.removed = indexed.into_keys().filter(|p| !seen.contains(p)).collect();
+    }
+    Ok(outcome)
+}
+
+/// Replace the chunks of embedded files and drop removed ones.
+fn store(
+    conn: &mut Connection,
+    pending: &[PendingFile],
+    vectors: &[Vec<f32>],
+    removed: &[String],
+    model_key: &str,
+) -> anyhow::Result<()> {
+    let tx = conn.transaction()?;
+    for path in removed {
+        tx.execute("DELETE FROM chunks WHERE path = ?1", params![path])?;
+        tx.execute("DELETE FROM files WHERE path = ?1", params![path])?;
+    }
+    let mut vectors = vectors.iter();
+    for file in pending {
+        tx.execute("DELETE FROM chunks WHERE path = ?1", params![file.path])?;
+        for chunk in &file.chunks {
+            let Some(vector) = vectors.next() else {
+                break;
+            };
+            tx.execute(
+                "INSERT INTO chunks (path, start_line, end_line, content, embedding)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    file.path,
+                    chunk.start_line as i64,
+                    chunk.end_line as i64,
+                    chunk.content,
+                    encode_vector(vector)
+                ],
+            )?;
+        }
+        tx.execute(
+            "INSERT INTO files (path, size, mtime, model) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(path) DO UPDATE SET size = excluded.size, mtime = excluded.mtime, model = excluded.model",
+            params![file.path, file.size, file.mtime, model_key],
+        )?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// A ranked chunk.
+#[derive(Debug)]
+struct Hit {
+    path: String,
+    start_line: i64,
+    end_line: i64,
+    content: String,
+    score: f32,
+}
+
+/// Rank every chunk under `root` against `query_vector`.
+fn rank(
+    conn: &Connection,
+    root: &Path,
+    query_vector: &[f32],
+    top_k: usize,
+) -> anyhow::Result<(Vec<Hit>, usize)> {
+    let mut stmt = conn.prepare(
+        "SELECT path, start_line, end_line, content, embedding FROM chunks
+         WHERE substr(path, 1, length(?1)) = ?1",
+    )?;
+    let rows = stmt.query_map(params![prefix_of(root)], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, i64>(1)?,
+            row.get::<_, i64>(2)?,
+            row.get::<_, String>(3)?,
+            row.get::<_, Vec<u8>>(4)?,
+        ))
+    })?;
+
+    let mut hits = Vec::new();
+    let mut total = 0usize;
+    for row in rows {
+        let (path, start_line, end_line, content, embedding) = row?;
+        total += 1;
+        hits.push(Hit {
+            path,
+            start_line,
+            end_line,
+            content,
+            score: cosine_similarity(query_vector, &decode_vector(&embedding)),
+        });
+    }
+    hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+    hits.truncate(top_k);
+    Ok((hits, total))
+}
+
+/// Natural-language code search over the workspace.
+pub struct SemanticSearch;
+
+#[async_trait]
+impl Tool for SemanticSearch {
+    fn name(&self) -> &str {
+        "semantic_search"
+    }
+
+    fn description(&self) -> &str {
+        "Find code by meaning rather than exact text, e.g. 'where is the auth token refreshed?'. Embeds workspace files (incrementally, cached across missions) and returns the most relevant chunks with file paths and line ranges. Use grep_search for exact identifiers."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "Natural-language description of the code you're looking for"
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Directory to search. Defaults to workspace ('.')."
+                },
+                "top_k": {
+                    "type": "integer",
+                    "description": "Number of chunks to return (default: 8, max: 50)"
+                },
+                "model": {
+                    "type": "string",
+                    "description": "Optional: embedding model ID (default: the provider's embedding model)"
+                },
+                "reindex": {
+                    "type": "boolean",
+                    "description": "Re-embed every file instead of only changed ones (default: false)"
+                }
+            },
+            "required": ["query"]
+        })
+    }
+
+    async fn execute(&self, args: Value, working_dir: &Path) -> anyhow::Result<String> {
+        let query = args["query"]
+            .as_str()
+            .map(str::trim)
+            .filter(|q| !q.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'query' argument"))?
+            .to_string();
+        let path = args["path"].as_str().unwrap_or(".");
+        let top_k = args["top_k"]
+            .as_u64()
+            .map(|n| n as usize)
+            .unwrap_or(DEFAULT_TOP_K)
+            .clamp(1, MAX_TOP_K);
+        let model = args["model"].as_str().map(str::to_string);
+        let reindex = args["reindex"].as_bool().unwrap_or(false);
+
+        let root = resolve_path(path, working_dir);
+        if !root.is_dir() {
+            return Err(anyhow::anyhow!("Not a directory: {}", path));
+        }
+        let root = root.canonicalize().unwrap_or(root);
+        let index_path = default_index_file(working_dir);
+
+        // Embed the query first: it resolves the model that keys the index.
+        let (query_vectors, model_used) =
+            embed(std::slice::from_ref(&query), model.as_deref()).await?;
+        let query_vector = query_vectors
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("No embedding returned for the query"))?;
+
+        let (db, scan_root, model_key) = (index_path.clone(), root.clone(), model_used.clone());
+        let outcome = tokio::task::spawn_blocking(move || {
+            scan(&open_index(&db)?, &scan_root, &model_key, reindex)
+        })
+        .await??;
+
+        let texts: Vec<String> = outcome
+            .pending
+            .iter()
+            .flat_map(|file| {
+                let relative = Path::new(&file.path)
+                    .strip_prefix(&root)
+                    .unwrap_or(Path::new(&file.path))
+                    .to_string_lossy()
+                    .to_string();
+                file.chunks
+                    .iter()
+                    .map(move |chunk| format!("File: {}\n{}", relative, chunk.content))
+            })
+            .collect();
+        let vectors = if texts.is_empty() {
+            Vec::new()
+        } else {
+            embed(&texts, Some(&model_used)).await?.0
+        };
+
+        let embedded_files = outcome.pending.len();
+        let (indexed_files, deferred_files) = (outcome.files, outcome.deferred);
+        let (db, rank_root, model_key) = (index_path, root.clone(), model_used.clone());
+        let (hits, total_chunks) = tokio::task::spawn_blocking(move || {
+            let mut conn = open_index(&db)?;
+            store(
+                &mut conn,
+                &outcome.pending,
+                &vectors,
+                &outcome.removed,
+                &model_key,
+            )?;
+            rank(&conn, &rank_root, &query_vector, top_k)
+        })
+        .await??;
+
+        let results: Vec<Value> = hits
+            .into_iter()
+            .map(|hit| {
+                json!({
+                    "path": hit.path,
+                    "start_line": hit.start_line,
+                    "end_line": hit.end_line,
+                    "score": (hit.score * 1000.0).round() / 1000.0,
+                    "content": hit.content,
+                })
+            })
+            .collect();
+
+        Ok(serde_json::to_string_pretty(&json!({
+            "query": query,
+            "root": root.to_string_lossy(),
+            "model": model_used,
+            "index": {
+                "files": indexed_files,
+                "chunks": total_chunks,
+                "embedded_now": embedded_files,
+                "pending": deferred_files,
+            },
+            "results": results,
+        }))?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_text_overlaps() {
+        let text: String = (1..=100).map(|i| format!("line {}\n", i)).collect();
+        let chunks = chunk_text(&text);
+        let ranges: Vec<(usize, usize)> =
+            chunks.iter().map(|c| (c.start_line, c.end_line)).collect();
+        assert_eq!(ranges, vec![(1, 40), (33, 72), (65, 100)]);
+        assert!(chunks[1].content.starts_with("line 33\n"));
+        assert!(chunk_text("").is_empty());
+    }
+
+    #[test]
+    fn test_scan_store_and_rank() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("repo");
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("node_modules")).unwrap();
+        std::fs::write(root.join("src/auth.rs"), "fn refresh_token() {}\n").unwrap();
+        std::fs::write(root.join("src/db.rs"), "fn connect() {}\n").unwrap();
+        std::fs::write(root.join("node_modules/x.js"), "ignored\n").unwrap();
+        std::fs::write(root.join("src/blob.bin"), [0u8, 1, 2]).unwrap();
+
+        let mut conn = open_index(&dir.path().join("semantic.db")).unwrap();
+        let outcome = scan(&conn, &root, "m", false).unwrap();
+        assert_eq!(outcome.pending.len(), 2);
+
+        let vectors: Vec<Vec<f32>> = outcome
+            .pending
+            .iter()
+            .map(|f| {
+                if f.path.ends_with("auth.rs") {
+                    vec![1.0, 0.0]
+                } else {
+                    vec![0.0, 1.0]
+                }
+            })
+            .collect();
+        store(&mut conn, &outcome.pending, &vectors, &outcome.removed, "m").unwrap();
+
+        // Unchanged files aren't re-embedded; a model change re-embeds them.
+        assert!(scan(&conn, &root, "m", false).unwrap().pending.is_empty());
+        assert_eq!(scan(&conn, &root, "other", false).unwrap().pending.len(), 2);
+
+        let (hits, total) = rank(&conn, &root, &[0.9, 0.1], 1).unwrap();
+        assert_eq!(total, 2);
+        assert!(hits[0].path.ends_with("src/auth.rs"));
+        assert_eq!((hits[0].start_line, hits[0].end_line), (1, 1));
+
+        std::fs::remove_file(root.join("src/db.rs")).unwrap();
+        let outcome = scan(&conn, &root, "m", false).unwrap();
+        assert_eq!(outcome.removed.len(), 1);
+        store(&mut conn, &[], &[], &outcome.removed, "m").unwrap();
+        assert_eq!(rank(&conn, &root, &[0.0, 1.0], 5).unwrap().1, 1);
+    }
+
+    #[test]
+    fn test_vector_roundtrip() {
+        let vector = vec![0.5, -1.25, 3.0];
+        assert_eq!(decode_vector(&encode_vector(&vector)), vector);
+    }
+}
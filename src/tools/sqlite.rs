@@ -0,0 +1,313 @@
+//! SQLite tool: run parameterized SQL against a database file.
+//!
+//! Databases are opened read-only unless `allow_write` is set, so exploratory
+//! queries can't modify data by accident.
+
+use std::path::Path;
+
+use async_trait::async_trait;
+use rusqlite::types::{Value as SqlValue, ValueRef};
+use rusqlite::{params_from_iter, Connection, OpenFlags};
+use serde_json::{json, Map, Value};
+
+use super::{resolve_path, Tool};
+
+/// Rows returned when the caller doesn't pass `max_rows`.
+const DEFAULT_MAX_ROWS: usize = 100;
+/// Longest cell shown in markdown output.
+const MARKDOWN_MAX_CELL_CHARS: usize = 200;
+
+/// Result of running one statement.
+#[derive(Debug)]
+enum QueryOutcome {
+    Rows {
+        columns: Vec<String>,
+        rows: Vec<Vec<Value>>,
+        truncated: bool,
+    },
+    Changed(usize),
+}
+
+/// Run a SQL query against a SQLite database file.
+pub struct SqliteQuery;
+
+#[async_trait]
+impl Tool for SqliteQuery {
+    fn name(&self) -> &str {
+        "sqlite_query"
+    }
+
+    fn description(&self) -> &str {
+        "Run a SQL statement against a SQLite database file and get the rows back as a markdown table or JSON. Use '?' placeholders with 'params' instead of inlining values. Opens the database read-only unless allow_write is true. Prefer this over running sqlite3 via run_command."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Database file. Use relative paths (e.g., 'data/app.db') for workspace files."
+                },
+                "sql": {
+                    "type": "string",
+                    "description": "A single SQL statement, e.g. 'SELECT * FROM users WHERE id = ?'"
+                },
+                "params": {
+                    "type": "array",
+                    "description": "Optional: values bound to '?' placeholders, in order"
+                },
+                "format": {
+                    "type": "string",
+                    "enum": ["markdown", "json"],
+                    "description": "Output format (default: markdown)"
+                },
+                "max_rows": {
+                    "type": "integer",
+                    "description": "Optional: maximum rows to return (default: 100)"
+                },
+                "allow_write": {
+                    "type": "boolean",
+                    "description": "Optional: open the database read-write (and create it if missing) to run INSERT/UPDATE/DDL (default: false)"
+                }
+            },
+            "required": ["path", "sql"]
+        })
+    }
+
+    async fn execute(&self, args: Value, working_dir: &Path) -> anyhow::Result<String> {
+        let path = args["path"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing 'path' argument"))?;
+        let sql = args["sql"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing 'sql' argument"))?
+            .to_string();
+        let params = match &args["params"] {
+            Value::Null => Vec::new(),
+            Value::Array(values) => values
+                .iter()
+                .map(json_to_sql)
+                .collect::<anyhow::Result<Vec<_>>>()?,
+            _ => return Err(anyhow::anyhow!("'params' must be an array")),
+        };
+        let format = args["format"].as_str().unwrap_or("markdown").to_string();
+        if format != "markdown" && format != "json" {
+            return Err(anyhow::anyhow!(
+                "Unknown format '{}': use markdown or json",
+                format
+            ));
+        }
+        let max_rows = args["max_rows"]
+            .as_u64()
+            .map(|n| n as usize)
+            .unwrap_or(DEFAULT_MAX_ROWS);
+        let allow_write = args["allow_write"].as_bool().unwrap_or(false);
+
+        let resolution = resolve_path(path, working_dir);
+        if !allow_write && !resolution.resolved.exists() {
+            return Err(anyhow::anyhow!(
+                "Database not found: {} (resolved to: {})",
+                path,
+                resolution.resolved.display()
+            ));
+        }
+
+        let db_path = resolution.resolved.clone();
+        let outcome = tokio::task::spawn_blocking(move || {
+            run_query(&db_path, &sql, params, max_rows, allow_write)
+        })
+        .await??;
+
+        Ok(match outcome {
+            QueryOutcome::Changed(n) => format!("Statement executed: {} row(s) changed", n),
+            QueryOutcome::Rows {
+                columns,
+                rows,
+                truncated,
+            } => {
+                if format == "json" {
+                    let rows: Vec<Value> = rows
+                        .into_iter()
+                        .map(|row| {
+                            let object: Map<String, Value> =
+                                columns.iter().cloned().zip(row).collect();
+                            Value::Object(object)
+                        })
+                        .collect();
+                    serde_json::to_string_pretty(&json!({
+                        "columns": columns,
+                        "rows": rows,
+                        "truncated": truncated,
+                    }))?
+                } else {
+                    render_markdown(&columns, &rows, truncated, max_rows)
+                }
+            }
+        })
+    }
+}
+
+fn json_to_sql(value: &Value) -> anyhow::Result<SqlValue> {
+    Ok(match value {
+        Value::Null => SqlValue::Null,
+        Value::Bool(b) => SqlValue::Integer(i64::from(*b)),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => SqlValue::Integer(i),
+            None => SqlValue::Real(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => SqlValue::Text(s.clone()),
+        other => {
+            return Err(anyhow::anyhow!(
+                "Unsupported parameter {}: use null, a boolean, number or string",
+                other
+            ))
+        }
+    })
+}
+
+fn sql_to_json(value: ValueRef<'_>) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(i) => json!(i),
+        ValueRef::Real(f) => json!(f),
+        ValueRef::Text(t) => Value::String(String::from_utf8_lossy(t).to_string()),
+        ValueRef::Blob(b) => Value::String(format!("<blob {} bytes>", b.len())),
+    }
+}
+
+fn run_query(
+    path: &Path,
+    sql: &str,
+    params: Vec<SqlValue>,
+    max_rows: usize,
+    allow_write: bool,
+) -> anyhow::Result<QueryOutcome> {
+    let flags = if allow_write {
+        OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_CREATE
+    } else {
+        OpenFlags::SQLITE_OPEN_READ_ONLY
+    };
+    let conn = Connection::open_with_flags(path, flags)?;
+    conn.busy_timeout(std::time::Duration::from_secs(5))?;
+
+    let mut stmt = conn.prepare(sql)?;
+    if stmt.column_count() == 0 {
+        let changed = stmt.execute(params_from_iter(params))?;
+        return Ok(QueryOutcome::Changed(changed));
+    }
+
+    let columns: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+    let mut rows = Vec::new();
+    let mut truncated = false;
+    let mut cursor = stmt.query(params_from_iter(params))?;
+    while let Some(row) = cursor.next()? {
+        if rows.len() >= max_rows {
+            truncated = true;
+            break;
+        }
+        let values = (0..columns.len())
+            .map(|i| row.get_ref(i).map(sql_to_json))
+            .collect::<Result<Vec<_>, _>>()?;
+        rows.push(values);
+    }
+    Ok(QueryOutcome::Rows {
+        columns,
+        rows,
+        truncated,
+    })
+}
+
+pub(super) fn render_markdown(
+    columns: &[String],
+    rows: &[Vec<Value>],
+    truncated: bool,
+    max_rows: usize,
+) -> String {
+    let cell = |value: &Value| -> String {
+        let text = match value {
+            Value::Null => "NULL".to_string(),
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        let text = text.replace('|', "\\|").replace('\n', " ");
+        let end = super::safe_truncate_index(&text, MARKDOWN_MAX_CELL_CHARS);
+        if end < text.len() {
+            format!("{}…", &text[..end])
+        } else {
+            text
+        }
+    };
+
+    let mut out = format!("| {} |\n", columns.join(" | "));
+    out.push_str(&format!("|{}\n", " --- |".repeat(columns.len())));
+    for row in rows {
+        let cells: Vec<String> = row.iter().map(cell).collect();
+        out.push_str(&format!("| {} |\n", cells.join(" | ")));
+    }
+    if truncated {
+        out.push_str(&format!(
+            "\n[Showing the first {} rows. Raise max_rows or add LIMIT/OFFSET to see more.]",
+            max_rows
+        ));
+    } else {
+        out.push_str(&format!(
+            "\n({} row{})",
+            rows.len(),
+            if rows.len() == 1 { "" } else { "s" }
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_sqlite_query_read_only_and_params() {
+        let dir = tempfile::tempdir().unwrap();
+        let conn = Connection::open(dir.path().join("app.db")).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE users (id INTEGER, name TEXT);
+             INSERT INTO users VALUES (1, 'ada'), (2, 'grace|hopper'), (3, NULL);",
+        )
+        .unwrap();
+        drop(conn);
+
+        let table = SqliteQuery
+            .execute(
+                json!({"path": "app.db", "sql": "SELECT id, name FROM users WHERE id >= ?", "params": [2]}),
+                dir.path(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            table,
+            "| id | name |\n| --- | --- |\n| 2 | grace\\|hopper |\n| 3 | NULL |\n\n(2 rows)"
+        );
+
+        let limited = SqliteQuery
+            .execute(
+                json!({"path": "app.db", "sql": "SELECT * FROM users", "format": "json", "max_rows": 1}),
+                dir.path(),
+            )
+            .await
+            .unwrap();
+        let limited: Value = serde_json::from_str(&limited).unwrap();
+        assert_eq!(limited["rows"], json!([{"id": 1, "name": "ada"}]));
+        assert_eq!(limited["truncated"], json!(true));
+
+        let write = json!({"path": "app.db", "sql": "DELETE FROM users"});
+        assert!(SqliteQuery
+            .execute(write.clone(), dir.path())
+            .await
+            .is_err());
+        let mut write = write;
+        write["allow_write"] = json!(true);
+        assert_eq!(
+            SqliteQuery.execute(write, dir.path()).await.unwrap(),
+            "Statement executed: 3 row(s) changed"
+        );
+    }
+}
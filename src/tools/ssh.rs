@@ -0,0 +1,368 @@
+//! Remote command execution over SSH.
+//!
+//! Hosts come from `SANDBOXED_SH_SSH_HOSTS` (see `config::SshConfig`); the
+//! agent picks a profile by name and never supplies its own destination or
+//! credentials. A host's private key is read from the secrets vault and
+//! written to a private temporary file for the duration of the call.
+//!
+//! When a profile has `allowed_commands`, the command must match one of the
+//! globs and may not contain shell operators, so an allowed prefix cannot be
+//! chained into something else.
+
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::process::Command;
+
+use super::web::resolve_secret;
+use super::{PrivateTempFile, Tool};
+use crate::config::{SshConfig, SshHost};
+
+const DEFAULT_TIMEOUT_SECS: u64 = 120;
+const MAX_TIMEOUT_SECS: u64 = 1800;
+const CONNECT_TIMEOUT_SECS: u64 = 15;
+const DEFAULT_MAX_OUTPUT_BYTES: usize = 64 * 1024;
+/// Characters that let a shell run more than the matched command.
+const SHELL_OPERATORS: &[char] = &[';', '&', '|', '`', '$', '<', '>', '\n', '\r', '(', ')'];
+
+/// Simple glob matching (`*` wildcard) over the whole command.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // No wildcard: exact match.
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(idx) => rest = &rest[idx + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+/// Check `command` against the host's allowlist (empty = anything).
+fn check_command(host: &SshHost, command: &str) -> anyhow::Result<()> {
+    if host.allowed_commands.is_empty() {
+        return Ok(());
+    }
+    if command.contains(SHELL_OPERATORS) {
+        return Err(anyhow::anyhow!(
+            "Host '{}' only allows listed commands; shell operators ({}) are not permitted",
+            host.name,
+            "; & | ` $ < > ( ) newline"
+        ));
+    }
+    if !host
+        .allowed_commands
+        .iter()
+        .any(|pattern| glob_match(pattern.trim(), command))
+    {
+        return Err(anyhow::anyhow!(
+            "Command not allowed on '{}'. Allowed: {}",
+            host.name,
+            host.allowed_commands.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+fn validate_profile(host: &SshHost) -> anyhow::Result<()> {
+    let safe = |value: &str| {
+        !value.is_empty()
+            && !value.starts_with('-')
+            && value
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "-_.:[]".contains(c))
+    };
+    if !safe(&host.host) || !safe(&host.user) {
+        return Err(anyhow::anyhow!(
+            "SSH host '{}' has an invalid host or user",
+            host.name
+        ));
+    }
+    Ok(())
+}
+
+/// `known_hosts` entry for a pinned host key.
+fn known_hosts_line(host: &SshHost, key: &str) -> String {
+    if host.port == 22 {
+        format!("{} {}\n", host.host, key.trim())
+    } else {
+        format!("[{}]:{} {}\n", host.host, host.port, key.trim())
+    }
+}
+
+/// Read up to `cap` bytes, then keep draining so the process never blocks on
+/// a full pipe. Returns the kept bytes and the total size.
+async fn read_capped<R: AsyncRead + Unpin>(mut reader: R, cap: usize) -> (Vec<u8>, usize) {
+    let mut kept = Vec::new();
+    let mut total = 0usize;
+    let mut buf = [0u8; 8192];
+    loop {
+        match reader.read(&mut buf).await {
+            Ok(0) | Err(_) => break,
+            Ok(n) => {
+                if kept.len() < cap {
+                    let take = n.min(cap - kept.len());
+                    kept.extend_from_slice(&buf[..take]);
+                }
+                total += n;
+            }
+        }
+    }
+    (kept, total)
+}
+
+fn render_stream(label: &str, bytes: &[u8], total: usize) -> String {
+    let text = String::from_utf8_lossy(bytes);
+    let mut out = format!("{}:\n{}", label, text.trim_end());
+    if total > bytes.len() {
+        out.push_str(&format!(
+            "\n[... output capped: {} of {} bytes shown ...]",
+            bytes.len(),
+            total
+        ));
+    }
+    out
+}
+
+/// Run a command on a configured remote host.
+pub struct SshExec;
+
+#[async_trait]
+impl Tool for SshExec {
+    fn name(&self) -> &str {
+        "ssh_exec"
+    }
+
+    fn description(&self) -> &str {
+        "Run a shell command on a remote host configured by the operator. Pass the host profile name (call with host='' to list profiles and their allowed commands). Returns the exit code plus capped stdout/stderr."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "host": {
+                    "type": "string",
+                    "description": "Host profile name"
+                },
+                "command": {
+                    "type": "string",
+                    "description": "Command to run on the remote host"
+                },
+                "timeout_secs": {
+                    "type": "integer",
+                    "description": "Timeout in seconds (default: 120, max: 1800)"
+                }
+            },
+            "required": ["host", "command"]
+        })
+    }
+
+    async fn execute(&self, args: Value, _working_dir: &Path) -> anyhow::Result<String> {
+        let config = SshConfig::from_env().map_err(|e| anyhow::anyhow!("{}", e))?;
+        if config.hosts.is_empty() {
+            return Err(anyhow::anyhow!(
+                "No SSH hosts are configured (set SANDBOXED_SH_SSH_HOSTS)"
+            ));
+        }
+        let name = args["host"].as_str().unwrap_or("");
+        let Some(host) = config.hosts.iter().find(|h| h.name == name) else {
+            let profiles: Vec<Value> = config
+                .hosts
+                .iter()
+                .map(|h| {
+                    json!({
+                        "name": h.name,
+                        "destination": format!("{}@{}:{}", h.user, h.host, h.port),
+                        "allowed_commands": h.allowed_commands,
+                    })
+                })
+                .collect();
+            return Err(anyhow::anyhow!(
+                "Unknown SSH host '{}'. Configured hosts:\n{}",
+                name,
+                serde_json::to_string_pretty(&profiles)?
+            ));
+        };
+        validate_profile(host)?;
+
+        let command = args["command"]
+            .as_str()
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'command' argument"))?;
+        check_command(host, command)?;
+
+        let key_file = match &host.key_secret {
+            Some(reference) => {
+                let mut key = resolve_secret(reference).await?;
+                // OpenSSH rejects keys without a trailing newline.
+                if !key.ends_with('\n') {
+                    key.push('\n');
+                }
+                Some(PrivateTempFile::create("ssh-key", &key)?)
+            }
+            None => None,
+        };
+        let known_hosts = match &host.host_key {
+            Some(key) => Some(PrivateTempFile::create(
+                "known-hosts",
+                &known_hosts_line(host, key),
+            )?),
+            None => None,
+        };
+
+        let mut cmd = Command::new("ssh");
+        cmd.args(["-o", "BatchMode=yes", "-o"])
+            .arg(format!("ConnectTimeout={}", CONNECT_TIMEOUT_SECS))
+            .arg("-p")
+            .arg(host.port.to_string())
+            .arg("-l")
+            .arg(&host.user);
+        if let Some(key) = &key_file {
+            cmd.arg("-i")
+                .arg(&key.path)
+                .args(["-o", "IdentitiesOnly=yes"]);
+        }
+        match &known_hosts {
+            Some(file) => {
+                cmd.arg("-o")
+                    .arg(format!("UserKnownHostsFile={}", file.path.display()))
+                    .args(["-o", "StrictHostKeyChecking=yes"]);
+            }
+            None => {
+                cmd.args(["-o", "StrictHostKeyChecking=accept-new"]);
+            }
+        }
+        cmd.arg("--")
+            .arg(&host.host)
+            .arg(command)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        let timeout = args["timeout_secs"]
+            .as_u64()
+            .unwrap_or(DEFAULT_TIMEOUT_SECS)
+            .clamp(1, MAX_TIMEOUT_SECS);
+        let cap = host.max_output_bytes.unwrap_or(DEFAULT_MAX_OUTPUT_BYTES);
+
+        let mut child = cmd
+            .spawn()
+            .map_err(|e| anyhow::anyhow!("Failed to run ssh (is it installed?): {}", e))?;
+        let stdout = child.stdout.take().expect("piped stdout");
+        let stderr = child.stderr.take().expect("piped stderr");
+        let run = async {
+            let (out, err) = tokio::join!(read_capped(stdout, cap), read_capped(stderr, cap));
+            let status = child.wait().await;
+            (out, err, status)
+        };
+        let (out, err, status) = tokio::time::timeout(Duration::from_secs(timeout), run)
+            .await
+            .map_err(|_| {
+                anyhow::anyhow!("Command on '{}' timed out after {}s", host.name, timeout)
+            })?;
+        drop(key_file);
+        drop(known_hosts);
+
+        let status = status?;
+        // ssh itself exits with 255 on connection or auth failures.
+        let exit = match status.code() {
+            Some(255) => "255 (ssh connection or authentication failure)".to_string(),
+            Some(code) => code.to_string(),
+            None => "killed".to_string(),
+        };
+        let mut result = format!("Host: {}\nExit code: {}\n\n", host.name, exit);
+        result.push_str(&render_stream("stdout", &out.0, out.1));
+        if out.1 > 0 || err.1 > 0 {
+            result.push_str("\n\n");
+            result.push_str(&render_stream("stderr", &err.0, err.1));
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(allowed: &[&str]) -> SshHost {
+        SshHost {
+            name: "web1".to_string(),
+            host: "10.0.0.5".to_string(),
+            port: 22,
+            user: "deploy".to_string(),
+            key_secret: None,
+            host_key: None,
+            allowed_commands: allowed.iter().map(|s| s.to_string()).collect(),
+            max_output_bytes: None,
+        }
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("uptime", "uptime"));
+        assert!(!glob_match("uptime", "uptime -p"));
+        assert!(glob_match("systemctl status *", "systemctl status nginx"));
+        assert!(glob_match(
+            "journalctl -u * --since *",
+            "journalctl -u app --since 1h"
+        ));
+        assert!(!glob_match("systemctl status *", "systemctl restart nginx"));
+        assert!(glob_match("*", "anything"));
+    }
+
+    #[test]
+    fn test_command_allowlist() {
+        assert!(check_command(&host(&[]), "rm -rf /tmp/x; reboot").is_ok());
+
+        let restricted = host(&["systemctl status *", "df -h"]);
+        assert!(check_command(&restricted, "systemctl status nginx").is_ok());
+        assert!(check_command(&restricted, "df -h").is_ok());
+        assert!(check_command(&restricted, "systemctl stop nginx").is_err());
+        assert!(check_command(&restricted, "systemctl status x; reboot").is_err());
+        assert!(check_command(&restricted, "systemctl status $(reboot)").is_err());
+        assert!(check_command(&restricted, "df -h\nreboot").is_err());
+    }
+
+    #[test]
+    fn test_profile_validation_and_known_hosts() {
+        assert!(validate_profile(&host(&[])).is_ok());
+        let mut bad = host(&[]);
+        bad.host = "-oProxyCommand=evil".to_string();
+        assert!(validate_profile(&bad).is_err());
+
+        let mut h = host(&[]);
+        assert_eq!(
+            known_hosts_line(&h, "ssh-ed25519 AAA"),
+            "10.0.0.5 ssh-ed25519 AAA\n"
+        );
+        h.port = 2222;
+        assert_eq!(
+            known_hosts_line(&h, "ssh-ed25519 AAA"),
+            "[10.0.0.5]:2222 ssh-ed25519 AAA\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_capped() {
+        let data = vec![b'x'; 20_000];
+        let (kept, total) = read_capped(&data[..], 100).await;
+        assert_eq!(kept.len(), 100);
+        assert_eq!(total, 20_000);
+        assert!(render_stream("stdout", &kept, total).contains("100 of 20000"));
+    }
+}
@@ -14,10 +14,11 @@ use std::time::Duration;
 
 use async_trait::async_trait;
 use serde_json::{json, Value};
-use tokio::io::AsyncWriteExt;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
 use tokio::process::Command;
 
 use super::{resolve_path_simple as resolve_path, Tool};
+use crate::config::CommandConfig;
 use crate::nspawn;
 
 /// Context information read from the local context file.
@@ -58,6 +59,63 @@ mod tests {
 
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn read_tail_keeps_last_bytes() {
+        let data: Vec<u8> = (0..50_000u32).map(|i| b'a' + (i % 26) as u8).collect();
+        let tail = read_tail(&data[..], 100).await;
+        let text = String::from_utf8(tail).unwrap();
+        assert!(text.starts_with("[... 49900 earlier bytes omitted ...]\n"));
+        assert!(text.ends_with(std::str::from_utf8(&data[49_900..]).unwrap()));
+
+        assert_eq!(read_tail(&b"short"[..], 100).await, b"short");
+    }
+
+    #[test]
+    fn env_allowlist_filters_call_env() {
+        let config = CommandConfig {
+            env_allowlist: vec!["NODE_ENV".to_string(), "APP_*".to_string()],
+            ..CommandConfig::default()
+        };
+        let args = json!({"env": {"NODE_ENV": "test", "APP_PORT": "8080", "LD_PRELOAD": "/x.so"}});
+        let (envs, rejected) = parse_env_filtered(&args, &config);
+        assert_eq!(envs.len(), 2);
+        assert_eq!(envs["APP_PORT"], "8080");
+        assert_eq!(rejected, vec!["LD_PRELOAD".to_string()]);
+    }
+
+    #[test]
+    fn timeout_is_capped_by_config() {
+        let config = CommandConfig {
+            max_timeout_secs: 10.0,
+            ..CommandConfig::default()
+        };
+        assert_eq!(
+            parse_timeout(&json!({"timeout_secs": 9999}), &config),
+            Duration::from_secs(10)
+        );
+        assert_eq!(
+            parse_timeout(&json!({"timeout_ms": 1500}), &config),
+            Duration::from_millis(1500)
+        );
+    }
+
+    #[tokio::test]
+    async fn timed_out_command_is_killed_with_partial_output() {
+        let options = CommandOptions {
+            timeout: Duration::from_millis(500),
+            ..parse_command_options(&json!({}))
+        };
+        let args = vec!["-c".to_string(), "echo started; sleep 30".to_string()];
+        let started = std::time::Instant::now();
+        let err = run_shell_command("/bin/sh", &args, None, &options)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert!(err.contains("timed out"), "{}", err);
+        assert!(err.contains("started"), "{}", err);
+    }
 }
 
 /// Read context information from the local context file or fall back to env vars.
@@ -171,7 +229,7 @@ const DANGEROUS_PATTERNS: &[(&str, &str)] = &[
 
 /// Validate a command against dangerous patterns.
 /// Returns Ok(()) if safe, Err with suggestion if blocked.
-fn validate_command(cmd: &str) -> Result<(), String> {
+pub(super) fn validate_command(cmd: &str) -> Result<(), String> {
     if allow_dangerous_commands() {
         return Ok(());
     }
@@ -270,7 +328,7 @@ fn allow_dangerous_commands() -> bool {
     )
 }
 
-fn container_root_from_env() -> Option<PathBuf> {
+pub(super) fn container_root_from_env() -> Option<PathBuf> {
     let workspace_type = env::var("SANDBOXED_SH_WORKSPACE_TYPE").ok()?;
     if workspace_type != "container" {
         return None;
@@ -288,60 +346,68 @@ fn container_root_from_env() -> Option<PathBuf> {
 }
 
 #[derive(Debug, Clone)]
-struct CommandOptions {
+pub(super) struct CommandOptions {
     timeout: Duration,
     env: HashMap<String, String>,
+    rejected_env: Vec<String>,
     clear_env: bool,
     stdin: Option<String>,
     shell: Option<String>,
     max_output_chars: usize,
+    max_stdout_bytes: usize,
+    max_stderr_bytes: usize,
     raw_output: bool,
 }
 
 const DEFAULT_MAX_OUTPUT_CHARS: usize = 10_000;
 const MAX_OUTPUT_CHARS_LIMIT: usize = 50_000;
-const DEFAULT_COMMAND_TIMEOUT_SECS: f64 = 300.0;
 
-fn default_timeout_from_env() -> Duration {
-    if let Ok(raw) = env::var("SANDBOXED_SH_COMMAND_TIMEOUT_SECS") {
-        if let Ok(value) = raw.parse::<f64>() {
-            if value > 0.0 {
-                return Duration::from_secs_f64(value);
-            }
-        }
-    }
-    Duration::from_secs_f64(DEFAULT_COMMAND_TIMEOUT_SECS)
-}
-
-fn parse_timeout(args: &Value) -> Duration {
-    if let Some(ms) = args.get("timeout_ms").and_then(|v| v.as_u64()) {
-        return Duration::from_millis(ms.max(1));
-    }
-    if let Some(secs) = args.get("timeout_secs").and_then(|v| v.as_u64()) {
-        return Duration::from_secs(secs.max(1));
-    }
-    if let Some(secs) = args.get("timeout").and_then(|v| v.as_f64()) {
-        if secs > 0.0 {
-            return Duration::from_secs_f64(secs);
+fn parse_timeout(args: &Value, config: &CommandConfig) -> Duration {
+    let requested = if let Some(ms) = args.get("timeout_ms").and_then(|v| v.as_u64()) {
+        Duration::from_millis(ms.max(1))
+    } else if let Some(secs) = args.get("timeout_secs").and_then(|v| v.as_u64()) {
+        Duration::from_secs(secs.max(1))
+    } else {
+        match args.get("timeout").and_then(|v| v.as_f64()) {
+            Some(secs) if secs > 0.0 => Duration::from_secs_f64(secs),
+            _ => Duration::from_secs_f64(config.default_timeout_secs),
         }
-    }
-    default_timeout_from_env()
+    };
+    requested.min(Duration::from_secs_f64(config.max_timeout_secs))
 }
 
-fn parse_env(args: &Value) -> HashMap<String, String> {
+/// Split the `env` argument into variables the allowlist accepts and the
+/// names it rejected.
+fn parse_env_filtered(
+    args: &Value,
+    config: &CommandConfig,
+) -> (HashMap<String, String>, Vec<String>) {
     let mut envs = HashMap::new();
+    let mut rejected = Vec::new();
     let Some(obj) = args.get("env").and_then(|v| v.as_object()) else {
-        return envs;
+        return (envs, rejected);
     };
     for (key, value) in obj.iter() {
         if let Some(val) = value.as_str() {
-            envs.insert(key.clone(), val.to_string());
+            if config.env_allowed(key) {
+                envs.insert(key.clone(), val.to_string());
+            } else {
+                rejected.push(key.clone());
+            }
         }
     }
-    envs
+    if !rejected.is_empty() {
+        rejected.sort();
+        tracing::warn!(keys = ?rejected, "Dropped env vars not in the command allowlist");
+    }
+    (envs, rejected)
 }
 
-fn workspace_env_vars() -> HashMap<String, String> {
+pub(super) fn parse_env(args: &Value) -> HashMap<String, String> {
+    parse_env_filtered(args, &CommandConfig::from_env()).0
+}
+
+pub(super) fn workspace_env_vars() -> HashMap<String, String> {
     let mut envs = HashMap::new();
     if let Ok(raw_path) = env::var("SANDBOXED_SH_WORKSPACE_ENV_VARS_FILE") {
         let path = raw_path.trim();
@@ -398,10 +464,20 @@ fn parse_max_output_chars(args: &Value) -> usize {
     max.clamp(1, MAX_OUTPUT_CHARS_LIMIT)
 }
 
-fn parse_command_options(args: &Value) -> CommandOptions {
+fn parse_byte_cap(args: &Value, key: &str, limit: usize) -> usize {
+    args.get(key)
+        .and_then(|v| v.as_u64())
+        .map(|v| (v as usize).clamp(1, limit))
+        .unwrap_or(limit)
+}
+
+pub(super) fn parse_command_options(args: &Value) -> CommandOptions {
+    let config = CommandConfig::from_env();
+    let (env, rejected_env) = parse_env_filtered(args, &config);
     CommandOptions {
-        timeout: parse_timeout(args),
-        env: parse_env(args),
+        timeout: parse_timeout(args, &config),
+        env,
+        rejected_env,
         clear_env: args
             .get("clear_env")
             .and_then(|v| v.as_bool())
@@ -415,6 +491,8 @@ fn parse_command_options(args: &Value) -> CommandOptions {
             .and_then(|v| v.as_str())
             .map(|s| s.to_string()),
         max_output_chars: parse_max_output_chars(args),
+        max_stdout_bytes: parse_byte_cap(args, "max_stdout_bytes", config.max_stdout_bytes),
+        max_stderr_bytes: parse_byte_cap(args, "max_stderr_bytes", config.max_stderr_bytes),
         raw_output: args.get("raw").and_then(|v| v.as_bool()).unwrap_or(false),
     }
 }
@@ -427,7 +505,7 @@ fn shell_exists(shell: &str, container_root: Option<&Path>) -> bool {
     Path::new(shell).exists()
 }
 
-fn resolve_shell(shell: Option<&str>, container_root: Option<&Path>) -> String {
+pub(super) fn resolve_shell(shell: Option<&str>, container_root: Option<&Path>) -> String {
     if let Some(shell) = shell {
         if shell_exists(shell, container_root) {
             return shell.to_string();
@@ -473,12 +551,25 @@ async fn run_shell_command(
     }
     cmd.stdin(Stdio::piped())
         .stdout(Stdio::piped())
-        .stderr(Stdio::piped());
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    // Own process group so a timeout can take down everything the shell started.
+    #[cfg(unix)]
+    cmd.process_group(0);
 
     let mut child = cmd
         .spawn()
         .map_err(|e| anyhow::anyhow!("Failed to execute command: {}", e))?;
 
+    let stdout = child.stdout.take().map(|out| {
+        let cap = options.max_stdout_bytes;
+        tokio::spawn(async move { read_tail(out, cap).await })
+    });
+    let stderr = child.stderr.take().map(|err| {
+        let cap = options.max_stderr_bytes;
+        tokio::spawn(async move { read_tail(err, cap).await })
+    });
+
     if let Some(input) = options.stdin.as_deref() {
         if let Some(mut stdin) = child.stdin.take() {
             stdin
@@ -487,16 +578,105 @@ async fn run_shell_command(
                 .map_err(|e| anyhow::anyhow!("Failed to write to stdin: {}", e))?;
         }
     }
+    // Close stdin so commands reading it see EOF.
+    drop(child.stdin.take());
+
+    let status = match tokio::time::timeout(options.timeout, child.wait()).await {
+        Ok(Ok(status)) => status,
+        Ok(Err(e)) => return Err(anyhow::anyhow!("Failed to execute command: {}", e)),
+        Err(_) => {
+            if let Some(pid) = child.id() {
+                let _ = tokio::task::spawn_blocking(move || {
+                    crate::workspace_exec::kill_tree_by_pid(pid)
+                })
+                .await;
+            }
+            let _ = child.kill().await;
+            let mut partial = String::new();
+            for (label, reader) in [("stdout", stdout), ("stderr", stderr)] {
+                let bytes = collect_tail(reader, Duration::from_secs(2)).await;
+                let text = sanitize_output(&bytes);
+                if !text.trim().is_empty() {
+                    partial.push_str(&format!(
+                        "\n--- {} (partial) ---\n{}",
+                        label,
+                        tail_chars(&text, 2000)
+                    ));
+                }
+            }
+            return Err(anyhow::anyhow!(
+                "Command timed out after {} seconds and was killed{}",
+                options.timeout.as_secs_f64(),
+                partial
+            ));
+        }
+    };
+
+    // Background children that inherited the pipes can keep them open after
+    // the shell exits; don't wait on them forever.
+    let stdout = collect_tail(stdout, Duration::from_secs(5)).await;
+    let stderr = collect_tail(stderr, Duration::from_secs(5)).await;
+    Ok(Output {
+        status,
+        stdout,
+        stderr,
+    })
+}
+
+/// Read a stream to the end, keeping only its last `cap` bytes. When bytes
+/// were dropped, the result starts with a marker saying how many.
+async fn read_tail<R: AsyncRead + Unpin>(mut reader: R, cap: usize) -> Vec<u8> {
+    let mut tail: Vec<u8> = Vec::new();
+    let mut dropped: u64 = 0;
+    let mut chunk = [0u8; 8192];
+    loop {
+        match reader.read(&mut chunk).await {
+            Ok(0) | Err(_) => break,
+            Ok(n) => {
+                tail.extend_from_slice(&chunk[..n]);
+                // Trim in batches rather than on every read.
+                if tail.len() > cap.saturating_mul(2).max(cap + chunk.len()) {
+                    let excess = tail.len() - cap;
+                    tail.drain(..excess);
+                    dropped += excess as u64;
+                }
+            }
+        }
+    }
+    if tail.len() > cap {
+        let excess = tail.len() - cap;
+        tail.drain(..excess);
+        dropped += excess as u64;
+    }
+    if dropped > 0 {
+        let mut marked = format!("[... {} earlier bytes omitted ...]\n", dropped).into_bytes();
+        marked.extend_from_slice(&tail);
+        return marked;
+    }
+    tail
+}
 
-    let output = tokio::time::timeout(options.timeout, child.wait_with_output()).await;
+/// The last `max` characters of `text`.
+fn tail_chars(text: &str, max: usize) -> &str {
+    if max == 0 {
+        return "";
+    }
+    match text.char_indices().rev().nth(max - 1) {
+        Some((idx, _)) => &text[idx..],
+        None => text,
+    }
+}
 
-    match output {
-        Ok(Ok(output)) => Ok(output),
-        Ok(Err(e)) => Err(anyhow::anyhow!("Failed to execute command: {}", e)),
-        Err(_) => Err(anyhow::anyhow!(
-            "Command timed out after {} seconds",
-            options.timeout.as_secs_f64()
-        )),
+async fn collect_tail(reader: Option<tokio::task::JoinHandle<Vec<u8>>>, wait: Duration) -> Vec<u8> {
+    let Some(mut handle) = reader else {
+        return Vec::new();
+    };
+    match tokio::time::timeout(wait, &mut handle).await {
+        Ok(Ok(bytes)) => bytes,
+        _ => {
+            handle.abort();
+            Vec::new()
+        }
     }
 }
 
@@ -567,19 +747,11 @@ fn read_runtime_display() -> Option<String> {
     }
 }
 
-async fn run_container_command(
-    container_root: &Path,
-    cwd: &Path,
-    command: &str,
-    options: &CommandOptions,
-) -> anyhow::Result<Output> {
-    let root = container_root
-        .canonicalize()
-        .unwrap_or_else(|_| container_root.to_path_buf());
+/// Map a host working directory to the corresponding path inside the container.
+pub(super) fn container_cwd(root: &Path, cwd: &Path) -> String {
     let cwd = cwd.canonicalize().unwrap_or_else(|_| cwd.to_path_buf());
-
-    let rel_str = if cwd.starts_with(&root) {
-        let rel = cwd.strip_prefix(&root).unwrap_or_else(|_| Path::new(""));
+    if cwd.starts_with(root) {
+        let rel = cwd.strip_prefix(root).unwrap_or_else(|_| Path::new(""));
         if rel.as_os_str().is_empty() {
             "/".to_string()
         } else {
@@ -591,7 +763,19 @@ async fn run_container_command(
         cwd.to_string_lossy().to_string()
     } else {
         "/".to_string()
-    };
+    }
+}
+
+async fn run_container_command(
+    container_root: &Path,
+    cwd: &Path,
+    command: &str,
+    options: &CommandOptions,
+) -> anyhow::Result<Output> {
+    let root = container_root
+        .canonicalize()
+        .unwrap_or_else(|_| container_root.to_path_buf());
+    let rel_str = container_cwd(&root, cwd);
 
     // If a container is already running (e.g., MCP server), run commands via nsenter.
     if let Ok(machine_name) = env::var("SANDBOXED_SH_WORKSPACE_NAME") {
@@ -705,7 +889,10 @@ async fn run_container_command(
     Ok(output)
 }
 
-async fn running_container_leader(machine_name: &str, options: &CommandOptions) -> Option<String> {
+pub(super) async fn running_container_leader(
+    machine_name: &str,
+    options: &CommandOptions,
+) -> Option<String> {
     let machinectl = if Path::new("/usr/bin/machinectl").exists() {
         "/usr/bin/machinectl"
     } else {
@@ -732,7 +919,7 @@ async fn running_container_leader(machine_name: &str, options: &CommandOptions)
     }
 }
 
-fn nsenter_command(rel_str: &str, command: &str) -> String {
+pub(super) fn nsenter_command(rel_str: &str, command: &str) -> String {
     let mut exports = Vec::new();
     exports.push("export SANDBOXED_SH_CONTEXT_ROOT=/root/context".to_string());
     if let Ok(context_dir) = env::var("SANDBOXED_SH_CONTEXT_DIR_NAME") {
@@ -814,7 +1001,7 @@ impl Tool for RunCommand {
                 },
                 "timeout_secs": {
                     "type": "integer",
-                    "description": "Timeout in seconds (default: 60)."
+                    "description": "Timeout in seconds (default: 300, capped by the server's maximum). The command and its children are killed when it expires."
                 },
                 "timeout_ms": {
                     "type": "integer",
@@ -826,7 +1013,7 @@ impl Tool for RunCommand {
                 },
                 "env": {
                     "type": "object",
-                    "description": "Environment variables to set for the command.",
+                    "description": "Environment variables to set for the command. Names outside the server's allowlist are dropped.",
                     "additionalProperties": { "type": "string" }
                 },
                 "clear_env": {
@@ -845,6 +1032,14 @@ impl Tool for RunCommand {
                     "type": "integer",
                     "description": "Maximum output characters to return (default: 10000)."
                 },
+                "max_stdout_bytes": {
+                    "type": "integer",
+                    "description": "Keep only the last N bytes of stdout (default and maximum set by the server, 1 MiB unless configured)."
+                },
+                "max_stderr_bytes": {
+                    "type": "integer",
+                    "description": "Keep only the last N bytes of stderr (default and maximum set by the server, 1 MiB unless configured)."
+                },
                 "raw": {
                     "type": "boolean",
                     "description": "Return combined stdout/stderr only (no headers or exit code)."
@@ -930,8 +1125,17 @@ impl Tool for RunCommand {
         };
 
         let mut result = result;
+        if !options.rejected_env.is_empty() && !options.raw_output {
+            result.push_str(&format!(
+                "\nNote: env vars not in the allowlist were not set: {}\n",
+                options.rejected_env.join(", ")
+            ));
+        }
         if result.len() > options.max_output_chars {
-            result.truncate(options.max_output_chars);
+            result.truncate(super::safe_truncate_index(
+                &result,
+                options.max_output_chars,
+            ));
             result.push_str("\n... [output truncated]");
         }
 
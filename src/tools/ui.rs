@@ -9,6 +9,7 @@ use serde_json::{json, Value};
 use std::path::Path;
 
 use super::Tool;
+use crate::config::ToolTimeoutAction;
 
 /// Ask the user to pick from a list of options (interactive).
 pub struct UiOptionList;
@@ -91,3 +92,517 @@ impl Tool for UiDataTable {
         Ok(serde_json::to_string(&args).unwrap_or_else(|_| args.to_string()))
     }
 }
+
+/// Render a chart (non-interactive).
+pub struct UiChart;
+
+#[async_trait]
+impl Tool for UiChart {
+    fn name(&self) -> &str {
+        "ui_chart"
+    }
+
+    fn description(&self) -> &str {
+        "Render a line/bar/area/pie/scatter chart in the dashboard (frontend Tool UI). Pass rows in 'data' with an x column ('xKey', default 'x') and the numeric columns to plot in 'series', or per-series arrays: 'labels' plus series: [{name, data: [numbers]}]."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "required": ["id", "series"],
+            "properties": {
+                "id": { "type": "string" },
+                "title": { "type": "string" },
+                "description": { "type": "string" },
+                "type": {
+                    "type": "string",
+                    "enum": ["line", "bar", "area", "pie", "scatter"],
+                    "default": "line"
+                },
+                "xKey": { "type": "string", "description": "Column in 'data' used for the x axis (default 'x'); category names for pie charts." },
+                "xLabel": { "type": "string" },
+                "yLabel": { "type": "string" },
+                "stacked": { "type": "boolean", "description": "Stack bar/area series." },
+                "data": {
+                    "type": "array",
+                    "items": { "type": "object" },
+                    "description": "Rows, e.g. [{\"x\": \"Mon\", \"latency\": 120}]."
+                },
+                "labels": {
+                    "type": "array",
+                    "description": "x values for per-series number arrays."
+                },
+                "series": {
+                    "type": "array",
+                    "minItems": 1,
+                    "description": "Column keys (strings or {key, label, color}) for row data, or {name, data, color} per-series arrays."
+                }
+            },
+            "additionalProperties": true
+        })
+    }
+
+    async fn execute(&self, args: Value, _workspace: &Path) -> anyhow::Result<String> {
+        Ok(serde_json::to_string(&args).unwrap_or_else(|_| args.to_string()))
+    }
+}
+
+/// Render a browsable tree of files the agent produced (non-interactive).
+pub struct UiFileTree;
+
+#[async_trait]
+impl Tool for UiFileTree {
+    fn name(&self) -> &str {
+        "ui_fileTree"
+    }
+
+    fn description(&self) -> &str {
+        "Present generated files/deliverables as a browsable tree with download links (frontend Tool UI). Use it at the end of a mission to list the artifacts you produced. Relative paths are resolved against the mission workspace."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "required": ["id", "files"],
+            "properties": {
+                "id": { "type": "string" },
+                "title": { "type": "string" },
+                "description": { "type": "string" },
+                "root": { "type": "string", "description": "Directory the paths are relative to (default: mission workspace)." },
+                "files": {
+                    "type": "array",
+                    "minItems": 1,
+                    "description": "Paths as strings, or {path, size, description} with size in bytes.",
+                    "items": {
+                        "type": ["string", "object"],
+                        "properties": {
+                            "path": { "type": "string" },
+                            "size": { "type": "integer", "minimum": 0 },
+                            "description": { "type": "string" }
+                        }
+                    }
+                }
+            },
+            "additionalProperties": true
+        })
+    }
+
+    async fn execute(&self, args: Value, _workspace: &Path) -> anyhow::Result<String> {
+        Ok(serde_json::to_string(&args).unwrap_or_else(|_| args.to_string()))
+    }
+}
+
+/// Ask the user to fill in a structured form (interactive).
+pub struct UiForm;
+
+#[async_trait]
+impl Tool for UiForm {
+    fn name(&self) -> &str {
+        "ui_form"
+    }
+
+    fn description(&self) -> &str {
+        "Render an interactive form to collect several related inputs in one step, e.g. deploy target + environment + confirmation (frontend Tool UI). The answer is {\"values\": {field_id: value}}, or null if the user cancels. Don't ask for secrets."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        // Intentionally permissive: we validate on the frontend before rendering.
+        json!({
+            "type": "object",
+            "required": ["id", "fields"],
+            "properties": {
+                "id": { "type": "string", "description": "Stable identifier for this UI element." },
+                "title": { "type": "string" },
+                "description": { "type": "string" },
+                "submitLabel": { "type": "string" },
+                "fields": {
+                    "type": "array",
+                    "minItems": 1,
+                    "items": {
+                        "type": "object",
+                        "required": ["id"],
+                        "properties": {
+                            "id": { "type": "string", "description": "Key of this field in the submitted values." },
+                            "label": { "type": "string" },
+                            "type": {
+                                "type": "string",
+                                "enum": ["text", "textarea", "number", "boolean", "select", "email", "url"],
+                                "default": "text"
+                            },
+                            "description": { "type": "string", "description": "Help text shown under the field." },
+                            "placeholder": { "type": "string" },
+                            "required": { "type": "boolean", "default": true },
+                            "default": { "description": "Initial value (string, number or boolean)." },
+                            "options": {
+                                "type": "array",
+                                "description": "Choices for select fields: strings or {value, label}.",
+                                "items": {}
+                            },
+                            "min": { "type": "number", "description": "Minimum value (number) or length (text)." },
+                            "max": { "type": "number", "description": "Maximum value (number) or length (text)." },
+                            "pattern": { "type": "string", "description": "Regex the whole text value must match." },
+                            "patternHint": { "type": "string", "description": "Shown when pattern doesn't match." }
+                        },
+                        "additionalProperties": true
+                    }
+                }
+            },
+            "additionalProperties": true
+        })
+    }
+
+    async fn execute(&self, args: Value, _workspace: &Path) -> anyhow::Result<String> {
+        Ok(serde_json::to_string(&args).unwrap_or_else(|_| args.to_string()))
+    }
+}
+
+/// Show proposed file changes as a side-by-side diff and wait for the user to
+/// approve or reject them (interactive).
+pub struct UiDiffViewer;
+
+#[async_trait]
+impl Tool for UiDiffViewer {
+    fn name(&self) -> &str {
+        "ui_diffViewer"
+    }
+
+    fn description(&self) -> &str {
+        "Show a side-by-side diff of proposed file changes and ask the user to approve them before you apply them (frontend Tool UI). Pass either 'oldContent' + 'newContent' or a unified 'diff'. The answer is {\"approved\": bool, \"comment\"?: string}, or null if the user dismisses it."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        // Intentionally permissive: we validate on the frontend before rendering.
+        json!({
+            "type": "object",
+            "required": ["id"],
+            "properties": {
+                "id": { "type": "string", "description": "Stable identifier for this UI element." },
+                "title": { "type": "string" },
+                "description": { "type": "string", "description": "Why the change is being proposed." },
+                "path": { "type": "string", "description": "File the change applies to." },
+                "oldContent": { "type": "string", "description": "Current file content (empty for a new file)." },
+                "newContent": { "type": "string", "description": "Proposed file content." },
+                "diff": { "type": "string", "description": "Unified diff, used instead of oldContent/newContent." },
+                "approveLabel": { "type": "string" },
+                "rejectLabel": { "type": "string" }
+            },
+            "additionalProperties": true
+        })
+    }
+
+    async fn execute(&self, args: Value, _workspace: &Path) -> anyhow::Result<String> {
+        Ok(serde_json::to_string(&args).unwrap_or_else(|_| args.to_string()))
+    }
+}
+
+/// Report batch progress (non-interactive). The control loop turns each call
+/// into an `AgentEvent::MissionProgress` so the dashboard can show a real bar.
+pub struct UiProgress;
+
+#[async_trait]
+impl Tool for UiProgress {
+    fn name(&self) -> &str {
+        "ui_progress"
+    }
+
+    fn description(&self) -> &str {
+        "Report progress on a batch job (e.g. 12 of 340 files processed) so the user sees a progress bar. Call it periodically while working through a known number of items; the last call should have current == total."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "required": ["label", "current", "total"],
+            "properties": {
+                "label": { "type": "string", "description": "What is being processed, e.g. 'Transcoding videos'." },
+                "current": { "type": "integer", "minimum": 0, "description": "Items done so far." },
+                "total": { "type": "integer", "minimum": 1, "description": "Total number of items." }
+            }
+        })
+    }
+
+    async fn execute(&self, args: Value, _workspace: &Path) -> anyhow::Result<String> {
+        let (label, current, total) =
+            parse_progress_args("ui_progress", &args).ok_or_else(|| {
+                anyhow::anyhow!("ui_progress needs 'label', 'current' and 'total' (total > 0)")
+            })?;
+        Ok(format!(
+            "Progress reported: {} ({}/{})",
+            label, current, total
+        ))
+    }
+}
+
+/// Extract `(label, current, total)` from a `ui_progress` call (optionally
+/// MCP-qualified). `current` is clamped to `total`; returns `None` for other
+/// tools or malformed args.
+pub fn parse_progress_args(name: &str, args: &Value) -> Option<(String, u64, u64)> {
+    if name != "ui_progress" && !name.ends_with("__ui_progress") {
+        return None;
+    }
+    let number = |key: &str| {
+        let v = args.get(key)?;
+        v.as_u64()
+            .or_else(|| v.as_f64().filter(|f| *f >= 0.0).map(|f| f as u64))
+            .or_else(|| v.as_str()?.trim().parse().ok())
+    };
+    let total = number("total").filter(|t| *t > 0)?;
+    let current = number("current")?.min(total);
+    let label = args
+        .get("label")
+        .and_then(|v| v.as_str())
+        .map(|s| s.chars().take(120).collect())
+        .unwrap_or_else(|| "Progress".to_string());
+    Some((label, current, total))
+}
+
+/// `ui_*` tools that only render output; they never wait for an answer.
+const DISPLAY_ONLY_UI_TOOLS: &[&str] = &["ui_dataTable", "ui_chart", "ui_fileTree", "ui_progress"];
+
+/// Whether a tool call is answered from the dashboard (via `FrontendToolHub`)
+/// instead of being executed. `ask_user` may arrive MCP-qualified
+/// (`mcp__workspace__ask_user`) since it's also served by the workspace MCP.
+pub fn is_frontend_tool(name: &str) -> bool {
+    if DISPLAY_ONLY_UI_TOOLS.contains(&name) {
+        return false;
+    }
+    name == "question"
+        || name.starts_with("ui_")
+        || name == "ask_user"
+        || name.ends_with("__ask_user")
+}
+
+/// Answer given to the agent when nobody answered in time.
+pub const NO_HUMAN_ANSWER: &str = "No human is available to answer right now. Proceed conservatively: pick the safest option, state the assumptions you make, and avoid irreversible actions.";
+
+/// Answer for a frontend tool call nobody answered in time, in the shape the
+/// dashboard would have sent. `None` leaves the call unanswered (`Fail`).
+pub fn unattended_answer(action: ToolTimeoutAction, tool: &str, args: &Value) -> Option<Value> {
+    let tool = tool.rsplit("__").next().unwrap_or(tool);
+    let pick_default = action == ToolTimeoutAction::DefaultOption;
+    match action {
+        ToolTimeoutAction::Fail => None,
+        _ if tool == "question" => {
+            // OpenCode questions: one list of labels per question
+            let questions = args
+                .get("questions")
+                .and_then(Value::as_array)
+                .cloned()
+                .unwrap_or_else(|| vec![args.clone()]);
+            let answers: Vec<Value> = questions
+                .iter()
+                .map(|question| {
+                    let label = pick_default
+                        .then(|| default_option(question))
+                        .flatten()
+                        .and_then(|option| option.get("label").cloned());
+                    json!([label.unwrap_or_else(|| json!(NO_HUMAN_ANSWER))])
+                })
+                .collect();
+            Some(json!({ "answers": answers }))
+        }
+        _ if tool == "ui_diffViewer" => {
+            Some(json!({ "approved": false, "comment": NO_HUMAN_ANSWER }))
+        }
+        ToolTimeoutAction::DefaultOption if tool == "ui_optionList" => {
+            let choice = default_option(args)
+                .and_then(|option| option.get("id").cloned())
+                .unwrap_or_else(|| json!(NO_HUMAN_ANSWER));
+            if args.get("multiple").and_then(Value::as_bool) == Some(true) {
+                Some(json!([choice]))
+            } else {
+                Some(choice)
+            }
+        }
+        ToolTimeoutAction::DefaultOption if tool == "ui_form" => {
+            let fields = args.get("fields").and_then(Value::as_array);
+            let mut values = serde_json::Map::new();
+            for field in fields.into_iter().flatten() {
+                let Some(id) = field.get("id").and_then(Value::as_str) else {
+                    continue;
+                };
+                match field.get("default") {
+                    Some(default) => {
+                        values.insert(id.to_string(), default.clone());
+                    }
+                    None if field.get("required").and_then(Value::as_bool) != Some(false) => {
+                        return Some(json!(NO_HUMAN_ANSWER));
+                    }
+                    None => {}
+                }
+            }
+            Some(json!({ "values": values }))
+        }
+        _ => Some(json!(NO_HUMAN_ANSWER)),
+    }
+}
+
+/// The option marked `"default": true`, else the first one.
+fn default_option(args: &Value) -> Option<&Value> {
+    let options = args.get("options")?.as_array()?;
+    options
+        .iter()
+        .find(|option| option.get("default").and_then(Value::as_bool) == Some(true))
+        .or_else(|| options.first())
+}
+
+/// Ask the user an open question and wait for a free-text, multi-field, or
+/// file answer (interactive).
+pub struct AskUser;
+
+#[async_trait]
+impl Tool for AskUser {
+    fn name(&self) -> &str {
+        "ask_user"
+    }
+
+    fn description(&self) -> &str {
+        "Pause and ask the user a clarifying question; the mission resumes with their answer. The answer can be free text, several named fields, or uploaded files (saved under ./context/, answered with their paths). Never use this to ask for passwords, tokens or other secrets. For picking among fixed choices, prefer ui_optionList."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "required": ["question"],
+            "properties": {
+                "question": { "type": "string", "description": "What you need to know." },
+                "context": { "type": "string", "description": "Optional: why you're asking / what you'll do with the answer." },
+                "fields": {
+                    "type": "array",
+                    "description": "Optional: named inputs. Defaults to a single free-text answer.",
+                    "items": {
+                        "type": "object",
+                        "required": ["id", "label"],
+                        "properties": {
+                            "id": { "type": "string", "description": "Key of this field in the answer." },
+                            "label": { "type": "string" },
+                            "type": {
+                                "type": "string",
+                                "enum": ["text", "textarea", "file"],
+                                "default": "text",
+                                "description": "'file' lets the user upload files; the answer is a list of paths."
+                            },
+                            "placeholder": { "type": "string" },
+                            "required": { "type": "boolean", "default": true },
+                            "multiple": { "type": "boolean", "default": false, "description": "For file fields: allow several files." }
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, _args: Value, _workspace: &Path) -> anyhow::Result<String> {
+        // Interactive missions intercept this call before it gets here.
+        Err(anyhow::anyhow!(
+            "No user is available to answer right now. Continue with a reasonable assumption and state it, or finish and explain what input is needed."
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_frontend_tool() {
+        assert!(is_frontend_tool("question"));
+        assert!(is_frontend_tool("ui_optionList"));
+        assert!(is_frontend_tool("ui_form"));
+        assert!(is_frontend_tool("ui_diffViewer"));
+        assert!(!is_frontend_tool("ui_chart"));
+        assert!(!is_frontend_tool("ui_fileTree"));
+        assert!(is_frontend_tool("ask_user"));
+        assert!(is_frontend_tool("mcp__workspace__ask_user"));
+        assert!(!is_frontend_tool("mcp__workspace__read_file"));
+        assert!(!is_frontend_tool("ask_user_later"));
+        assert!(!is_frontend_tool("ui_progress"));
+    }
+
+    #[test]
+    fn test_unattended_answer() {
+        let options = json!({
+            "id": "pick",
+            "options": [
+                { "id": "allow", "label": "Allow" },
+                { "id": "deny", "label": "Deny", "default": true }
+            ]
+        });
+        assert_eq!(
+            unattended_answer(ToolTimeoutAction::DefaultOption, "ui_optionList", &options),
+            Some(json!("deny"))
+        );
+        assert_eq!(
+            unattended_answer(ToolTimeoutAction::Proceed, "ui_optionList", &options),
+            Some(json!(NO_HUMAN_ANSWER))
+        );
+        assert_eq!(
+            unattended_answer(ToolTimeoutAction::Fail, "ui_optionList", &options),
+            None
+        );
+
+        let question = json!({
+            "questions": [
+                { "question": "Which?", "options": [{ "label": "A" }, { "label": "B" }] },
+                { "question": "Why?" }
+            ]
+        });
+        assert_eq!(
+            unattended_answer(ToolTimeoutAction::DefaultOption, "question", &question),
+            Some(json!({ "answers": [["A"], [NO_HUMAN_ANSWER]] }))
+        );
+
+        let form = json!({
+            "fields": [
+                { "id": "env", "default": "staging" },
+                { "id": "note", "required": false }
+            ]
+        });
+        assert_eq!(
+            unattended_answer(ToolTimeoutAction::DefaultOption, "ui_form", &form),
+            Some(json!({ "values": { "env": "staging" } }))
+        );
+        let form = json!({ "fields": [{ "id": "env" }] });
+        assert_eq!(
+            unattended_answer(ToolTimeoutAction::DefaultOption, "ui_form", &form),
+            Some(json!(NO_HUMAN_ANSWER))
+        );
+        assert_eq!(
+            unattended_answer(
+                ToolTimeoutAction::DefaultOption,
+                "ui_diffViewer",
+                &json!({})
+            ),
+            Some(json!({ "approved": false, "comment": NO_HUMAN_ANSWER }))
+        );
+    }
+
+    #[test]
+    fn test_parse_progress_args() {
+        assert_eq!(
+            parse_progress_args(
+                "mcp__workspace__ui_progress",
+                &json!({ "label": "Files", "current": 12, "total": 340 })
+            ),
+            Some(("Files".to_string(), 12, 340))
+        );
+        assert_eq!(
+            parse_progress_args(
+                "ui_progress",
+                &json!({ "label": "x", "current": "50", "total": 10 })
+            ),
+            Some(("x".to_string(), 10, 10))
+        );
+        assert_eq!(
+            parse_progress_args(
+                "ui_progress",
+                &json!({ "label": "x", "current": 1, "total": 0 })
+            ),
+            None
+        );
+        assert_eq!(
+            parse_progress_args("ui_chart", &json!({ "current": 1, "total": 2 })),
+            None
+        );
+    }
+}
@@ -0,0 +1,516 @@
+//! Vision tool: ask a multimodal model about an image or a desktop screenshot.
+//!
+//! The model is reached through the AI providers configured in the dashboard
+//! (Anthropic, or any OpenAI-compatible API). When no provider store is
+//! available — e.g. inside the workspace MCP process — the usual API key
+//! environment variables are used instead.
+//!
+//! The same providers also serve text-only completions via `complete_text`
+//! (used to summarize oversized tool results and by mission verifiers).
+
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde_json::{json, Value};
+
+use super::{resolve_path, Tool};
+use crate::ai_providers::{AIProvider, ProviderType};
+
+/// Largest image accepted; providers reject bigger payloads.
+const MAX_IMAGE_BYTES: u64 = 5 * 1024 * 1024;
+/// Answer length when the caller doesn't pass `max_tokens`.
+const DEFAULT_MAX_TOKENS: u64 = 1024;
+const REQUEST_TIMEOUT_SECS: u64 = 120;
+const DEFAULT_PROMPT: &str =
+    "Describe this image in detail, including any visible text, UI elements and their positions.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VisionApi {
+    Anthropic,
+    OpenAiCompatible,
+}
+
+/// A resolved model endpoint.
+#[derive(Debug)]
+struct VisionProvider {
+    label: String,
+    api: VisionApi,
+    base_url: String,
+    api_key: Option<String>,
+    model: String,
+}
+
+/// API flavour, default base URL and default vision model for a provider type.
+fn vision_defaults(provider_type: ProviderType) -> Option<(VisionApi, &'static str, &'static str)> {
+    Some(match provider_type {
+        ProviderType::Anthropic => (
+            VisionApi::Anthropic,
+            "https://api.anthropic.com",
+            "claude-sonnet-4-20250514",
+        ),
+        ProviderType::OpenAI => (
+            VisionApi::OpenAiCompatible,
+            "https://api.openai.com/v1",
+            "gpt-4o",
+        ),
+        ProviderType::OpenRouter => (
+            VisionApi::OpenAiCompatible,
+            "https://openrouter.ai/api/v1",
+            "openai/gpt-4o",
+        ),
+        ProviderType::Google => (
+            VisionApi::OpenAiCompatible,
+            "https://generativelanguage.googleapis.com/v1beta/openai",
+            "gemini-2.0-flash",
+        ),
+        ProviderType::Xai => (
+            VisionApi::OpenAiCompatible,
+            "https://api.x.ai/v1",
+            "grok-2-vision-1212",
+        ),
+        ProviderType::Custom => (VisionApi::OpenAiCompatible, "", ""),
+        _ => return None,
+    })
+}
+
+fn provider_from_config(provider: &AIProvider, model: Option<&str>) -> Option<VisionProvider> {
+    let (api, base_url, default_model) = vision_defaults(provider.provider_type)?;
+    // OAuth tokens are scoped to the coding agents; only API keys work here.
+    if provider.api_key.is_none() && provider.provider_type != ProviderType::Custom {
+        return None;
+    }
+    let base_url = provider
+        .base_url
+        .clone()
+        .unwrap_or_else(|| base_url.to_string());
+    let model = model
+        .map(str::to_string)
+        .or_else(|| {
+            provider
+                .custom_models
+                .as_ref()
+                .and_then(|models| models.first())
+                .map(|m| m.id.clone())
+        })
+        .unwrap_or_else(|| default_model.to_string());
+    if base_url.is_empty() || model.is_empty() {
+        return None;
+    }
+    Some(VisionProvider {
+        label: provider.name.clone(),
+        api,
+        base_url,
+        api_key: provider.api_key.clone(),
+        model,
+    })
+}
+
+/// Pick a provider: the requested type if given, otherwise the default
+/// provider, then any other usable one, then API keys from the environment.
+async fn resolve_provider(
+    requested: Option<&str>,
+    model: Option<&str>,
+) -> anyhow::Result<VisionProvider> {
+    let requested = match requested {
+        Some(id) => Some(
+            ProviderType::from_id(id)
+                .ok_or_else(|| anyhow::anyhow!("Unknown provider '{}'", id))?,
+        ),
+        None => None,
+    };
+    let wanted = |t: ProviderType| requested.is_none() || requested == Some(t);
+
+    if let Some(store) = crate::ai_providers::global() {
+        let default = store.get_default().await;
+        let mut providers = store.list().await;
+        providers.sort_by_key(|p| Some(p.id) != default.as_ref().map(|d| d.id));
+        for provider in providers
+            .iter()
+            .filter(|p| p.enabled && wanted(p.provider_type))
+        {
+            if let Some(resolved) = provider_from_config(provider, model) {
+                return Ok(resolved);
+            }
+        }
+    }
+
+    for provider_type in [
+        ProviderType::Anthropic,
+        ProviderType::OpenAI,
+        ProviderType::OpenRouter,
+        ProviderType::Google,
+    ] {
+        if !wanted(provider_type) {
+            continue;
+        }
+        let Some(key) = provider_type
+            .env_var_name()
+            .and_then(|var| std::env::var(var).ok())
+            .filter(|key| !key.trim().is_empty())
+        else {
+            continue;
+        };
+        let mut provider = AIProvider::new(provider_type, provider_type.display_name().to_string());
+        provider.api_key = Some(key);
+        if let Some(resolved) = provider_from_config(&provider, model) {
+            return Ok(resolved);
+        }
+    }
+
+    Err(anyhow::anyhow!(
+        "No vision-capable AI provider is configured. Add an Anthropic, OpenAI, OpenRouter, Google or custom OpenAI-compatible provider with an API key."
+    ))
+}
+
+/// Detect an image's media type from its magic bytes.
+fn image_media_type(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        Some("image/gif")
+    } else if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else {
+        None
+    }
+}
+
+/// Endpoint URL and request body for one image question.
+fn build_request(
+    provider: &VisionProvider,
+    media_type: &str,
+    image_b64: &str,
+    prompt: &str,
+    max_tokens: u64,
+) -> (String, Value) {
+    let base = provider.base_url.trim_end_matches('/');
+    match provider.api {
+        VisionApi::Anthropic => {
+            let url = if base.ends_with("/v1") {
+                format!("{}/messages", base)
+            } else {
+                format!("{}/v1/messages", base)
+            };
+            let body = json!({
+                "model": provider.model,
+                "max_tokens": max_tokens,
+                "messages": [{
+                    "role": "user",
+                    "content": [
+                        {
+                            "type": "image",
+                            "source": {"type": "base64", "media_type": media_type, "data": image_b64}
+                        },
+                        {"type": "text", "text": prompt}
+                    ]
+                }]
+            });
+            (url, body)
+        }
+        VisionApi::OpenAiCompatible => {
+            let url = if base.ends_with("/chat/completions") {
+                base.to_string()
+            } else {
+                format!("{}/chat/completions", base)
+            };
+            let body = json!({
+                "model": provider.model,
+                "max_tokens": max_tokens,
+                "messages": [{
+                    "role": "user",
+                    "content": [
+                        {"type": "text", "text": prompt},
+                        {
+                            "type": "image_url",
+                            "image_url": {"url": format!("data:{};base64,{}", media_type, image_b64)}
+                        }
+                    ]
+                }]
+            });
+            (url, body)
+        }
+    }
+}
+
+/// Endpoint URL and request body for a text-only prompt.
+fn build_text_request(provider: &VisionProvider, prompt: &str, max_tokens: u64) -> (String, Value) {
+    let base = provider.base_url.trim_end_matches('/');
+    let url = match provider.api {
+        VisionApi::Anthropic if base.ends_with("/v1") => format!("{}/messages", base),
+        VisionApi::Anthropic => format!("{}/v1/messages", base),
+        VisionApi::OpenAiCompatible if base.ends_with("/chat/completions") => base.to_string(),
+        VisionApi::OpenAiCompatible => format!("{}/chat/completions", base),
+    };
+    let body = json!({
+        "model": provider.model,
+        "max_tokens": max_tokens,
+        "messages": [{"role": "user", "content": prompt}]
+    });
+    (url, body)
+}
+
+/// Send a request to the provider and return its answer text.
+async fn send_request(
+    provider: &VisionProvider,
+    url: &str,
+    body: &Value,
+) -> anyhow::Result<String> {
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .build()?;
+    let mut request = client.post(url).json(body);
+    if let Some(key) = &provider.api_key {
+        request = match provider.api {
+            VisionApi::Anthropic => request
+                .header("x-api-key", key)
+                .header("anthropic-version", "2023-06-01"),
+            VisionApi::OpenAiCompatible => request.bearer_auth(key),
+        };
+    }
+    let response = request.send().await?;
+    let status = response.status();
+    let text = response.text().await?;
+    if !status.is_success() {
+        let end = super::safe_truncate_index(&text, 500);
+        return Err(anyhow::anyhow!(
+            "Request to {} failed ({}): {}",
+            provider.label,
+            status,
+            &text[..end]
+        ));
+    }
+    let response: Value = serde_json::from_str(&text)?;
+    parse_answer(provider.api, &response)
+        .ok_or_else(|| anyhow::anyhow!("{} returned no answer", provider.label))
+}
+
+/// Run a text-only prompt on the given provider type (e.g. `"openai"`), or
+/// the default provider. Returns the answer and the model that produced it.
+pub async fn complete_text(
+    prompt: &str,
+    provider: Option<&str>,
+    model: Option<&str>,
+    max_tokens: u64,
+) -> anyhow::Result<(String, String)> {
+    let provider = resolve_provider(provider, model).await?;
+    let (url, body) = build_text_request(&provider, prompt, max_tokens);
+    let answer = send_request(&provider, &url, &body).await?;
+    Ok((answer, provider.model))
+}
+
+/// Pull the answer text out of a provider response.
+fn parse_answer(api: VisionApi, response: &Value) -> Option<String> {
+    let text = match api {
+        VisionApi::Anthropic => response["content"]
+            .as_array()?
+            .iter()
+            .filter_map(|block| block["text"].as_str())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        VisionApi::OpenAiCompatible => match &response["choices"][0]["message"]["content"] {
+            Value::String(s) => s.clone(),
+            Value::Array(parts) => parts
+                .iter()
+                .filter_map(|part| part["text"].as_str())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            _ => return None,
+        },
+    };
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// Take a desktop screenshot and return the saved file.
+async fn capture_screenshot(args: &Value, working_dir: &Path) -> anyhow::Result<PathBuf> {
+    let display = args["display"]
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("'display' is required when screenshot is true"))?;
+    let mut screenshot_args = json!({ "display": display });
+    if let Some(wait) = args["wait_seconds"].as_f64() {
+        screenshot_args["wait_seconds"] = json!(wait);
+    }
+    if args["region"].is_object() {
+        screenshot_args["region"] = args["region"].clone();
+    }
+    let output = super::desktop::Screenshot
+        .execute(screenshot_args, working_dir)
+        .await?;
+    let result: Value = serde_json::from_str(output.trim())?;
+    result["path"]
+        .as_str()
+        .map(PathBuf::from)
+        .ok_or_else(|| anyhow::anyhow!("Screenshot did not report a file path"))
+}
+
+/// Analyze an image or desktop screenshot with a multimodal model.
+pub struct AnalyzeImage;
+
+#[async_trait]
+impl Tool for AnalyzeImage {
+    fn name(&self) -> &str {
+        "analyze_image"
+    }
+
+    fn description(&self) -> &str {
+        "Ask a vision model about an image file or a fresh desktop screenshot and get a text answer back. Use it to read what's on screen (buttons, dialogs, text, layout) during desktop automation, or to describe charts and photos. Pass a specific question in 'prompt' for precise answers."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Image file (PNG, JPEG, GIF or WebP). Use relative paths for workspace files. Omit when screenshot is true."
+                },
+                "screenshot": {
+                    "type": "boolean",
+                    "description": "Optional: capture the desktop instead of reading a file (default: false)"
+                },
+                "display": {
+                    "type": "string",
+                    "description": "Display to capture (e.g., ':99'); required with screenshot"
+                },
+                "region": {
+                    "type": "object",
+                    "description": "Optional: screenshot region (x, y, width, height)",
+                    "properties": {
+                        "x": { "type": "integer" },
+                        "y": { "type": "integer" },
+                        "width": { "type": "integer" },
+                        "height": { "type": "integer" }
+                    }
+                },
+                "wait_seconds": {
+                    "type": "number",
+                    "description": "Optional: seconds to wait before the screenshot (default: 0)"
+                },
+                "prompt": {
+                    "type": "string",
+                    "description": "Question or instruction for the model (default: a detailed description)"
+                },
+                "provider": {
+                    "type": "string",
+                    "description": "Optional: provider to use ('anthropic', 'openai', 'open-router', 'google', 'xai', 'custom'); default is the configured default provider"
+                },
+                "model": {
+                    "type": "string",
+                    "description": "Optional: model ID (default: a vision model for the provider)"
+                },
+                "max_tokens": {
+                    "type": "integer",
+                    "description": "Optional: maximum answer length in tokens (default: 1024)"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: Value, working_dir: &Path) -> anyhow::Result<String> {
+        let image_path = if args["screenshot"].as_bool().unwrap_or(false) {
+            capture_screenshot(&args, working_dir).await?
+        } else {
+            let path = args["path"]
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("Provide 'path' or set screenshot=true"))?;
+            resolve_path(path, working_dir).resolved
+        };
+
+        let metadata = tokio::fs::metadata(&image_path)
+            .await
+            .map_err(|_| anyhow::anyhow!("Image not found: {}", image_path.display()))?;
+        if metadata.len() > MAX_IMAGE_BYTES {
+            return Err(anyhow::anyhow!(
+                "Image is too large ({} bytes, max {}); crop or downscale it first",
+                metadata.len(),
+                MAX_IMAGE_BYTES
+            ));
+        }
+        let bytes = tokio::fs::read(&image_path).await?;
+        let media_type = image_media_type(&bytes).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Unsupported image format: {} (use PNG, JPEG, GIF or WebP)",
+                image_path.display()
+            )
+        })?;
+
+        let provider = resolve_provider(args["provider"].as_str(), args["model"].as_str()).await?;
+        let prompt = args["prompt"].as_str().unwrap_or(DEFAULT_PROMPT);
+        let max_tokens = args["max_tokens"].as_u64().unwrap_or(DEFAULT_MAX_TOKENS);
+        let (url, body) = build_request(
+            &provider,
+            media_type,
+            &BASE64.encode(&bytes),
+            prompt,
+            max_tokens,
+        );
+
+        let answer = send_request(&provider, &url, &body).await?;
+
+        Ok(format!(
+            "{}\n\n[Analyzed {} with {}]",
+            answer,
+            image_path.display(),
+            provider.model
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_media_type() {
+        assert_eq!(
+            image_media_type(b"\x89PNG\r\n\x1a\nrest"),
+            Some("image/png")
+        );
+        assert_eq!(
+            image_media_type(&[0xFF, 0xD8, 0xFF, 0xE0]),
+            Some("image/jpeg")
+        );
+        assert_eq!(
+            image_media_type(b"RIFF\0\0\0\0WEBPVP8 "),
+            Some("image/webp")
+        );
+        assert_eq!(image_media_type(b"%PDF-1.7"), None);
+    }
+
+    #[test]
+    fn test_request_and_answer_per_api() {
+        let mut config = AIProvider::new(ProviderType::Anthropic, "Work".to_string());
+        assert!(provider_from_config(&config, None).is_none());
+        config.api_key = Some("key".to_string());
+        let anthropic = provider_from_config(&config, None).unwrap();
+        let (url, body) = build_request(&anthropic, "image/png", "AAAA", "What is this?", 64);
+        assert_eq!(url, "https://api.anthropic.com/v1/messages");
+        assert_eq!(body["messages"][0]["content"][0]["source"]["data"], "AAAA");
+        let response = json!({"content": [{"type": "text", "text": " A cat. "}]});
+        assert_eq!(
+            parse_answer(VisionApi::Anthropic, &response).as_deref(),
+            Some("A cat.")
+        );
+
+        let mut custom = AIProvider::new(ProviderType::Custom, "Local".to_string());
+        custom.base_url = Some("http://localhost:8000/v1/".to_string());
+        let local = provider_from_config(&custom, Some("llava")).unwrap();
+        let (url, body) = build_request(&local, "image/jpeg", "BBBB", "Read it", 64);
+        assert_eq!(url, "http://localhost:8000/v1/chat/completions");
+        assert_eq!(
+            body["messages"][0]["content"][1]["image_url"]["url"],
+            "data:image/jpeg;base64,BBBB"
+        );
+        let (url, body) = build_text_request(&local, "Summarize", 32);
+        assert_eq!(url, "http://localhost:8000/v1/chat/completions");
+        assert_eq!(body["messages"][0]["content"], "Summarize");
+        let response = json!({"choices": [{"message": {"content": "Submit button"}}]});
+        assert_eq!(
+            parse_answer(VisionApi::OpenAiCompatible, &response).as_deref(),
+            Some("Submit button")
+        );
+    }
+}
@@ -1,6 +1,7 @@
-//! Web access tools: fetch URLs.
+//! Web access tools: fetch URLs, make HTTP requests and search the web.
 //!
-//! Only the `fetch_url` tool remains; search is handled upstream by OpenCode/OMO agents.
+//! `web_search` goes through pluggable providers (Brave, SearXNG, Google
+//! CSE) configured by [`crate::config::SearchConfig`].
 
 use std::path::Path;
 
@@ -116,6 +117,699 @@ impl Tool for FetchUrl {
     }
 }
 
+/// Default and maximum timeouts for `http_request`, in seconds.
+const HTTP_REQUEST_DEFAULT_TIMEOUT_SECS: u64 = 30;
+const HTTP_REQUEST_MAX_TIMEOUT_SECS: u64 = 300;
+/// Default cap on the response body returned by `http_request`.
+const HTTP_REQUEST_DEFAULT_MAX_BYTES: usize = 20000;
+/// Vault registry `http_request` takes credentials from.
+const HTTP_SECRETS_REGISTRY: &str = "http";
+/// Secret label listing the hosts a credential may be sent to, comma-separated
+/// (`*.example.com` matches the domain and its subdomains).
+const ALLOWED_HOSTS_LABEL: &str = "allowed_hosts";
+
+/// Make an HTTP request with any method, headers, body and auth.
+///
+/// Credentials are referenced as `http/key` in the secrets vault and resolved
+/// here, so the model never sees them. A credential is only sent to the hosts
+/// in its `allowed_hosts` label, and any secret value that shows up in the
+/// response is redacted.
+pub struct HttpRequest;
+
+#[async_trait]
+impl Tool for HttpRequest {
+    fn name(&self) -> &str {
+        "http_request"
+    }
+
+    fn description(&self) -> &str {
+        "Make an HTTP request (GET, POST, PUT, PATCH, DELETE, HEAD, OPTIONS) with custom headers and a JSON, form or raw body. For credentials, reference a secret from the 'http' registry as 'http/key' in auth.secret or as {{secret:http/key}} inside a header value; never paste secret values. A secret is only sent to the hosts listed in its allowed_hosts label. Returns the status, key response headers and the body (truncated to max_response_bytes)."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "url": {
+                    "type": "string",
+                    "description": "The URL to request"
+                },
+                "method": {
+                    "type": "string",
+                    "description": "HTTP method (default: GET)"
+                },
+                "headers": {
+                    "type": "object",
+                    "additionalProperties": { "type": "string" },
+                    "description": "Optional request headers. Values may contain {{secret:http/key}} placeholders."
+                },
+                "json": {
+                    "description": "Optional JSON body (sets Content-Type: application/json)"
+                },
+                "form": {
+                    "type": "object",
+                    "additionalProperties": { "type": "string" },
+                    "description": "Optional form body (application/x-www-form-urlencoded)"
+                },
+                "body": {
+                    "type": "string",
+                    "description": "Optional raw body"
+                },
+                "auth": {
+                    "type": "object",
+                    "properties": {
+                        "type": { "type": "string", "enum": ["bearer", "basic"] },
+                        "secret": {
+                            "type": "string",
+                            "description": "Secret reference 'http/key' holding the token (bearer) or password (basic)"
+                        },
+                        "username": {
+                            "type": "string",
+                            "description": "Username for basic auth"
+                        }
+                    },
+                    "required": ["type", "secret"]
+                },
+                "timeout_secs": {
+                    "type": "integer",
+                    "description": "Optional: request timeout in seconds (default: 30, max: 300)"
+                },
+                "max_response_bytes": {
+                    "type": "integer",
+                    "description": "Optional: maximum response body bytes to return (default: 20000)"
+                }
+            },
+            "required": ["url"]
+        })
+    }
+
+    async fn execute(&self, args: Value, _workspace: &Path) -> anyhow::Result<String> {
+        let url = args["url"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Missing 'url' argument"))?;
+        let parsed = reqwest::Url::parse(url).map_err(|e| anyhow::anyhow!("Invalid URL: {}", e))?;
+        let host = parsed
+            .host_str()
+            .ok_or_else(|| anyhow::anyhow!("URL has no host: {}", url))?
+            .to_string();
+        let method = args["method"].as_str().unwrap_or("GET").to_uppercase();
+        let method = reqwest::Method::from_bytes(method.as_bytes())
+            .map_err(|_| anyhow::anyhow!("Invalid HTTP method: {}", method))?;
+        let timeout = args["timeout_secs"]
+            .as_u64()
+            .unwrap_or(HTTP_REQUEST_DEFAULT_TIMEOUT_SECS)
+            .clamp(1, HTTP_REQUEST_MAX_TIMEOUT_SECS);
+        let max_bytes = args["max_response_bytes"]
+            .as_u64()
+            .map(|n| n as usize)
+            .unwrap_or(HTTP_REQUEST_DEFAULT_MAX_BYTES);
+
+        let body_kinds = ["json", "form", "body"]
+            .iter()
+            .filter(|k| !args[**k].is_null())
+            .count();
+        if body_kinds > 1 {
+            return Err(anyhow::anyhow!("Pass only one of 'json', 'form' or 'body'"));
+        }
+
+        // Secret values used in this request, redacted from the output.
+        let mut secrets: Vec<String> = Vec::new();
+        let mut headers = Vec::new();
+        if let Some(fields) = args["headers"].as_object() {
+            for (name, value) in fields {
+                let value = value
+                    .as_str()
+                    .ok_or_else(|| anyhow::anyhow!("Header '{}' must be a string", name))?;
+                let value = expand_secret_placeholders(value, &host, &mut secrets).await?;
+                headers.push((name.clone(), value));
+            }
+        }
+        let auth_secret = match args["auth"].as_object() {
+            Some(auth) => {
+                let reference = auth.get("secret").and_then(|v| v.as_str()).ok_or_else(|| {
+                    anyhow::anyhow!("auth.secret must be an 'http/key' reference")
+                })?;
+                let secret = resolve_http_secret(reference, &host).await?;
+                secrets.push(secret.clone());
+                Some(secret)
+            }
+            None => None,
+        };
+
+        let mut builder = reqwest::Client::builder()
+            .user_agent("Mozilla/5.0 (compatible; Sandboxed/1.0)")
+            .timeout(std::time::Duration::from_secs(timeout));
+        if !secrets.is_empty() {
+            // Don't carry credentials to a host they aren't bound to.
+            builder = builder.redirect(reqwest::redirect::Policy::custom(move |attempt| {
+                if attempt.url().host_str() != Some(host.as_str()) {
+                    attempt.stop()
+                } else if attempt.previous().len() >= 10 {
+                    attempt.error("too many redirects")
+                } else {
+                    attempt.follow()
+                }
+            }));
+        }
+        let client = builder.build()?;
+        let mut request = client.request(method.clone(), url);
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+
+        if let (Some(auth), Some(secret)) = (args["auth"].as_object(), auth_secret) {
+            request = match auth.get("type").and_then(|v| v.as_str()) {
+                Some("bearer") => request.bearer_auth(secret),
+                Some("basic") => {
+                    let username = auth.get("username").and_then(|v| v.as_str()).unwrap_or("");
+                    request.basic_auth(username, Some(secret))
+                }
+                other => {
+                    return Err(anyhow::anyhow!(
+                        "Unsupported auth type {:?}: use 'bearer' or 'basic'",
+                        other
+                    ))
+                }
+            };
+        }
+
+        if !args["json"].is_null() {
+            request = request.json(&args["json"]);
+        } else if let Some(form) = args["form"].as_object() {
+            let fields: Vec<(String, String)> = form
+                .iter()
+                .map(|(k, v)| {
+                    let v = v
+                        .as_str()
+                        .map(str::to_string)
+                        .unwrap_or_else(|| v.to_string());
+                    (k.clone(), v)
+                })
+                .collect();
+            request = request.form(&fields);
+        } else if let Some(body) = args["body"].as_str() {
+            request = request.body(body.to_string());
+        }
+
+        let response = request.send().await.map_err(|e| {
+            anyhow::anyhow!(
+                "Request failed: {}",
+                redact_secrets(&e.to_string(), &secrets)
+            )
+        })?;
+        let status = response.status();
+
+        let mut result = format!(
+            "HTTP {} {}\n",
+            status.as_u16(),
+            status.canonical_reason().unwrap_or("")
+        );
+        for name in ["content-type", "content-length", "location", "retry-after"] {
+            if let Some(value) = response.headers().get(name).and_then(|v| v.to_str().ok()) {
+                result.push_str(&format!("{}: {}\n", name, value));
+            }
+        }
+
+        if method != reqwest::Method::HEAD {
+            let bytes = response.bytes().await?;
+            let body = String::from_utf8_lossy(&bytes);
+            let end = super::safe_truncate_index(&body, max_bytes);
+            result.push('\n');
+            result.push_str(&body[..end]);
+            if end < body.len() {
+                result.push_str(&format!(
+                    "\n\n[Response truncated: showing {} of {} bytes]",
+                    end,
+                    bytes.len()
+                ));
+            }
+        }
+
+        Ok(redact_secrets(&result, &secrets))
+    }
+}
+
+/// Default and maximum number of `web_search` results.
+const WEB_SEARCH_DEFAULT_COUNT: usize = 5;
+const WEB_SEARCH_MAX_COUNT: usize = 20;
+const WEB_SEARCH_TIMEOUT_SECS: u64 = 20;
+
+/// One `web_search` hit.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+struct SearchResult {
+    title: String,
+    url: String,
+    snippet: String,
+}
+
+/// Why a search provider could not answer.
+#[derive(Debug)]
+enum SearchError {
+    /// The provider throttled us or the quota is used up
+    RateLimited,
+    Failed(String),
+}
+
+impl std::fmt::Display for SearchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::RateLimited => write!(f, "rate limited"),
+            Self::Failed(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// A web search backend.
+#[async_trait]
+trait SearchProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    async fn search(
+        &self,
+        client: &reqwest::Client,
+        query: &str,
+        count: usize,
+    ) -> Result<Vec<SearchResult>, SearchError>;
+}
+
+/// Brave Search API (`search/brave_api_key`).
+struct BraveSearch {
+    api_key: String,
+}
+
+#[async_trait]
+impl SearchProvider for BraveSearch {
+    fn name(&self) -> &'static str {
+        "brave"
+    }
+
+    async fn search(
+        &self,
+        client: &reqwest::Client,
+        query: &str,
+        count: usize,
+    ) -> Result<Vec<SearchResult>, SearchError> {
+        let request = client
+            .get("https://api.search.brave.com/res/v1/web/search")
+            .query(&[("q", query), ("count", count.to_string().as_str())])
+            .header("Accept", "application/json")
+            .header("X-Subscription-Token", &self.api_key);
+        Ok(parse_brave_results(&search_json(request).await?))
+    }
+}
+
+/// A self-hosted SearXNG instance with the JSON format enabled.
+struct SearxngSearch {
+    base_url: String,
+}
+
+#[async_trait]
+impl SearchProvider for SearxngSearch {
+    fn name(&self) -> &'static str {
+        "searxng"
+    }
+
+    async fn search(
+        &self,
+        client: &reqwest::Client,
+        query: &str,
+        _count: usize,
+    ) -> Result<Vec<SearchResult>, SearchError> {
+        let request = client
+            .get(format!("{}/search", self.base_url))
+            .query(&[("q", query), ("format", "json")]);
+        Ok(parse_searxng_results(&search_json(request).await?))
+    }
+}
+
+/// Google Programmable Search (Custom Search JSON API).
+struct GoogleCseSearch {
+    api_key: String,
+    cx: String,
+}
+
+#[async_trait]
+impl SearchProvider for GoogleCseSearch {
+    fn name(&self) -> &'static str {
+        "google"
+    }
+
+    async fn search(
+        &self,
+        client: &reqwest::Client,
+        query: &str,
+        count: usize,
+    ) -> Result<Vec<SearchResult>, SearchError> {
+        // The API returns at most 10 results per page.
+        let num = count.min(10).to_string();
+        let request = client
+            .get("https://www.googleapis.com/customsearch/v1")
+            .query(&[
+                ("key", self.api_key.as_str()),
+                ("cx", self.cx.as_str()),
+                ("q", query),
+                ("num", num.as_str()),
+            ]);
+        Ok(parse_google_results(&search_json(request).await?))
+    }
+}
+
+/// Send a search request and decode the JSON body, mapping throttling
+/// responses to [`SearchError::RateLimited`].
+async fn search_json(request: reqwest::RequestBuilder) -> Result<Value, SearchError> {
+    let response = request
+        .send()
+        .await
+        .map_err(|e| SearchError::Failed(format!("request failed: {}", e.without_url())))?;
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return Err(SearchError::RateLimited);
+    }
+    // Google reports exhausted quotas as 403 with a rate limit reason.
+    if status == reqwest::StatusCode::FORBIDDEN
+        && (body.contains("rateLimitExceeded") || body.contains("dailyLimitExceeded"))
+    {
+        return Err(SearchError::RateLimited);
+    }
+    if !status.is_success() {
+        let end = super::safe_truncate_index(&body, 300);
+        return Err(SearchError::Failed(format!(
+            "HTTP {}: {}",
+            status.as_u16(),
+            body[..end].trim()
+        )));
+    }
+    serde_json::from_str(&body)
+        .map_err(|e| SearchError::Failed(format!("invalid JSON response: {}", e)))
+}
+
+fn search_results(
+    items: Option<&Value>,
+    title: &str,
+    url: &str,
+    snippet: &str,
+) -> Vec<SearchResult> {
+    items
+        .and_then(|v| v.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| {
+                    Some(SearchResult {
+                        title: extract_text_from_html(item[title].as_str().unwrap_or("")),
+                        url: item[url].as_str()?.to_string(),
+                        snippet: extract_text_from_html(item[snippet].as_str().unwrap_or("")),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn parse_brave_results(body: &Value) -> Vec<SearchResult> {
+    search_results(body.pointer("/web/results"), "title", "url", "description")
+}
+
+fn parse_searxng_results(body: &Value) -> Vec<SearchResult> {
+    search_results(body.get("results"), "title", "url", "content")
+}
+
+fn parse_google_results(body: &Value) -> Vec<SearchResult> {
+    search_results(body.get("items"), "title", "link", "snippet")
+}
+
+/// Look up a provider API key in the secrets vault, then the environment.
+async fn search_api_key(reference: &str, env: &str) -> Option<String> {
+    if let Ok(key) = resolve_secret(reference).await {
+        return Some(key);
+    }
+    std::env::var(env).ok().filter(|v| !v.trim().is_empty())
+}
+
+/// Build the configured providers in order. Providers missing a key or URL
+/// are reported in the second list instead.
+async fn configured_search_providers(
+    config: &crate::config::SearchConfig,
+) -> (Vec<Box<dyn SearchProvider>>, Vec<String>) {
+    use crate::config::SearchProviderKind;
+
+    let mut providers: Vec<Box<dyn SearchProvider>> = Vec::new();
+    let mut unavailable = Vec::new();
+    for kind in &config.providers {
+        let provider: Option<Box<dyn SearchProvider>> = match kind {
+            SearchProviderKind::Brave => {
+                search_api_key("search/brave_api_key", "BRAVE_SEARCH_API_KEY")
+                    .await
+                    .map(|api_key| Box::new(BraveSearch { api_key }) as Box<dyn SearchProvider>)
+            }
+            SearchProviderKind::Searxng => config
+                .searxng_url
+                .clone()
+                .map(|base_url| Box::new(SearxngSearch { base_url }) as Box<dyn SearchProvider>),
+            SearchProviderKind::GoogleCse => {
+                match (
+                    search_api_key("search/google_api_key", "GOOGLE_CSE_API_KEY").await,
+                    config.google_cse_id.clone(),
+                ) {
+                    (Some(api_key), Some(cx)) => {
+                        Some(Box::new(GoogleCseSearch { api_key, cx }) as Box<dyn SearchProvider>)
+                    }
+                    _ => None,
+                }
+            }
+        };
+        match provider {
+            Some(provider) => providers.push(provider),
+            None => unavailable.push(kind.as_str().to_string()),
+        }
+    }
+    (providers, unavailable)
+}
+
+/// Try `providers` in order until one answers. Returns the provider name,
+/// its results and the `(provider, error)` pairs that were skipped.
+async fn search_with_fallback(
+    providers: &[Box<dyn SearchProvider>],
+    client: &reqwest::Client,
+    query: &str,
+    count: usize,
+) -> Result<(&'static str, Vec<SearchResult>, Vec<(String, String)>), Vec<(String, String)>> {
+    let mut failures = Vec::new();
+    for provider in providers {
+        match provider.search(client, query, count).await {
+            Ok(mut results) => {
+                results.truncate(count);
+                return Ok((provider.name(), results, failures));
+            }
+            Err(e) => {
+                tracing::warn!("web_search provider {} failed: {}", provider.name(), e);
+                failures.push((provider.name().to_string(), e.to_string()));
+            }
+        }
+    }
+    Err(failures)
+}
+
+/// Search the web through the configured providers (Brave, SearXNG, Google
+/// CSE), falling back to the next one when a provider is rate-limited or down.
+pub struct WebSearch;
+
+#[async_trait]
+impl Tool for WebSearch {
+    fn name(&self) -> &str {
+        "web_search"
+    }
+
+    fn description(&self) -> &str {
+        "Search the web. Returns a JSON list of results (title, url, snippet) and which search provider answered. Providers are tried in the configured order; rate-limited or failing providers are skipped automatically. Use fetch_url to read a result."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "The search query"
+                },
+                "count": {
+                    "type": "integer",
+                    "description": "Optional: number of results (default: 5, max: 20)"
+                },
+                "provider": {
+                    "type": "string",
+                    "enum": ["brave", "searxng", "google"],
+                    "description": "Optional: use only this provider instead of the configured order"
+                }
+            },
+            "required": ["query"]
+        })
+    }
+
+    async fn execute(&self, args: Value, _workspace: &Path) -> anyhow::Result<String> {
+        let query = args["query"]
+            .as_str()
+            .map(str::trim)
+            .filter(|q| !q.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'query' argument"))?;
+        let count = args["count"]
+            .as_u64()
+            .map(|n| n as usize)
+            .unwrap_or(WEB_SEARCH_DEFAULT_COUNT)
+            .clamp(1, WEB_SEARCH_MAX_COUNT);
+
+        let mut config = crate::config::SearchConfig::from_env()?;
+        if let Some(provider) = args["provider"].as_str() {
+            config.providers = vec![provider.parse().map_err(|e: String| anyhow::anyhow!(e))?];
+        }
+
+        let (providers, unavailable) = configured_search_providers(&config).await;
+        if providers.is_empty() {
+            return Err(anyhow::anyhow!(
+                "No search provider is configured (tried: {}). Set the search/brave_api_key secret, SANDBOXED_SH_SEARXNG_URL, or search/google_api_key with SANDBOXED_SH_GOOGLE_CSE_ID.",
+                unavailable.join(", ")
+            ));
+        }
+
+        let client = reqwest::Client::builder()
+            .user_agent("Mozilla/5.0 (compatible; Sandboxed/1.0)")
+            .timeout(std::time::Duration::from_secs(WEB_SEARCH_TIMEOUT_SECS))
+            .build()?;
+
+        match search_with_fallback(&providers, &client, query, count).await {
+            Ok((provider, results, failures)) => {
+                let skipped: Vec<Value> = failures
+                    .iter()
+                    .map(|(provider, error)| json!({ "provider": provider, "error": error }))
+                    .collect();
+                Ok(serde_json::to_string_pretty(&json!({
+                    "query": query,
+                    "provider": provider,
+                    "results": results,
+                    "skipped": skipped,
+                }))?)
+            }
+            Err(failures) => {
+                let detail: Vec<String> = failures
+                    .iter()
+                    .map(|(provider, error)| format!("{}: {}", provider, error))
+                    .collect();
+                Err(anyhow::anyhow!(
+                    "All search providers failed ({})",
+                    detail.join("; ")
+                ))
+            }
+        }
+    }
+}
+
+/// Replace `{{secret:http/key}}` placeholders with secret values bound to
+/// `host`.
+async fn expand_secret_placeholders(
+    value: &str,
+    host: &str,
+    secrets: &mut Vec<String>,
+) -> anyhow::Result<String> {
+    let mut out = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("{{secret:") {
+        let after = &rest[start + "{{secret:".len()..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| anyhow::anyhow!("Unterminated {{{{secret:...}}}} placeholder"))?;
+        let secret = resolve_http_secret(after[..end].trim(), host).await?;
+        out.push_str(&rest[..start]);
+        out.push_str(&secret);
+        secrets.push(secret);
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Resolve an `http_request` credential. It must live in the `http` registry
+/// and list `host` in its `allowed_hosts` label.
+async fn resolve_http_secret(reference: &str, host: &str) -> anyhow::Result<String> {
+    let (registry, key) = split_secret_reference(reference)?;
+    if registry != HTTP_SECRETS_REGISTRY {
+        return Err(anyhow::anyhow!(
+            "http_request only uses secrets from the '{}' registry, not '{}'",
+            HTTP_SECRETS_REGISTRY,
+            reference
+        ));
+    }
+    let store = super::call_secrets().ok_or_else(|| {
+        anyhow::anyhow!("No secrets vault is available to resolve '{}'", reference)
+    })?;
+    let info = store
+        .list_secrets(registry)
+        .await
+        .ok()
+        .and_then(|secrets| secrets.into_iter().find(|s| s.key == key))
+        .ok_or_else(|| anyhow::anyhow!("Secret '{}' not found", reference))?;
+    let allowed = info
+        .labels
+        .get(ALLOWED_HOSTS_LABEL)
+        .map(String::as_str)
+        .unwrap_or("");
+    if !host_allowed(allowed, host) {
+        return Err(anyhow::anyhow!(
+            "Secret '{}' may not be sent to {} (add it to the secret's '{}' label)",
+            reference,
+            host,
+            ALLOWED_HOSTS_LABEL
+        ));
+    }
+    store.get_secret(registry, key).await
+}
+
+/// Whether `host` matches one of the comma-separated `allowed` patterns.
+fn host_allowed(allowed: &str, host: &str) -> bool {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    allowed
+        .split(',')
+        .map(|pattern| pattern.trim().to_ascii_lowercase())
+        .filter(|pattern| !pattern.is_empty())
+        .any(|pattern| match pattern.strip_prefix("*.") {
+            Some(domain) => host == domain || host.ends_with(&format!(".{}", domain)),
+            None => host == pattern,
+        })
+}
+
+fn split_secret_reference(reference: &str) -> anyhow::Result<(&str, &str)> {
+    reference
+        .split_once('/')
+        .ok_or_else(|| anyhow::anyhow!("Secret reference must be 'registry/key': {}", reference))
+}
+
+/// Resolve a `registry/key` reference from the secrets vault of the current
+/// tool call.
+pub(super) async fn resolve_secret(reference: &str) -> anyhow::Result<String> {
+    let (registry, key) = split_secret_reference(reference)?;
+    let store = super::call_secrets().ok_or_else(|| {
+        anyhow::anyhow!("No secrets vault is available to resolve '{}'", reference)
+    })?;
+    store.get_secret(registry, key).await.map_err(|_| {
+        anyhow::anyhow!(
+            "Secret '{}' not found (is the secrets vault unlocked?)",
+            reference
+        )
+    })
+}
+
+pub(super) fn redact_secrets(text: &str, secrets: &[String]) -> String {
+    let mut text = text.to_string();
+    for secret in secrets {
+        // Very short values would redact ordinary text.
+        if secret.len() >= 4 {
+            text = text.replace(secret.as_str(), "[REDACTED]");
+        }
+    }
+    text
+}
+
 /// Extract readable text from HTML (simple approach).
 fn extract_text_from_html(html: &str) -> String {
     // Remove script and style tags
@@ -169,3 +863,173 @@ fn html_decode(s: &str) -> String {
         .replace("&#39;", "'")
         .replace("&nbsp;", " ")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_host_allowed() {
+        assert!(host_allowed("api.github.com", "api.github.com"));
+        assert!(host_allowed(
+            "example.org, api.github.com",
+            "API.GitHub.com."
+        ));
+        assert!(host_allowed("*.example.com", "api.example.com"));
+        assert!(host_allowed("*.example.com", "example.com"));
+        assert!(!host_allowed("*.example.com", "example.com.evil.io"));
+        assert!(!host_allowed("api.github.com", "github.com"));
+        assert!(!host_allowed("", "api.github.com"));
+    }
+
+    #[tokio::test]
+    async fn test_http_secrets_are_bound_to_allowed_hosts() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = crate::secrets::SecretsStore::new(dir.path()).await.unwrap();
+        store.initialize("default").await.unwrap();
+        store.unlock("passphrase").await.unwrap();
+        let mut metadata = crate::secrets::SecretMetadata::default();
+        metadata.labels.insert(
+            ALLOWED_HOSTS_LABEL.to_string(),
+            "api.github.com".to_string(),
+        );
+        store
+            .set_secret("http", "github", "ghp_abc123", Some(metadata))
+            .await
+            .unwrap();
+        store
+            .set_secret("http", "unbound", "tok_unbound", None)
+            .await
+            .unwrap();
+        store
+            .set_secret("api-keys", "github", "ghp_other", None)
+            .await
+            .unwrap();
+
+        let store = Some(std::sync::Arc::new(store));
+        super::super::CALL_SECRETS
+            .scope(store, async {
+                let mut secrets = Vec::new();
+                let header = expand_secret_placeholders(
+                    "token {{secret:http/github}}",
+                    "api.github.com",
+                    &mut secrets,
+                )
+                .await
+                .unwrap();
+                assert_eq!(header, "token ghp_abc123");
+                assert_eq!(
+                    redact_secrets("echo: token ghp_abc123", &secrets),
+                    "echo: token [REDACTED]"
+                );
+
+                // Wrong host, no allowed_hosts label, other registries.
+                assert!(resolve_http_secret("http/github", "evil.example.com")
+                    .await
+                    .is_err());
+                assert!(resolve_http_secret("http/unbound", "api.github.com")
+                    .await
+                    .is_err());
+                assert!(resolve_http_secret("api-keys/github", "api.github.com")
+                    .await
+                    .is_err());
+                assert!(resolve_http_secret("http/missing", "api.github.com")
+                    .await
+                    .is_err());
+                assert!(resolve_secret("no-registry").await.is_err());
+            })
+            .await;
+
+        // Outside a tool call there is no vault.
+        assert!(resolve_secret("http/github").await.is_err());
+    }
+
+    struct FakeProvider {
+        name: &'static str,
+        outcome: fn() -> Result<Vec<SearchResult>, SearchError>,
+    }
+
+    #[async_trait]
+    impl SearchProvider for FakeProvider {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        async fn search(
+            &self,
+            _client: &reqwest::Client,
+            _query: &str,
+            _count: usize,
+        ) -> Result<Vec<SearchResult>, SearchError> {
+            (self.outcome)()
+        }
+    }
+
+    fn hit(n: usize) -> SearchResult {
+        SearchResult {
+            title: format!("Result {}", n),
+            url: format!("https://example.com/{}", n),
+            snippet: String::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_web_search_falls_back_when_rate_limited() {
+        let providers: Vec<Box<dyn SearchProvider>> = vec![
+            Box::new(FakeProvider {
+                name: "brave",
+                outcome: || Err(SearchError::RateLimited),
+            }),
+            Box::new(FakeProvider {
+                name: "searxng",
+                outcome: || Ok((0..5).map(hit).collect()),
+            }),
+            Box::new(FakeProvider {
+                name: "google",
+                outcome: || panic!("should not be reached"),
+            }),
+        ];
+        let client = reqwest::Client::new();
+        let (provider, results, failures) = search_with_fallback(&providers, &client, "rust", 3)
+            .await
+            .unwrap();
+        assert_eq!(provider, "searxng");
+        assert_eq!(results, vec![hit(0), hit(1), hit(2)]);
+        assert_eq!(
+            failures,
+            vec![("brave".to_string(), "rate limited".to_string())]
+        );
+
+        let failures = search_with_fallback(&providers[..1], &client, "rust", 3)
+            .await
+            .unwrap_err();
+        assert_eq!(failures.len(), 1);
+    }
+
+    #[test]
+    fn test_search_result_parsers() {
+        let brave = json!({"web": {"results": [
+            {"title": "<strong>Rust</strong> Lang", "url": "https://rust-lang.org", "description": "A &amp; B"}
+        ]}});
+        assert_eq!(
+            parse_brave_results(&brave),
+            vec![SearchResult {
+                title: "Rust Lang".to_string(),
+                url: "https://rust-lang.org".to_string(),
+                snippet: "A & B".to_string(),
+            }]
+        );
+
+        let searxng = json!({"results": [
+            {"title": "Docs", "url": "https://doc.rust-lang.org", "content": "The book"},
+            {"title": "No URL"}
+        ]});
+        assert_eq!(parse_searxng_results(&searxng).len(), 1);
+
+        let google = json!({"items": [
+            {"title": "Crates", "link": "https://crates.io", "snippet": "Registry"}
+        ]});
+        assert_eq!(parse_google_results(&google)[0].url, "https://crates.io");
+        assert!(parse_google_results(&json!({})).is_empty());
+    }
+}
@@ -178,10 +178,22 @@ pub struct Workspace {
     /// Non-empty = allowlist of MCP names.
     #[serde(default)]
     pub mcps: Vec<String>,
+    /// Built-in and MCP tool names available in this workspace (`*` globs allowed).
+    /// Empty = all tools.
+    /// Non-empty = allowlist of tool names.
+    #[serde(default)]
+    pub tools: Vec<String>,
     /// Config profile to use for this workspace (from workspace template).
     /// Defaults to "default" if not specified.
     #[serde(default)]
     pub config_profile: Option<String>,
+    /// Library branch to load skills, commands and agents from.
+    /// None = whatever branch the library is checked out on.
+    #[serde(default)]
+    pub library_branch: Option<String>,
+    /// Missions that may run in this workspace at once. None = no limit.
+    #[serde(default)]
+    pub max_concurrent_missions: Option<u32>,
 }
 
 impl Workspace {
@@ -206,7 +218,10 @@ impl Workspace {
             shared_network: None,
             tailscale_mode: None,
             mcps: Vec::new(),
+            tools: Vec::new(),
             config_profile: None,
+            library_branch: None,
+            max_concurrent_missions: None,
         }
     }
 
@@ -232,6 +247,9 @@ impl Workspace {
             shared_network: None,
             tailscale_mode: None,
             mcps: Vec::new(),
+            tools: Vec::new(),
+            library_branch: None,
+            max_concurrent_missions: None,
         }
     }
 }
@@ -422,7 +440,10 @@ impl WorkspaceStore {
                     shared_network: None, // Default to shared network
                     tailscale_mode: None,
                     mcps: Vec::new(),
+                    tools: Vec::new(),
                     config_profile: None,
+                    library_branch: None,
+                    max_concurrent_missions: None,
                 };
 
                 orphaned.push(workspace);
@@ -2616,12 +2637,12 @@ async fn resolve_workspace_skill_names(
     workspace: &Workspace,
     library: &LibraryStore,
 ) -> anyhow::Result<Vec<String>> {
-    if !workspace.skills.is_empty() {
-        return Ok(workspace.skills.clone());
-    }
-
-    // Default host workspace should expose all library skills when none are explicitly configured.
-    if workspace.id == DEFAULT_WORKSPACE_ID && workspace.workspace_type == WorkspaceType::Host {
+    let mut names = if !workspace.skills.is_empty() {
+        workspace.skills.clone()
+    } else if workspace.id == DEFAULT_WORKSPACE_ID
+        && workspace.workspace_type == WorkspaceType::Host
+    {
+        // Default host workspace should expose all library skills when none are explicitly configured.
         let skills = library.list_skills().await?;
         let names: Vec<String> = skills.into_iter().map(|skill| skill.name).collect();
         tracing::debug!(
@@ -2629,10 +2650,119 @@ async fn resolve_workspace_skill_names(
             count = names.len(),
             "Using all library skills for default host workspace"
         );
-        return Ok(names);
+        names
+    } else {
+        return Ok(Vec::new());
+    };
+
+    // Skills disabled in the library, or limited to other workspaces, are left out.
+    let settings = library.get_skill_settings().await?;
+    let workspace_id = workspace.id.to_string();
+    names.retain(|name| {
+        let allowed = settings
+            .get(name)
+            .map_or(true, |s| s.allows(&workspace_id, &workspace.name));
+        if !allowed {
+            tracing::debug!(workspace = %workspace.name, skill = %name, "Skipping disabled skill");
+        }
+        allowed
+    });
+    Ok(names)
+}
+
+/// Check the dependencies the workspace's skills declare (`requires` in
+/// SKILL.md) against its enabled MCP servers, tool allowlist and environment.
+/// Returns one human-readable message per missing dependency.
+pub async fn check_skill_requirements(
+    workspace: &Workspace,
+    mcp: &McpRegistry,
+    library: &LibraryStore,
+) -> Vec<String> {
+    let pinned = match pinned_library(workspace, library).await {
+        Ok(pinned) => pinned,
+        Err(e) => {
+            tracing::warn!(
+                workspace = %workspace.name,
+                error = %e,
+                "Failed to load pinned library for skill requirements"
+            );
+            None
+        }
+    };
+    let library = pinned.as_ref().unwrap_or(library);
+    let skill_names = match resolve_workspace_skill_names(workspace, library).await {
+        Ok(names) => names,
+        Err(e) => {
+            tracing::warn!(
+                workspace = %workspace.name,
+                error = %e,
+                "Failed to resolve skill names for skill requirements"
+            );
+            return Vec::new();
+        }
+    };
+    let requirements = library.collect_skill_requirements(&skill_names).await;
+    if requirements.is_empty() {
+        return Vec::new();
     }
 
-    Ok(Vec::new())
+    let mcp_names: Vec<String> =
+        filter_mcp_configs_for_workspace(mcp.list_configs().await, &workspace.mcps)
+            .into_iter()
+            .map(|c| c.name)
+            .collect();
+    // Host workspaces also inherit the server's environment.
+    let env_set = |key: &str| {
+        workspace.env_vars.get(key).is_some_and(|v| !v.is_empty())
+            || (workspace.workspace_type == WorkspaceType::Host
+                && std::env::var_os(key).is_some_and(|v| !v.is_empty()))
+    };
+
+    let mut missing = Vec::new();
+    for (skill, requires) in requirements {
+        for server in requires.mcp.iter().filter(|s| !mcp_names.contains(s)) {
+            missing.push(format!(
+                "Skill '{}' requires MCP server '{}', which is not enabled in workspace '{}'",
+                skill, server, workspace.name
+            ));
+        }
+        for tool in requires
+            .tools
+            .iter()
+            .filter(|t| !crate::tools::policy::tool_allowed(&workspace.tools, t.as_str()))
+        {
+            missing.push(format!(
+                "Skill '{}' requires tool '{}', which workspace '{}' does not allow",
+                skill, tool, workspace.name
+            ));
+        }
+        for key in requires.env.iter().filter(|k| !env_set(k.as_str())) {
+            missing.push(format!(
+                "Skill '{}' requires environment variable '{}', which is not set in workspace '{}'",
+                skill, key, workspace.name
+            ));
+        }
+    }
+    missing
+}
+
+/// The library checked out at the workspace's pinned branch, if it has one.
+async fn pinned_library(
+    workspace: &Workspace,
+    library: &LibraryStore,
+) -> anyhow::Result<Option<LibraryStore>> {
+    let Some(branch) = workspace.library_branch.as_deref() else {
+        return Ok(None);
+    };
+    let pinned = library.for_branch(branch).await.map_err(|e| {
+        anyhow::anyhow!(
+            "Failed to load library branch '{}' for workspace '{}': {}",
+            branch,
+            workspace.name,
+            e
+        )
+    })?;
+    Ok(Some(pinned))
 }
 
 /// Sync skills from library to workspace's `.opencode/skill/` directory.
@@ -2641,6 +2771,8 @@ pub async fn sync_workspace_skills(
     workspace: &Workspace,
     library: &LibraryStore,
 ) -> anyhow::Result<()> {
+    let pinned = pinned_library(workspace, library).await?;
+    let library = pinned.as_ref().unwrap_or(library);
     let skill_names = resolve_workspace_skill_names(workspace, library).await?;
     sync_skills_to_dir(&workspace.path, &skill_names, &workspace.name, library).await
 }
@@ -2803,7 +2935,8 @@ pub async fn write_agents_to_workspace(
 
     for agent in agents {
         let agent_path = agents_dir.join(format!("{}.md", agent.name));
-        tokio::fs::write(&agent_path, &agent.content).await?;
+        let content = crate::library::agents::to_opencode_markdown(&agent.content);
+        tokio::fs::write(&agent_path, content).await?;
 
         tracing::debug!(
             agent = %agent.name,
@@ -2821,12 +2954,47 @@ pub async fn write_agents_to_workspace(
     Ok(())
 }
 
-/// Sync library agents to a specific directory's `.opencode/agent/` folder.
+/// Write library agents as Claude Code subagents in `.claude/agents/<name>.md`.
+pub async fn write_claudecode_agents_to_workspace(
+    workspace_dir: &Path,
+    agents: &[AgentContent],
+) -> anyhow::Result<()> {
+    let agents_dir = workspace_dir.join(".claude").join("agents");
+
+    // Clean up old agents directory to remove stale subagents
+    if agents_dir.exists() {
+        let _ = tokio::fs::remove_dir_all(&agents_dir).await;
+    }
+
+    if agents.is_empty() {
+        return Ok(());
+    }
+
+    tokio::fs::create_dir_all(&agents_dir).await?;
+
+    for agent in agents {
+        let agent_path = agents_dir.join(format!("{}.md", agent.name));
+        let content = crate::library::agents::to_claudecode_markdown(&agent.name, &agent.content);
+        tokio::fs::write(&agent_path, content).await?;
+
+        tracing::debug!(
+            agent = %agent.name,
+            workspace = %workspace_dir.display(),
+            "Wrote Claude Code subagent to workspace"
+        );
+    }
+
+    Ok(())
+}
+
+/// Sync library agents to a specific directory's `.opencode/agent/` folder,
+/// and to `.claude/agents/` for the Claude Code backend.
 pub async fn sync_agents_to_dir(
     target_dir: &Path,
     agent_names: &[String],
     context_name: &str,
     library: &LibraryStore,
+    backend_id: &str,
 ) -> anyhow::Result<()> {
     if agent_names.is_empty() {
         tracing::debug!(
@@ -2857,6 +3025,9 @@ pub async fn sync_agents_to_dir(
     }
 
     write_agents_to_workspace(target_dir, &agents_to_write).await?;
+    if backend_id == "claudecode" {
+        write_claudecode_agents_to_workspace(target_dir, &agents_to_write).await?;
+    }
 
     tracing::info!(
         context = %context_name,
@@ -2899,6 +3070,41 @@ fn filter_mcp_configs_for_workspace(
         .collect()
 }
 
+/// Environment passed to the workspace's MCP servers: the workspace env vars
+/// plus the tool allowlist, so `workspace-mcp` only exposes allowed tools,
+/// and the tool policy and result limits it enforces on each call.
+async fn workspace_mcp_env(workspace: &Workspace) -> HashMap<String, String> {
+    let mut env = workspace.env_vars.clone();
+    if !workspace.tools.is_empty() {
+        env.insert(
+            crate::tools::policy::TOOL_ALLOWLIST_ENV.to_string(),
+            workspace.tools.join(","),
+        );
+    }
+    env.insert(
+        crate::tools::policy::WORKSPACE_ID_ENV.to_string(),
+        workspace.id.to_string(),
+    );
+    if let Some(settings) = crate::settings::global() {
+        let policy = settings.get_tool_policy().await;
+        if !policy.is_empty() {
+            if let Ok(raw) = serde_json::to_string(&policy) {
+                env.insert(crate::tools::policy::TOOL_POLICY_ENV.to_string(), raw);
+            }
+        }
+        let limits = settings.get_tool_result_limits().await;
+        if !limits.is_default() {
+            if let Ok(raw) = serde_json::to_string(&limits) {
+                env.insert(
+                    crate::tools::result_limits::TOOL_RESULT_LIMITS_ENV.to_string(),
+                    raw,
+                );
+            }
+        }
+    }
+    env
+}
+
 /// Prepare a custom workspace directory and write `opencode.json`.
 pub async fn prepare_custom_workspace(
     _config: &Config,
@@ -2955,7 +3161,7 @@ pub async fn prepare_mission_workspace_in(
         mcp_configs,
         &workspace.path,
         workspace.workspace_type,
-        &workspace.env_vars,
+        &workspace_mcp_env(workspace).await,
         skill_allowlist,
         None, // No command_contents for simple workspace preparation
         workspace.shared_network,
@@ -3028,6 +3234,14 @@ pub async fn prepare_mission_workspace_with_skills_backend(
     let dir = mission_workspace_dir_for_root(&workspace.path, mission_id);
     prepare_workspace_dir(&dir).await?;
 
+    // Workspaces pinned to a library branch read skills, commands and agents
+    // from that branch instead of the library's current checkout.
+    let pinned = match library {
+        Some(lib) => pinned_library(workspace, lib).await?,
+        None => None,
+    };
+    let library = pinned.as_ref().or(library);
+
     // Get custom providers: use provided list or read from file
     let providers_from_file;
     let effective_custom_providers = if let Some(providers) = custom_providers {
@@ -3121,7 +3335,7 @@ pub async fn prepare_mission_workspace_with_skills_backend(
         mcp_configs,
         &workspace.path,
         workspace.workspace_type,
-        &workspace.env_vars,
+        &workspace_mcp_env(workspace).await,
         skill_allowlist,
         skill_contents.as_deref(),
         command_contents.as_deref(),
@@ -3253,7 +3467,8 @@ pub async fn prepare_mission_workspace_with_skills_backend(
             }
         };
         if !agent_names.is_empty() {
-            if let Err(e) = sync_agents_to_dir(&dir, &agent_names, &context, lib).await {
+            if let Err(e) = sync_agents_to_dir(&dir, &agent_names, &context, lib, backend_id).await
+            {
                 tracing::warn!(
                     mission = %mission_id,
                     workspace = %workspace.name,
@@ -18,6 +18,29 @@ use tokio::process::{Child, Command};
 use crate::nspawn;
 use crate::workspace::{use_nspawn_for_workspace, TailscaleMode, Workspace, WorkspaceType};
 
+/// Kill a spawned command together with everything it started.
+///
+/// Container commands run behind an `nsenter` or `systemd-nspawn` wrapper, and
+/// killing only the wrapper can leave the CLI running inside the container. The
+/// container-side processes are visible from the host, so the tree is walked
+/// from the wrapper's PID; the wrapper's process group is signalled as well to
+/// catch processes that were already reparented.
+pub async fn kill_process_tree(child: &mut Child) {
+    if let Some(pid) = child.id() {
+        let _ = tokio::task::spawn_blocking(move || kill_tree_by_pid(pid)).await;
+    }
+    let _ = child.kill().await;
+}
+
+/// Kill a process, its descendants and its process group.
+pub fn kill_tree_by_pid(pid: u32) {
+    crate::process_registry::kill_process_tree(pid);
+    #[cfg(unix)]
+    unsafe {
+        libc::killpg(pid as libc::pid_t, libc::SIGKILL);
+    }
+}
+
 fn select_container_resolv_conf() -> Option<PathBuf> {
     let default_path = PathBuf::from("/etc/resolv.conf");
     let content = fs::read_to_string(&default_path).ok()?;
@@ -113,7 +136,11 @@ enum PtyMasterHandle {
 }
 
 impl PtyChild {
+    /// Kill the child and every process it started (see [`kill_process_tree`]).
     pub fn kill(&mut self) {
+        if let Some(pid) = self.pid() {
+            kill_tree_by_pid(pid);
+        }
         match &mut self.child {
             PtyChildProcess::PortablePty(c) => {
                 let _ = c.kill();
@@ -125,6 +152,15 @@ impl PtyChild {
         }
     }
 
+    /// OS process id of the child, if known.
+    pub fn pid(&self) -> Option<u32> {
+        match &self.child {
+            PtyChildProcess::PortablePty(c) => c.process_id(),
+            #[cfg(unix)]
+            PtyChildProcess::Std(c) => Some(c.id()),
+        }
+    }
+
     pub fn take_writer(&self) -> anyhow::Result<Box<dyn std::io::Write + Send>> {
         match &self.master {
             PtyMasterHandle::PortablePty(m) => Ok(m.take_writer()?),
@@ -161,6 +197,50 @@ impl PtyChild {
         }
     }
 
+    /// Stop the terminal from echoing input and buffering it by line, so a CLI
+    /// reading its stdin gets what is written, whatever the line length.
+    pub fn set_raw_input(&self) -> anyhow::Result<()> {
+        #[cfg(unix)]
+        self.update_termios(|t| t.c_lflag &= !(libc::ICANON | libc::ECHO))?;
+        Ok(())
+    }
+
+    /// Close the child's stdin: switch the terminal back to line mode and
+    /// send EOF (which only counts at the start of a line).
+    pub fn end_input(&self, writer: &mut dyn std::io::Write) -> anyhow::Result<()> {
+        #[cfg(unix)]
+        {
+            let mut eof = 0;
+            self.update_termios(|t| {
+                t.c_lflag |= libc::ICANON;
+                eof = t.c_cc[libc::VEOF];
+            })?;
+            writer.write_all(&[b'\n', eof])?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    fn update_termios(&self, update: impl FnOnce(&mut libc::termios)) -> anyhow::Result<()> {
+        use std::os::unix::io::AsRawFd;
+        let fd = match &self.master {
+            PtyMasterHandle::PortablePty(m) => m
+                .as_raw_fd()
+                .ok_or_else(|| anyhow::anyhow!("PTY has no file descriptor"))?,
+            PtyMasterHandle::Unix(fd) => fd.as_raw_fd(),
+        };
+        let mut t: libc::termios = unsafe { std::mem::zeroed() };
+        if unsafe { libc::tcgetattr(fd, &mut t) } != 0 {
+            anyhow::bail!("tcgetattr() failed: {}", std::io::Error::last_os_error());
+        }
+        update(&mut t);
+        if unsafe { libc::tcsetattr(fd, libc::TCSANOW, &t) } != 0 {
+            anyhow::bail!("tcsetattr() failed: {}", std::io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
     /// Wait for the child process to exit. Must be called from a blocking context.
     pub fn wait(&mut self) -> std::io::Result<portable_pty::ExitStatus> {
         match &mut self.child {
@@ -481,6 +561,10 @@ impl WorkspaceExec {
         // Note: env vars are now exported in the shell command, not here.
         // Setting them here with cmd.envs() doesn't propagate into the container.
         cmd.stdin(stdin).stdout(stdout).stderr(stderr);
+        // Own process group so cancellation can signal the wrapper and the
+        // CLI it starts inside the container together.
+        #[cfg(unix)]
+        cmd.process_group(0);
         Ok(cmd)
     }
 
@@ -724,6 +808,8 @@ impl WorkspaceExec {
                 }
 
                 cmd.stdin(stdin).stdout(stdout).stderr(stderr);
+                #[cfg(unix)]
+                cmd.process_group(0);
                 Ok(cmd)
             }
         }
@@ -1112,3 +1198,49 @@ impl WorkspaceExec {
         })
     }
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncBufReadExt, BufReader};
+
+    /// Whether `pid` is still running (zombies waiting to be reaped count as
+    /// gone).
+    fn is_running(pid: u32) -> bool {
+        std::process::Command::new("ps")
+            .args(["-o", "stat=", "-p", &pid.to_string()])
+            .output()
+            .map(|out| {
+                let stat = String::from_utf8_lossy(&out.stdout);
+                let stat = stat.trim();
+                !stat.is_empty() && !stat.starts_with('Z')
+            })
+            .unwrap_or(false)
+    }
+
+    #[tokio::test]
+    async fn test_kill_process_tree_kills_grandchildren() {
+        let mut cmd = Command::new("/bin/sh");
+        cmd.args(["-c", "sleep 30 & echo $!; wait"])
+            .stdout(Stdio::piped())
+            .process_group(0);
+        let mut child = cmd.spawn().unwrap();
+        let child_pid = child.id().unwrap();
+
+        let stdout = child.stdout.take().unwrap();
+        let mut line = String::new();
+        BufReader::new(stdout).read_line(&mut line).await.unwrap();
+        let grandchild_pid: u32 = line.trim().parse().unwrap();
+        assert!(is_running(grandchild_pid));
+
+        kill_process_tree(&mut child).await;
+        let _ = child.wait().await;
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        while is_running(grandchild_pid) && std::time::Instant::now() < deadline {
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+        assert!(!is_running(child_pid));
+        assert!(!is_running(grandchild_pid));
+    }
+}